@@ -0,0 +1,186 @@
+// Rust guideline compliant 2026-02-27
+
+//! Bounded cache of inference verdicts, keyed by transaction id.
+//!
+//! Replayed and duplicated traffic carries ids the model has already scored.
+//! An [`InferenceCache`] lets the Consumer answer them without another
+//! Modelizer call. It keeps at most `capacity` verdicts and evicts the least
+//! recently used one first. A verdict only holds for the model version that
+//! produced it: results from another model or version empty the cache before
+//! they are stored, and the Consumer clears it on every switch it applies.
+
+use std::collections::{BTreeMap, HashMap};
+
+use domain::{InferredTransaction, Transaction, TransactionId};
+
+use crate::TARGET;
+
+/// One cached verdict.
+#[derive(Debug, Clone, Copy)]
+struct Verdict {
+    predicted_fraud: bool,
+    /// Recency stamp; the key of this id in `InferenceCache::recency`.
+    used: u64,
+}
+
+/// Least-recently-used cache of the verdicts of one model version.
+#[derive(Debug)]
+pub(crate) struct InferenceCache {
+    capacity: usize,
+    /// Model name and version every cached verdict came from.
+    model: Option<(String, String)>,
+    verdicts: HashMap<TransactionId, Verdict>,
+    /// Cached ids by last use, oldest first.
+    recency: BTreeMap<u64, TransactionId>,
+    /// Next recency stamp.
+    next_use: u64,
+}
+
+impl InferenceCache {
+    /// An empty cache keeping at most `capacity` verdicts; `capacity >= 1`.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            model: None,
+            verdicts: HashMap::with_capacity(capacity.min(4096)),
+            recency: BTreeMap::new(),
+            next_use: 0,
+        }
+    }
+
+    /// The cached verdict for `tx`, as an `InferredTransaction` marked
+    /// `cached`; `tx` is handed back on a miss.
+    pub(crate) fn get(&mut self, tx: Transaction) -> Result<InferredTransaction, Transaction> {
+        let Some((name, version)) = &self.model else {
+            return Err(tx);
+        };
+        let Some(verdict) = self.verdicts.get_mut(&tx.id) else {
+            return Err(tx);
+        };
+        self.recency.remove(&verdict.used);
+        verdict.used = self.next_use;
+        self.recency.insert(self.next_use, tx.id);
+        self.next_use += 1;
+        let mut inferred =
+            InferredTransaction::new(tx, verdict.predicted_fraud, name.clone(), version.clone());
+        inferred.cached = true;
+        Ok(inferred)
+    }
+
+    /// Store the verdict of `inferred`, evicting the least recently used one
+    /// when full. A verdict from another model or version than the cached
+    /// ones empties the cache first.
+    pub(crate) fn insert(&mut self, inferred: &InferredTransaction) {
+        let same_model = self.model.as_ref().is_some_and(|(name, version)| {
+            *name == inferred.model_name && *version == inferred.model_version
+        });
+        if !same_model {
+            if !self.verdicts.is_empty() {
+                tracing::debug!(
+                    target: TARGET,
+                    model_name = %inferred.model_name,
+                    model_version = %inferred.model_version,
+                    "consumer.cache.invalidated: model changed"
+                );
+            }
+            self.clear();
+            self.model = Some((inferred.model_name.clone(), inferred.model_version.clone()));
+        }
+        let id = inferred.id();
+        let verdict = Verdict { predicted_fraud: inferred.predicted_fraud, used: self.next_use };
+        if let Some(old) = self.verdicts.insert(id, verdict) {
+            self.recency.remove(&old.used);
+        }
+        self.recency.insert(self.next_use, id);
+        self.next_use += 1;
+        if self.verdicts.len() > self.capacity
+            && let Some((_, oldest)) = self.recency.pop_first()
+        {
+            self.verdicts.remove(&oldest);
+        }
+    }
+
+    /// Forget every verdict.
+    pub(crate) fn clear(&mut self) {
+        self.model = None;
+        self.verdicts.clear();
+        self.recency.clear();
+    }
+
+    /// Number of cached verdicts.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.verdicts.len()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::InferenceCache;
+    use domain::{Currency, InferredTransaction, Transaction, TransactionId};
+
+    fn tx(n: u128) -> Transaction {
+        Transaction {
+            id: TransactionId::from_uuid(uuid::Uuid::from_u128(n)),
+            amount: 10.0,
+            last_name: "Doe".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    fn inferred(n: u128, version: &str) -> InferredTransaction {
+        InferredTransaction::new(tx(n), n.is_multiple_of(2), "DEMO", version)
+    }
+
+    // IC-T01: a hit carries the stored verdict, marked cached; a miss hands
+    // the transaction back.
+    #[test]
+    fn hit_is_marked_cached_and_miss_returns_the_transaction() {
+        let mut cache = InferenceCache::new(4);
+        cache.insert(&inferred(2, "4"));
+
+        let hit = cache.get(tx(2)).unwrap();
+        assert!(hit.cached && hit.predicted_fraud);
+        assert_eq!((hit.model_name.as_str(), hit.model_version.as_str()), ("DEMO", "4"));
+        assert_eq!(cache.get(tx(3)).unwrap_err(), tx(3));
+    }
+
+    // IC-T02: beyond capacity, the least recently used verdict goes first;
+    // a hit counts as a use.
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = InferenceCache::new(2);
+        cache.insert(&inferred(1, "4"));
+        cache.insert(&inferred(2, "4"));
+        cache.get(tx(1)).unwrap();
+
+        cache.insert(&inferred(3, "4"));
+
+        assert_eq!(cache.len(), 2);
+        cache.get(tx(2)).unwrap_err();
+        cache.get(tx(1)).unwrap();
+        cache.get(tx(3)).unwrap();
+    }
+
+    // IC-T03: a verdict from another version empties the cache first.
+    #[test]
+    fn another_version_invalidates_the_cache() {
+        let mut cache = InferenceCache::new(4);
+        cache.insert(&inferred(1, "4"));
+        cache.insert(&inferred(2, "4"));
+
+        cache.insert(&inferred(3, "3"));
+
+        assert_eq!(cache.len(), 1);
+        cache.get(tx(1)).unwrap_err();
+        assert_eq!(cache.get(tx(3)).unwrap().model_version, "3");
+        cache.clear();
+        assert_eq!(cache.len(), 0);
+        cache.get(tx(3)).unwrap_err();
+    }
+}
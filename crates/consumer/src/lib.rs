@@ -4,41 +4,229 @@
 //! triggers fraud alarms, and writes results to Buffer2.
 //!
 //! Entry points: [`Consumer::consume_once`], [`Consumer::run`],
-//! [`Consumer::switch_model_version`]. Configuration via [`ConsumerConfig::builder`].
+//! [`Consumer::run_with_commands`], [`Consumer::run_with_dead_letters`],
+//! [`Consumer::run_with_commands_and_dead_letters`],
+//! [`Consumer::switch_model_version`], [`Consumer::warmup`], [`Consumer::stats`].
+//! Configuration via [`ConsumerConfig::builder`].
+//!
+//! With [`ConsumerConfigBuilder::validate_input`], every transaction read from
+//! Buffer1 is checked with `Transaction::validate_max`; invalid ones are
+//! counted, optionally quarantined through a `DeadLetter` port, and never
+//! reach the Modelizer.
+//!
+//! With [`ConsumerConfigBuilder::currency_converter`], non-EUR amounts are
+//! converted to EUR for the amount checks, so thresholds apply to EUR values;
+//! transactions keep their own amount and currency, and currencies without a
+//! rate are rejected the same way.
+//!
+//! With [`ConsumerConfigBuilder::adaptive_interval`], the sleep between
+//! iterations follows how full the last read was instead of staying fixed;
+//! see `AdaptiveInterval`.
+//!
+//! With [`Consumer::with_observer`], a [`BatchObserver`] is told about every
+//! batch the run loops process and why they stopped.
+//!
+//! With [`ConsumerConfigBuilder::shed_above`], the depth-aware run loops drop
+//! the oldest Buffer1 backlog instead of falling ever further behind; see
+//! [`LoadShedding`].
+//!
+//! With [`ConsumerConfigBuilder::quarantine`], a batch the Modelizer fails on
+//! is inferred again one transaction at a time; the transactions that still
+//! fail go to the `Quarantine` port instead of failing the run, and
+//! [`requeue`] later writes them back into Buffer1.
+//!
+//! With [`ConsumerConfigBuilder::switch_cooldown`], model switches closer
+//! together than the cooldown are rejected with
+//! [`ConsumerError::SwitchThrottled`]; [`Consumer::rollback_model_version`]
+//! reverts the last switch, and [`ConsumerStats::switches`] keeps the recent
+//! ones.
+//!
+//! Every alarm delivery is timed into [`ConsumerStats::alarm_latency`]; with
+//! [`ConsumerConfigBuilder::slow_alarm_threshold`], the slow ones are also
+//! logged, so a degrading alarm sink shows up before it throttles the run.
+//!
+//! Every `infer` result is checked against the `Modelizer` contract, one
+//! output per input: a length mismatch fails the batch with
+//! [`ConsumerError::InferenceMismatch`] before any alarm or Buffer2 write.
+//! With [`ConsumerConfigBuilder::inference_check`] set to
+//! [`InferenceCheck::Ids`], each output must also carry the id of the input
+//! at its position.
+//!
+//! Every transaction written to Buffer2 carries its `delivery_attempts`: each
+//! retry of a rejected remainder (see [`ConsumerConfigBuilder::write_retries`])
+//! adds one to the transactions it resends. [`ConsumerStats::delivery_attempts`]
+//! keeps their maximum and mean.
+//!
+//! With [`ConsumerConfigBuilder::settings`], the run loops read
+//! `poll_interval2`, `batch_size_mode` and `slow_alarm_threshold` from a live
+//! `DynamicSettings` channel at the top of each iteration, so they can change
+//! without a restart.
+//!
+//! With [`ConsumerConfigBuilder::inference_cache`], transactions whose id was
+//! already scored by the active model version are answered from a bounded LRU
+//! cache instead of the Modelizer, and written with `cached` set.
 
-use domain::{Alarm, AlarmError, Buffer1Read, Buffer2, BufferError, Modelizer, ModelizerError, ModelVersion};
+mod alarm_latency;
+mod cache;
+mod observer;
+mod quarantine;
+
+pub use alarm_latency::AlarmLatency;
+pub use observer::{BatchObserver, ConsumeOutcome, CountingObserver, LoggingObserver, RunEnd};
+pub use quarantine::{RequeueError, requeue};
+
+use cache::InferenceCache;
+use domain::{
+    AdaptiveInterval, Alarm, AlarmError, BatchSizeMode, Buffer1Read, Buffer2, BufferDepth, Clock,
+    ConfigError, ConfigSummary, Currency, CurrencyConverter, DeadLetter, DynamicSettings,
+    ErrorChain, EventSender, InferredTransaction, InvalidTransaction, LiveSettings, Modelizer,
+    ModelizerError, ModelVersion, PacingStats, PipelineEvent, Preset, Quarantine, ReadError,
+    RejectedTransaction, RngPort, SettingsReceiver, Sleeper, Stage, StopReason, StorageError,
+    SystemClock, TokioSleeper, Transaction, TransactionId, WriteError,
+};
 use rand::{Rng, SeedableRng, rngs::StdRng};
-use std::cell::RefCell;
-use std::time::Duration;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
+use tokio::sync::mpsc;
+
+/// `tracing` target of every Consumer event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::CONSUMER;
 
 // ---------------------------------------------------------------------------
 // ConsumerError
 // ---------------------------------------------------------------------------
 
 /// Errors that can occur during transaction consumption.
+///
+/// `Display` gives only this layer's context; the wrapped error is the
+/// [`source`](std::error::Error::source). Log with [`ErrorChain`] to see both.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum ConsumerError {
     /// The supplied configuration is invalid.
-    #[error("invalid consumer configuration: {reason}")]
-    InvalidConfig {
-        /// Human-readable description of the problem.
-        reason: String,
-    },
+    #[error("invalid consumer configuration")]
+    InvalidConfig(#[from] ConfigError),
     /// A Buffer1 read failed.
-    #[error("buffer1 read error: {0}")]
-    Read(BufferError),
+    #[error("buffer1 read error")]
+    Read(#[source] ReadError),
     /// Modelizer inference or version-switch failed.
-    #[error("modelizer error: {0}")]
-    Inference(ModelizerError),
+    #[error("modelizer error")]
+    Inference(#[source] ModelizerError),
     /// A Buffer2 write failed.
-    #[error("buffer2 write error: {0}")]
-    Write(BufferError),
+    #[error("buffer2 write error")]
+    Write(#[source] WriteError),
+    /// Quarantining rejected transactions failed.
+    #[error("dead-letter write error")]
+    DeadLetter(#[source] StorageError),
+    /// A model switch came sooner than `switch_cooldown` after the last one.
+    #[error("model switch throttled: {since_last:?} since the last switch, cooldown {cooldown:?}")]
+    SwitchThrottled {
+        /// Time elapsed since the last applied switch.
+        since_last: Duration,
+        /// Configured minimum time between switches.
+        cooldown: Duration,
+    },
+    /// The Modelizer returned a different number of results than it was
+    /// given transactions.
+    #[error("modelizer returned {got} results for {expected} transactions")]
+    InferenceMismatch {
+        /// Transactions sent to `infer`.
+        expected: usize,
+        /// Results it returned.
+        got: usize,
+    },
+    /// The Modelizer returned a result out of order or for another
+    /// transaction; only checked with [`InferenceCheck::Ids`].
+    #[error("modelizer result {position} is for {got}, expected {expected}")]
+    InferenceOutOfOrder {
+        /// Index in the batch of the first mismatched result.
+        position: usize,
+        /// Id of the transaction sent at that position.
+        expected: TransactionId,
+        /// Id of the result returned there.
+        got: TransactionId,
+    },
+}
+
+impl ConsumerError {
+    /// Whether restarting the run loop after a backoff may succeed.
+    ///
+    /// - `InvalidConfig`: fatal.
+    /// - `Read`: fatal (`Closed` is the normal end of data; a read cannot report `Full`).
+    /// - `Inference`: retryable for `InferenceFailed`, fatal for `SwitchFailed`.
+    /// - `Write`: retryable for `Full`, fatal for `Closed`.
+    /// - `DeadLetter`: retryable for `Unavailable`, fatal for `CapacityExceeded`.
+    /// - `SwitchThrottled`: fatal; only a rejected command, the run loops never return it.
+    /// - `InferenceMismatch`, `InferenceOutOfOrder`: fatal; the Modelizer breaks its contract.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::InvalidConfig(_)
+            | Self::Read(_)
+            | Self::SwitchThrottled { .. }
+            | Self::InferenceMismatch { .. }
+            | Self::InferenceOutOfOrder { .. } => false,
+            Self::Inference(e) => e.is_retryable(),
+            Self::Write(e) => e.is_retryable(),
+            Self::DeadLetter(e) => e.is_retryable(),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
 // ConsumerConfig + builder
 // ---------------------------------------------------------------------------
 
+/// Load-shedding policy set by [`ConsumerConfigBuilder::shed_above`].
+///
+/// Before each read, when Buffer1 holds more than `depth` transactions, the
+/// Consumer reads and discards the oldest ones until only `keep_latest` remain.
+/// Shed transactions are counted in [`ConsumerStats::shed`] and quarantined
+/// with reason `InvalidTransaction::Shed` when a `DeadLetter` sink is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadShedding {
+    /// Buffer1 depth above which shedding starts.
+    pub depth: usize,
+    /// Transactions left in Buffer1 after shedding; at most `depth`.
+    pub keep_latest: usize,
+}
+
+/// Order of the Buffer2 write and the alarms of a batch, set by
+/// [`ConsumerConfigBuilder::alarm_ordering`].
+///
+/// `AlarmsFirst` alerts as early as possible, but a failed write leaves
+/// alarms sent for transactions that never reach storage. `WriteFirst` only
+/// alerts on batches Buffer2 accepted, at the cost of delaying every alert by
+/// the write latency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AlarmOrdering {
+    /// Trigger the alarms, then write the batch (default).
+    #[default]
+    AlarmsFirst,
+    /// Write the batch, then trigger the alarms; a failed write fires none.
+    WriteFirst,
+}
+
+/// How closely each `infer` result is checked against its batch, set by
+/// [`ConsumerConfigBuilder::inference_check`].
+///
+/// A result that fails the check fails the batch before any alarm or
+/// Buffer2 write, with [`ConsumerError::InferenceMismatch`] or
+/// [`ConsumerError::InferenceOutOfOrder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InferenceCheck {
+    /// One result per transaction (default).
+    #[default]
+    Length,
+    /// Also, each result carries the id of the transaction at its position.
+    /// Costs one pass over each batch; meant for debugging a new adapter.
+    Ids,
+}
+
 /// Runtime configuration for a [`Consumer`].
 ///
 /// Construct via [`ConsumerConfig::builder`].
@@ -46,12 +234,70 @@ pub enum ConsumerError {
 pub struct ConsumerConfig {
     /// Maximum number of transactions per batch (range: `[1, n2_max]`).
     pub n2_max: usize,
+    /// Whether every read requests exactly `n2_max` transactions instead of
+    /// a random size in `[1, n2_max]`.
+    pub fixed_batch_size: bool,
+    /// How read sizes are drawn; ignored when `fixed_batch_size` is set.
+    pub batch_size_mode: BatchSizeMode,
     /// Delay between successive batch-processing iterations.
     pub poll_interval2: Duration,
     /// Optional upper bound on the number of iterations. `None` means infinite.
     pub iterations: Option<u64>,
     /// Optional RNG seed for reproducible batch sizes. `None` seeds from the OS.
     pub seed: Option<u64>,
+    /// Consecutive empty depth polls that end a [`ConsumerCommand::DrainAndStop`].
+    pub drain_idle_polls: usize,
+    /// Optional observer channel for [`PipelineEvent`]s.
+    pub events: Option<EventSender>,
+    /// Optional cap on alarms triggered per batch. `None` means unlimited.
+    pub max_alarms_per_batch: Option<usize>,
+    /// Synthetic transactions sent through the Modelizer before the first
+    /// batch of `run`/`run_with_commands`. `0` disables warmup.
+    pub warmup: usize,
+    /// Whether a failed warmup aborts the run (`true`) or is only logged.
+    pub warmup_strict: bool,
+    /// Whether to validate transactions read from Buffer1 before inference.
+    pub validate_input: bool,
+    /// Largest valid amount when `validate_input` is set.
+    pub max_amount: f64,
+    /// Adapt the inter-iteration sleep to the last read. `None` sleeps
+    /// `poll_interval2` every time.
+    pub adaptive_interval: Option<AdaptiveInterval>,
+    /// Drop the oldest backlog above a Buffer1 depth. `None` never sheds.
+    pub shed_above: Option<LoadShedding>,
+    /// Waits out the inter-iteration delay.
+    pub sleeper: Arc<dyn Sleeper>,
+    /// Minimum time between two model switches. `None` never throttles.
+    pub switch_cooldown: Option<Duration>,
+    /// Most recent model switches kept in [`ConsumerStats::switches`].
+    pub switch_history: usize,
+    /// Time source for switch timestamps and the cooldown.
+    pub clock: Arc<dyn Clock>,
+    /// Converts amounts to EUR for validation only. `None` checks them as-is.
+    pub currency_converter: Option<Arc<dyn CurrencyConverter>>,
+    /// Whether alarms fire before or after the Buffer2 write.
+    pub alarm_ordering: AlarmOrdering,
+    /// Holds transactions inference failed on. `None` fails the batch instead.
+    pub quarantine: Option<Arc<dyn Quarantine>>,
+    /// How many times a `Full` on the remainder of a partially accepted
+    /// Buffer2 write is retried before the batch fails.
+    pub write_retries: u32,
+    /// Largest slice of an inferred batch handed to Buffer2 in one write.
+    /// `None` writes the whole batch at once.
+    pub write_chunk_size: Option<usize>,
+    /// Alarm deliveries taking longer than this are logged as slow. `None`
+    /// never warns.
+    pub slow_alarm_threshold: Option<Duration>,
+    /// Live settings channel; `None` keeps `poll_interval2`, `batch_size_mode`
+    /// and `slow_alarm_threshold` for the run.
+    pub settings: Option<SettingsReceiver>,
+    /// How each inference result is checked against its batch.
+    pub inference_check: InferenceCheck,
+    /// Verdicts kept by the inference cache. `None` sends every transaction
+    /// to the Modelizer.
+    pub inference_cache: Option<usize>,
+    /// Preset the builder started from, if any.
+    pub preset: Option<Preset>,
 }
 
 /// Builder for [`ConsumerConfig`].
@@ -60,28 +306,230 @@ pub struct ConsumerConfig {
 #[derive(Debug)]
 pub struct ConsumerConfigBuilder {
     n2_max: usize,
+    fixed_batch_size: bool,
+    batch_size_mode: BatchSizeMode,
     poll_interval2: Duration,
     iterations: Option<u64>,
     seed: Option<u64>,
+    drain_idle_polls: usize,
+    events: Option<EventSender>,
+    max_alarms_per_batch: Option<usize>,
+    warmup: usize,
+    warmup_strict: bool,
+    validate_input: bool,
+    max_amount: f64,
+    adaptive_interval: Option<AdaptiveInterval>,
+    shed_above: Option<LoadShedding>,
+    sleeper: Arc<dyn Sleeper>,
+    switch_cooldown: Option<Duration>,
+    switch_history: usize,
+    clock: Arc<dyn Clock>,
+    currency_converter: Option<Arc<dyn CurrencyConverter>>,
+    alarm_ordering: AlarmOrdering,
+    quarantine: Option<Arc<dyn Quarantine>>,
+    write_retries: u32,
+    write_chunk_size: Option<usize>,
+    slow_alarm_threshold: Option<Duration>,
+    settings: Option<SettingsReceiver>,
+    inference_check: InferenceCheck,
+    inference_cache: Option<usize>,
+    preset: Option<Preset>,
 }
 
 impl ConsumerConfig {
     /// Create a builder. `n2_max` is the only required parameter.
     ///
-    /// Default values: `poll_interval2 = 100 ms`, `iterations = None`, `seed = None`.
+    /// Default values: `fixed_batch_size = false`, `batch_size_mode = Uniform`,
+    /// `poll_interval2 = 100 ms`, `iterations = None`, `seed = None`,
+    /// `drain_idle_polls = 3`, `events = None`, `max_alarms_per_batch = None`,
+    /// `warmup = 0`, `warmup_strict = false`, `validate_input = false`,
+    /// `max_amount = Transaction::MAX_AMOUNT`,
+    /// `adaptive_interval = None`, `shed_above = None`, `sleeper = TokioSleeper`,
+    /// `switch_cooldown = None`, `switch_history = 10`, `clock = SystemClock`,
+    /// `currency_converter = None`, `alarm_ordering = AlarmsFirst`, `quarantine = None`,
+    /// `write_retries = 3`, `write_chunk_size = None`, `slow_alarm_threshold = None`,
+    /// `settings = None`, `inference_check = Length`, `inference_cache = None`,
+    /// `preset = None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use consumer::{ConsumerConfig, ConsumerError};
+    /// use std::time::Duration;
+    ///
+    /// let config = ConsumerConfig::builder(50)
+    ///     .poll_interval2(Duration::from_millis(25))
+    ///     .seed(2)
+    ///     .build()?;
+    /// assert_eq!((config.n2_max, config.poll_interval2), (50, Duration::from_millis(25)));
+    ///
+    /// // `build` validates: a zero batch size is rejected.
+    /// let err = ConsumerConfig::builder(0).build().unwrap_err();
+    /// assert!(matches!(err, ConsumerError::InvalidConfig(e) if e.field == "n2_max"));
+    /// # Ok::<(), ConsumerError>(())
+    /// ```
     #[must_use]
     pub fn builder(n2_max: usize) -> ConsumerConfigBuilder {
         ConsumerConfigBuilder {
             n2_max,
+            fixed_batch_size: false,
+            batch_size_mode: BatchSizeMode::Uniform,
             // 100 ms chosen as a reasonable demo cadence; lower for tests.
             poll_interval2: Duration::from_millis(100),
             iterations: None,
             seed: None,
+            // A few polls give a concurrent producer a chance to land a last batch.
+            drain_idle_polls: 3,
+            events: None,
+            max_alarms_per_batch: None,
+            warmup: 0,
+            warmup_strict: false,
+            validate_input: false,
+            max_amount: Transaction::MAX_AMOUNT,
+            adaptive_interval: None,
+            shed_above: None,
+            sleeper: Arc::new(TokioSleeper),
+            switch_cooldown: None,
+            switch_history: 10,
+            clock: Arc::new(SystemClock),
+            currency_converter: None,
+            alarm_ordering: AlarmOrdering::AlarmsFirst,
+            quarantine: None,
+            write_retries: 3,
+            write_chunk_size: None,
+            slow_alarm_threshold: None,
+            settings: None,
+            inference_check: InferenceCheck::Length,
+            inference_cache: None,
+            preset: None,
         }
     }
+
+    /// Create a builder pre-populated with `preset`: `n2_max`,
+    /// `fixed_batch_size` and `poll_interval2` as listed on [`Preset`], every
+    /// other value as in [`builder`](Self::builder). Later builder calls
+    /// override the preset values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use consumer::{ConsumerConfig, ConsumerError};
+    /// use domain::Preset;
+    /// use std::time::Duration;
+    ///
+    /// let config = ConsumerConfig::from_preset(Preset::LowLatency)
+    ///     .poll_interval2(Duration::ZERO)
+    ///     .build()?;
+    /// assert_eq!((config.n2_max, config.poll_interval2), (10, Duration::ZERO));
+    /// assert_eq!(config.summary().get("preset"), Some("low-latency"));
+    /// # Ok::<(), ConsumerError>(())
+    /// ```
+    #[must_use]
+    pub fn from_preset(preset: Preset) -> ConsumerConfigBuilder {
+        let builder = match preset {
+            Preset::Demo => Self::builder(50).poll_interval2(Duration::from_millis(25)),
+            Preset::Stress => Self::builder(1_000)
+                .fixed_batch_size(true)
+                .poll_interval2(Duration::from_millis(1)),
+            Preset::LowLatency => Self::builder(10).poll_interval2(Duration::from_millis(1)),
+        };
+        ConsumerConfigBuilder { preset: Some(preset), ..builder }
+    }
+
+    /// [`Preset::Demo`]: reads of up to 50 every 25 ms, often enough that
+    /// the Producer's half-second batches never pile up.
+    #[must_use]
+    pub fn demo() -> ConsumerConfigBuilder {
+        Self::from_preset(Preset::Demo)
+    }
+
+    /// [`Preset::Stress`]: full reads of 1 000 every millisecond, to keep
+    /// up with a stress Producer and load the model.
+    #[must_use]
+    pub fn stress() -> ConsumerConfigBuilder {
+        Self::from_preset(Preset::Stress)
+    }
+
+    /// [`Preset::LowLatency`]: reads of up to 10 every millisecond, so a
+    /// transaction is scored soon after it lands in Buffer1.
+    #[must_use]
+    pub fn low_latency() -> ConsumerConfigBuilder {
+        Self::from_preset(Preset::LowLatency)
+    }
+
+    /// The effective settings, logged when a [`Consumer`] run starts and
+    /// embedded in run reports. Sleeper, clock and observer are left out; the
+    /// currency converter and the quarantine only show whether one is set.
+    #[must_use]
+    pub fn summary(&self) -> ConfigSummary {
+        let shed_above = self
+            .shed_above
+            .map(|shed| format!("depth {} keep {}", shed.depth, shed.keep_latest));
+        let alarm_ordering = match self.alarm_ordering {
+            AlarmOrdering::AlarmsFirst => "alarms first",
+            AlarmOrdering::WriteFirst => "write first",
+        };
+        let inference_check = match self.inference_check {
+            InferenceCheck::Length => "length",
+            InferenceCheck::Ids => "ids",
+        };
+        ConfigSummary::new("consumer")
+            .optional("preset", self.preset)
+            .field("n2_max", self.n2_max)
+            .field("fixed_batch_size", self.fixed_batch_size)
+            .field("batch_size_mode", self.batch_size_mode)
+            .duration("poll_interval2", self.poll_interval2)
+            .optional("iterations", self.iterations)
+            .optional("seed", self.seed)
+            .field("drain_idle_polls", self.drain_idle_polls)
+            .optional("max_alarms_per_batch", self.max_alarms_per_batch)
+            .field("warmup", self.warmup)
+            .field("warmup_strict", self.warmup_strict)
+            .field("validate_input", self.validate_input)
+            .field("max_amount", self.max_amount)
+            .optional("adaptive_interval", self.adaptive_interval)
+            .optional("shed_above", shed_above)
+            .optional("switch_cooldown", self.switch_cooldown.map(|d| format!("{d:?}")))
+            .field("switch_history", self.switch_history)
+            .field("currency_converter", self.currency_converter.is_some())
+            .field("alarm_ordering", alarm_ordering)
+            .field("quarantine", self.quarantine.is_some())
+            .field("write_retries", self.write_retries)
+            .optional("write_chunk_size", self.write_chunk_size)
+            .optional("slow_alarm_threshold", self.slow_alarm_threshold.map(|d| format!("{d:?}")))
+            .field("inference_check", inference_check)
+            .optional("inference_cache", self.inference_cache)
+    }
 }
 
 impl ConsumerConfigBuilder {
+    /// Override the maximum batch size, e.g. the one of a preset.
+    #[must_use]
+    pub fn n2_max(mut self, n2_max: usize) -> Self {
+        self.n2_max = n2_max;
+        self
+    }
+
+    /// Request exactly `n2_max` transactions per read instead of a random
+    /// size in `[1, n2_max]`, for reproducible capacity planning. A read may
+    /// still return fewer when Buffer1 holds less.
+    ///
+    /// With [`adaptive_interval`](Self::adaptive_interval), a batch then
+    /// counts as full only when it reaches `n2_max`.
+    #[must_use]
+    pub fn fixed_batch_size(mut self, fixed: bool) -> Self {
+        self.fixed_batch_size = fixed;
+        self
+    }
+
+    /// Draw read sizes in `[1, n2_max]` with `mode` instead of uniformly.
+    /// [`fixed_batch_size`](Self::fixed_batch_size) takes precedence.
+    #[must_use]
+    pub fn batch_size_mode(mut self, mode: BatchSizeMode) -> Self {
+        self.batch_size_mode = mode;
+        self
+    }
+
     /// Override the inter-iteration delay.
     #[must_use]
     pub fn poll_interval2(mut self, poll_interval2: Duration) -> Self {
@@ -104,27 +552,492 @@ impl ConsumerConfigBuilder {
         self
     }
 
+    /// Number of consecutive empty Buffer1 observations after which a
+    /// [`ConsumerCommand::DrainAndStop`] completes.
+    #[must_use]
+    pub fn drain_idle_polls(mut self, k: usize) -> Self {
+        self.drain_idle_polls = k;
+        self
+    }
+
+    /// Publish `BatchInferred`, `AlarmTriggered`, `AlarmFailed` and `StageStopped`
+    /// events to `events`.
+    #[must_use]
+    pub fn events(mut self, events: EventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Trigger at most `n` alarms per batch. Further fraudulent transactions
+    /// in the batch are only counted (see [`ConsumerStats::alarms_suppressed`])
+    /// and reported in one warning; they are still written to Buffer2.
+    #[must_use]
+    pub fn max_alarms_per_batch(mut self, n: usize) -> Self {
+        self.max_alarms_per_batch = Some(n);
+        self
+    }
+
+    /// Send `n` synthetic transactions through the Modelizer before the first
+    /// real batch (see [`Consumer::warmup`]), absorbing adapter cold-start cost.
+    #[must_use]
+    pub fn warmup(mut self, n: usize) -> Self {
+        self.warmup = n;
+        self
+    }
+
+    /// Abort the run when warmup fails instead of logging and carrying on.
+    #[must_use]
+    pub fn warmup_strict(mut self, strict: bool) -> Self {
+        self.warmup_strict = strict;
+        self
+    }
+
+    /// Check every transaction read from Buffer1 (finite amount, `> 0`,
+    /// `<= max_amount`) and divert invalid ones away from the Modelizer.
+    ///
+    /// Rejections are counted in [`ConsumerStats::rejected`] and, when run via
+    /// [`Consumer::run_with_dead_letters`], quarantined.
+    #[must_use]
+    pub fn validate_input(mut self, validate: bool) -> Self {
+        self.validate_input = validate;
+        self
+    }
+
+    /// How closely each inference result is checked; see [`InferenceCheck`].
+    #[must_use]
+    pub fn inference_check(mut self, check: InferenceCheck) -> Self {
+        self.inference_check = check;
+        self
+    }
+
+    /// Largest valid amount for [`validate_input`](Self::validate_input).
+    #[must_use]
+    pub fn max_amount(mut self, max: f64) -> Self {
+        self.max_amount = max;
+        self
+    }
+
+    /// Replace the fixed `poll_interval2` sleep with `adaptive`: no sleep after
+    /// a full batch, a growing one after near-empty reads.
+    #[must_use]
+    pub fn adaptive_interval(mut self, adaptive: AdaptiveInterval) -> Self {
+        self.adaptive_interval = Some(adaptive);
+        self
+    }
+
+    /// Shed load: whenever Buffer1 reports more than `depth` transactions,
+    /// read and discard the oldest until only `keep_latest` remain, then
+    /// process as usual. See [`LoadShedding`].
+    ///
+    /// Needs Buffer1's depth, so only [`Consumer::run_with_dead_letters`],
+    /// [`Consumer::run_with_commands`] and
+    /// [`Consumer::run_with_commands_and_dead_letters`] apply it (the two
+    /// dead-letter ones also quarantine what they shed); `run` and
+    /// `consume_once` never shed. Draining after [`ConsumerCommand::DrainAndStop`] never
+    /// sheds either.
+    #[must_use]
+    pub fn shed_above(mut self, depth: usize, keep_latest: usize) -> Self {
+        self.shed_above = Some(LoadShedding { depth, keep_latest });
+        self
+    }
+
+    /// Retry a `Full` on the rejected remainder of a partially accepted
+    /// Buffer2 write up to `n` times (default 3), `poll_interval2` apart.
+    ///
+    /// The remainder is written again after `poll_interval2` as long as
+    /// Buffer2 keeps accepting part of it; only attempts that accept nothing
+    /// use up a retry. A batch rejected whole is never retried here: the
+    /// write fails with [`ConsumerError::Write`] as before.
+    #[must_use]
+    pub fn write_retries(mut self, n: u32) -> Self {
+        self.write_retries = n;
+        self
+    }
+
+    /// Write each inferred batch to Buffer2 in chunks of at most `n`
+    /// transactions, in order, instead of in one `write_batch`.
+    ///
+    /// Keeps a bounded Buffer2 useful with very large batches. Once the first
+    /// chunk is accepted, every later one counts as the remainder of a
+    /// partially accepted write: a `Full` on it is retried as described in
+    /// [`write_retries`](Self::write_retries), the budget restarting with each
+    /// chunk, and only the chunks not yet written are sent again.
+    #[must_use]
+    pub fn write_chunk_size(mut self, n: usize) -> Self {
+        self.write_chunk_size = Some(n);
+        self
+    }
+
+    /// Log a `consumer.alarm.slow` warning, with the transaction id and the
+    /// elapsed time, for every alarm delivery that takes longer than `threshold`.
+    ///
+    /// Every delivery is timed either way and counted in
+    /// [`ConsumerStats::alarm_latency`]; the threshold only adds the warning
+    /// and the slow count. With an asynchronous alarm dispatcher the time is
+    /// that of handing the alarm over, which grows when its queue backs up.
+    #[must_use]
+    pub fn slow_alarm_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_alarm_threshold = Some(threshold);
+        self
+    }
+
+    /// Follow `settings` while running: the run loops adopt the latest
+    /// `poll_interval2`, `batch_size_mode` and `slow_alarm_threshold`
+    /// published on it at the top of each iteration. An invalid update is
+    /// logged and ignored.
+    #[must_use]
+    pub fn settings(mut self, settings: SettingsReceiver) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Inject the sleeper that waits between iterations (virtual-time simulations).
+    #[must_use]
+    pub fn sleeper(mut self, sleeper: impl Sleeper + 'static) -> Self {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    /// Reject a model switch arriving less than `cooldown` after the last
+    /// applied one with [`ConsumerError::SwitchThrottled`]. Rollbacks are
+    /// never throttled, but they restart the cooldown.
+    #[must_use]
+    pub fn switch_cooldown(mut self, cooldown: Duration) -> Self {
+        self.switch_cooldown = Some(cooldown);
+        self
+    }
+
+    /// Keep the last `k` model switches in [`ConsumerStats::switches`];
+    /// `0` keeps none.
+    #[must_use]
+    pub fn switch_history(mut self, k: usize) -> Self {
+        self.switch_history = k;
+        self
+    }
+
+    /// Inject the time source used to stamp switches and measure the cooldown.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Check the amount of every non-EUR transaction read from Buffer1 in EUR,
+    /// converted through `converter`. Only the `max_amount` check sees the EUR
+    /// amount; the transaction keeps its own amount and currency.
+    ///
+    /// Transactions whose currency has no rate are rejected with
+    /// [`InvalidTransaction::NoExchangeRate`], counted in
+    /// [`ConsumerStats::rejected`] and quarantined like invalid input.
+    #[must_use]
+    pub fn currency_converter(mut self, converter: impl CurrencyConverter + 'static) -> Self {
+        self.currency_converter = Some(Arc::new(converter));
+        self
+    }
+
+    /// Choose whether alarms fire before or after the Buffer2 write; see
+    /// [`AlarmOrdering`] for the trade-off.
+    #[must_use]
+    pub fn alarm_ordering(mut self, ordering: AlarmOrdering) -> Self {
+        self.alarm_ordering = ordering;
+        self
+    }
+
+    /// Keep the transactions inference fails on in `quarantine` instead of
+    /// failing the batch.
+    ///
+    /// When the Modelizer fails a batch with a retryable error, each of its
+    /// transactions is inferred again on its own; those that still fail are
+    /// recorded with the error as reason and counted in
+    /// [`ConsumerStats::quarantined`], and the rest of the batch carries on.
+    /// A failing quarantine is logged and counted in
+    /// [`ConsumerStats::quarantine_errors`]; it never aborts the batch.
+    #[must_use]
+    pub fn quarantine(mut self, quarantine: impl Quarantine + 'static) -> Self {
+        self.quarantine = Some(Arc::new(quarantine));
+        self
+    }
+
+    /// Answer transactions whose id the active model version already scored
+    /// from a cache of the last `capacity` verdicts, least recently used
+    /// evicted first, instead of sending them to the Modelizer again.
+    ///
+    /// Meant for replayed or duplicated traffic, where a model call costs
+    /// money. Cache hits are written with `cached` set and counted in
+    /// [`ConsumerStats::cache_hits`]. The cache is cleared on every switch
+    /// the Consumer applies, and whenever the Modelizer answers with another
+    /// model or version than the cached verdicts.
+    #[must_use]
+    pub fn inference_cache(mut self, capacity: usize) -> Self {
+        self.inference_cache = Some(capacity);
+        self
+    }
+
     /// Validate and build the configuration.
     ///
     /// # Errors
     ///
-    /// Returns [`ConsumerError::InvalidConfig`] when `n2_max` is zero.
+    /// Returns [`ConsumerError::InvalidConfig`] when `n2_max`, `iterations`,
+    /// `drain_idle_polls`, `write_chunk_size` or `inference_cache` is zero,
+    /// `max_amount` is not finite and `> 0`, `batch_size_mode` or
+    /// `adaptive_interval` is invalid, or `shed_above` keeps more than its
+    /// depth.
     #[must_use = "the Result must be checked; use ? or unwrap"]
     pub fn build(self) -> Result<ConsumerConfig, ConsumerError> {
         if self.n2_max == 0 {
-            return Err(ConsumerError::InvalidConfig {
-                reason: "n2_max must be >= 1".to_owned(),
-            });
+            return Err(ConfigError::new("n2_max", self.n2_max, "must be >= 1").into());
+        }
+        if self.iterations == Some(0) {
+            return Err(ConfigError::new("iterations", 0, "must be >= 1").into());
+        }
+        if self.drain_idle_polls == 0 {
+            return Err(
+                ConfigError::new("drain_idle_polls", self.drain_idle_polls, "must be >= 1").into(),
+            );
+        }
+        if self.write_chunk_size == Some(0) {
+            return Err(ConfigError::new("write_chunk_size", 0, "must be >= 1").into());
+        }
+        if self.inference_cache == Some(0) {
+            return Err(ConfigError::new("inference_cache", 0, "must be >= 1").into());
+        }
+        if !(self.max_amount.is_finite() && self.max_amount > 0.0) {
+            return Err(
+                ConfigError::new("max_amount", self.max_amount, "must be finite and > 0").into(),
+            );
+        }
+        self.batch_size_mode.validate()?;
+        if let Some(adaptive) = &self.adaptive_interval {
+            adaptive.validate()?;
+        }
+        if let Some(shed) = self.shed_above
+            && shed.keep_latest > shed.depth
+        {
+            return Err(
+                ConfigError::new("keep_latest", shed.keep_latest, "must be <= shed depth").into(),
+            );
         }
         Ok(ConsumerConfig {
             n2_max: self.n2_max,
+            fixed_batch_size: self.fixed_batch_size,
+            batch_size_mode: self.batch_size_mode,
             poll_interval2: self.poll_interval2,
             iterations: self.iterations,
             seed: self.seed,
+            drain_idle_polls: self.drain_idle_polls,
+            events: self.events,
+            max_alarms_per_batch: self.max_alarms_per_batch,
+            warmup: self.warmup,
+            warmup_strict: self.warmup_strict,
+            validate_input: self.validate_input,
+            max_amount: self.max_amount,
+            adaptive_interval: self.adaptive_interval,
+            shed_above: self.shed_above,
+            sleeper: self.sleeper,
+            switch_cooldown: self.switch_cooldown,
+            switch_history: self.switch_history,
+            clock: self.clock,
+            currency_converter: self.currency_converter,
+            alarm_ordering: self.alarm_ordering,
+            quarantine: self.quarantine,
+            write_retries: self.write_retries,
+            write_chunk_size: self.write_chunk_size,
+            slow_alarm_threshold: self.slow_alarm_threshold,
+            settings: self.settings,
+            inference_check: self.inference_check,
+            inference_cache: self.inference_cache,
+            preset: self.preset,
         })
     }
 }
 
+// ---------------------------------------------------------------------------
+// ConsumerCommand
+// ---------------------------------------------------------------------------
+
+/// Operator commands accepted by [`Consumer::run_with_commands`].
+///
+/// Commands are applied at the top of each iteration, before the next read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsumerCommand {
+    /// Switch the Modelizer to another version; takes effect on the next batch.
+    ///
+    /// Rejected, and only logged, within `switch_cooldown` of the last switch.
+    SwitchVersion(ModelVersion),
+    /// Switch back to the version active before the last switch; see
+    /// [`Consumer::rollback_model_version`].
+    Rollback,
+    /// Stop reading Buffer1 until [`Resume`](Self::Resume).
+    Pause,
+    /// Resume reading after [`Pause`](Self::Pause).
+    Resume,
+    /// Finish what is already in Buffer1 with no inter-iteration sleep, then
+    /// return `Ok(())` without waiting for Buffer1 to be closed.
+    ///
+    /// Completes when Buffer1 reports `Closed`, or when its depth is observed
+    /// empty for `drain_idle_polls` consecutive polls. Overrides `Pause`.
+    DrainAndStop,
+}
+
+// ---------------------------------------------------------------------------
+// ConsumerStats
+// ---------------------------------------------------------------------------
+
+/// Counters accumulated for a single model version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VersionStats {
+    /// Number of transactions inferred by this version.
+    pub transactions: u64,
+    /// Number of those transactions flagged as fraudulent.
+    pub flagged: u64,
+}
+
+/// One applied model switch, as kept in [`ConsumerStats::switches`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwitchRecord {
+    /// Version active before the switch.
+    pub from: ModelVersion,
+    /// Version switched to.
+    pub to: ModelVersion,
+    /// When the switch was applied, from the configured clock.
+    pub at: SystemTime,
+    /// Whether the switch came from a rollback.
+    pub rollback: bool,
+}
+
+/// Buffer2 write attempts of the transactions written so far, as carried in
+/// their `InferredTransaction::delivery_attempts`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliveryAttempts {
+    /// Transactions written to Buffer2.
+    pub written: u64,
+    /// Sum of their attempts.
+    pub total: u64,
+    /// Most attempts any of them took; 0 before the first write.
+    pub max: u8,
+}
+
+impl DeliveryAttempts {
+    /// Mean attempts per written transaction; `None` before the first write.
+    #[must_use]
+    pub fn mean(&self) -> Option<f64> {
+        #[expect(clippy::cast_precision_loss, reason = "counts stay far below 2^52")]
+        let mean = (self.written > 0).then(|| self.total as f64 / self.written as f64);
+        mean
+    }
+
+    /// Count `written` transactions that took `attempts` attempts each.
+    fn record(&mut self, written: usize, attempts: u8) {
+        self.written += written as u64;
+        self.total += written as u64 * u64::from(attempts);
+        self.max = self.max.max(attempts);
+    }
+}
+
+/// Cumulative counters over the lifetime of a [`Consumer`].
+///
+/// Obtain a snapshot via [`Consumer::stats`]. Per-version counters are keyed by
+/// the `model_version` string carried in each `InferredTransaction`, so they
+/// reflect what the Modelizer actually returned, including across switches.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsumerStats {
+    /// Number of batches inferred.
+    pub batches: u64,
+    /// Total number of transactions inferred.
+    pub transactions: u64,
+    /// Total number of transactions flagged as fraudulent.
+    pub flagged: u64,
+    /// Per-model-version breakdown, ordered by version string.
+    pub per_version: BTreeMap<String, VersionStats>,
+    /// Fraudulent transactions whose alarm was skipped by `max_alarms_per_batch`.
+    pub alarms_suppressed: u64,
+    /// Transactions rejected by input validation; never inferred.
+    pub rejected: u64,
+    /// Transactions discarded unread by load shedding; never inferred.
+    pub shed: u64,
+    /// Transactions inference failed on, kept by the configured quarantine.
+    pub quarantined: u64,
+    /// Transactions inference failed on that the quarantine could not record; lost.
+    pub quarantine_errors: u64,
+    /// Reads that returned no transactions; nothing was inferred or written.
+    pub empty_polls: u64,
+    /// Sleeps taken between run-loop iterations.
+    pub pacing: PacingStats,
+    /// Last `switch_history` applied model switches, oldest first.
+    pub switches: Vec<SwitchRecord>,
+    /// Delivery times of the triggered alarms, failed deliveries included.
+    pub alarm_latency: AlarmLatency,
+    /// Buffer2 write attempts of the written transactions.
+    pub delivery_attempts: DeliveryAttempts,
+    /// Transactions answered from the inference cache; never sent to the
+    /// Modelizer.
+    pub cache_hits: u64,
+}
+
+impl fmt::Display for ConsumerStats {
+    /// Multi-line summary: one totals line, then one line per model version.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "consumer: {} batches, {} transactions, {} flagged",
+            self.batches, self.transactions, self.flagged
+        )?;
+        if self.alarms_suppressed > 0 {
+            write!(f, ", {} alarms suppressed", self.alarms_suppressed)?;
+        }
+        if self.rejected > 0 {
+            write!(f, ", {} rejected", self.rejected)?;
+        }
+        if self.shed > 0 {
+            write!(f, ", {} shed", self.shed)?;
+        }
+        if self.quarantined > 0 {
+            write!(f, ", {} quarantined", self.quarantined)?;
+        }
+        if self.quarantine_errors > 0 {
+            write!(f, ", {} quarantine errors", self.quarantine_errors)?;
+        }
+        if self.empty_polls > 0 {
+            write!(f, ", {} empty polls", self.empty_polls)?;
+        }
+        if self.cache_hits > 0 {
+            write!(f, ", {} cache hits", self.cache_hits)?;
+        }
+        if self.delivery_attempts.max > 1 {
+            let mean = self.delivery_attempts.mean().unwrap_or_default();
+            write!(f, ", write attempts max {} mean {mean:.2}", self.delivery_attempts.max)?;
+        }
+        if self.alarm_latency.count() > 0 {
+            write!(f, "\n  alarm delivery: {}", self.alarm_latency)?;
+        }
+        for (version, vs) in &self.per_version {
+            write!(
+                f,
+                "\n  model version {version}: {} transactions, {} flagged",
+                vs.transactions, vs.flagged
+            )?;
+        }
+        for switch in &self.switches {
+            let rollback = if switch.rollback { " (rollback)" } else { "" };
+            write!(f, "\n  model switch {:?} -> {:?}{rollback}", switch.from, switch.to)?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// NoDeadLetter
+// ---------------------------------------------------------------------------
+
+/// Type witness for "no quarantine sink"; never called.
+struct NoDeadLetter;
+
+impl DeadLetter for NoDeadLetter {
+    async fn write_dead_letters(&self, _batch: Vec<RejectedTransaction>) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Consumer
 // ---------------------------------------------------------------------------
@@ -138,702 +1051,4224 @@ impl ConsumerConfigBuilder {
 pub struct Consumer {
     config: ConsumerConfig,
     /// Interior mutability required because all public methods take `&self`.
-    rng: RefCell<StdRng>,
+    rng: RefCell<Box<dyn RngPort>>,
+    /// Seed of the `StdRng`; `None` when the RNG was injected.
+    effective_seed: Option<u64>,
+    /// Cumulative counters; updated from each inferred batch.
+    stats: RefCell<ConsumerStats>,
+    /// `(read, requested)` sizes of the last Buffer1 read, for the adaptive sleep.
+    last_read: Cell<(usize, usize)>,
+    /// Notified by the run loops, in registration order.
+    observers: Vec<Box<dyn BatchObserver>>,
+    /// Model version the Modelizer was last switched to; assumed `N` until then.
+    active_version: Cell<ModelVersion>,
+    /// Version active before the last switch; the target of a rollback.
+    previous_version: Cell<Option<ModelVersion>>,
+    /// When the last switch was applied, for the cooldown.
+    last_switch_at: Cell<Option<SystemTime>>,
+    /// Settings in force; see [`ConsumerConfigBuilder::settings`].
+    settings: LiveSettings,
+    /// Verdicts of the active model version; see
+    /// [`ConsumerConfigBuilder::inference_cache`].
+    cache: Option<RefCell<InferenceCache>>,
 }
 
 impl Consumer {
     /// Create a new consumer from `config`.
     ///
-    /// Seeds the RNG from `config.seed` if set, otherwise from the OS.
+    /// Seeds the RNG from `config.seed` if set, otherwise from a seed drawn
+    /// from the OS; either way it is kept as [`effective_seed`](Self::effective_seed).
     #[must_use]
     pub fn new(config: ConsumerConfig) -> Self {
-        let rng = match config.seed {
-            Some(seed) => StdRng::seed_from_u64(seed),
-            None => StdRng::from_os_rng(),
+        let seed = config.seed.unwrap_or_else(rand::random);
+        tracing::info!(target: TARGET, seed, "consumer.seed");
+        let mut consumer = Self::with_rng(config, StdRng::seed_from_u64(seed));
+        consumer.effective_seed = Some(seed);
+        consumer
+    }
+
+    /// Like [`new`](Self::new), but draws batch sizes and warmup data from
+    /// `rng` instead of a `StdRng`; `config.seed` is ignored. Lets tests
+    /// inject a recording or replaying generator.
+    #[must_use]
+    pub fn with_rng(config: ConsumerConfig, rng: impl RngPort + 'static) -> Self {
+        let seed = DynamicSettings {
+            poll_interval2: config.poll_interval2,
+            batch_size_mode: config.batch_size_mode,
+            slow_alarm_threshold: config.slow_alarm_threshold,
+            ..DynamicSettings::default()
         };
-        Self { config, rng: RefCell::new(rng) }
+        let settings = LiveSettings::new(seed, config.settings.clone());
+        let cache = config.inference_cache.map(|n| RefCell::new(InferenceCache::new(n)));
+        Self {
+            config,
+            rng: RefCell::new(Box::new(rng)),
+            effective_seed: None,
+            stats: RefCell::new(ConsumerStats::default()),
+            last_read: Cell::new((0, 0)),
+            observers: vec![],
+            active_version: Cell::new(ModelVersion::N),
+            previous_version: Cell::new(None),
+            last_switch_at: Cell::new(None),
+            settings,
+            cache,
+        }
     }
 
-    /// Read one batch from Buffer1, infer via Modelizer, trigger best-effort
-    /// alarms for fraudulent transactions, and write all results to Buffer2.
-    ///
-    /// Returns collected alarm failures in `Ok(vec)`; hard errors propagate as `Err`.
-    ///
-    /// # Errors
+    /// Register `observer` with the run loops; see [`BatchObserver`].
     ///
-    /// Returns [`ConsumerError::Read`] on Buffer1 failure (including `Closed`),
-    /// [`ConsumerError::Inference`] on Modelizer failure, or
-    /// [`ConsumerError::Write`] on Buffer2 failure.
-    #[tracing::instrument(skip_all, level = "debug")]
-    pub async fn consume_once<B1, M, A, B2>(
-        &self,
-        buf1: &B1,
-        modelizer: &M,
-        alarm: &A,
-        buf2: &B2,
-    ) -> Result<Vec<AlarmError>, ConsumerError>
-    where
-        B1: Buffer1Read,
-        M: Modelizer,
-        A: Alarm,
-        B2: Buffer2,
-    {
-        let n2 = self.rng.borrow_mut().random_range(1..=self.config.n2_max);
-        let batch = buf1.read_batch(n2).await.map_err(ConsumerError::Read)?;
+    /// Accepts a single observer or a tuple of up to three. Calling it again
+    /// adds another; all are notified in registration order.
+    #[must_use]
+    pub fn with_observer(mut self, observer: impl BatchObserver + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
 
-        tracing::debug!(size = batch.len(), "consumer.batch.read");
+    /// The configuration this consumer was built with.
+    #[must_use]
+    pub fn config(&self) -> &ConsumerConfig {
+        &self.config
+    }
 
-        let inferred = modelizer.infer(batch).await.map_err(ConsumerError::Inference)?;
+    /// Seed the RNG was built from: `config.seed`, or the one drawn from the
+    /// OS when it was `None`. Passing it back as `config.seed` replays the
+    /// run. `None` for a consumer built [`with_rng`](Self::with_rng).
+    #[must_use]
+    pub fn effective_seed(&self) -> Option<u64> {
+        self.effective_seed
+    }
 
-        // Best-effort alarm delivery: attempt every fraudulent transaction,
-        // collect failures without aborting the batch.
-        let mut alarm_errors: Vec<AlarmError> = vec![];
-        for tx in &inferred {
-            if tx.predicted_fraud && let Err(e) = alarm.trigger(tx).await {
-                alarm_errors.push(e);
+    /// Return a snapshot of the cumulative counters.
+    #[must_use]
+    pub fn stats(&self) -> ConsumerStats {
+        self.stats.borrow().clone()
+    }
+
+    /// Publish `event` to the configured observer channel, if any.
+    fn emit(&self, event: PipelineEvent) {
+        if let Some(events) = &self.config.events {
+            // Err only means no receiver is subscribed right now.
+            let _ = events.send(event);
+        }
+    }
+
+    /// Tell the observers about a processed batch.
+    fn notify_batch(&self, outcome: &ConsumeOutcome) {
+        for observer in &self.observers {
+            observer.on_batch(outcome);
+        }
+    }
+
+    /// Log the alarm failures of a consumed batch and notify the observers,
+    /// unless the read was empty. Returns whether it was.
+    fn batch_done(&self, outcome: &ConsumeOutcome, alarm_errors: &[AlarmError]) -> bool {
+        for e in alarm_errors {
+            tracing::warn!(target: TARGET, error = %e, "consumer.alarm.failed");
+        }
+        if outcome.read == 0 {
+            return true;
+        }
+        self.notify_batch(outcome);
+        false
+    }
+
+    /// Publish `StageStopped` for this stage and notify the observers.
+    fn emit_stopped(&self, end: &RunEnd) {
+        for observer in &self.observers {
+            observer.on_stop(end);
+        }
+        let reason = end.to_string();
+        self.emit(PipelineEvent::StageStopped { stage: Stage::Consumer, reason });
+    }
+
+    /// Log and publish a clean stop; returns `reason` for the run loop to return.
+    fn stopped(&self, reason: StopReason) -> StopReason {
+        let iterations = reason.iterations();
+        domain::log_stop!("consumer", reason, iterations);
+        self.emit_stopped(&RunEnd::Stopped(reason));
+        reason
+    }
+
+    /// Sleep between two iterations: `poll_interval2`, or the adaptive sleep
+    /// for the last read. Recorded in [`ConsumerStats::pacing`].
+    async fn pause_between_iterations(&self) {
+        let base = self.settings.get().poll_interval2;
+        let sleep = {
+            let mut stats = self.stats.borrow_mut();
+            let sleep = self.config.adaptive_interval.map_or(base, |adaptive| {
+                let (read, requested) = self.last_read.get();
+                adaptive.next_sleep(base, stats.pacing.last, read, requested)
+            });
+            stats.pacing.record(sleep);
+            sleep
+        };
+        tracing::debug!(target: TARGET, sleep_ms = sleep.as_millis(), "consumer.sleep");
+        self.config.sleeper.sleep(sleep).await;
+    }
+
+    /// Prime `modelizer` with `n` synthetic transactions and discard the results.
+    ///
+    /// The transactions come from the consumer's own RNG (so a seeded consumer
+    /// warms up deterministically) and never reach alarms, Buffer2, stats or
+    /// events. `n == 0` makes no Modelizer call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConsumerError::Inference`] if the Modelizer rejects the batch.
+    #[tracing::instrument(target = "pipeline::consumer", skip(self, modelizer))]
+    pub async fn warmup<M: Modelizer>(&self, modelizer: &M, n: usize) -> Result<(), ConsumerError> {
+        if n == 0 {
+            return Ok(());
+        }
+        let batch: Vec<Transaction> = {
+            let mut rng = self.rng.borrow_mut();
+            (0..n)
+                .map(|_| Transaction {
+                    id: TransactionId::from_uuid(
+                        uuid::Builder::from_random_bytes(rng.random()).into_uuid(),
+                    ),
+                    amount: f64::from(rng.random_range(1u32..=1_000_000u32)) / 100.0,
+                    last_name: "warmup".to_owned(),
+                    currency: Currency::Eur,
+                    tenant_id: None,
+                })
+                .collect()
+        };
+        self.infer_checked(modelizer, batch).await?;
+        tracing::info!(target: TARGET, batch_size = n, "consumer.warmup.completed");
+        Ok(())
+    }
+
+    /// Run the configured warmup at the start of a run loop.
+    ///
+    /// A failure is returned only when `warmup_strict` is set; otherwise it is
+    /// logged and the run proceeds.
+    async fn warmup_before_run<M: Modelizer>(&self, modelizer: &M) -> Result<(), ConsumerError> {
+        match self.warmup(modelizer, self.config.warmup).await {
+            Err(e) if self.config.warmup_strict => {
+                self.emit_stopped(&RunEnd::Failed(ErrorChain(&e).to_string()));
+                Err(e)
             }
+            Err(e) => {
+                tracing::warn!(target: TARGET, error = %ErrorChain(&e), "consumer.warmup.failed");
+                Ok(())
+            }
+            Ok(()) => Ok(()),
         }
+    }
 
-        buf2.write_batch(inferred).await.map_err(ConsumerError::Write)?;
+    /// Read one batch from Buffer1, infer via Modelizer, trigger best-effort
+    /// alarms for fraudulent transactions, and write all results to Buffer2.
+    ///
+    /// Returns collected alarm failures in `Ok(vec)`; hard errors propagate as `Err`.
+    /// Alarms fire before the write, or after it with
+    /// [`AlarmOrdering::WriteFirst`], in which case a failed write fires none.
+    /// An empty read is counted in [`ConsumerStats::empty_polls`] and does
+    /// nothing else: no inference, no alarm, no Buffer2 write.
+    /// With `validate_input`, invalid transactions are only counted; see
+    /// [`consume_once_with_dead_letters`](Self::consume_once_with_dead_letters)
+    /// to quarantine them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConsumerError::Read`] on Buffer1 failure (including `Closed`),
+    /// [`ConsumerError::Inference`] on Modelizer failure, or
+    /// [`ConsumerError::Write`] on Buffer2 failure.
+    ///
+    /// # Examples
+    ///
+    /// The ports come in pipeline order: Buffer1, Modelizer, Alarm, Buffer2.
+    ///
+    /// ```
+    /// use consumer::{Consumer, ConsumerConfig};
+    /// use domain::{Buffer1 as _, BufferDepth as _, Currency, Transaction, TransactionId};
+    /// use modelizer::Modelizer;
+    /// use pipeline::memory::{CountingAlarm, MemoryBuffer, RateModel};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let buffer1 = MemoryBuffer::new();
+    /// let batch = (1..=4).map(|i| Transaction {
+    ///     id: TransactionId::new_v4(),
+    ///     amount: f64::from(i) * 10.0,
+    ///     last_name: "Doe".to_owned(),
+    ///     currency: Currency::Eur,
+    ///     tenant_id: None,
+    /// });
+    /// buffer1.write_batch(batch.collect()).await?;
+    ///
+    /// let consumer = Consumer::new(ConsumerConfig::builder(4).fixed_batch_size(true).build()?);
+    /// // RateModel 1.0 flags every transaction.
+    /// let modelizer = Modelizer::new(RateModel::new(1.0, 0));
+    /// let (alarm, buffer2) = (CountingAlarm::new(), MemoryBuffer::new());
+    ///
+    /// let alarm_errors = consumer.consume_once(&buffer1, &modelizer, &alarm, &buffer2).await?;
+    /// assert!(alarm_errors.is_empty());
+    /// assert_eq!((buffer1.depth(), buffer2.depth()), (0, 4));
+    /// assert_eq!((alarm.count(), consumer.stats().flagged), (4, 4));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(target = "pipeline::consumer", skip_all, level = "debug")]
+    pub async fn consume_once<B1, M, A, B2>(
+        &self,
+        buf1: &B1,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+    ) -> Result<Vec<AlarmError>, ConsumerError>
+    where
+        B1: Buffer1Read,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+    {
+        let (_, alarm_errors) =
+            self.consume_batch(buf1, modelizer, alarm, buf2, None::<&NoDeadLetter>).await?;
+        Ok(alarm_errors)
+    }
 
+    /// Like [`consume_once`](Self::consume_once), but quarantines the
+    /// transactions rejected by `validate_input` into `dead_letters`.
+    ///
+    /// # Errors
+    ///
+    /// As [`consume_once`](Self::consume_once), plus
+    /// [`ConsumerError::DeadLetter`] when quarantining fails.
+    #[tracing::instrument(target = "pipeline::consumer", skip_all, level = "debug")]
+    pub async fn consume_once_with_dead_letters<B1, M, A, B2, D>(
+        &self,
+        buf1: &B1,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+        dead_letters: &D,
+    ) -> Result<Vec<AlarmError>, ConsumerError>
+    where
+        B1: Buffer1Read,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+        D: DeadLetter,
+    {
+        let (_, alarm_errors) =
+            self.consume_batch(buf1, modelizer, alarm, buf2, Some(dead_letters)).await?;
         Ok(alarm_errors)
     }
 
+    /// Write `batch` to `buf2`, in chunks of `write_chunk_size` if set; see
+    /// [`ConsumerConfigBuilder::write_chunk_size`].
+    async fn write<B2: Buffer2>(
+        &self,
+        buf2: &B2,
+        mut batch: Vec<InferredTransaction>,
+    ) -> Result<(), WriteError> {
+        let Some(size) = self.config.write_chunk_size else {
+            return self.write_chunk(buf2, &mut batch, false).await;
+        };
+        let mut rest = batch.into_iter();
+        let mut chunk: Vec<_> = rest.by_ref().take(size).collect();
+        self.write_chunk(buf2, &mut chunk, false).await?;
+        loop {
+            chunk.extend(rest.by_ref().take(size));
+            if chunk.is_empty() {
+                return Ok(());
+            }
+            let (size, remaining) = (chunk.len(), rest.len());
+            tracing::debug!(target: TARGET, batch_size = size, remaining, "consumer.batch.chunk");
+            self.write_chunk(buf2, &mut chunk, true).await?;
+        }
+    }
+
+    /// Write `chunk` to `buf2` with `write_batch_partial`, retrying the
+    /// rejected remainder as described in [`ConsumerConfigBuilder::write_retries`].
+    /// With `retry_full` unset, a `Full` on the first attempt fails at once.
+    async fn write_chunk<B2: Buffer2>(
+        &self,
+        buf2: &B2,
+        chunk: &mut Vec<InferredTransaction>,
+        mut retry_full: bool,
+    ) -> Result<(), WriteError> {
+        let mut retries = 0;
+        loop {
+            // The chunk is resent whole, so its transactions share one count.
+            let attempts = chunk.first().map_or(1, |it| it.delivery_attempts);
+            match buf2.write_batch_partial(chunk).await {
+                Ok(written) => self.stats.borrow_mut().delivery_attempts.record(written, attempts),
+                // The provided write_batch_partial hands the whole chunk to
+                // write_batch, leaving nothing to retry.
+                Err(e @ WriteError::Full { .. }) if chunk.is_empty() => return Err(e),
+                Err(WriteError::Full { .. }) if retry_full && retries < self.config.write_retries => {
+                    retries += 1;
+                }
+                Err(e) => return Err(e),
+            }
+            if chunk.is_empty() {
+                return Ok(());
+            }
+            retry_full = true;
+            tracing::debug!(
+                target: TARGET,
+                remaining = chunk.len(),
+                retries,
+                "consumer.batch.partial"
+            );
+            self.config.sleeper.sleep(self.settings.get().poll_interval2).await;
+            for it in chunk.iter_mut() {
+                it.delivery_attempts = it.delivery_attempts.saturating_add(1);
+            }
+        }
+    }
+
+    /// Best-effort alarm delivery: attempt every fraudulent transaction of
+    /// `batch` up to the per-batch cap, collecting failures without aborting.
+    ///
+    /// Returns the delivery errors and the number of alarms attempted.
+    async fn trigger_alarms<A: Alarm>(
+        &self,
+        alarm: &A,
+        batch: &[InferredTransaction],
+    ) -> (Vec<AlarmError>, usize) {
+        let mut alarm_errors: Vec<AlarmError> = vec![];
+        let mut triggered = 0usize;
+        let mut suppressed = 0u64;
+        for tx in batch.iter().filter(|tx| tx.predicted_fraud) {
+            if self.config.max_alarms_per_batch.is_some_and(|cap| triggered >= cap) {
+                suppressed += 1;
+                continue;
+            }
+            triggered += 1;
+            let start = Instant::now();
+            let delivered = alarm.trigger(tx).await;
+            self.record_alarm_latency(tx.id(), start.elapsed());
+            match delivered {
+                Ok(()) => {
+                    let amount = tx.transaction.amount;
+                    self.emit(PipelineEvent::AlarmTriggered { id: tx.id(), amount });
+                }
+                Err(e) => {
+                    self.emit(PipelineEvent::AlarmFailed { id: tx.id() });
+                    alarm_errors.push(e);
+                }
+            }
+        }
+        if suppressed > 0 {
+            tracing::warn!(
+                target: TARGET,
+                suppressed,
+                triggered,
+                "consumer.alarm.suppressed: per-batch cap reached"
+            );
+            self.stats.borrow_mut().alarms_suppressed += suppressed;
+        }
+        (alarm_errors, triggered)
+    }
+
+    /// Adopt the latest live settings, if any were published; an invalid
+    /// update is logged and the settings in force are kept.
+    fn refresh_settings(&self) {
+        match self.settings.refresh() {
+            Ok(false) => {}
+            Ok(true) => {
+                let settings = self.settings.get();
+                tracing::info!(
+                    target: TARGET,
+                    poll_interval2 = ?settings.poll_interval2,
+                    batch_size_mode = %settings.batch_size_mode,
+                    slow_alarm_threshold = ?settings.slow_alarm_threshold,
+                    "consumer.settings.reloaded"
+                );
+            }
+            Err(e) => tracing::warn!(target: TARGET, error = %e, "consumer.settings.rejected"),
+        }
+    }
+
+    /// Account for one alarm delivery of `elapsed`, warning when it exceeds
+    /// `slow_alarm_threshold`.
+    fn record_alarm_latency(&self, id: TransactionId, elapsed: Duration) {
+        let threshold = self.settings.get().slow_alarm_threshold;
+        let slow = threshold.is_some_and(|threshold| elapsed > threshold);
+        if slow {
+            tracing::warn!(target: TARGET, %id, ?elapsed, "consumer.alarm.slow");
+        }
+        self.stats.borrow_mut().alarm_latency.record(elapsed, slow);
+    }
+
+    /// One batch, as `consume_once`; also returns what it did for observers.
+    async fn consume_batch<B1, M, A, B2, D>(
+        &self,
+        buf1: &B1,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+        dead_letters: Option<&D>,
+    ) -> Result<(ConsumeOutcome, Vec<AlarmError>), ConsumerError>
+    where
+        B1: Buffer1Read,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+        D: DeadLetter,
+    {
+        let n2 = if self.config.fixed_batch_size {
+            self.config.n2_max
+        } else {
+            let mode = self.settings.get().batch_size_mode;
+            mode.sample(self.config.n2_max, &mut **self.rng.borrow_mut())
+        };
+        let mut batch = buf1.read_batch(n2).await.map_err(ConsumerError::Read)?;
+        self.last_read.set((batch.len(), n2));
+
+        if batch.is_empty() {
+            // Adapters fed by a channel or HTTP may time out with nothing to return.
+            self.stats.borrow_mut().empty_polls += 1;
+            tracing::debug!(target: TARGET, max = n2, "consumer.batch.empty");
+            return Ok((ConsumeOutcome::default(), vec![]));
+        }
+
+        tracing::debug!(target: TARGET, batch_size = batch.len(), "consumer.batch.read");
+        let read = batch.len();
+
+        if self.config.validate_input || self.config.currency_converter.is_some() {
+            batch = self.reject_invalid(batch, dead_letters).await?;
+            if batch.is_empty() {
+                // Everything was rejected: nothing to infer, alarm or forward.
+                self.emit(PipelineEvent::BatchDiscarded { size: read });
+                let outcome = ConsumeOutcome { read, rejected: read, ..ConsumeOutcome::default() };
+                return Ok((outcome, vec![]));
+            }
+        }
+
+        let rejected = read - batch.len();
+        let (inferred, quarantined) = self.infer_cached(modelizer, batch).await?;
+        if inferred.is_empty() {
+            // Everything was quarantined: nothing to alarm or forward.
+            self.emit(PipelineEvent::BatchDiscarded { size: read });
+            let outcome =
+                ConsumeOutcome { read, rejected, quarantined, ..ConsumeOutcome::default() };
+            return Ok((outcome, vec![]));
+        }
+        self.record_batch(&inferred);
+        let mut outcome = ConsumeOutcome {
+            read,
+            rejected,
+            quarantined,
+            inferred: inferred.len(),
+            flagged: inferred.iter().filter(|tx| tx.predicted_fraud).count(),
+            model_version: inferred.first().map(|tx| tx.model_version.clone()).unwrap_or_default(),
+            ..ConsumeOutcome::default()
+        };
+        self.emit(PipelineEvent::BatchInferred {
+            size: outcome.inferred,
+            flagged: outcome.flagged,
+            version: outcome.model_version.clone(),
+        });
+
+        let (alarm_errors, triggered) = match self.config.alarm_ordering {
+            AlarmOrdering::AlarmsFirst => {
+                let delivery = self.trigger_alarms(alarm, &inferred).await;
+                self.write(buf2, inferred).await.map_err(ConsumerError::Write)?;
+                delivery
+            }
+            AlarmOrdering::WriteFirst => {
+                // The write consumes the batch; keep the flagged ones for the alarms.
+                let flagged: Vec<InferredTransaction> =
+                    inferred.iter().filter(|tx| tx.predicted_fraud).cloned().collect();
+                self.write(buf2, inferred).await.map_err(ConsumerError::Write)?;
+                self.trigger_alarms(alarm, &flagged).await
+            }
+        };
+
+        outcome.alarms_failed = alarm_errors.len();
+        outcome.alarms_suppressed = outcome.flagged - triggered;
+        Ok((outcome, alarm_errors))
+    }
+
     /// Run the consumption loop until stopped.
     ///
-    /// Calls [`consume_once`](Self::consume_once) repeatedly, sleeping `poll_interval2`
-    /// between iterations. Stops cleanly when:
-    /// - Buffer1 signals [`BufferError::Closed`] (returns `Ok(())`), or
-    /// - `config.iterations` batches have been processed (returns `Ok(())`).
+    /// First performs the configured warmup (see [`ConsumerConfigBuilder::warmup`]),
+    /// then calls [`consume_once`](Self::consume_once) repeatedly, sleeping `poll_interval2`
+    /// (or the adaptive sleep, see [`ConsumerConfigBuilder::adaptive_interval`])
+    /// between iterations. Stops cleanly, returning the matching
+    /// [`StopReason`], when:
+    /// - Buffer1 signals [`ReadError::Closed`] (`BufferClosed`), or
+    /// - `config.iterations` batches have been processed (`IterationLimit`).
+    ///
+    /// An empty read counts as an iteration, so `iterations` still bounds a
+    /// loop over an adapter that keeps timing out; it is logged at `debug`
+    /// only and not reported to the observers.
     ///
     /// Alarm failures within a batch are logged as warnings but do not abort the loop.
     ///
     /// # Errors
     ///
-    /// Returns [`ConsumerError`] for any hard error other than Buffer1 `Closed`.
-    #[tracing::instrument(name = "consumer.run", skip_all)]
+    /// Returns [`ConsumerError`] for any hard error other than Buffer1 `Closed`,
+    /// including a failed warmup when `warmup_strict` is set.
+    #[tracing::instrument(target = "pipeline::consumer", name = "consumer.run", skip_all)]
     pub async fn run<B1, M, A, B2>(
         &self,
         buf1: &B1,
         modelizer: &M,
         alarm: &A,
         buf2: &B2,
-    ) -> Result<(), ConsumerError>
+    ) -> Result<StopReason, ConsumerError>
+    where
+        B1: Buffer1Read,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+    {
+        self.run_loop(buf1, None, modelizer, alarm, buf2, None::<&NoDeadLetter>).await
+    }
+
+    /// Like [`run`](Self::run), but quarantines the transactions rejected by
+    /// `validate_input` into `dead_letters`.
+    ///
+    /// Also applies [`shed_above`](ConsumerConfigBuilder::shed_above), so shed
+    /// transactions are quarantined too.
+    ///
+    /// # Errors
+    ///
+    /// As [`run`](Self::run), plus [`ConsumerError::DeadLetter`] when
+    /// quarantining fails.
+    #[tracing::instrument(target = "pipeline::consumer", name = "consumer.run", skip_all)]
+    pub async fn run_with_dead_letters<B1, M, A, B2, D>(
+        &self,
+        buf1: &B1,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+        dead_letters: &D,
+    ) -> Result<StopReason, ConsumerError>
+    where
+        B1: Buffer1Read + BufferDepth,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+        D: DeadLetter,
+    {
+        self.run_loop(buf1, Some(buf1), modelizer, alarm, buf2, Some(dead_letters)).await
+    }
+
+    /// Shared loop of `run` and `run_with_dead_letters`; sheds load only when
+    /// Buffer1's `depth` is known.
+    async fn run_loop<B1, M, A, B2, D>(
+        &self,
+        buf1: &B1,
+        depth: Option<&dyn BufferDepth>,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+        dead_letters: Option<&D>,
+    ) -> Result<StopReason, ConsumerError>
     where
         B1: Buffer1Read,
         M: Modelizer,
         A: Alarm,
         B2: Buffer2,
+        D: DeadLetter,
     {
+        tracing::info!(target: TARGET, config = %self.config.summary(), "consumer.run.config");
+        self.warmup_before_run(modelizer).await?;
         let mut count = 0u64;
         loop {
-            match self.consume_once(buf1, modelizer, alarm, buf2).await {
-                Ok(alarm_errs) => {
-                    for e in &alarm_errs {
-                        tracing::warn!(error = %e, "consumer.alarm.failed");
-                    }
+            self.refresh_settings();
+            let batch = async {
+                if let Some(depth) = depth {
+                    self.shed_backlog(buf1, depth, dead_letters).await?;
                 }
-                Err(ConsumerError::Read(BufferError::Closed)) => {
-                    tracing::info!(count, "consumer.run.stopped: buffer closed");
-                    return Ok(());
+                self.consume_batch(buf1, modelizer, alarm, buf2, dead_letters).await
+            };
+            let (empty, outcome) = match batch.await {
+                Ok((outcome, alarm_errs)) => (self.batch_done(&outcome, &alarm_errs), outcome),
+                Err(ConsumerError::Read(ReadError::Closed)) => {
+                    return Ok(self.stopped(StopReason::BufferClosed { iterations: count }));
                 }
-                Err(e) => return Err(e),
-            }
+                Err(e) => {
+                    self.emit_stopped(&RunEnd::Failed(ErrorChain(&e).to_string()));
+                    return Err(e);
+                }
+            };
 
             count += 1;
-            tracing::info!(iteration = count, "consumer.batch.processed");
+            if !empty {
+                domain::log_batch!(
+                    "consumer",
+                    outcome.inferred,
+                    outcome.flagged,
+                    iteration = count
+                );
+            }
 
             if let Some(max) = self.config.iterations
                 && count >= max
             {
-                tracing::info!("consumer.run.stopped: iteration limit reached");
-                return Ok(());
+                return Ok(self.stopped(StopReason::IterationLimit { iterations: count }));
             }
 
-            tokio::time::sleep(self.config.poll_interval2).await;
+            self.pause_between_iterations().await;
         }
     }
 
-    /// Delegate a model version switch to the Modelizer port.
+    /// Run the consumption loop, applying operator commands between iterations.
     ///
-    /// Consumer holds no version state; Modelizer owns it internally.
+    /// Behaves like [`run`](Self::run) and additionally drains `commands`
+    /// (non-blocking) at the top of every iteration; see [`ConsumerCommand`].
+    /// A disconnected command channel is ignored and the loop carries on.
+    ///
+    /// After a [`ConsumerCommand::DrainAndStop`] the loop returns
+    /// `Ok(StopReason::Cancelled { .. })` on its own; the caller's shutdown
+    /// cascade (closing Buffer2) then proceeds as after a normal `run`.
     ///
     /// # Errors
     ///
-    /// Returns [`ConsumerError::Inference`] if the switch fails.
-    #[tracing::instrument(skip(self, modelizer), fields(?version))]
-    pub async fn switch_model_version<M: Modelizer>(
+    /// Returns [`ConsumerError`] for any hard error other than Buffer1 `Closed`,
+    /// including a failed [`ConsumerCommand::SwitchVersion`] or
+    /// [`ConsumerCommand::Rollback`] or a failed warmup when `warmup_strict`
+    /// is set. A throttled switch is only logged.
+    ///
+    /// Transactions rejected by `validate_input` or shed by
+    /// [`shed_above`](ConsumerConfigBuilder::shed_above) are only counted
+    /// here; [`run_with_commands_and_dead_letters`](Self::run_with_commands_and_dead_letters)
+    /// also quarantines them.
+    #[tracing::instrument(
+        target = "pipeline::consumer",
+        name = "consumer.run_with_commands",
+        skip_all
+    )]
+    pub async fn run_with_commands<B1, M, A, B2>(
         &self,
+        buf1: &B1,
         modelizer: &M,
-        version: ModelVersion,
-    ) -> Result<(), ConsumerError> {
-        modelizer.switch_version(version).await.map_err(ConsumerError::Inference)
+        alarm: &A,
+        buf2: &B2,
+        commands: mpsc::Receiver<ConsumerCommand>,
+    ) -> Result<StopReason, ConsumerError>
+    where
+        B1: Buffer1Read + BufferDepth,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+    {
+        self.command_loop(buf1, modelizer, alarm, buf2, commands, None::<&NoDeadLetter>).await
     }
-}
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+    /// Like [`run_with_commands`](Self::run_with_commands), but quarantines
+    /// the transactions rejected by `validate_input` or shed by
+    /// [`shed_above`](ConsumerConfigBuilder::shed_above) into `dead_letters`.
+    ///
+    /// # Errors
+    ///
+    /// As [`run_with_commands`](Self::run_with_commands), plus
+    /// [`ConsumerError::DeadLetter`] when quarantining fails.
+    #[tracing::instrument(
+        target = "pipeline::consumer",
+        name = "consumer.run_with_commands",
+        skip_all
+    )]
+    pub async fn run_with_commands_and_dead_letters<B1, M, A, B2, D>(
+        &self,
+        buf1: &B1,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+        commands: mpsc::Receiver<ConsumerCommand>,
+        dead_letters: &D,
+    ) -> Result<StopReason, ConsumerError>
+    where
+        B1: Buffer1Read + BufferDepth,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+        D: DeadLetter,
+    {
+        self.command_loop(buf1, modelizer, alarm, buf2, commands, Some(dead_letters)).await
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::{Consumer, ConsumerConfig, ConsumerError};
-    use domain::{
-        Alarm, AlarmError, Buffer1Read, Buffer2, BufferError, InferredTransaction,
-        Modelizer, ModelizerError, ModelVersion, Transaction,
-    };
-    use std::cell::{Cell, RefCell};
-    use std::collections::VecDeque;
-    use std::time::Duration;
+    /// Shared loop of `run_with_commands` and
+    /// `run_with_commands_and_dead_letters`.
+    async fn command_loop<B1, M, A, B2, D>(
+        &self,
+        buf1: &B1,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+        mut commands: mpsc::Receiver<ConsumerCommand>,
+        dead_letters: Option<&D>,
+    ) -> Result<StopReason, ConsumerError>
+    where
+        B1: Buffer1Read + BufferDepth,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2,
+        D: DeadLetter,
+    {
+        tracing::info!(target: TARGET, config = %self.config.summary(), "consumer.run.config");
+        self.warmup_before_run(modelizer).await?;
+        let mut count = 0u64;
+        let mut paused = false;
+        // Transaction total when DrainAndStop arrived; `Some` while draining.
+        let mut drain_start: Option<u64> = None;
+        let mut idle_polls = 0usize;
+        loop {
+            self.refresh_settings();
+            while let Ok(command) = commands.try_recv() {
+                match command {
+                    ConsumerCommand::SwitchVersion(version) => {
+                        let result = self.switch_model_version(modelizer, version).await;
+                        self.switch_command_result(result)?;
+                    }
+                    ConsumerCommand::Rollback => {
+                        let result = self.rollback_model_version(modelizer).await.map(|_| ());
+                        self.switch_command_result(result)?;
+                    }
+                    ConsumerCommand::Pause => {
+                        tracing::info!(target: TARGET, "consumer.command.pause");
+                        paused = true;
+                    }
+                    ConsumerCommand::Resume => {
+                        tracing::info!(target: TARGET, "consumer.command.resume");
+                        paused = false;
+                    }
+                    ConsumerCommand::DrainAndStop => {
+                        tracing::info!(
+                            target: TARGET,
+                            depth = buf1.depth(),
+                            "consumer.command.drain_and_stop"
+                        );
+                        if drain_start.is_none() {
+                            drain_start = Some(self.stats.borrow().transactions);
+                        }
+                    }
+                }
+            }
 
-    // ------------------------------------------------------------------
-    // Test helpers
-    // ------------------------------------------------------------------
+            if let Some(start) = drain_start {
+                // Never block on an empty buffer while draining: an open but
+                // empty ConcurrentBuffer would park read_batch indefinitely.
+                if buf1.depth() == 0 {
+                    idle_polls += 1;
+                    if idle_polls >= self.config.drain_idle_polls {
+                        let drained = self.stats.borrow().transactions - start;
+                        tracing::info!(target: TARGET, drained, "consumer.drain.completed");
+                        return Ok(self.stopped(StopReason::Cancelled { iterations: count }));
+                    }
+                    tokio::task::yield_now().await;
+                    continue;
+                }
+                idle_polls = 0;
+            } else if paused {
+                self.config.sleeper.sleep(self.settings.get().poll_interval2).await;
+                continue;
+            }
+
+            let batch = async {
+                // Draining finishes the backlog instead of shedding it.
+                if drain_start.is_none() {
+                    self.shed_backlog(buf1, buf1, dead_letters).await?;
+                }
+                self.consume_batch(buf1, modelizer, alarm, buf2, dead_letters).await
+            };
+            let (empty, outcome) = match batch.await {
+                Ok((outcome, alarm_errs)) => (self.batch_done(&outcome, &alarm_errs), outcome),
+                Err(ConsumerError::Read(ReadError::Closed)) => {
+                    if let Some(start) = drain_start {
+                        let drained = self.stats.borrow().transactions - start;
+                        tracing::info!(target: TARGET, drained, "consumer.drain.completed");
+                    }
+                    return Ok(self.stopped(StopReason::BufferClosed { iterations: count }));
+                }
+                Err(e) => {
+                    self.emit_stopped(&RunEnd::Failed(ErrorChain(&e).to_string()));
+                    return Err(e);
+                }
+            };
+
+            count += 1;
+            if !empty {
+                domain::log_batch!(
+                    "consumer",
+                    outcome.inferred,
+                    outcome.flagged,
+                    iteration = count
+                );
+            }
+
+            if let Some(max) = self.config.iterations
+                && count >= max
+            {
+                return Ok(self.stopped(StopReason::IterationLimit { iterations: count }));
+            }
+
+            // Draining skips the inter-iteration sleep entirely.
+            if drain_start.is_none() {
+                self.pause_between_iterations().await;
+            }
+        }
+    }
+
+    /// Apply the `shed_above` policy: when `depth` reports more than its
+    /// limit, read and discard the oldest transactions of `buf1` until
+    /// `keep_latest` remain, quarantining them into `dead_letters` if given.
+    /// Returns how many were shed.
+    ///
+    /// Reads at most `n2_max` per call and re-checks `depth` before each one,
+    /// so it never blocks on a buffer another reader has just emptied.
+    async fn shed_backlog<B1: Buffer1Read, D: DeadLetter>(
+        &self,
+        buf1: &B1,
+        depth: &dyn BufferDepth,
+        dead_letters: Option<&D>,
+    ) -> Result<usize, ConsumerError> {
+        let Some(policy) = self.config.shed_above else {
+            return Ok(0);
+        };
+        let observed = depth.depth();
+        if observed <= policy.depth {
+            return Ok(0);
+        }
+        let mut shed = 0usize;
+        loop {
+            let excess = depth.depth().saturating_sub(policy.keep_latest);
+            let n = excess.min(observed - policy.keep_latest - shed).min(self.config.n2_max);
+            if n == 0 {
+                break;
+            }
+            let batch = buf1.read_batch(n).await.map_err(ConsumerError::Read)?;
+            if batch.is_empty() {
+                break;
+            }
+            shed += batch.len();
+            self.stats.borrow_mut().shed += batch.len() as u64;
+            if let Some(dead_letters) = dead_letters {
+                let reason = InvalidTransaction::Shed { depth: observed };
+                let rejected = batch
+                    .into_iter()
+                    .map(|transaction| RejectedTransaction { transaction, reason: reason.clone() })
+                    .collect();
+                dead_letters.write_dead_letters(rejected).await.map_err(ConsumerError::DeadLetter)?;
+            }
+        }
+        tracing::warn!(
+            target: TARGET,
+            shed,
+            depth = observed,
+            keep_latest = policy.keep_latest,
+            "consumer.backlog.shed"
+        );
+        Ok(shed)
+    }
+
+    /// Answer the transactions of `batch` the inference cache knows, infer
+    /// the others with [`infer_or_quarantine`](Self::infer_or_quarantine) and
+    /// cache their verdicts. The batch order is kept.
+    ///
+    /// Without a cache, this is `infer_or_quarantine`; with every
+    /// transaction cached, the Modelizer is not called.
+    async fn infer_cached<M: Modelizer>(
+        &self,
+        modelizer: &M,
+        batch: Vec<Transaction>,
+    ) -> Result<(Vec<InferredTransaction>, usize), ConsumerError> {
+        let Some(cache) = &self.cache else {
+            return self.infer_or_quarantine(modelizer, batch).await;
+        };
+        // A miss keeps its slot as the id the Modelizer result will carry.
+        let mut slots = Vec::with_capacity(batch.len());
+        let mut misses = vec![];
+        {
+            let mut cache = cache.borrow_mut();
+            for tx in batch {
+                match cache.get(tx) {
+                    Ok(hit) => slots.push(Ok(hit)),
+                    Err(tx) => {
+                        slots.push(Err(tx.id));
+                        misses.push(tx);
+                    }
+                }
+            }
+        }
+        let hits = slots.len() - misses.len();
+        if hits > 0 {
+            tracing::debug!(target: TARGET, hits, misses = misses.len(), "consumer.cache.hits");
+            self.stats.borrow_mut().cache_hits += hits as u64;
+        }
+        if misses.is_empty() {
+            return Ok((slots.into_iter().flatten().collect(), 0));
+        }
+        let (fresh, quarantined) = self.infer_or_quarantine(modelizer, misses).await?;
+        let mut cache = cache.borrow_mut();
+        for inferred in &fresh {
+            cache.insert(inferred);
+        }
+        // Quarantined misses have no result: their slots are left out.
+        let mut fresh = fresh.into_iter().peekable();
+        let inferred = slots
+            .into_iter()
+            .filter_map(|slot| match slot {
+                Ok(hit) => Some(hit),
+                Err(id) => fresh.next_if(|inferred| inferred.id() == id),
+            })
+            .collect();
+        Ok((inferred, quarantined))
+    }
+
+    /// Infer `batch`; with a quarantine configured, isolate the transactions
+    /// a failed batch cannot be scored on.
+    ///
+    /// A retryable failure of the whole batch is followed by one `infer` call
+    /// per transaction. Those that fail again are quarantined and left out of
+    /// the returned batch; their number is returned alongside it.
+    async fn infer_or_quarantine<M: Modelizer>(
+        &self,
+        modelizer: &M,
+        batch: Vec<Transaction>,
+    ) -> Result<(Vec<InferredTransaction>, usize), ConsumerError> {
+        let Some(quarantine) = &self.config.quarantine else {
+            return Ok((self.infer_checked(modelizer, batch).await?, 0));
+        };
+        let error = match self.infer_checked(modelizer, batch.clone()).await {
+            Ok(inferred) => return Ok((inferred, 0)),
+            Err(ConsumerError::Inference(e)) if e.is_retryable() => e,
+            Err(e) => return Err(e),
+        };
+        tracing::warn!(
+            target: TARGET,
+            error = %ErrorChain(&error),
+            batch_size = batch.len(),
+            "consumer.inference.isolating: batch failed, inferring one transaction at a time"
+        );
+        let mut inferred = Vec::with_capacity(batch.len());
+        let mut quarantined = 0;
+        for tx in batch {
+            match self.infer_checked(modelizer, vec![tx.clone()]).await {
+                Ok(mut one) => inferred.append(&mut one),
+                Err(ConsumerError::Inference(e)) => {
+                    quarantined += 1;
+                    self.quarantine_one(quarantine.as_ref(), &tx, &e).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((inferred, quarantined))
+    }
+
+    /// `infer`, holding the Modelizer to one result per transaction, in
+    /// order; ids are only compared with [`InferenceCheck::Ids`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConsumerError::Inference`] when inference fails, or
+    /// [`ConsumerError::InferenceMismatch`] /
+    /// [`ConsumerError::InferenceOutOfOrder`] when the result breaks the
+    /// contract.
+    async fn infer_checked<M: Modelizer>(
+        &self,
+        modelizer: &M,
+        batch: Vec<Transaction>,
+    ) -> Result<Vec<InferredTransaction>, ConsumerError> {
+        let expected = batch.len();
+        let ids: Vec<TransactionId> = if self.config.inference_check == InferenceCheck::Ids {
+            batch.iter().map(|tx| tx.id).collect()
+        } else {
+            vec![]
+        };
+        let inferred = modelizer.infer(batch).await.map_err(ConsumerError::Inference)?;
+        if inferred.len() != expected {
+            tracing::error!(
+                target: TARGET,
+                expected,
+                got = inferred.len(),
+                "consumer.inference.mismatch"
+            );
+            return Err(ConsumerError::InferenceMismatch { expected, got: inferred.len() });
+        }
+        let mismatch = ids.iter().zip(&inferred).position(|(id, result)| *id != result.id());
+        if let Some(position) = mismatch {
+            let (expected, got) = (ids[position], inferred[position].id());
+            tracing::error!(
+                target: TARGET,
+                position,
+                %expected,
+                %got,
+                "consumer.inference.out_of_order"
+            );
+            return Err(ConsumerError::InferenceOutOfOrder { position, expected, got });
+        }
+        Ok(inferred)
+    }
+
+    /// Record `tx` in `quarantine` with `error` as reason; a quarantine
+    /// failure is logged and counted, never returned.
+    async fn quarantine_one(
+        &self,
+        quarantine: &dyn Quarantine,
+        tx: &Transaction,
+        error: &ModelizerError,
+    ) {
+        let reason = error.to_string();
+        match quarantine.record(tx, &reason).await {
+            Ok(()) => {
+                tracing::warn!(
+                    target: TARGET,
+                    transaction_id = %tx.id,
+                    %reason,
+                    "consumer.inference.quarantined"
+                );
+                self.stats.borrow_mut().quarantined += 1;
+            }
+            Err(e) => {
+                tracing::error!(
+                    target: TARGET,
+                    transaction_id = %tx.id,
+                    %reason,
+                    error = %ErrorChain(&e),
+                    "consumer.quarantine.failed: transaction dropped"
+                );
+                self.stats.borrow_mut().quarantine_errors += 1;
+            }
+        }
+    }
+
+    /// Split off the transactions that fail EUR conversion (if a converter is
+    /// set) or validation, count them and quarantine them into `dead_letters`
+    /// if given. Returns the valid ones, unchanged.
+    async fn reject_invalid<D: DeadLetter>(
+        &self,
+        batch: Vec<Transaction>,
+        dead_letters: Option<&D>,
+    ) -> Result<Vec<Transaction>, ConsumerError> {
+        let mut valid = Vec::with_capacity(batch.len());
+        let mut rejected = vec![];
+        for mut transaction in batch {
+            let checked = self.eur_amount(&transaction).and_then(|eur| {
+                if !self.config.validate_input {
+                    return Ok(());
+                }
+                // The limit applies to the EUR amount; the transaction keeps its own.
+                let amount = std::mem::replace(&mut transaction.amount, eur);
+                let checked = transaction.validate_max(self.config.max_amount);
+                transaction.amount = amount;
+                checked
+            });
+            match checked {
+                Ok(()) => valid.push(transaction),
+                Err(reason) => {
+                    tracing::warn!(
+                        target: TARGET,
+                        transaction_id = %transaction.id,
+                        %reason,
+                        "consumer.input.rejected"
+                    );
+                    rejected.push(RejectedTransaction { transaction, reason });
+                }
+            }
+        }
+        if rejected.is_empty() {
+            return Ok(valid);
+        }
+        self.stats.borrow_mut().rejected += rejected.len() as u64;
+        if let Some(dead_letters) = dead_letters {
+            dead_letters.write_dead_letters(rejected).await.map_err(ConsumerError::DeadLetter)?;
+        }
+        Ok(valid)
+    }
+
+    /// Amount of `transaction` in EUR through the configured converter.
+    ///
+    /// Without a converter, or for EUR, this is the transaction's own amount.
+    fn eur_amount(&self, transaction: &Transaction) -> Result<f64, InvalidTransaction> {
+        let Some(converter) = &self.config.currency_converter else {
+            return Ok(transaction.amount);
+        };
+        if transaction.currency == Currency::Eur {
+            return Ok(transaction.amount);
+        }
+        converter.to_eur(transaction.amount, &transaction.currency).ok_or_else(|| {
+            InvalidTransaction::NoExchangeRate { currency: transaction.currency.clone() }
+        })
+    }
+
+    /// Accumulate totals and per-version counters from an inferred batch.
+    fn record_batch(&self, inferred: &[InferredTransaction]) {
+        let mut stats = self.stats.borrow_mut();
+        stats.batches += 1;
+        for tx in inferred {
+            let flagged = u64::from(tx.predicted_fraud);
+            stats.transactions += 1;
+            stats.flagged += flagged;
+            let vs = stats.per_version.entry(tx.model_version.clone()).or_default();
+            vs.transactions += 1;
+            vs.flagged += flagged;
+        }
+    }
+
+    /// Nack a throttled switch command; a failed one stops the run.
+    fn switch_command_result(
+        &self,
+        result: Result<(), ConsumerError>,
+    ) -> Result<(), ConsumerError> {
+        match result {
+            Err(e @ ConsumerError::SwitchThrottled { .. }) => {
+                tracing::warn!(target: TARGET, error = %e, "consumer.command.rejected");
+                Ok(())
+            }
+            Err(e) => {
+                self.emit_stopped(&RunEnd::Failed(ErrorChain(&e).to_string()));
+                Err(e)
+            }
+            Ok(()) => Ok(()),
+        }
+    }
+
+    /// Delegate a model version switch to the Modelizer port.
+    ///
+    /// The Modelizer owns the version; the Consumer only tracks the versions
+    /// it switched between, for [`rollback_model_version`](Self::rollback_model_version)
+    /// and [`ConsumerStats::switches`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConsumerError::SwitchThrottled`] within `switch_cooldown` of
+    /// the last switch (the Modelizer is not called), or
+    /// [`ConsumerError::Inference`] if the switch fails.
+    #[tracing::instrument(target = "pipeline::consumer", skip(self, modelizer), fields(?version))]
+    pub async fn switch_model_version<M: Modelizer>(
+        &self,
+        modelizer: &M,
+        version: ModelVersion,
+    ) -> Result<(), ConsumerError> {
+        let now = self.config.clock.now();
+        let last_switch = self.last_switch_at.get();
+        if let (Some(cooldown), Some(last)) = (self.config.switch_cooldown, last_switch) {
+            // A clock that went backwards counts as no time elapsed.
+            let since_last = now.duration_since(last).unwrap_or(Duration::ZERO);
+            if since_last < cooldown {
+                return Err(ConsumerError::SwitchThrottled { since_last, cooldown });
+            }
+        }
+        self.apply_switch(modelizer, version, now, false).await
+    }
+
+    /// Switch back to the version active before the last switch and return
+    /// it, or return `None` without calling the Modelizer if there was no
+    /// switch yet. Never throttled.
+    ///
+    /// A rollback is a switch itself: rolling back twice returns to the
+    /// version first rolled back from.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConsumerError::Inference`] if the switch fails.
+    #[tracing::instrument(target = "pipeline::consumer", skip_all)]
+    pub async fn rollback_model_version<M: Modelizer>(
+        &self,
+        modelizer: &M,
+    ) -> Result<Option<ModelVersion>, ConsumerError> {
+        let Some(previous) = self.previous_version.get() else {
+            tracing::warn!(target: TARGET, "consumer.rollback.nothing_to_roll_back");
+            return Ok(None);
+        };
+        self.apply_switch(modelizer, previous, self.config.clock.now(), true).await?;
+        Ok(Some(previous))
+    }
+
+    /// Switch `modelizer` to `version` and record the switch at `at`.
+    async fn apply_switch<M: Modelizer>(
+        &self,
+        modelizer: &M,
+        version: ModelVersion,
+        at: SystemTime,
+        rollback: bool,
+    ) -> Result<(), ConsumerError> {
+        modelizer.switch_version(version).await.map_err(ConsumerError::Inference)?;
+        if let Some(cache) = &self.cache {
+            // Cached verdicts belong to the version switched away from.
+            cache.borrow_mut().clear();
+        }
+        let from = self.active_version.replace(version);
+        self.previous_version.set(Some(from));
+        self.last_switch_at.set(Some(at));
+        tracing::info!(target: TARGET, ?from, to = ?version, rollback, "consumer.model.switched");
+
+        let keep = self.config.switch_history;
+        if keep > 0 {
+            let switches = &mut self.stats.borrow_mut().switches;
+            if switches.len() >= keep {
+                switches.drain(..=switches.len() - keep);
+            }
+            switches.push(SwitchRecord { from, to: version, at, rollback });
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AlarmOrdering, BatchObserver, ConsumeOutcome, Consumer, ConsumerCommand, ConsumerConfig,
+        ConsumerError, CountingObserver, DeliveryAttempts, InferenceCheck, LoggingObserver, RunEnd,
+        SwitchRecord, VersionStats, requeue,
+    };
+    use domain::{
+        AdaptiveInterval, Alarm, AlarmError, BatchSizeMode, Buffer1, Buffer1Read, Buffer2,
+        BufferDepth, Clock, Currency, CurrencyConverter, DeadLetter, ErrorChain,
+        InferredTransaction, InvalidTransaction, Modelizer, ModelizerError, ModelVersion,
+        PipelineEvent, Preset, Quarantine, QuarantineFuture, QuarantinedTransaction, ReadError,
+        RejectedTransaction, Stage, StopReason, StorageError, Transaction, TransactionId,
+        WriteError,
+    };
+    use rand::{SeedableRng as _, rngs::StdRng};
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    // ------------------------------------------------------------------
+    // Test helpers
+    // ------------------------------------------------------------------
+
+    fn make_tx() -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.00_f64,
+            last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    fn make_txs(n: usize) -> Vec<Transaction> {
+        (0..n).map(|_| make_tx()).collect()
+    }
+
+    fn make_consumer(n2_max: usize, seed: u64) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(n2_max)
+                .seed(seed)
+                .poll_interval2(Duration::ZERO)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    // ------------------------------------------------------------------
+    // Mock adapters (T017)
+    // ------------------------------------------------------------------
+
+    struct MockBuffer1Read {
+        transactions: RefCell<VecDeque<Transaction>>,
+    }
+
+    impl MockBuffer1Read {
+        fn new(transactions: Vec<Transaction>) -> Self {
+            Self { transactions: RefCell::new(VecDeque::from(transactions)) }
+        }
+    }
+
+    /// Appends, so a requeue can refill the buffer a test reads from.
+    impl Buffer1 for MockBuffer1Read {
+        async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+            self.transactions.borrow_mut().extend(batch);
+            Ok(())
+        }
+    }
+
+    impl Buffer1Read for MockBuffer1Read {
+        async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+            let mut queue = self.transactions.borrow_mut();
+            if queue.is_empty() {
+                return Err(ReadError::Closed);
+            }
+            let count = max.min(queue.len());
+            Ok(queue.drain(..count).collect())
+        }
+    }
+
+    impl BufferDepth for MockBuffer1Read {
+        fn depth(&self) -> usize {
+            self.transactions.borrow().len()
+        }
+    }
+
+    /// Depth-aware Buffer1 that is never closed; reading it empty is a test bug.
+    struct OpenBuffer1 {
+        transactions: RefCell<VecDeque<Transaction>>,
+    }
+
+    impl OpenBuffer1 {
+        fn new(transactions: Vec<Transaction>) -> Self {
+            Self { transactions: RefCell::new(VecDeque::from(transactions)) }
+        }
+    }
+
+    impl Buffer1Read for OpenBuffer1 {
+        async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+            let mut queue = self.transactions.borrow_mut();
+            assert!(!queue.is_empty(), "read on an empty open buffer would block forever");
+            let count = max.min(queue.len());
+            Ok(queue.drain(..count).collect())
+        }
+    }
+
+    impl BufferDepth for OpenBuffer1 {
+        fn depth(&self) -> usize {
+            self.transactions.borrow().len()
+        }
+    }
+
+    struct MockModelizer {
+        predicted_fraud: bool,
+        infer_call_count: Cell<u32>,
+        last_batch_size: Cell<usize>,
+        last_switch: Cell<Option<ModelVersion>>,
+        fail_infer: bool,
+        fail_switch: bool,
+    }
+
+    impl MockModelizer {
+        fn new(predicted_fraud: bool) -> Self {
+            Self {
+                predicted_fraud,
+                infer_call_count: Cell::new(0),
+                last_batch_size: Cell::new(0),
+                last_switch: Cell::new(None),
+                fail_infer: false,
+                fail_switch: false,
+            }
+        }
+
+        fn failing_infer() -> Self {
+            Self { fail_infer: true, ..Self::new(false) }
+        }
+
+        fn failing_switch() -> Self {
+            Self { fail_switch: true, ..Self::new(false) }
+        }
+    }
+
+    impl Modelizer for MockModelizer {
+        async fn infer(
+            &self,
+            batch: Vec<Transaction>,
+        ) -> Result<Vec<InferredTransaction>, ModelizerError> {
+            if self.fail_infer {
+                return Err(ModelizerError::InferenceFailed {
+                    reason: "mock failure".to_owned(),
+                });
+            }
+            self.infer_call_count.set(self.infer_call_count.get() + 1);
+            self.last_batch_size.set(batch.len());
+            // Version follows the last switch so per-version stats can be exercised.
+            let model_version = match self.last_switch.get() {
+                Some(ModelVersion::NMinus1) => "v_prev",
+                _ => "v_test",
+            };
+            Ok(batch
+                .into_iter()
+                .map(|tx| InferredTransaction {
+                    predicted_fraud: self.predicted_fraud,
+                    model_name: "MOCK".to_owned(),
+                    model_version: model_version.to_owned(),
+                    delivery_attempts: 1,
+                    transaction: tx,
+                    cached: false,
+                })
+                .collect())
+        }
+
+        async fn switch_version(
+            &self,
+            version: ModelVersion,
+        ) -> Result<(), ModelizerError> {
+            if self.fail_switch {
+                return Err(ModelizerError::SwitchFailed {
+                    reason: "mock failure".to_owned(),
+                });
+            }
+            self.last_switch.set(Some(version));
+            Ok(())
+        }
+    }
+
+    struct MockAlarm {
+        call_count: Cell<u32>,
+        always_fail: bool,
+        triggered: RefCell<Vec<TransactionId>>,
+    }
+
+    impl MockAlarm {
+        fn new() -> Self {
+            Self { call_count: Cell::new(0), always_fail: false, triggered: RefCell::default() }
+        }
+
+        fn always_failing() -> Self {
+            Self { always_fail: true, ..Self::new() }
+        }
+    }
+
+    impl Alarm for MockAlarm {
+        async fn trigger(
+            &self,
+            transaction: &InferredTransaction,
+        ) -> Result<(), AlarmError> {
+            self.call_count.set(self.call_count.get() + 1);
+            self.triggered.borrow_mut().push(transaction.id());
+            if self.always_fail {
+                return Err(AlarmError::DeliveryFailed {
+                    reason: format!("mock fail for tx {}", transaction.id()),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    struct MockBuffer2 {
+        captured: RefCell<Vec<InferredTransaction>>,
+        fail: Option<WriteError>,
+        write_calls: Cell<u32>,
+    }
+
+    impl MockBuffer2 {
+        fn new() -> Self {
+            Self { captured: RefCell::new(vec![]), fail: None, write_calls: Cell::new(0) }
+        }
+
+        fn with_fail(error: WriteError) -> Self {
+            Self { fail: Some(error), ..Self::new() }
+        }
+    }
+
+    impl Buffer2 for MockBuffer2 {
+        async fn write_batch(
+            &self,
+            batch: Vec<InferredTransaction>,
+        ) -> Result<(), WriteError> {
+            self.write_calls.set(self.write_calls.get() + 1);
+            if let Some(e) = &self.fail {
+                return Err(e.clone());
+            }
+            self.captured.borrow_mut().extend(batch);
+            Ok(())
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // T015: ConsumerConfig validation
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn config_rejects_zero_n2_max() {
+        let result = ConsumerConfig::builder(0).build();
+        assert!(matches!(result, Err(ConsumerError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn config_errors_name_the_field() {
+        let field = |r: Result<ConsumerConfig, ConsumerError>| match r {
+            Err(ConsumerError::InvalidConfig(e)) => e,
+            other => panic!("expected InvalidConfig, got {other:?}"),
+        };
+        let e = field(ConsumerConfig::builder(0).build());
+        assert_eq!((e.field, e.value.as_str(), e.constraint), ("n2_max", "0", "must be >= 1"));
+        assert_eq!(field(ConsumerConfig::builder(10).iterations(0).build()).field, "iterations");
+        assert_eq!(
+            field(ConsumerConfig::builder(10).drain_idle_polls(0).build()).field,
+            "drain_idle_polls"
+        );
+        assert_eq!(
+            field(ConsumerConfig::builder(10).write_chunk_size(0).build()).field,
+            "write_chunk_size"
+        );
+        assert_eq!(field(ConsumerConfig::builder(10).max_amount(0.0).build()).field, "max_amount");
+        assert_eq!(
+            field(ConsumerConfig::builder(10).max_amount(f64::NAN).build()).field,
+            "max_amount"
+        );
+    }
+
+    #[test]
+    fn builder_defaults_poll_interval2() {
+        let config = ConsumerConfig::builder(10).build().unwrap();
+        assert_eq!(config.poll_interval2, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn builder_with_seed() {
+        let config = ConsumerConfig::builder(10).seed(42).build().unwrap();
+        assert_eq!(config.seed, Some(42));
+    }
+
+    #[test]
+    fn builder_with_iterations() {
+        let config = ConsumerConfig::builder(10).iterations(5).build().unwrap();
+        assert_eq!(config.iterations, Some(5));
+    }
+
+    // ------------------------------------------------------------------
+    // Presets
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn presets_fill_size_and_interval() {
+        let values = |builder: super::ConsumerConfigBuilder| {
+            let c = builder.build().unwrap();
+            let preset = c.summary().get("preset").map(str::to_owned);
+            (c.n2_max, c.fixed_batch_size, c.poll_interval2, preset)
+        };
+        let ms = Duration::from_millis;
+        assert_eq!(values(ConsumerConfig::demo()), (50, false, ms(25), Some("demo".to_owned())));
+        assert_eq!(
+            values(ConsumerConfig::stress()),
+            (1_000, true, ms(1), Some("stress".to_owned()))
+        );
+        assert_eq!(
+            values(ConsumerConfig::low_latency()),
+            (10, false, ms(1), Some("low-latency".to_owned()))
+        );
+        for preset in Preset::ALL {
+            assert_eq!(ConsumerConfig::from_preset(preset).build().unwrap().preset, Some(preset));
+        }
+        assert_eq!(ConsumerConfig::builder(10).build().unwrap().preset, None);
+    }
+
+    #[test]
+    fn builder_overrides_win_over_the_preset() {
+        let config = ConsumerConfig::stress()
+            .n2_max(5)
+            .fixed_batch_size(false)
+            .poll_interval2(Duration::ZERO)
+            .inference_cache(64)
+            .build()
+            .unwrap();
+        assert_eq!((config.n2_max, config.fixed_batch_size), (5, false));
+        assert_eq!((config.poll_interval2, config.inference_cache), (Duration::ZERO, Some(64)));
+        assert_eq!(config.preset, Some(Preset::Stress));
+    }
+
+    // ------------------------------------------------------------------
+    // T018: US1 -- read behavior
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn batch_size_within_n2_max_range() {
+        let n2_max = 10;
+        let consumer = make_consumer(n2_max, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(1000));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        let sz = modelizer.last_batch_size.get();
+        assert!(sz >= 1 && sz <= n2_max, "batch size {sz} out of [1, {n2_max}]");
+    }
+
+    #[tokio::test]
+    async fn batch_size_capped_by_available_data() {
+        // n2_max >> available transactions; buffer caps the returned batch.
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(3));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(modelizer.last_batch_size.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn seeded_batch_size_is_deterministic() {
+        let c1 = make_consumer(10, 99);
+        let c2 = make_consumer(10, 99);
+        let buf1_a = MockBuffer1Read::new(make_txs(100));
+        let buf1_b = MockBuffer1Read::new(make_txs(100));
+        let m1 = MockModelizer::new(false);
+        let m2 = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        c1.consume_once(&buf1_a, &m1, &alarm, &buf2).await.unwrap();
+        c2.consume_once(&buf1_b, &m2, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(
+            m1.last_batch_size.get(),
+            m2.last_batch_size.get(),
+            "identical seeds must request identical batch sizes"
+        );
+    }
+
+    #[tokio::test]
+    async fn effective_seed_replays_an_unseeded_consumer() {
+        let config = || ConsumerConfig::builder(10).poll_interval2(Duration::ZERO);
+        let unseeded = Consumer::new(config().build().unwrap());
+        let seed = unseeded.effective_seed().unwrap();
+        let replay = Consumer::new(config().seed(seed).build().unwrap());
+        assert_eq!(replay.effective_seed(), Some(seed));
+
+        let mut sizes = (vec![], vec![]);
+        for _ in 0..10 {
+            for (consumer, sizes) in [(&unseeded, &mut sizes.0), (&replay, &mut sizes.1)] {
+                let modelizer = MockModelizer::new(false);
+                let (buf1, buf2) = (MockBuffer1Read::new(make_txs(10)), MockBuffer2::new());
+                consumer.consume_once(&buf1, &modelizer, &MockAlarm::new(), &buf2).await.unwrap();
+                sizes.push(modelizer.last_batch_size.get());
+            }
+        }
+
+        assert_eq!(sizes.0, sizes.1);
+        let injected = Consumer::with_rng(config().build().unwrap(), StdRng::seed_from_u64(1));
+        assert_eq!(injected.effective_seed(), None);
+    }
+
+    #[tokio::test]
+    async fn fixed_batch_size_consumes_exactly_iterations_times_max() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(7)
+                .iterations(10)
+                .poll_interval2(Duration::ZERO)
+                .fixed_batch_size(true)
+                .build()
+                .unwrap(),
+        );
+        let buf1 = MockBuffer1Read::new(make_txs(1000));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        let reason = consumer.run(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(reason, StopReason::IterationLimit { iterations: 10 });
+        assert_eq!(buf2.captured.borrow().len(), 100);
+        assert_eq!(consumer.stats().transactions, 100);
+        let debug = format!("{:?}", consumer.config);
+        assert!(debug.contains("fixed_batch_size: true"), "{debug}");
+    }
+
+    #[tokio::test]
+    async fn seeded_batch_sizes_follow_the_batch_size_mode() {
+        for mode in [BatchSizeMode::Uniform, BatchSizeMode::Geometric { p: 0.3 }] {
+            let consumer = Consumer::new(
+                ConsumerConfig::builder(10)
+                    .seed(5)
+                    .poll_interval2(Duration::ZERO)
+                    .batch_size_mode(mode)
+                    .build()
+                    .unwrap(),
+            );
+            let mut reference = StdRng::seed_from_u64(5);
+            let buf1 = MockBuffer1Read::new(make_txs(1000));
+            let modelizer = MockModelizer::new(false);
+            let alarm = MockAlarm::new();
+            let buf2 = MockBuffer2::new();
+
+            for _ in 0..20 {
+                consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+                let expected = mode.sample(10, &mut reference);
+                assert_eq!(modelizer.last_batch_size.get(), expected, "{mode:?}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn always_max_reads_n2_max_every_time() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(5)
+                .poll_interval2(Duration::ZERO)
+                .batch_size_mode(BatchSizeMode::AlwaysMax)
+                .build()
+                .unwrap(),
+        );
+        let buf1 = MockBuffer1Read::new(make_txs(1000));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        for _ in 0..20 {
+            consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+            assert_eq!(modelizer.last_batch_size.get(), 10);
+        }
+    }
+
+    #[test]
+    fn builder_rejects_a_geometric_p_outside_zero_one() {
+        for p in [0.0, 1.5, f64::NAN] {
+            let mode = BatchSizeMode::Geometric { p };
+            let result = ConsumerConfig::builder(10).batch_size_mode(mode).build();
+            let Err(ConsumerError::InvalidConfig(e)) = result else {
+                panic!("p = {p} must be rejected");
+            };
+            assert_eq!(e.field, "p");
+        }
+    }
+
+    #[tokio::test]
+    async fn fixed_batch_size_false_matches_default() {
+        let plain = make_consumer(10, 99);
+        let unfixed = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(99)
+                .poll_interval2(Duration::ZERO)
+                .fixed_batch_size(false)
+                .build()
+                .unwrap(),
+        );
+        let buf1_a = MockBuffer1Read::new(make_txs(1000));
+        let buf1_b = MockBuffer1Read::new(make_txs(1000));
+        let (m1, m2) = (MockModelizer::new(false), MockModelizer::new(false));
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        for _ in 0..20 {
+            plain.consume_once(&buf1_a, &m1, &alarm, &buf2).await.unwrap();
+            unfixed.consume_once(&buf1_b, &m2, &alarm, &buf2).await.unwrap();
+            assert_eq!(m1.last_batch_size.get(), m2.last_batch_size.get());
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // T019: US1 -- run loop
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn run_processes_n_iterations() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(7)
+                .iterations(3)
+                .poll_interval2(Duration::ZERO)
+                .build()
+                .unwrap(),
+        );
+        let buf1 = MockBuffer1Read::new(make_txs(1000));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        let reason = consumer.run(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(reason, StopReason::IterationLimit { iterations: 3 });
+        assert_eq!(modelizer.infer_call_count.get(), 3, "expected 3 infer calls");
+    }
+
+    #[tokio::test]
+    async fn run_stops_gracefully_on_closed() {
+        let consumer = make_consumer(10, 1);
+        // Empty buffer signals Closed immediately.
+        let buf1 = MockBuffer1Read::new(vec![]);
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        let result = consumer.run(&buf1, &modelizer, &alarm, &buf2).await;
+        assert!(
+            matches!(result, Ok(StopReason::BufferClosed { iterations: 0 })),
+            "Closed must terminate cleanly: {result:?}"
+        );
+    }
+
+    // ------------------------------------------------------------------
+    // T022: US2 -- Modelizer interaction
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn consume_once_sends_full_batch_to_modelizer() {
+        // 10 transactions preloaded, n2_max = 100 -> all 10 reach Modelizer.
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(modelizer.last_batch_size.get(), 10);
+    }
+
+    #[tokio::test]
+    async fn inference_error_propagates_as_consumer_error_inference() {
+        let consumer = make_consumer(10, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        let modelizer = MockModelizer::failing_infer();
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+        assert!(
+            matches!(result, Err(ConsumerError::Inference(_))),
+            "inference failure must map to ConsumerError::Inference: {result:?}"
+        );
+    }
+
+    // ------------------------------------------------------------------
+    // Inference contract: one result per input, in order
+    // ------------------------------------------------------------------
+
+    /// How [`TamperingModelizer`] breaks the contract.
+    #[derive(Clone, Copy)]
+    enum Tamper {
+        DropLast,
+        DuplicateLast,
+        SwapFirstTwo,
+    }
+
+    /// Modelizer flagging every transaction, then tampering with the result.
+    struct TamperingModelizer {
+        inner: MockModelizer,
+        tamper: Tamper,
+    }
+
+    impl TamperingModelizer {
+        fn new(tamper: Tamper) -> Self {
+            Self { inner: MockModelizer::new(true), tamper }
+        }
+    }
+
+    impl Modelizer for TamperingModelizer {
+        async fn infer(
+            &self,
+            batch: Vec<Transaction>,
+        ) -> Result<Vec<InferredTransaction>, ModelizerError> {
+            let mut inferred = self.inner.infer(batch).await?;
+            match self.tamper {
+                Tamper::DropLast => {
+                    inferred.pop();
+                }
+                Tamper::DuplicateLast => inferred.push(inferred[inferred.len() - 1].clone()),
+                Tamper::SwapFirstTwo => inferred.swap(0, 1),
+            }
+            Ok(inferred)
+        }
+
+        async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+            self.inner.switch_version(version).await
+        }
+    }
+
+    fn verifying_consumer(check: InferenceCheck) -> Consumer {
+        let config = ConsumerConfig::builder(100)
+            .seed(1)
+            .fixed_batch_size(true)
+            .inference_check(check)
+            .build()
+            .unwrap();
+        Consumer::new(config)
+    }
+
+    #[tokio::test]
+    async fn dropped_result_fails_before_any_alarm_or_write() {
+        let consumer = verifying_consumer(InferenceCheck::Length);
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        let modelizer = TamperingModelizer::new(Tamper::DropLast);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+
+        assert!(
+            matches!(result, Err(ConsumerError::InferenceMismatch { expected: 10, got: 9 })),
+            "{result:?}"
+        );
+        assert_eq!(alarm.call_count.get(), 0);
+        assert_eq!(buf2.write_calls.get(), 0);
+        assert_eq!(consumer.stats().transactions, 0);
+    }
+
+    #[tokio::test]
+    async fn extra_result_is_a_mismatch_too() {
+        let consumer = verifying_consumer(InferenceCheck::Length);
+        let buf1 = MockBuffer1Read::new(make_txs(4));
+        let modelizer = TamperingModelizer::new(Tamper::DuplicateLast);
+        let (alarm, buf2) = (MockAlarm::new(), MockBuffer2::new());
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+
+        assert!(
+            matches!(result, Err(ConsumerError::InferenceMismatch { expected: 4, got: 5 })),
+            "{result:?}"
+        );
+        assert_eq!(buf2.write_calls.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn reordered_results_fail_only_when_ids_are_verified() {
+        let txs = make_txs(3);
+        let (first, second) = (txs[0].id, txs[1].id);
+        let modelizer = TamperingModelizer::new(Tamper::SwapFirstTwo);
+        let (alarm, buf2) = (MockAlarm::new(), MockBuffer2::new());
+
+        let lenient = verifying_consumer(InferenceCheck::Length);
+        let buf1 = MockBuffer1Read::new(txs.clone());
+        lenient.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        assert_eq!(buf2.captured.borrow().len(), 3);
+
+        let strict = verifying_consumer(InferenceCheck::Ids);
+        let buf1 = MockBuffer1Read::new(txs);
+        let result = strict.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+        assert!(
+            matches!(
+                result,
+                Err(ConsumerError::InferenceOutOfOrder { position: 0, expected, got })
+                    if expected == first && got == second
+            ),
+            "{result:?}"
+        );
+        assert_eq!(buf2.write_calls.get(), 1, "only the lenient batch was written");
+    }
+
+    #[tokio::test]
+    async fn contract_checks_leave_the_happy_path_alone() {
+        let consumer = verifying_consumer(InferenceCheck::Ids);
+        let txs = make_txs(10);
+        let ids: Vec<TransactionId> = txs.iter().map(|tx| tx.id).collect();
+        let buf1 = MockBuffer1Read::new(txs);
+        let modelizer = MockModelizer::new(true);
+        let (alarm, buf2) = (MockAlarm::new(), MockBuffer2::new());
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        let written: Vec<TransactionId> =
+            buf2.captured.borrow().iter().map(InferredTransaction::id).collect();
+        assert_eq!(written, ids);
+        assert_eq!(alarm.call_count.get(), 10);
+    }
+
+    // ------------------------------------------------------------------
+    // T023: US2 -- InferredTransaction enrichment fields
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn inferred_tx_enrichment_fields_survive_pipeline() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(2));
+        let modelizer = MockModelizer::new(true);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        let captured = buf2.captured.borrow();
+        assert_eq!(captured.len(), 2);
+        for tx in captured.iter() {
+            assert!(tx.predicted_fraud);
+            assert_eq!(tx.model_name, "MOCK");
+            assert_eq!(tx.model_version, "v_test");
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // T025: US4 -- Buffer2 write
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn all_inferred_tx_written_to_buf2_regardless_of_fraud() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(true); // all fraudulent
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(buf2.captured.borrow().len(), 5, "all 5 must reach Buffer2");
+    }
+
+    #[tokio::test]
+    async fn buf2_full_propagates_as_consumer_error_write() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::with_fail(WriteError::Full { capacity: 0, rejected: 0 });
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+        assert!(
+            matches!(result, Err(ConsumerError::Write(WriteError::Full { .. }))),
+            "Full must map to ConsumerError::Write: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn buf2_closed_propagates_as_consumer_error_write() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::with_fail(WriteError::Closed);
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+        assert!(
+            matches!(result, Err(ConsumerError::Write(WriteError::Closed))),
+            "Closed must map to ConsumerError::Write: {result:?}"
+        );
+    }
+
+    // ------------------------------------------------------------------
+    // Partial Buffer2 writes
+    // ------------------------------------------------------------------
+
+    /// Buffer2 accepting, per write, the next scripted number of items; once
+    /// the script runs out, nothing fits.
+    #[derive(Default)]
+    struct RoomBuffer2 {
+        room: RefCell<std::collections::VecDeque<usize>>,
+        /// Size of the batch handed to each write, in call order.
+        attempts: RefCell<Vec<usize>>,
+        written: RefCell<Vec<InferredTransaction>>,
+    }
+
+    impl RoomBuffer2 {
+        fn with_room(room: &[usize]) -> Self {
+            Self { room: RefCell::new(room.iter().copied().collect()), ..Self::default() }
+        }
+    }
+
+    impl Buffer2 for RoomBuffer2 {
+        async fn write_batch(&self, _batch: Vec<InferredTransaction>) -> Result<(), WriteError> {
+            unreachable!("the Consumer writes through write_batch_partial")
+        }
+
+        async fn write_batch_partial(
+            &self,
+            batch: &mut Vec<InferredTransaction>,
+        ) -> Result<usize, WriteError> {
+            self.attempts.borrow_mut().push(batch.len());
+            let room = self.room.borrow_mut().pop_front().unwrap_or(0);
+            if room == 0 {
+                return Err(WriteError::Full { capacity: 0, rejected: batch.len() });
+            }
+            let accepted = room.min(batch.len());
+            self.written.borrow_mut().extend(batch.drain(..accepted));
+            Ok(accepted)
+        }
+    }
+
+    fn partial_consumer(write_retries: u32) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(5)
+                .fixed_batch_size(true)
+                .poll_interval2(Duration::ZERO)
+                .write_retries(write_retries)
+                .seed(1)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn partial_buffer2_write_retries_only_the_remainder() {
+        let txs = make_txs(5);
+        let buf1 = MockBuffer1Read::new(txs.clone());
+        let buf2 = RoomBuffer2::with_room(&[4, 1]);
+
+        partial_consumer(3)
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
+
+        assert_eq!(*buf2.attempts.borrow(), [5, 1]);
+        let written: Vec<_> = buf2.written.borrow().iter().map(InferredTransaction::id).collect();
+        assert_eq!(written, txs.iter().map(|tx| tx.id).collect::<Vec<_>>());
+    }
+
+    fn chunked_consumer(write_retries: u32) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(10)
+                .fixed_batch_size(true)
+                .poll_interval2(Duration::ZERO)
+                .write_retries(write_retries)
+                .write_chunk_size(3)
+                .seed(1)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn chunked_write_splits_the_batch_in_order() {
+        let txs = make_txs(10);
+        let buf1 = MockBuffer1Read::new(txs.clone());
+        let buf2 = RoomBuffer2::with_room(&[10, 10, 10, 10]);
+
+        chunked_consumer(3)
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
+
+        assert_eq!(*buf2.attempts.borrow(), [3, 3, 3, 1]);
+        let written: Vec<_> = buf2.written.borrow().iter().map(InferredTransaction::id).collect();
+        assert_eq!(written, txs.iter().map(|tx| tx.id).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn chunked_write_retries_only_the_unwritten_chunks() {
+        let txs = make_txs(10);
+        let buf1 = MockBuffer1Read::new(txs.clone());
+        // The third chunk finds Buffer2 full once, then everything fits.
+        let buf2 = RoomBuffer2::with_room(&[10, 10, 0, 10, 10]);
+
+        chunked_consumer(1)
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
+
+        assert_eq!(*buf2.attempts.borrow(), [3, 3, 3, 3, 1]);
+        let written: Vec<_> = buf2.written.borrow().iter().map(InferredTransaction::id).collect();
+        assert_eq!(written, txs.iter().map(|tx| tx.id).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn chunked_write_failing_mid_batch_keeps_the_written_chunks() {
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        let buf2 = RoomBuffer2::with_room(&[10, 10]);
+
+        let result = chunked_consumer(0)
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await;
+
+        assert!(
+            matches!(result, Err(ConsumerError::Write(WriteError::Full { rejected: 3, .. }))),
+            "{result:?}"
+        );
+        assert_eq!(*buf2.attempts.borrow(), [3, 3, 3]);
+        assert_eq!(buf2.written.borrow().len(), 6);
+    }
+
+    /// Buffer2 relying on the provided `write_batch_partial`: the `n`-th
+    /// write (1-based) fails with `Full`, every other one is accepted.
+    struct FullOnNthWrite {
+        n: u32,
+        calls: Cell<u32>,
+        written: RefCell<Vec<InferredTransaction>>,
+    }
+
+    impl Buffer2 for FullOnNthWrite {
+        async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), WriteError> {
+            self.calls.set(self.calls.get() + 1);
+            if self.calls.get() == self.n {
+                return Err(WriteError::Full { capacity: 2, rejected: batch.len() });
+            }
+            self.written.borrow_mut().extend(batch);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn chunk_full_through_the_provided_partial_write_fails_the_batch() {
+        let buf1 = MockBuffer1Read::new(make_txs(4));
+        let buf2 = FullOnNthWrite { n: 2, calls: Cell::new(0), written: RefCell::new(vec![]) };
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(4)
+                .fixed_batch_size(true)
+                .poll_interval2(Duration::ZERO)
+                .write_chunk_size(2)
+                .seed(1)
+                .build()
+                .unwrap(),
+        );
+
+        let result = consumer
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await;
+
+        // The second chunk went to write_batch and was dropped: nothing is
+        // left to retry, so the batch fails instead of reporting success.
+        assert!(
+            matches!(result, Err(ConsumerError::Write(WriteError::Full { rejected: 2, .. }))),
+            "{result:?}"
+        );
+        assert_eq!(buf2.calls.get(), 2);
+        assert_eq!(buf2.written.borrow().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn buffer2_remainder_full_past_write_retries_fails_the_batch() {
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let buf2 = RoomBuffer2::with_room(&[4]);
+
+        let result = partial_consumer(1)
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await;
+
+        assert!(
+            matches!(result, Err(ConsumerError::Write(WriteError::Full { rejected: 1, .. }))),
+            "{result:?}"
+        );
+        // The first Full on the remainder uses the only retry; the second fails.
+        assert_eq!(*buf2.attempts.borrow(), [5, 1, 1]);
+        assert_eq!(buf2.written.borrow().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn chunk_written_after_two_full_attempts_carries_three_attempts() {
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        // The second chunk finds Buffer2 full twice, then everything fits.
+        let buf2 = RoomBuffer2::with_room(&[10, 0, 0, 10, 10, 10]);
+        let consumer = chunked_consumer(3);
+
+        consumer
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
+
+        let attempts: Vec<_> =
+            buf2.written.borrow().iter().map(|it| it.delivery_attempts).collect();
+        assert_eq!(attempts, [1, 1, 1, 3, 3, 3, 1, 1, 1, 1]);
+        let stats = consumer.stats();
+        assert_eq!(stats.delivery_attempts, DeliveryAttempts { written: 10, total: 16, max: 3 });
+        assert_eq!(stats.delivery_attempts.mean(), Some(1.6));
+        assert!(stats.to_string().contains(", write attempts max 3 mean 1.60"), "{stats}");
+    }
+
+    #[tokio::test]
+    async fn first_time_writes_carry_one_attempt() {
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        let buf2 = RoomBuffer2::with_room(&[10, 10, 10, 10]);
+        let consumer = chunked_consumer(3);
+
+        consumer
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
+
+        assert!(buf2.written.borrow().iter().all(|it| it.delivery_attempts == 1));
+        let stats = consumer.stats();
+        assert_eq!(stats.delivery_attempts, DeliveryAttempts { written: 10, total: 10, max: 1 });
+        assert!(!stats.to_string().contains("write attempts"), "{stats}");
+    }
+
+    #[tokio::test]
+    async fn buffer2_with_no_room_fails_without_retry() {
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let buf2 = RoomBuffer2::with_room(&[]);
+
+        let result = partial_consumer(3)
+            .consume_once(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await;
+
+        assert!(
+            matches!(result, Err(ConsumerError::Write(WriteError::Full { rejected: 5, .. }))),
+            "{result:?}"
+        );
+        assert_eq!(*buf2.attempts.borrow(), [5]);
+    }
+
+    // ------------------------------------------------------------------
+    // AlarmOrdering
+    // ------------------------------------------------------------------
+
+    fn ordered_consumer(ordering: AlarmOrdering) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(100)
+                .seed(1)
+                .fixed_batch_size(true)
+                .alarm_ordering(ordering)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn write_first_fires_no_alarm_when_buffer2_fails() {
+        let consumer = ordered_consumer(AlarmOrdering::WriteFirst);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(true);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::with_fail(WriteError::Full { capacity: 0, rejected: 0 });
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+
+        assert!(matches!(result, Err(ConsumerError::Write(WriteError::Full { .. }))));
+        assert_eq!(buf2.write_calls.get(), 1);
+        assert_eq!(alarm.call_count.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn alarms_first_still_fires_when_buffer2_fails() {
+        let consumer = ordered_consumer(AlarmOrdering::AlarmsFirst);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(true);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::with_fail(WriteError::Full { capacity: 0, rejected: 0 });
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+
+        assert!(matches!(result, Err(ConsumerError::Write(WriteError::Full { .. }))));
+        assert_eq!(alarm.call_count.get(), 5);
+        let default = ConsumerConfig::builder(1).build().unwrap().alarm_ordering;
+        assert_eq!(default, AlarmOrdering::AlarmsFirst);
+    }
+
+    #[tokio::test]
+    async fn both_orderings_deliver_the_same_alarms() {
+        let txs = make_txs(40);
+        let mut delivered = vec![];
+        for ordering in [AlarmOrdering::AlarmsFirst, AlarmOrdering::WriteFirst] {
+            let consumer = Consumer::new(
+                ConsumerConfig::builder(100)
+                    .fixed_batch_size(true)
+                    .max_alarms_per_batch(30)
+                    .alarm_ordering(ordering)
+                    .build()
+                    .unwrap(),
+            );
+            let buf1 = MockBuffer1Read::new(txs.clone());
+            let modelizer = MockModelizer::new(true);
+            let alarm = MockAlarm::new();
+            let buf2 = MockBuffer2::new();
+
+            let errors = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+            assert!(errors.is_empty());
+            assert_eq!(buf2.captured.borrow().len(), 40);
+            assert_eq!(consumer.stats().alarms_suppressed, 10);
+            delivered.push(alarm.triggered.take());
+        }
+        assert_eq!(delivered[0].len(), 30);
+        assert_eq!(delivered[0], delivered[1]);
+    }
+
+    // ------------------------------------------------------------------
+    // T027: US3 -- alarm count
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn exactly_n_alarms_for_n_fraudulent_tx() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(true); // all 5 fraudulent
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(alarm.call_count.get(), 5, "5 alarms for 5 fraudulent tx");
+    }
+
+    #[tokio::test]
+    async fn no_alarms_for_zero_fraudulent_tx() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false); // none fraudulent
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(alarm.call_count.get(), 0, "0 alarms when none fraudulent");
+    }
+
+    #[tokio::test]
+    async fn zero_alarms_when_all_legitimate() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(3));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(alarm.call_count.get(), 0);
+    }
+
+    // ------------------------------------------------------------------
+    // T028: US3 -- best-effort alarm delivery
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn all_alarms_attempted_on_partial_failure() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(4));
+        let modelizer = MockModelizer::new(true); // all 4 fraudulent
+        let alarm = MockAlarm::always_failing();
+        let buf2 = MockBuffer2::new();
+
+        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
+        assert!(result.is_ok(), "alarm failures must not abort consume_once: {result:?}");
+
+        assert_eq!(alarm.call_count.get(), 4, "all 4 alarms must be attempted");
+    }
+
+    #[tokio::test]
+    async fn alarm_failures_returned_in_ok_vec() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(3));
+        let modelizer = MockModelizer::new(true); // all 3 fraudulent
+        let alarm = MockAlarm::always_failing();
+        let buf2 = MockBuffer2::new();
+
+        let alarm_errors = consumer
+            .consume_once(&buf1, &modelizer, &alarm, &buf2)
+            .await
+            .unwrap();
+
+        assert_eq!(alarm_errors.len(), 3, "3 failures for 3 fraudulent tx");
+    }
+
+    #[tokio::test]
+    async fn buf2_write_not_blocked_by_alarm_failure() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(2));
+        let modelizer = MockModelizer::new(true); // both fraudulent
+        let alarm = MockAlarm::always_failing();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert_eq!(buf2.captured.borrow().len(), 2, "Buffer2 write must proceed");
+    }
+
+    // ------------------------------------------------------------------
+    // T030: US5 -- model version switch
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn switch_to_n_minus1_calls_modelizer_switch_version() {
+        let consumer = make_consumer(10, 1);
+        let modelizer = MockModelizer::new(false);
+
+        consumer
+            .switch_model_version(&modelizer, ModelVersion::NMinus1)
+            .await
+            .unwrap();
+
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::NMinus1));
+    }
+
+    #[tokio::test]
+    async fn switch_to_n_calls_modelizer_switch_version() {
+        let consumer = make_consumer(10, 1);
+        let modelizer = MockModelizer::new(false);
+
+        consumer
+            .switch_model_version(&modelizer, ModelVersion::N)
+            .await
+            .unwrap();
+
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::N));
+    }
+
+    #[tokio::test]
+    async fn switch_error_maps_to_consumer_error_inference() {
+        let consumer = make_consumer(10, 1);
+        let modelizer = MockModelizer::failing_switch();
+
+        let result = consumer
+            .switch_model_version(&modelizer, ModelVersion::N)
+            .await;
+
+        assert!(
+            matches!(result, Err(ConsumerError::Inference(_))),
+            "switch failure must map to ConsumerError::Inference: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn default_model_version_is_n() {
+        // Consumer must not call switch_version implicitly before infer.
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        assert!(
+            modelizer.last_switch.get().is_none(),
+            "Consumer must not call switch_version implicitly"
+        );
+        assert_eq!(modelizer.infer_call_count.get(), 1, "infer must be called once");
+    }
+
+    // ------------------------------------------------------------------
+    // Per-model-version stats
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn stats_start_empty() {
+        let consumer = make_consumer(10, 1);
+        let stats = consumer.stats();
+        assert_eq!(stats.batches, 0);
+        assert_eq!(stats.transactions, 0);
+        assert!(stats.per_version.is_empty());
+    }
+
+    #[tokio::test]
+    async fn per_version_stats_match_modelizer_output_across_switch() {
+        let consumer = make_consumer(10, 3);
+        let buf1 = MockBuffer1Read::new(make_txs(1000));
+        let modelizer = MockModelizer::new(true);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        for _ in 0..3 {
+            consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        }
+        consumer
+            .switch_model_version(&modelizer, ModelVersion::NMinus1)
+            .await
+            .unwrap();
+        for _ in 0..4 {
+            consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        }
+
+        // Tally what the mock actually produced, as captured by Buffer2.
+        let captured = buf2.captured.borrow();
+        let count_version = |v: &str| {
+            let n = captured.iter().filter(|tx| tx.model_version == v).count();
+            u64::try_from(n).unwrap()
+        };
+        let current = count_version("v_test");
+        let previous = count_version("v_prev");
+
+        let stats = consumer.stats();
+        assert_eq!(stats.batches, 7);
+        assert_eq!(stats.transactions, current + previous);
+        assert_eq!(stats.flagged, current + previous, "mock flags every transaction");
+        assert_eq!(stats.per_version.len(), 2);
+        assert_eq!(
+            stats.per_version["v_test"],
+            VersionStats { transactions: current, flagged: current }
+        );
+        assert_eq!(
+            stats.per_version["v_prev"],
+            VersionStats { transactions: previous, flagged: previous }
+        );
+    }
+
+    #[tokio::test]
+    async fn per_version_stats_count_no_flags_for_legitimate() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        let stats = consumer.stats();
+        assert_eq!(stats.flagged, 0);
+        assert_eq!(
+            stats.per_version["v_test"],
+            VersionStats { transactions: 5, flagged: 0 }
+        );
+    }
+
+    #[test]
+    fn stats_display_lists_each_version() {
+        let mut stats = super::ConsumerStats { batches: 2, transactions: 7, flagged: 1, ..Default::default() };
+        stats.per_version.insert("3".to_owned(), VersionStats { transactions: 3, flagged: 0 });
+        stats.per_version.insert("4".to_owned(), VersionStats { transactions: 4, flagged: 1 });
+        let text = stats.to_string();
+        assert!(text.starts_with("consumer: 2 batches, 7 transactions, 1 flagged"));
+        assert!(text.contains("model version 3: 3 transactions, 0 flagged"));
+        assert!(text.contains("model version 4: 4 transactions, 1 flagged"));
+    }
+
+    // ------------------------------------------------------------------
+    // Commands: drain-and-stop, pause, switch
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn config_rejects_zero_drain_idle_polls() {
+        let result = ConsumerConfig::builder(10).drain_idle_polls(0).build();
+        assert!(matches!(result, Err(ConsumerError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn drain_and_stop_flushes_buffer1_without_close() {
+        let consumer = make_consumer(10, 1);
+        let buf1 = OpenBuffer1::new(make_txs(50));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tx.send(ConsumerCommand::DrainAndStop).await.unwrap();
+
+        let reason = consumer
+            .run_with_commands(&buf1, &modelizer, &alarm, &buf2, rx)
+            .await
+            .unwrap();
+
+        assert!(matches!(reason, StopReason::Cancelled { iterations: 5.. }), "{reason:?}");
+        assert_eq!(buf2.captured.borrow().len(), 50, "all 50 must reach Buffer2");
+        assert_eq!(buf1.depth(), 0);
+        assert_eq!(consumer.stats().transactions, 50);
+    }
+
+    #[tokio::test]
+    async fn drain_and_stop_overrides_pause() {
+        let consumer = make_consumer(10, 1);
+        let buf1 = OpenBuffer1::new(make_txs(20));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tx.send(ConsumerCommand::Pause).await.unwrap();
+        tx.send(ConsumerCommand::DrainAndStop).await.unwrap();
+
+        consumer
+            .run_with_commands(&buf1, &modelizer, &alarm, &buf2, rx)
+            .await
+            .unwrap();
+
+        assert_eq!(buf2.captured.borrow().len(), 20);
+    }
+
+    #[tokio::test]
+    async fn switch_command_applies_before_next_batch() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false);
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tx.send(ConsumerCommand::SwitchVersion(ModelVersion::NMinus1)).await.unwrap();
+        drop(tx);
+
+        // MockBuffer1Read closes once drained, ending the loop.
+        let reason = consumer
+            .run_with_commands(&buf1, &modelizer, &alarm, &buf2, rx)
+            .await
+            .unwrap();
+
+        assert!(matches!(reason, StopReason::BufferClosed { .. }), "{reason:?}");
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::NMinus1));
+        assert!(buf2.captured.borrow().iter().all(|tx| tx.model_version == "v_prev"));
+    }
+
+    /// Clock moved forward by hand, from the Unix epoch.
+    #[derive(Debug, Clone, Default)]
+    struct StepClock(Arc<AtomicU64>);
+
+    impl StepClock {
+        fn advance(&self, secs: u64) {
+            self.0.fetch_add(secs, Ordering::Relaxed);
+        }
+
+        fn at(secs: u64) -> SystemTime {
+            UNIX_EPOCH + Duration::from_secs(secs)
+        }
+    }
+
+    impl Clock for StepClock {
+        fn now(&self) -> SystemTime {
+            Self::at(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    fn cooldown_consumer(clock: &StepClock) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(1)
+                .poll_interval2(Duration::ZERO)
+                .switch_cooldown(Duration::from_mins(1))
+                .switch_history(2)
+                .clock(clock.clone())
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn second_rapid_switch_is_throttled() {
+        let clock = StepClock::default();
+        let consumer = cooldown_consumer(&clock);
+        let modelizer = MockModelizer::new(false);
+
+        consumer.switch_model_version(&modelizer, ModelVersion::NMinus1).await.unwrap();
+        clock.advance(59);
+        let result = consumer.switch_model_version(&modelizer, ModelVersion::N).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(ConsumerError::SwitchThrottled { since_last, cooldown })
+                    if since_last == Duration::from_secs(59) && cooldown == Duration::from_mins(1)
+            ),
+            "{result:?}"
+        );
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::NMinus1));
+        assert_eq!(consumer.stats().switches.len(), 1);
+
+        clock.advance(1);
+        consumer.switch_model_version(&modelizer, ModelVersion::N).await.unwrap();
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::N));
+    }
+
+    #[tokio::test]
+    async fn throttled_switch_command_is_nacked_and_the_run_continues() {
+        let consumer = cooldown_consumer(&StepClock::default());
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false);
+        let buf2 = MockBuffer2::new();
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tx.send(ConsumerCommand::SwitchVersion(ModelVersion::NMinus1)).await.unwrap();
+        tx.send(ConsumerCommand::SwitchVersion(ModelVersion::N)).await.unwrap();
+        drop(tx);
+
+        let reason = consumer
+            .run_with_commands(&buf1, &modelizer, &MockAlarm::new(), &buf2, rx)
+            .await
+            .unwrap();
+
+        assert!(matches!(reason, StopReason::BufferClosed { .. }), "{reason:?}");
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::NMinus1));
+        assert_eq!(buf2.captured.borrow().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_the_prior_version() {
+        let clock = StepClock::default();
+        let consumer = cooldown_consumer(&clock);
+        let modelizer = MockModelizer::new(false);
+
+        assert_eq!(consumer.rollback_model_version(&modelizer).await.unwrap(), None);
+        assert_eq!(modelizer.last_switch.get(), None, "nothing to roll back");
+
+        consumer.switch_model_version(&modelizer, ModelVersion::NMinus1).await.unwrap();
+        // Within the cooldown: a rollback still goes through.
+        clock.advance(5);
+        let restored = consumer.rollback_model_version(&modelizer).await.unwrap();
+
+        assert_eq!(restored, Some(ModelVersion::N));
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::N));
+    }
+
+    #[tokio::test]
+    async fn rollback_command_switches_back_before_next_batch() {
+        let consumer = make_consumer(100, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(5));
+        let modelizer = MockModelizer::new(false);
+        let buf2 = MockBuffer2::new();
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tx.send(ConsumerCommand::SwitchVersion(ModelVersion::NMinus1)).await.unwrap();
+        tx.send(ConsumerCommand::Rollback).await.unwrap();
+        drop(tx);
+
+        consumer
+            .run_with_commands(&buf1, &modelizer, &MockAlarm::new(), &buf2, rx)
+            .await
+            .unwrap();
+
+        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::N));
+        assert!(buf2.captured.borrow().iter().all(|tx| tx.model_version == "v_test"));
+    }
+
+    #[tokio::test]
+    async fn switch_history_keeps_the_last_k_switches() {
+        let clock = StepClock::default();
+        let consumer = cooldown_consumer(&clock);
+        let modelizer = MockModelizer::new(false);
+
+        consumer.switch_model_version(&modelizer, ModelVersion::NMinus1).await.unwrap();
+        clock.advance(60);
+        consumer.switch_model_version(&modelizer, ModelVersion::N).await.unwrap();
+        clock.advance(10);
+        consumer.rollback_model_version(&modelizer).await.unwrap();
+        clock.advance(10);
+        // Throttled: not recorded.
+        let throttled = consumer.switch_model_version(&modelizer, ModelVersion::N).await;
+        assert!(matches!(throttled, Err(ConsumerError::SwitchThrottled { .. })));
+
+        let switches = consumer.stats().switches;
+        assert_eq!(
+            switches,
+            vec![
+                SwitchRecord {
+                    from: ModelVersion::NMinus1,
+                    to: ModelVersion::N,
+                    at: StepClock::at(60),
+                    rollback: false,
+                },
+                SwitchRecord {
+                    from: ModelVersion::N,
+                    to: ModelVersion::NMinus1,
+                    at: StepClock::at(70),
+                    rollback: true,
+                },
+            ]
+        );
+        assert!(consumer.stats().to_string().ends_with("model switch N -> NMinus1 (rollback)"));
+    }
+
+    // ------------------------------------------------------------------
+    // Retryable classification
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn error_retryable_classification() {
+        let invalid = ConsumerConfig::builder(0).build().unwrap_err();
+        assert!(!invalid.is_retryable());
+        assert!(!ConsumerError::Read(ReadError::Closed).is_retryable());
+        assert!(ConsumerError::Write(WriteError::Full { capacity: 1, rejected: 1 }).is_retryable());
+        assert!(!ConsumerError::Write(WriteError::Closed).is_retryable());
+        let failed = ModelizerError::InferenceFailed { reason: "t".to_owned() };
+        assert!(ConsumerError::Inference(failed).is_retryable());
+        let switch = ModelizerError::SwitchFailed { reason: "t".to_owned() };
+        assert!(!ConsumerError::Inference(switch).is_retryable());
+        assert!(ConsumerError::DeadLetter(StorageError::Unavailable).is_retryable());
+        assert!(!ConsumerError::InferenceMismatch { expected: 2, got: 1 }.is_retryable());
+    }
+
+    #[test]
+    fn error_display_leaves_the_cause_to_source() {
+        let dead_letter = ConsumerError::DeadLetter(StorageError::Unavailable);
+        assert_eq!(
+            ErrorChain(&dead_letter).messages(),
+            ["dead-letter write error", "storage unavailable"]
+        );
+        let read = ConsumerError::Read(ReadError::Closed);
+        assert_eq!(ErrorChain(&read).to_string(), "buffer1 read error: buffer closed");
+        let invalid = ConsumerConfig::builder(0).build().unwrap_err();
+        assert_eq!(
+            ErrorChain(&invalid).messages(),
+            ["invalid consumer configuration", "n2_max must be >= 1 (got 0)"]
+        );
+    }
+
+    // ------------------------------------------------------------------
+    // Pipeline events
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn run_publishes_events() {
+        let (events, mut rx) = tokio::sync::broadcast::channel(64);
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(3)
+                .seed(42)
+                .poll_interval2(Duration::ZERO)
+                .events(events)
+                .build()
+                .unwrap(),
+        );
+        let txs = make_txs(5);
+        let ids: Vec<_> = txs.iter().map(|tx| tx.id).collect();
+        let buf1 = MockBuffer1Read::new(txs);
+        let modelizer = MockModelizer::new(true);
+        let alarm = MockAlarm::always_failing();
+        let buf2 = MockBuffer2::new();
+
+        consumer.run(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+
+        // Each batch: BatchInferred, then one AlarmFailed per (flagged) transaction.
+        let mut alarmed = vec![];
+        let mut inferred_total = 0;
+        loop {
+            match rx.try_recv().unwrap() {
+                PipelineEvent::BatchInferred { size, flagged, version } => {
+                    assert_eq!(flagged, size);
+                    assert_eq!(version, "v_test");
+                    inferred_total += size;
+                }
+                PipelineEvent::AlarmFailed { id } => {
+                    assert!(alarmed.len() < inferred_total, "alarm before its batch");
+                    alarmed.push(id);
+                }
+                PipelineEvent::StageStopped { stage, reason } => {
+                    assert_eq!(stage, Stage::Consumer);
+                    assert_eq!(reason, "buffer closed");
+                    break;
+                }
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+        assert_eq!(inferred_total, 5);
+        assert_eq!(alarmed, ids);
+        assert!(rx.try_recv().is_err(), "StageStopped must be the last event");
+    }
+
+    #[tokio::test]
+    async fn delivered_alarms_publish_alarm_triggered() {
+        let (events, mut rx) = tokio::sync::broadcast::channel(64);
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(3).poll_interval2(Duration::ZERO).events(events).build().unwrap(),
+        );
+        let txs = make_txs(5);
+        let expected: Vec<_> = txs.iter().map(|tx| (tx.id, tx.amount)).collect();
+        let buf1 = MockBuffer1Read::new(txs);
+
+        let alarm = MockAlarm::new();
+        consumer.run(&buf1, &MockModelizer::new(true), &alarm, &MockBuffer2::new()).await.unwrap();
+
+        let mut triggered = vec![];
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                PipelineEvent::AlarmTriggered { id, amount } => triggered.push((id, amount)),
+                PipelineEvent::AlarmFailed { .. } => panic!("MockAlarm::new never fails"),
+                _ => {}
+            }
+        }
+        assert_eq!(triggered, expected);
+    }
+
+    #[tokio::test]
+    async fn run_without_subscriber_is_unaffected() {
+        // A sender whose only receiver is dropped: every send fails, silently.
+        let (events, rx) = tokio::sync::broadcast::channel(1);
+        drop(rx);
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(3).poll_interval2(Duration::ZERO).events(events).build().unwrap(),
+        );
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        let buf2 = MockBuffer2::new();
+
+        let result = consumer.run(&buf1, &MockModelizer::new(true), &MockAlarm::new(), &buf2).await;
+
+        assert!(matches!(result, Ok(StopReason::BufferClosed { .. })), "{result:?}");
+        assert_eq!(buf2.captured.borrow().len(), 10);
+    }
+
+    // ------------------------------------------------------------------
+    // Alarm cap
+    // ------------------------------------------------------------------
+
+    /// Buffer1 that hands out its whole content in one read, ignoring `max`,
+    /// so a test controls the batch size exactly.
+    struct WholeBuffer1(RefCell<Vec<Transaction>>);
+
+    impl Buffer1Read for WholeBuffer1 {
+        async fn read_batch(&self, _max: usize) -> Result<Vec<Transaction>, ReadError> {
+            let batch = std::mem::take(&mut *self.0.borrow_mut());
+            if batch.is_empty() {
+                return Err(ReadError::Closed);
+            }
+            Ok(batch)
+        }
+    }
+
+    #[tokio::test]
+    async fn alarm_cap_suppresses_overflow_but_writes_all() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10).seed(1).max_alarms_per_batch(3).build().unwrap(),
+        );
+        let buf1 = WholeBuffer1(RefCell::new(make_txs(10)));
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        let errors = consumer
+            .consume_once(&buf1, &MockModelizer::new(true), &alarm, &buf2)
+            .await
+            .unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(alarm.call_count.get(), 3, "exactly cap alarms triggered");
+        assert_eq!(consumer.stats().alarms_suppressed, 7);
+        assert_eq!(buf2.captured.borrow().len(), 10, "suppressed txs still reach Buffer2");
+        assert!(consumer.stats().to_string().contains(", 7 alarms suppressed"));
+    }
+
+    #[tokio::test]
+    async fn alarm_cap_default_is_unlimited() {
+        let consumer = make_consumer(10, 1);
+        let buf1 = MockBuffer1Read::new(make_txs(10));
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.run(&buf1, &MockModelizer::new(true), &alarm, &buf2).await.unwrap();
+
+        assert_eq!(alarm.call_count.get(), 10);
+        assert_eq!(consumer.stats().alarms_suppressed, 0);
+    }
+
+    // ------------------------------------------------------------------
+    // Alarm latency
+    // ------------------------------------------------------------------
+
+    /// Alarm whose n-th delivery sleeps the n-th delay, on the tokio clock.
+    struct SleepingAlarm(RefCell<std::collections::VecDeque<Duration>>);
+
+    impl Alarm for SleepingAlarm {
+        async fn trigger(&self, _transaction: &InferredTransaction) -> Result<(), AlarmError> {
+            let delay = self.0.borrow_mut().pop_front().unwrap_or_default();
+            tokio::time::sleep(delay).await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn alarm_latency_times_every_delivery() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(1)
+                .slow_alarm_threshold(Duration::from_millis(100))
+                .build()
+                .unwrap(),
+        );
+        let buf1 = WholeBuffer1(RefCell::new(make_txs(10)));
+        let mut delays = vec![Duration::from_millis(50); 9];
+        // Exactly at the threshold is not slow; only the 5 s delivery is.
+        delays[3] = Duration::from_millis(100);
+        delays.push(Duration::from_secs(5));
+        let alarm = SleepingAlarm(RefCell::new(delays.into()));
+
+        consumer
+            .consume_once(&buf1, &MockModelizer::new(true), &alarm, &MockBuffer2::new())
+            .await
+            .unwrap();
+
+        let latency = consumer.stats().alarm_latency;
+        assert_eq!(latency.count(), 10);
+        assert_eq!(latency.p50(), Duration::from_millis(50));
+        assert_eq!(latency.p95(), Duration::from_secs(5));
+        assert_eq!(latency.max(), Duration::from_secs(5));
+        assert_eq!(latency.slow(), 1, "one warning, above the threshold only");
+        assert!(
+            consumer
+                .stats()
+                .to_string()
+                .contains("\n  alarm delivery: 10 alarms, p50 50ms, p95 5s, max 5s, 1 slow")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn alarm_latency_without_threshold_never_warns() {
+        let consumer = make_consumer(10, 1);
+        let buf1 = WholeBuffer1(RefCell::new(make_txs(3)));
+        let alarm = SleepingAlarm(RefCell::new(vec![Duration::from_secs(45); 3].into()));
+
+        consumer
+            .consume_once(&buf1, &MockModelizer::new(true), &alarm, &MockBuffer2::new())
+            .await
+            .unwrap();
+
+        let latency = consumer.stats().alarm_latency;
+        assert_eq!(latency.count(), 3);
+        assert_eq!(latency.max(), Duration::from_secs(45));
+        assert_eq!(latency.slow(), 0);
+    }
+
+    // ------------------------------------------------------------------
+    // Warmup
+    // ------------------------------------------------------------------
+
+    /// Shared call log recording the order of Buffer1 reads and infer calls.
+    type CallLog = Rc<RefCell<Vec<String>>>;
+
+    /// Buffer1 that logs every read, then delegates to [`MockBuffer1Read`].
+    struct TracedBuffer1 {
+        inner: MockBuffer1Read,
+        log: CallLog,
+    }
+
+    impl Buffer1Read for TracedBuffer1 {
+        async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+            self.log.borrow_mut().push("read".to_owned());
+            self.inner.read_batch(max).await
+        }
+    }
+
+    /// Modelizer that logs every infer call with its batch size and can
+    /// fail the first one.
+    struct TracedModelizer {
+        inner: MockModelizer,
+        log: CallLog,
+        fail_first: Cell<bool>,
+    }
+
+    impl Modelizer for TracedModelizer {
+        async fn infer(
+            &self,
+            batch: Vec<Transaction>,
+        ) -> Result<Vec<InferredTransaction>, ModelizerError> {
+            self.log.borrow_mut().push(format!("infer {}", batch.len()));
+            if self.fail_first.replace(false) {
+                return Err(ModelizerError::InferenceFailed { reason: "cold".to_owned() });
+            }
+            self.inner.infer(batch).await
+        }
+
+        async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+            self.inner.switch_version(version).await
+        }
+    }
+
+    fn make_traced(n_txs: usize, fail_first: bool) -> (TracedBuffer1, TracedModelizer, CallLog) {
+        let log = CallLog::default();
+        let buf1 = TracedBuffer1 { inner: MockBuffer1Read::new(make_txs(n_txs)), log: Rc::clone(&log) };
+        let modelizer = TracedModelizer {
+            inner: MockModelizer::new(false),
+            log: Rc::clone(&log),
+            fail_first: Cell::new(fail_first),
+        };
+        (buf1, modelizer, log)
+    }
+
+    fn make_warm_consumer(warmup: usize, strict: bool) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(1)
+                .poll_interval2(Duration::ZERO)
+                .warmup(warmup)
+                .warmup_strict(strict)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn warmup_batch_precedes_first_read() {
+        let consumer = make_warm_consumer(5, false);
+        let (buf1, modelizer, log) = make_traced(3, false);
+        let buf2 = MockBuffer2::new();
+
+        consumer.run(&buf1, &modelizer, &MockAlarm::new(), &buf2).await.unwrap();
+
+        assert_eq!(log.borrow()[..2], ["infer 5".to_owned(), "read".to_owned()]);
+        // Warmup results are discarded: only real transactions reach Buffer2 and stats.
+        assert_eq!(buf2.captured.borrow().len(), 3);
+        assert_eq!(consumer.stats().transactions, 3);
+    }
+
+    #[tokio::test]
+    async fn warmup_zero_makes_no_call() {
+        let consumer = make_warm_consumer(0, false);
+        let (buf1, modelizer, log) = make_traced(3, false);
+
+        consumer.warmup(&modelizer, 0).await.unwrap();
+        assert!(log.borrow().is_empty());
+
+        consumer.run(&buf1, &modelizer, &MockAlarm::new(), &MockBuffer2::new()).await.unwrap();
+        assert_eq!(log.borrow()[0], "read");
+    }
+
+    #[tokio::test]
+    async fn lenient_warmup_failure_proceeds() {
+        let consumer = make_warm_consumer(4, false);
+        let (buf1, modelizer, log) = make_traced(6, true);
+        let buf2 = MockBuffer2::new();
+
+        consumer.run(&buf1, &modelizer, &MockAlarm::new(), &buf2).await.unwrap();
+
+        assert_eq!(log.borrow()[0], "infer 4");
+        assert_eq!(buf2.captured.borrow().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn strict_warmup_failure_aborts_before_reading() {
+        let consumer = make_warm_consumer(4, true);
+        let (buf1, modelizer, log) = make_traced(6, true);
+
+        let result = consumer.run(&buf1, &modelizer, &MockAlarm::new(), &MockBuffer2::new()).await;
+
+        assert!(matches!(result, Err(ConsumerError::Inference(_))));
+        assert_eq!(*log.borrow(), ["infer 4".to_owned()]);
+    }
+
+    // ------------------------------------------------------------------
+    // Input validation
+    // ------------------------------------------------------------------
+
+    #[derive(Default)]
+    struct MockDeadLetter {
+        quarantined: RefCell<Vec<RejectedTransaction>>,
+    }
+
+    impl DeadLetter for MockDeadLetter {
+        async fn write_dead_letters(
+            &self,
+            batch: Vec<RejectedTransaction>,
+        ) -> Result<(), StorageError> {
+            self.quarantined.borrow_mut().extend(batch);
+            Ok(())
+        }
+    }
+
+    fn make_amounts(amounts: &[f64]) -> Vec<Transaction> {
+        amounts.iter().map(|&amount| Transaction { amount, ..make_tx() }).collect()
+    }
+
+    fn make_validating_consumer() -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(1)
+                .poll_interval2(Duration::ZERO)
+                .validate_input(true)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn invalid_amounts_are_quarantined_not_inferred() {
+        let consumer = make_validating_consumer();
+        let buf1 = MockBuffer1Read::new(make_amounts(&[f64::NAN, -1.0, 42.5]));
+        let modelizer = MockModelizer::new(false);
+        let buf2 = MockBuffer2::new();
+        let dead_letters = MockDeadLetter::default();
+
+        consumer
+            .run_with_dead_letters(&buf1, &modelizer, &MockAlarm::new(), &buf2, &dead_letters)
+            .await
+            .unwrap();
+
+        assert_eq!(modelizer.last_batch_size.get(), 1);
+        let captured = buf2.captured.borrow();
+        assert_eq!(captured.len(), 1);
+        assert!((captured[0].transaction.amount - 42.5).abs() < f64::EPSILON);
+        let quarantined = dead_letters.quarantined.borrow();
+        assert_eq!(quarantined.len(), 2);
+        assert!(matches!(quarantined[0].reason, InvalidTransaction::NonFiniteAmount(_)));
+        assert_eq!(quarantined[1].reason, InvalidTransaction::NonPositiveAmount(-1.0));
+        let stats = consumer.stats();
+        assert_eq!((stats.transactions, stats.rejected), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn all_invalid_batch_skips_modelizer() {
+        let (events, mut rx) = tokio::sync::broadcast::channel(16);
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(1)
+                .poll_interval2(Duration::ZERO)
+                .validate_input(true)
+                .events(events)
+                .build()
+                .unwrap(),
+        );
+        let buf1 = MockBuffer1Read::new(make_amounts(&[0.0, 20_000.0]));
+        let modelizer = MockModelizer::new(true);
+        let buf2 = MockBuffer2::new();
+
+        consumer.run(&buf1, &modelizer, &MockAlarm::new(), &buf2).await.unwrap();
+
+        assert_eq!(modelizer.infer_call_count.get(), 0);
+        assert!(buf2.captured.borrow().is_empty());
+        assert_eq!(consumer.stats().rejected, 2);
+        // The read still shows as progress, to the Watchdog among others.
+        assert_eq!(rx.try_recv().unwrap(), PipelineEvent::BatchDiscarded { size: 2 });
+    }
+
+    #[tokio::test]
+    async fn validation_is_off_by_default() {
+        let consumer = make_consumer(10, 1);
+        let buf1 = MockBuffer1Read::new(make_amounts(&[-1.0]));
+        let modelizer = MockModelizer::new(false);
+
+        consumer.run(&buf1, &modelizer, &MockAlarm::new(), &MockBuffer2::new()).await.unwrap();
+
+        assert_eq!(modelizer.last_batch_size.get(), 1);
+        assert_eq!(consumer.stats().rejected, 0);
+    }
+
+    // ------------------------------------------------------------------
+    // Empty reads
+    // ------------------------------------------------------------------
+
+    /// Returns pre-scripted batches whatever `max`, then `Closed`, like an
+    /// adapter whose reads time out.
+    struct ScriptedBuffer1 {
+        batches: RefCell<VecDeque<Vec<Transaction>>>,
+    }
+
+    impl ScriptedBuffer1 {
+        fn new(sizes: &[usize]) -> Self {
+            Self { batches: RefCell::new(sizes.iter().map(|&n| make_txs(n)).collect()) }
+        }
+    }
+
+    impl Buffer1Read for ScriptedBuffer1 {
+        async fn read_batch(&self, _max: usize) -> Result<Vec<Transaction>, ReadError> {
+            self.batches.borrow_mut().pop_front().ok_or(ReadError::Closed)
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_reads_skip_inference_and_writes() {
+        let counter = Rc::new(CountingObserver::new());
+        let consumer = make_consumer(10, 1).with_observer(Rc::clone(&counter));
+        let buf1 = ScriptedBuffer1::new(&[0, 0, 4]);
+        let modelizer = MockModelizer::new(false);
+        let buf2 = MockBuffer2::new();
+
+        let stop = consumer.run(&buf1, &modelizer, &MockAlarm::new(), &buf2).await.unwrap();
+
+        // Empty polls still count as iterations; the loop reaches the data.
+        assert_eq!(stop, StopReason::BufferClosed { iterations: 3 });
+        assert_eq!(modelizer.infer_call_count.get(), 1);
+        assert_eq!((buf2.write_calls.get(), buf2.captured.borrow().len()), (1, 4));
+        assert_eq!(counter.batches(), 1, "observers only see the non-empty batch");
+        let stats = consumer.stats();
+        assert_eq!((stats.empty_polls, stats.batches, stats.transactions), (2, 1, 4));
+        assert!(stats.to_string().contains("2 empty polls"), "{stats}");
+    }
+
+    #[tokio::test]
+    async fn empty_read_counts_toward_the_iteration_limit() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .poll_interval2(Duration::ZERO)
+                .iterations(2)
+                .build()
+                .unwrap(),
+        );
+        let buf1 = ScriptedBuffer1::new(&[0, 0, 4]);
+        let modelizer = MockModelizer::new(false);
+        let buf2 = MockBuffer2::new();
+
+        let stop = consumer.run(&buf1, &modelizer, &MockAlarm::new(), &buf2).await.unwrap();
+
+        assert_eq!(stop, StopReason::IterationLimit { iterations: 2 });
+        assert_eq!(buf2.write_calls.get(), 0);
+        assert_eq!(consumer.stats().empty_polls, 2);
+    }
+
+    // ------------------------------------------------------------------
+    // Currency conversion
+    // ------------------------------------------------------------------
+
+    /// Knows a USD rate only.
+    #[derive(Debug)]
+    struct HalfDollar;
+
+    impl CurrencyConverter for HalfDollar {
+        fn rate_to_eur(&self, currency: &Currency) -> Option<f64> {
+            (*currency == Currency::Usd).then_some(0.5)
+        }
+    }
+
+    fn make_priced(prices: &[(f64, Currency)]) -> Vec<Transaction> {
+        prices
+            .iter()
+            .map(|(amount, currency)| Transaction {
+                amount: *amount,
+                currency: currency.clone(),
+                ..make_tx()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn converter_keeps_amounts_and_rejects_unknown_rates() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(1)
+                .poll_interval2(Duration::ZERO)
+                .currency_converter(HalfDollar)
+                .build()
+                .unwrap(),
+        );
+        let buf1 = MockBuffer1Read::new(make_priced(&[
+            (100.0, Currency::Usd),
+            (30.0, Currency::Eur),
+            (10.0, Currency::Other("CHF".to_owned())),
+        ]));
+        let buf2 = MockBuffer2::new();
+        let dead_letters = MockDeadLetter::default();
+
+        consumer
+            .run_with_dead_letters(
+                &buf1,
+                &MockModelizer::new(false),
+                &MockAlarm::new(),
+                &buf2,
+                &dead_letters,
+            )
+            .await
+            .unwrap();
+
+        let forwarded: Vec<(f64, Currency)> = buf2
+            .captured
+            .borrow()
+            .iter()
+            .map(|tx| (tx.transaction.amount, tx.transaction.currency.clone()))
+            .collect();
+        assert_eq!(forwarded, [(100.0, Currency::Usd), (30.0, Currency::Eur)]);
+        let quarantined = dead_letters.quarantined.borrow();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(
+            quarantined[0].reason,
+            InvalidTransaction::NoExchangeRate { currency: Currency::Other("CHF".to_owned()) }
+        );
+        assert_eq!(consumer.stats().rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn amount_threshold_applies_to_the_converted_amount() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(1)
+                .poll_interval2(Duration::ZERO)
+                .validate_input(true)
+                .max_amount(100.0)
+                .currency_converter(HalfDollar)
+                .build()
+                .unwrap(),
+        );
+        // 150 USD is 75 EUR (kept, still in USD); 150 EUR is over the limit (rejected).
+        let buf1 =
+            MockBuffer1Read::new(make_priced(&[(150.0, Currency::Usd), (150.0, Currency::Eur)]));
+        let alarm = MockAlarm::new();
+        let buf2 = MockBuffer2::new();
+
+        consumer.run(&buf1, &MockModelizer::new(true), &alarm, &buf2).await.unwrap();
 
-    fn make_tx() -> Transaction {
-        Transaction {
-            id: uuid::Uuid::new_v4(),
-            amount: 1.00_f64,
-            last_name: "Test".to_owned(),
-        }
+        assert_eq!(forwarded_amounts(&buf2), [150.0]);
+        assert_eq!(buf2.captured.borrow()[0].transaction.currency, Currency::Usd);
+        assert_eq!(alarm.call_count.get(), 1);
+        assert_eq!(consumer.stats().rejected, 1);
     }
 
-    fn make_txs(n: usize) -> Vec<Transaction> {
-        (0..n).map(|_| make_tx()).collect()
+    #[tokio::test]
+    async fn without_a_converter_amounts_pass_through() {
+        let consumer = make_consumer(10, 1);
+        let buf1 = MockBuffer1Read::new(make_priced(&[(150.0, Currency::Usd)]));
+        let buf2 = MockBuffer2::new();
+
+        consumer.run(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2).await.unwrap();
+
+        let captured = buf2.captured.borrow();
+        assert_eq!(captured[0].transaction.currency, Currency::Usd);
+        assert_eq!(forwarded_amounts(&buf2), [150.0]);
     }
 
-    fn make_consumer(n2_max: usize, seed: u64) -> Consumer {
+    // ------------------------------------------------------------------
+    // Load shedding
+    // ------------------------------------------------------------------
+
+    /// One fixed-size batch of `n2_max`, shedding above `depth`.
+    fn make_shedding_consumer(n2_max: usize, depth: usize, keep_latest: usize) -> Consumer {
         Consumer::new(
             ConsumerConfig::builder(n2_max)
-                .seed(seed)
+                .fixed_batch_size(true)
+                .iterations(1)
                 .poll_interval2(Duration::ZERO)
+                .shed_above(depth, keep_latest)
                 .build()
                 .unwrap(),
         )
     }
 
-    // ------------------------------------------------------------------
-    // Mock adapters (T017)
-    // ------------------------------------------------------------------
-
-    struct MockBuffer1Read {
-        transactions: RefCell<VecDeque<Transaction>>,
+    /// `n` transactions, oldest first, whose amounts count up from 1.
+    fn make_numbered(n: u32) -> Vec<Transaction> {
+        (1..=n).map(|i| Transaction { amount: f64::from(i), ..make_tx() }).collect()
     }
 
-    impl MockBuffer1Read {
-        fn new(transactions: Vec<Transaction>) -> Self {
-            Self { transactions: RefCell::new(VecDeque::from(transactions)) }
-        }
+    fn numbered(range: std::ops::RangeInclusive<u32>) -> Vec<f64> {
+        range.map(f64::from).collect()
     }
 
-    impl Buffer1Read for MockBuffer1Read {
-        async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, BufferError> {
-            let mut queue = self.transactions.borrow_mut();
-            if queue.is_empty() {
-                return Err(BufferError::Closed);
-            }
-            let count = max.min(queue.len());
-            Ok(queue.drain(..count).collect())
-        }
+    fn forwarded_amounts(buf2: &MockBuffer2) -> Vec<f64> {
+        buf2.captured.borrow().iter().map(|tx| tx.transaction.amount).collect()
     }
 
-    struct MockModelizer {
-        predicted_fraud: bool,
-        infer_call_count: Cell<u32>,
-        last_batch_size: Cell<usize>,
-        last_switch: Cell<Option<ModelVersion>>,
-        fail_infer: bool,
-        fail_switch: bool,
+    #[test]
+    fn config_rejects_keeping_more_than_the_shed_depth() {
+        let result = ConsumerConfig::builder(10).shed_above(5, 6).build();
+        assert!(matches!(
+            result,
+            Err(ConsumerError::InvalidConfig(ref e)) if e.field == "keep_latest"
+        ));
+        ConsumerConfig::builder(10).shed_above(5, 5).build().unwrap();
     }
 
-    impl MockModelizer {
-        fn new(predicted_fraud: bool) -> Self {
-            Self {
-                predicted_fraud,
-                infer_call_count: Cell::new(0),
-                last_batch_size: Cell::new(0),
-                last_switch: Cell::new(None),
-                fail_infer: false,
-                fail_switch: false,
-            }
-        }
+    #[tokio::test]
+    async fn deep_backlog_is_shed_down_to_keep_latest_and_quarantined() {
+        let consumer = make_shedding_consumer(10, 50, 20);
+        let buf1 = OpenBuffer1::new(make_numbered(100));
+        let buf2 = MockBuffer2::new();
+        let dead_letters = MockDeadLetter::default();
 
-        fn failing_infer() -> Self {
-            Self { fail_infer: true, ..Self::new(false) }
-        }
+        consumer
+            .run_with_dead_letters(
+                &buf1,
+                &MockModelizer::new(false),
+                &MockAlarm::new(),
+                &buf2,
+                &dead_letters,
+            )
+            .await
+            .unwrap();
 
-        fn failing_switch() -> Self {
-            Self { fail_switch: true, ..Self::new(false) }
-        }
+        // The oldest 80 are shed, then one batch of the 20 kept is processed.
+        let quarantined = dead_letters.quarantined.borrow();
+        let shed: Vec<f64> = quarantined.iter().map(|r| r.transaction.amount).collect();
+        assert_eq!(shed, numbered(1..=80));
+        assert!(quarantined.iter().all(|r| r.reason == InvalidTransaction::Shed { depth: 100 }));
+        assert_eq!(forwarded_amounts(&buf2), numbered(81..=90));
+        assert_eq!(buf1.depth(), 10);
+        let stats = consumer.stats();
+        assert_eq!((stats.shed, stats.transactions), (80, 10));
+        assert!(stats.to_string().contains(", 80 shed"), "{stats}");
     }
 
-    impl Modelizer for MockModelizer {
-        async fn infer(
-            &self,
-            batch: Vec<Transaction>,
-        ) -> Result<Vec<InferredTransaction>, ModelizerError> {
-            if self.fail_infer {
-                return Err(ModelizerError::InferenceFailed {
-                    reason: "mock failure".to_owned(),
-                });
-            }
-            self.infer_call_count.set(self.infer_call_count.get() + 1);
-            self.last_batch_size.set(batch.len());
-            Ok(batch
-                .into_iter()
-                .map(|tx| InferredTransaction {
-                    predicted_fraud: self.predicted_fraud,
-                    model_name: "MOCK".to_owned(),
-                    model_version: "v_test".to_owned(),
-                    transaction: tx,
-                })
-                .collect())
-        }
+    #[tokio::test]
+    async fn nothing_is_shed_at_or_below_the_limit() {
+        let consumer = make_shedding_consumer(10, 50, 20);
+        let buf1 = OpenBuffer1::new(make_numbered(50));
+        let buf2 = MockBuffer2::new();
+        let dead_letters = MockDeadLetter::default();
 
-        async fn switch_version(
-            &self,
-            version: ModelVersion,
-        ) -> Result<(), ModelizerError> {
-            if self.fail_switch {
-                return Err(ModelizerError::SwitchFailed {
-                    reason: "mock failure".to_owned(),
-                });
-            }
-            self.last_switch.set(Some(version));
-            Ok(())
-        }
-    }
+        consumer
+            .run_with_dead_letters(
+                &buf1,
+                &MockModelizer::new(false),
+                &MockAlarm::new(),
+                &buf2,
+                &dead_letters,
+            )
+            .await
+            .unwrap();
 
-    struct MockAlarm {
-        call_count: Cell<u32>,
-        always_fail: bool,
+        assert!(dead_letters.quarantined.borrow().is_empty());
+        assert_eq!(forwarded_amounts(&buf2), numbered(1..=10));
+        assert_eq!(buf1.depth(), 40);
+        assert_eq!(consumer.stats().shed, 0);
     }
 
-    impl MockAlarm {
-        fn new() -> Self {
-            Self { call_count: Cell::new(0), always_fail: false }
-        }
+    #[tokio::test]
+    async fn run_with_commands_sheds_and_counts_without_a_sink() {
+        let consumer = make_shedding_consumer(10, 30, 5);
+        let buf1 = OpenBuffer1::new(make_numbered(40));
+        let buf2 = MockBuffer2::new();
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
 
-        fn always_failing() -> Self {
-            Self { call_count: Cell::new(0), always_fail: true }
-        }
-    }
+        consumer
+            .run_with_commands(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2, rx)
+            .await
+            .unwrap();
 
-    impl Alarm for MockAlarm {
-        async fn trigger(
-            &self,
-            transaction: &InferredTransaction,
-        ) -> Result<(), AlarmError> {
-            self.call_count.set(self.call_count.get() + 1);
-            if self.always_fail {
-                return Err(AlarmError::DeliveryFailed {
-                    reason: format!("mock fail for tx {}", transaction.id()),
-                });
-            }
-            Ok(())
-        }
+        assert_eq!(consumer.stats().shed, 35);
+        assert_eq!(forwarded_amounts(&buf2), numbered(36..=40));
+        assert_eq!(buf1.depth(), 0);
     }
 
-    struct MockBuffer2 {
-        captured: RefCell<Vec<InferredTransaction>>,
-        fail: Option<BufferError>,
-    }
+    #[tokio::test]
+    async fn run_with_commands_quarantines_what_it_sheds_into_the_sink() {
+        let consumer = make_shedding_consumer(10, 30, 5);
+        let buf1 = OpenBuffer1::new(make_numbered(40));
+        let buf2 = MockBuffer2::new();
+        let dead_letters = MockDeadLetter::default();
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
 
-    impl MockBuffer2 {
-        fn new() -> Self {
-            Self { captured: RefCell::new(vec![]), fail: None }
-        }
+        consumer
+            .run_with_commands_and_dead_letters(
+                &buf1,
+                &MockModelizer::new(false),
+                &MockAlarm::new(),
+                &buf2,
+                rx,
+                &dead_letters,
+            )
+            .await
+            .unwrap();
 
-        fn with_fail(error: BufferError) -> Self {
-            Self { captured: RefCell::new(vec![]), fail: Some(error) }
-        }
+        let quarantined = dead_letters.quarantined.borrow();
+        let shed: Vec<f64> = quarantined.iter().map(|r| r.transaction.amount).collect();
+        assert_eq!(shed, numbered(1..=35));
+        assert!(quarantined.iter().all(|r| r.reason == InvalidTransaction::Shed { depth: 40 }));
+        assert_eq!(consumer.stats().shed, 35);
+        assert_eq!(forwarded_amounts(&buf2), numbered(36..=40));
     }
 
-    impl Buffer2 for MockBuffer2 {
-        async fn write_batch(
-            &self,
-            batch: Vec<InferredTransaction>,
-        ) -> Result<(), BufferError> {
-            if let Some(e) = &self.fail {
-                return Err(e.clone());
-            }
-            self.captured.borrow_mut().extend(batch);
-            Ok(())
-        }
+    #[tokio::test]
+    async fn plain_run_never_sheds() {
+        let consumer = make_shedding_consumer(10, 5, 0);
+        let buf1 = OpenBuffer1::new(make_numbered(40));
+
+        consumer
+            .run(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &MockBuffer2::new())
+            .await
+            .unwrap();
+
+        assert_eq!(consumer.stats().shed, 0);
+        assert_eq!(buf1.depth(), 30);
     }
 
     // ------------------------------------------------------------------
-    // T015: ConsumerConfig validation
+    // Adaptive interval
     // ------------------------------------------------------------------
 
-    #[test]
-    fn config_rejects_zero_n2_max() {
-        let result = ConsumerConfig::builder(0).build();
-        assert!(matches!(result, Err(ConsumerError::InvalidConfig { .. })));
-    }
+    #[tokio::test(start_paused = true)]
+    async fn adaptive_interval_drains_deep_buffer_without_sleeping() {
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(3)
+                .poll_interval2(Duration::from_millis(100))
+                .iterations(20)
+                .adaptive_interval(AdaptiveInterval::new(3, Duration::from_secs(1)))
+                .build()
+                .unwrap(),
+        );
+        let buf1 = MockBuffer1Read::new(make_txs(1_000));
+        let start = tokio::time::Instant::now();
 
-    #[test]
-    fn builder_defaults_poll_interval2() {
-        let config = ConsumerConfig::builder(10).build().unwrap();
-        assert_eq!(config.poll_interval2, Duration::from_millis(100));
-    }
+        consumer
+            .run(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &MockBuffer2::new())
+            .await
+            .unwrap();
 
-    #[test]
-    fn builder_with_seed() {
-        let config = ConsumerConfig::builder(10).seed(42).build().unwrap();
-        assert_eq!(config.seed, Some(42));
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        let pacing = consumer.stats().pacing;
+        assert_eq!((pacing.sleeps, pacing.total), (19, Duration::ZERO));
     }
 
     #[test]
-    fn builder_with_iterations() {
-        let config = ConsumerConfig::builder(10).iterations(5).build().unwrap();
-        assert_eq!(config.iterations, Some(5));
+    fn invalid_adaptive_interval_is_rejected() {
+        let adaptive = AdaptiveInterval { multiplier: 0, ..AdaptiveInterval::new(1, Duration::ZERO) };
+        let err = ConsumerConfig::builder(5).adaptive_interval(adaptive).build().unwrap_err();
+        assert!(matches!(err, ConsumerError::InvalidConfig(ref e) if e.field == "multiplier"));
     }
 
     // ------------------------------------------------------------------
-    // T018: US1 -- read behavior
+    // Batch observers
     // ------------------------------------------------------------------
 
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Seen {
+        Batch(ConsumeOutcome),
+        Stop(RunEnd),
+    }
+
+    /// Records every notification, in order.
+    #[derive(Debug, Default)]
+    struct RecordingObserver {
+        seen: RefCell<Vec<Seen>>,
+    }
+
+    impl BatchObserver for RecordingObserver {
+        fn on_batch(&self, outcome: &ConsumeOutcome) {
+            self.seen.borrow_mut().push(Seen::Batch(outcome.clone()));
+        }
+
+        fn on_stop(&self, end: &RunEnd) {
+            self.seen.borrow_mut().push(Seen::Stop(end.clone()));
+        }
+    }
+
     #[tokio::test]
-    async fn batch_size_within_n2_max_range() {
-        let n2_max = 10;
-        let consumer = make_consumer(n2_max, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(1000));
-        let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+    async fn observer_sees_every_batch_in_order_then_closed() {
+        let recorder = Rc::new(RecordingObserver::default());
+        let consumer = make_consumer(10, 42).with_observer(Rc::clone(&recorder));
+        let modelizer = MockModelizer::new(true);
+        let alarm = MockAlarm::always_failing();
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        let reason = consumer
+            .run(&MockBuffer1Read::new(make_txs(25)), &modelizer, &alarm, &MockBuffer2::new())
+            .await
+            .unwrap();
 
-        let sz = modelizer.last_batch_size.get();
-        assert!(sz >= 1 && sz <= n2_max, "batch size {sz} out of [1, {n2_max}]");
+        // Same seed, same batch sizes: replay by hand to get the expected outcomes.
+        let reference = make_consumer(10, 42);
+        let buf1 = MockBuffer1Read::new(make_txs(25));
+        let mut expected = vec![];
+        while let Ok(errors) =
+            reference.consume_once(&buf1, &modelizer, &alarm, &MockBuffer2::new()).await
+        {
+            let n = errors.len();
+            expected.push(Seen::Batch(ConsumeOutcome {
+                read: n,
+                inferred: n,
+                flagged: n,
+                alarms_failed: n,
+                model_version: "v_test".to_owned(),
+                ..ConsumeOutcome::default()
+            }));
+        }
+        let batches = expected.len() as u64;
+        assert_eq!(reason, StopReason::BufferClosed { iterations: batches });
+        expected.push(Seen::Stop(RunEnd::Stopped(reason)));
+        assert_eq!(*recorder.seen.borrow(), expected);
     }
 
     #[tokio::test]
-    async fn batch_size_capped_by_available_data() {
-        // n2_max >> available transactions; buffer caps the returned batch.
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(3));
-        let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+    async fn tuple_of_observers_sees_iteration_limit() {
+        let counter = Rc::new(CountingObserver::new());
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(5)
+                .seed(7)
+                .poll_interval2(Duration::ZERO)
+                .iterations(3)
+                .build()
+                .unwrap(),
+        )
+        .with_observer((Rc::clone(&counter), LoggingObserver));
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        consumer
+            .run(
+                &MockBuffer1Read::new(make_txs(100)),
+                &MockModelizer::new(false),
+                &MockAlarm::new(),
+                &MockBuffer2::new(),
+            )
+            .await
+            .unwrap();
 
-        assert_eq!(modelizer.last_batch_size.get(), 3);
+        assert_eq!(counter.batches(), 3);
+        assert_eq!(counter.transactions(), consumer.stats().transactions);
+        assert_eq!(counter.flagged(), 0);
+        let reason = StopReason::IterationLimit { iterations: 3 };
+        assert_eq!(counter.run_end(), Some(RunEnd::Stopped(reason)));
     }
 
     #[tokio::test]
-    async fn seeded_batch_size_is_deterministic() {
-        let c1 = make_consumer(10, 99);
-        let c2 = make_consumer(10, 99);
-        let buf1_a = MockBuffer1Read::new(make_txs(100));
-        let buf1_b = MockBuffer1Read::new(make_txs(100));
-        let m1 = MockModelizer::new(false);
-        let m2 = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+    async fn observer_sees_error_as_terminal_reason() {
+        let recorder = Rc::new(RecordingObserver::default());
+        let consumer = make_consumer(10, 1).with_observer(Rc::clone(&recorder));
 
-        c1.consume_once(&buf1_a, &m1, &alarm, &buf2).await.unwrap();
-        c2.consume_once(&buf1_b, &m2, &alarm, &buf2).await.unwrap();
+        let result = consumer
+            .run(
+                &MockBuffer1Read::new(make_txs(5)),
+                &MockModelizer::failing_infer(),
+                &MockAlarm::new(),
+                &MockBuffer2::new(),
+            )
+            .await;
 
-        assert_eq!(
-            m1.last_batch_size.get(),
-            m2.last_batch_size.get(),
-            "identical seeds must request identical batch sizes"
-        );
+        let Err(error) = result else { panic!("inference failure must stop the run") };
+        let expected = vec![Seen::Stop(RunEnd::Failed(ErrorChain(&error).to_string()))];
+        assert_eq!(*recorder.seen.borrow(), expected);
     }
 
-    // ------------------------------------------------------------------
-    // T019: US1 -- run loop
-    // ------------------------------------------------------------------
+    #[tokio::test]
+    async fn observers_run_in_registration_order_and_see_drain() {
+        let first = Rc::new(CountingObserver::new());
+        let second = Rc::new(RecordingObserver::default());
+        let consumer = make_consumer(10, 1)
+            .with_observer(Rc::clone(&first))
+            .with_observer(Rc::clone(&second));
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tx.send(ConsumerCommand::DrainAndStop).await.unwrap();
+
+        let reason = consumer
+            .run_with_commands(
+                &OpenBuffer1::new(make_txs(30)),
+                &MockModelizer::new(false),
+                &MockAlarm::new(),
+                &MockBuffer2::new(),
+                rx,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(first.transactions(), 30);
+        assert_eq!(reason, StopReason::Cancelled { iterations: first.batches() });
+        assert_eq!(first.run_end(), Some(RunEnd::Stopped(reason)));
+        let seen = second.seen.borrow();
+        assert_eq!(seen.last(), Some(&Seen::Stop(RunEnd::Stopped(reason))));
+        assert_eq!(seen.len() as u64, first.batches() + 1);
+    }
 
     #[tokio::test]
-    async fn run_processes_n_iterations() {
+    async fn rejected_transactions_are_reported_in_outcome() {
+        let recorder = Rc::new(RecordingObserver::default());
         let consumer = Consumer::new(
             ConsumerConfig::builder(10)
-                .seed(7)
-                .iterations(3)
+                .seed(5)
                 .poll_interval2(Duration::ZERO)
+                .validate_input(true)
                 .build()
                 .unwrap(),
-        );
-        let buf1 = MockBuffer1Read::new(make_txs(1000));
-        let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+        )
+        .with_observer(Rc::clone(&recorder));
+        let mut txs = make_txs(12);
+        txs[1].amount = -1.0;
+        txs[7].amount = f64::NAN;
 
-        consumer.run(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        consumer
+            .run(
+                &MockBuffer1Read::new(txs),
+                &MockModelizer::new(false),
+                &MockAlarm::new(),
+                &MockBuffer2::new(),
+            )
+            .await
+            .unwrap();
 
-        assert_eq!(modelizer.infer_call_count.get(), 3, "expected 3 infer calls");
+        let totals = recorder.seen.borrow().iter().fold((0, 0, 0), |acc, seen| match seen {
+            Seen::Batch(o) => (acc.0 + o.read, acc.1 + o.rejected, acc.2 + o.inferred),
+            Seen::Stop(_) => acc,
+        });
+        assert_eq!(totals, (12, 2, 10));
     }
 
-    #[tokio::test]
-    async fn run_stops_gracefully_on_closed() {
-        let consumer = make_consumer(10, 1);
-        // Empty buffer signals Closed immediately.
-        let buf1 = MockBuffer1Read::new(vec![]);
-        let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+    // ------------------------------------------------------------------
+    // Config summary
+    // ------------------------------------------------------------------
 
-        let result = consumer.run(&buf1, &modelizer, &alarm, &buf2).await;
-        assert!(result.is_ok(), "Closed must terminate cleanly: {result:?}");
+    #[test]
+    fn summary_reports_the_builder_values() {
+        let config = ConsumerConfig::builder(8)
+            .batch_size_mode(BatchSizeMode::AlwaysMax)
+            .poll_interval2(Duration::from_millis(20))
+            .seed(43)
+            .max_alarms_per_batch(2)
+            .adaptive_interval(AdaptiveInterval::new(3, Duration::from_secs(1)))
+            .shed_above(100, 10)
+            .switch_cooldown(Duration::from_secs(5))
+            .alarm_ordering(AlarmOrdering::WriteFirst)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.summary().to_string(),
+            "consumer: preset=none n2_max=8 fixed_batch_size=false batch_size_mode=always max \
+             poll_interval2=20ms iterations=none seed=43 drain_idle_polls=3 \
+             max_alarms_per_batch=2 warmup=0 warmup_strict=false validate_input=false \
+             max_amount=10000 adaptive_interval=below 3 x2 up to 1s \
+             shed_above=depth 100 keep 10 switch_cooldown=5s switch_history=10 \
+             currency_converter=false alarm_ordering=write first quarantine=false \
+             write_retries=3 write_chunk_size=none slow_alarm_threshold=none \
+             inference_check=length inference_cache=none"
+        );
+        let consumer = Consumer::new(config);
+        assert_eq!(consumer.config().summary().get("n2_max"), Some("8"));
     }
 
     // ------------------------------------------------------------------
-    // T022: US2 -- Modelizer interaction
+    // Quarantine
     // ------------------------------------------------------------------
 
-    #[tokio::test]
-    async fn consume_once_sends_full_batch_to_modelizer() {
-        // 10 transactions preloaded, n2_max = 100 -> all 10 reach Modelizer.
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(10));
-        let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+    /// Modelizer that fails any batch holding a poisoned id, as a model
+    /// choking on one malformed transaction would.
+    struct PoisonedModelizer {
+        inner: MockModelizer,
+        poisoned: Vec<TransactionId>,
+    }
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+    impl Modelizer for PoisonedModelizer {
+        async fn infer(
+            &self,
+            batch: Vec<Transaction>,
+        ) -> Result<Vec<InferredTransaction>, ModelizerError> {
+            if let Some(tx) = batch.iter().find(|tx| self.poisoned.contains(&tx.id)) {
+                let reason = format!("poisoned {}", tx.id);
+                return Err(ModelizerError::InferenceFailed { reason });
+            }
+            self.inner.infer(batch).await
+        }
 
-        assert_eq!(modelizer.last_batch_size.get(), 10);
+        async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+            self.inner.switch_version(version).await
+        }
     }
 
-    #[tokio::test]
-    async fn inference_error_propagates_as_consumer_error_inference() {
-        let consumer = make_consumer(10, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(10));
-        let modelizer = MockModelizer::failing_infer();
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
-
-        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
-        assert!(
-            matches!(result, Err(ConsumerError::Inference(_))),
-            "inference failure must map to ConsumerError::Inference: {result:?}"
-        );
+    /// Quarantine sharing its rows with the test, or failing every call.
+    #[derive(Debug, Clone, Default)]
+    struct SharedQuarantine {
+        rows: Arc<std::sync::Mutex<Vec<QuarantinedTransaction>>>,
+        down: bool,
     }
 
-    // ------------------------------------------------------------------
-    // T023: US2 -- InferredTransaction enrichment fields
-    // ------------------------------------------------------------------
+    impl SharedQuarantine {
+        fn reasons(&self) -> Vec<(TransactionId, String)> {
+            let rows = self.rows.lock().unwrap();
+            rows.iter().map(|row| (row.transaction.id, row.reason.clone())).collect()
+        }
 
-    #[tokio::test]
-    async fn inferred_tx_enrichment_fields_survive_pipeline() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(2));
-        let modelizer = MockModelizer::new(true);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+        fn check(&self) -> Result<(), StorageError> {
+            if self.down { Err(StorageError::Unavailable) } else { Ok(()) }
+        }
+    }
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+    impl Quarantine for SharedQuarantine {
+        fn record<'a>(&'a self, tx: &'a Transaction, reason: &'a str) -> QuarantineFuture<'a, ()> {
+            Box::pin(async move {
+                self.check()?;
+                self.rows.lock().unwrap().push(QuarantinedTransaction {
+                    transaction: tx.clone(),
+                    reason: reason.to_owned(),
+                    quarantined_at: UNIX_EPOCH,
+                });
+                Ok(())
+            })
+        }
 
-        let captured = buf2.captured.borrow();
-        assert_eq!(captured.len(), 2);
-        for tx in captured.iter() {
-            assert!(tx.predicted_fraud);
-            assert_eq!(tx.model_name, "MOCK");
-            assert_eq!(tx.model_version, "v_test");
+        fn quarantined(&self, max: usize) -> QuarantineFuture<'_, Vec<QuarantinedTransaction>> {
+            Box::pin(async move {
+                self.check()?;
+                Ok(self.rows.lock().unwrap().iter().take(max).cloned().collect())
+            })
+        }
+
+        fn release<'a>(&'a self, ids: &'a [TransactionId]) -> QuarantineFuture<'a, ()> {
+            Box::pin(async move {
+                self.check()?;
+                self.rows.lock().unwrap().retain(|row| !ids.contains(&row.transaction.id));
+                Ok(())
+            })
         }
     }
 
-    // ------------------------------------------------------------------
-    // T025: US4 -- Buffer2 write
-    // ------------------------------------------------------------------
+    fn quarantining_consumer(quarantine: &SharedQuarantine) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(4)
+                .fixed_batch_size(true)
+                .poll_interval2(Duration::ZERO)
+                .quarantine(quarantine.clone())
+                .build()
+                .unwrap(),
+        )
+    }
 
     #[tokio::test]
-    async fn all_inferred_tx_written_to_buf2_regardless_of_fraud() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(5));
-        let modelizer = MockModelizer::new(true); // all fraudulent
-        let alarm = MockAlarm::new();
+    async fn failing_ids_are_quarantined_with_the_inference_error() {
+        let txs = make_txs(10);
+        let poisoned = vec![txs[1].id, txs[6].id];
+        let modelizer = PoisonedModelizer { inner: MockModelizer::new(false), poisoned };
+        let quarantine = SharedQuarantine::default();
+        let consumer = quarantining_consumer(&quarantine);
         let buf2 = MockBuffer2::new();
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        let reason = consumer
+            .run(&MockBuffer1Read::new(txs.clone()), &modelizer, &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
 
-        assert_eq!(buf2.captured.borrow().len(), 5, "all 5 must reach Buffer2");
+        assert!(matches!(reason, StopReason::BufferClosed { .. }), "{reason:?}");
+        let expected: Vec<_> = [&txs[1], &txs[6]]
+            .iter()
+            .map(|tx| (tx.id, format!("inference failed: poisoned {}", tx.id)))
+            .collect();
+        assert_eq!(quarantine.reasons(), expected);
+        let forwarded: Vec<_> = buf2.captured.borrow().iter().map(InferredTransaction::id).collect();
+        let healthy: Vec<_> =
+            txs.iter().map(|tx| tx.id).filter(|id| *id != txs[1].id && *id != txs[6].id).collect();
+        assert_eq!(forwarded, healthy);
+        let stats = consumer.stats();
+        assert_eq!((stats.quarantined, stats.quarantine_errors, stats.transactions), (2, 0, 8));
     }
 
     #[tokio::test]
-    async fn buf2_full_propagates_as_consumer_error_write() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(5));
-        let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::with_fail(BufferError::Full { capacity: 0 });
-
-        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
-        assert!(
-            matches!(result, Err(ConsumerError::Write(BufferError::Full { .. }))),
-            "Full must map to ConsumerError::Write: {result:?}"
-        );
-    }
+    async fn without_quarantine_a_failed_batch_fails_the_run() {
+        let txs = make_txs(4);
+        let modelizer =
+            PoisonedModelizer { inner: MockModelizer::new(false), poisoned: vec![txs[0].id] };
+        let consumer = make_consumer(4, 1);
 
-    #[tokio::test]
-    async fn buf2_closed_propagates_as_consumer_error_write() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(5));
-        let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::with_fail(BufferError::Closed);
+        let result = consumer
+            .run(&MockBuffer1Read::new(txs), &modelizer, &MockAlarm::new(), &MockBuffer2::new())
+            .await;
 
-        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
-        assert!(
-            matches!(result, Err(ConsumerError::Write(BufferError::Closed))),
-            "Closed must map to ConsumerError::Write: {result:?}"
-        );
+        assert!(matches!(result, Err(ConsumerError::Inference(_))), "{result:?}");
     }
 
-    // ------------------------------------------------------------------
-    // T027: US3 -- alarm count
-    // ------------------------------------------------------------------
-
     #[tokio::test]
-    async fn exactly_n_alarms_for_n_fraudulent_tx() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(5));
-        let modelizer = MockModelizer::new(true); // all 5 fraudulent
-        let alarm = MockAlarm::new();
+    async fn requeue_round_trips_quarantined_transactions() {
+        let txs = make_txs(6);
+        let poisoned = vec![txs[2].id, txs[3].id];
+        let quarantine = SharedQuarantine::default();
+        let buf1 = MockBuffer1Read::new(txs.clone());
         let buf2 = MockBuffer2::new();
+        let sick = PoisonedModelizer { inner: MockModelizer::new(false), poisoned };
+        quarantining_consumer(&quarantine)
+            .run(&buf1, &sick, &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        // The model is fixed: requeue and score them again.
+        assert_eq!(requeue(&quarantine, &buf1, 100).await.unwrap(), 2);
+        assert!(quarantine.reasons().is_empty());
+        quarantining_consumer(&quarantine)
+            .run(&buf1, &MockModelizer::new(false), &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
 
-        assert_eq!(alarm.call_count.get(), 5, "5 alarms for 5 fraudulent tx");
+        let mut forwarded: Vec<_> = buf2.captured.borrow().iter().map(InferredTransaction::id).collect();
+        let mut all: Vec<_> = txs.iter().map(|tx| tx.id).collect();
+        forwarded.sort();
+        all.sort();
+        assert_eq!(forwarded, all);
     }
 
     #[tokio::test]
-    async fn no_alarms_for_zero_fraudulent_tx() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(5));
-        let modelizer = MockModelizer::new(false); // none fraudulent
-        let alarm = MockAlarm::new();
+    async fn failing_quarantine_does_not_abort_the_batch() {
+        let txs = make_txs(4);
+        let modelizer =
+            PoisonedModelizer { inner: MockModelizer::new(false), poisoned: vec![txs[0].id] };
+        let quarantine = SharedQuarantine { down: true, ..SharedQuarantine::default() };
+        let consumer = quarantining_consumer(&quarantine);
         let buf2 = MockBuffer2::new();
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        let errors = consumer
+            .consume_once(&MockBuffer1Read::new(txs), &modelizer, &MockAlarm::new(), &buf2)
+            .await
+            .unwrap();
 
-        assert_eq!(alarm.call_count.get(), 0, "0 alarms when none fraudulent");
+        assert!(errors.is_empty());
+        assert_eq!(buf2.captured.borrow().len(), 3, "the healthy transactions go on");
+        let stats = consumer.stats();
+        assert_eq!((stats.quarantined, stats.quarantine_errors), (0, 1));
     }
 
     #[tokio::test]
-    async fn zero_alarms_when_all_legitimate() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(3));
-        let modelizer = MockModelizer::new(false);
+    async fn fully_quarantined_batch_writes_nothing() {
+        let txs = make_txs(2);
+        let poisoned = txs.iter().map(|tx| tx.id).collect();
+        let modelizer = PoisonedModelizer { inner: MockModelizer::new(true), poisoned };
+        let quarantine = SharedQuarantine::default();
+        let consumer = quarantining_consumer(&quarantine);
         let alarm = MockAlarm::new();
         let buf2 = MockBuffer2::new();
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        consumer.consume_once(&MockBuffer1Read::new(txs), &modelizer, &alarm, &buf2).await.unwrap();
 
+        assert_eq!(quarantine.reasons().len(), 2);
+        assert_eq!(buf2.write_calls.get(), 0);
         assert_eq!(alarm.call_count.get(), 0);
     }
 
     // ------------------------------------------------------------------
-    // T028: US3 -- best-effort alarm delivery
+    // Inference cache
     // ------------------------------------------------------------------
 
-    #[tokio::test]
-    async fn all_alarms_attempted_on_partial_failure() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(4));
-        let modelizer = MockModelizer::new(true); // all 4 fraudulent
-        let alarm = MockAlarm::always_failing();
-        let buf2 = MockBuffer2::new();
+    /// Model counting its `classify` calls into a shared counter; the
+    /// version follows the switches.
+    #[derive(Default)]
+    struct CountingModel {
+        classified: Rc<Cell<u32>>,
+        previous: Cell<bool>,
+    }
 
-        let result = consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await;
-        assert!(result.is_ok(), "alarm failures must not abort consume_once: {result:?}");
+    impl domain::Model for CountingModel {
+        async fn classify(&self, tx: &Transaction) -> Result<bool, ModelizerError> {
+            self.classified.set(self.classified.get() + 1);
+            Ok(tx.amount > 100.0)
+        }
 
-        assert_eq!(alarm.call_count.get(), 4, "all 4 alarms must be attempted");
-    }
+        fn name(&self) -> &'static str {
+            "COUNTING"
+        }
 
-    #[tokio::test]
-    async fn alarm_failures_returned_in_ok_vec() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(3));
-        let modelizer = MockModelizer::new(true); // all 3 fraudulent
-        let alarm = MockAlarm::always_failing();
-        let buf2 = MockBuffer2::new();
+        fn active_version(&self) -> &'static str {
+            if self.previous.get() { "3" } else { "4" }
+        }
 
-        let alarm_errors = consumer
-            .consume_once(&buf1, &modelizer, &alarm, &buf2)
-            .await
-            .unwrap();
+        async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+            self.previous.set(version == ModelVersion::NMinus1);
+            Ok(())
+        }
+    }
 
-        assert_eq!(alarm_errors.len(), 3, "3 failures for 3 fraudulent tx");
+    fn caching_consumer(capacity: usize) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(10)
+                .fixed_batch_size(true)
+                .inference_cache(capacity)
+                .build()
+                .unwrap(),
+        )
     }
 
-    #[tokio::test]
-    async fn buf2_write_not_blocked_by_alarm_failure() {
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(2));
-        let modelizer = MockModelizer::new(true); // both fraudulent
-        let alarm = MockAlarm::always_failing();
+    /// Consume `txs` as one batch; returns what reached Buffer2.
+    async fn consume_cached<M: Modelizer>(
+        consumer: &Consumer,
+        modelizer: &M,
+        txs: &[Transaction],
+    ) -> Vec<InferredTransaction> {
         let buf2 = MockBuffer2::new();
-
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
-
-        assert_eq!(buf2.captured.borrow().len(), 2, "Buffer2 write must proceed");
+        let buf1 = MockBuffer1Read::new(txs.to_vec());
+        consumer.consume_once(&buf1, modelizer, &MockAlarm::new(), &buf2).await.unwrap();
+        buf2.captured.take()
     }
 
-    // ------------------------------------------------------------------
-    // T030: US5 -- model version switch
-    // ------------------------------------------------------------------
-
     #[tokio::test]
-    async fn switch_to_n_minus1_calls_modelizer_switch_version() {
-        let consumer = make_consumer(10, 1);
-        let modelizer = MockModelizer::new(false);
+    async fn repeated_id_is_classified_once() {
+        let consumer = caching_consumer(16);
+        let model = CountingModel::default();
+        let classified = Rc::clone(&model.classified);
+        let modelizer = modelizer::Modelizer::new(model);
+        let (a, b) = (make_tx(), Transaction { amount: 500.0, ..make_tx() });
 
-        consumer
-            .switch_model_version(&modelizer, ModelVersion::NMinus1)
-            .await
-            .unwrap();
+        let first = consume_cached(&consumer, &modelizer, &[a.clone(), b.clone()]).await;
+        let replayed = consume_cached(&consumer, &modelizer, &[b.clone(), make_tx(), a]).await;
 
-        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::NMinus1));
+        assert_eq!(classified.get(), 3, "only the new transaction is classified");
+        assert!(first.iter().all(|it| !it.cached));
+        let cached: Vec<_> = replayed.iter().map(|it| (it.id(), it.cached)).collect();
+        assert_eq!(cached, [(b.id, true), (replayed[1].id(), false), (first[0].id(), true)]);
+        assert!(replayed[0].predicted_fraud && !replayed[2].predicted_fraud);
+        assert_eq!(replayed[0].model_version, "4");
+        assert_eq!(consumer.stats().cache_hits, 2);
+        assert!(consumer.stats().to_string().contains(", 2 cache hits"));
     }
 
     #[tokio::test]
-    async fn switch_to_n_calls_modelizer_switch_version() {
-        let consumer = make_consumer(10, 1);
-        let modelizer = MockModelizer::new(false);
+    async fn fully_cached_batch_skips_the_modelizer() {
+        let consumer = caching_consumer(16);
+        let modelizer = MockModelizer::new(true);
+        let txs = make_txs(3);
+        consume_cached(&consumer, &modelizer, &txs).await;
 
-        consumer
-            .switch_model_version(&modelizer, ModelVersion::N)
-            .await
-            .unwrap();
+        let replayed = consume_cached(&consumer, &modelizer, &txs).await;
 
-        assert_eq!(modelizer.last_switch.get(), Some(ModelVersion::N));
+        assert_eq!(modelizer.infer_call_count.get(), 1);
+        assert!(replayed.iter().all(|it| it.cached && it.predicted_fraud));
+        assert_eq!(consumer.stats().transactions, 6, "hits are counted as inferred");
     }
 
     #[tokio::test]
-    async fn switch_error_maps_to_consumer_error_inference() {
-        let consumer = make_consumer(10, 1);
-        let modelizer = MockModelizer::failing_switch();
+    async fn version_switch_clears_the_cache() {
+        let consumer = caching_consumer(16);
+        let model = CountingModel::default();
+        let classified = Rc::clone(&model.classified);
+        let modelizer = modelizer::Modelizer::new(model);
+        let tx = make_tx();
+        consume_cached(&consumer, &modelizer, std::slice::from_ref(&tx)).await;
 
-        let result = consumer
-            .switch_model_version(&modelizer, ModelVersion::N)
-            .await;
+        consumer.switch_model_version(&modelizer, ModelVersion::NMinus1).await.unwrap();
+        let after = consume_cached(&consumer, &modelizer, &[tx]).await;
 
-        assert!(
-            matches!(result, Err(ConsumerError::Inference(_))),
-            "switch failure must map to ConsumerError::Inference: {result:?}"
-        );
+        assert_eq!(classified.get(), 2);
+        assert!(!after[0].cached);
+        assert_eq!(after[0].model_version, "3");
     }
 
     #[tokio::test]
-    async fn default_model_version_is_n() {
-        // Consumer must not call switch_version implicitly before infer.
-        let consumer = make_consumer(100, 1);
-        let buf1 = MockBuffer1Read::new(make_txs(5));
+    async fn cache_evicts_at_its_capacity() {
         let modelizer = MockModelizer::new(false);
-        let alarm = MockAlarm::new();
-        let buf2 = MockBuffer2::new();
+        let consumer = caching_consumer(2);
+        let txs = make_txs(3);
+        for tx in &txs {
+            consume_cached(&consumer, &modelizer, std::slice::from_ref(tx)).await;
+        }
 
-        consumer.consume_once(&buf1, &modelizer, &alarm, &buf2).await.unwrap();
+        // The first id was evicted by the third; the two others are still cached.
+        let replayed = consume_cached(&consumer, &modelizer, &txs[1..]).await;
+        assert!(replayed.iter().all(|it| it.cached));
+        let evicted = consume_cached(&consumer, &modelizer, &txs[..1]).await;
+        assert!(!evicted[0].cached);
+        assert_eq!(modelizer.infer_call_count.get(), 4);
+    }
 
-        assert!(
-            modelizer.last_switch.get().is_none(),
-            "Consumer must not call switch_version implicitly"
+    #[tokio::test]
+    async fn quarantined_miss_is_left_out_of_a_cached_batch() {
+        let txs = make_txs(3);
+        let modelizer =
+            PoisonedModelizer { inner: MockModelizer::new(false), poisoned: vec![txs[1].id] };
+        let quarantine = SharedQuarantine::default();
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .fixed_batch_size(true)
+                .inference_cache(8)
+                .quarantine(quarantine.clone())
+                .build()
+                .unwrap(),
         );
-        assert_eq!(modelizer.infer_call_count.get(), 1, "infer must be called once");
+        consume_cached(&consumer, &modelizer, &txs[..1]).await;
+
+        let written = consume_cached(&consumer, &modelizer, &txs).await;
+
+        let ids: Vec<_> = written.iter().map(|it| (it.id(), it.cached)).collect();
+        assert_eq!(ids, [(txs[0].id, true), (txs[2].id, false)]);
+        assert_eq!(consumer.stats().quarantined, 1);
+    }
+
+    #[test]
+    fn zero_inference_cache_is_rejected() {
+        let err = ConsumerConfig::builder(1).inference_cache(0).build().unwrap_err();
+        assert!(matches!(err, ConsumerError::InvalidConfig(ref e) if e.field == "inference_cache"));
     }
 }
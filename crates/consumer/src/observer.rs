@@ -0,0 +1,251 @@
+// Rust guideline compliant 2026-02-27
+
+//! Per-batch hooks for the Consumer run loops.
+//!
+//! A [`BatchObserver`] registered with [`Consumer::with_observer`] sees a
+//! [`ConsumeOutcome`] after every successful batch and one [`RunEnd`] when the
+//! loop ends. Observers are synchronous and infallible: they must not
+//! block, and they cannot change what the Consumer does.
+//!
+//! [`Consumer::with_observer`]: crate::Consumer::with_observer
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+use domain::StopReason;
+
+use crate::TARGET;
+
+// ---------------------------------------------------------------------------
+// ConsumeOutcome + RunEnd
+// ---------------------------------------------------------------------------
+
+/// What one successful batch did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsumeOutcome {
+    /// Transactions read from Buffer1.
+    pub read: usize,
+    /// Transactions rejected by input validation; never inferred.
+    pub rejected: usize,
+    /// Transactions inference failed on, handed to the quarantine.
+    pub quarantined: usize,
+    /// Transactions inferred and written to Buffer2.
+    pub inferred: usize,
+    /// Inferred transactions flagged as fraudulent.
+    pub flagged: usize,
+    /// Alarms attempted but not delivered.
+    pub alarms_failed: usize,
+    /// Alarms skipped by `max_alarms_per_batch`.
+    pub alarms_suppressed: usize,
+    /// Model version of the batch; empty when nothing was inferred.
+    pub model_version: String,
+}
+
+/// How a Consumer run loop ended.
+///
+/// Displays as the `reason` of the matching `PipelineEvent::StageStopped`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunEnd {
+    /// The loop returned `Ok` with this reason.
+    Stopped(StopReason),
+    /// The loop returned this error (displayed).
+    Failed(String),
+}
+
+impl fmt::Display for RunEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stopped(reason) => f.write_str(reason.label()),
+            Self::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// BatchObserver
+// ---------------------------------------------------------------------------
+
+/// Callback invoked by the Consumer run loops.
+///
+/// Implemented for `Rc<T>` (keep a handle to read the observer back) and for
+/// tuples of up to three observers, notified in order.
+pub trait BatchObserver: fmt::Debug {
+    /// Called after each successful non-empty batch, in processing order.
+    fn on_batch(&self, outcome: &ConsumeOutcome);
+
+    /// Called once when the run loop stops, whatever the reason.
+    fn on_stop(&self, end: &RunEnd) {
+        let _ = end;
+    }
+}
+
+impl<T: BatchObserver + ?Sized> BatchObserver for Rc<T> {
+    fn on_batch(&self, outcome: &ConsumeOutcome) {
+        (**self).on_batch(outcome);
+    }
+
+    fn on_stop(&self, end: &RunEnd) {
+        (**self).on_stop(end);
+    }
+}
+
+impl<A: BatchObserver, B: BatchObserver> BatchObserver for (A, B) {
+    fn on_batch(&self, outcome: &ConsumeOutcome) {
+        self.0.on_batch(outcome);
+        self.1.on_batch(outcome);
+    }
+
+    fn on_stop(&self, end: &RunEnd) {
+        self.0.on_stop(end);
+        self.1.on_stop(end);
+    }
+}
+
+impl<A: BatchObserver, B: BatchObserver, C: BatchObserver> BatchObserver for (A, B, C) {
+    fn on_batch(&self, outcome: &ConsumeOutcome) {
+        self.0.on_batch(outcome);
+        self.1.on_batch(outcome);
+        self.2.on_batch(outcome);
+    }
+
+    fn on_stop(&self, end: &RunEnd) {
+        self.0.on_stop(end);
+        self.1.on_stop(end);
+        self.2.on_stop(end);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Built-in observers
+// ---------------------------------------------------------------------------
+
+/// Logs every outcome at debug level and the run end at info level.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingObserver;
+
+impl BatchObserver for LoggingObserver {
+    fn on_batch(&self, outcome: &ConsumeOutcome) {
+        tracing::debug!(
+            target: TARGET,
+            read = outcome.read,
+            rejected = outcome.rejected,
+            inferred = outcome.inferred,
+            flagged = outcome.flagged,
+            alarms_failed = outcome.alarms_failed,
+            alarms_suppressed = outcome.alarms_suppressed,
+            version = %outcome.model_version,
+            "consumer.observer.batch"
+        );
+    }
+
+    fn on_stop(&self, end: &RunEnd) {
+        tracing::info!(target: TARGET, %end, "consumer.observer.stopped");
+    }
+}
+
+/// Totals the outcomes it sees and remembers how the run ended.
+///
+/// Register it through an `Rc` to read the counters while or after the
+/// Consumer runs.
+#[derive(Debug, Default)]
+pub struct CountingObserver {
+    batches: Cell<u64>,
+    transactions: Cell<u64>,
+    flagged: Cell<u64>,
+    run_end: RefCell<Option<RunEnd>>,
+}
+
+impl CountingObserver {
+    /// Create an observer with all counters at zero.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Batches seen.
+    #[must_use]
+    pub fn batches(&self) -> u64 {
+        self.batches.get()
+    }
+
+    /// Inferred transactions seen.
+    #[must_use]
+    pub fn transactions(&self) -> u64 {
+        self.transactions.get()
+    }
+
+    /// Flagged transactions seen.
+    #[must_use]
+    pub fn flagged(&self) -> u64 {
+        self.flagged.get()
+    }
+
+    /// The terminal notification, once the loop has stopped.
+    #[must_use]
+    pub fn run_end(&self) -> Option<RunEnd> {
+        self.run_end.borrow().clone()
+    }
+}
+
+impl BatchObserver for CountingObserver {
+    fn on_batch(&self, outcome: &ConsumeOutcome) {
+        self.batches.set(self.batches.get() + 1);
+        self.transactions.set(self.transactions.get() + outcome.inferred as u64);
+        self.flagged.set(self.flagged.get() + outcome.flagged as u64);
+    }
+
+    fn on_stop(&self, end: &RunEnd) {
+        *self.run_end.borrow_mut() = Some(end.clone());
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchObserver, ConsumeOutcome, CountingObserver, RunEnd};
+    use domain::StopReason;
+    use std::rc::Rc;
+
+    fn outcome(inferred: usize, flagged: usize) -> ConsumeOutcome {
+        ConsumeOutcome { read: inferred, inferred, flagged, ..ConsumeOutcome::default() }
+    }
+
+    #[test]
+    fn counting_observer_totals_outcomes() {
+        let counter = CountingObserver::new();
+        counter.on_batch(&outcome(10, 2));
+        counter.on_batch(&outcome(5, 0));
+        assert_eq!((counter.batches(), counter.transactions(), counter.flagged()), (2, 15, 2));
+        assert_eq!(counter.run_end(), None);
+        let end = RunEnd::Stopped(StopReason::BufferClosed { iterations: 2 });
+        counter.on_stop(&end);
+        assert_eq!(counter.run_end(), Some(end));
+    }
+
+    #[test]
+    fn tuple_notifies_every_member() {
+        let a = Rc::new(CountingObserver::new());
+        let b = Rc::new(CountingObserver::new());
+        let both = (Rc::clone(&a), Rc::clone(&b));
+        both.on_batch(&outcome(3, 1));
+        let end = RunEnd::Stopped(StopReason::IterationLimit { iterations: 1 });
+        both.on_stop(&end);
+        for counter in [&a, &b] {
+            assert_eq!(counter.transactions(), 3);
+            assert_eq!(counter.run_end(), Some(end.clone()));
+        }
+    }
+
+    #[test]
+    fn run_end_displays_like_stage_stopped() {
+        let stopped = |reason| RunEnd::Stopped(reason).to_string();
+        assert_eq!(stopped(StopReason::BufferClosed { iterations: 4 }), "buffer closed");
+        assert_eq!(stopped(StopReason::IterationLimit { iterations: 4 }), "iteration limit reached");
+        assert_eq!(stopped(StopReason::Cancelled { iterations: 4 }), "cancelled");
+        assert_eq!(RunEnd::Failed("boom".to_owned()).to_string(), "boom");
+    }
+}
@@ -0,0 +1,197 @@
+// Rust guideline compliant 2026-02-27
+
+//! Putting quarantined transactions back into the pipeline.
+//!
+//! A Consumer configured with [`ConsumerConfigBuilder::quarantine`] keeps the
+//! transactions it cannot score in a `Quarantine`. Once the cause is fixed
+//! (model redeployed, feature store back), [`requeue`] writes them back into
+//! Buffer1 so the Consumer scores them like any other transaction.
+//!
+//! [`ConsumerConfigBuilder::quarantine`]: crate::ConsumerConfigBuilder::quarantine
+
+use domain::{Buffer1, Quarantine, StorageError, TransactionId, WriteError};
+
+use crate::TARGET;
+
+/// Errors returned by [`requeue`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum RequeueError {
+    /// Reading or releasing the quarantined transactions failed.
+    #[error("quarantine error")]
+    Quarantine(#[source] StorageError),
+    /// The Buffer1 write failed; the transactions stay quarantined.
+    #[error("buffer1 write error")]
+    Write(#[source] WriteError),
+}
+
+/// Move up to `max` quarantined transactions, oldest first, into `buf1`.
+///
+/// The transactions are written as one batch, then released from the
+/// quarantine. A failed write leaves them quarantined; a failed release after
+/// a successful write leaves them in both places, so the next requeue writes
+/// them again.
+///
+/// Returns the number of transactions requeued; `0` when the quarantine is empty.
+///
+/// # Errors
+///
+/// Returns [`RequeueError::Quarantine`] when the quarantine cannot be read or
+/// released, and [`RequeueError::Write`] when `buf1` rejects the batch.
+pub async fn requeue<Q, B>(quarantine: &Q, buf1: &B, max: usize) -> Result<usize, RequeueError>
+where
+    Q: Quarantine + ?Sized,
+    B: Buffer1,
+{
+    let rows = quarantine.quarantined(max).await.map_err(RequeueError::Quarantine)?;
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    let ids: Vec<TransactionId> = rows.iter().map(|row| row.transaction.id).collect();
+    let batch = rows.into_iter().map(|row| row.transaction).collect();
+    buf1.write_batch(batch).await.map_err(RequeueError::Write)?;
+    quarantine.release(&ids).await.map_err(RequeueError::Quarantine)?;
+    tracing::info!(target: TARGET, requeued = ids.len(), "consumer.quarantine.requeued");
+    Ok(ids.len())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{RequeueError, requeue};
+    use domain::{
+        Buffer1, Currency, Quarantine, QuarantineFuture, QuarantinedTransaction, StorageError,
+        Transaction, TransactionId, WriteError,
+    };
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    /// Quarantine over a `Vec`, in record order.
+    #[derive(Debug, Default)]
+    struct VecQuarantine(Mutex<Vec<QuarantinedTransaction>>);
+
+    impl VecQuarantine {
+        fn ids(&self) -> Vec<TransactionId> {
+            self.0.lock().unwrap().iter().map(|row| row.transaction.id).collect()
+        }
+    }
+
+    impl Quarantine for VecQuarantine {
+        fn record<'a>(&'a self, tx: &'a Transaction, reason: &'a str) -> QuarantineFuture<'a, ()> {
+            self.0.lock().unwrap().push(QuarantinedTransaction {
+                transaction: tx.clone(),
+                reason: reason.to_owned(),
+                quarantined_at: SystemTime::UNIX_EPOCH,
+            });
+            Box::pin(async { Ok(()) })
+        }
+
+        fn quarantined(&self, max: usize) -> QuarantineFuture<'_, Vec<QuarantinedTransaction>> {
+            let rows = self.0.lock().unwrap().iter().take(max).cloned().collect();
+            Box::pin(async { Ok(rows) })
+        }
+
+        fn release<'a>(&'a self, ids: &'a [TransactionId]) -> QuarantineFuture<'a, ()> {
+            self.0.lock().unwrap().retain(|row| !ids.contains(&row.transaction.id));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    /// Buffer1 capturing written transactions, or failing every write.
+    #[derive(Default)]
+    struct CapturingBuffer1 {
+        written: RefCell<Vec<Transaction>>,
+        closed: bool,
+    }
+
+    impl Buffer1 for CapturingBuffer1 {
+        async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+            if self.closed {
+                return Err(WriteError::Closed);
+            }
+            self.written.borrow_mut().extend(batch);
+            Ok(())
+        }
+    }
+
+    fn make_tx() -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: 42.0,
+            last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    async fn quarantine_of(n: usize) -> (VecQuarantine, Vec<Transaction>) {
+        let quarantine = VecQuarantine::default();
+        let txs: Vec<_> = (0..n).map(|_| make_tx()).collect();
+        for tx in &txs {
+            quarantine.record(tx, "inference failed: test").await.unwrap();
+        }
+        (quarantine, txs)
+    }
+
+    #[tokio::test]
+    async fn requeue_moves_the_oldest_into_buffer1() {
+        let (quarantine, txs) = quarantine_of(5).await;
+        let buf1 = CapturingBuffer1::default();
+
+        assert_eq!(requeue(&quarantine, &buf1, 3).await.unwrap(), 3);
+
+        assert_eq!(*buf1.written.borrow(), txs[..3]);
+        let left: Vec<_> = txs[3..].iter().map(|tx| tx.id).collect();
+        assert_eq!(quarantine.ids(), left);
+    }
+
+    #[tokio::test]
+    async fn requeue_of_an_empty_quarantine_writes_nothing() {
+        let buf1 = CapturingBuffer1 { closed: true, ..CapturingBuffer1::default() };
+
+        // No write is attempted, so the closed buffer is never seen.
+        assert_eq!(requeue(&VecQuarantine::default(), &buf1, 10).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_write_keeps_the_transactions_quarantined() {
+        let (quarantine, txs) = quarantine_of(2).await;
+        let buf1 = CapturingBuffer1 { closed: true, ..CapturingBuffer1::default() };
+
+        let result = requeue(&quarantine, &buf1, 10).await;
+
+        assert!(matches!(result, Err(RequeueError::Write(WriteError::Closed))), "{result:?}");
+        assert_eq!(quarantine.ids(), txs.iter().map(|tx| tx.id).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn unreadable_quarantine_is_reported() {
+        #[derive(Debug)]
+        struct Down;
+
+        impl Quarantine for Down {
+            fn record<'a>(&'a self, _: &'a Transaction, _: &'a str) -> QuarantineFuture<'a, ()> {
+                Box::pin(async { Err(StorageError::Unavailable) })
+            }
+
+            fn quarantined(&self, _: usize) -> QuarantineFuture<'_, Vec<QuarantinedTransaction>> {
+                Box::pin(async { Err(StorageError::Unavailable) })
+            }
+
+            fn release<'a>(&'a self, _: &'a [TransactionId]) -> QuarantineFuture<'a, ()> {
+                Box::pin(async { Err(StorageError::Unavailable) })
+            }
+        }
+
+        let result = requeue(&Down, &CapturingBuffer1::default(), 10).await;
+
+        assert!(
+            matches!(result, Err(RequeueError::Quarantine(StorageError::Unavailable))),
+            "{result:?}"
+        );
+    }
+}
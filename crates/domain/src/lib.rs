@@ -2,19 +2,270 @@
 
 //! Shared domain types for the fraud-detection pipeline.
 //!
-//! Defines `Transaction`, `BufferError`, `StorageError`, and the hexagonal port traits:
-//! `Buffer1`, `Buffer1Read`, `Buffer2`, `Buffer2Read`, `Storage`, `Model`, `Modelizer`, and `Alarm`.
+//! Defines `Transaction`, `TransactionId`, `Currency`, `ReadError`, `WriteError`,
+//! `StorageError`, and the hexagonal port traits: `Buffer1`, `Buffer1Read`, `Buffer2`,
+//! `Buffer2Read`, `Storage` (with its `RunId` / `RunMeta` / `RunSummary` lifecycle),
+//! `Model`, `FeatureExtractor`, `Modelizer`, `Alarm`, `StorageRead`, `BucketSink`,
+//! `DeadLetter`, `Quarantine`, `AlarmAudit`, `Clock`, `CurrencyConverter`, `RngPort`, and
+//! `Sleeper`, plus the optional `BufferDepth` capability, the `BatchSizeMode` and
+//! `AdaptiveInterval` stage policies, the `DynamicSettings` stages reload while running,
+//! the `ConfigSummary` of a stage configuration, the `StreamDigest` of a transaction
+//! stream, and the `PipelineEvent` observer feed.
 //! All pipeline components depend on this crate; no other crate is imported here.
+//! [`profile`] holds the bench-only timing hooks of the `bench-profile` feature;
+//! [`obs`] the canonical log field names and the stage log macros;
+//! [`stats`] the streaming percentiles every latency report is computed with.
+
+pub mod obs;
+pub mod profile;
+pub mod stats;
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Identity of a [`Transaction`], a UUID.
+///
+/// `Display` shows the first 8 hex digits (`3f2a9c1e`) to keep log lines
+/// readable; the alternate form `{:#}` and [`full`](Self::full) give the full
+/// hyphenated string, which is what storage adapters persist. The prefix is
+/// not unique: ids from `IdStrategy::Sequential` or v7 ids from the same
+/// millisecond share it. `Ord` follows the UUID bytes, so sorting is stable
+/// across runs. With the `serde` feature, ids (de)serialize as the full string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TransactionId(uuid::Uuid);
+
+impl TransactionId {
+    /// Number of hex digits shown by the short `Display` form.
+    pub const SHORT_LEN: usize = 8;
+
+    /// A new random (v4) id.
+    #[must_use]
+    pub fn new_v4() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    /// Wrap an existing UUID.
+    #[must_use]
+    pub const fn from_uuid(uuid: uuid::Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Full hyphenated lowercase form, e.g. `3f2a9c1e-...`; parses back with `FromStr`.
+    #[must_use]
+    pub fn full(&self) -> String {
+        self.0.hyphenated().to_string()
+    }
+}
+
+impl fmt::Display for TransactionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Display::fmt(&self.0.hyphenated(), f);
+        }
+        let mut buf = uuid::Uuid::encode_buffer();
+        let simple = self.0.simple().encode_lower(&mut buf);
+        f.write_str(&simple[..Self::SHORT_LEN])
+    }
+}
+
+/// Error returned when parsing a [`TransactionId`] fails.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid transaction id")]
+pub struct ParseTransactionIdError(#[source] uuid::Error);
+
+impl FromStr for TransactionId {
+    type Err = ParseTransactionIdError;
+
+    /// Parse any UUID form accepted by `uuid` (hyphenated, simple, URN, braced).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        uuid::Uuid::parse_str(s).map(Self).map_err(ParseTransactionIdError)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TransactionId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{self:#}"))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TransactionId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Currency of a [`Transaction`] amount.
+///
+/// Written and parsed as its ISO 4217 code; any code other than `EUR`, `USD`
+/// and `GBP` is kept as-is in `Other`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Currency {
+    /// Euro, the pipeline's reference currency (default).
+    #[default]
+    Eur,
+    /// US dollar.
+    Usd,
+    /// Pound sterling.
+    Gbp,
+    /// Any other currency, by code (e.g. `"CHF"`).
+    Other(String),
+}
+
+impl Currency {
+    /// ISO 4217 code, e.g. `"EUR"`.
+    #[must_use]
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Eur => "EUR",
+            Self::Usd => "USD",
+            Self::Gbp => "GBP",
+            Self::Other(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = Infallible;
+
+    /// Map `EUR`, `USD` and `GBP` to their variants, anything else to `Other`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "EUR" => Self::Eur,
+            "USD" => Self::Usd,
+            "GBP" => Self::Gbp,
+            other => Self::Other(other.to_owned()),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Currency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Currency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let Ok(currency) = s.parse();
+        Ok(currency)
+    }
+}
 
 /// A single banking transaction produced by the pipeline.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Transaction {
     /// Unique identifier (UUID v4-compatible random bytes).
-    pub id: uuid::Uuid,
-    /// Transaction amount in euros, range `[0.01, 10_000.00]`.
+    pub id: TransactionId,
+    /// Transaction amount in `currency`, range `[0.01, 10_000.00]`.
     pub amount: f64,
     /// Account holder last name.
     pub last_name: String,
+    /// Currency of `amount`.
+    pub currency: Currency,
+    /// Upstream source (bank) the transaction came from; `None` unless the
+    /// Producer generates for several tenants.
+    pub tenant_id: Option<String>,
+}
+
+impl Transaction {
+    /// Largest amount the Producer generates, and the default validation bound.
+    pub const MAX_AMOUNT: f64 = 10_000.0;
+
+    /// Stable 64-bit hash of the id and the amount in whole cents, as folded
+    /// into a [`StreamDigest`].
+    ///
+    /// FNV-1a over the 16 UUID bytes followed by the rounded cents as
+    /// little-endian `i64`, so it does not depend on the platform or on
+    /// float noise below a cent. The name and currency are not hashed.
+    #[must_use]
+    pub fn digest_hash(&self) -> u64 {
+        const OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01B3;
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "amounts are far below i64::MAX cents; out-of-range values saturate"
+        )]
+        let cents = (self.amount * 100.0).round() as i64;
+        self.id.0.as_bytes().iter().chain(&cents.to_le_bytes()).fold(OFFSET, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+        })
+    }
+
+    /// Check that `amount` is finite, `> 0` and `<= MAX_AMOUNT`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`InvalidTransaction`] rule the amount breaks.
+    pub fn validate(&self) -> Result<(), InvalidTransaction> {
+        self.validate_max(Self::MAX_AMOUNT)
+    }
+
+    /// Check that `amount` is finite, `> 0` and `<= max_amount`.
+    ///
+    /// For adapters that ingest external data and apply their own bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`InvalidTransaction`] rule the amount breaks.
+    pub fn validate_max(&self, max_amount: f64) -> Result<(), InvalidTransaction> {
+        let amount = self.amount;
+        if !amount.is_finite() {
+            Err(InvalidTransaction::NonFiniteAmount(amount))
+        } else if amount <= 0.0 {
+            Err(InvalidTransaction::NonPositiveAmount(amount))
+        } else if amount > max_amount {
+            Err(InvalidTransaction::AmountTooLarge { amount, max: max_amount })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Why a [`Transaction`] failed validation or was otherwise rejected.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum InvalidTransaction {
+    /// Amount is NaN or infinite.
+    #[error("amount is not finite (got {0})")]
+    NonFiniteAmount(f64),
+    /// Amount is zero or negative.
+    #[error("amount must be > 0 (got {0})")]
+    NonPositiveAmount(f64),
+    /// Amount is above the configured maximum.
+    #[error("amount must be <= {max} (got {amount})")]
+    AmountTooLarge {
+        /// The rejected amount.
+        amount: f64,
+        /// The bound it exceeded.
+        max: f64,
+    },
+    /// Valid, but dropped unread by Consumer load shedding.
+    #[error("shed at Buffer1 depth {depth}")]
+    Shed {
+        /// Buffer1 depth that triggered the shedding.
+        depth: usize,
+    },
+    /// The Consumer's `CurrencyConverter` has no EUR rate for the currency.
+    #[error("no EUR exchange rate for {currency}")]
+    NoExchangeRate {
+        /// The currency without a rate.
+        currency: Currency,
+    },
 }
 
 /// A transaction enriched with Modelizer inference results.
@@ -28,16 +279,58 @@ pub struct InferredTransaction {
     pub model_name: String,
     /// Version string of the model used (e.g. "v1").
     pub model_version: String,
+    /// Buffer2 write attempts this transaction took, the successful one
+    /// included: 1 unless the Consumer had to retry the write.
+    pub delivery_attempts: u8,
+    /// `true` when the verdict came from the Consumer's inference cache
+    /// instead of a Modelizer call.
+    pub cached: bool,
 }
 
 impl InferredTransaction {
+    /// Wrap `transaction` with its inference result, on its first delivery
+    /// attempt.
+    #[must_use]
+    pub fn new(
+        transaction: Transaction,
+        predicted_fraud: bool,
+        model_name: impl Into<String>,
+        model_version: impl Into<String>,
+    ) -> Self {
+        Self {
+            transaction,
+            predicted_fraud,
+            model_name: model_name.into(),
+            model_version: model_version.into(),
+            delivery_attempts: 1,
+            cached: false,
+        }
+    }
+
     /// Return the transaction ID, delegating to the wrapped transaction.
     #[must_use]
-    pub fn id(&self) -> uuid::Uuid {
+    pub fn id(&self) -> TransactionId {
         self.transaction.id
     }
 }
 
+/// Wire format version stamped on every new [`PendingTransaction`].
+///
+/// - 1: records persisted before versioning existed (no stored version).
+/// - 2: adds the stored `record_version` itself.
+/// - 3: adds `persisted_at` and `reviewed_at`.
+/// - 4: adds the transaction `currency`; older records are in EUR.
+/// - 5: adds the `run_id` of the run that persisted the record.
+/// - 6: adds the `delivery_attempts` of the inferred transaction; older
+///   records took 1.
+/// - 7: adds the transaction `tenant_id`; older records have none.
+/// - 8: adds the `cached` flag of the inferred transaction; older records
+///   were all inferred by the model.
+///
+/// Readers branch on the stored version and fill defaults for fields an
+/// older record lacks, so old rows stay readable as the struct grows.
+pub const RECORD_VERSION: u32 = 8;
+
 /// A transaction awaiting full verification, wrapping an inferred result.
 #[derive(Debug, Clone, PartialEq)]
 pub struct PendingTransaction {
@@ -52,28 +345,109 @@ pub struct PendingTransaction {
     /// `None` until reviewed; `Some(true)` = confirmed fraud,
     /// `Some(false)` = confirmed legitimate. Used to build ML training sets.
     pub actual_fraud: Option<bool>,
+    /// Wire format version of this record; see [`RECORD_VERSION`].
+    pub record_version: u32,
+    /// When the Logger turned the inferred transaction into this record.
+    ///
+    /// Stamped from the Logger's [`Clock`]; `None` for records that predate it.
+    pub persisted_at: Option<SystemTime>,
+    /// When the review verdict was recorded.
+    ///
+    /// Stamped by the [`Review`] adapter; `None` until reviewed.
+    pub reviewed_at: Option<SystemTime>,
+    /// Run that persisted this record (see [`Storage::begin_run`]).
+    ///
+    /// Stamped by the Logger; `None` outside a recorded run and for records
+    /// that predate it.
+    pub run_id: Option<RunId>,
 }
 
 impl PendingTransaction {
+    /// Wrap `inferred_transaction` as unreviewed, at the current [`RECORD_VERSION`].
+    #[must_use]
+    pub fn new(inferred_transaction: InferredTransaction) -> Self {
+        Self {
+            inferred_transaction,
+            is_reviewed: false,
+            actual_fraud: None,
+            record_version: RECORD_VERSION,
+            persisted_at: None,
+            reviewed_at: None,
+            run_id: None,
+        }
+    }
+
     /// Return the transaction ID, delegating through the inferred transaction.
     #[must_use]
-    pub fn id(&self) -> uuid::Uuid {
+    pub fn id(&self) -> TransactionId {
         self.inferred_transaction.id()
     }
 }
 
 /// Errors that a storage implementation may return.
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
 pub enum StorageError {
     /// Storage has reached its maximum item capacity.
-    #[error("storage capacity exceeded (capacity: {capacity})")]
+    #[error("storage capacity exceeded (capacity: {capacity}, remaining: {remaining})")]
     CapacityExceeded {
         /// Maximum number of items the storage can hold.
         capacity: usize,
+        /// Items that would still fit; a batch of at most this size succeeds.
+        remaining: usize,
     },
     /// Storage backend is unreachable or otherwise unavailable.
     #[error("storage unavailable")]
     Unavailable,
+    /// A written record read back differently from what was sent (diagnostic
+    /// read-your-writes check, see the `SQLite` adapter's verification mode).
+    #[error("write verification failed for {id}: {field} differs")]
+    VerificationFailed {
+        /// Transaction whose row does not match.
+        id: TransactionId,
+        /// First column that differs; `"id"` when the row is missing.
+        field: &'static str,
+    },
+    /// The secondary backend of a mirrored storage failed too many writes in
+    /// a row (see the `MirroredStorage` adapter's escalation policy).
+    #[error("mirror secondary failed {failures} consecutive writes")]
+    MirrorFailed {
+        /// Consecutive secondary failures, the escalating one included.
+        failures: u32,
+    },
+    /// A file-backed storage could not open, write or flush its file.
+    #[error("storage I/O error on {}: {kind}", path.display())]
+    Io {
+        /// File the operation was on.
+        path: std::path::PathBuf,
+        /// What went wrong, e.g. `PermissionDenied` while another program
+        /// holds the file.
+        kind: std::io::ErrorKind,
+    },
+    /// A record field has no representation in a file-backed storage's
+    /// format (e.g. a non-finite amount in a CSV file).
+    #[error("cannot encode {field} of {id} into {}", path.display())]
+    Encoding {
+        /// File the record was bound for.
+        path: std::path::PathBuf,
+        /// Transaction whose field cannot be encoded.
+        id: TransactionId,
+        /// The offending field.
+        field: &'static str,
+    },
+}
+
+impl StorageError {
+    /// Whether retrying the write later may succeed.
+    ///
+    /// `Unavailable` and `Io` are transient (a file may be locked by a
+    /// reader for a while); `CapacityExceeded` does not clear by retrying,
+    /// and neither does a `VerificationFailed` backend, a `MirrorFailed`
+    /// secondary or a record that fails `Encoding`.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Unavailable | Self::Io { .. })
+    }
 }
 
 /// Selectable model version for Modelizer switch commands.
@@ -87,6 +461,7 @@ pub enum ModelVersion {
 
 /// Errors from the Modelizer hexagonal port.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum ModelizerError {
     /// Inference could not be completed.
     #[error("inference failed: {reason}")]
@@ -102,8 +477,20 @@ pub enum ModelizerError {
     },
 }
 
+impl ModelizerError {
+    /// Whether retrying the operation later may succeed.
+    ///
+    /// `InferenceFailed` is treated as transient (e.g. a remote model timing
+    /// out); `SwitchFailed` means the requested version cannot be applied.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::InferenceFailed { .. })
+    }
+}
+
 /// Errors from the Alarm hexagonal port.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum AlarmError {
     /// Alarm could not be delivered.
     #[error("delivery failed: {reason}")]
@@ -113,15 +500,270 @@ pub enum AlarmError {
     },
 }
 
-/// Errors that a buffer implementation may return.
+/// Errors that a buffer read (`Buffer1Read`, `Buffer2Read`) may return.
+///
+/// A read never reports `Full`: only [`WriteError`] has that variant.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ReadError {
+    /// Buffer has been closed and drained; no further items will arrive.
+    #[error("buffer closed")]
+    Closed,
+    /// A remote or external backend behind the buffer failed (I/O error,
+    /// non-success response, timeout).
+    #[error("buffer backend failed: {0}")]
+    Backend(String),
+}
+
+impl ReadError {
+    /// Whether retrying the operation later may succeed.
+    ///
+    /// `Backend` may be (the remote end may recover); `Closed` is final.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Backend(_))
+    }
+}
+
+/// Errors that a buffer write (`Buffer1`, `Buffer2`) may return.
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
-pub enum BufferError {
-    /// Buffer has reached its maximum capacity.
-    #[error("buffer full (capacity: {capacity})")]
-    Full { capacity: usize },
+#[non_exhaustive]
+pub enum WriteError {
+    /// Buffer has reached its maximum capacity: none of the `rejected` items
+    /// of the write fit.
+    #[error("buffer full (capacity: {capacity}, rejected: {rejected})")]
+    Full { capacity: usize, rejected: usize },
     /// Buffer has been closed; no further writes are accepted.
     #[error("buffer closed")]
     Closed,
+    /// A remote or external backend behind the buffer failed (I/O error,
+    /// non-success response, timeout).
+    #[error("buffer backend failed: {0}")]
+    Backend(String),
+}
+
+impl WriteError {
+    /// Whether retrying the operation later may succeed.
+    ///
+    /// `Full` is transient (a reader may drain the buffer); `Backend` may be
+    /// (the remote end may recover); `Closed` is final.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Full { .. } | Self::Backend(_))
+    }
+}
+
+/// A builder field that failed validation.
+///
+/// Displays as `n1_max must be >= 1 (got 0)`; component errors wrap it in
+/// their `InvalidConfig` variant.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{field} {constraint} (got {value})")]
+pub struct ConfigError {
+    /// Builder field name, e.g. `n1_max`.
+    pub field: &'static str,
+    /// The rejected value, as supplied.
+    pub value: String,
+    /// The rule it broke, e.g. `must be >= 1`.
+    pub constraint: &'static str,
+}
+
+impl ConfigError {
+    /// Record that `field` was set to `value`, which breaks `constraint`.
+    #[must_use]
+    pub fn new(field: &'static str, value: impl std::fmt::Display, constraint: &'static str) -> Self {
+        Self { field, value: value.to_string(), constraint }
+    }
+
+    /// Render for a command-line front end: `--n1-max: must be >= 1 (got 0)`.
+    #[must_use]
+    pub fn cli_message(&self) -> String {
+        format!("--{}: {} (got {})", self.field.replace('_', "-"), self.constraint, self.value)
+    }
+}
+
+/// The effective settings of a stage configuration, for logs and run records.
+///
+/// Built by each config's `summary()` from explicit name/value pairs, so the
+/// rendering does not change when a config's `Debug` does. Values are kept as
+/// strings, in insertion order. `Display` gives
+/// `producer: n1_max=10 poll_interval1=100ms seed=none`; with the `serde`
+/// feature a summary serializes as a JSON object of the same pairs.
+///
+/// Secrets (tokens, passwords) must be added with [`redacted`](Self::redacted),
+/// never [`field`](Self::field), so they cannot leak into logs or storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSummary {
+    component: &'static str,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl ConfigSummary {
+    /// Placeholder shown instead of a set secret.
+    pub const REDACTED: &'static str = "<redacted>";
+
+    /// Start an empty summary of `component`, e.g. `"producer"`.
+    #[must_use]
+    pub fn new(component: &'static str) -> Self {
+        Self { component, fields: Vec::new() }
+    }
+
+    /// Record `name` with its `Display` value.
+    #[must_use]
+    pub fn field(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.fields.push((name, value.to_string()));
+        self
+    }
+
+    /// Record an optional `name`; `None` shows as `none`.
+    #[must_use]
+    pub fn optional(self, name: &'static str, value: Option<impl fmt::Display>) -> Self {
+        match value {
+            Some(value) => self.field(name, value),
+            None => self.field(name, "none"),
+        }
+    }
+
+    /// Record a duration as `100ms`, `1.5s`, ...
+    #[must_use]
+    pub fn duration(self, name: &'static str, value: Duration) -> Self {
+        self.field(name, format_args!("{value:?}"))
+    }
+
+    /// Record a secret: only whether it is set, as [`REDACTED`](Self::REDACTED)
+    /// or `none`.
+    #[must_use]
+    pub fn redacted(self, name: &'static str, is_set: bool) -> Self {
+        self.optional(name, is_set.then_some(Self::REDACTED))
+    }
+
+    /// The summarized component, e.g. `"producer"`.
+    #[must_use]
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+
+    /// The value recorded for `name`, if any.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str())
+    }
+
+    /// All name/value pairs, in insertion order.
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.fields.iter().map(|(n, v)| (*n, v.as_str()))
+    }
+}
+
+impl fmt::Display for ConfigSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.component)?;
+        for (name, value) in &self.fields {
+            write!(f, " {name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ConfigSummary {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.fields())
+    }
+}
+
+/// Named starting point for a stage configuration.
+///
+/// The Producer, Consumer and Logger configs each offer `demo()`, `stress()`
+/// and `low_latency()` constructors (and `from_preset`), returning their
+/// builder pre-populated with the batch sizes and poll intervals below. Any
+/// later builder call overrides a preset value, and the preset name is
+/// recorded in the config summary.
+///
+/// | Preset        | Producer           | Consumer           | Logger             |
+/// |---------------|--------------------|--------------------|--------------------|
+/// | `demo`        | 100, 500 ms        | 50, 25 ms          | 10, 25 ms          |
+/// | `stress`      | 1 000 fixed, 1 ms  | 1 000 fixed, 1 ms  | 1 000 fixed, 1 ms  |
+/// | `low-latency` | 10, 10 ms          | 10, 1 ms           | 10, 1 ms           |
+///
+/// (maximum batch size, `fixed` when every batch is that size; poll interval)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// Readable logs in real time: a batch every half second, what the demo
+    /// binaries run by default.
+    Demo,
+    /// Throughput testing: full 1 000-transaction batches with no real pause
+    /// between them, to find where buffers, model or storage saturate.
+    Stress,
+    /// Shortest time from production to persistence: small batches read as
+    /// soon as they land, at the cost of more iterations per transaction.
+    LowLatency,
+}
+
+impl Preset {
+    /// Every preset, in documentation order.
+    pub const ALL: [Self; 3] = [Self::Demo, Self::Stress, Self::LowLatency];
+
+    /// The name used on the command line and in summaries, e.g. `low-latency`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Demo => "demo",
+            Self::Stress => "stress",
+            Self::LowLatency => "low-latency",
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = ConfigError;
+
+    /// Parse a [`name`](Self::name).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == s)
+            .ok_or_else(|| ConfigError::new("profile", s, "must be demo, stress or low-latency"))
+    }
+}
+
+/// An error followed by its [`source`](std::error::Error::source) chain.
+///
+/// Wrapping errors display only their own context (`storage write error`), so
+/// logging one with `%e` drops the cause. `ErrorChain(&e)` displays the whole
+/// chain joined by `": "`: `storage write error: storage unavailable`.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorChain<'a>(pub &'a (dyn std::error::Error + 'static));
+
+impl<'a> ErrorChain<'a> {
+    /// The error and its sources, outermost first.
+    fn links(self) -> impl Iterator<Item = &'a (dyn std::error::Error + 'static)> {
+        std::iter::successors(Some(self.0), |e| e.source())
+    }
+
+    /// The `Display` text of each link, outermost first; its length is the chain depth.
+    #[must_use]
+    pub fn messages(self) -> Vec<String> {
+        self.links().map(ToString::to_string).collect()
+    }
+}
+
+impl fmt::Display for ErrorChain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.links().enumerate() {
+            if i > 0 {
+                f.write_str(": ")?;
+            }
+            write!(f, "{e}")?;
+        }
+        Ok(())
+    }
 }
 
 /// Hexagonal port: the write side of the first inter-component buffer.
@@ -138,15 +780,53 @@ pub trait Buffer1 {
     ///
     /// # Errors
     ///
-    /// Returns `BufferError::Full` when capacity is exceeded, or
-    /// `BufferError::Closed` when the buffer has been shut down.
-    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), BufferError>;
+    /// Returns `WriteError::Full` when capacity is exceeded, or
+    /// `WriteError::Closed` when the buffer has been shut down.
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError>;
+
+    /// Write as many transactions of `batch` as fit and return how many.
+    ///
+    /// The accepted transactions are a prefix of `batch` and are removed from
+    /// it; the rest stays in `batch` for the caller to retry. The provided
+    /// implementation is all-or-nothing: it hands the whole batch to
+    /// [`write_batch`](Self::write_batch) and returns its length. Bounded
+    /// adapters override it to accept part of a batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WriteError::Full` only when no transaction fits, or
+    /// `WriteError::Closed` when the buffer has been shut down. An overriding
+    /// adapter leaves `batch` untouched on error; the provided implementation
+    /// has already handed it to `write_batch` and leaves it empty.
+    async fn write_batch_partial(&self, batch: &mut Vec<Transaction>) -> Result<usize, WriteError> {
+        let len = batch.len();
+        self.write_batch(std::mem::take(batch)).await?;
+        Ok(len)
+    }
+
+    /// Whether the buffer is known to be closed, so any write would fail with
+    /// `WriteError::Closed`.
+    ///
+    /// Lets a writer skip preparing a batch nobody will accept. The provided
+    /// implementation returns `false`: an adapter that cannot tell is only
+    /// found closed by writing to it.
+    fn is_closed(&self) -> bool {
+        false
+    }
 }
 
 /// Hexagonal port: the read side of the first inter-component buffer.
 ///
 /// Consumer depends exclusively on this trait -- never on a concrete adapter.
-/// Implementations signal exhaustion via `BufferError::Closed`.
+/// Implementations signal exhaustion via `ReadError::Closed`.
+///
+/// # Exclusive drain
+///
+/// Every written transaction is returned by exactly one `read_batch` call,
+/// whichever reader makes it. Several Consumers may therefore share one
+/// buffer and split the work without duplicates or losses. An adapter that
+/// cannot guarantee this (e.g. one that broadcasts to every reader) must
+/// document it and must not be shared between Consumers.
 #[expect(
     async_fn_in_trait,
     reason = "no dyn dispatch needed; internal workspace only"
@@ -154,12 +834,18 @@ pub trait Buffer1 {
 pub trait Buffer1Read {
     /// Read up to `max` transactions from the buffer.
     ///
-    /// Returns between 1 and `max` transactions when data is available.
+    /// Returns between 1 and `max` transactions when data is available. The
+    /// returned transactions are removed: no other call, from this reader or
+    /// a concurrent one, returns them again.
+    ///
+    /// May return an empty `Vec` when no data arrived in time (e.g. a
+    /// channel- or HTTP-fed adapter whose read timed out). The Consumer
+    /// treats that as an empty poll: nothing is inferred or written.
     ///
     /// # Errors
     ///
-    /// Returns `BufferError::Closed` when the buffer is closed and drained.
-    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, BufferError>;
+    /// Returns `ReadError::Closed` when the buffer is closed and drained.
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError>;
 }
 
 /// Hexagonal port: the write side of the second inter-component buffer.
@@ -174,15 +860,31 @@ pub trait Buffer2 {
     ///
     /// # Errors
     ///
-    /// Returns `BufferError::Full` when capacity is exceeded, or
-    /// `BufferError::Closed` when the buffer has been shut down.
-    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), BufferError>;
+    /// Returns `WriteError::Full` when capacity is exceeded, or
+    /// `WriteError::Closed` when the buffer has been shut down.
+    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), WriteError>;
+
+    /// Write as many inferred transactions of `batch` as fit and return how
+    /// many, removing them from its front; see [`Buffer1::write_batch_partial`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `WriteError::Full` only when no item fits, or
+    /// `WriteError::Closed` when the buffer has been shut down.
+    async fn write_batch_partial(
+        &self,
+        batch: &mut Vec<InferredTransaction>,
+    ) -> Result<usize, WriteError> {
+        let len = batch.len();
+        self.write_batch(std::mem::take(batch)).await?;
+        Ok(len)
+    }
 }
 
 /// Hexagonal port: the read side of the second inter-component buffer.
 ///
 /// Logger depends exclusively on this trait -- never on a concrete adapter.
-/// Implementations signal exhaustion via `BufferError::Closed`.
+/// Implementations signal exhaustion via `ReadError::Closed`.
 #[expect(
     async_fn_in_trait,
     reason = "no dyn dispatch needed; internal workspace only"
@@ -190,17 +892,71 @@ pub trait Buffer2 {
 pub trait Buffer2Read {
     /// Read up to `max` inferred transactions from the buffer.
     ///
-    /// Returns between 1 and `max` items when data is available.
+    /// Returns between 1 and `max` items when data is available, or an empty
+    /// `Vec` when none arrived in time; the Logger then writes nothing.
     ///
     /// # Errors
     ///
-    /// Returns `BufferError::Closed` when the buffer is closed and drained.
-    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, BufferError>;
+    /// Returns `ReadError::Closed` when the buffer is closed and drained.
+    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError>;
+}
+
+/// Optional buffer capability: report how many items are currently queued.
+///
+/// Lets a reader decide whether to keep polling without blocking on an empty
+/// buffer (e.g. the Consumer's drain-and-stop command). Implemented alongside
+/// the read port by adapters that can answer cheaply.
+pub trait BufferDepth {
+    /// Number of items currently waiting to be read.
+    fn depth(&self) -> usize;
+}
+
+/// Identifier of a pipeline run, assigned by [`Storage::begin_run`].
+///
+/// `RunId(0)` is what a storage that does not record runs returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RunId(pub u64);
+
+impl fmt::Display for RunId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "run {}", self.0)
+    }
+}
+
+/// What a run records about itself when it starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunMeta {
+    /// When the run started.
+    pub started_at: SystemTime,
+    /// RNG seed per stage or model (e.g. `"producer"`); unseeded ones are absent.
+    pub seeds: BTreeMap<String, u64>,
+    /// Snapshot of the run configuration, as a JSON document.
+    pub config: String,
+}
+
+/// Final counts of a run, recorded when it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSummary {
+    /// When the run ended.
+    pub ended_at: SystemTime,
+    /// Transactions generated by the Producer.
+    pub produced: u64,
+    /// Transactions inferred by the Consumer.
+    pub inferred: u64,
+    /// Transactions among `inferred` flagged as fraud.
+    pub flagged: u64,
+    /// Transactions handed to storage by the Logger.
+    pub persisted: u64,
 }
 
 /// Hexagonal port: persistent storage for pending transactions.
 ///
 /// Logger depends exclusively on this trait -- never on a concrete adapter.
+///
+/// [`begin_run`](Self::begin_run) and [`end_run`](Self::end_run) bracket a
+/// pipeline run so records can be attributed to it (see
+/// [`PendingTransaction::run_id`]). Both default to no-ops for storages that
+/// do not record runs; decorators must forward them.
 #[expect(
     async_fn_in_trait,
     reason = "no dyn dispatch needed; internal workspace only"
@@ -213,20 +969,271 @@ pub trait Storage {
     /// Returns `StorageError::CapacityExceeded` when the store is full, or
     /// `StorageError::Unavailable` when the backend cannot be reached.
     async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError>;
+
+    /// Record the start of a run described by `meta` and return its id.
+    ///
+    /// The default records nothing and returns `RunId(0)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    async fn begin_run(&self, meta: RunMeta) -> Result<RunId, StorageError> {
+        let _ = meta;
+        Ok(RunId::default())
+    }
+
+    /// Record the end of run `id` with its final counts.
+    ///
+    /// The default records nothing. Ending an unknown run is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    async fn end_run(&self, id: RunId, summary: RunSummary) -> Result<(), StorageError> {
+        let _ = (id, summary);
+        Ok(())
+    }
 }
 
-/// Hexagonal port: per-transaction classification model.
+/// A persisted [`PendingTransaction`] with its position in storage order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredTransaction {
+    /// Storage-order position (e.g. `SQLite` rowid); increasing, starts at 1.
+    pub position: u64,
+    /// The record, with defaults for fields its `record_version` predates.
+    pub pending: PendingTransaction,
+}
+
+/// Hexagonal port: paged read access to persisted pending transactions.
 ///
-/// Implemented by concrete model adapters (e.g. `DemoModel`). The Modelizer
-/// component depends exclusively on this trait -- never on a concrete adapter.
-/// Adapters are responsible for mapping abstract `ModelVersion` to their own
-/// concrete version identifiers.
+/// Implemented alongside [`Storage`] by adapters that can read back what they
+/// persisted (reviewers, backfills, replays).
 #[expect(
     async_fn_in_trait,
     reason = "no dyn dispatch needed; internal workspace only"
 )]
-pub trait Model {
-    /// Classify a single transaction as fraudulent or legitimate.
+pub trait StorageRead {
+    /// Return up to `limit` records with `position > after`, in storage order.
+    ///
+    /// Pass `after = 0` to start from the beginning; pass the last returned
+    /// `position` to fetch the next page. An empty page means no more records.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be read.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError>;
+
+    /// Return the ids of the first `limit` records, in storage order.
+    ///
+    /// Fewer ids means the storage holds fewer records. The default pages
+    /// through [`read_page`](Self::read_page); adapters that can select the
+    /// id column alone should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be read.
+    async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+        const PAGE: usize = 1_000;
+        let mut ids = Vec::new();
+        let mut after = 0;
+        while ids.len() < limit {
+            let page = self.read_page(after, PAGE.min(limit - ids.len())).await?;
+            let Some(last) = page.last() else { break };
+            after = last.position;
+            ids.extend(page.iter().map(|stored| stored.pending.id()));
+        }
+        Ok(ids)
+    }
+}
+
+/// Review verdict for one persisted transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReviewOutcome {
+    /// Id of the reviewed transaction.
+    pub id: TransactionId,
+    /// Ground-truth label: `true` = confirmed fraud.
+    pub actual_fraud: bool,
+}
+
+/// Hexagonal port: persist review verdicts on stored pending transactions.
+///
+/// Recording an outcome sets `is_reviewed = true`, `actual_fraud` and
+/// `reviewed_at` (the adapter's current time) on the matching record.
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait Review {
+    /// Record every outcome; ids with no stored record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError>;
+}
+
+/// A transaction rejected at a port boundary, with the reason.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedTransaction {
+    /// The transaction as received.
+    pub transaction: Transaction,
+    /// The validation rule it broke, or why it was shed.
+    pub reason: InvalidTransaction,
+}
+
+/// Hexagonal port: quarantine for transactions that must not enter the pipeline.
+///
+/// Rejected transactions are kept for inspection and never inferred or persisted
+/// as pending transactions.
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait DeadLetter {
+    /// Quarantine a batch of rejected transactions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    async fn write_dead_letters(&self, batch: Vec<RejectedTransaction>) -> Result<(), StorageError>;
+}
+
+/// A transaction the Modelizer could not score, as kept by a [`Quarantine`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedTransaction {
+    /// The transaction as read from Buffer1.
+    pub transaction: Transaction,
+    /// Why it could not be scored: the inference error, displayed.
+    pub reason: String,
+    /// When it was quarantined (the adapter's clock).
+    pub quarantined_at: SystemTime,
+}
+
+/// Boxed future returned by the [`Quarantine`] methods.
+pub type QuarantineFuture<'a, T> =
+    std::pin::Pin<Box<dyn Future<Output = Result<T, StorageError>> + 'a>>;
+
+/// Hexagonal port: durable holding area for transactions inference failed on.
+///
+/// Unlike [`DeadLetter`], quarantined transactions are valid; they are kept
+/// so they can be scored again later (e.g. with `consumer::requeue`) instead
+/// of being lost with their batch. Boxed futures keep the port
+/// dyn-compatible, so a Consumer config can hold one.
+pub trait Quarantine: fmt::Debug + Send + Sync {
+    /// Keep `tx`, with the `reason` it could not be scored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    fn record<'a>(&'a self, tx: &'a Transaction, reason: &'a str) -> QuarantineFuture<'a, ()>;
+
+    /// Up to `max` quarantined transactions, oldest first, left in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    fn quarantined(&self, max: usize) -> QuarantineFuture<'_, Vec<QuarantinedTransaction>>;
+
+    /// Remove the quarantined transactions with these ids; unknown ids are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    fn release<'a>(&'a self, ids: &'a [TransactionId]) -> QuarantineFuture<'a, ()>;
+}
+
+/// A new prediction for a stored transaction, produced by a backfill run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RescoredPrediction {
+    /// Id of the stored transaction.
+    pub id: TransactionId,
+    /// Name of the model that produced the new prediction.
+    pub model_name: String,
+    /// Version of the model that produced the new prediction.
+    pub model_version: String,
+    /// New fraud prediction.
+    pub predicted_fraud: bool,
+    /// When the prediction was made.
+    pub scored_at: SystemTime,
+}
+
+/// Hexagonal port: sink for backfill predictions, with a per-job high-water
+/// mark so an interrupted backfill resumes where it stopped.
+///
+/// Positions are those of [`StoredTransaction::position`].
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait RescoreSink {
+    /// Position of the last record processed by `job`; `0` if it never ran.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be read.
+    async fn rescore_high_water(&self, job: &str) -> Result<u64, StorageError>;
+
+    /// Persist `rows` and move `job`'s high-water mark to `high_water`.
+    ///
+    /// Both happen or neither does, so a crash never records predictions
+    /// without their progress (or the reverse).
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be written.
+    async fn write_rescored(
+        &self,
+        job: &str,
+        rows: &[RescoredPrediction],
+        high_water: u64,
+    ) -> Result<(), StorageError>;
+}
+
+/// Total and flagged transaction counts for one wall-clock minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MinuteBucket {
+    /// Minutes since the Unix epoch.
+    pub minute: u64,
+    /// Transactions persisted during the minute.
+    pub total: u64,
+    /// Transactions among `total` flagged as fraud by the model.
+    pub flagged: u64,
+}
+
+/// Hexagonal port: sink for per-minute aggregate counts.
+///
+/// Buckets carry cumulative counts, so implementations must overwrite any
+/// existing entry for the same minute; flushing the same bucket twice is then
+/// idempotent.
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait BucketSink {
+    /// Insert or replace the given buckets, keyed by `minute`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    async fn upsert_buckets(&self, buckets: &[MinuteBucket]) -> Result<(), StorageError>;
+}
+
+/// Hexagonal port: per-transaction classification model.
+///
+/// Implemented by concrete model adapters (e.g. `DemoModel`). The Modelizer
+/// component depends exclusively on this trait -- never on a concrete adapter.
+/// Adapters are responsible for mapping abstract `ModelVersion` to their own
+/// concrete version identifiers.
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait Model {
+    /// Classify a single transaction as fraudulent or legitimate.
     ///
     /// # Errors
     ///
@@ -247,53 +1254,802 @@ pub trait Model {
     async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError>;
 }
 
-/// Hexagonal port: inference and version-switching for transaction classification.
-///
-/// Consumer calls `infer` once per batch and `switch_version` to change models.
-#[expect(
-    async_fn_in_trait,
-    reason = "no dyn dispatch needed; internal workspace only"
-)]
-pub trait Modelizer {
-    /// Run inference on a batch of transactions.
-    ///
-    /// Returns one `InferredTransaction` per input (same order, same count).
-    ///
-    /// # Errors
-    ///
-    /// Returns `ModelizerError::InferenceFailed` on failure.
-    async fn infer(
-        &self,
-        batch: Vec<Transaction>,
-    ) -> Result<Vec<InferredTransaction>, ModelizerError>;
-
-    /// Switch to a different model version; takes effect on the next `infer` call.
-    ///
-    /// # Errors
-    ///
-    /// Returns `ModelizerError::SwitchFailed` on failure.
-    async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError>;
+/// Engineered numeric input of a [`Model`], as produced by a [`FeatureExtractor`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Features(pub Vec<f64>);
+
+impl Features {
+    /// Feature values, in the order given by [`FeatureExtractor::names`].
+    #[must_use]
+    pub fn as_slice(&self) -> &[f64] {
+        &self.0
+    }
+}
+
+/// Hexagonal port: turns a [`Transaction`] into the [`Features`] a model scores.
+///
+/// Model adapters take an extractor at construction instead of reading
+/// transaction fields themselves, so feature engineering can change without
+/// touching the Modelizer or the Consumer. Implementations must be
+/// deterministic: the same transaction always yields the same features.
+pub trait FeatureExtractor: fmt::Debug {
+    /// Features of `tx`.
+    fn extract(&self, tx: &Transaction) -> Features;
+
+    /// Name of each feature, in [`extract`](Self::extract) order.
+    fn names(&self) -> &[&'static str];
+}
+
+/// Default [`FeatureExtractor`]: amount, `ln(1 + amount)`, last-name length in
+/// characters and a last-name hash bucket.
+///
+/// The bucket is the 64-bit FNV-1a hash of the name's UTF-8 bytes modulo the
+/// bucket count, stable across platforms and Rust releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BasicFeatures {
+    buckets: u32,
+}
+
+impl BasicFeatures {
+    /// Feature names, in extraction order.
+    pub const NAMES: [&'static str; 4] = ["amount", "log_amount", "name_len", "name_bucket"];
+
+    /// Name-hash buckets of [`BasicFeatures::default`].
+    pub const DEFAULT_BUCKETS: u32 = 16;
+
+    /// Extractor hashing last names into `buckets` buckets (`0` counts as `1`).
+    #[must_use]
+    pub fn new(buckets: u32) -> Self {
+        Self { buckets: buckets.max(1) }
+    }
+}
+
+impl Default for BasicFeatures {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_BUCKETS)
+    }
+}
+
+impl FeatureExtractor for BasicFeatures {
+    fn extract(&self, tx: &Transaction) -> Features {
+        let name_len = u32::try_from(tx.last_name.chars().count()).unwrap_or(u32::MAX);
+        // Always below `buckets`, so it fits a u32.
+        let bucket = fnv1a(tx.last_name.as_bytes()) % u64::from(self.buckets);
+        let bucket = u32::try_from(bucket).unwrap_or(u32::MAX);
+        Features(vec![tx.amount, tx.amount.ln_1p(), f64::from(name_len), f64::from(bucket)])
+    }
+
+    fn names(&self) -> &[&'static str] {
+        &Self::NAMES
+    }
+}
+
+/// 64-bit FNV-1a hash of `bytes`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(PRIME))
+}
+
+/// Hexagonal port: inference and version-switching for transaction classification.
+///
+/// Consumer calls `infer` once per batch and `switch_version` to change models.
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait Modelizer {
+    /// Run inference on a batch of transactions.
+    ///
+    /// Returns one `InferredTransaction` per input (same order, same count).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelizerError::InferenceFailed` on failure.
+    async fn infer(
+        &self,
+        batch: Vec<Transaction>,
+    ) -> Result<Vec<InferredTransaction>, ModelizerError>;
+
+    /// Switch to a different model version; takes effect on the next `infer` call.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelizerError::SwitchFailed` on failure.
+    async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError>;
+}
+
+/// Hexagonal port: per-transaction fraud alert delivery.
+///
+/// Consumer calls `trigger` once per fraudulent transaction per batch (best-effort).
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait Alarm {
+    /// Trigger a fraud alert for the given transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AlarmError::DeliveryFailed` when the alert cannot be delivered.
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError>;
+}
+
+/// Severity of a fraud alert, as recorded in the alarm audit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmSeverity {
+    /// Amount below the high-severity threshold.
+    Low,
+    /// Amount at or above the high-severity threshold.
+    High,
+}
+
+impl AlarmSeverity {
+    /// Stable lowercase name, used as the stored value: `"low"` or `"high"`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::High => "high",
+        }
+    }
+
+    /// Inverse of [`as_str`](Self::as_str); `None` for any other string.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "low" => Some(Self::Low),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// Result of one `Alarm::trigger` call, as recorded in the alarm audit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    /// The alarm accepted the alert.
+    Delivered,
+    /// The alarm returned an error.
+    Failed {
+        /// The alarm error, as displayed.
+        reason: String,
+    },
+}
+
+impl DeliveryOutcome {
+    /// Whether the alert was delivered.
+    #[must_use]
+    pub fn is_delivered(&self) -> bool {
+        matches!(self, Self::Delivered)
+    }
+}
+
+/// One audited alarm delivery attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlarmDelivery {
+    /// Id of the flagged transaction.
+    pub transaction_id: TransactionId,
+    /// Severity of the alert.
+    pub severity: AlarmSeverity,
+    /// When the delivery attempt completed.
+    pub delivered_at: SystemTime,
+    /// Whether the alarm accepted the alert.
+    pub outcome: DeliveryOutcome,
+}
+
+/// Hexagonal port: audit trail of alarm delivery attempts.
+///
+/// Records every alert actually handed to an `Alarm`, delivered or not, for
+/// compliance. Flagged transactions whose alert was never attempted (e.g.
+/// suppressed by sampling) have no record.
+#[expect(
+    async_fn_in_trait,
+    reason = "no dyn dispatch needed; internal workspace only"
+)]
+pub trait AlarmAudit {
+    /// Append one delivery attempt to the audit trail.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the backend cannot be reached.
+    async fn record_delivery(&self, delivery: &AlarmDelivery) -> Result<(), StorageError>;
+}
+
+/// Hexagonal port: exchange-rate lookup used to normalize amounts to EUR.
+///
+/// A Consumer given a converter checks every non-EUR amount in EUR, so amount
+/// thresholds mean the same thing whatever the currency; the transaction
+/// itself keeps its amount and currency. Without one, amounts are checked as
+/// they are (identity).
+pub trait CurrencyConverter: std::fmt::Debug + Send + Sync {
+    /// EUR value of one unit of `currency`, or `None` when no rate is known.
+    fn rate_to_eur(&self, currency: &Currency) -> Option<f64>;
+
+    /// `amount` of `currency` in EUR, or `None` when no rate is known.
+    ///
+    /// EUR amounts are returned unchanged without a lookup.
+    fn to_eur(&self, amount: f64, currency: &Currency) -> Option<f64> {
+        if *currency == Currency::Eur {
+            return Some(amount);
+        }
+        self.rate_to_eur(currency).map(|rate| amount * rate)
+    }
+}
+
+/// Hexagonal port: source of randomness for the pipeline stages.
+///
+/// Stages draw batch sizes and synthetic data from a `Box<dyn RngPort>`,
+/// seeded `StdRng` by default. Implemented for every `RngCore + Debug`, so a
+/// counter-based generator, or one that records or replays its draws, can
+/// be injected instead to reproduce a failing run exactly.
+pub trait RngPort: rand::RngCore + fmt::Debug {}
+
+impl<T: rand::RngCore + fmt::Debug + ?Sized> RngPort for T {}
+
+/// Hexagonal port: source of wall-clock time.
+///
+/// Components that stamp data with the current time depend on this trait so
+/// tests can inject a frozen clock. [`SystemClock`] is the production default.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// Current wall-clock time.
+    fn now(&self) -> SystemTime;
+}
+
+/// `Clock` adapter reading the operating-system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// `Clock` adapter frozen at a fixed instant (tests, reproducible runs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// Boxed future returned by [`Sleeper::sleep`].
+pub type SleepFuture<'a> = std::pin::Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// Hexagonal port: waiting between run-loop iterations.
+///
+/// Every stage sleep goes through this trait so a simulation can drive the
+/// stages on virtual time. [`TokioSleeper`] is the production default; under
+/// `tokio::time::pause()` it already follows the paused clock.
+pub trait Sleeper: std::fmt::Debug + Send + Sync {
+    /// Wait for `duration`.
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_>;
+}
+
+/// `Sleeper` adapter backed by `tokio::time::sleep`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+impl Sleeper for TokioSleeper {
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Adaptive sleep between run-loop iterations, shared by the Consumer and
+/// Logger configs.
+///
+/// After each read, [`next_sleep`](Self::next_sleep) picks the next sleep from
+/// how full the read was:
+///
+/// - a full batch (as many items as requested): no sleep, the stage is behind;
+/// - fewer than `low_watermark` items: the previous sleep times `multiplier`,
+///   starting from the configured poll interval and capped at `max_interval`;
+/// - anything in between: the configured poll interval.
+///
+/// A full batch resets the back-off. With a zero poll interval the sleep
+/// never grows past zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveInterval {
+    /// Reads returning fewer items than this back off.
+    pub low_watermark: usize,
+    /// Back-off growth factor per consecutive low read; must be >= 2.
+    pub multiplier: u32,
+    /// Upper bound on the sleep; must be > 0.
+    pub max_interval: Duration,
+}
+
+impl AdaptiveInterval {
+    /// Back off below `low_watermark`, doubling up to `max_interval`.
+    #[must_use]
+    pub fn new(low_watermark: usize, max_interval: Duration) -> Self {
+        Self { low_watermark, multiplier: 2, max_interval }
+    }
+
+    /// Check the fields; builders call this from `build`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError`] when `multiplier < 2` or `max_interval` is zero.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.multiplier < 2 {
+            return Err(ConfigError::new("multiplier", self.multiplier, "must be >= 2"));
+        }
+        if self.max_interval.is_zero() {
+            return Err(ConfigError::new(
+                "max_interval",
+                format!("{:?}", self.max_interval),
+                "must be > 0",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sleep before the next iteration.
+    ///
+    /// `base` is the configured poll interval, `previous` the last sleep, and
+    /// `read` / `requested` the size of the last read and the `max` it asked for.
+    #[must_use]
+    pub fn next_sleep(
+        &self,
+        base: Duration,
+        previous: Duration,
+        read: usize,
+        requested: usize,
+    ) -> Duration {
+        if read >= requested {
+            Duration::ZERO
+        } else if read < self.low_watermark {
+            let grown = if previous < base { base } else { previous.saturating_mul(self.multiplier) };
+            grown.min(self.max_interval)
+        } else {
+            base
+        }
+    }
+}
+
+/// `below 5 x2 up to 1s`: the low watermark, multiplier and cap.
+impl fmt::Display for AdaptiveInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "below {} x{} up to {:?}", self.low_watermark, self.multiplier, self.max_interval)
+    }
+}
+
+/// How a stage sizes each read, given its configured maximum.
+///
+/// Uniform sizes model a stage that samples the backlog; real pollers more
+/// often take everything available up to the maximum ([`AlwaysMax`]), or
+/// mostly small reads with the odd large one ([`Geometric`]).
+///
+/// [`AlwaysMax`]: Self::AlwaysMax
+/// [`Geometric`]: Self::Geometric
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BatchSizeMode {
+    /// Uniform in `[1, max]` (default; unchanged seeded output).
+    #[default]
+    Uniform,
+    /// Always `max`; a read still returns fewer when the buffer holds less.
+    AlwaysMax,
+    /// Geometric on `1, 2, ...` with success probability `p`, capped at
+    /// `max`: a mean of `1 / p` before capping.
+    Geometric {
+        /// Success probability; must be in `(0, 1]`.
+        p: f64,
+    },
+}
+
+impl BatchSizeMode {
+    /// Check the fields; builders call this from `build`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError`] on `p` when a `Geometric` probability is not in
+    /// `(0, 1]`.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match *self {
+            Self::Geometric { p } if !(p > 0.0 && p <= 1.0) => {
+                Err(ConfigError::new("p", p, "must be in (0, 1]"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Draw the size of the next read, in `[1, max]`.
+    ///
+    /// `AlwaysMax` draws nothing from `rng`; the other modes draw once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is 0; builders reject a zero maximum.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        reason = "the draw is >= 1 and capped at max before the cast"
+    )]
+    pub fn sample(self, max: usize, rng: &mut dyn RngPort) -> usize {
+        use rand::Rng as _;
+
+        assert!(max > 0, "batch size maximum must be >= 1");
+        match self {
+            Self::Uniform => rng.random_range(1..=max),
+            Self::AlwaysMax => max,
+            Self::Geometric { p } => {
+                // Inverse CDF. ln_1p keeps the divisor non-zero for tiny p;
+                // with p = 1 it is -inf and every draw is 1.
+                let u: f64 = rng.random();
+                let k = ((-u).ln_1p() / (-p).ln_1p()).floor() + 1.0;
+                k.min(max as f64) as usize
+            }
+        }
+    }
+}
+
+/// `uniform`, `always max` or `geometric p 0.5`.
+impl fmt::Display for BatchSizeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uniform => f.write_str("uniform"),
+            Self::AlwaysMax => f.write_str("always max"),
+            Self::Geometric { p } => write!(f, "geometric p {p}"),
+        }
+    }
+}
+
+/// The stage settings that may change while the pipeline runs.
+///
+/// The binary publishes them on a `tokio::sync::watch` channel; a stage given
+/// the [`SettingsReceiver`] (see the `settings` option of each config
+/// builder) reads the latest value at the top of each run-loop iteration,
+/// through a [`LiveSettings`], and uses only the fields it owns. Everything
+/// else in a config is fixed for the run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DynamicSettings {
+    /// Producer sleep between iterations.
+    pub poll_interval1: Duration,
+    /// Consumer sleep between iterations (the base of an adaptive sleep).
+    pub poll_interval2: Duration,
+    /// Logger sleep between iterations (the base of an adaptive sleep).
+    pub poll_interval3: Duration,
+    /// How the Consumer and Logger size each read, unless their batch size
+    /// is fixed.
+    pub batch_size_mode: BatchSizeMode,
+    /// Alarm deliveries taking longer than this are logged as slow. `None`
+    /// never warns.
+    pub slow_alarm_threshold: Option<Duration>,
+}
+
+/// The config builders' defaults: 100 ms intervals, uniform reads, no slow
+/// alarm threshold.
+impl Default for DynamicSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval1: Duration::from_millis(100),
+            poll_interval2: Duration::from_millis(100),
+            poll_interval3: Duration::from_millis(100),
+            batch_size_mode: BatchSizeMode::Uniform,
+            slow_alarm_threshold: None,
+        }
+    }
+}
+
+impl DynamicSettings {
+    /// Check the fields, as the config builders do.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError`] when `batch_size_mode` is invalid.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.batch_size_mode.validate()
+    }
+}
+
+/// Receiving end of the [`DynamicSettings`] channel.
+pub type SettingsReceiver = tokio::sync::watch::Receiver<DynamicSettings>;
+
+/// The latest valid [`DynamicSettings`] published on a channel.
+///
+/// A published value that fails [`DynamicSettings::validate`] is rejected:
+/// [`refresh`](Self::refresh) reports it, once, and the previous settings
+/// stay in force. Once the sender is dropped the last accepted settings hold
+/// for the rest of the run.
+#[derive(Debug)]
+pub struct LiveSettings {
+    rx: std::cell::RefCell<SettingsReceiver>,
+    accepted: std::cell::Cell<DynamicSettings>,
+}
+
+impl LiveSettings {
+    /// Start from `seed`, the stage's configured values, and follow `rx`:
+    /// the first [`refresh`](Self::refresh) adopts its current value. Without
+    /// `rx`, follow a channel nobody publishes on: `seed` holds for the run.
+    #[must_use]
+    pub fn new(seed: DynamicSettings, rx: Option<SettingsReceiver>) -> Self {
+        let rx = rx.map_or_else(
+            || tokio::sync::watch::channel(seed).1,
+            |mut rx| {
+                rx.mark_changed();
+                rx
+            },
+        );
+        Self {
+            rx: std::cell::RefCell::new(rx),
+            accepted: std::cell::Cell::new(seed),
+        }
+    }
+
+    /// Adopt the latest published settings if they changed since the last
+    /// call; returns whether the settings in force changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError`] when the new settings are invalid; they are
+    /// discarded and [`get`](Self::get) keeps returning the previous ones.
+    pub fn refresh(&self) -> Result<bool, ConfigError> {
+        let mut rx = self.rx.borrow_mut();
+        let latest = rx.borrow_and_update();
+        if !latest.has_changed() {
+            return Ok(false);
+        }
+        let latest = *latest;
+        latest.validate()?;
+        let changed = latest != self.accepted.get();
+        self.accepted.set(latest);
+        Ok(changed)
+    }
+
+    /// The settings in force.
+    #[must_use]
+    pub fn get(&self) -> DynamicSettings {
+        self.accepted.get()
+    }
+}
+
+/// Run-loop sleep counters of a stage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacingStats {
+    /// Sleeps taken between iterations.
+    pub sleeps: u64,
+    /// Sum of every sleep.
+    pub total: Duration,
+    /// Most recent sleep; `Duration::ZERO` before the first one.
+    pub last: Duration,
+}
+
+impl PacingStats {
+    /// Account for one sleep of `sleep`.
+    pub fn record(&mut self, sleep: Duration) {
+        self.sleeps += 1;
+        self.total = self.total.saturating_add(sleep);
+        self.last = sleep;
+    }
+}
+
+/// Order-insensitive digest of a transaction stream, to check that what one
+/// stage wrote is what another persisted, beyond counting.
+///
+/// Each transaction is hashed from its id and its amount in whole cents (see
+/// [`Transaction::digest_hash`]); the digest keeps the count, the wrapping
+/// sum and the XOR of those hashes, so the order and the batching of the
+/// stream do not matter. Amounts converted along the way (e.g. by a currency
+/// converter) hash differently.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamDigest {
+    count: u64,
+    sum: u64,
+    xor: u64,
+}
+
+impl StreamDigest {
+    /// Fold `tx` into the digest.
+    pub fn add(&mut self, tx: &Transaction) {
+        self.add_hash(tx.digest_hash());
+    }
+
+    /// Fold a transaction by its precomputed [`Transaction::digest_hash`],
+    /// e.g. one taken before the transaction was handed over.
+    pub fn add_hash(&mut self, hash: u64) {
+        self.count += 1;
+        self.sum = self.sum.wrapping_add(hash);
+        self.xor ^= hash;
+    }
+
+    /// Number of transactions folded in.
+    #[must_use]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Compare this digest, taken upstream, with `downstream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DigestMismatch`] when the two streams differ.
+    pub fn check(&self, downstream: &Self) -> Result<(), DigestMismatch> {
+        if self == downstream {
+            Ok(())
+        } else {
+            Err(DigestMismatch { upstream: *self, downstream: *downstream })
+        }
+    }
+}
+
+impl<'a> Extend<&'a Transaction> for StreamDigest {
+    fn extend<I: IntoIterator<Item = &'a Transaction>>(&mut self, iter: I) {
+        for tx in iter {
+            self.add(tx);
+        }
+    }
+}
+
+/// Displays as `<count> tx <sum><xor>` in hex, e.g. `3 tx 0123...cdef`.
+impl fmt::Display for StreamDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} tx {:016x}{:016x}", self.count, self.sum, self.xor)
+    }
+}
+
+/// Two [`StreamDigest`]s of what should be the same stream differ.
+///
+/// `Display` names the count difference, e.g. `digest mismatch: 10
+/// upstream, 9 downstream (1 missing)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("digest mismatch: {} upstream, {} downstream ({})", upstream.count, downstream.count, self.difference())]
+pub struct DigestMismatch {
+    /// Digest of the stream as written.
+    pub upstream: StreamDigest,
+    /// Digest of the stream as received.
+    pub downstream: StreamDigest,
+}
+
+impl DigestMismatch {
+    /// The count difference in words: `n missing`, `n extra`, or `same count`
+    /// when only the content differs.
+    fn difference(&self) -> String {
+        let (up, down) = (self.upstream.count, self.downstream.count);
+        match up.cmp(&down) {
+            std::cmp::Ordering::Greater => format!("{} missing", up - down),
+            std::cmp::Ordering::Less => format!("{} extra", down - up),
+            std::cmp::Ordering::Equal => "same count, different content".to_owned(),
+        }
+    }
+}
+
+/// Pipeline stage identifier carried by [`PipelineEvent::StageStopped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// The Producer stage.
+    Producer,
+    /// The Consumer stage.
+    Consumer,
+    /// The Logger stage.
+    Logger,
+}
+
+/// Why a stage's run loop returned successfully.
+///
+/// `iterations` counts the batches the loop completed before stopping. Its
+/// [`label`](Self::label) is the `reason` of the matching
+/// [`PipelineEvent::StageStopped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The input buffer reported `Closed` (the Producer: its output buffer).
+    BufferClosed {
+        /// Batches completed.
+        iterations: u64,
+    },
+    /// The configured `iterations` limit was reached.
+    IterationLimit {
+        /// Batches completed.
+        iterations: u64,
+    },
+    /// Stopped on request, e.g. after the Consumer's `DrainAndStop` command.
+    Cancelled {
+        /// Batches completed.
+        iterations: u64,
+    },
+    /// The Producer streamed its whole pre-generated dataset.
+    Exhausted {
+        /// Batches completed.
+        iterations: u64,
+    },
+}
+
+impl StopReason {
+    /// Batches completed before the loop stopped.
+    #[must_use]
+    pub fn iterations(self) -> u64 {
+        match self {
+            Self::BufferClosed { iterations }
+            | Self::IterationLimit { iterations }
+            | Self::Cancelled { iterations }
+            | Self::Exhausted { iterations } => iterations,
+        }
+    }
+
+    /// Short description without the count, e.g. `buffer closed`.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::BufferClosed { .. } => "buffer closed",
+            Self::IterationLimit { .. } => "iteration limit reached",
+            Self::Cancelled { .. } => "cancelled",
+            Self::Exhausted { .. } => "dataset exhausted",
+        }
+    }
+}
+
+impl std::fmt::Display for StopReason {
+    /// `<label> after <n> iterations`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} after {} iterations", self.label(), self.iterations())
+    }
+}
+
+/// Structured progress event published by pipeline stages for observers
+/// (dashboards, TUIs) as an alternative to parsing logs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineEvent {
+    /// Producer wrote a batch to Buffer1.
+    BatchProduced {
+        /// Number of transactions in the batch.
+        size: usize,
+    },
+    /// Consumer inferred a batch.
+    BatchInferred {
+        /// Number of transactions in the batch.
+        size: usize,
+        /// Number of them predicted fraudulent.
+        flagged: usize,
+        /// Model version reported on the inferred transactions.
+        version: String,
+    },
+    /// Consumer read a batch and kept none of it: every transaction was
+    /// rejected by validation or quarantined by the Modelizer.
+    BatchDiscarded {
+        /// Number of transactions in the batch.
+        size: usize,
+    },
+    /// An alarm was delivered for a fraudulent transaction.
+    AlarmTriggered {
+        /// Id of the flagged transaction.
+        id: TransactionId,
+        /// Its amount.
+        amount: f64,
+    },
+    /// An alarm could not be delivered for a fraudulent transaction.
+    AlarmFailed {
+        /// Id of the transaction whose alarm failed.
+        id: TransactionId,
+    },
+    /// Logger persisted a batch to storage.
+    BatchPersisted {
+        /// Number of transactions in the batch.
+        size: usize,
+    },
+    /// A stage's run loop returned.
+    StageStopped {
+        /// Stage that stopped.
+        stage: Stage,
+        /// Why it stopped: a [`StopReason::label`], or the error message.
+        reason: String,
+    },
 }
 
-/// Hexagonal port: per-transaction fraud alert delivery.
+/// Broadcast sender through which stages publish [`PipelineEvent`]s.
 ///
-/// Consumer calls `trigger` once per fraudulent transaction per batch (best-effort).
-#[expect(
-    async_fn_in_trait,
-    reason = "no dyn dispatch needed; internal workspace only"
-)]
-pub trait Alarm {
-    /// Trigger a fraud alert for the given transaction.
-    ///
-    /// # Errors
-    ///
-    /// Returns `AlarmError::DeliveryFailed` when the alert cannot be delivered.
-    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError>;
-}
+/// Stages only call the non-blocking `send` and ignore its result: having no
+/// subscriber is normal, and a lagging receiver loses events, never the pipeline.
+pub type EventSender = tokio::sync::broadcast::Sender<PipelineEvent>;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng as _, SeedableRng as _};
     use std::cell::RefCell;
 
     // ------------------------------------------------------------------
@@ -304,24 +2060,117 @@ mod tests {
     // 42.00 is an exact integer-valued f64 literal; assert_eq! is intentional.
     #[expect(clippy::float_cmp, reason = "exact integer-valued literal")]
     fn transaction_fields() {
-        let id = uuid::Uuid::new_v4();
+        let id = TransactionId::new_v4();
         let tx = Transaction {
             id,
             amount: 42.00_f64,
             last_name: "Smith".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
         };
         assert_eq!(tx.id, id);
         assert_eq!(tx.amount, 42.00_f64);
         assert_eq!(tx.last_name, "Smith");
     }
 
+    #[test]
+    fn transaction_id_displays_a_short_prefix_and_parses_the_full_form() {
+        let uuid = uuid::Uuid::parse_str("3f2a9c1e-5b7d-4e2f-9a01-0123456789ab").unwrap();
+        let id = TransactionId::from_uuid(uuid);
+
+        assert_eq!(id.to_string(), "3f2a9c1e");
+        assert_eq!(format!("{id:#}"), "3f2a9c1e-5b7d-4e2f-9a01-0123456789ab");
+        assert_eq!(id.full(), format!("{id:#}"));
+        assert_eq!(id.full().parse::<TransactionId>(), Ok(id));
+        assert_eq!("3F2A9C1E5B7D4E2F9A010123456789AB".parse::<TransactionId>(), Ok(id));
+        let err = "3f2a9c1e".parse::<TransactionId>().unwrap_err();
+        let messages = ErrorChain(&err).messages();
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert_eq!(messages[0], "invalid transaction id");
+    }
+
+    #[test]
+    fn transaction_id_orders_by_uuid_bytes() {
+        let id = |n| TransactionId::from_uuid(uuid::Uuid::from_u128(n));
+        let mut ids = vec![id(3), id(1), id(2)];
+        ids.sort();
+        assert_eq!(ids, [id(1), id(2), id(3)]);
+        assert_ne!(TransactionId::new_v4(), TransactionId::new_v4());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn transaction_id_serializes_as_the_full_string() {
+        let id = TransactionId::new_v4();
+
+        let json = serde_json::to_string(&id).unwrap();
+
+        assert_eq!(json, format!("\"{}\"", id.full()));
+        assert_eq!(serde_json::from_str::<TransactionId>(&json).unwrap(), id);
+        let invalid = serde_json::from_str::<TransactionId>("\"not-an-id\"");
+        invalid.unwrap_err();
+    }
+
+    #[test]
+    fn currency_round_trips_through_its_code() {
+        let other = Currency::Other("JPY".to_owned());
+        for currency in [Currency::Eur, Currency::Usd, Currency::Gbp, other.clone()] {
+            assert_eq!(currency.to_string().parse::<Currency>(), Ok(currency));
+        }
+        assert_eq!(other.code(), "JPY");
+        assert_eq!(Currency::default(), Currency::Eur);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn other_currency_serializes_as_its_code() {
+        let other = Currency::Other("CHF".to_owned());
+
+        let json = serde_json::to_string(&other).unwrap();
+
+        assert_eq!(json, "\"CHF\"");
+        assert_eq!(serde_json::from_str::<Currency>(&json).unwrap(), other);
+        assert_eq!(serde_json::from_str::<Currency>("\"USD\"").unwrap(), Currency::Usd);
+    }
+
+    #[derive(Debug)]
+    struct FixedRates;
+
+    impl CurrencyConverter for FixedRates {
+        fn rate_to_eur(&self, currency: &Currency) -> Option<f64> {
+            match currency {
+                Currency::Usd => Some(0.5),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn converter_passes_eur_through_and_scales_known_rates() {
+        assert_eq!(FixedRates.to_eur(10.0, &Currency::Eur), Some(10.0));
+        assert_eq!(FixedRates.to_eur(10.0, &Currency::Usd), Some(5.0));
+        assert_eq!(FixedRates.to_eur(10.0, &Currency::Gbp), None);
+    }
+
+    #[test]
+    fn alarm_severity_round_trips_through_its_name() {
+        for severity in [AlarmSeverity::Low, AlarmSeverity::High] {
+            assert_eq!(AlarmSeverity::parse(severity.as_str()), Some(severity));
+        }
+        assert_eq!(AlarmSeverity::parse("critical"), None);
+        assert!(DeliveryOutcome::Delivered.is_delivered());
+        assert!(!DeliveryOutcome::Failed { reason: "down".to_owned() }.is_delivered());
+    }
+
     #[test]
     fn buffer_error_variants() {
-        let full = BufferError::Full { capacity: 10 };
-        let closed = BufferError::Closed;
-        assert_eq!(full, BufferError::Full { capacity: 10 });
-        assert_eq!(closed, BufferError::Closed);
+        let full = WriteError::Full { capacity: 10, rejected: 3 };
+        let closed = WriteError::Closed;
+        assert_eq!(full, WriteError::Full { capacity: 10, rejected: 3 });
+        assert_eq!(full.to_string(), "buffer full (capacity: 10, rejected: 3)");
+        assert_eq!(closed, WriteError::Closed);
         assert_ne!(full, closed);
+        assert_eq!(ReadError::Closed.to_string(), closed.to_string());
     }
 
     /// Verify that a minimal `Buffer1` implementation stores transactions correctly.
@@ -332,7 +2181,7 @@ mod tests {
         }
 
         impl Buffer1 for TestBuffer {
-            async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), BufferError> {
+            async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
                 self.inner.borrow_mut().extend(batch);
                 Ok(())
             }
@@ -342,13 +2191,23 @@ mod tests {
             inner: RefCell::new(vec![]),
         };
         let tx = Transaction {
-            id: uuid::Uuid::new_v4(),
+            id: TransactionId::new_v4(),
             amount: 1.00_f64,
             last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
         };
         buf.write_batch(vec![tx.clone()]).await.unwrap();
         assert_eq!(buf.inner.borrow().len(), 1);
         assert_eq!(buf.inner.borrow()[0], tx);
+
+        // The provided write_batch_partial writes the whole batch through write_batch.
+        let mut batch = vec![tx.clone(), tx.clone()];
+        assert_eq!(buf.write_batch_partial(&mut batch).await, Ok(2));
+        assert!(batch.is_empty());
+        assert_eq!(buf.inner.borrow().len(), 3);
+        // Without the capability, a buffer is never known to be closed.
+        assert!(!buf.is_closed());
     }
 
     // ------------------------------------------------------------------
@@ -357,13 +2216,21 @@ mod tests {
 
     #[test]
     fn inferred_transaction_fields() {
-        let id = uuid::Uuid::new_v4();
-        let tx = Transaction { id, amount: 99.99_f64, last_name: "Dupont".to_owned() };
+        let id = TransactionId::new_v4();
+        let tx = Transaction {
+            id,
+            amount: 99.99_f64,
+            last_name: "Dupont".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
         let inferred = InferredTransaction {
             transaction: tx.clone(),
             predicted_fraud: true,
             model_name: "DINN".to_owned(),
             model_version: "v1".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
         };
         assert_eq!(inferred.id(), tx.id);
         assert!(inferred.predicted_fraud);
@@ -410,11 +2277,11 @@ mod tests {
                 Ok(false)
             }
 
-            fn name(&self) -> &str {
+            fn name(&self) -> &'static str {
                 "minimal"
             }
 
-            fn active_version(&self) -> &str {
+            fn active_version(&self) -> &'static str {
                 "0"
             }
 
@@ -425,9 +2292,11 @@ mod tests {
 
         let m = MinimalModel;
         let tx = Transaction {
-            id: uuid::Uuid::new_v4(),
+            id: TransactionId::new_v4(),
             amount: 1.00_f64,
             last_name: "T".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
         };
         let fraud = m.classify(&tx).await.unwrap();
         assert!(!fraud);
@@ -442,50 +2311,234 @@ mod tests {
 
     #[test]
     fn pending_transaction_fields() {
-        let id = uuid::Uuid::new_v4();
-        let tx = Transaction { id, amount: 10.00_f64, last_name: "Durand".to_owned() };
+        let id = TransactionId::new_v4();
+        let tx = Transaction {
+            id,
+            amount: 10.00_f64,
+            last_name: "Durand".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
         let inferred = InferredTransaction {
             transaction: tx,
             predicted_fraud: true,
             model_name: "DEMO".to_owned(),
             model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
         };
-        let pending = PendingTransaction {
-            inferred_transaction: inferred.clone(),
-            is_reviewed: false,
-            actual_fraud: None,
-        };
+        let pending = PendingTransaction::new(inferred.clone());
         // id() delegates through inferred_transaction.id().
         assert_eq!(pending.id(), id);
         assert!(!pending.is_reviewed);
         assert!(pending.actual_fraud.is_none());
+        assert_eq!(pending.record_version, RECORD_VERSION);
+        assert_eq!((pending.persisted_at, pending.reviewed_at), (None, None));
+        assert_eq!(pending.run_id, None);
         assert_eq!(pending.inferred_transaction, inferred);
     }
 
     #[test]
     fn pending_transaction_clone_and_eq() {
-        let id = uuid::Uuid::new_v4();
-        let tx = Transaction { id, amount: 1.00_f64, last_name: "A".to_owned() };
+        let id = TransactionId::new_v4();
+        let tx = Transaction {
+            id,
+            amount: 1.00_f64,
+            last_name: "A".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
         let inferred = InferredTransaction {
             transaction: tx,
             predicted_fraud: false,
             model_name: "M".to_owned(),
             model_version: "1".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
         };
-        let p1 = PendingTransaction { inferred_transaction: inferred, is_reviewed: false, actual_fraud: None };
+        let p1 = PendingTransaction::new(inferred);
         let p2 = p1.clone();
         assert_eq!(p1, p2);
     }
 
+    #[tokio::test]
+    async fn storage_run_lifecycle_defaults_to_no_ops() {
+        struct Sink;
+
+        impl Storage for Sink {
+            async fn write_batch(&self, _: Vec<PendingTransaction>) -> Result<(), StorageError> {
+                Ok(())
+            }
+        }
+
+        let meta = RunMeta {
+            started_at: SystemTime::UNIX_EPOCH,
+            seeds: BTreeMap::new(),
+            config: "{}".to_owned(),
+        };
+        let summary = RunSummary {
+            ended_at: SystemTime::UNIX_EPOCH,
+            produced: 1,
+            inferred: 1,
+            flagged: 0,
+            persisted: 1,
+        };
+
+        assert_eq!(Sink.begin_run(meta).await, Ok(RunId(0)));
+        assert_eq!(Sink.end_run(RunId(0), summary).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn storage_read_all_ids_pages_through_read_page() {
+        struct Pages(Vec<PendingTransaction>);
+
+        impl StorageRead for Pages {
+            async fn read_page(
+                &self,
+                after: u64,
+                limit: usize,
+            ) -> Result<Vec<StoredTransaction>, StorageError> {
+                let skip = usize::try_from(after).unwrap();
+                let page = self.0.iter().zip(1u64..).skip(skip).take(limit);
+                let stored = |(p, position): (&PendingTransaction, u64)| StoredTransaction {
+                    position,
+                    pending: p.clone(),
+                };
+                Ok(page.map(stored).collect())
+            }
+        }
+
+        let pending = |n: u128| {
+            let id = TransactionId::from_uuid(uuid::Uuid::from_u128(n));
+            let tx = Transaction {
+                id,
+                amount: 1.00_f64,
+                last_name: "A".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            };
+            PendingTransaction::new(InferredTransaction {
+                transaction: tx,
+                predicted_fraud: false,
+                model_name: "M".to_owned(),
+                model_version: "1".to_owned(),
+                delivery_attempts: 1,
+                cached: false,
+            })
+        };
+        let storage = Pages((1..=2_500).map(pending).collect());
+
+        let ids = storage.all_ids(2_001).await.unwrap();
+        assert_eq!(ids.len(), 2_001);
+        assert_eq!((ids[0], ids[2_000]), (pending(1).id(), pending(2_001).id()));
+        assert_eq!(storage.all_ids(10_000).await.unwrap().len(), 2_500);
+        assert!(storage.all_ids(0).await.unwrap().is_empty());
+    }
+
+    // ------------------------------------------------------------------
+    // AdaptiveInterval
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn adaptive_interval_backs_off_and_resets() {
+        let ms = Duration::from_millis;
+        let a = AdaptiveInterval::new(3, ms(10));
+        let base = ms(1);
+        // Full batch: no sleep, whatever came before.
+        assert_eq!(a.next_sleep(base, ms(10), 8, 8), Duration::ZERO);
+        // Low reads: base, then doubling up to the cap.
+        let mut sleep = Duration::ZERO;
+        let mut seen = vec![];
+        for _ in 0..6 {
+            sleep = a.next_sleep(base, sleep, 1, 8);
+            seen.push(sleep);
+        }
+        assert_eq!(seen, [ms(1), ms(2), ms(4), ms(8), ms(10), ms(10)]);
+        // In between: the configured interval.
+        assert_eq!(a.next_sleep(base, ms(10), 5, 8), base);
+        assert_eq!(a.validate(), Ok(()));
+        assert_eq!(AdaptiveInterval { multiplier: 1, ..a }.validate().unwrap_err().field, "multiplier");
+    }
+
+    // ------------------------------------------------------------------
+    // BatchSizeMode
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn stage_policies_display() {
+        let adaptive = AdaptiveInterval::new(5, Duration::from_secs(1));
+        assert_eq!(adaptive.to_string(), "below 5 x2 up to 1s");
+        assert_eq!(BatchSizeMode::Uniform.to_string(), "uniform");
+        assert_eq!(BatchSizeMode::AlwaysMax.to_string(), "always max");
+        assert_eq!(BatchSizeMode::Geometric { p: 0.5 }.to_string(), "geometric p 0.5");
+    }
+
+    fn draws(mode: BatchSizeMode, max: usize, seed: u64) -> Vec<usize> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..200).map(|_| mode.sample(max, &mut rng)).collect()
+    }
+
+    #[test]
+    fn uniform_batch_sizes_match_the_plain_range_draws() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let expected: Vec<usize> = (0..200).map(|_| rng.random_range(1..=50)).collect();
+        assert_eq!(draws(BatchSizeMode::Uniform, 50, 7), expected);
+    }
+
+    #[test]
+    fn always_max_batch_sizes_are_max_and_draw_nothing() {
+        assert_eq!(draws(BatchSizeMode::AlwaysMax, 50, 7), [50; 200]);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut reference = StdRng::seed_from_u64(7);
+        BatchSizeMode::AlwaysMax.sample(50, &mut rng);
+        assert_eq!(rng.random::<u64>(), reference.random::<u64>());
+    }
+
+    #[test]
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "reference draw is >= 1 and capped at 50"
+    )]
+    fn geometric_batch_sizes_follow_the_inverse_cdf() {
+        let mode = BatchSizeMode::Geometric { p: 0.25 };
+        let mut rng = StdRng::seed_from_u64(7);
+        let expected: Vec<usize> = (0..200)
+            .map(|_| {
+                let u: f64 = rng.random();
+                let k = ((-u).ln_1p() / (-0.25_f64).ln_1p()).floor() + 1.0;
+                k.min(50.0) as usize
+            })
+            .collect();
+        let sizes = draws(mode, 50, 7);
+        assert_eq!(sizes, expected);
+        assert_eq!(draws(mode, 50, 7), sizes, "seeded draws repeat");
+        // Mostly small: with p = 1/4 about 3/4 of the reads are <= 5.
+        let small = sizes.iter().filter(|&&n| n <= 5).count();
+        assert!(small > 120, "{small} small reads out of 200");
+        assert_eq!(draws(BatchSizeMode::Geometric { p: 1.0 }, 50, 7), [1; 200]);
+        assert!(draws(BatchSizeMode::Geometric { p: 1e-300 }, 50, 7).iter().all(|&n| n == 50));
+    }
+
+    #[test]
+    fn geometric_p_must_be_in_zero_one() {
+        for p in [0.0, -0.5, 1.5, f64::NAN] {
+            let e = BatchSizeMode::Geometric { p }.validate().unwrap_err();
+            assert_eq!(e.field, "p");
+        }
+        assert_eq!(BatchSizeMode::Geometric { p: 1.0 }.validate(), Ok(()));
+        assert_eq!(BatchSizeMode::Uniform.validate(), Ok(()));
+    }
+
     // ------------------------------------------------------------------
     // T010: StorageError tests
     // ------------------------------------------------------------------
 
     #[test]
     fn storage_error_capacity_exceeded() {
-        let e = StorageError::CapacityExceeded { capacity: 42 };
-        assert_eq!(e, StorageError::CapacityExceeded { capacity: 42 });
-        assert!(e.to_string().contains("42"));
+        let e = StorageError::CapacityExceeded { capacity: 42, remaining: 5 };
+        assert_eq!(e, StorageError::CapacityExceeded { capacity: 42, remaining: 5 });
+        assert_eq!(e.to_string(), "storage capacity exceeded (capacity: 42, remaining: 5)");
     }
 
     #[test]
@@ -495,10 +2548,38 @@ mod tests {
         assert!(!e.to_string().is_empty());
     }
 
+    #[test]
+    fn storage_error_verification_failed_names_the_field() {
+        let id: TransactionId = "3f2a9c1e-5b7d-4e2f-9a01-0123456789ab".parse().unwrap();
+        let e = StorageError::VerificationFailed { id, field: "amount" };
+        assert_eq!(e.to_string(), format!("write verification failed for {id}: amount differs"));
+        assert!(!e.is_retryable());
+    }
+
+    #[test]
+    fn storage_error_mirror_failed_is_not_retryable() {
+        let e = StorageError::MirrorFailed { failures: 3 };
+        assert_eq!(e.to_string(), "mirror secondary failed 3 consecutive writes");
+        assert!(!e.is_retryable());
+    }
+
+    #[test]
+    fn storage_error_io_and_encoding_name_the_path() {
+        let path = std::path::PathBuf::from("out.csv");
+        let e = StorageError::Io { path: path.clone(), kind: std::io::ErrorKind::PermissionDenied };
+        assert_eq!(e.to_string(), "storage I/O error on out.csv: permission denied");
+        assert!(e.is_retryable());
+
+        let id: TransactionId = "3f2a9c1e-5b7d-4e2f-9a01-0123456789ab".parse().unwrap();
+        let e = StorageError::Encoding { path, id, field: "amount" };
+        assert_eq!(e.to_string(), format!("cannot encode amount of {id} into out.csv"));
+        assert!(!e.is_retryable());
+    }
+
     #[test]
     fn storage_error_variants_differ() {
         assert_ne!(
-            StorageError::CapacityExceeded { capacity: 0 },
+            StorageError::CapacityExceeded { capacity: 0, remaining: 0 },
             StorageError::Unavailable
         );
     }
@@ -509,7 +2590,7 @@ mod tests {
         struct AllPorts;
 
         impl Buffer1Read for AllPorts {
-            async fn read_batch(&self, _max: usize) -> Result<Vec<Transaction>, BufferError> {
+            async fn read_batch(&self, _max: usize) -> Result<Vec<Transaction>, ReadError> {
                 Ok(vec![])
             }
         }
@@ -518,7 +2599,7 @@ mod tests {
             async fn write_batch(
                 &self,
                 _batch: Vec<InferredTransaction>,
-            ) -> Result<(), BufferError> {
+            ) -> Result<(), WriteError> {
                 Ok(())
             }
         }
@@ -534,7 +2615,9 @@ mod tests {
                         predicted_fraud: false,
                         model_name: "test".to_owned(),
                         model_version: "v0".to_owned(),
+                        delivery_attempts: 1,
                         transaction: tx,
+                        cached: false,
                     })
                     .collect())
             }
@@ -565,14 +2648,313 @@ mod tests {
         ports.switch_version(ModelVersion::N).await.unwrap();
         let tx_for_alarm = InferredTransaction {
             transaction: Transaction {
-                id: uuid::Uuid::new_v4(),
+                id: TransactionId::new_v4(),
                 amount: 1.0_f64,
                 last_name: "T".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
             },
             predicted_fraud: true,
             model_name: "t".to_owned(),
             model_version: "v0".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
         };
         ports.trigger(&tx_for_alarm).await.unwrap();
     }
+
+    // ------------------------------------------------------------------
+    // FeatureExtractor port
+    // ------------------------------------------------------------------
+
+    fn tx_named(amount: f64, last_name: &str) -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount,
+            last_name: last_name.to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    #[test]
+    // float_cmp may or may not see through the Vec/array comparison.
+    #[allow(clippy::float_cmp, reason = "exact integer-valued features")]
+    fn basic_features_maps_known_transactions_exactly() {
+        let basic = BasicFeatures::default();
+
+        assert_eq!(basic.extract(&tx_named(0.0, "Smith")).0, [0.0, 0.0, 5.0, 0.0]);
+        assert_eq!(basic.extract(&tx_named(0.0, "Dupont")).0, [0.0, 0.0, 6.0, 1.0]);
+        assert_eq!(basic.names(), ["amount", "log_amount", "name_len", "name_bucket"]);
+    }
+
+    #[test]
+    #[expect(clippy::float_cmp, reason = "exact integer-valued features")]
+    fn basic_features_amount_and_log_amount() {
+        let features = BasicFeatures::default().extract(&tx_named(100.0, "Smith"));
+
+        assert_eq!(features.as_slice()[0], 100.0);
+        assert!((features.as_slice()[1] - 101.0_f64.ln()).abs() < 1e-12, "{features:?}");
+    }
+
+    #[test]
+    #[expect(clippy::float_cmp, reason = "exact integer-valued features")]
+    fn basic_features_name_length_counts_characters() {
+        // 6 characters, 7 UTF-8 bytes.
+        let features = BasicFeatures::default().extract(&tx_named(1.0, "Müller"));
+
+        assert_eq!(features.as_slice()[2], 6.0);
+    }
+
+    #[test]
+    #[expect(clippy::float_cmp, reason = "exact integer-valued features")]
+    fn basic_features_name_bucket_is_fnv1a_modulo_buckets() {
+        let bucket = |buckets, name| BasicFeatures::new(buckets).extract(&tx_named(1.0, name)).0[3];
+
+        // FNV-1a("Müller") = 9_502_709_030_245_728_220 = 12 (mod 16).
+        assert_eq!(bucket(16, "Müller"), 12.0);
+        // The empty name hashes to the FNV offset basis, 5 (mod 16).
+        assert_eq!(bucket(16, ""), 5.0);
+        assert_eq!(bucket(0, "Müller"), 0.0);
+    }
+
+    #[test]
+    fn basic_features_are_deterministic() {
+        let tx = tx_named(42.5, "Martin");
+
+        assert_eq!(BasicFeatures::default().extract(&tx), BasicFeatures::default().extract(&tx));
+    }
+
+    // ------------------------------------------------------------------
+    // Clock port
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn fixed_clock_is_frozen() {
+        let t = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let clock = FixedClock(t);
+        assert_eq!(clock.now(), t);
+        assert_eq!(clock.now(), t);
+    }
+
+    #[test]
+    fn system_clock_is_after_epoch() {
+        assert!(SystemClock.now() > SystemTime::UNIX_EPOCH);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tokio_sleeper_follows_paused_time() {
+        let start = tokio::time::Instant::now();
+        TokioSleeper.sleep(Duration::from_secs(30)).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(30));
+    }
+
+    // ------------------------------------------------------------------
+    // Retryable classification
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn retryable_classification() {
+        assert!(WriteError::Full { capacity: 1, rejected: 1 }.is_retryable());
+        assert!(!WriteError::Closed.is_retryable());
+        assert!(WriteError::Backend("connection refused".to_owned()).is_retryable());
+        assert!(!ReadError::Closed.is_retryable());
+        assert!(ReadError::Backend("connection refused".to_owned()).is_retryable());
+        assert!(StorageError::Unavailable.is_retryable());
+        assert!(!StorageError::CapacityExceeded { capacity: 1, remaining: 0 }.is_retryable());
+        assert!(ModelizerError::InferenceFailed { reason: "t".to_owned() }.is_retryable());
+        assert!(!ModelizerError::SwitchFailed { reason: "t".to_owned() }.is_retryable());
+    }
+
+    #[test]
+    fn config_error_display_and_cli_message() {
+        let e = ConfigError::new("n1_max", 0, "must be >= 1");
+        assert_eq!(e.to_string(), "n1_max must be >= 1 (got 0)");
+        assert_eq!(e.cli_message(), "--n1-max: must be >= 1 (got 0)");
+    }
+
+    #[test]
+    fn config_summary_display_is_stable() {
+        let summary = ConfigSummary::new("producer")
+            .field("n1_max", 10)
+            .duration("poll_interval1", Duration::from_millis(100))
+            .optional("seed", None::<u64>)
+            .optional("iterations", Some(3))
+            .redacted("webhook_token", true)
+            .redacted("api_key", false);
+
+        assert_eq!(
+            summary.to_string(),
+            "producer: n1_max=10 poll_interval1=100ms seed=none iterations=3 \
+             webhook_token=<redacted> api_key=none"
+        );
+        assert_eq!(summary.component(), "producer");
+        assert_eq!(summary.get("iterations"), Some("3"));
+        assert_eq!(summary.get("missing"), None);
+        assert_eq!(summary.fields().count(), 6);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_summary_serializes_as_an_ordered_object() {
+        let summary = ConfigSummary::new("logger")
+            .field("n3_max", 5)
+            .optional("seed", Some(42))
+            .redacted("webhook_token", true);
+
+        let json = serde_json::to_string(&summary).unwrap();
+
+        assert_eq!(json, r#"{"n3_max":"5","seed":"42","webhook_token":"<redacted>"}"#);
+    }
+
+    #[test]
+    fn preset_names_round_trip() {
+        for preset in Preset::ALL {
+            assert_eq!(preset.to_string().parse::<Preset>(), Ok(preset));
+        }
+        assert_eq!(Preset::LowLatency.name(), "low-latency");
+        let err = "fast".parse::<Preset>().unwrap_err();
+        assert_eq!(err.cli_message(), "--profile: must be demo, stress or low-latency (got fast)");
+    }
+
+    #[test]
+    fn error_chain_walks_sources() {
+        let leaf = StorageError::Unavailable;
+        assert_eq!(ErrorChain(&leaf).messages(), ["storage unavailable"]);
+        assert_eq!(ErrorChain(&leaf).to_string(), "storage unavailable");
+
+        let err = "not-a-uuid".parse::<TransactionId>().unwrap_err();
+        let messages = ErrorChain(&err).messages();
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert_eq!(ErrorChain(&err).to_string(), messages.join(": "));
+    }
+
+    #[test]
+    fn transaction_validate_checks_amount() {
+        let id = TransactionId::from_uuid(uuid::Uuid::nil());
+        let tx = |amount| Transaction {
+            id,
+            amount,
+            last_name: "T".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
+        assert_eq!(tx(0.01).validate(), Ok(()));
+        assert_eq!(tx(Transaction::MAX_AMOUNT).validate(), Ok(()));
+        assert!(matches!(tx(f64::NAN).validate(), Err(InvalidTransaction::NonFiniteAmount(_))));
+        assert_eq!(
+            tx(f64::INFINITY).validate(),
+            Err(InvalidTransaction::NonFiniteAmount(f64::INFINITY))
+        );
+        assert_eq!(tx(0.0).validate(), Err(InvalidTransaction::NonPositiveAmount(0.0)));
+        assert_eq!(tx(-5.0).validate(), Err(InvalidTransaction::NonPositiveAmount(-5.0)));
+        assert_eq!(
+            tx(10_000.01).validate(),
+            Err(InvalidTransaction::AmountTooLarge { amount: 10_000.01, max: 10_000.0 })
+        );
+        assert_eq!(
+            tx(600.0).validate_max(500.0).unwrap_err().to_string(),
+            "amount must be <= 500 (got 600)"
+        );
+    }
+
+    fn digest_tx(n: u128, amount: f64) -> Transaction {
+        Transaction {
+            id: TransactionId::from_uuid(uuid::Uuid::from_u128(n)),
+            amount,
+            last_name: "Smith".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    #[test]
+    fn digest_hash_is_pinned_and_ignores_sub_cent_noise() {
+        let uuid = uuid::Uuid::parse_str("3f2a9c1e-5b7d-4e2f-9a01-0123456789ab").unwrap();
+        let tx = Transaction { id: TransactionId::from_uuid(uuid), ..digest_tx(0, 12.34) };
+
+        // FNV-1a of the UUID bytes and 1234 as little-endian i64.
+        assert_eq!(tx.digest_hash(), 0x8CA7_6BD3_3E9A_0A36);
+        let noisy =
+            Transaction { amount: 12.340_000_1, last_name: "Jones".to_owned(), ..tx.clone() };
+        assert_eq!(noisy.digest_hash(), tx.digest_hash());
+        assert_ne!(digest_tx(1, 12.35).digest_hash(), digest_tx(1, 12.34).digest_hash());
+    }
+
+    #[test]
+    fn stream_digest_ignores_order_and_names_the_count_difference() {
+        let txs: Vec<_> = (0..5u8).map(|n| digest_tx(n.into(), 10.0 + f64::from(n))).collect();
+        let mut upstream = StreamDigest::default();
+        upstream.extend(&txs);
+        let mut reversed = StreamDigest::default();
+        reversed.extend(txs.iter().rev());
+        assert_eq!(upstream.check(&reversed), Ok(()));
+        assert_eq!(upstream.count(), 5);
+
+        let mut short = StreamDigest::default();
+        short.extend(&txs[1..]);
+        let err = upstream.check(&short).unwrap_err();
+        assert_eq!(err.to_string(), "digest mismatch: 5 upstream, 4 downstream (1 missing)");
+        assert!(short.check(&upstream).unwrap_err().to_string().ends_with("(1 extra)"));
+
+        let mut altered = StreamDigest::default();
+        altered.extend(&txs[1..]);
+        altered.add(&digest_tx(0, 99.0));
+        let err = upstream.check(&altered).unwrap_err();
+        assert!(err.to_string().ends_with("(same count, different content)"), "{err}");
+    }
+
+    #[test]
+    fn stop_reason_label_and_display() {
+        let reason = StopReason::BufferClosed { iterations: 3 };
+        assert_eq!((reason.label(), reason.iterations()), ("buffer closed", 3));
+        assert_eq!(reason.to_string(), "buffer closed after 3 iterations");
+        assert_eq!(StopReason::IterationLimit { iterations: 1 }.label(), "iteration limit reached");
+        assert_eq!(StopReason::Cancelled { iterations: 0 }.label(), "cancelled");
+        assert_eq!(StopReason::Exhausted { iterations: 2 }.iterations(), 2);
+    }
+
+    #[test]
+    fn live_settings_adopt_valid_updates_and_keep_the_old_on_invalid() {
+        let seed = DynamicSettings {
+            poll_interval1: Duration::from_millis(5),
+            ..DynamicSettings::default()
+        };
+        let (tx, rx) = tokio::sync::watch::channel(seed);
+        let live = LiveSettings::new(seed, Some(rx));
+        // The current value counts as published once; it equals the seed.
+        assert_eq!(live.refresh(), Ok(false));
+        assert_eq!(live.refresh(), Ok(false));
+
+        let faster = DynamicSettings {
+            poll_interval1: Duration::from_millis(1),
+            ..seed
+        };
+        tx.send_replace(faster);
+        assert_eq!(live.refresh(), Ok(true));
+        assert_eq!(live.get(), faster);
+
+        tx.send_replace(DynamicSettings {
+            batch_size_mode: BatchSizeMode::Geometric { p: 2.0 },
+            ..faster
+        });
+        live.refresh().unwrap_err();
+        assert_eq!(live.get(), faster, "an invalid update keeps the settings in force");
+        assert_eq!(live.refresh(), Ok(false), "a rejected update is reported once");
+
+        drop(tx);
+        assert_eq!(live.refresh(), Ok(false));
+        assert_eq!(live.get(), faster);
+    }
+
+    #[test]
+    fn live_settings_without_a_receiver_keep_the_seed() {
+        let seed = DynamicSettings {
+            poll_interval3: Duration::from_secs(2),
+            ..DynamicSettings::default()
+        };
+        let live = LiveSettings::new(seed, None);
+        assert_eq!(live.refresh(), Ok(false));
+        assert_eq!(live.get(), seed);
+    }
 }
@@ -0,0 +1,113 @@
+// Rust guideline compliant 2026-02-27
+
+//! Canonical field names of the stage log events.
+//!
+//! Every stage reports the same facts (a batch went through, the run loop
+//! stopped), and a log pipeline can only aggregate them across stages if the
+//! fields are spelled the same everywhere. The constants below are those
+//! names; [`log_batch!`](crate::log_batch) and [`log_stop!`](crate::log_stop)
+//! emit the two shared events with them, so call sites cannot drift to
+//! `size` in one stage and `n` in another. Other events name a batch size
+//! [`BATCH_SIZE`] too.
+//!
+//! The macros expand to `tracing` macros: the calling crate depends on
+//! `tracing`, while this crate stays free of it.
+//!
+//! Every stage event also names its stage in its `tracing` target, one of
+//! the [`target`] constants, rather than the module path it is logged from.
+//! `RUST_LOG` directives then select stages: `pipeline::consumer=debug,warn`
+//! shows the Consumer at debug and everything else at warn only, and
+//! `pipeline=debug` shows every stage. The adapters behind the stages and
+//! the code running them log under `pipeline::*` targets too, so
+//! `pipeline=debug` covers them as well.
+
+/// `tracing` targets of the stage events, `pipeline::<stage>`.
+///
+/// `#[tracing::instrument]` only takes a literal target, so stage spans spell
+/// these strings out.
+pub mod target {
+    /// Producer events, the load generator's included.
+    pub const PRODUCER: &str = "pipeline::producer";
+    /// Consumer events, its cache, observer and quarantine included.
+    pub const CONSUMER: &str = "pipeline::consumer";
+    /// Modelizer events.
+    pub const MODELIZER: &str = "pipeline::modelizer";
+    /// Logger events.
+    pub const LOGGER: &str = "pipeline::logger";
+    /// Reviewer events.
+    pub const REVIEWER: &str = "pipeline::reviewer";
+    /// Events of the buffer adapters between the stages.
+    pub const BUFFER: &str = "pipeline::buffer";
+    /// Events of the storage adapters behind the Logger, and of migrations
+    /// between them.
+    pub const STORAGE: &str = "pipeline::storage";
+    /// Events of the alarm adapters behind the Consumer.
+    pub const ALARM: &str = "pipeline::alarm";
+    /// Events of the code running the stages: supervision, watchdog, shutdown,
+    /// settings reloads and rescore backfills.
+    pub const ORCHESTRATOR: &str = "pipeline::orchestrator";
+}
+
+/// Emitting component: `producer`, `consumer`, `logger` or `modelizer`.
+pub const STAGE: &str = "stage";
+
+/// Number of transactions in the batch an event is about.
+pub const BATCH_SIZE: &str = "batch_size";
+
+/// Transactions of the batch predicted fraudulent; zero before inference.
+pub const FLAGGED: &str = "flagged";
+
+/// 1-based index of the batch in the run loop, when the event has one.
+pub const ITERATION: &str = "iteration";
+
+/// Run loop iterations completed, on a stop event.
+pub const ITERATIONS: &str = "iterations";
+
+/// Short `StopReason` label, on a stop event.
+pub const REASON: &str = "reason";
+
+/// Log that `stage` moved a batch of `size` transactions, `flagged` of which
+/// were predicted fraudulent, as a `<stage>.batch` info event with target
+/// `pipeline::<stage>`.
+///
+/// A leading `tracing` level overrides info; extra `tracing` fields may
+/// follow:
+///
+/// ```text
+/// log_batch!("producer", batch.len(), 0, iteration = count);
+/// log_batch!(DEBUG, "modelizer", results.len(), flagged);
+/// ```
+#[macro_export]
+macro_rules! log_batch {
+    ($level:ident, $stage:literal, $size:expr, $flagged:expr $(, $($field:tt)+)?) => {
+        ::tracing::event!(
+            target: concat!("pipeline::", $stage),
+            ::tracing::Level::$level,
+            { $crate::obs::STAGE } = $stage,
+            { $crate::obs::BATCH_SIZE } = $size,
+            { $crate::obs::FLAGGED } = $flagged,
+            $($($field)+,)?
+            concat!($stage, ".batch")
+        )
+    };
+    ($stage:literal, $size:expr, $flagged:expr $(, $($field:tt)+)?) => {
+        $crate::log_batch!(INFO, $stage, $size, $flagged $(, $($field)+)?)
+    };
+}
+
+/// Log that the run loop of `stage` stopped for `reason` (a `StopReason`)
+/// after `iterations`, as a `<stage>.run.stopped` info event with target
+/// `pipeline::<stage>`.
+#[macro_export]
+macro_rules! log_stop {
+    ($stage:literal, $reason:expr, $iterations:expr) => {
+        ::tracing::event!(
+            target: concat!("pipeline::", $stage),
+            ::tracing::Level::INFO,
+            { $crate::obs::STAGE } = $stage,
+            { $crate::obs::REASON } = $reason.label(),
+            { $crate::obs::ITERATIONS } = $iterations,
+            concat!($stage, ".run.stopped")
+        )
+    };
+}
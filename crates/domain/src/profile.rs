@@ -0,0 +1,217 @@
+// Rust guideline compliant 2026-02-27
+
+//! Opt-in timing of the hot inner operations of the stages, for the bench.
+//!
+//! With the `bench-profile` feature, [`time`] and [`time_async`] add the
+//! elapsed time of every call to a thread-local counter of its [`Op`], and
+//! [`take`] returns and resets the counters of the current thread. The
+//! pipeline runs on a `current_thread` runtime, so one thread sees every stage.
+//!
+//! Without the feature both wrappers only call through: no `Instant`, no
+//! thread-local, and [`take`] always returns an empty [`Profile`].
+
+use std::time::Duration;
+
+#[cfg(feature = "bench-profile")]
+use std::cell::RefCell;
+#[cfg(feature = "bench-profile")]
+use std::time::Instant;
+
+/// Whether this build records timings (`bench-profile` feature).
+pub const ENABLED: bool = cfg!(feature = "bench-profile");
+
+/// A timed operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Producer: drawing the bytes of a transaction id.
+    UuidFill,
+    /// Producer: drawing an amount from the configured distribution.
+    AmountSample,
+    /// Producer: copying the drawn last name into the transaction.
+    NameClone,
+    /// Modelizer: one `Model::classify` call.
+    Classify,
+    /// Logger: building a `PendingTransaction` from an inferred one.
+    PendingBuild,
+}
+
+impl Op {
+    /// Every operation, in table order.
+    pub const ALL: [Self; 5] =
+        [Self::UuidFill, Self::AmountSample, Self::NameClone, Self::Classify, Self::PendingBuild];
+
+    /// Component the operation runs in: `producer`, `modelizer` or `logger`.
+    #[must_use]
+    pub fn stage(self) -> &'static str {
+        match self {
+            Self::UuidFill | Self::AmountSample | Self::NameClone => "producer",
+            Self::Classify => "modelizer",
+            Self::PendingBuild => "logger",
+        }
+    }
+
+    /// Short name, e.g. `uuid fill`.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::UuidFill => "uuid fill",
+            Self::AmountSample => "amount sample",
+            Self::NameClone => "name clone",
+            Self::Classify => "classify",
+            Self::PendingBuild => "pending build",
+        }
+    }
+
+    /// Position in [`ALL`](Self::ALL) and in the counters.
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Calls and cumulated time of one [`Op`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpTime {
+    /// Timed calls.
+    pub calls: u64,
+    /// Time spent in them.
+    pub total: Duration,
+}
+
+/// Per-operation timings of the current thread, as returned by [`take`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Profile {
+    times: [OpTime; Op::ALL.len()],
+}
+
+impl Profile {
+    /// Timings of `op`.
+    #[must_use]
+    pub fn get(&self, op: Op) -> OpTime {
+        self.times[op.index()]
+    }
+
+    /// Time spent in all operations.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.times.iter().map(|t| t.total).sum()
+    }
+
+    /// `true` when no call was timed (always, without `bench-profile`).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.times.iter().all(|t| t.calls == 0)
+    }
+}
+
+#[cfg(feature = "bench-profile")]
+thread_local! {
+    static PROFILE: RefCell<Profile> = RefCell::new(Profile::default());
+}
+
+/// Add one call of `op` taking `elapsed` to the current thread's counters.
+#[cfg(feature = "bench-profile")]
+fn record(op: Op, elapsed: Duration) {
+    PROFILE.with(|profile| {
+        let time = &mut profile.borrow_mut().times[op.index()];
+        time.calls += 1;
+        time.total += elapsed;
+    });
+}
+
+/// Run `f`, timed as `op` with `bench-profile`.
+#[inline]
+pub fn time<T>(op: Op, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "bench-profile")]
+    {
+        let start = Instant::now();
+        let out = f();
+        record(op, start.elapsed());
+        out
+    }
+    #[cfg(not(feature = "bench-profile"))]
+    {
+        let _ = op;
+        f()
+    }
+}
+
+/// Await `future`, timed as `op` with `bench-profile`.
+///
+/// The time includes any suspension of `future`; only wrap calls that do not
+/// wait on I/O or timers.
+pub async fn time_async<F: Future>(op: Op, future: F) -> F::Output {
+    #[cfg(feature = "bench-profile")]
+    {
+        let start = Instant::now();
+        let out = future.await;
+        record(op, start.elapsed());
+        out
+    }
+    #[cfg(not(feature = "bench-profile"))]
+    {
+        let _ = op;
+        future.await
+    }
+}
+
+/// Return the current thread's timings and reset them.
+#[must_use]
+pub fn take() -> Profile {
+    #[cfg(feature = "bench-profile")]
+    {
+        PROFILE.with(RefCell::take)
+    }
+    #[cfg(not(feature = "bench-profile"))]
+    {
+        Profile::default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // PF-T01: `Op::ALL` is in index order, with each op's stage and label.
+    #[test]
+    fn op_table_order_matches_index() {
+        for (i, op) in Op::ALL.into_iter().enumerate() {
+            assert_eq!(op.index(), i, "{op:?}");
+        }
+        assert_eq!(Op::Classify.stage(), "modelizer");
+        assert_eq!(Op::UuidFill.label(), "uuid fill");
+    }
+
+    // PF-T02: sync and async timed calls are counted, and `take` resets them.
+    #[cfg(feature = "bench-profile")]
+    #[tokio::test]
+    async fn timed_calls_are_counted_and_reset_by_take() {
+        let _ = take();
+
+        let n = time(Op::NameClone, || "Test".to_owned());
+        let m = time_async(Op::Classify, async { 2 }).await;
+
+        let profile = take();
+        assert_eq!((n.as_str(), m), ("Test", 2));
+        assert_eq!(profile.get(Op::NameClone).calls, 1);
+        assert_eq!(profile.get(Op::Classify).calls, 1);
+        assert_eq!(profile.get(Op::UuidFill), OpTime::default());
+        assert!(take().is_empty());
+    }
+
+    // PF-T03: without the feature nothing is timed or stored; `ENABLED` is
+    // checked at compile time.
+    #[cfg(not(feature = "bench-profile"))]
+    #[test]
+    fn feature_off_records_nothing() {
+        const { assert!(!ENABLED) };
+
+        let n = time(Op::NameClone, || "Test".to_owned());
+
+        assert_eq!(n, "Test");
+        assert!(take().is_empty());
+    }
+}
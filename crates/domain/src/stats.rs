@@ -0,0 +1,328 @@
+// Rust guideline compliant 2026-02-27
+
+//! Streaming percentiles of durations in bounded memory.
+//!
+//! [`StreamingPercentiles`] is the one estimator behind every latency report
+//! (buffer queue delay, alarm delivery time): it counts each
+//! recorded duration, in nanoseconds, in the bucket of its first three
+//! significant digits (`1_234_567 ns` goes to `1_230_000 ns`). Memory is
+//! bounded by the number of such buckets a run touches -- at most 900 per
+//! decade, a few dozen in practice -- however many durations are recorded.
+//!
+//! A quantile reports the lower edge of the bucket holding its rank, clamped
+//! to the smallest and largest duration recorded. It is therefore never above
+//! the true value and less than 1% below it, and exact for durations with at
+//! most three significant digits (`5 ms`, `120 ms`, `2.5 s`), for the
+//! minimum and for the maximum. Histograms merge exactly: merging two and
+//! querying gives what recording both streams into one would have.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Significant digits kept per bucket; sets the relative error below 1%.
+const SIGNIFICANT: u32 = 3;
+
+/// Bucketed histogram of durations, queried by quantile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamingPercentiles {
+    /// Bucket lower edge in nanoseconds -> durations counted in it.
+    buckets: BTreeMap<u64, u64>,
+    count: u64,
+    min: Duration,
+    max: Duration,
+}
+
+impl StreamingPercentiles {
+    /// Create an empty histogram.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one `duration`; beyond `u64::MAX` nanoseconds (584 years) it is
+    /// bucketed as that.
+    pub fn record(&mut self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        *self.buckets.entry(bucket(nanos)).or_insert(0) += 1;
+        self.min = if self.count == 0 { duration } else { self.min.min(duration) };
+        self.max = self.max.max(duration);
+        self.count += 1;
+    }
+
+    /// Nearest-rank `q`-quantile, `q` in `0.0 ..= 1.0` (clamped; `0.5` is the
+    /// median); `Duration::ZERO` before the first record.
+    ///
+    /// See the [module documentation](self) for the error bound.
+    #[must_use]
+    pub fn quantile(&self, q: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = rank(self.count, q);
+        if rank == self.count {
+            return self.max;
+        }
+        let mut seen = 0;
+        for (&edge, &n) in &self.buckets {
+            seen += n;
+            if seen >= rank {
+                return Duration::from_nanos(edge).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+
+    /// Median; see [`quantile`](Self::quantile).
+    #[must_use]
+    pub fn p50(&self) -> Duration {
+        self.quantile(0.50)
+    }
+
+    /// 95th percentile; see [`quantile`](Self::quantile).
+    #[must_use]
+    pub fn p95(&self) -> Duration {
+        self.quantile(0.95)
+    }
+
+    /// 99th percentile; see [`quantile`](Self::quantile).
+    #[must_use]
+    pub fn p99(&self) -> Duration {
+        self.quantile(0.99)
+    }
+
+    /// Number of durations recorded.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Whether nothing was recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Shortest duration recorded; `Duration::ZERO` before the first one.
+    #[must_use]
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    /// Longest duration recorded; `Duration::ZERO` before the first one.
+    #[must_use]
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Add every duration recorded by `other`, e.g. to combine the
+    /// histograms of several Consumers.
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        for (&edge, &n) in &other.buckets {
+            *self.buckets.entry(edge).or_insert(0) += n;
+        }
+        self.min = if self.count == 0 { other.min } else { self.min.min(other.min) };
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+    }
+
+    /// Forget every duration recorded, e.g. at the start of a report window.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Lower edge of the bucket of `nanos`: its first [`SIGNIFICANT`] digits.
+fn bucket(nanos: u64) -> u64 {
+    let digits = nanos.checked_ilog10().map_or(1, |log| log + 1);
+    let scale = 10_u64.pow(digits.saturating_sub(SIGNIFICANT));
+    nanos - nanos % scale
+}
+
+/// Nearest rank of the `q`-quantile among `count` values, in `1 ..= count`.
+///
+/// `q` is taken in millionths so the rank is computed on integers: `0.07`
+/// of 100 is rank 7, not 8 through a float rounding up.
+fn rank(count: u64, q: f64) -> u64 {
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "clamped to 0 ..= 1_000_000 before the cast; NaN casts to 0"
+    )]
+    let ppm = (q.clamp(0.0, 1.0) * 1_000_000.0).round() as u128;
+    let rank = (u128::from(count) * ppm).div_ceil(1_000_000);
+    u64::try_from(rank).unwrap_or(count).clamp(1, count)
+}
+
+impl fmt::Display for StreamingPercentiles {
+    /// `N samples, p50 .., p95 .., p99 .., max ..`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} samples, p50 {:?}, p95 {:?}, p99 {:?}, max {:?}",
+            self.count,
+            self.p50(),
+            self.p95(),
+            self.p99(),
+            self.max
+        )
+    }
+}
+
+/// Serializes the report view: `count`, then `p50_ns`, `p95_ns`, `p99_ns`,
+/// `min_ns` and `max_ns` in nanoseconds, not the buckets.
+#[cfg(feature = "serde")]
+impl serde::Serialize for StreamingPercentiles {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct as _;
+
+        let nanos = |d: Duration| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX);
+        let mut s = serializer.serialize_struct("StreamingPercentiles", 6)?;
+        s.serialize_field("count", &self.count)?;
+        s.serialize_field("p50_ns", &nanos(self.p50()))?;
+        s.serialize_field("p95_ns", &nanos(self.p95()))?;
+        s.serialize_field("p99_ns", &nanos(self.p99()))?;
+        s.serialize_field("min_ns", &nanos(self.min))?;
+        s.serialize_field("max_ns", &nanos(self.max))?;
+        s.end()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{StreamingPercentiles, bucket};
+    use std::time::Duration;
+
+    fn recorded(durations: impl IntoIterator<Item = Duration>) -> StreamingPercentiles {
+        let mut p = StreamingPercentiles::new();
+        for d in durations {
+            p.record(d);
+        }
+        p
+    }
+
+    // SP-T01: durations with three significant digits give exact
+    // nearest-rank quantiles.
+    #[test]
+    fn round_durations_give_exact_quantiles() {
+        let p = recorded((1..=100).map(Duration::from_millis));
+
+        assert_eq!(p.count(), 100);
+        assert_eq!(p.p50(), Duration::from_millis(50));
+        assert_eq!(p.p95(), Duration::from_millis(95));
+        assert_eq!(p.p99(), Duration::from_millis(99));
+        assert_eq!(p.quantile(0.0), Duration::from_millis(1));
+        assert_eq!(p.quantile(1.0), Duration::from_millis(100));
+        assert_eq!(p.quantile(0.07), Duration::from_millis(7));
+        assert_eq!(
+            p.to_string(),
+            "100 samples, p50 50ms, p95 95ms, p99 99ms, max 100ms"
+        );
+    }
+
+    // SP-T02: on a uniform and a log-uniform distribution of arbitrary
+    // durations, every quantile is within 1% below the exact one.
+    #[test]
+    fn quantiles_stay_within_the_error_bound() {
+        // Deterministic spread: a Weyl sequence over 1 µs .. 10 s.
+        let uniform: Vec<u64> =
+            (1..=10_000_u64).map(|i| 1_000 + (i * 7_919_876_543) % 9_999_999_000).collect();
+        let log_uniform: Vec<u64> =
+            (0..10_000_u32).map(|i| 10_u64.pow(3 + i % 7) + u64::from(i) * 104_729).collect();
+
+        for values in [uniform, log_uniform] {
+            let p = recorded(values.iter().map(|&n| Duration::from_nanos(n)));
+            let mut sorted = values.clone();
+            sorted.sort_unstable();
+            for q in [0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99, 0.999] {
+                let rank = super::rank(p.count(), q);
+                let exact = Duration::from_nanos(sorted[usize::try_from(rank).unwrap() - 1]);
+                let estimate = p.quantile(q);
+                assert!(estimate <= exact, "q {q}: {estimate:?} above {exact:?}");
+                assert!(
+                    exact.saturating_sub(estimate) <= exact / 100,
+                    "q {q}: {estimate:?} more than 1% below {exact:?}"
+                );
+            }
+        }
+    }
+
+    // SP-T03: merging two histograms equals recording both streams into one.
+    #[test]
+    fn merge_equals_recording_both_streams() {
+        let a: Vec<Duration> = (1..=300).map(|i| Duration::from_micros(i * 37)).collect();
+        let b: Vec<Duration> = (1..=500).map(|i| Duration::from_micros(i * 91 + 5)).collect();
+
+        let mut merged = recorded(a.iter().copied());
+        merged.merge(&recorded(b.iter().copied()));
+        let whole = recorded(a.iter().chain(&b).copied());
+
+        assert_eq!(merged, whole);
+        // Merging into or from an empty histogram keeps min and max.
+        let mut empty = StreamingPercentiles::new();
+        empty.merge(&whole);
+        assert_eq!(empty, whole);
+        merged.merge(&StreamingPercentiles::new());
+        assert_eq!(merged, whole);
+    }
+
+    // SP-T04: zero samples, zero durations, saturation and reset.
+    #[test]
+    fn edge_cases() {
+        let mut p = StreamingPercentiles::new();
+        assert!(p.is_empty());
+        assert_eq!((p.p50(), p.min(), p.max()), (Duration::ZERO, Duration::ZERO, Duration::ZERO));
+
+        p.record(Duration::ZERO);
+        assert_eq!(p.p99(), Duration::ZERO);
+
+        // Beyond u64::MAX nanoseconds: bucketed as that, max stays exact.
+        p.record(Duration::MAX);
+        assert_eq!(p.quantile(1.0), Duration::MAX);
+        assert_eq!(p.quantile(0.5), Duration::ZERO);
+        // Out-of-range and NaN quantiles clamp.
+        assert_eq!(p.quantile(7.0), Duration::MAX);
+        assert_eq!(p.quantile(-1.0), Duration::ZERO);
+        assert_eq!(p.quantile(f64::NAN), Duration::ZERO);
+
+        p.reset();
+        assert_eq!(p, StreamingPercentiles::new());
+        assert_eq!(bucket(0), 0);
+        assert_eq!(bucket(999), 999);
+        assert_eq!(bucket(1_234_567), 1_230_000);
+        assert_eq!(bucket(u64::MAX), 18_400_000_000_000_000_000);
+    }
+
+    // SP-T05: memory is bounded by the buckets touched, not the records.
+    #[test]
+    fn memory_is_bounded_by_buckets() {
+        let mut p = StreamingPercentiles::new();
+        for i in 0..100_000_u64 {
+            p.record(Duration::from_nanos(1_000_000 + i % 9_000_000));
+        }
+        // 1 ms .. 10 ms spans one decade: at most 900 buckets.
+        assert!(p.buckets.len() <= 900, "{} buckets", p.buckets.len());
+        assert_eq!(p.count(), 100_000);
+    }
+
+    // SP-T06: the serde report view carries the quantiles in nanoseconds.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_the_report_view() {
+        let p = recorded([1, 2, 3, 4].map(Duration::from_millis));
+        assert_eq!(
+            serde_json::to_string(&p).unwrap(),
+            concat!(
+                r#"{"count":4,"p50_ns":2000000,"p95_ns":4000000,"p99_ns":4000000,"#,
+                r#""min_ns":1000000,"max_ns":4000000}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,422 @@
+// Rust guideline compliant 2026-02-27
+
+//! `--ab-compare`: how differently two models flag identical traffic.
+//!
+//! A seeded dataset is pregenerated once, then streamed through two
+//! independently constructed pipelines (Producer, Consumer, Logger, each
+//! supervised as in a normal run) that differ only in the model: the `--model`
+//! backend at version N against the same backend at version N-1, or against
+//! `--model-b`. Both arms are checked to have persisted exactly the dataset,
+//! then their flagged sets are compared: counts, overlap and the amounts of
+//! the transactions only one arm flagged.
+//!
+//! ```text
+//! # DEMO 4 against DEMO 3 over 10 000 transactions
+//! cargo run -- --ab-compare 10000 --model demo:7
+//!
+//! # Another backend as arm B, another dataset, disagreements as CSV
+//! cargo run -- --ab-compare 10000 --model-b bench --ab-seed 9 --ab-csv disagreements.csv
+//! ```
+//!
+//! An unseeded `demo` model draws from the OS in each arm, so part of the
+//! disagreement is noise; `demo:<seed>` gives both arms the same draws.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use consumer::{Consumer, ConsumerConfig};
+use domain::{Model, ModelVersion, Transaction, TransactionId};
+use logger::{Logger, LoggerConfig};
+use modelizer::Modelizer;
+use pipeline::memory::{CountingAlarm, MemoryStorage};
+use producer::{Producer, ProducerConfig};
+
+use crate::adapters::concurrent_buffer::ConcurrentBuffer;
+use crate::adapters::concurrent_buffer2::ConcurrentBuffer2;
+use crate::model_backend::{ModelBackend, ModelSpec};
+use crate::orchestrator::{RestartPolicy, supervise};
+
+/// Dataset seed when `--ab-seed` is absent.
+pub const DEFAULT_SEED: u64 = 42;
+
+/// Maximum batch size of every stage of both arms.
+const BATCH: usize = 100;
+
+/// `--ab-compare` options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbArgs {
+    /// Transactions in the shared dataset.
+    pub transactions: usize,
+    /// Producer seed of the dataset.
+    pub seed: u64,
+    /// Arm B backend; `None` compares `--model` at version N-1.
+    pub model_b: Option<ModelSpec>,
+    /// Where to write the disagreements as CSV, if anywhere.
+    pub csv: Option<PathBuf>,
+}
+
+impl AbArgs {
+    /// Find `--ab-compare <transactions>` and its options in `args`; `None`
+    /// when `--ab-compare` is absent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option has no value or a malformed one.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Option<Self>> {
+        let mut transactions = None;
+        let mut ab = Self { transactions: 0, seed: DEFAULT_SEED, model_b: None, csv: None };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let name = arg.as_str();
+            if !matches!(name, "--ab-compare" | "--ab-seed" | "--model-b" | "--ab-csv") {
+                continue;
+            }
+            let Some(value) = args.next() else { anyhow::bail!("{name} requires a value") };
+            let context = || format!("{name} {value}");
+            match name {
+                "--ab-compare" => transactions = Some(value.parse().with_context(context)?),
+                "--ab-seed" => ab.seed = value.parse().with_context(context)?,
+                "--model-b" => ab.model_b = Some(ModelSpec::parse(&value)?),
+                _ => ab.csv = Some(PathBuf::from(value)),
+            }
+        }
+        Ok(transactions.map(|transactions| Self { transactions, ..ab }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Arms
+// ---------------------------------------------------------------------------
+
+/// What one arm persisted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arm {
+    /// Model name and version, e.g. `"DEMO 4"`.
+    pub label: String,
+    /// Every persisted transaction id, sorted.
+    pub inputs: Vec<TransactionId>,
+    /// The transactions the model flagged.
+    pub flagged: BTreeMap<TransactionId, Transaction>,
+}
+
+/// Generate the shared dataset: `transactions` seeded transactions.
+///
+/// # Errors
+///
+/// Returns an error if the Producer config is rejected.
+pub fn pregenerate(transactions: usize, seed: u64) -> anyhow::Result<Vec<Transaction>> {
+    let config = ProducerConfig::builder(BATCH).seed(seed).pregenerate(transactions).build()?;
+    Ok(Producer::new(config).dataset().to_vec())
+}
+
+/// Stream `dataset` through a fresh pipeline scoring with `model`.
+///
+/// # Errors
+///
+/// Returns an error if a config is rejected or a stage fails.
+pub async fn run_arm<M: Model>(dataset: &[Transaction], model: M) -> anyhow::Result<Arm> {
+    let label = format!("{} {}", model.name(), model.active_version());
+    let modelizer = Modelizer::new(model);
+    // No poll intervals: the arm is a batch job. Seeded batch sizes keep the
+    // two arms' Consumer and Logger batches identical.
+    let producer_config = ProducerConfig::builder(BATCH).poll_interval1(Duration::ZERO).build()?;
+    let producer = Producer::pregenerated(producer_config, dataset.to_vec());
+    let consumer_config = ConsumerConfig::builder(BATCH).poll_interval2(Duration::ZERO).seed(1);
+    let consumer = Consumer::new(consumer_config.build()?);
+    let logger_config = LoggerConfig::builder(BATCH).poll_interval3(Duration::ZERO).seed(2);
+    let logger = Logger::new(logger_config.build()?);
+    let (buffer1, buffer2) = (ConcurrentBuffer::new(), ConcurrentBuffer2::new());
+    // Flagged transactions are read back from storage; nobody is paged.
+    let (alarm, storage) = (CountingAlarm::new(), MemoryStorage::new());
+
+    let policy = RestartPolicy::default();
+    tokio::try_join!(
+        async {
+            let r = supervise("producer", policy, || producer.run(&buffer1)).await;
+            buffer1.close();
+            r.context("producer failed")
+        },
+        async {
+            let r = supervise("consumer", policy, || {
+                consumer.run(&buffer1, &modelizer, &alarm, &buffer2)
+            })
+            .await;
+            buffer2.close();
+            r.context("consumer failed")
+        },
+        async {
+            supervise("logger", policy, || logger.run(&buffer2, &storage))
+                .await
+                .context("logger failed")
+        },
+    )?;
+
+    let items = storage.items();
+    let mut inputs: Vec<_> = items.iter().map(|p| p.inferred_transaction.id()).collect();
+    inputs.sort_unstable();
+    let flagged = items
+        .into_iter()
+        .filter(|p| p.inferred_transaction.predicted_fraud)
+        .map(|p| (p.inferred_transaction.id(), p.inferred_transaction.transaction))
+        .collect();
+    Ok(Arm { label, inputs, flagged })
+}
+
+/// Pregenerate the dataset, run both arms and print the comparison; write
+/// the disagreements to `--ab-csv` if given.
+///
+/// # Errors
+///
+/// Returns an error if a model cannot be built or switched, an arm fails,
+/// the arms saw different inputs or the CSV cannot be written.
+pub async fn run(model_spec: &ModelSpec, args: &AbArgs) -> anyhow::Result<()> {
+    let dataset = pregenerate(args.transactions, args.seed)?;
+    let a = run_arm(&dataset, ModelBackend::from_spec(model_spec)?).await?;
+    let model_b = if let Some(spec) = &args.model_b {
+        ModelBackend::from_spec(spec)?
+    } else {
+        let model = ModelBackend::from_spec(model_spec)?;
+        model
+            .switch_version(ModelVersion::NMinus1)
+            .await
+            .context("arm B needs version N-1 of --model; pick another with --model-b")?;
+        model
+    };
+    let b = run_arm(&dataset, model_b).await?;
+    let comparison = Comparison::new(a, b)?;
+    println!("{comparison}");
+    if let Some(path) = &args.csv {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("failed to create {}", path.display()))?;
+        comparison
+            .write_csv(io::BufWriter::new(file))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        let rows = comparison.only_a.len() + comparison.only_b.len();
+        println!("ab-compare: {rows} disagreements written to {}", path.display());
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Comparison
+// ---------------------------------------------------------------------------
+
+/// Flagged-set comparison of two arms over the same inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Transactions each arm saw.
+    pub transactions: usize,
+    /// Arm A label and flagged count.
+    pub a: (String, usize),
+    /// Arm B label and flagged count.
+    pub b: (String, usize),
+    /// Transactions both arms flagged.
+    pub both: usize,
+    /// Transactions only arm A flagged, by id.
+    pub only_a: Vec<Transaction>,
+    /// Transactions only arm B flagged, by id.
+    pub only_b: Vec<Transaction>,
+}
+
+impl Comparison {
+    /// Compare `a` and `b`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arms persisted different transactions.
+    pub fn new(a: Arm, b: Arm) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            a.inputs == b.inputs,
+            "arms saw different inputs: {} persisted {}, {} persisted {}",
+            a.label,
+            a.inputs.len(),
+            b.label,
+            b.inputs.len()
+        );
+        let ids_a: BTreeSet<_> = a.flagged.keys().collect();
+        let ids_b: BTreeSet<_> = b.flagged.keys().collect();
+        let both = ids_a.intersection(&ids_b).count();
+        let only = |arm: &Arm, other: &BTreeSet<&TransactionId>| -> Vec<Transaction> {
+            let only = arm.flagged.iter().filter(|(id, _)| !other.contains(id));
+            only.map(|(_, tx)| tx.clone()).collect()
+        };
+        Ok(Self {
+            transactions: a.inputs.len(),
+            both,
+            only_a: only(&a, &ids_b),
+            only_b: only(&b, &ids_a),
+            a: (a.label, a.flagged.len()),
+            b: (b.label, b.flagged.len()),
+        })
+    }
+
+    /// Jaccard index of the two flagged sets; 1 when neither flagged anything.
+    #[must_use]
+    pub fn overlap(&self) -> f64 {
+        let union = self.both + self.only_a.len() + self.only_b.len();
+        if union == 0 {
+            return 1.0;
+        }
+        #[expect(clippy::cast_precision_loss, reason = "counts stay far below 2^52")]
+        let overlap = self.both as f64 / union as f64;
+        overlap
+    }
+
+    /// Write `transaction_id,amount,currency,flagged_by` rows, only-A first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the writer's I/O error.
+    pub fn write_csv(&self, mut w: impl Write) -> io::Result<()> {
+        writeln!(w, "transaction_id,amount,currency,flagged_by")?;
+        for (arm, only) in [("A", &self.only_a), ("B", &self.only_b)] {
+            for tx in only {
+                writeln!(w, "{},{:.2},{},{arm}", tx.id.full(), tx.amount, tx.currency)?;
+            }
+        }
+        w.flush()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[expect(clippy::cast_precision_loss, reason = "counts stay far below 2^52")]
+        let rate = |n: usize| 100.0 * n as f64 / self.transactions.max(1) as f64;
+        // Folded from +0.0: an empty f64 sum is -0.0.
+        let amount = |only: &[Transaction]| only.iter().fold(0.0, |sum, tx| sum + tx.amount);
+        writeln!(f, "ab-compare: {} identical transactions", self.transactions)?;
+        writeln!(f, "{:<8} {:<12} {:>8} {:>7} {:>12}", "", "model", "flagged", "rate", "amount")?;
+        for (arm, (label, flagged)) in [("A", &self.a), ("B", &self.b)] {
+            writeln!(f, "{arm:<8} {label:<12} {flagged:>8} {:>6.2}%", rate(*flagged))?;
+        }
+        writeln!(f, "{:<8} {:<12} {:>8} {:>6.2}%", "both", "", self.both, rate(self.both))?;
+        for (arm, only) in [("only A", &self.only_a), ("only B", &self.only_b)] {
+            let n = only.len();
+            writeln!(f, "{arm:<8} {:<12} {n:>8} {:>6.2}% {:>12.2}", "", rate(n), amount(only))?;
+        }
+        write!(f, "overlap (Jaccard): {:.3}", self.overlap())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{AbArgs, Arm, Comparison, DEFAULT_SEED, pregenerate, run_arm};
+    use crate::model_backend::ModelSpec;
+    use domain::{Transaction, TransactionId};
+    use pipeline::memory::RateModel;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn args(args: &[&str]) -> anyhow::Result<Option<AbArgs>> {
+        AbArgs::from_args(args.iter().map(|&arg| arg.to_owned()))
+    }
+
+    /// Arm over `inputs` flagging the `flagged` ones, amount = index + 1.
+    fn arm(label: &str, inputs: &[Transaction], flagged: &[usize]) -> Arm {
+        let mut ids: Vec<_> = inputs.iter().map(|tx| tx.id).collect();
+        ids.sort_unstable();
+        let flagged: BTreeMap<TransactionId, Transaction> =
+            flagged.iter().map(|&i| (inputs[i].id, inputs[i].clone())).collect();
+        Arm { label: label.to_owned(), inputs: ids, flagged }
+    }
+
+    fn dataset(n: usize) -> Vec<Transaction> {
+        let mut dataset = pregenerate(n, 1).unwrap();
+        for (i, tx) in dataset.iter_mut().enumerate() {
+            tx.amount = f64::from(u32::try_from(i).unwrap() + 1);
+        }
+        dataset
+    }
+
+    // AB-T01: --ab-compare takes a count; the other options need it.
+    #[test]
+    fn ab_args_from_args() {
+        assert_eq!(args(&["--model-b", "bench"]).unwrap(), None);
+        let ab = args(&["--ab-compare", "500", "--model-b", "bench", "--ab-csv", "d.csv"]);
+        let expected = AbArgs {
+            transactions: 500,
+            seed: DEFAULT_SEED,
+            model_b: Some(ModelSpec::Bench),
+            csv: Some(PathBuf::from("d.csv")),
+        };
+        assert_eq!(ab.unwrap(), Some(expected));
+        assert_eq!(args(&["--ab-seed", "9", "--ab-compare", "5"]).unwrap().unwrap().seed, 9);
+        args(&["--ab-compare", "many"]).unwrap_err();
+        args(&["--ab-compare"]).unwrap_err();
+    }
+
+    // AB-T02: overlap counts, disagreement amounts and the Jaccard index.
+    #[test]
+    fn comparison_counts_the_overlap() {
+        let inputs = dataset(10);
+        let comparison =
+            Comparison::new(arm("A", &inputs, &[0, 1, 2, 3]), arm("B", &inputs, &[2, 3, 4]))
+                .unwrap();
+        assert_eq!((comparison.a.1, comparison.b.1, comparison.both), (4, 3, 2));
+        let amounts = |only: &[Transaction]| only.iter().map(|tx| tx.amount).sum::<f64>();
+        assert!((amounts(&comparison.only_a) - 3.0).abs() < 1e-9, "1.0 + 2.0");
+        assert!((amounts(&comparison.only_b) - 5.0).abs() < 1e-9);
+        assert!((comparison.overlap() - 2.0 / 5.0).abs() < 1e-9);
+
+        let none = Comparison::new(arm("A", &inputs, &[]), arm("B", &inputs, &[])).unwrap();
+        assert!((none.overlap() - 1.0).abs() < f64::EPSILON);
+        let text = comparison.to_string();
+        assert!(text.starts_with("ab-compare: 10 identical transactions"), "{text}");
+        assert!(text.ends_with("overlap (Jaccard): 0.400"), "{text}");
+        assert!(!none.to_string().contains("-0.00"), "{none}");
+    }
+
+    // AB-T03: arms that persisted different transactions are not compared.
+    #[test]
+    fn comparison_rejects_different_inputs() {
+        let inputs = dataset(4);
+        let error = Comparison::new(arm("A", &inputs, &[]), arm("B", &inputs[..3], &[]))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "arms saw different inputs: A persisted 4, B persisted 3");
+    }
+
+    // AB-T04: both seeded arms persist exactly the pregenerated dataset.
+    #[tokio::test]
+    async fn seeded_arms_see_identical_inputs() {
+        let dataset = pregenerate(250, 3).unwrap();
+        let a = run_arm(&dataset, RateModel::new(0.2, 5)).await.unwrap();
+        let b = run_arm(&dataset, RateModel::new(0.2, 6)).await.unwrap();
+        let mut ids: Vec<_> = dataset.iter().map(|tx| tx.id).collect();
+        ids.sort_unstable();
+        assert_eq!((&a.inputs, &b.inputs), (&ids, &ids));
+        // Same seed, same draws in the same order: the arms agree.
+        let again = run_arm(&dataset, RateModel::new(0.2, 5)).await.unwrap();
+        assert_eq!(again.flagged, a.flagged);
+
+        let (flagged_a, flagged_b) = (a.flagged.len(), b.flagged.len());
+        let comparison = Comparison::new(a, b).unwrap();
+        assert_eq!(comparison.transactions, 250);
+        assert_eq!(comparison.both + comparison.only_a.len(), flagged_a);
+        assert_eq!(comparison.both + comparison.only_b.len(), flagged_b);
+    }
+
+    // AB-T05: the CSV lists only-A then only-B disagreements.
+    #[test]
+    fn disagreements_as_csv() {
+        let inputs = dataset(3);
+        let comparison =
+            Comparison::new(arm("A", &inputs, &[0, 1]), arm("B", &inputs, &[1, 2])).unwrap();
+        let mut csv = Vec::new();
+        comparison.write_csv(&mut csv).unwrap();
+        let expected = format!(
+            "transaction_id,amount,currency,flagged_by\n{},1.00,EUR,A\n{},3.00,EUR,B\n",
+            inputs[0].id.full(),
+            inputs[2].id.full()
+        );
+        assert_eq!(String::from_utf8(csv).unwrap(), expected);
+    }
+}
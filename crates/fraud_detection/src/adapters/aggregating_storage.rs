@@ -0,0 +1,353 @@
+// Rust guideline compliant 2026-02-16
+
+//! Decorator for the `Storage` port that keeps per-minute fraud counts.
+//!
+//! [`AggregatingStorage`] forwards every batch unchanged to the wrapped
+//! storage, then adds the batch to the bucket of the current minute (total
+//! and flagged counts). Transactions carry no timestamp yet, so the minute
+//! comes from the injected `Clock`. Buckets touched since the last flush are
+//! upserted into the wrapped `BucketSink` every `flush_interval`, and are
+//! always readable in memory via [`AggregatingStorage::buckets`].
+//! `StorageRead`, `Review` and the run lifecycle pass straight through to the
+//! wrapped storage.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use domain::{
+    BucketSink, Clock, MinuteBucket, PendingTransaction, Review, ReviewOutcome, RunId, RunMeta,
+    RunSummary, Storage, StorageError, StorageRead, StoredTransaction, SystemClock, TransactionId,
+};
+
+/// `tracing` target of every storage event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::STORAGE;
+
+#[derive(Debug)]
+struct AggregateState {
+    buckets: BTreeMap<u64, MinuteBucket>,
+    /// Minutes whose bucket changed since the last successful flush.
+    dirty: BTreeSet<u64>,
+    last_flush: SystemTime,
+}
+
+/// `Storage` decorator maintaining per-minute total and flagged counts.
+// #[allow] not #[expect]: dead_code fires in fraud_detection_bench only.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+#[derive(Debug)]
+pub struct AggregatingStorage<S> {
+    inner: S,
+    clock: Arc<dyn Clock>,
+    flush_interval: Duration,
+    state: RefCell<AggregateState>,
+}
+
+impl<S> AggregatingStorage<S> {
+    /// Wrap `inner`, bucketing by wall-clock minute.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    #[must_use]
+    pub fn new(inner: S, flush_interval: Duration) -> Self {
+        Self::with_clock(inner, flush_interval, Arc::new(SystemClock))
+    }
+
+    /// Wrap `inner`, bucketing by the minute reported by `clock`.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    #[must_use]
+    pub fn with_clock(inner: S, flush_interval: Duration, clock: Arc<dyn Clock>) -> Self {
+        let last_flush = clock.now();
+        Self {
+            inner,
+            clock,
+            flush_interval,
+            state: RefCell::new(AggregateState {
+                buckets: BTreeMap::new(),
+                dirty: BTreeSet::new(),
+                last_flush,
+            }),
+        }
+    }
+
+    /// All buckets recorded so far, ordered by minute.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    #[must_use]
+    pub fn buckets(&self) -> Vec<MinuteBucket> {
+        self.state.borrow().buckets.values().copied().collect()
+    }
+
+    /// The wrapped storage.
+    // fraud_detection reads it only with the arrow feature.
+    #[allow(dead_code, reason = "used by fraud_detection_sqlite and by fraud_detection with arrow")]
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn record(&self, now: SystemTime, total: usize, flagged: usize) {
+        let minute = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            / 60;
+        let mut state = self.state.borrow_mut();
+        let bucket = state
+            .buckets
+            .entry(minute)
+            .or_insert(MinuteBucket { minute, ..MinuteBucket::default() });
+        bucket.total += total as u64;
+        bucket.flagged += flagged as u64;
+        state.dirty.insert(minute);
+    }
+}
+
+impl<S: BucketSink> AggregatingStorage<S> {
+    /// Upsert every bucket changed since the last successful flush.
+    ///
+    /// Buckets hold cumulative counts, so a repeated flush is idempotent.
+    ///
+    /// # Errors
+    ///
+    /// Returns the sink error; the buckets stay pending for the next flush.
+    pub async fn flush(&self) -> Result<(), StorageError> {
+        let pending: Vec<MinuteBucket> = {
+            let state = self.state.borrow();
+            state.dirty.iter().map(|m| state.buckets[m]).collect()
+        };
+        if pending.is_empty() {
+            return Ok(());
+        }
+        self.inner.upsert_buckets(&pending).await?;
+        let mut state = self.state.borrow_mut();
+        state.dirty.clear();
+        state.last_flush = self.clock.now();
+        tracing::debug!(target: TARGET, buckets = pending.len(), "aggregating_storage.flushed");
+        Ok(())
+    }
+}
+
+impl<S: Storage + BucketSink> Storage for AggregatingStorage<S> {
+    /// Forward `batch` to the wrapped storage, then count it in the current minute.
+    ///
+    /// A failed periodic flush is logged and retried on the next write; it
+    /// does not fail the batch, which the wrapped storage already accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns the wrapped storage error; the batch is then not counted.
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        let total = batch.len();
+        let flagged = batch
+            .iter()
+            .filter(|pt| pt.inferred_transaction.predicted_fraud)
+            .count();
+        self.inner.write_batch(batch).await?;
+
+        let now = self.clock.now();
+        self.record(now, total, flagged);
+
+        let last_flush = self.state.borrow().last_flush;
+        if now.duration_since(last_flush).unwrap_or_default() >= self.flush_interval
+            && let Err(e) = self.flush().await
+        {
+            tracing::warn!(target: TARGET, error = %e, "aggregating_storage.flush_failed");
+        }
+        Ok(())
+    }
+
+    /// Forward to the wrapped storage.
+    async fn begin_run(&self, meta: RunMeta) -> Result<RunId, StorageError> {
+        self.inner.begin_run(meta).await
+    }
+
+    /// Forward to the wrapped storage.
+    async fn end_run(&self, id: RunId, summary: RunSummary) -> Result<(), StorageError> {
+        self.inner.end_run(id, summary).await
+    }
+}
+
+impl<S: StorageRead> StorageRead for AggregatingStorage<S> {
+    /// Forward to the wrapped storage.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        self.inner.read_page(after, limit).await
+    }
+
+    /// Forward to the wrapped storage.
+    async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+        self.inner.all_ids(limit).await
+    }
+}
+
+impl<S: Review> Review for AggregatingStorage<S> {
+    /// Forward to the wrapped storage; reviews do not change the minute counts.
+    async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError> {
+        self.inner.record_reviews(outcomes).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::AggregatingStorage;
+    use domain::{
+        BucketSink, Clock, Currency, InferredTransaction, MinuteBucket, PendingTransaction, Storage,
+        StorageError, Transaction, TransactionId,
+    };
+    use std::cell::{Cell, RefCell};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, SystemTime};
+
+    /// Clock whose time (seconds since epoch) is set by the test.
+    #[derive(Debug, Clone)]
+    struct ManualClock(Arc<AtomicU64>);
+
+    impl ManualClock {
+        fn set(&self, secs: u64) {
+            self.0.store(secs, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    /// Records delegated transactions and upserted buckets.
+    #[derive(Default)]
+    struct RecordingStorage {
+        written: RefCell<Vec<PendingTransaction>>,
+        rows: RefCell<BTreeMap<u64, MinuteBucket>>,
+        upserts: Cell<usize>,
+    }
+
+    impl Storage for RecordingStorage {
+        async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            self.written.borrow_mut().extend(batch);
+            Ok(())
+        }
+    }
+
+    impl BucketSink for RecordingStorage {
+        async fn upsert_buckets(&self, buckets: &[MinuteBucket]) -> Result<(), StorageError> {
+            self.upserts.set(self.upserts.get() + 1);
+            let mut rows = self.rows.borrow_mut();
+            for b in buckets {
+                rows.insert(b.minute, *b);
+            }
+            Ok(())
+        }
+    }
+
+    fn make_pending(predicted_fraud: bool) -> PendingTransaction {
+        PendingTransaction::new(InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: 1.00_f64,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        })
+    }
+
+    /// `flags[i]` sets `predicted_fraud` on the i-th item.
+    fn make_batch(flags: &[bool]) -> Vec<PendingTransaction> {
+        flags.iter().map(|&f| make_pending(f)).collect()
+    }
+
+    const T0: u64 = 1_700_000_040; // start of minute 28_333_334
+
+    fn make_storage(
+        flush_interval: Duration,
+    ) -> (AggregatingStorage<RecordingStorage>, ManualClock) {
+        let clock = ManualClock(Arc::new(AtomicU64::new(T0)));
+        let storage = AggregatingStorage::with_clock(
+            RecordingStorage::default(),
+            flush_interval,
+            Arc::new(clock.clone()),
+        );
+        (storage, clock)
+    }
+
+    // AGG-T01: batches spanning three minutes land in three buckets and rows.
+    #[tokio::test]
+    async fn three_minutes_produce_three_buckets() {
+        let (storage, clock) = make_storage(Duration::from_mins(1));
+        let m0 = T0 / 60;
+
+        storage.write_batch(make_batch(&[true, false, false])).await.unwrap();
+        clock.set(T0 + 30);
+        storage.write_batch(make_batch(&[false, false])).await.unwrap();
+        clock.set(T0 + 60);
+        storage.write_batch(make_batch(&[true, true])).await.unwrap();
+        clock.set(T0 + 150);
+        storage.write_batch(make_batch(&[false])).await.unwrap();
+        storage.flush().await.unwrap();
+
+        let expected = vec![
+            MinuteBucket { minute: m0, total: 5, flagged: 1 },
+            MinuteBucket { minute: m0 + 1, total: 2, flagged: 2 },
+            MinuteBucket { minute: m0 + 2, total: 1, flagged: 0 },
+        ];
+        assert_eq!(storage.buckets(), expected);
+        let rows: Vec<_> = storage.inner.rows.borrow().values().copied().collect();
+        assert_eq!(rows, expected);
+    }
+
+    // AGG-T02: the delegate receives every transaction unchanged and in order.
+    #[tokio::test]
+    async fn delegate_receives_every_transaction() {
+        let (storage, clock) = make_storage(Duration::from_mins(1));
+        let first = make_batch(&[true, false]);
+        let second = make_batch(&[false, true, false]);
+        let mut expected = first.clone();
+        expected.extend(second.clone());
+
+        storage.write_batch(first).await.unwrap();
+        clock.set(T0 + 90);
+        storage.write_batch(second).await.unwrap();
+
+        assert_eq!(*storage.inner.written.borrow(), expected);
+    }
+
+    // AGG-T03: flushing happens only once the interval has elapsed.
+    #[tokio::test]
+    async fn flush_waits_for_interval() {
+        let (storage, clock) = make_storage(Duration::from_mins(1));
+
+        storage.write_batch(make_batch(&[false])).await.unwrap();
+        assert_eq!(storage.inner.upserts.get(), 0);
+        clock.set(T0 + 60);
+        storage.write_batch(make_batch(&[true])).await.unwrap();
+        assert_eq!(storage.inner.upserts.get(), 1);
+        assert_eq!(storage.inner.rows.borrow().len(), 2);
+    }
+
+    // AGG-T04: a repeated flush with nothing new does not touch the sink.
+    #[tokio::test]
+    async fn flush_without_changes_is_noop() {
+        let (storage, _clock) = make_storage(Duration::from_mins(1));
+
+        storage.write_batch(make_batch(&[true])).await.unwrap();
+        storage.flush().await.unwrap();
+        storage.flush().await.unwrap();
+        assert_eq!(storage.inner.upserts.get(), 1);
+    }
+}
@@ -0,0 +1,433 @@
+// Rust guideline compliant 2026-02-27
+
+//! Decorator for the `Alarm` port that delivers alerts off the hot path.
+//!
+//! [`AsyncAlarmDispatcher::trigger`] only enqueues a copy of the transaction
+//! and returns; a background task drains the queue and calls the wrapped
+//! alarm, so a slow adapter (e.g. a webhook with seconds of latency) no
+//! longer holds up `consume_once`. The queue is bounded: when it is full,
+//! the [`OverflowPolicy`] either drops the oldest queued alert or rejects
+//! the new one with `DeliveryFailed`. [`AsyncAlarmDispatcher::shutdown`]
+//! stops accepting alerts and waits until everything queued is delivered.
+//!
+//! Like every stage of the pipeline the dispatcher is `!Send`: the task is
+//! started with `tokio::task::spawn_local`, so the dispatcher must be built
+//! inside a `tokio::task::LocalSet`. Failures of the wrapped alarm are
+//! logged and counted; nobody awaits them anymore.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
+use domain::{Alarm, AlarmError, InferredTransaction};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// `tracing` target of every alarm event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ALARM;
+
+// ---------------------------------------------------------------------------
+// OverflowPolicy / DispatcherStats
+// ---------------------------------------------------------------------------
+
+/// What [`AsyncAlarmDispatcher::trigger`] does when the queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the oldest queued alert to make room; `trigger` succeeds.
+    DropOldest,
+    /// Keep the queue as is; `trigger` fails with `DeliveryFailed`.
+    Reject,
+}
+
+/// Alert counters of an [`AsyncAlarmDispatcher`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispatcherStats {
+    /// Alerts accepted into the queue.
+    pub enqueued: u64,
+    /// Alerts the wrapped alarm accepted.
+    pub delivered: u64,
+    /// Alerts the wrapped alarm failed to deliver.
+    pub failed: u64,
+    /// Queued alerts discarded by [`OverflowPolicy::DropOldest`].
+    pub dropped: u64,
+    /// Alerts refused by [`OverflowPolicy::Reject`] or after shutdown.
+    pub rejected: u64,
+}
+
+impl fmt::Display for DispatcherStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "alarm dispatcher: {} enqueued, {} delivered, {} failed, {} dropped, {} rejected",
+            self.enqueued, self.delivered, self.failed, self.dropped, self.rejected
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shared state
+// ---------------------------------------------------------------------------
+
+/// Queue and counters shared by the dispatcher and its delivery task.
+#[derive(Debug, Default)]
+struct Shared {
+    queue: RefCell<VecDeque<InferredTransaction>>,
+    /// Set by `shutdown` (or drop): no new alerts, the task exits once empty.
+    closed: Cell<bool>,
+    /// Wakes the task on a new alert or on close.
+    wake: Notify,
+    stats: Cell<DispatcherStats>,
+}
+
+impl Shared {
+    fn update(&self, f: impl FnOnce(&mut DispatcherStats)) {
+        let mut stats = self.stats.get();
+        f(&mut stats);
+        self.stats.set(stats);
+    }
+
+    fn close(&self) {
+        self.closed.set(true);
+        self.wake.notify_one();
+    }
+}
+
+/// Delivery task: pop alerts in order until closed and drained.
+async fn deliver<A: Alarm>(inner: A, shared: Rc<Shared>) {
+    loop {
+        // Scope the borrow so it is dropped before any await.
+        let next = shared.queue.borrow_mut().pop_front();
+        match next {
+            Some(tx) => match inner.trigger(&tx).await {
+                Ok(()) => shared.update(|s| s.delivered += 1),
+                Err(e) => {
+                    shared.update(|s| s.failed += 1);
+                    tracing::warn!(
+                        target: TARGET,
+                        transaction_id = %tx.id(),
+                        error = %e,
+                        "alarm_dispatcher.delivery_failed"
+                    );
+                }
+            },
+            None if shared.closed.get() => break,
+            // notify_one keeps a permit, so an alert queued in between is not missed.
+            None => shared.wake.notified().await,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AsyncAlarmDispatcher
+// ---------------------------------------------------------------------------
+
+/// `Alarm` decorator queueing alerts for a background delivery task.
+#[derive(Debug)]
+pub struct AsyncAlarmDispatcher {
+    shared: Rc<Shared>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    task: RefCell<Option<JoinHandle<()>>>,
+}
+
+impl AsyncAlarmDispatcher {
+    /// Wrap `inner` behind a queue of at most `capacity` alerts (`0` counts as
+    /// `1`) and start the delivery task.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a `tokio::task::LocalSet`.
+    #[must_use]
+    pub fn new<A: Alarm + 'static>(inner: A, capacity: usize, policy: OverflowPolicy) -> Self {
+        let shared = Rc::new(Shared::default());
+        let task = tokio::task::spawn_local(deliver(inner, Rc::clone(&shared)));
+        Self { shared, capacity: capacity.max(1), policy, task: RefCell::new(Some(task)) }
+    }
+
+    /// Counters so far.
+    #[must_use]
+    pub fn stats(&self) -> DispatcherStats {
+        self.shared.stats.get()
+    }
+
+    /// Alerts queued and not yet handed to the wrapped alarm.
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        self.shared.queue.borrow().len()
+    }
+
+    /// Stop accepting alerts and wait until every queued one has been handed
+    /// to the wrapped alarm; returns the final counters.
+    ///
+    /// Idempotent: later calls return the counters immediately.
+    pub async fn shutdown(&self) -> DispatcherStats {
+        self.shared.close();
+        // Take the handle first so the RefCell borrow is not held across the await.
+        let task = self.task.borrow_mut().take();
+        if let Some(task) = task
+            && let Err(e) = task.await
+        {
+            tracing::error!(target: TARGET, error = %e, "alarm_dispatcher.task_failed");
+        }
+        let stats = self.stats();
+        tracing::info!(target: TARGET, %stats, "alarm_dispatcher.shutdown");
+        stats
+    }
+}
+
+impl Drop for AsyncAlarmDispatcher {
+    /// Let the task deliver what is queued and exit instead of waiting forever.
+    fn drop(&mut self) {
+        self.shared.close();
+    }
+}
+
+impl Alarm for AsyncAlarmDispatcher {
+    /// Queue a copy of `transaction` for delivery and return immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AlarmError::DeliveryFailed` after [`shutdown`](Self::shutdown),
+    /// or when the queue is full under [`OverflowPolicy::Reject`].
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        if self.shared.closed.get() {
+            self.shared.update(|s| s.rejected += 1);
+            return Err(AlarmError::DeliveryFailed {
+                reason: "alarm dispatcher is shut down".to_owned(),
+            });
+        }
+        let mut queue = self.shared.queue.borrow_mut();
+        if queue.len() >= self.capacity {
+            match self.policy {
+                OverflowPolicy::Reject => {
+                    self.shared.update(|s| s.rejected += 1);
+                    return Err(AlarmError::DeliveryFailed {
+                        reason: format!("alarm queue full (capacity: {})", self.capacity),
+                    });
+                }
+                OverflowPolicy::DropOldest => {
+                    if let Some(dropped) = queue.pop_front() {
+                        self.shared.update(|s| s.dropped += 1);
+                        tracing::warn!(
+                            target: TARGET,
+                            transaction_id = %dropped.id(),
+                            "alarm_dispatcher.dropped_oldest"
+                        );
+                    }
+                }
+            }
+        }
+        queue.push_back(transaction.clone());
+        self.shared.update(|s| s.enqueued += 1);
+        self.shared.wake.notify_one();
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{AsyncAlarmDispatcher, DispatcherStats, OverflowPolicy};
+    use crate::adapters::concurrent_buffer::ConcurrentBuffer;
+    use crate::adapters::concurrent_buffer2::ConcurrentBuffer2;
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{
+        Alarm, AlarmError, Buffer1 as _, Currency, InferredTransaction, Transaction, TransactionId,
+    };
+    use modelizer::Modelizer;
+    use pipeline::memory::RateModel;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+    use tokio::task::LocalSet;
+    use tokio::time::Instant;
+
+    /// Records delivered ids after an optional delay; fails when `fail` is set.
+    #[derive(Debug, Clone, Default)]
+    struct RecordingAlarm {
+        delivered: Rc<RefCell<Vec<TransactionId>>>,
+        delay: Duration,
+        fail: bool,
+    }
+
+    impl Alarm for RecordingAlarm {
+        async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(AlarmError::DeliveryFailed { reason: "webhook down".to_owned() });
+            }
+            self.delivered.borrow_mut().push(transaction.id());
+            Ok(())
+        }
+    }
+
+    fn slow_alarm() -> RecordingAlarm {
+        RecordingAlarm { delay: Duration::from_secs(2), ..RecordingAlarm::default() }
+    }
+
+    fn make_inferred() -> InferredTransaction {
+        InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: 1.00_f64,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud: true,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        }
+    }
+
+    fn make_txs(n: usize) -> Vec<Transaction> {
+        (0..n)
+            .map(|_| Transaction {
+                id: TransactionId::new_v4(),
+                amount: 1.00_f64,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            })
+            .collect()
+    }
+
+    /// Virtual time one `consume_once` of 3 all-fraud transactions takes with `alarm`.
+    async fn consume_once_duration<A: Alarm>(alarm: &A) -> Duration {
+        let buffer1 = ConcurrentBuffer::new();
+        buffer1.write_batch(make_txs(3)).await.unwrap();
+        let buffer2 = ConcurrentBuffer2::new();
+        let modelizer = Modelizer::new(RateModel::new(1.0, 1));
+        // n2_max 1: exactly one transaction per consume_once.
+        let consumer = Consumer::new(ConsumerConfig::builder(1).seed(1).build().unwrap());
+
+        let start = Instant::now();
+        let errors = consumer.consume_once(&buffer1, &modelizer, alarm, &buffer2).await.unwrap();
+        assert!(errors.is_empty());
+        start.elapsed()
+    }
+
+    // AD-T01: a slow inner alarm no longer slows consume_once down.
+    #[tokio::test(start_paused = true)]
+    async fn slow_inner_alarm_does_not_slow_consume_once() {
+        LocalSet::new()
+            .run_until(async {
+                let inline = consume_once_duration(&slow_alarm()).await;
+                let alarm = slow_alarm();
+                let dispatcher =
+                    AsyncAlarmDispatcher::new(alarm.clone(), 16, OverflowPolicy::Reject);
+
+                let queued = consume_once_duration(&dispatcher).await;
+
+                assert!(inline >= Duration::from_secs(2), "inline: {inline:?}");
+                assert_eq!(queued, Duration::ZERO);
+                assert_eq!(dispatcher.shutdown().await.delivered, 1);
+                assert_eq!(alarm.delivered.borrow().len(), 1);
+            })
+            .await;
+    }
+
+    // AD-T02: DropOldest keeps the newest alerts and counts the dropped ones.
+    #[tokio::test(start_paused = true)]
+    async fn drop_oldest_keeps_the_newest_alerts() {
+        LocalSet::new()
+            .run_until(async {
+                let alarm = slow_alarm();
+                let dispatcher =
+                    AsyncAlarmDispatcher::new(alarm.clone(), 2, OverflowPolicy::DropOldest);
+                let txs: Vec<_> = (0..5).map(|_| make_inferred()).collect();
+
+                // No await point yields to the task in between: the queue overflows.
+                for tx in &txs {
+                    dispatcher.trigger(tx).await.unwrap();
+                }
+                assert_eq!(dispatcher.queued(), 2);
+                let stats = dispatcher.shutdown().await;
+
+                assert_eq!((stats.enqueued, stats.dropped, stats.delivered), (5, 3, 2));
+                assert_eq!(*alarm.delivered.borrow(), [txs[3].id(), txs[4].id()]);
+            })
+            .await;
+    }
+
+    // AD-T03: Reject refuses new alerts once full and keeps the queued ones.
+    #[tokio::test(start_paused = true)]
+    async fn reject_refuses_alerts_when_full() {
+        LocalSet::new()
+            .run_until(async {
+                let alarm = slow_alarm();
+                let dispatcher =
+                    AsyncAlarmDispatcher::new(alarm.clone(), 2, OverflowPolicy::Reject);
+                let txs: Vec<_> = (0..5).map(|_| make_inferred()).collect();
+
+                let mut results = vec![];
+                for tx in &txs {
+                    results.push(dispatcher.trigger(tx).await);
+                }
+                let stats = dispatcher.shutdown().await;
+
+                assert!(results[..2].iter().all(Result::is_ok));
+                assert!(
+                    results[2..]
+                        .iter()
+                        .all(|r| matches!(r, Err(AlarmError::DeliveryFailed { .. })))
+                );
+                assert_eq!((stats.enqueued, stats.rejected, stats.delivered), (2, 3, 2));
+                assert_eq!(*alarm.delivered.borrow(), [txs[0].id(), txs[1].id()]);
+            })
+            .await;
+    }
+
+    // AD-T04: shutdown flushes every queued alert, in order, then refuses new ones.
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_flushes_everything_queued() {
+        LocalSet::new()
+            .run_until(async {
+                let alarm = slow_alarm();
+                let dispatcher =
+                    AsyncAlarmDispatcher::new(alarm.clone(), 100, OverflowPolicy::Reject);
+                let txs: Vec<_> = (0..10).map(|_| make_inferred()).collect();
+                for tx in &txs {
+                    dispatcher.trigger(tx).await.unwrap();
+                }
+
+                let start = Instant::now();
+                let stats = dispatcher.shutdown().await;
+
+                assert_eq!(start.elapsed(), Duration::from_secs(20));
+                let expected =
+                    DispatcherStats { enqueued: 10, delivered: 10, ..DispatcherStats::default() };
+                assert_eq!(stats, expected);
+                let ids: Vec<_> = txs.iter().map(InferredTransaction::id).collect();
+                assert_eq!(*alarm.delivered.borrow(), ids);
+                assert_eq!(dispatcher.queued(), 0);
+                let late = dispatcher.trigger(&make_inferred()).await;
+                assert!(matches!(late, Err(AlarmError::DeliveryFailed { .. })));
+                assert_eq!(dispatcher.shutdown().await.rejected, 1);
+            })
+            .await;
+    }
+
+    // AD-T05: inner failures are counted, not returned to the caller.
+    #[tokio::test(start_paused = true)]
+    async fn inner_failures_are_counted() {
+        LocalSet::new()
+            .run_until(async {
+                let alarm = RecordingAlarm { fail: true, ..RecordingAlarm::default() };
+                let dispatcher = AsyncAlarmDispatcher::new(alarm, 4, OverflowPolicy::Reject);
+
+                dispatcher.trigger(&make_inferred()).await.unwrap();
+                dispatcher.trigger(&make_inferred()).await.unwrap();
+                let stats = dispatcher.shutdown().await;
+
+                assert_eq!((stats.delivered, stats.failed), (0, 2));
+            })
+            .await;
+    }
+}
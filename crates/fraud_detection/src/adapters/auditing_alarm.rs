@@ -0,0 +1,288 @@
+// Rust guideline compliant 2026-02-27
+
+//! Decorator for the `Alarm` port that records every delivery attempt.
+//!
+//! [`AuditingAlarm`] forwards each alert to the wrapped alarm, then appends
+//! an `AlarmDelivery` (transaction id, severity, time, outcome) to an
+//! `AlarmAudit` sink and returns the wrapped alarm's result unchanged. An
+//! alert is `High` severity from `high_amount` (in EUR) up, `Low` below;
+//! with [`AuditingAlarm::with_converter`], non-EUR amounts are converted
+//! first, and an amount without a rate is `High`. Audit
+//! failures are logged and never turn into alarm errors: losing an audit
+//! row must not look like a lost alert to the Consumer.
+
+use std::sync::Arc;
+
+use domain::{
+    Alarm, AlarmAudit, AlarmDelivery, AlarmError, AlarmSeverity, Clock, CurrencyConverter,
+    DeliveryOutcome, InferredTransaction, SystemClock,
+};
+
+/// `tracing` target of every alarm event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ALARM;
+
+/// `Alarm` decorator auditing the outcome of each wrapped `trigger` call.
+// #[allow] not #[expect]: dead_code fires in fraud_detection and fraud_detection_bench only.
+#[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in fraud_detection and fraud_detection_bench")]
+#[derive(Debug)]
+pub struct AuditingAlarm<A, S> {
+    inner: A,
+    audit: S,
+    /// `High` severity threshold, in EUR.
+    high_amount: f64,
+    /// Time source for `delivered_at`.
+    clock: Arc<dyn Clock>,
+    /// Converts non-EUR amounts for the severity; `None` compares them as they are.
+    converter: Option<Arc<dyn CurrencyConverter>>,
+}
+
+impl<A, S> AuditingAlarm<A, S> {
+    /// Wrap `inner`, recording its deliveries into `audit`; alerts of at
+    /// least `high_amount` are audited as `High` severity.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in fraud_detection and fraud_detection_bench")]
+    #[must_use]
+    pub fn new(inner: A, audit: S, high_amount: f64) -> Self {
+        Self { inner, audit, high_amount, clock: Arc::new(SystemClock), converter: None }
+    }
+
+    /// Stamp `delivered_at` from `clock` instead of the system clock.
+    // #[allow] not #[expect]: only tests call this.
+    #[allow(dead_code, reason = "clock injection for tests; the binary uses SystemClock")]
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Convert non-EUR amounts to EUR through `converter` before comparing
+    /// them with `high_amount`.
+    // #[allow] not #[expect]: only tests call this.
+    #[allow(dead_code, reason = "the binaries configure no currency converter")]
+    #[must_use]
+    pub fn with_converter(mut self, converter: Arc<dyn CurrencyConverter>) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Severity of an alert for `transaction`, from its EUR amount.
+    fn severity(&self, transaction: &InferredTransaction) -> AlarmSeverity {
+        let tx = &transaction.transaction;
+        let amount = match &self.converter {
+            Some(converter) => converter.to_eur(tx.amount, &tx.currency),
+            None => Some(tx.amount),
+        };
+        if amount.is_none_or(|amount| amount >= self.high_amount) {
+            AlarmSeverity::High
+        } else {
+            AlarmSeverity::Low
+        }
+    }
+}
+
+impl<A: Alarm, S: AlarmAudit> Alarm for AuditingAlarm<A, S> {
+    /// Forward `transaction` to the wrapped alarm and audit the outcome.
+    ///
+    /// # Errors
+    ///
+    /// Returns the wrapped alarm's error, unchanged; audit failures are only
+    /// logged.
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        let result = self.inner.trigger(transaction).await;
+        let delivery = AlarmDelivery {
+            transaction_id: transaction.id(),
+            severity: self.severity(transaction),
+            delivered_at: self.clock.now(),
+            outcome: match &result {
+                Ok(()) => DeliveryOutcome::Delivered,
+                Err(e) => DeliveryOutcome::Failed { reason: e.to_string() },
+            },
+        };
+        if let Err(e) = self.audit.record_delivery(&delivery).await {
+            tracing::warn!(
+                target: TARGET,
+                transaction_id = %delivery.transaction_id,
+                error = %e,
+                "auditing_alarm.record_failed"
+            );
+        }
+        result
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::AuditingAlarm;
+    use crate::adapters::concurrent_buffer::ConcurrentBuffer;
+    use crate::adapters::concurrent_buffer2::ConcurrentBuffer2;
+    use crate::adapters::in_memory_storage::InMemoryStorage;
+    use crate::adapters::log_alarm::LogAlarm;
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{
+        Alarm, AlarmAudit, AlarmDelivery, AlarmError, AlarmSeverity, Buffer1 as _, Currency,
+        CurrencyConverter, DeliveryOutcome, FixedClock, InferredTransaction, StorageError,
+        Transaction, TransactionId,
+    };
+    use modelizer::Modelizer;
+    use pipeline::memory::RateModel;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    /// Always fails to deliver.
+    #[derive(Debug)]
+    struct DownAlarm;
+
+    impl Alarm for DownAlarm {
+        async fn trigger(&self, _transaction: &InferredTransaction) -> Result<(), AlarmError> {
+            Err(AlarmError::DeliveryFailed { reason: "webhook down".to_owned() })
+        }
+    }
+
+    /// Audit sink whose backend is always unavailable.
+    #[derive(Debug)]
+    struct DownAudit;
+
+    impl AlarmAudit for DownAudit {
+        async fn record_delivery(&self, _delivery: &AlarmDelivery) -> Result<(), StorageError> {
+            Err(StorageError::Unavailable)
+        }
+    }
+
+    /// Knows a USD rate only.
+    #[derive(Debug)]
+    struct HalfDollar;
+
+    impl CurrencyConverter for HalfDollar {
+        fn rate_to_eur(&self, currency: &Currency) -> Option<f64> {
+            (*currency == Currency::Usd).then_some(0.5)
+        }
+    }
+
+    fn t0() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    fn audited<A>(inner: A) -> AuditingAlarm<A, InMemoryStorage> {
+        AuditingAlarm::new(inner, InMemoryStorage::new(0), 10.0)
+            .with_clock(Arc::new(FixedClock(t0())))
+    }
+
+    fn make_inferred(amount: f64) -> InferredTransaction {
+        make_priced(amount, Currency::Eur)
+    }
+
+    fn make_priced(amount: f64, currency: Currency) -> InferredTransaction {
+        InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount,
+                last_name: "Test".to_owned(),
+                currency,
+                tenant_id: None,
+            },
+            predicted_fraud: true,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        }
+    }
+
+    // AA-T01: successful deliveries are recorded as delivered, with severity.
+    #[tokio::test]
+    async fn successes_are_recorded_as_delivered() {
+        let alarm = audited(LogAlarm::new());
+        let (low, high) = (make_inferred(9.99), make_inferred(10.0));
+
+        alarm.trigger(&low).await.unwrap();
+        alarm.trigger(&high).await.unwrap();
+
+        let delivered = |tx: &InferredTransaction, severity| AlarmDelivery {
+            transaction_id: tx.id(),
+            severity,
+            delivered_at: t0(),
+            outcome: DeliveryOutcome::Delivered,
+        };
+        assert_eq!(
+            alarm.audit.deliveries(),
+            [delivered(&low, AlarmSeverity::Low), delivered(&high, AlarmSeverity::High)]
+        );
+    }
+
+    // AA-T02: a failed delivery is recorded as failed and its error returned.
+    #[tokio::test]
+    async fn failures_are_recorded_and_returned() {
+        let alarm = audited(DownAlarm);
+        let tx = make_inferred(50.0);
+
+        let result = alarm.trigger(&tx).await;
+
+        let Err(AlarmError::DeliveryFailed { reason }) = result else {
+            panic!("the wrapped alarm's error must pass through: {result:?}");
+        };
+        assert_eq!(reason, "webhook down");
+        let deliveries = alarm.audit.deliveries();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].transaction_id, tx.id());
+        assert_eq!(
+            deliveries[0].outcome,
+            DeliveryOutcome::Failed { reason: "delivery failed: webhook down".to_owned() }
+        );
+    }
+
+    // AA-T03: an unavailable audit sink changes no alarm result.
+    #[tokio::test]
+    async fn audit_failure_does_not_affect_results() {
+        let ok = AuditingAlarm::new(LogAlarm::new(), DownAudit, 10.0);
+        let down = AuditingAlarm::new(DownAlarm, DownAudit, 10.0);
+
+        assert!(matches!(ok.trigger(&make_inferred(1.0)).await, Ok(())));
+        assert!(matches!(
+            down.trigger(&make_inferred(1.0)).await,
+            Err(AlarmError::DeliveryFailed { .. })
+        ));
+    }
+
+    // AA-T04: the Consumer still receives the wrapped alarm's errors.
+    #[tokio::test]
+    async fn consumer_receives_the_wrapped_alarm_errors() {
+        let buffer1 = ConcurrentBuffer::new();
+        buffer1.write_batch(vec![make_inferred(1.0).transaction]).await.unwrap();
+        let buffer2 = ConcurrentBuffer2::new();
+        // Fraud rate 1.0: every transaction is flagged and alarmed.
+        let modelizer = Modelizer::new(RateModel::new(1.0, 1));
+        let consumer = Consumer::new(ConsumerConfig::builder(1).seed(1).build().unwrap());
+        let alarm = audited(DownAlarm);
+
+        let errors = consumer.consume_once(&buffer1, &modelizer, &alarm, &buffer2).await.unwrap();
+
+        assert_eq!(errors.len(), 1);
+        let deliveries = alarm.audit.deliveries();
+        assert_eq!(deliveries.len(), 1);
+        assert!(!deliveries[0].outcome.is_delivered());
+    }
+
+    // AA-T05: with a converter the severity follows the EUR amount, and an
+    // amount without a rate is audited as High.
+    #[tokio::test]
+    async fn severity_follows_the_eur_amount() {
+        let alarm = audited(LogAlarm::new()).with_converter(Arc::new(HalfDollar));
+        // USD 15 is EUR 7.50: Low although 15 is above the threshold.
+        let txs = [
+            make_priced(15.0, Currency::Usd),
+            make_priced(20.0, Currency::Usd),
+            make_priced(1.0, Currency::Gbp),
+        ];
+
+        for tx in &txs {
+            alarm.trigger(tx).await.unwrap();
+        }
+
+        let severities: Vec<_> = alarm.audit.deliveries().iter().map(|d| d.severity).collect();
+        assert_eq!(severities, [AlarmSeverity::Low, AlarmSeverity::High, AlarmSeverity::High]);
+    }
+}
@@ -8,21 +8,46 @@
 //!
 //! Always returns `Ok(false)` (no fraud), eliminating RNG overhead and
 //! preventing any `LogAlarm` calls during benchmarks.
+//!
+//! [`BenchModel::with_latency`] adds a simulated per-transaction inference
+//! cost, awaited with `tokio::time::sleep`. Besides `fraud_detection_bench`,
+//! the pipeline binaries run it with `--model bench`.
+
+use std::cell::Cell;
+use std::time::Duration;
 
 use domain::{Model, ModelizerError, ModelVersion, Transaction};
 
 /// `Model` adapter that always classifies transactions as non-fraudulent.
 ///
-/// No RNG, no I/O, no state mutation -- minimal overhead for throughput
-/// measurement.
+/// No RNG, no I/O -- minimal overhead for throughput measurement. Without
+/// latency, `classify` never suspends.
 #[derive(Debug)]
-pub struct BenchModel;
+pub struct BenchModel {
+    /// Simulated cost of one `classify` call; zero by default.
+    latency: Duration,
+    /// Latency accrued but not slept yet (always under one millisecond).
+    owed: Cell<Duration>,
+}
 
 impl BenchModel {
-    /// Create a new bench model adapter.
+    /// Create a new bench model adapter with no latency.
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self { latency: Duration::ZERO, owed: Cell::new(Duration::ZERO) }
+    }
+
+    /// Charge `latency` per classified transaction.
+    ///
+    /// tokio's timer has millisecond resolution, so latency accrues across
+    /// calls and is slept in whole milliseconds once it adds up to one: 100
+    /// transactions at 50 us sleep 5 ms in total, not 100 rounded-up sleeps.
+    // #[allow] not #[expect]: only fraud_detection_bench sets a latency.
+    #[allow(dead_code, reason = "used by fraud_detection_bench; `--model bench` has no latency")]
+    #[must_use]
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
     }
 }
 
@@ -33,12 +58,21 @@ impl Default for BenchModel {
 }
 
 impl Model for BenchModel {
-    /// Always returns `Ok(false)` (no fraud).
+    /// Always returns `Ok(false)` (no fraud), after the configured latency.
     ///
     /// # Errors
     ///
     /// Infallible; always returns `Ok(false)`.
     async fn classify(&self, _tx: &Transaction) -> Result<bool, ModelizerError> {
+        if !self.latency.is_zero() {
+            let owed = self.owed.get() + self.latency;
+            let millis = u64::try_from(owed.as_millis()).unwrap_or(u64::MAX);
+            let slept = Duration::from_millis(millis);
+            self.owed.set(owed.saturating_sub(slept));
+            if !slept.is_zero() {
+                tokio::time::sleep(slept).await;
+            }
+        }
         Ok(false)
     }
 
@@ -61,3 +95,67 @@ impl Model for BenchModel {
         Ok(())
     }
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::BenchModel;
+    use domain::{Currency, Model as _, Transaction, TransactionId};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    fn make_tx() -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.00_f64,
+            last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    /// Classify `n` transactions; return the verdicts and the time it took.
+    async fn classify_n(model: &BenchModel, n: usize) -> (Vec<bool>, Duration) {
+        let start = Instant::now();
+        let mut verdicts = vec![];
+        for _ in 0..n {
+            verdicts.push(model.classify(&make_tx()).await.unwrap());
+        }
+        (verdicts, start.elapsed())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_latency_never_sleeps() {
+        let (verdicts, elapsed) = classify_n(&BenchModel::new(), 100).await;
+
+        assert_eq!(verdicts, vec![false; 100]);
+        assert_eq!(elapsed, Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn latency_accrues_and_is_slept_in_whole_milliseconds() {
+        let model = BenchModel::new().with_latency(Duration::from_micros(250));
+
+        // 0.75 ms accrued: below the timer resolution, nothing slept yet.
+        let (_, elapsed) = classify_n(&model, 3).await;
+        assert_eq!(elapsed, Duration::ZERO);
+
+        // 8 more calls bring the total to 11 x 250 us = 2.75 ms: 2 ms slept.
+        let (verdicts, elapsed) = classify_n(&model, 8).await;
+        assert_eq!(verdicts, vec![false; 8]);
+        assert!(elapsed >= Duration::from_millis(2), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(3), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn millisecond_latency_is_slept_on_every_call() {
+        let model = BenchModel::new().with_latency(Duration::from_millis(2));
+
+        let (_, elapsed) = classify_n(&model, 5).await;
+
+        assert!(elapsed >= Duration::from_millis(10), "{elapsed:?}");
+    }
+}
@@ -17,9 +17,12 @@
 //! round), making results unstable and hard to reproduce.
 //!
 //! If you need to benchmark a specific storage backend, wire it directly in
-//! a dedicated binary and measure it in isolation.
+//! a dedicated binary and measure it in isolation. To ask "what if storage
+//! took 2 ms per batch" instead, [`BenchStorage::with_latency`] awaits a
+//! fixed `tokio::time::sleep` per write while still discarding the data.
 
 use std::cell::RefCell;
+use std::time::Duration;
 
 use domain::{PendingTransaction, Storage, StorageError};
 
@@ -35,13 +38,25 @@ use domain::{PendingTransaction, Storage, StorageError};
 #[derive(Debug)]
 pub struct BenchStorage {
     count: RefCell<usize>,
+    /// Simulated cost of one `write_batch` call; zero by default.
+    latency: Duration,
 }
 
 impl BenchStorage {
-    /// Create a new discard storage with a zero transaction count.
+    /// Create a new discard storage with a zero transaction count and no
+    /// latency.
     #[must_use]
     pub fn new() -> Self {
-        Self { count: RefCell::new(0) }
+        Self { count: RefCell::new(0), latency: Duration::ZERO }
+    }
+
+    /// Sleep `latency` in every `write_batch`, whatever the batch size.
+    ///
+    /// tokio's timer rounds sleeps up to whole milliseconds.
+    #[must_use]
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
     }
 
     /// Return the cumulative number of transactions received so far.
@@ -58,14 +73,81 @@ impl Default for BenchStorage {
 }
 
 impl Storage for BenchStorage {
-    /// Increment the counter by `batch.len()` and drop the batch.
+    /// Wait out the configured latency, increment the counter by
+    /// `batch.len()` and drop the batch.
     ///
     /// # Errors
     ///
     /// Infallible; always returns `Ok(())`.
     async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
         *self.count.borrow_mut() += batch.len();
         // Batch dropped here -- no persistence, no allocation.
         Ok(())
     }
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::BenchStorage;
+    use domain::{
+        Currency, InferredTransaction, PendingTransaction, Storage as _, Transaction, TransactionId,
+    };
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    fn make_batch(n: usize) -> Vec<PendingTransaction> {
+        (0..n)
+            .map(|_| {
+                PendingTransaction::new(InferredTransaction {
+                    transaction: Transaction {
+                        id: TransactionId::new_v4(),
+                        amount: 1.00_f64,
+                        last_name: "Test".to_owned(),
+                        currency: Currency::Eur,
+                        tenant_id: None,
+                    },
+                    predicted_fraud: false,
+                    model_name: "BENCH".to_owned(),
+                    model_version: "1".to_owned(),
+                    delivery_attempts: 1,
+                    cached: false,
+                })
+            })
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_latency_counts_without_sleeping() {
+        let storage = BenchStorage::new();
+        let start = Instant::now();
+
+        for n in [3, 0, 7] {
+            storage.write_batch(make_batch(n)).await.unwrap();
+        }
+
+        assert_eq!(storage.count(), 10);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn latency_is_awaited_once_per_batch() {
+        let storage = BenchStorage::new().with_latency(Duration::from_millis(2));
+        let start = Instant::now();
+
+        for n in [3, 0, 7] {
+            storage.write_batch(make_batch(n)).await.unwrap();
+        }
+
+        assert_eq!(storage.count(), 10);
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(6), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(7), "{elapsed:?}");
+    }
+}
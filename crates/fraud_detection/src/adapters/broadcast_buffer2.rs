@@ -0,0 +1,528 @@
+// Rust guideline compliant 2026-02-27
+
+//! Fan-out adapter for the `Buffer2` port: every reader sees every item.
+//!
+//! [`BroadcastBuffer2`] is written to like any `Buffer2`, but is read through
+//! [`Subscriber`] handles obtained from [`BroadcastBuffer2::subscribe`]. Each
+//! subscriber has its own queue, so the Logger and, say, a live dashboard read
+//! the same inferred-transaction stream without taking items from each other.
+//! A subscriber registered after writes began starts at the current position.
+//!
+//! With a per-subscriber capacity, [`LagPolicy`] decides what a lagging
+//! subscriber costs: the writer waits for it, or it loses its oldest items.
+//!
+//! Like `ConcurrentBuffer2`, an empty open subscriber cooperatively yields
+//! rather than signaling `Closed`. `close()` on the buffer ends every
+//! subscriber; `close()` on a subscriber ends only that one.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use domain::{Buffer2, Buffer2Read, BufferDepth, InferredTransaction, ReadError, WriteError};
+
+// ---------------------------------------------------------------------------
+// LagPolicy
+// ---------------------------------------------------------------------------
+
+/// What a write does when a subscriber's queue would exceed the capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// The writer waits until every open subscriber has room for the batch.
+    Block,
+    /// The lagging subscriber loses its oldest items; the writer never waits.
+    DropOldest,
+}
+
+// ---------------------------------------------------------------------------
+// Inner state
+// ---------------------------------------------------------------------------
+
+/// One subscriber's queue and state.
+#[derive(Debug, Default)]
+struct Lane {
+    data: VecDeque<InferredTransaction>,
+    /// Items discarded by [`LagPolicy::DropOldest`].
+    dropped: u64,
+    closed: bool,
+}
+
+/// One slot per subscriber ever registered (`None` once its handle is
+/// dropped) and the buffer-wide close flag.
+#[derive(Debug)]
+struct BroadcastInner {
+    lanes: Vec<Option<Lane>>,
+    closed: bool,
+}
+
+impl BroadcastInner {
+    /// Lanes still receiving writes.
+    fn open_lanes(&mut self) -> impl Iterator<Item = &mut Lane> {
+        self.lanes.iter_mut().flatten().filter(|lane| !lane.closed)
+    }
+
+    /// Append a copy of `items` to every open lane, then trim each lane back
+    /// to `capacity` (if any), counting what it loses.
+    fn append(&mut self, items: &[InferredTransaction], capacity: Option<usize>) {
+        for lane in self.open_lanes() {
+            lane.data.extend(items.iter().cloned());
+            if let Some(capacity) = capacity
+                && lane.data.len() > capacity
+            {
+                let overflow = lane.data.len() - capacity;
+                lane.data.drain(..overflow);
+                lane.dropped += overflow as u64;
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// BroadcastBuffer2
+// ---------------------------------------------------------------------------
+
+/// `Buffer2` adapter copying every batch to all registered [`Subscriber`]s.
+///
+/// Items written while no subscriber is registered are discarded. Borrows
+/// of the shared `RefCell` are always dropped before a `yield_now` point.
+#[derive(Debug)]
+pub struct BroadcastBuffer2 {
+    inner: RefCell<BroadcastInner>,
+    capacity: Option<usize>,
+    policy: LagPolicy,
+}
+
+impl BroadcastBuffer2 {
+    /// Create an empty, open buffer with unbounded subscriber queues.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: RefCell::new(BroadcastInner { lanes: Vec::new(), closed: false }),
+            capacity: None,
+            policy: LagPolicy::Block,
+        }
+    }
+
+    /// Create an empty, open buffer holding at most `capacity` items per
+    /// subscriber (`0` counts as `1`), applying `policy` to lagging ones.
+    ///
+    /// Under [`LagPolicy::Block`], a batch larger than `capacity` never fits
+    /// and is rejected with [`WriteError::Full`].
+    #[must_use]
+    pub fn with_capacity(capacity: usize, policy: LagPolicy) -> Self {
+        Self { capacity: Some(capacity.max(1)), policy, ..Self::new() }
+    }
+
+    /// Register a new subscriber. It receives every item written from now on,
+    /// none written before.
+    #[must_use]
+    pub fn subscribe(&self) -> Subscriber<'_> {
+        let mut inner = self.inner.borrow_mut();
+        inner.lanes.push(Some(Lane::default()));
+        Subscriber { buffer: self, slot: inner.lanes.len() - 1 }
+    }
+
+    /// Number of subscribers still receiving writes.
+    #[must_use]
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.borrow_mut().open_lanes().count()
+    }
+
+    /// Signal end-of-data to every subscriber. Idempotent: safe to call multiple times.
+    pub fn close(&self) {
+        self.inner.borrow_mut().closed = true;
+    }
+}
+
+impl Default for BroadcastBuffer2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Buffer2 for BroadcastBuffer2 {
+    /// Append a copy of `batch` to every open subscriber.
+    ///
+    /// Under [`LagPolicy::Block`], yields until every open subscriber has room
+    /// for the whole batch; under [`LagPolicy::DropOldest`], trims each
+    /// subscriber back to the capacity instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WriteError::Closed`] if the buffer has been closed, and
+    /// [`WriteError::Full`] if `batch` can never fit under `Block`.
+    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), WriteError> {
+        if let Some(capacity) = self.capacity
+            && self.policy == LagPolicy::Block
+            && batch.len() > capacity
+        {
+            return Err(WriteError::Full { capacity, rejected: batch.len() });
+        }
+        loop {
+            // Scope the borrow so it is dropped before yield_now().await.
+            let result = {
+                let mut inner = self.inner.borrow_mut();
+                if inner.closed {
+                    Some(Err(WriteError::Closed))
+                } else if self.policy == LagPolicy::Block
+                    && let Some(capacity) = self.capacity
+                    && inner.open_lanes().any(|lane| lane.data.len() + batch.len() > capacity)
+                {
+                    None
+                } else {
+                    inner.append(&batch, self.capacity);
+                    Some(Ok(()))
+                }
+            };
+
+            match result {
+                Some(r) => return r,
+                None => tokio::task::yield_now().await,
+            }
+        }
+    }
+
+    /// Append to every open subscriber the longest prefix of `batch` that
+    /// fits, removing it from `batch`, and return its length. Never waits.
+    ///
+    /// Under [`LagPolicy::Block`], the prefix is limited by the fullest open
+    /// subscriber; under [`LagPolicy::DropOldest`], the whole batch is taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WriteError::Closed`] if the buffer has been closed, and
+    /// [`WriteError::Full`] if no item fits; `batch` is then left untouched.
+    async fn write_batch_partial(
+        &self,
+        batch: &mut Vec<InferredTransaction>,
+    ) -> Result<usize, WriteError> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.closed {
+            return Err(WriteError::Closed);
+        }
+        let room = match self.capacity {
+            Some(capacity) if self.policy == LagPolicy::Block => inner
+                .open_lanes()
+                .map(|lane| capacity.saturating_sub(lane.data.len()))
+                .min()
+                .unwrap_or(usize::MAX),
+            _ => usize::MAX,
+        };
+        if let Some(capacity) = self.capacity
+            && room == 0
+            && !batch.is_empty()
+        {
+            return Err(WriteError::Full { capacity, rejected: batch.len() });
+        }
+        let accepted = room.min(batch.len());
+        inner.append(&batch[..accepted], self.capacity);
+        batch.drain(..accepted);
+        Ok(accepted)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Subscriber
+// ---------------------------------------------------------------------------
+
+/// Reading handle of a [`BroadcastBuffer2`], with its own queue and close state.
+///
+/// Dropping the handle unregisters it, so it no longer holds back a
+/// [`LagPolicy::Block`] writer.
+#[derive(Debug)]
+pub struct Subscriber<'a> {
+    buffer: &'a BroadcastBuffer2,
+    slot: usize,
+}
+
+impl Subscriber<'_> {
+    /// Run `f` on this subscriber's lane.
+    fn with_lane<R>(&self, f: impl FnOnce(&mut Lane, bool) -> R) -> R {
+        let mut inner = self.buffer.inner.borrow_mut();
+        let closed = inner.closed;
+        let lane = inner.lanes[self.slot].as_mut().expect("lane lives as long as its handle");
+        f(lane, closed)
+    }
+
+    /// Stop receiving writes. Items already queued can still be read, then
+    /// reads return `Closed`. Other subscribers are unaffected.
+    pub fn close(&self) {
+        self.with_lane(|lane, _| lane.closed = true);
+    }
+
+    /// Items this subscriber lost to [`LagPolicy::DropOldest`].
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.with_lane(|lane, _| lane.dropped)
+    }
+}
+
+impl Drop for Subscriber<'_> {
+    fn drop(&mut self) {
+        self.buffer.inner.borrow_mut().lanes[self.slot] = None;
+    }
+}
+
+impl Buffer2Read for Subscriber<'_> {
+    /// Drain up to `max` items from this subscriber's queue; yield and retry
+    /// if empty and open.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadError::Closed`] when the queue is empty and either the
+    /// buffer or this subscriber has been closed.
+    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
+        loop {
+            let result = self.with_lane(|lane, buffer_closed| {
+                if !lane.data.is_empty() {
+                    let count = max.min(lane.data.len());
+                    Some(Ok(lane.data.drain(..count).collect()))
+                } else if lane.closed || buffer_closed {
+                    Some(Err(ReadError::Closed))
+                } else {
+                    None
+                }
+            });
+
+            match result {
+                Some(r) => return r,
+                None => tokio::task::yield_now().await,
+            }
+        }
+    }
+}
+
+impl BufferDepth for Subscriber<'_> {
+    /// Number of items waiting in this subscriber's queue.
+    fn depth(&self) -> usize {
+        self.with_lane(|lane, _| lane.data.len())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{BroadcastBuffer2, LagPolicy};
+    use domain::{
+        Buffer2 as _, Buffer2Read as _, BufferDepth as _, Currency, InferredTransaction, ReadError,
+        Transaction, TransactionId, WriteError,
+    };
+    use std::cell::Cell;
+
+    fn make_batch(n: usize) -> Vec<InferredTransaction> {
+        (0..n)
+            .map(|_| InferredTransaction {
+                transaction: Transaction {
+                    id: TransactionId::new_v4(),
+                    amount: 1.00_f64,
+                    last_name: "Test".to_owned(),
+                    currency: Currency::Eur,
+                    tenant_id: None,
+                },
+                predicted_fraud: false,
+                model_name: "DEMO".to_owned(),
+                model_version: "4".to_owned(),
+                delivery_attempts: 1,
+                cached: false,
+            })
+            .collect()
+    }
+
+    fn ids(items: &[InferredTransaction]) -> Vec<TransactionId> {
+        items.iter().map(InferredTransaction::id).collect()
+    }
+
+    /// Read until `Closed`, `max` items at a time.
+    async fn drain(reader: &impl domain::Buffer2Read, max: usize) -> Vec<TransactionId> {
+        let mut seen = Vec::new();
+        while let Ok(batch) = reader.read_batch(max).await {
+            seen.extend(ids(&batch));
+        }
+        seen
+    }
+
+    // BB-T01: two subscribers each receive the complete sequence, in order.
+    #[tokio::test]
+    async fn every_subscriber_sees_every_item() {
+        let buffer = BroadcastBuffer2::new();
+        let (a, b) = (buffer.subscribe(), buffer.subscribe());
+        let first = make_batch(3);
+        let second = make_batch(2);
+        let expected = [ids(&first), ids(&second)].concat();
+
+        buffer.write_batch(first).await.unwrap();
+        buffer.write_batch(second).await.unwrap();
+        buffer.close();
+
+        assert_eq!(drain(&a, 10).await, expected);
+        assert_eq!(drain(&b, 10).await, expected);
+    }
+
+    // BB-T02: readers draining at different rates, concurrently with the
+    // writer, still each get everything.
+    #[tokio::test]
+    async fn independent_drain_rates() {
+        let buffer = BroadcastBuffer2::new();
+        let (fast, slow) = (buffer.subscribe(), buffer.subscribe());
+        let batches: Vec<_> = (0..5).map(|_| make_batch(4)).collect();
+        let expected = ids(&batches.concat());
+
+        let ((), fast_seen, slow_seen) = tokio::join!(
+            async {
+                for batch in batches {
+                    buffer.write_batch(batch).await.unwrap();
+                    tokio::task::yield_now().await;
+                }
+                buffer.close();
+            },
+            drain(&fast, 10),
+            drain(&slow, 1),
+        );
+
+        assert_eq!(fast_seen, expected);
+        assert_eq!(slow_seen, expected);
+    }
+
+    // BB-T03: a subscriber registered after writes starts at the current position.
+    #[tokio::test]
+    async fn late_subscriber_starts_at_current_position() {
+        let buffer = BroadcastBuffer2::new();
+        let early = buffer.subscribe();
+        buffer.write_batch(make_batch(3)).await.unwrap();
+
+        let late = buffer.subscribe();
+        let after = make_batch(2);
+        let after_ids = ids(&after);
+        buffer.write_batch(after).await.unwrap();
+        buffer.close();
+
+        assert_eq!(early.depth(), 5);
+        assert_eq!(drain(&late, 10).await, after_ids);
+    }
+
+    // BB-T04: DropOldest trims only the lagging subscriber and counts the loss.
+    #[tokio::test]
+    async fn drop_oldest_trims_the_lagging_subscriber() {
+        let buffer = BroadcastBuffer2::with_capacity(3, LagPolicy::DropOldest);
+        let (fast, slow) = (buffer.subscribe(), buffer.subscribe());
+        let items = make_batch(5);
+        let expected = ids(&items);
+
+        for item in items {
+            buffer.write_batch(vec![item]).await.unwrap();
+            fast.read_batch(10).await.unwrap();
+        }
+        buffer.close();
+
+        assert_eq!((fast.dropped(), slow.dropped()), (0, 2));
+        assert_eq!(drain(&slow, 10).await, expected[2..]);
+    }
+
+    // BB-T05: Block makes the writer wait until the lagging subscriber reads.
+    #[tokio::test]
+    async fn block_waits_for_the_lagging_subscriber() {
+        let buffer = BroadcastBuffer2::with_capacity(2, LagPolicy::Block);
+        let sub = buffer.subscribe();
+        buffer.write_batch(make_batch(2)).await.unwrap();
+        let written = Cell::new(false);
+
+        let ((), read) = tokio::join!(
+            async {
+                buffer.write_batch(make_batch(1)).await.unwrap();
+                written.set(true);
+            },
+            async {
+                tokio::task::yield_now().await;
+                assert!(!written.get(), "writer must wait while the subscriber is full");
+                sub.read_batch(1).await.unwrap()
+            },
+        );
+
+        assert_eq!(read.len(), 1);
+        assert!(written.get());
+        assert_eq!((sub.depth(), sub.dropped()), (2, 0));
+    }
+
+    // BB-T06: under Block, a batch larger than the capacity is rejected.
+    #[tokio::test]
+    async fn block_rejects_oversized_batch() {
+        let buffer = BroadcastBuffer2::with_capacity(2, LagPolicy::Block);
+        let _sub = buffer.subscribe();
+
+        let result = buffer.write_batch(make_batch(3)).await;
+        assert_eq!(result, Err(WriteError::Full { capacity: 2, rejected: 3 }));
+    }
+
+    // BB-T07: a partial write into a capacity one short of the batch accepts
+    // all but the last item, for every subscriber, and keeps the rest.
+    #[tokio::test]
+    async fn partial_write_one_short_accepts_n_minus_one() {
+        let buffer = BroadcastBuffer2::with_capacity(4, LagPolicy::Block);
+        let (a, b) = (buffer.subscribe(), buffer.subscribe());
+        let mut batch = make_batch(5);
+        let expected = ids(&batch);
+
+        let accepted = buffer.write_batch_partial(&mut batch).await.unwrap();
+
+        assert_eq!(accepted, 4);
+        assert_eq!(ids(&batch), expected[4..]);
+        assert_eq!(ids(&a.read_batch(10).await.unwrap()), expected[..4]);
+        assert_eq!(ids(&b.read_batch(10).await.unwrap()), expected[..4]);
+    }
+
+    // BB-T08: a partial write is Full only when no item fits, and then leaves
+    // the batch untouched; the fullest subscriber sets the limit.
+    #[tokio::test]
+    async fn partial_write_is_full_only_when_nothing_fits() {
+        let buffer = BroadcastBuffer2::with_capacity(3, LagPolicy::Block);
+        let (full, empty) = (buffer.subscribe(), buffer.subscribe());
+        buffer.write_batch(make_batch(3)).await.unwrap();
+        empty.read_batch(10).await.unwrap();
+        let mut batch = make_batch(2);
+
+        let result = buffer.write_batch_partial(&mut batch).await;
+        assert_eq!(result, Err(WriteError::Full { capacity: 3, rejected: 2 }));
+        assert_eq!(batch.len(), 2);
+
+        full.read_batch(1).await.unwrap();
+        assert_eq!(buffer.write_batch_partial(&mut batch).await, Ok(1));
+        assert_eq!((batch.len(), full.depth(), empty.depth()), (1, 3, 1));
+    }
+
+    // BB-T09: closing the buffer ends every subscriber after it drains.
+    #[tokio::test]
+    async fn close_propagates_to_all_subscribers() {
+        let buffer = BroadcastBuffer2::new();
+        let (a, b) = (buffer.subscribe(), buffer.subscribe());
+        buffer.write_batch(make_batch(1)).await.unwrap();
+        buffer.close();
+        buffer.close(); // must not panic
+
+        assert_eq!(a.read_batch(10).await.unwrap().len(), 1);
+        assert_eq!(a.read_batch(10).await, Err(ReadError::Closed));
+        assert_eq!(b.read_batch(10).await.unwrap().len(), 1);
+        assert_eq!(b.read_batch(10).await, Err(ReadError::Closed));
+        assert_eq!(buffer.write_batch(make_batch(1)).await, Err(WriteError::Closed));
+    }
+
+    // BB-T10: a closed or dropped subscriber no longer receives writes nor
+    // holds back a Block writer; the others keep going.
+    #[tokio::test]
+    async fn closed_subscriber_leaves_the_others_alone() {
+        let buffer = BroadcastBuffer2::with_capacity(1, LagPolicy::Block);
+        let (closed, live) = (buffer.subscribe(), buffer.subscribe());
+        let dropped = buffer.subscribe();
+        closed.close();
+        drop(dropped);
+        assert_eq!(buffer.subscriber_count(), 1);
+
+        buffer.write_batch(make_batch(1)).await.unwrap();
+
+        assert_eq!(closed.read_batch(10).await, Err(ReadError::Closed));
+        assert_eq!(live.read_batch(10).await.unwrap().len(), 1);
+        buffer.write_batch(make_batch(1)).await.unwrap();
+        assert_eq!(live.depth(), 1);
+    }
+}
@@ -5,10 +5,18 @@
 //! Unlike `InMemoryBuffer`, an empty buffer cooperatively yields rather than
 //! signaling `Closed`. Explicit `close()` signals end-of-data to readers.
 //! Designed for `tokio::join!` on a `current_thread` runtime.
+//!
+//! Reads drain exclusively, so several Consumers may share one buffer.
+//!
+//! Built with [`ConcurrentBuffer::with_latency_tracing`], the buffer also
+//! measures how long each transaction waited between write and read; see the
+//! `queue_latency` module.
 
 use std::cell::RefCell;
 
-use domain::{Buffer1, Buffer1Read, BufferError, Transaction};
+use domain::{Buffer1, Buffer1Read, BufferDepth, ReadError, Transaction, WriteError};
+
+use super::queue_latency::{LatencyStats, QueueLatency};
 
 // ---------------------------------------------------------------------------
 // Inner state
@@ -19,6 +27,8 @@ use domain::{Buffer1, Buffer1Read, BufferError, Transaction};
 struct ConcurrentBufferInner {
     data: Vec<Transaction>,
     closed: bool,
+    /// Queue delay tracker; `None` unless tracing was enabled.
+    latency: Option<QueueLatency>,
 }
 
 // ---------------------------------------------------------------------------
@@ -39,11 +49,25 @@ impl ConcurrentBuffer {
     /// Create an empty, open buffer.
     #[must_use]
     pub fn new() -> Self {
+        Self::with_latency_tracing(false)
+    }
+
+    /// Create an empty, open buffer; with `trace`, every write stamps its
+    /// transactions and every read records their queue delay.
+    #[must_use]
+    pub fn with_latency_tracing(trace: bool) -> Self {
+        let latency = trace.then(QueueLatency::new);
         Self {
-            inner: RefCell::new(ConcurrentBufferInner { data: vec![], closed: false }),
+            inner: RefCell::new(ConcurrentBufferInner { data: vec![], closed: false, latency }),
         }
     }
 
+    /// Queue delay percentiles so far; `None` without latency tracing.
+    #[must_use]
+    pub fn latency_stats(&self) -> Option<LatencyStats> {
+        self.inner.borrow().latency.as_ref().map(QueueLatency::stats)
+    }
+
     /// Signal end-of-data. Idempotent: safe to call multiple times.
     pub fn close(&self) {
         self.inner.borrow_mut().closed = true;
@@ -61,15 +85,22 @@ impl Buffer1 for ConcurrentBuffer {
     ///
     /// # Errors
     ///
-    /// Returns [`BufferError::Closed`] if the buffer has been closed.
-    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), BufferError> {
+    /// Returns [`WriteError::Closed`] if the buffer has been closed.
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
         let mut inner = self.inner.borrow_mut();
         if inner.closed {
-            return Err(BufferError::Closed);
+            return Err(WriteError::Closed);
+        }
+        if let Some(latency) = &mut inner.latency {
+            latency.enqueue(batch.iter().map(|tx| tx.id));
         }
         inner.data.extend(batch);
         Ok(())
     }
+
+    fn is_closed(&self) -> bool {
+        self.inner.borrow().closed
+    }
 }
 
 impl Buffer1Read for ConcurrentBuffer {
@@ -81,8 +112,8 @@ impl Buffer1Read for ConcurrentBuffer {
     ///
     /// # Errors
     ///
-    /// Returns [`BufferError::Closed`] when the buffer is empty and closed.
-    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, BufferError> {
+    /// Returns [`ReadError::Closed`] when the buffer is empty and closed.
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
         loop {
             // Scope the borrow so it is dropped before yield_now().await,
             // preventing a panic on re-entrant polling within tokio::join!.
@@ -90,9 +121,13 @@ impl Buffer1Read for ConcurrentBuffer {
                 let mut inner = self.inner.borrow_mut();
                 if !inner.data.is_empty() {
                     let count = max.min(inner.data.len());
-                    Some(Ok(inner.data.drain(..count).collect()))
+                    let batch: Vec<Transaction> = inner.data.drain(..count).collect();
+                    if let Some(latency) = &mut inner.latency {
+                        latency.dequeue(batch.iter().map(|tx| tx.id));
+                    }
+                    Some(Ok(batch))
                 } else if inner.closed {
-                    Some(Err(BufferError::Closed))
+                    Some(Err(ReadError::Closed))
                 } else {
                     None
                 }
@@ -106,6 +141,13 @@ impl Buffer1Read for ConcurrentBuffer {
     }
 }
 
+impl BufferDepth for ConcurrentBuffer {
+    /// Number of buffered transactions not yet read.
+    fn depth(&self) -> usize {
+        self.inner.borrow().data.len()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -113,11 +155,27 @@ impl Buffer1Read for ConcurrentBuffer {
 #[cfg(test)]
 mod tests {
     use super::ConcurrentBuffer;
-    use domain::{Buffer1 as _, Buffer1Read as _, BufferError, Transaction};
-    use uuid::Uuid;
+    use crate::adapters::concurrent_buffer2::ConcurrentBuffer2;
+    use crate::adapters::demo_model::DemoModel;
+    use crate::adapters::log_alarm::LogAlarm;
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{
+        Buffer1 as _, Buffer1Read as _, BufferDepth as _, Currency, ReadError, StopReason,
+        Transaction, TransactionId, WriteError,
+    };
+    use modelizer::Modelizer;
+    use producer::{Producer, ProducerConfig};
+    use std::collections::HashSet;
+    use std::time::Duration;
 
     fn make_tx() -> Transaction {
-        Transaction { id: Uuid::new_v4(), amount: 1.00_f64, last_name: "Test".to_owned() }
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.00_f64,
+            last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
     }
 
     fn make_txs(n: usize) -> Vec<Transaction> {
@@ -148,7 +206,7 @@ mod tests {
         buffer.close();
 
         let result = buffer.read_batch(1).await;
-        assert_eq!(result, Err(BufferError::Closed));
+        assert_eq!(result, Err(ReadError::Closed));
     }
 
     // CB-T03: writing to a closed buffer returns Err(Closed).
@@ -158,7 +216,7 @@ mod tests {
         buffer.close();
 
         let result = buffer.write_batch(make_txs(1)).await;
-        assert_eq!(result, Err(BufferError::Closed));
+        assert_eq!(result, Err(WriteError::Closed));
     }
 
     // CB-T04: successive reads drain from the front (FIFO order).
@@ -190,7 +248,7 @@ mod tests {
         buffer.close(); // must not panic
 
         let result = buffer.read_batch(1).await;
-        assert_eq!(result, Err(BufferError::Closed));
+        assert_eq!(result, Err(ReadError::Closed));
     }
 
     // CB-T06: read_batch yields on empty+open; a concurrent write unblocks it.
@@ -202,11 +260,108 @@ mod tests {
     async fn yield_unblocks_read() {
         let buffer = ConcurrentBuffer::new();
 
-        let (read_result, _) = tokio::join!(
+        let (read_result, ()) = tokio::join!(
             buffer.read_batch(1),
             async { buffer.write_batch(vec![make_tx()]).await.unwrap(); }
         );
 
         assert_eq!(read_result.unwrap().len(), 1);
     }
+
+    // CB-T07: depth tracks writes and partial reads.
+    #[tokio::test]
+    async fn depth_tracks_writes_and_reads() {
+        let buffer = ConcurrentBuffer::new();
+        assert_eq!(buffer.depth(), 0);
+
+        buffer.write_batch(make_txs(5)).await.unwrap();
+        assert_eq!(buffer.depth(), 5);
+
+        buffer.read_batch(2).await.unwrap();
+        assert_eq!(buffer.depth(), 3);
+    }
+
+    // CB-T08: concurrent readers drain every transaction exactly once.
+    #[tokio::test]
+    async fn satisfies_exclusive_drain_contract() {
+        let buffer = ConcurrentBuffer::new();
+        test_support::contract::buffer1_exclusive_drain(&buffer, || buffer.close()).await;
+    }
+
+    // CB-T09: two Consumers with different seeds share one Buffer1 and one
+    // Buffer2; together they infer every transaction exactly once.
+    #[tokio::test]
+    async fn two_consumers_split_the_work() {
+        let buffer1 = ConcurrentBuffer::new();
+        let buffer2 = ConcurrentBuffer2::new();
+        let modelizer = Modelizer::new(DemoModel::new(Some(4)));
+        let alarm = LogAlarm::new();
+        let consumer = |seed| {
+            let config = ConsumerConfig::builder(10)
+                .poll_interval2(Duration::from_millis(1))
+                .seed(seed)
+                .build()
+                .unwrap();
+            Consumer::new(config)
+        };
+        let (a, b) = (consumer(1), consumer(2));
+        let txs = make_txs(300);
+        let produced: HashSet<TransactionId> = txs.iter().map(|t| t.id).collect();
+
+        tokio::join!(
+            async {
+                for chunk in txs.chunks(20) {
+                    buffer1.write_batch(chunk.to_vec()).await.unwrap();
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+                buffer1.close();
+            },
+            async { a.run(&buffer1, &modelizer, &alarm, &buffer2).await.unwrap() },
+            async { b.run(&buffer1, &modelizer, &alarm, &buffer2).await.unwrap() },
+        );
+        buffer2.close();
+
+        let inferred = buffer2.peek(usize::MAX);
+        let ids: HashSet<TransactionId> = inferred.iter().map(|t| t.transaction.id).collect();
+        assert_eq!(inferred.len(), ids.len(), "no transaction may be inferred twice");
+        assert_eq!(ids, produced);
+        let (sa, sb) = (a.stats().transactions, b.stats().transactions);
+        assert!(sa > 0 && sb > 0, "both consumers must take work: {sa} / {sb}");
+        assert_eq!(sa + sb, 300);
+    }
+
+    // CB-T10: with latency tracing, delays between write and read give exact
+    // percentiles; without it there are no stats.
+    #[tokio::test(start_paused = true)]
+    async fn latency_tracing_measures_queue_delay() {
+        assert_eq!(ConcurrentBuffer::new().latency_stats(), None);
+        let buffer = ConcurrentBuffer::with_latency_tracing(true);
+        buffer.write_batch(make_txs(4)).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(3)).await;
+        buffer.read_batch(2).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(4)).await;
+        buffer.read_batch(2).await.unwrap();
+
+        let stats = buffer.latency_stats().unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.p50, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(7));
+        assert_eq!(stats.max, Duration::from_millis(7));
+    }
+
+    // CB-T11: a closed buffer stops the Producer before it generates a batch.
+    #[tokio::test]
+    async fn closed_buffer_stops_producer_before_generation() {
+        let buffer = ConcurrentBuffer::new();
+        buffer.close();
+        let config = ProducerConfig::builder(10).poll_interval1(Duration::ZERO).build().unwrap();
+        let producer = Producer::new(config);
+
+        let stop = producer.run(&buffer).await.unwrap();
+
+        assert_eq!(stop, StopReason::BufferClosed { iterations: 0 });
+        assert_eq!(producer.stats().batches, 0);
+        assert!(buffer.is_closed());
+    }
 }
@@ -5,10 +5,18 @@
 //! Unlike `InMemoryBuffer2`, an empty buffer cooperatively yields rather than
 //! signaling `Closed`. Explicit `close()` signals end-of-data to readers.
 //! Designed for `tokio::join!` on a `current_thread` runtime.
+//!
+//! `peek()` and `counts()` give monitoring tools a non-consuming view of the
+//! buffer; they never block and do not interact with the yield/close logic.
+//!
+//! [`ConcurrentBuffer2::with_latency_tracing`] measures queue delays like
+//! `ConcurrentBuffer` does.
 
 use std::cell::RefCell;
 
-use domain::{Buffer2, Buffer2Read, BufferError, InferredTransaction};
+use domain::{Buffer2, Buffer2Read, BufferDepth, InferredTransaction, ReadError, WriteError};
+
+use super::queue_latency::{LatencyStats, QueueLatency};
 
 // ---------------------------------------------------------------------------
 // Inner state
@@ -18,7 +26,28 @@ use domain::{Buffer2, Buffer2Read, BufferError, InferredTransaction};
 #[derive(Debug)]
 struct ConcurrentBuffer2Inner {
     data: Vec<InferredTransaction>,
+    /// Number of items in `data` with `predicted_fraud == true`, maintained
+    /// incrementally on write/read so `counts()` stays O(1).
+    flagged: usize,
     closed: bool,
+    /// Queue delay tracker; `None` unless tracing was enabled.
+    latency: Option<QueueLatency>,
+}
+
+/// Point-in-time occupancy of a [`ConcurrentBuffer2`].
+// Inspection API for monitoring tools; no binary reads it yet.
+#[allow(dead_code, reason = "inspection API for monitoring tools; not yet used by a binary")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferCounts {
+    /// Number of inferred transactions waiting to be read.
+    pub depth: usize,
+    /// How many of those are flagged as fraudulent.
+    pub flagged: usize,
+}
+
+/// Number of fraud-flagged items in `items`.
+fn count_flagged(items: &[InferredTransaction]) -> usize {
+    items.iter().filter(|tx| tx.predicted_fraud).count()
 }
 
 // ---------------------------------------------------------------------------
@@ -39,11 +68,47 @@ impl ConcurrentBuffer2 {
     /// Create an empty, open buffer.
     #[must_use]
     pub fn new() -> Self {
+        Self::with_latency_tracing(false)
+    }
+
+    /// Create an empty, open buffer; with `trace`, every write stamps its
+    /// transactions and every read records their queue delay.
+    #[must_use]
+    pub fn with_latency_tracing(trace: bool) -> Self {
+        let latency = trace.then(QueueLatency::new);
         Self {
-            inner: RefCell::new(ConcurrentBuffer2Inner { data: vec![], closed: false }),
+            inner: RefCell::new(ConcurrentBuffer2Inner {
+                data: vec![],
+                flagged: 0,
+                closed: false,
+                latency,
+            }),
         }
     }
 
+    /// Queue delay percentiles so far; `None` without latency tracing.
+    #[must_use]
+    pub fn latency_stats(&self) -> Option<LatencyStats> {
+        self.inner.borrow().latency.as_ref().map(QueueLatency::stats)
+    }
+
+    /// Clone up to `max` items from the front without consuming them.
+    // See BufferCounts allow(dead_code) comment above.
+    #[allow(dead_code, reason = "inspection API for monitoring tools; not yet used by a binary")]
+    #[must_use]
+    pub fn peek(&self, max: usize) -> Vec<InferredTransaction> {
+        self.inner.borrow().data.iter().take(max).cloned().collect()
+    }
+
+    /// Current depth and flagged count; O(1).
+    // See BufferCounts allow(dead_code) comment above.
+    #[allow(dead_code, reason = "inspection API for monitoring tools; not yet used by a binary")]
+    #[must_use]
+    pub fn counts(&self) -> BufferCounts {
+        let inner = self.inner.borrow();
+        BufferCounts { depth: inner.data.len(), flagged: inner.flagged }
+    }
+
     /// Signal end-of-data. Idempotent: safe to call multiple times.
     pub fn close(&self) {
         self.inner.borrow_mut().closed = true;
@@ -61,11 +126,15 @@ impl Buffer2 for ConcurrentBuffer2 {
     ///
     /// # Errors
     ///
-    /// Returns [`BufferError::Closed`] if the buffer has been closed.
-    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), BufferError> {
+    /// Returns [`WriteError::Closed`] if the buffer has been closed.
+    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), WriteError> {
         let mut inner = self.inner.borrow_mut();
         if inner.closed {
-            return Err(BufferError::Closed);
+            return Err(WriteError::Closed);
+        }
+        inner.flagged += count_flagged(&batch);
+        if let Some(latency) = &mut inner.latency {
+            latency.enqueue(batch.iter().map(InferredTransaction::id));
         }
         inner.data.extend(batch);
         Ok(())
@@ -81,8 +150,8 @@ impl Buffer2Read for ConcurrentBuffer2 {
     ///
     /// # Errors
     ///
-    /// Returns [`BufferError::Closed`] when the buffer is empty and closed.
-    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, BufferError> {
+    /// Returns [`ReadError::Closed`] when the buffer is empty and closed.
+    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
         loop {
             // Scope the borrow so it is dropped before yield_now().await,
             // preventing a panic on re-entrant polling within tokio::join!.
@@ -90,9 +159,14 @@ impl Buffer2Read for ConcurrentBuffer2 {
                 let mut inner = self.inner.borrow_mut();
                 if !inner.data.is_empty() {
                     let count = max.min(inner.data.len());
-                    Some(Ok(inner.data.drain(..count).collect()))
+                    let batch: Vec<InferredTransaction> = inner.data.drain(..count).collect();
+                    inner.flagged -= count_flagged(&batch);
+                    if let Some(latency) = &mut inner.latency {
+                        latency.dequeue(batch.iter().map(InferredTransaction::id));
+                    }
+                    Some(Ok(batch))
                 } else if inner.closed {
-                    Some(Err(BufferError::Closed))
+                    Some(Err(ReadError::Closed))
                 } else {
                     None
                 }
@@ -106,26 +180,40 @@ impl Buffer2Read for ConcurrentBuffer2 {
     }
 }
 
+impl BufferDepth for ConcurrentBuffer2 {
+    /// Number of buffered inferred transactions not yet read.
+    fn depth(&self) -> usize {
+        self.inner.borrow().data.len()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
 
 #[cfg(test)]
 mod tests {
-    use super::ConcurrentBuffer2;
-    use domain::{Buffer2 as _, Buffer2Read as _, BufferError, InferredTransaction, Transaction};
-    use uuid::Uuid;
+    use super::{BufferCounts, ConcurrentBuffer2};
+    use domain::{
+        Buffer2 as _, Buffer2Read as _, BufferDepth as _, Currency, InferredTransaction, ReadError,
+        Transaction, TransactionId, WriteError,
+    };
+    use std::time::Duration;
 
     fn make_inferred() -> InferredTransaction {
         InferredTransaction {
             transaction: Transaction {
-                id: Uuid::new_v4(),
+                id: TransactionId::new_v4(),
                 amount: 1.00_f64,
                 last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
             },
             predicted_fraud: false,
             model_name: "DEMO".to_owned(),
             model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
         }
     }
 
@@ -133,12 +221,20 @@ mod tests {
         (0..n).map(|_| make_inferred()).collect()
     }
 
+    /// `flags[i]` sets `predicted_fraud` on the i-th item.
+    fn make_mixed(flags: &[bool]) -> Vec<InferredTransaction> {
+        flags
+            .iter()
+            .map(|&f| InferredTransaction { predicted_fraud: f, ..make_inferred() })
+            .collect()
+    }
+
     // CB2-T01: write/read roundtrip preserves all items.
     #[tokio::test]
     async fn write_read_roundtrip() {
         let buffer = ConcurrentBuffer2::new();
         let items = make_batch(3);
-        let ids: Vec<_> = items.iter().map(|t| t.id()).collect();
+        let ids: Vec<_> = items.iter().map(InferredTransaction::id).collect();
 
         buffer.write_batch(items).await.unwrap();
         buffer.close();
@@ -157,7 +253,7 @@ mod tests {
         buffer.close();
 
         let result = buffer.read_batch(1).await;
-        assert_eq!(result, Err(BufferError::Closed));
+        assert_eq!(result, Err(ReadError::Closed));
     }
 
     // CB2-T03: writing to a closed buffer returns Err(Closed).
@@ -167,7 +263,7 @@ mod tests {
         buffer.close();
 
         let result = buffer.write_batch(make_batch(1)).await;
-        assert_eq!(result, Err(BufferError::Closed));
+        assert_eq!(result, Err(WriteError::Closed));
     }
 
     // CB2-T04: successive reads drain from the front (FIFO order).
@@ -175,7 +271,7 @@ mod tests {
     async fn drain_from_front() {
         let buffer = ConcurrentBuffer2::new();
         let items = make_batch(4);
-        let ids: Vec<_> = items.iter().map(|t| t.id()).collect();
+        let ids: Vec<_> = items.iter().map(InferredTransaction::id).collect();
 
         buffer.write_batch(items).await.unwrap();
         buffer.close();
@@ -199,7 +295,7 @@ mod tests {
         buffer.close(); // must not panic
 
         let result = buffer.read_batch(1).await;
-        assert_eq!(result, Err(BufferError::Closed));
+        assert_eq!(result, Err(ReadError::Closed));
     }
 
     // CB2-T06: read_batch yields on empty+open; a concurrent write unblocks it.
@@ -207,11 +303,95 @@ mod tests {
     async fn yield_unblocks_read() {
         let buffer = ConcurrentBuffer2::new();
 
-        let (read_result, _) = tokio::join!(
+        let (read_result, ()) = tokio::join!(
             buffer.read_batch(1),
             async { buffer.write_batch(vec![make_inferred()]).await.unwrap(); }
         );
 
         assert_eq!(read_result.unwrap().len(), 1);
     }
+
+    // CB2-T07: depth tracks writes and partial reads.
+    #[tokio::test]
+    async fn depth_tracks_writes_and_reads() {
+        let buffer = ConcurrentBuffer2::new();
+        assert_eq!(buffer.depth(), 0);
+
+        buffer.write_batch(make_batch(5)).await.unwrap();
+        assert_eq!(buffer.depth(), 5);
+
+        buffer.read_batch(2).await.unwrap();
+        assert_eq!(buffer.depth(), 3);
+    }
+
+    // CB2-T08: peek clones without consuming; a later full read sees everything.
+    #[tokio::test]
+    async fn peek_does_not_consume() {
+        let buffer = ConcurrentBuffer2::new();
+        let items = make_mixed(&[true, false, true, false]);
+        let ids: Vec<_> = items.iter().map(InferredTransaction::id).collect();
+        buffer.write_batch(items).await.unwrap();
+
+        let peeked = buffer.peek(2);
+        assert_eq!(peeked.iter().map(InferredTransaction::id).collect::<Vec<_>>(), ids[..2]);
+        assert_eq!(buffer.peek(10).len(), 4);
+
+        let read = buffer.read_batch(10).await.unwrap();
+        assert_eq!(read.iter().map(InferredTransaction::id).collect::<Vec<_>>(), ids);
+    }
+
+    // CB2-T09: counts track depth and flagged across partial reads.
+    #[tokio::test]
+    async fn counts_track_partial_reads() {
+        let buffer = ConcurrentBuffer2::new();
+        assert_eq!(buffer.counts(), BufferCounts { depth: 0, flagged: 0 });
+
+        buffer.write_batch(make_mixed(&[true, true, false, true, false])).await.unwrap();
+        assert_eq!(buffer.counts(), BufferCounts { depth: 5, flagged: 3 });
+
+        // Front two are both flagged.
+        buffer.read_batch(2).await.unwrap();
+        assert_eq!(buffer.counts(), BufferCounts { depth: 3, flagged: 1 });
+
+        buffer.write_batch(make_mixed(&[true])).await.unwrap();
+        assert_eq!(buffer.counts(), BufferCounts { depth: 4, flagged: 2 });
+
+        buffer.read_batch(10).await.unwrap();
+        assert_eq!(buffer.counts(), BufferCounts { depth: 0, flagged: 0 });
+    }
+
+    // CB2-T10: close does not break peek or counts, and read still drains.
+    #[tokio::test]
+    async fn close_keeps_peek_working() {
+        let buffer = ConcurrentBuffer2::new();
+        buffer.write_batch(make_mixed(&[false, true])).await.unwrap();
+        buffer.close();
+
+        assert_eq!(buffer.peek(10).len(), 2);
+        assert_eq!(buffer.counts(), BufferCounts { depth: 2, flagged: 1 });
+        assert_eq!(buffer.read_batch(10).await.unwrap().len(), 2);
+        assert_eq!(buffer.read_batch(1).await, Err(ReadError::Closed));
+        assert!(buffer.peek(10).is_empty());
+    }
+
+    // CB2-T11: with latency tracing, each read records the delay since the
+    // write of its items.
+    #[tokio::test(start_paused = true)]
+    async fn latency_tracing_measures_queue_delay() {
+        assert_eq!(ConcurrentBuffer2::new().latency_stats(), None);
+        let buffer = ConcurrentBuffer2::with_latency_tracing(true);
+        buffer.write_batch(make_mixed(&[true, false])).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        buffer.write_batch(make_mixed(&[false])).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        buffer.read_batch(10).await.unwrap();
+
+        let stats = buffer.latency_stats().unwrap();
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.p50, Duration::from_millis(6));
+        assert_eq!(stats.p99, Duration::from_millis(6));
+        // The peeked-at counters are unaffected by tracing.
+        assert_eq!(buffer.counts(), BufferCounts { depth: 0, flagged: 0 });
+    }
 }
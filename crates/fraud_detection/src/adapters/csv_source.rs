@@ -0,0 +1,546 @@
+// Rust guideline compliant 2026-02-27
+
+//! Labelled CSV fraud datasets in the Kaggle `creditcard.csv` layout.
+//!
+//! The file has a header row naming `Time`, `V1` .. `V28` (anonymized PCA
+//! components), `Amount` and `Class` (`1` for fraud), in any order; values
+//! may be quoted. [`CsvDataset::open`] parses it once:
+//!
+//! - each row becomes a `Transaction` in EUR with a deterministic id (its
+//!   line number) and an anonymized last name; `Time` is ignored;
+//! - the V-columns are kept on the side, by id, and reach models through the
+//!   [`DatasetFeatures`] extractor: the `Transaction` type stays unchanged,
+//!   so adapters that ignore features (e.g. `DemoModel`) are unaffected;
+//! - `Class` is the ground truth [`CsvDataset::evaluate`] compares
+//!   predictions with, as an [`Evaluation`] (confusion matrix, accuracy,
+//!   precision, recall).
+//!
+//! A row with the wrong number of columns, a non-numeric value, a negative
+//! amount or a `Class` other than 0/1 is skipped with a
+//! `csv_source.row_skipped` warning and counted in
+//! [`CsvDataset::skipped`]. [`CsvSource`] then streams the transactions to
+//! the Consumer as a `Buffer1Read`.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use domain::{
+    Buffer1Read, Currency, FeatureExtractor, Features, InferredTransaction, ReadError,
+    Transaction, TransactionId,
+};
+
+/// `tracing` target of every buffer event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::BUFFER;
+
+/// PCA components of a row, `V1` .. `V28`.
+const V_COLUMNS: usize = 28;
+
+/// Last name of every dataset transaction: the dataset has none.
+const ANONYMIZED: &str = "ANONYMIZED";
+
+/// Names of the [`DatasetFeatures`], in extraction order.
+pub const FEATURE_NAMES: [&str; V_COLUMNS + 1] = [
+    "V1", "V2", "V3", "V4", "V5", "V6", "V7", "V8", "V9", "V10", "V11", "V12", "V13", "V14",
+    "V15", "V16", "V17", "V18", "V19", "V20", "V21", "V22", "V23", "V24", "V25", "V26", "V27",
+    "V28", "amount",
+];
+
+/// One parsed row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRow {
+    /// The row as a pipeline transaction.
+    pub transaction: Transaction,
+    /// `V1` .. `V28`.
+    pub features: Vec<f64>,
+    /// `Class`: whether the transaction was fraud.
+    pub class: bool,
+}
+
+/// Column index of every field a row is parsed from.
+struct Columns {
+    v: [usize; V_COLUMNS],
+    amount: usize,
+    class: usize,
+    count: usize,
+}
+
+impl Columns {
+    /// Locate the columns in `header`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first required column the header lacks.
+    fn locate(header: &str) -> anyhow::Result<Self> {
+        let names: Vec<&str> = header.split(',').map(unquote).collect();
+        let find = |name: &str| {
+            names
+                .iter()
+                .position(|&column| column == name)
+                .with_context(|| format!("missing column {name}"))
+        };
+        let mut v = [0; V_COLUMNS];
+        for (i, slot) in v.iter_mut().enumerate() {
+            *slot = find(FEATURE_NAMES[i])?;
+        }
+        Ok(Self { v, amount: find("Amount")?, class: find("Class")?, count: names.len() })
+    }
+
+    /// Parse the fields of line `line`, or say why it is malformed.
+    fn parse(&self, line: u64, fields: &[&str]) -> Result<CsvRow, String> {
+        if fields.len() != self.count {
+            return Err(format!("{} columns, expected {}", fields.len(), self.count));
+        }
+        let number = |index: usize| {
+            let value = unquote(fields[index]);
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .ok_or_else(|| format!("column {} is not a number: {value:?}", index + 1))
+        };
+        let features = self.v.iter().map(|&index| number(index)).collect::<Result<_, _>>()?;
+        let amount = number(self.amount)?;
+        if amount < 0.0 {
+            return Err(format!("negative amount {amount}"));
+        }
+        let class = match unquote(fields[self.class]) {
+            "0" => false,
+            "1" => true,
+            other => return Err(format!("class {other:?} is neither 0 nor 1")),
+        };
+        let transaction = Transaction {
+            id: TransactionId::from_uuid(uuid::Uuid::from_u128(u128::from(line))),
+            amount,
+            last_name: ANONYMIZED.to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
+        Ok(CsvRow { transaction, features, class })
+    }
+}
+
+/// `value` without surrounding whitespace and double quotes.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value)
+}
+
+/// A parsed labelled dataset.
+#[derive(Debug, Clone)]
+pub struct CsvDataset {
+    rows: Vec<CsvRow>,
+    skipped: usize,
+}
+
+impl CsvDataset {
+    /// Parse the CSV file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or its header lacks a
+    /// required column; malformed rows are skipped instead.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        Self::parse(BufReader::new(file))
+            .with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// Parse a CSV document from `reader`.
+    ///
+    /// # Errors
+    ///
+    /// As [`open`](Self::open).
+    pub fn parse(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut lines = reader.lines();
+        let header = lines.next().context("empty file")?.context("failed to read the header")?;
+        let columns = Columns::locate(&header)?;
+        let (mut rows, mut skipped) = (Vec::new(), 0);
+        // Line 1 is the header.
+        for (line, text) in (2..).zip(lines) {
+            let text = text.with_context(|| format!("failed to read line {line}"))?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = text.split(',').collect();
+            match columns.parse(line, &fields) {
+                Ok(row) => rows.push(row),
+                Err(reason) => {
+                    skipped += 1;
+                    tracing::warn!(target: TARGET, line, %reason, "csv_source.row_skipped");
+                }
+            }
+        }
+        Ok(Self { rows, skipped })
+    }
+
+    /// Rows parsed, in file order.
+    #[must_use]
+    pub fn rows(&self) -> &[CsvRow] {
+        &self.rows
+    }
+
+    /// Malformed rows skipped.
+    #[must_use]
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// A source streaming every transaction, in file order.
+    #[must_use]
+    pub fn source(&self) -> CsvSource {
+        CsvSource {
+            pending: RefCell::new(self.rows.iter().map(|row| row.transaction.clone()).collect()),
+        }
+    }
+
+    /// An extractor surfacing the V-columns of these rows to a model.
+    #[must_use]
+    pub fn features(&self) -> DatasetFeatures {
+        let by_id = self.rows.iter().map(|row| (row.transaction.id, row.features.clone()));
+        DatasetFeatures { by_id: Arc::new(by_id.collect()) }
+    }
+
+    /// Compare the predictions of `inferred` with the `Class` of their row;
+    /// transactions not from this dataset count as unlabelled.
+    pub fn evaluate<'a>(
+        &self,
+        inferred: impl IntoIterator<Item = &'a InferredTransaction>,
+    ) -> Evaluation {
+        let labels: HashMap<TransactionId, bool> =
+            self.rows.iter().map(|row| (row.transaction.id, row.class)).collect();
+        let mut evaluation = Evaluation::default();
+        for it in inferred {
+            match labels.get(&it.id()) {
+                Some(&actual) => evaluation.record(it.predicted_fraud, actual),
+                None => evaluation.unlabelled += 1,
+            }
+        }
+        evaluation
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CsvSource
+// ---------------------------------------------------------------------------
+
+/// `Buffer1Read` adapter streaming the transactions of a [`CsvDataset`].
+///
+/// Every read after the last transaction returns `ReadError::Closed`.
+#[derive(Debug)]
+pub struct CsvSource {
+    pending: RefCell<VecDeque<Transaction>>,
+}
+
+impl CsvSource {
+    /// Transactions not read yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.pending.borrow().len()
+    }
+}
+
+impl Buffer1Read for CsvSource {
+    /// Take the next `max` transactions at most.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::Closed` once every transaction was read.
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+        let mut pending = self.pending.borrow_mut();
+        if pending.is_empty() {
+            return Err(ReadError::Closed);
+        }
+        let n = max.min(pending.len());
+        Ok(pending.drain(..n).collect())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DatasetFeatures
+// ---------------------------------------------------------------------------
+
+/// `FeatureExtractor` returning the V-columns of a dataset row, then the
+/// amount (see [`FEATURE_NAMES`]).
+///
+/// A transaction that is not from the dataset gets zeros for the V-columns.
+#[derive(Clone)]
+pub struct DatasetFeatures {
+    by_id: Arc<HashMap<TransactionId, Vec<f64>>>,
+}
+
+impl fmt::Debug for DatasetFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DatasetFeatures").field("rows", &self.by_id.len()).finish()
+    }
+}
+
+impl FeatureExtractor for DatasetFeatures {
+    fn extract(&self, tx: &Transaction) -> Features {
+        let mut values = self.by_id.get(&tx.id).cloned().unwrap_or_else(|| vec![0.0; V_COLUMNS]);
+        values.push(tx.amount);
+        Features(values)
+    }
+
+    fn names(&self) -> &[&'static str] {
+        &FEATURE_NAMES
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+/// Predictions against ground truth, as returned by [`CsvDataset::evaluate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Evaluation {
+    /// Fraud flagged as fraud.
+    pub true_positives: u64,
+    /// Legitimate transactions flagged as fraud.
+    pub false_positives: u64,
+    /// Legitimate transactions left unflagged.
+    pub true_negatives: u64,
+    /// Fraud left unflagged.
+    pub false_negatives: u64,
+    /// Predictions without a label.
+    pub unlabelled: u64,
+}
+
+impl Evaluation {
+    /// Count one labelled prediction.
+    pub fn record(&mut self, predicted: bool, actual: bool) {
+        let count = match (predicted, actual) {
+            (true, true) => &mut self.true_positives,
+            (true, false) => &mut self.false_positives,
+            (false, false) => &mut self.true_negatives,
+            (false, true) => &mut self.false_negatives,
+        };
+        *count += 1;
+    }
+
+    /// Labelled predictions.
+    #[must_use]
+    pub fn labelled(&self) -> u64 {
+        self.true_positives + self.false_positives + self.true_negatives + self.false_negatives
+    }
+
+    /// Share of labelled predictions that were right; `None` without any.
+    #[must_use]
+    pub fn accuracy(&self) -> Option<f64> {
+        ratio(self.true_positives + self.true_negatives, self.labelled())
+    }
+
+    /// Share of flagged transactions that were fraud; `None` if none was flagged.
+    #[must_use]
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    /// Share of fraud that was flagged; `None` without fraud.
+    #[must_use]
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_negatives)
+    }
+}
+
+#[expect(clippy::cast_precision_loss, reason = "dataset row counts fit f64 exactly")]
+fn ratio(part: u64, whole: u64) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+impl fmt::Display for Evaluation {
+    /// `evaluation: N labelled, accuracy A%, precision P%, recall R%
+    /// (tp .., fp .., tn .., fn ..)`; `n/a` for an undefined ratio.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent =
+            |r: Option<f64>| r.map_or_else(|| "n/a".to_owned(), |r| format!("{:.1}%", r * 100.0));
+        write!(
+            f,
+            "evaluation: {} labelled, accuracy {}, precision {}, recall {} \
+             (tp {}, fp {}, tn {}, fn {})",
+            self.labelled(),
+            percent(self.accuracy()),
+            percent(self.precision()),
+            percent(self.recall()),
+            self.true_positives,
+            self.false_positives,
+            self.true_negatives,
+            self.false_negatives
+        )?;
+        if self.unlabelled > 0 {
+            write!(f, ", {} unlabelled", self.unlabelled)?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvDataset, DatasetFeatures, Evaluation, FEATURE_NAMES};
+    use domain::{
+        Buffer1Read, FeatureExtractor, Features, InferredTransaction, Model, ModelVersion,
+        ModelizerError, Modelizer as _, ReadError, Transaction,
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/creditcard_sample.csv");
+
+    fn fixture() -> CsvDataset {
+        CsvDataset::parse(FIXTURE.as_bytes()).unwrap()
+    }
+
+    /// Model recording the features of every transaction it classifies;
+    /// flags those above `threshold`.
+    #[derive(Debug)]
+    struct FeatureModel {
+        extractor: DatasetFeatures,
+        seen: Rc<RefCell<Vec<Features>>>,
+        threshold: f64,
+    }
+
+    impl Model for FeatureModel {
+        async fn classify(&self, tx: &Transaction) -> Result<bool, ModelizerError> {
+            self.seen.borrow_mut().push(self.extractor.extract(tx));
+            Ok(tx.amount > self.threshold)
+        }
+
+        fn name(&self) -> &'static str {
+            "FEATURES"
+        }
+
+        fn active_version(&self) -> &'static str {
+            "1"
+        }
+
+        async fn switch_version(&self, _version: ModelVersion) -> Result<(), ModelizerError> {
+            Ok(())
+        }
+    }
+
+    // CS-T01: the 20 well-formed rows parse with their features and labels;
+    // the 3 malformed ones are counted.
+    #[test]
+    fn fixture_parses_rows_features_and_labels() {
+        let dataset = fixture();
+
+        assert_eq!(dataset.rows().len(), 20);
+        assert_eq!(dataset.skipped(), 3);
+        assert_eq!(dataset.rows().iter().filter(|row| row.class).count(), 4);
+        let first = &dataset.rows()[0];
+        assert_eq!(first.features.len(), 28);
+        assert!((first.features[0] - 2.935_109).abs() < 1e-9);
+        assert_eq!(first.transaction.last_name, "ANONYMIZED");
+        // Ids are unique and stable across parses.
+        let ids: std::collections::HashSet<_> =
+            dataset.rows().iter().map(|row| row.transaction.id).collect();
+        assert_eq!(ids.len(), 20);
+        assert_eq!(fixture().rows()[7].transaction.id, dataset.rows()[7].transaction.id);
+    }
+
+    // CS-T02: each kind of malformed row is skipped, and a header without a
+    // required column is an error.
+    #[test]
+    fn malformed_rows_are_skipped_and_counted() {
+        let mut lines = FIXTURE.lines();
+        let (header, row) = (lines.next().unwrap(), lines.next().unwrap());
+        let with_field = |index: usize, value: &str| {
+            let mut fields: Vec<&str> = row.split(',').collect();
+            fields[index] = value;
+            fields.join(",")
+        };
+        let csv = [
+            header.to_owned(),
+            row.to_owned(),
+            "1,2,3".to_owned(),
+            with_field(1, "x"),
+            with_field(29, "-1.0"),
+            with_field(30, "\"7\""),
+        ]
+        .join("\n");
+
+        let dataset = CsvDataset::parse(csv.as_bytes()).unwrap();
+
+        assert_eq!(dataset.rows().len(), 1);
+        assert_eq!(dataset.skipped(), 4);
+        let missing = CsvDataset::parse("\"Time\",\"V1\",\"Amount\"\n".as_bytes()).unwrap_err();
+        assert_eq!(missing.to_string(), "missing column V2");
+    }
+
+    // CS-T03: the features reach a model intact, through the source and the
+    // Modelizer.
+    #[tokio::test]
+    async fn features_reach_the_model_intact() {
+        let dataset = fixture();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let model = FeatureModel {
+            extractor: dataset.features(),
+            seen: Rc::clone(&seen),
+            threshold: f64::MAX,
+        };
+        let modelizer = modelizer::Modelizer::new(model);
+        let source = dataset.source();
+
+        let batch = source.read_batch(usize::MAX).await.unwrap();
+        modelizer.infer(batch).await.unwrap();
+
+        assert_eq!(source.read_batch(1).await, Err(ReadError::Closed));
+        let expected: Vec<Features> = dataset
+            .rows()
+            .iter()
+            .map(|row| {
+                let mut values = row.features.clone();
+                values.push(row.transaction.amount);
+                Features(values)
+            })
+            .collect();
+        assert_eq!(*seen.borrow(), expected);
+        assert_eq!(dataset.features().names(), FEATURE_NAMES);
+    }
+
+    // CS-T04: the evaluation compares each prediction with its row's Class.
+    #[test]
+    fn evaluation_counts_the_confusion_matrix() {
+        let dataset = fixture();
+        let predict = |row: &super::CsvRow, predicted_fraud| InferredTransaction {
+            transaction: row.transaction.clone(),
+            predicted_fraud,
+            model_name: "TEST".to_owned(),
+            model_version: "1".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        };
+        // Flag the first 6 rows; only row 3 of them is fraud.
+        let inferred: Vec<_> =
+            dataset.rows().iter().enumerate().map(|(i, row)| predict(row, i < 6)).collect();
+
+        let evaluation = dataset.evaluate(&inferred);
+
+        assert_eq!(
+            evaluation,
+            Evaluation {
+                true_positives: 1,
+                false_positives: 5,
+                true_negatives: 11,
+                false_negatives: 3,
+                unlabelled: 0,
+            }
+        );
+        assert_eq!(
+            evaluation.to_string(),
+            "evaluation: 20 labelled, accuracy 60.0%, precision 16.7%, recall 25.0% \
+             (tp 1, fp 5, tn 11, fn 3)"
+        );
+        assert_eq!(Evaluation::default().accuracy(), None);
+    }
+}
@@ -0,0 +1,360 @@
+// Rust guideline compliant 2026-02-27
+
+//! Append-only CSV adapter for the `Storage` port, for output that opens in a
+//! spreadsheet without tooling.
+//!
+//! [`CsvStorage`] appends one row per [`PendingTransaction`] to its file and
+//! writes the [`HEADER`] only when it creates the file (or finds it empty),
+//! so a restarted pipeline keeps appending under the same header. Text
+//! fields are quoted as RFC 4180 requires and rows end with CRLF. Each batch
+//! is encoded in memory first, then appended and flushed in one write: a
+//! record that cannot be encoded fails the batch before the file is touched.
+//!
+//! With [`CsvStorage::daily_rotation`], `out.csv` becomes one file per UTC
+//! day, `out-YYYY-MM-DD.csv`, picked from the clock when each batch is
+//! written.
+//!
+//! Failures carry the file: `StorageError::Io` (retryable, e.g. the file is
+//! locked by the spreadsheet that has it open) and `StorageError::Encoding`.
+
+use std::cell::Cell;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use domain::{Clock, PendingTransaction, Storage, StorageError, SystemClock};
+use tokio::io::AsyncWriteExt as _;
+
+/// `tracing` target of every storage event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::STORAGE;
+
+/// First line of every file, in column order.
+pub const HEADER: &str = "id,amount,last_name,currency,predicted_fraud,model_name,\
+                          model_version,is_reviewed,actual_fraud,record_version,persisted_at,\
+                          reviewed_at,run_id";
+
+/// `Storage` adapter appending rows to a CSV file.
+#[derive(Debug)]
+pub struct CsvStorage {
+    path: PathBuf,
+    daily: bool,
+    clock: Arc<dyn Clock>,
+    rows: Cell<u64>,
+}
+
+impl CsvStorage {
+    /// Append to `path`, creating it with a header on the first write.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), daily: false, clock: Arc::new(SystemClock), rows: Cell::new(0) }
+    }
+
+    /// Write to one file per UTC day: `out.csv` becomes `out-YYYY-MM-DD.csv`.
+    #[must_use]
+    pub fn daily_rotation(mut self) -> Self {
+        self.daily = true;
+        self
+    }
+
+    /// Pick the daily file from `clock` instead of the system clock.
+    #[must_use]
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Rows appended since construction.
+    #[must_use]
+    pub fn rows_written(&self) -> u64 {
+        self.rows.get()
+    }
+
+    /// File the next batch goes to.
+    #[must_use]
+    pub fn current_path(&self) -> PathBuf {
+        if !self.daily {
+            return self.path.clone();
+        }
+        let (year, month, day) = civil_date(self.clock.now());
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = self.path.extension().map_or("csv".into(), |e| e.to_string_lossy());
+        self.path.with_file_name(format!("{stem}-{year:04}-{month:02}-{day:02}.{extension}"))
+    }
+}
+
+impl Storage for CsvStorage {
+    /// Append `batch`, with the header first if the file is new or empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Encoding` when a record cannot be written as
+    /// CSV (nothing is appended), or `StorageError::Io` when the file cannot
+    /// be opened, written or flushed.
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let path = self.current_path();
+        let mut rows = String::new();
+        for pending in &batch {
+            encode_row(&mut rows, pending).map_err(|field| StorageError::Encoding {
+                path: path.clone(),
+                id: pending.id(),
+                field,
+            })?;
+        }
+
+        let io = |e: std::io::Error| StorageError::Io { path: path.clone(), kind: e.kind() };
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(io)?;
+        if file.metadata().await.map_err(io)?.len() == 0 {
+            rows.insert_str(0, &format!("{HEADER}\r\n"));
+        }
+        file.write_all(rows.as_bytes()).await.map_err(io)?;
+        file.flush().await.map_err(io)?;
+
+        self.rows.set(self.rows.get() + batch.len() as u64);
+        tracing::debug!(
+            target: TARGET,
+            path = %path.display(),
+            rows = batch.len(),
+            "csv_storage.appended"
+        );
+        Ok(())
+    }
+}
+
+/// Append the CSV row of `pending` to `out`; on failure, name the field.
+fn encode_row(out: &mut String, pending: &PendingTransaction) -> Result<(), &'static str> {
+    let inferred = &pending.inferred_transaction;
+    let tx = &inferred.transaction;
+    if !tx.amount.is_finite() {
+        return Err("amount");
+    }
+    let persisted_at = timestamp(pending.persisted_at).ok_or("persisted_at")?;
+    let reviewed_at = timestamp(pending.reviewed_at).ok_or("reviewed_at")?;
+    let actual_fraud = pending.actual_fraud.map(|f| f.to_string()).unwrap_or_default();
+    let run_id = pending.run_id.map(|id| id.0.to_string()).unwrap_or_default();
+    let _ = write!(
+        out,
+        "{},{},{},{},{},{},{},{},{actual_fraud},{},{persisted_at},{reviewed_at},{run_id}\r\n",
+        tx.id.full(),
+        tx.amount,
+        quote(&tx.last_name),
+        tx.currency.code(),
+        inferred.predicted_fraud,
+        quote(&inferred.model_name),
+        quote(&inferred.model_version),
+        pending.is_reviewed,
+        pending.record_version,
+    );
+    Ok(())
+}
+
+/// RFC 4180 field: quoted, with quotes doubled, when it holds a comma, a
+/// quote or a line break; as is otherwise.
+fn quote(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// `2026-03-01T12:34:56.789Z`, empty for `None`; `None` before the epoch.
+fn timestamp(time: Option<SystemTime>) -> Option<String> {
+    let Some(time) = time else { return Some(String::new()) };
+    let since = time.duration_since(UNIX_EPOCH).ok()?;
+    let (year, month, day) = civil_date(time);
+    let secs = since.as_secs() % 86_400;
+    Some(format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        since.subsec_millis()
+    ))
+}
+
+/// UTC calendar date of `time`; the epoch for earlier times.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss,
+    reason = "days since 1970 fit an i64; month and day are in 1..=31"
+)]
+fn civil_date(time: SystemTime) -> (i64, u32, u32) {
+    let days = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / 86_400) as i64;
+    // Days-to-civil over 400-year eras, counted from 0000-03-01.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvStorage, HEADER, civil_date};
+    use domain::{
+        Clock, Currency, InferredTransaction, PendingTransaction, RECORD_VERSION, Storage,
+        StorageError, Transaction, TransactionId,
+    };
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// Clock whose time (seconds since epoch) is set by the test.
+    #[derive(Debug, Clone)]
+    struct ManualClock(Arc<AtomicU64>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> SystemTime {
+            UNIX_EPOCH + Duration::from_secs(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("csv-storage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn pending(last_name: &str) -> PendingTransaction {
+        PendingTransaction::new(InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: 12.5,
+                last_name: last_name.to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud: false,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        })
+    }
+
+    fn batch(n: usize) -> Vec<PendingTransaction> {
+        (0..n).map(|_| pending("Smith")).collect()
+    }
+
+    /// Data lines of `path`: every CRLF-terminated line but the header.
+    fn data_lines(path: &PathBuf) -> Vec<String> {
+        let text = std::fs::read_to_string(path).unwrap();
+        let mut lines = text.split_terminator("\r\n").map(str::to_owned);
+        assert_eq!(lines.next().as_deref(), Some(HEADER));
+        lines.collect()
+    }
+
+    // CV-T01: a restarted storage appends under the existing header.
+    #[tokio::test]
+    async fn header_is_written_once_across_restarts() {
+        let dir = temp_dir();
+        let path = dir.join("out.csv");
+        let first = CsvStorage::new(&path);
+        first.write_batch(batch(3)).await.unwrap();
+        first.write_batch(batch(2)).await.unwrap();
+        assert_eq!(first.rows_written(), 5);
+
+        let restarted = CsvStorage::new(&path);
+        restarted.write_batch(batch(4)).await.unwrap();
+        assert_eq!(restarted.rows_written(), 4);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.matches(HEADER).count(), 1);
+        assert_eq!(data_lines(&path).len(), 9);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // CV-T02: names with commas, quotes and line breaks are RFC 4180 quoted.
+    #[tokio::test]
+    async fn awkward_names_are_quoted() {
+        let dir = temp_dir();
+        let path = dir.join("out.csv");
+        let storage = CsvStorage::new(&path);
+        let names = ["Smith, Jr.", "O\"Brien", "Line\nBreak", "Plain"];
+        storage.write_batch(names.iter().map(|name| pending(name)).collect()).await.unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains(",\"Smith, Jr.\",EUR,"), "{text}");
+        assert!(text.contains(",\"O\"\"Brien\",EUR,"), "{text}");
+        assert!(text.contains(",\"Line\nBreak\",EUR,"), "{text}");
+        let plain = format!(",Plain,EUR,false,DEMO,4,false,,{RECORD_VERSION},,,\r\n");
+        assert!(text.contains(&plain), "{text}");
+        // The embedded line break stays inside its field: still 4 records.
+        assert_eq!(data_lines(&path).len(), 4);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // CV-T03: daily rotation switches file at the UTC date boundary.
+    #[tokio::test]
+    async fn daily_rotation_switches_file_at_midnight() {
+        let dir = temp_dir();
+        // 2026-03-01T23:59:59Z.
+        let clock = ManualClock(Arc::new(AtomicU64::new(1_772_409_599)));
+        let storage =
+            CsvStorage::new(dir.join("out.csv")).daily_rotation().clock(Arc::new(clock.clone()));
+        storage.write_batch(batch(2)).await.unwrap();
+        clock.0.fetch_add(1, Ordering::SeqCst);
+        storage.write_batch(batch(3)).await.unwrap();
+
+        let before = dir.join("out-2026-03-01.csv");
+        let after = dir.join("out-2026-03-02.csv");
+        assert_eq!(storage.current_path(), after);
+        assert_eq!(data_lines(&before).len(), 2);
+        assert_eq!(data_lines(&after).len(), 3);
+        assert_eq!(storage.rows_written(), 5);
+        assert!(!dir.join("out.csv").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // CV-T04: encoding and I/O failures name the file; nothing is appended.
+    #[tokio::test]
+    async fn failures_carry_the_path() {
+        let dir = temp_dir();
+        let path = dir.join("out.csv");
+        let storage = CsvStorage::new(&path);
+        let mut bad = pending("Nan");
+        bad.inferred_transaction.transaction.amount = f64::NAN;
+        let id = bad.id();
+
+        let result = storage.write_batch(vec![pending("Fine"), bad]).await;
+        assert_eq!(result, Err(StorageError::Encoding { path: path.clone(), id, field: "amount" }));
+        assert!(!path.exists());
+        assert_eq!(storage.rows_written(), 0);
+
+        let missing = dir.join("no-such-dir").join("out.csv");
+        let result = CsvStorage::new(&missing).write_batch(batch(1)).await;
+        assert_eq!(
+            result,
+            Err(StorageError::Io { path: missing, kind: std::io::ErrorKind::NotFound })
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn civil_date_handles_leap_years() {
+        let at = |secs| civil_date(UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(at(0), (1970, 1, 1));
+        assert_eq!(at(951_782_400), (2000, 2, 29));
+        assert_eq!(at(1_772_409_599), (2026, 3, 1));
+    }
+}
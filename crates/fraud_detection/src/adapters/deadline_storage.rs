@@ -0,0 +1,233 @@
+// Rust guideline compliant 2026-02-27
+
+//! Decorator for the `Storage` port that bounds how long a write may take.
+//!
+//! A write that hangs (e.g. a `SQLite` file lock held by a reader) would stall
+//! the Logger forever without an error. [`DeadlineStorage`] races every batch
+//! write and bucket upsert against `tokio::time::timeout`: past the deadline
+//! the inner future is dropped, a `deadline_storage.timeout` warning logs the
+//! elapsed time and batch size, and the write fails with
+//! `StorageError::Unavailable`, which the Logger already retries. Whether the
+//! dropped write reached the backend is unknown, so the retry relies on the
+//! storage being idempotent per id. Reads, reviews and the run lifecycle pass
+//! straight through.
+
+use std::time::Duration;
+
+use domain::{
+    BucketSink, MinuteBucket, PendingTransaction, Review, ReviewOutcome, RunId, RunMeta,
+    RunSummary, Storage, StorageError, StorageRead, StoredTransaction, TransactionId,
+};
+use tokio::time::Instant;
+
+/// `tracing` target of every storage event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::STORAGE;
+
+/// `Storage` decorator failing writes that outlast a deadline.
+#[derive(Debug)]
+pub struct DeadlineStorage<S> {
+    inner: S,
+    deadline: Duration,
+}
+
+impl<S> DeadlineStorage<S> {
+    /// Wrap `inner`; every write must complete within `deadline`.
+    #[must_use]
+    pub fn new(inner: S, deadline: Duration) -> Self {
+        Self { inner, deadline }
+    }
+
+    /// Longest a write may take.
+    #[must_use]
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+
+    /// Await `write` for at most the deadline; `items` is only logged.
+    async fn within_deadline(
+        &self,
+        items: usize,
+        write: impl Future<Output = Result<(), StorageError>>,
+    ) -> Result<(), StorageError> {
+        let start = Instant::now();
+        if let Ok(result) = tokio::time::timeout(self.deadline, write).await {
+            return result;
+        }
+        let elapsed_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        tracing::warn!(target: TARGET, elapsed_ms, batch_size = items, "deadline_storage.timeout");
+        Err(StorageError::Unavailable)
+    }
+}
+
+impl<S: Storage> Storage for DeadlineStorage<S> {
+    /// Forward `batch`, failing it once the deadline expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` past the deadline, otherwise the
+    /// wrapped storage error.
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        let items = batch.len();
+        self.within_deadline(items, self.inner.write_batch(batch)).await
+    }
+
+    /// Forward to the wrapped storage, without a deadline.
+    async fn begin_run(&self, meta: RunMeta) -> Result<RunId, StorageError> {
+        self.inner.begin_run(meta).await
+    }
+
+    /// Forward to the wrapped storage, without a deadline.
+    async fn end_run(&self, id: RunId, summary: RunSummary) -> Result<(), StorageError> {
+        self.inner.end_run(id, summary).await
+    }
+}
+
+impl<S: BucketSink> BucketSink for DeadlineStorage<S> {
+    /// Forward `buckets`, failing the upsert once the deadline expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` past the deadline, otherwise the
+    /// wrapped sink error.
+    async fn upsert_buckets(&self, buckets: &[MinuteBucket]) -> Result<(), StorageError> {
+        self.within_deadline(buckets.len(), self.inner.upsert_buckets(buckets)).await
+    }
+}
+
+impl<S: StorageRead> StorageRead for DeadlineStorage<S> {
+    /// Forward to the wrapped storage, without a deadline.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        self.inner.read_page(after, limit).await
+    }
+
+    /// Forward to the wrapped storage, without a deadline.
+    async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+        self.inner.all_ids(limit).await
+    }
+}
+
+impl<S: Review> Review for DeadlineStorage<S> {
+    /// Forward to the wrapped storage, without a deadline.
+    async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError> {
+        self.inner.record_reviews(outcomes).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::DeadlineStorage;
+    use domain::{
+        BucketSink, Currency, InferredTransaction, MinuteBucket, PendingTransaction,
+        Storage, StorageError, Transaction, TransactionId,
+    };
+    use std::cell::Cell;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// Storage taking `latency` per write, or never completing with `None`.
+    #[derive(Debug)]
+    struct SlowStorage {
+        latency: Option<Duration>,
+        written: Cell<usize>,
+    }
+
+    impl SlowStorage {
+        fn new(latency: Option<Duration>) -> Self {
+            Self { latency, written: Cell::new(0) }
+        }
+    }
+
+    impl Storage for SlowStorage {
+        async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            match self.latency {
+                Some(latency) => tokio::time::sleep(latency).await,
+                None => std::future::pending::<()>().await,
+            }
+            self.written.set(self.written.get() + batch.len());
+            Ok(())
+        }
+    }
+
+    impl BucketSink for SlowStorage {
+        async fn upsert_buckets(&self, _buckets: &[MinuteBucket]) -> Result<(), StorageError> {
+            std::future::pending().await
+        }
+    }
+
+    fn make_batch(n: usize) -> Vec<PendingTransaction> {
+        (0..n)
+            .map(|_| {
+                PendingTransaction::new(InferredTransaction {
+                    transaction: Transaction {
+                        id: TransactionId::new_v4(),
+                        amount: 1.00_f64,
+                        last_name: "Test".to_owned(),
+                        currency: Currency::Eur,
+                        tenant_id: None,
+                    },
+                    predicted_fraud: false,
+                    model_name: "DEMO".to_owned(),
+                    model_version: "4".to_owned(),
+                    delivery_attempts: 1,
+                    cached: false,
+                })
+            })
+            .collect()
+    }
+
+    // DS-T01: a write that never completes fails with Unavailable at the
+    // deadline, not later.
+    #[tokio::test(start_paused = true)]
+    async fn hung_write_times_out_as_unavailable() {
+        let storage = DeadlineStorage::new(SlowStorage::new(None), Duration::from_secs(5));
+        let start = Instant::now();
+
+        let result = storage.write_batch(make_batch(3)).await;
+
+        assert_eq!(result, Err(StorageError::Unavailable));
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+        assert_eq!(storage.inner.written.get(), 0);
+    }
+
+    // DS-T02: a write within the deadline is forwarded unchanged.
+    #[tokio::test(start_paused = true)]
+    async fn fast_write_is_unaffected() {
+        let inner = SlowStorage::new(Some(Duration::from_millis(10)));
+        let storage = DeadlineStorage::new(inner, Duration::from_secs(5));
+
+        storage.write_batch(make_batch(3)).await.unwrap();
+
+        assert_eq!(storage.inner.written.get(), 3);
+    }
+
+    // DS-T03: the deadline is set per constructor: the same 2 s write
+    // passes a 3 s deadline and fails a 1 s one.
+    #[tokio::test(start_paused = true)]
+    async fn deadline_is_configurable() {
+        let slow = || SlowStorage::new(Some(Duration::from_secs(2)));
+        let lenient = DeadlineStorage::new(slow(), Duration::from_secs(3));
+        let strict = DeadlineStorage::new(slow(), Duration::from_secs(1));
+
+        assert_eq!(lenient.deadline(), Duration::from_secs(3));
+        assert_eq!(lenient.write_batch(make_batch(1)).await, Ok(()));
+        assert_eq!(strict.write_batch(make_batch(1)).await, Err(StorageError::Unavailable));
+    }
+
+    // DS-T04: bucket upserts carry the same deadline.
+    #[tokio::test(start_paused = true)]
+    async fn hung_bucket_upsert_times_out() {
+        let storage = DeadlineStorage::new(SlowStorage::new(None), Duration::from_secs(1));
+
+        let result = storage.upsert_buckets(&[MinuteBucket::default()]).await;
+
+        assert_eq!(result, Err(StorageError::Unavailable));
+    }
+}
@@ -5,12 +5,26 @@
 //! Named `"DEMO"`, with two versions: 4 (N, latest) and 3 (N-1, previous).
 //! Classifies transactions probabilistically: 4% fraud rate for version 4,
 //! 3% for version 3. Supports seeded randomness for reproducible tests.
+//!
+//! `DemoModel` is `Send + Sync`: concurrent `classify` calls never contend on
+//! a lock. Each call takes the next index from an atomic counter and draws
+//! from a fresh RNG seeded with the base seed and that index, so a seeded
+//! model hands out the same draws whatever the interleaving: the fraud count
+//! over N calls is reproducible, only which call gets which draw may change.
+//! The active version sits behind a `Mutex`, held only to read or swap it.
 
-use std::cell::RefCell;
+use std::sync::{Mutex, PoisonError};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use domain::{Model, ModelizerError, ModelVersion, Transaction};
 use rand::{Rng, SeedableRng, rngs::StdRng};
 
+/// `tracing` target of every DEMO model event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::MODELIZER;
+
+/// Odd constant spreading call indices over the seed space (splitmix64).
+const SEED_STRIDE: u64 = 0x9E37_79B9_7F4A_7C15;
+
 /// Concrete adapter for the `domain::Model` port.
 ///
 /// Maps `ModelVersion::N` to version `"4"` and `ModelVersion::NMinus1` to `"3"`.
@@ -23,36 +37,64 @@ use rand::{Rng, SeedableRng, rngs::StdRng};
 #[derive(Debug)]
 pub struct DemoModel {
     /// Currently active version; interior mutability required (trait takes `&self`).
-    current_version: RefCell<ModelVersion>,
-    /// RNG for probabilistic fraud classification; seeded for reproducibility (FR-011).
-    rng: RefCell<StdRng>,
+    current_version: Mutex<ModelVersion>,
+    /// Base seed of the per-call RNGs; fixed for reproducibility (FR-011).
+    seed: u64,
+    /// `classify` calls so far; the index of the next call.
+    calls: AtomicU64,
 }
 
 impl DemoModel {
     /// Create a new DEMO model.
     ///
-    /// `seed = Some(s)` produces deterministic results; `None` seeds from the OS.
+    /// `seed = Some(s)` produces deterministic results; `None` draws a seed
+    /// from the OS, logged and kept as [`effective_seed`](Self::effective_seed).
     /// Starts with `ModelVersion::N` (version 4) per FR-007.
     // See struct-level allow(dead_code) comment above.
     #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
     #[must_use]
     pub fn new(seed: Option<u64>) -> Self {
-        let rng = match seed {
-            Some(s) => StdRng::seed_from_u64(s),
-            None => StdRng::from_os_rng(),
-        };
+        let seed = seed.unwrap_or_else(rand::random);
+        tracing::info!(target: TARGET, seed, "model.seed");
         Self {
             // FR-007: default to version N at startup.
-            current_version: RefCell::new(ModelVersion::N),
-            rng: RefCell::new(rng),
+            current_version: Mutex::new(ModelVersion::N),
+            seed,
+            calls: AtomicU64::new(0),
         }
     }
 
+    /// Base seed actually in use; passing it back to [`new`](Self::new)
+    /// replays the draws.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    #[must_use]
+    pub fn effective_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Currently active version.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    fn version(&self) -> ModelVersion {
+        // A poisoned lock still holds a whole `ModelVersion` (it is `Copy`).
+        *self.current_version.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// RNG of the next `classify` call, derived from the base seed and the
+    /// call index.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    fn next_rng(&self) -> StdRng {
+        let call = self.calls.fetch_add(1, Ordering::Relaxed);
+        StdRng::seed_from_u64(self.seed.wrapping_add(call.wrapping_mul(SEED_STRIDE)))
+    }
+
     /// Fraud probability for the currently active version.
     // See struct-level allow(dead_code) comment above.
     #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
     fn fraud_rate(&self) -> f64 {
-        match *self.current_version.borrow() {
+        match self.version() {
             ModelVersion::N => 0.04,       // FR-006: version 4 detects ~4%
             ModelVersion::NMinus1 => 0.03, // FR-005: version 3 detects ~3%
         }
@@ -67,9 +109,9 @@ impl Model for DemoModel {
     /// Currently infallible; returns `Ok(bool)`.
     async fn classify(&self, _tx: &Transaction) -> Result<bool, ModelizerError> {
         let rate = self.fraud_rate();
-        let roll: f64 = self.rng.borrow_mut().random();
+        let roll: f64 = self.next_rng().random();
         let is_fraud = roll < rate;
-        tracing::debug!(fraud = is_fraud, rate, "demo_model.classify");
+        tracing::debug!(target: TARGET, fraud = is_fraud, rate, "demo_model.classify");
         Ok(is_fraud)
     }
 
@@ -80,7 +122,7 @@ impl Model for DemoModel {
 
     /// Returns `"4"` for `ModelVersion::N` and `"3"` for `ModelVersion::NMinus1` (FR-004, FR-015).
     fn active_version(&self) -> &'static str {
-        match *self.current_version.borrow() {
+        match self.version() {
             ModelVersion::N => "4",
             ModelVersion::NMinus1 => "3",
         }
@@ -94,8 +136,8 @@ impl Model for DemoModel {
     ///
     /// Currently infallible; returns `Ok(())`.
     async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
-        tracing::info!(?version, "demo_model.switch_version");
-        *self.current_version.borrow_mut() = version;
+        tracing::info!(target: TARGET, ?version, "demo_model.switch_version");
+        *self.current_version.lock().unwrap_or_else(PoisonError::into_inner) = version;
         Ok(())
     }
 }
@@ -103,6 +145,8 @@ impl Model for DemoModel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use domain::{Currency, TransactionId};
+    use std::sync::Arc;
 
     // ------------------------------------------------------------------
     // T014: name
@@ -153,7 +197,13 @@ mod tests {
 
     #[tokio::test]
     async fn classify_seeded_is_deterministic() {
-        let tx = Transaction { id: uuid::Uuid::new_v4(), amount: 1.0_f64, last_name: "A".to_owned() };
+        let tx = Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.0_f64,
+            last_name: "A".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
         let m1 = DemoModel::new(Some(42));
         let m2 = DemoModel::new(Some(42));
         let results1: Vec<bool> = {
@@ -173,22 +223,49 @@ mod tests {
         assert_eq!(results1, results2, "identical seeds must produce identical sequences");
     }
 
+    #[tokio::test]
+    async fn effective_seed_replays_an_unseeded_model() {
+        let tx = Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.0_f64,
+            last_name: "A".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
+        let unseeded = DemoModel::new(None);
+        let replay = DemoModel::new(Some(unseeded.effective_seed()));
+        assert_eq!(DemoModel::new(Some(42)).effective_seed(), 42);
+
+        for _ in 0..100 {
+            assert_eq!(
+                unseeded.classify(&tx).await.unwrap(),
+                replay.classify(&tx).await.unwrap()
+            );
+        }
+    }
+
     // ------------------------------------------------------------------
     // T026: fraud rate ~4% for version N
     // ------------------------------------------------------------------
 
     #[tokio::test]
     async fn fraud_rate_v4_is_approx_4pct() {
-        let tx = Transaction { id: uuid::Uuid::new_v4(), amount: 1.0_f64, last_name: "B".to_owned() };
+        let tx = Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.0_f64,
+            last_name: "B".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
         let m = DemoModel::new(Some(0));
-        let count = 10_000usize;
-        let mut fraud = 0usize;
+        let count = 10_000_u32;
+        let mut fraud = 0_u32;
         for _ in 0..count {
             if m.classify(&tx).await.unwrap() {
                 fraud += 1;
             }
         }
-        let rate = (fraud as f64) / (count as f64) * 100.0_f64;
+        let rate = f64::from(fraud) / f64::from(count) * 100.0_f64;
         assert!(
             (3.0_f64..=5.0_f64).contains(&rate),
             "v4 fraud rate {rate:.2}% not in [3%, 5%]"
@@ -201,20 +278,131 @@ mod tests {
 
     #[tokio::test]
     async fn fraud_rate_v3_is_approx_3pct() {
-        let tx = Transaction { id: uuid::Uuid::new_v4(), amount: 1.0_f64, last_name: "C".to_owned() };
+        let tx = Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.0_f64,
+            last_name: "C".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        };
         let m = DemoModel::new(Some(0));
         m.switch_version(ModelVersion::NMinus1).await.unwrap();
-        let count = 10_000usize;
-        let mut fraud = 0usize;
+        let count = 10_000_u32;
+        let mut fraud = 0_u32;
         for _ in 0..count {
             if m.classify(&tx).await.unwrap() {
                 fraud += 1;
             }
         }
-        let rate = (fraud as f64) / (count as f64) * 100.0_f64;
+        let rate = f64::from(fraud) / f64::from(count) * 100.0_f64;
         assert!(
             (2.0_f64..=4.0_f64).contains(&rate),
             "v3 fraud rate {rate:.2}% not in [2%, 4%]"
         );
     }
+
+    // ------------------------------------------------------------------
+    // Concurrency
+    // ------------------------------------------------------------------
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    fn make_tx() -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: 1.0_f64,
+            last_name: "D".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    /// Run `future` to completion on a fresh current-thread runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    /// Fraud count of `per_thread` classify calls from each of `threads` OS
+    /// threads sharing `model`.
+    fn classify_in_parallel(model: &DemoModel, threads: usize, per_thread: usize) -> usize {
+        let tx = make_tx();
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        block_on(async {
+                            let mut fraud = 0;
+                            for _ in 0..per_thread {
+                                fraud += usize::from(model.classify(&tx).await.unwrap());
+                            }
+                            fraud
+                        })
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        })
+    }
+
+    #[test]
+    fn demo_model_is_send_and_sync() {
+        assert_send_sync::<DemoModel>();
+    }
+
+    #[tokio::test]
+    async fn classify_runs_in_spawned_tasks() {
+        let model = Arc::new(DemoModel::new(Some(7)));
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let model = Arc::clone(&model);
+                tokio::spawn(async move {
+                    let tx = make_tx();
+                    for _ in 0..100 {
+                        model.classify(&tx).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(model.calls.load(Ordering::Relaxed), 800);
+    }
+
+    // The same seed gives the same fraud count whether the calls run on one
+    // thread or are spread over four.
+    #[test]
+    fn parallel_classify_keeps_the_seeded_fraud_count() {
+        let expected = classify_in_parallel(&DemoModel::new(Some(42)), 1, 4_000);
+
+        let shared = DemoModel::new(Some(42));
+        let fraud = classify_in_parallel(&shared, 4, 1_000);
+
+        assert_eq!(fraud, expected);
+        assert_eq!(shared.calls.load(Ordering::Relaxed), 4_000);
+    }
+
+    #[test]
+    fn switch_version_during_parallel_classify_is_safe() {
+        let model = DemoModel::new(Some(3));
+
+        let fraud = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                block_on(async {
+                    for i in 0..1_000 {
+                        let version =
+                            if i % 2 == 0 { ModelVersion::NMinus1 } else { ModelVersion::N };
+                        model.switch_version(version).await.unwrap();
+                    }
+                });
+            });
+            classify_in_parallel(&model, 3, 1_000)
+        });
+
+        assert!(fraud < 3_000);
+        // The last switch (i = 999) was back to N.
+        assert_eq!(model.active_version(), "4");
+    }
 }
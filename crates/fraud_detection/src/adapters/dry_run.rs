@@ -0,0 +1,319 @@
+// Rust guideline compliant 2026-02-27
+
+//! No-op recording decorator for the `Storage` and `Alarm` ports.
+//!
+//! [`DryRun`] wraps an adapter with external effects (a database, an alert
+//! channel) and never calls it: each write or alert is logged at `info` level
+//! with its size and a sample record, counted, and reported as delivered. The
+//! stages upstream run exactly as they would against the real adapter, and
+//! [`DryRun::stats`] tells what the sink would have received.
+//!
+//! `begin_run` and `end_run` are not forwarded either: the default no-ops
+//! apply, so no run is recorded. Neither are the writes of the `Review`,
+//! `BucketSink` and `AlarmAudit` ports, which are counted like batches. The
+//! `StorageRead` reads have no effect and always reach the wrapped adapter.
+//!
+//! [`DryRun::enabled`] set to `false` forwards every call instead, so a binary
+//! keeps the same adapter types whether its dry-run flag is set or not.
+
+use std::cell::Cell;
+use std::fmt;
+
+use domain::{
+    Alarm, AlarmAudit, AlarmDelivery, AlarmError, BucketSink, InferredTransaction, MinuteBucket,
+    PendingTransaction, Review, ReviewOutcome, RunId, RunMeta, RunSummary, Storage, StorageError,
+    StorageRead, StoredTransaction, TransactionId,
+};
+
+/// Would-have counts of a [`DryRun`] sink.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DryRunStats {
+    /// Name of the sink in logs and in the summary, e.g. `"sqlite"`.
+    pub sink: &'static str,
+    /// Calls that would have reached the sink: batches or alerts.
+    pub calls: u64,
+    /// Records those calls carried: transactions or alerts.
+    pub records: u64,
+}
+
+impl fmt::Display for DryRunStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "DRY RUN {}: would have written {} records in {} calls",
+            self.sink, self.records, self.calls
+        )
+    }
+}
+
+/// `Storage` or `Alarm` decorator that records calls instead of making them.
+#[derive(Debug)]
+pub struct DryRun<T> {
+    inner: T,
+    stats: Cell<DryRunStats>,
+    enabled: bool,
+}
+
+impl<T> DryRun<T> {
+    /// Wrap `inner`, which is never called; `sink` names it in logs and stats.
+    #[must_use]
+    pub fn new(sink: &'static str, inner: T) -> Self {
+        Self {
+            inner,
+            stats: Cell::new(DryRunStats { sink, ..DryRunStats::default() }),
+            enabled: true,
+        }
+    }
+
+    /// Record calls (`true`, the default), or forward every call to the
+    /// wrapped adapter and count nothing (`false`).
+    #[must_use]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// The wrapped adapter, e.g. for reads that have no side effect.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Would-have counts so far.
+    #[must_use]
+    pub fn stats(&self) -> DryRunStats {
+        self.stats.get()
+    }
+
+    /// Count one call carrying `records` records and return the new totals.
+    fn record(&self, records: usize) -> DryRunStats {
+        let mut stats = self.stats.get();
+        stats.calls += 1;
+        stats.records += records as u64;
+        self.stats.set(stats);
+        stats
+    }
+
+    /// Count and log a write of `records` records that has no sample to show.
+    fn record_write(&self, records: usize) {
+        let stats = self.record(records);
+        tracing::info!(
+            target: domain::obs::target::STORAGE,
+            sink = stats.sink,
+            records,
+            total = stats.records,
+            "dry_run.storage.would_write"
+        );
+    }
+}
+
+impl<T: Storage> Storage for DryRun<T> {
+    /// Count and log `batch` without writing it; always succeeds.
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        if !self.enabled {
+            return self.inner.write_batch(batch).await;
+        }
+        let stats = self.record(batch.len());
+        if let Some(sample) = batch.first() {
+            let transaction = &sample.inferred_transaction.transaction;
+            tracing::info!(
+                target: domain::obs::target::STORAGE,
+                sink = stats.sink,
+                records = batch.len(),
+                total = stats.records,
+                sample_id = %transaction.id,
+                sample_amount = transaction.amount,
+                "dry_run.storage.would_write"
+            );
+        }
+        Ok(())
+    }
+
+    /// Record no run: returns `RunId(0)`.
+    async fn begin_run(&self, meta: RunMeta) -> Result<RunId, StorageError> {
+        if !self.enabled {
+            return self.inner.begin_run(meta).await;
+        }
+        Ok(RunId::default())
+    }
+
+    /// Record nothing.
+    async fn end_run(&self, id: RunId, summary: RunSummary) -> Result<(), StorageError> {
+        if !self.enabled {
+            return self.inner.end_run(id, summary).await;
+        }
+        Ok(())
+    }
+}
+
+impl<T: StorageRead> StorageRead for DryRun<T> {
+    /// Forward to the wrapped storage, dry or not.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        self.inner.read_page(after, limit).await
+    }
+
+    /// Forward to the wrapped storage, dry or not.
+    async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+        self.inner.all_ids(limit).await
+    }
+}
+
+impl<T: Review> Review for DryRun<T> {
+    /// Count and log the outcomes without recording them; always succeeds.
+    async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError> {
+        if !self.enabled {
+            return self.inner.record_reviews(outcomes).await;
+        }
+        self.record_write(outcomes.len());
+        Ok(())
+    }
+}
+
+impl<T: BucketSink> BucketSink for DryRun<T> {
+    /// Count and log the buckets without upserting them; always succeeds.
+    async fn upsert_buckets(&self, buckets: &[MinuteBucket]) -> Result<(), StorageError> {
+        if !self.enabled {
+            return self.inner.upsert_buckets(buckets).await;
+        }
+        self.record_write(buckets.len());
+        Ok(())
+    }
+}
+
+impl<T: AlarmAudit> AlarmAudit for DryRun<T> {
+    /// Count and log the delivery without appending it; always succeeds.
+    async fn record_delivery(&self, delivery: &AlarmDelivery) -> Result<(), StorageError> {
+        if !self.enabled {
+            return self.inner.record_delivery(delivery).await;
+        }
+        self.record_write(1);
+        Ok(())
+    }
+}
+
+impl<T: Alarm> Alarm for DryRun<T> {
+    /// Count and log the alert without sending it; always succeeds.
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        if !self.enabled {
+            return self.inner.trigger(transaction).await;
+        }
+        let stats = self.record(1);
+        tracing::info!(
+            target: domain::obs::target::ALARM,
+            sink = stats.sink,
+            total = stats.records,
+            transaction_id = %transaction.id(),
+            amount = transaction.transaction.amount,
+            "dry_run.alarm.would_trigger"
+        );
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{DryRun, DryRunStats};
+    use crate::adapters::in_memory_storage::InMemoryStorage;
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{Alarm, Review as _, ReviewOutcome, Storage, StorageRead as _};
+    use logger::{Logger, LoggerConfig};
+    use modelizer::Modelizer;
+    use pipeline::Pipeline;
+    use pipeline::memory::{CountingAlarm, MemoryBuffer, MemoryStorage, RateModel};
+    use producer::{Producer, ProducerConfig};
+    use std::time::Duration;
+
+    /// Run the same seeded 4-iteration pipeline, flagging ~30%, into `alarm`
+    /// and `storage`.
+    async fn seeded_run(alarm: &impl Alarm, storage: &impl Storage) {
+        let pipeline = Pipeline::new(
+            Producer::new(
+                ProducerConfig::builder(20)
+                    .poll_interval1(Duration::ZERO)
+                    .iterations(4)
+                    .seed(1)
+                    .build()
+                    .unwrap(),
+            ),
+            Consumer::new(
+                ConsumerConfig::builder(20).poll_interval2(Duration::ZERO).seed(2).build().unwrap(),
+            ),
+            Logger::new(
+                LoggerConfig::builder(20).poll_interval3(Duration::ZERO).seed(3).build().unwrap(),
+            ),
+        );
+        let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+        let modelizer = Modelizer::new(RateModel::new(0.3, 4));
+        pipeline.run(&buffer1, &modelizer, alarm, &buffer2, storage).await.unwrap();
+    }
+
+    // DR-T01: a dry run reaches neither spy and counts what a real run delivers.
+    #[tokio::test]
+    async fn dry_run_writes_nothing_and_counts_what_a_real_run_writes() {
+        let (alarm, storage) = (CountingAlarm::new(), MemoryStorage::new());
+        seeded_run(&alarm, &storage).await;
+        assert!(alarm.count() > 0 && !storage.is_empty());
+
+        let dry_alarm = DryRun::new("alarm", CountingAlarm::new());
+        let dry_storage = DryRun::new("storage", MemoryStorage::new());
+        seeded_run(&dry_alarm, &dry_storage).await;
+
+        assert_eq!(dry_alarm.inner().count(), 0);
+        assert!(dry_storage.inner().is_empty());
+        let alarms = dry_alarm.stats();
+        assert_eq!((alarms.records, alarms.calls), (alarm.count(), alarm.count()));
+        let writes = dry_storage.stats();
+        assert_eq!(writes.records, storage.len() as u64);
+        assert!((1..=writes.records).contains(&writes.calls), "{writes:?}");
+    }
+
+    // DR-T02: the summary line names the sink and its would-have counts.
+    #[test]
+    fn stats_display_is_annotated_dry_run() {
+        let stats = DryRunStats { sink: "sqlite", calls: 3, records: 42 };
+        assert_eq!(stats.to_string(), "DRY RUN sqlite: would have written 42 records in 3 calls");
+        let fresh = DryRun::new("alarm", ()).stats();
+        assert_eq!(fresh, DryRunStats { sink: "alarm", calls: 0, records: 0 });
+    }
+
+    // DR-T03: disabled, every call reaches the wrapped adapters and nothing
+    // is counted.
+    #[tokio::test]
+    async fn disabled_dry_run_forwards_every_call() {
+        let alarm = DryRun::new("alarm", CountingAlarm::new()).enabled(false);
+        let storage = DryRun::new("storage", MemoryStorage::new()).enabled(false);
+
+        seeded_run(&alarm, &storage).await;
+
+        assert!(alarm.inner().count() > 0 && !storage.inner().is_empty());
+        assert_eq!((alarm.stats().calls, storage.stats().calls), (0, 0));
+    }
+
+    // DR-T04: reads reach the wrapped storage; review verdicts are only counted.
+    #[tokio::test]
+    async fn dry_run_reads_through_but_records_no_review() {
+        let storage = DryRun::new("sqlite", InMemoryStorage::new(1_000)).enabled(false);
+        seeded_run(&CountingAlarm::new(), &storage).await;
+        let dry = storage.enabled(true);
+
+        let page = dry.read_page(0, 5).await.unwrap();
+        let outcomes: Vec<_> = page
+            .iter()
+            .map(|stored| ReviewOutcome { id: stored.pending.id(), actual_fraud: true })
+            .collect();
+        dry.record_reviews(&outcomes).await.unwrap();
+
+        assert_eq!(page.len(), 5);
+        let stored = dry.inner().read_page(0, 5).await.unwrap();
+        assert!(stored.iter().all(|stored| !stored.pending.is_reviewed));
+        assert_eq!(dry.stats(), DryRunStats { sink: "sqlite", calls: 1, records: 5 });
+    }
+}
@@ -0,0 +1,187 @@
+// Rust guideline compliant 2026-02-27
+
+//! Decorator for the `Alarm` port that falls back to a second channel.
+//!
+//! [`EscalatingAlarm`] delivers every alert through the primary alarm (e.g. a
+//! webhook). When the primary fails, the alert escalates to the secondary
+//! (e.g. a durable file or log), so a channel outage does not drop the alert
+//! silently: the escalation is counted and logged as
+//! `escalating_alarm.escalated` with the primary's failure reason, and the
+//! trigger succeeds once the secondary delivers. Only when both fail does the
+//! trigger fail, with a `DeliveryFailed` naming both reasons.
+//!
+//! Either leg may itself be a decorator (retries, sampling, auditing): the
+//! secondary is only tried once the primary, as composed, gave up.
+
+use std::cell::Cell;
+
+use domain::{Alarm, AlarmError, InferredTransaction};
+
+/// `tracing` target of every alarm event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ALARM;
+
+/// `Alarm` decorator retrying failed deliveries on a secondary channel.
+#[derive(Debug)]
+pub struct EscalatingAlarm<P, S> {
+    primary: P,
+    secondary: S,
+    escalated: Cell<u64>,
+    failed: Cell<u64>,
+}
+
+impl<P, S> EscalatingAlarm<P, S> {
+    /// Deliver through `primary`, escalating to `secondary` when it fails.
+    #[must_use]
+    pub fn new(primary: P, secondary: S) -> Self {
+        Self { primary, secondary, escalated: Cell::new(0), failed: Cell::new(0) }
+    }
+
+    /// Alerts the primary failed to deliver, handed to the secondary.
+    #[must_use]
+    pub fn escalated(&self) -> u64 {
+        self.escalated.get()
+    }
+
+    /// Escalated alerts the secondary failed to deliver too.
+    #[must_use]
+    pub fn failed(&self) -> u64 {
+        self.failed.get()
+    }
+}
+
+impl<P: Alarm, S: Alarm> Alarm for EscalatingAlarm<P, S> {
+    /// Deliver `transaction` through the primary, else through the secondary.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AlarmError::DeliveryFailed` naming both failure reasons when
+    /// neither channel delivers.
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        let Err(primary) = self.primary.trigger(transaction).await else {
+            return Ok(());
+        };
+        let primary = reason(&primary);
+        self.escalated.set(self.escalated.get() + 1);
+        tracing::warn!(
+            target: TARGET,
+            transaction_id = %transaction.id(),
+            reason = %primary,
+            "escalating_alarm.escalated"
+        );
+        let Err(secondary) = self.secondary.trigger(transaction).await else {
+            return Ok(());
+        };
+        self.failed.set(self.failed.get() + 1);
+        let secondary = reason(&secondary);
+        Err(AlarmError::DeliveryFailed {
+            reason: format!("primary: {primary}; secondary: {secondary}"),
+        })
+    }
+}
+
+/// Failure reason of `error`, without the `delivery failed` prefix.
+fn reason(error: &AlarmError) -> String {
+    match error {
+        AlarmError::DeliveryFailed { reason } => reason.clone(),
+        other => other.to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::EscalatingAlarm;
+    use domain::{Alarm, AlarmError, Currency, InferredTransaction, Transaction, TransactionId};
+    use std::cell::RefCell;
+
+    /// Records delivered alert ids, or fails every delivery with `down`.
+    #[derive(Default)]
+    struct RecordingAlarm {
+        down: Option<&'static str>,
+        delivered: RefCell<Vec<TransactionId>>,
+    }
+
+    impl RecordingAlarm {
+        fn down(reason: &'static str) -> Self {
+            Self { down: Some(reason), ..Self::default() }
+        }
+    }
+
+    impl Alarm for RecordingAlarm {
+        async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+            if let Some(reason) = self.down {
+                return Err(AlarmError::DeliveryFailed { reason: reason.to_owned() });
+            }
+            self.delivered.borrow_mut().push(transaction.id());
+            Ok(())
+        }
+    }
+
+    fn make_fraud() -> InferredTransaction {
+        InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: 99.0,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud: true,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        }
+    }
+
+    // EA-T01: a failed primary escalates the alert to the secondary, and the
+    // trigger succeeds.
+    #[tokio::test]
+    async fn primary_failure_escalates_to_the_secondary() {
+        let alarm = EscalatingAlarm::new(
+            RecordingAlarm::down("webhook returned 503"),
+            RecordingAlarm::default(),
+        );
+        let fraud = make_fraud();
+
+        alarm.trigger(&fraud).await.unwrap();
+
+        assert_eq!(*alarm.secondary.delivered.borrow(), [fraud.id()]);
+        assert_eq!(alarm.escalated(), 1);
+        assert_eq!(alarm.failed(), 0);
+    }
+
+    // EA-T02: when both channels fail, the error names both reasons.
+    #[tokio::test]
+    async fn both_failures_are_combined() {
+        let alarm = EscalatingAlarm::new(
+            RecordingAlarm::down("webhook returned 503"),
+            RecordingAlarm::down("disk full"),
+        );
+
+        let err = alarm.trigger(&make_fraud()).await.unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "delivery failed: primary: webhook returned 503; secondary: disk full"
+        );
+        assert_eq!((alarm.escalated(), alarm.failed()), (1, 1));
+    }
+
+    // EA-T03: a delivered alert never reaches the secondary.
+    #[tokio::test]
+    async fn primary_success_skips_the_secondary() {
+        let alarm = EscalatingAlarm::new(RecordingAlarm::default(), RecordingAlarm::default());
+
+        for _ in 0..3 {
+            alarm.trigger(&make_fraud()).await.unwrap();
+        }
+
+        assert_eq!(alarm.primary.delivered.borrow().len(), 3);
+        assert!(alarm.secondary.delivered.borrow().is_empty());
+        assert_eq!(alarm.escalated(), 0);
+    }
+}
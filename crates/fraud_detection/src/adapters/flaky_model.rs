@@ -0,0 +1,357 @@
+// Rust guideline compliant 2026-02-27
+
+//! Chaos decorator for the `Model` port -- resilience testing only.
+//!
+//! [`FlakyModel`] wraps a real model and, per `classify` call, awaits a
+//! simulated network latency drawn from a [`LatencyDistribution`], then fails
+//! with `InferenceFailed` with probability `error_rate`. A failure can start a
+//! burst: the next `burst - 1` calls fail as well, without a draw, the way a
+//! remote model stays down for a while. Otherwise the inner verdict is
+//! returned unchanged.
+//!
+//! Everything is drawn from one `StdRng` seeded at construction, in call
+//! order (latency first, then the failure draw), so a seed replays the same
+//! script. Each call is recorded as an [`Injection`] for assertions. With a
+//! zero error rate and a zero fixed latency the decorator is transparent: it
+//! never sleeps, never fails and draws nothing from its RNG.
+
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+use domain::{Model, ModelizerError, ModelVersion, Transaction};
+use rand::{Rng as _, SeedableRng as _, rngs::StdRng};
+
+/// Cap of a Pareto latency, as a multiple of its scale: heavy tails stay
+/// long enough to trip a timeout without stalling a test forever.
+pub const PARETO_CAP: u32 = 1_000;
+
+// ---------------------------------------------------------------------------
+// LatencyDistribution
+// ---------------------------------------------------------------------------
+
+/// Distribution of the latency injected before each `classify` call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatencyDistribution {
+    /// Always `Duration`; draws nothing from the RNG.
+    Fixed(Duration),
+    /// Uniform over `[min, max]` (bounds swapped if reversed).
+    Uniform {
+        /// Shortest latency.
+        min: Duration,
+        /// Longest latency.
+        max: Duration,
+    },
+    /// Pareto with minimum `scale` and tail index `shape` (smaller is
+    /// heavier), capped at [`PARETO_CAP`] x `scale`.
+    Pareto {
+        /// Minimum latency, also the mode.
+        scale: Duration,
+        /// Tail index; a non-positive or NaN shape yields `scale` every time.
+        shape: f64,
+    },
+}
+
+impl Default for LatencyDistribution {
+    fn default() -> Self {
+        Self::Fixed(Duration::ZERO)
+    }
+}
+
+impl LatencyDistribution {
+    /// Draw one latency from `rng`.
+    fn sample(self, rng: &mut StdRng) -> Duration {
+        match self {
+            Self::Fixed(latency) => latency,
+            Self::Uniform { min, max } => {
+                let (min, max) = if min <= max { (min, max) } else { (max, min) };
+                let min_nanos = u64::try_from(min.as_nanos()).unwrap_or(u64::MAX);
+                let max_nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+                Duration::from_nanos(rng.random_range(min_nanos..=max_nanos))
+            }
+            Self::Pareto { scale, shape } => {
+                // 1 - [0, 1) is in (0, 1]: the power never divides by zero.
+                let u = 1.0 - rng.random::<f64>();
+                let factor = if shape > 0.0 { u.powf(-1.0 / shape) } else { 1.0 };
+                scale.mul_f64(factor.min(f64::from(PARETO_CAP)))
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// FlakyModel
+// ---------------------------------------------------------------------------
+
+/// What a [`FlakyModel`] injected into one `classify` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Injection {
+    /// Latency awaited before answering.
+    pub latency: Duration,
+    /// `true` when the call failed with `InferenceFailed`.
+    pub failed: bool,
+}
+
+/// `Model` decorator injecting seeded latency and `InferenceFailed` errors.
+#[derive(Debug)]
+pub struct FlakyModel<M> {
+    inner: M,
+    latency: LatencyDistribution,
+    error_rate: f64,
+    burst: u32,
+    rng: RefCell<StdRng>,
+    /// Failures still owed by the current burst.
+    burst_left: Cell<u32>,
+    injections: RefCell<Vec<Injection>>,
+}
+
+impl<M> FlakyModel<M> {
+    /// Wrap `inner` with no latency and no errors; every draw comes from a
+    /// `StdRng` seeded with `seed`.
+    #[must_use]
+    pub fn new(inner: M, seed: u64) -> Self {
+        Self {
+            inner,
+            latency: LatencyDistribution::default(),
+            error_rate: 0.0,
+            burst: 1,
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+            burst_left: Cell::new(0),
+            injections: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Await a latency drawn from `latency` before each call.
+    #[must_use]
+    pub fn with_latency(mut self, latency: LatencyDistribution) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fail a call with probability `error_rate` (clamped to `[0, 1]`, NaN
+    /// meaning 0).
+    #[must_use]
+    pub fn with_error_rate(mut self, error_rate: f64) -> Self {
+        self.error_rate = if error_rate.is_nan() { 0.0 } else { error_rate.clamp(0.0, 1.0) };
+        self
+    }
+
+    /// Make each drawn failure a burst of `burst` consecutive failures (at
+    /// least 1, the default).
+    #[must_use]
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Every injection so far, in call order.
+    #[must_use]
+    pub fn injections(&self) -> Vec<Injection> {
+        self.injections.borrow().clone()
+    }
+
+    /// Number of calls failed so far.
+    #[must_use]
+    pub fn injected_errors(&self) -> usize {
+        self.injections.borrow().iter().filter(|i| i.failed).count()
+    }
+
+    /// Total latency injected so far.
+    #[must_use]
+    pub fn injected_latency(&self) -> Duration {
+        self.injections.borrow().iter().map(|i| i.latency).sum()
+    }
+
+    /// Draw the next injection: latency first, then the failure.
+    fn next_injection(&self) -> Injection {
+        let mut rng = self.rng.borrow_mut();
+        let latency = self.latency.sample(&mut rng);
+        let burst_left = self.burst_left.get();
+        let failed = if burst_left > 0 {
+            self.burst_left.set(burst_left - 1);
+            true
+        } else if self.error_rate > 0.0 && rng.random_bool(self.error_rate) {
+            self.burst_left.set(self.burst - 1);
+            true
+        } else {
+            false
+        };
+        Injection { latency, failed }
+    }
+}
+
+impl<M: Model> Model for FlakyModel<M> {
+    /// Await the injected latency, then fail or forward to the inner model.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelizerError::InferenceFailed` for an injected failure, or
+    /// the inner model's error.
+    async fn classify(&self, tx: &Transaction) -> Result<bool, ModelizerError> {
+        let injection = self.next_injection();
+        self.injections.borrow_mut().push(injection);
+        if !injection.latency.is_zero() {
+            tokio::time::sleep(injection.latency).await;
+        }
+        if injection.failed {
+            return Err(ModelizerError::InferenceFailed {
+                reason: format!("injected failure ({})", self.inner.name()),
+            });
+        }
+        self.inner.classify(tx).await
+    }
+
+    /// Name of the inner model.
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    /// Active version of the inner model.
+    fn active_version(&self) -> &str {
+        self.inner.active_version()
+    }
+
+    /// Forwarded to the inner model; never injected.
+    ///
+    /// # Errors
+    ///
+    /// Returns the inner model's error.
+    async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+        self.inner.switch_version(version).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{FlakyModel, Injection, LatencyDistribution, PARETO_CAP};
+    use crate::adapters::bench_model::BenchModel;
+    use crate::adapters::demo_model::DemoModel;
+    use domain::{Currency, Model, ModelizerError, Transaction, TransactionId};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    fn make_tx(i: u32) -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: f64::from(i),
+            last_name: format!("Name{i}"),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    /// Classify `n` transactions; return the results and the time it took.
+    async fn classify_n(
+        model: &impl Model,
+        n: u32,
+    ) -> (Vec<Result<bool, ModelizerError>>, Duration) {
+        let start = Instant::now();
+        let mut results = vec![];
+        for i in 0..n {
+            results.push(model.classify(&make_tx(i)).await);
+        }
+        (results, start.elapsed())
+    }
+
+    // FM-T01: zero error rate and zero latency forward every verdict, never
+    // sleep and never fail.
+    #[tokio::test(start_paused = true)]
+    async fn zero_rate_and_latency_are_transparent() {
+        let flaky = FlakyModel::new(DemoModel::new(Some(7)), 1);
+        let reference = DemoModel::new(Some(7));
+
+        let (results, elapsed) = classify_n(&flaky, 200).await;
+        let (expected, _) = classify_n(&reference, 200).await;
+
+        let verdicts: Vec<bool> = results.into_iter().map(Result::unwrap).collect();
+        let expected: Vec<bool> = expected.into_iter().map(Result::unwrap).collect();
+        assert_eq!(verdicts, expected);
+        assert_eq!(elapsed, Duration::ZERO);
+        assert_eq!(flaky.injected_errors(), 0);
+        assert_eq!(flaky.name(), reference.name());
+    }
+
+    // FM-T02: under a seed the error count matches the rate and the script
+    // replays exactly.
+    #[tokio::test(start_paused = true)]
+    async fn seeded_errors_match_the_rate_and_replay() {
+        let flaky = || FlakyModel::new(BenchModel::new(), 42).with_error_rate(0.1);
+        let first = flaky();
+        let second = flaky();
+
+        let (results, _) = classify_n(&first, 2_000).await;
+        classify_n(&second, 2_000).await;
+
+        let failed = results.iter().filter(|r| r.is_err()).count();
+        assert_eq!(failed, first.injected_errors());
+        assert!((140..=260).contains(&failed), "{failed} errors at 10 % of 2 000");
+        assert!(results.iter().flatten().all(|verdict| !verdict));
+        assert_eq!(first.injections(), second.injections());
+    }
+
+    // FM-T03: every failure run lasts at least the burst length.
+    #[tokio::test(start_paused = true)]
+    async fn failures_come_in_bursts() {
+        let flaky = FlakyModel::new(BenchModel::new(), 9).with_error_rate(0.05).with_burst(4);
+
+        classify_n(&flaky, 2_000).await;
+
+        let injections = flaky.injections();
+        let runs: Vec<usize> = injections
+            .split(|i| !i.failed)
+            .map(<[Injection]>::len)
+            .filter(|len| *len > 0)
+            .collect();
+        // Back-to-back bursts merge; only the last one may be cut short.
+        let (_, complete) = runs.split_last().expect("5 % of 2 000 calls fail");
+        assert!(complete.iter().all(|len| len % 4 == 0), "{runs:?}");
+    }
+
+    // FM-T04: the paused clock advances by exactly the injected latency.
+    #[tokio::test(start_paused = true)]
+    async fn fixed_latency_sums_on_the_paused_clock() {
+        let flaky = FlakyModel::new(BenchModel::new(), 3)
+            .with_latency(LatencyDistribution::Fixed(Duration::from_millis(2)));
+
+        let (_, elapsed) = classify_n(&flaky, 50).await;
+
+        assert_eq!(flaky.injected_latency(), Duration::from_millis(100));
+        assert!(elapsed >= Duration::from_millis(100), "{elapsed:?}");
+    }
+
+    // FM-T05: uniform latency averages the midpoint and stays in its bounds.
+    #[tokio::test(start_paused = true)]
+    async fn uniform_latency_stays_in_bounds_and_averages_the_midpoint() {
+        let (min, max) = (Duration::from_millis(1), Duration::from_millis(3));
+        let flaky = FlakyModel::new(BenchModel::new(), 5)
+            .with_latency(LatencyDistribution::Uniform { min, max });
+
+        let (_, elapsed) = classify_n(&flaky, 1_000).await;
+
+        let total = flaky.injected_latency();
+        assert!(flaky.injections().iter().all(|i| (min..=max).contains(&i.latency)));
+        assert!(total > Duration::from_millis(1_900) && total < Duration::from_millis(2_100));
+        assert!(elapsed >= total, "{elapsed:?} < {total:?}");
+    }
+
+    // FM-T06: Pareto latency never goes below its scale nor above the cap,
+    // and about half the draws stay under scale x 2^(1/shape).
+    #[tokio::test(start_paused = true)]
+    async fn pareto_latency_is_bounded_with_the_expected_median() {
+        let scale = Duration::from_millis(1);
+        let flaky = FlakyModel::new(BenchModel::new(), 11)
+            .with_latency(LatencyDistribution::Pareto { scale, shape: 2.0 });
+
+        classify_n(&flaky, 1_000).await;
+
+        let injections = flaky.injections();
+        let cap = scale * PARETO_CAP;
+        assert!(injections.iter().all(|i| i.latency >= scale && i.latency <= cap));
+        let median = scale.mul_f64(2.0_f64.sqrt());
+        let below = injections.iter().filter(|i| i.latency <= median).count();
+        assert!((430..=570).contains(&below), "{below} of 1 000 under the median");
+    }
+}
@@ -0,0 +1,281 @@
+// Rust guideline compliant 2026-02-27
+
+//! HTTP sink adapter for the `Buffer1` port.
+//!
+//! POSTs each batch as a JSON array (see `jsonl_buffer::batch_json`) to a
+//! plain `http://` endpoint, e.g. a webhook under load test. Minimal HTTP/1.1
+//! over `tokio::net::TcpStream`: one connection per batch, `Connection: close`,
+//! no TLS, no redirects. Write-only, like `JsonlBuffer1`.
+
+use std::time::Duration;
+
+use domain::{Buffer1, Transaction, WriteError};
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use tokio::net::TcpStream;
+
+use crate::jsonl_buffer::batch_json;
+
+/// `tracing` target of every buffer event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::BUFFER;
+
+/// Limit for one POST, connect to last response byte.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ---------------------------------------------------------------------------
+// HttpBuffer1
+// ---------------------------------------------------------------------------
+
+/// `Buffer1` adapter sending each batch as an HTTP `POST` to an endpoint.
+///
+/// Any 2xx status is success. Connection errors, timeouts and other statuses
+/// map to [`WriteError::Backend`], which the Producer surfaces as a
+/// retryable error.
+#[derive(Debug)]
+pub struct HttpBuffer1 {
+    /// Original URL, for error messages.
+    url: String,
+    /// `host` or `host:port`, sent as the `Host` header.
+    authority: String,
+    host: String,
+    port: u16,
+    /// Request target, starting with `/`.
+    path: String,
+}
+
+impl HttpBuffer1 {
+    /// Create an adapter for `url`, of the form `http://host[:port][/path]`.
+    ///
+    /// Port defaults to 80 and path to `/`. No connection is made yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WriteError::Backend`] when `url` is not a plain `http://` URL
+    /// with a host and a valid port.
+    pub fn new(url: &str) -> Result<Self, WriteError> {
+        let invalid = |reason: &str| WriteError::Backend(format!("invalid url {url:?}: {reason}"));
+        let Some(rest) = url.strip_prefix("http://") else {
+            return Err(invalid("only http:// is supported"));
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let Ok(port) = port.parse::<u16>() else {
+                    return Err(invalid("bad port"));
+                };
+                (host, port)
+            }
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+        Ok(Self {
+            url: url.to_owned(),
+            authority: authority.to_owned(),
+            host: host.to_owned(),
+            port,
+            path: path.to_owned(),
+        })
+    }
+
+    /// Send `body` and return the response status line.
+    async fn post(&self, body: &str) -> std::io::Result<String> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.authority,
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(body.as_bytes()).await?;
+        stream.shutdown().await?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await?;
+        let status_line = response
+            .split(|&b| b == b'\r' || b == b'\n')
+            .next()
+            .unwrap_or_default();
+        Ok(String::from_utf8_lossy(status_line).into_owned())
+    }
+}
+
+/// Status code from an HTTP/1.x status line, e.g. `HTTP/1.1 204 No Content`.
+fn status_code(status_line: &str) -> Option<u16> {
+    let mut parts = status_line.split_whitespace();
+    let version = parts.next()?;
+    if !version.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+impl Buffer1 for HttpBuffer1 {
+    /// POST `batch` as a JSON array and wait for the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WriteError::Backend`] on connection failure, timeout, a
+    /// malformed response or a non-2xx status; never `Full` or `Closed`.
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+        let body = batch_json(&batch);
+        let Ok(result) = tokio::time::timeout(REQUEST_TIMEOUT, self.post(&body)).await else {
+            return Err(WriteError::Backend(format!(
+                "POST {} timed out after {:?}",
+                self.url, REQUEST_TIMEOUT
+            )));
+        };
+        let status_line =
+            result.map_err(|e| WriteError::Backend(format!("POST {}: {e}", self.url)))?;
+        match status_code(&status_line) {
+            Some(200..=299) => {
+                tracing::debug!(
+                    target: TARGET,
+                    size = batch.len(),
+                    status = %status_line,
+                    "http_buffer.posted"
+                );
+                Ok(())
+            }
+            _ => Err(WriteError::Backend(format!(
+                "POST {} answered {status_line:?}",
+                self.url
+            ))),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpBuffer1, status_code};
+    use crate::jsonl_buffer::batch_json;
+    use domain::{Buffer1 as _, Currency, Transaction, TransactionId, WriteError};
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+    use tokio::net::TcpListener;
+
+    fn make_txs(n: u128) -> Vec<Transaction> {
+        (1..=n)
+            .map(|i| Transaction {
+                id: TransactionId::from_uuid(uuid::Uuid::from_u128(i)),
+                amount: 10.0,
+                last_name: "Smith".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            })
+            .collect()
+    }
+
+    /// Accept one connection, reply with `status`, return the raw request.
+    async fn serve_once(listener: &TcpListener, status: &str) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        // The client shuts down its write half after the body.
+        socket.read_to_end(&mut request).await.unwrap();
+        let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+        String::from_utf8(request).unwrap()
+    }
+
+    // ------------------------------------------------------------------
+    // HB-T01: success path
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn posts_batch_as_json_array() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let buffer = HttpBuffer1::new(&format!("http://127.0.0.1:{port}/ingest")).unwrap();
+        let batch = make_txs(3);
+
+        let (request, result) = tokio::join!(
+            serve_once(&listener, "200 OK"),
+            buffer.write_batch(batch.clone())
+        );
+
+        assert_eq!(result, Ok(()));
+        assert!(
+            request.starts_with("POST /ingest HTTP/1.1\r\n"),
+            "{request}"
+        );
+        assert!(request.contains(&format!("Host: 127.0.0.1:{port}\r\n")));
+        assert!(request.contains("Content-Type: application/json\r\n"));
+        let body = batch_json(&batch);
+        assert!(request.contains(&format!("Content-Length: {}\r\n", body.len())));
+        assert!(request.ends_with(&format!("\r\n\r\n{body}")));
+    }
+
+    // ------------------------------------------------------------------
+    // HB-T02: failures map to Backend
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn non_2xx_status_is_backend_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let buffer = HttpBuffer1::new(&format!("http://127.0.0.1:{port}")).unwrap();
+
+        let (_, result) = tokio::join!(
+            serve_once(&listener, "503 Service Unavailable"),
+            buffer.write_batch(make_txs(1))
+        );
+
+        let Err(WriteError::Backend(msg)) = result else {
+            panic!("expected Backend, got {result:?}");
+        };
+        assert!(msg.contains("503"), "{msg}");
+    }
+
+    #[tokio::test]
+    async fn connection_refused_is_backend_error() {
+        // Bind then drop to get a port nobody listens on.
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let buffer = HttpBuffer1::new(&format!("http://127.0.0.1:{port}/")).unwrap();
+        let result = buffer.write_batch(make_txs(1)).await;
+        assert!(matches!(result, Err(WriteError::Backend(_))), "{result:?}");
+        assert!(result.unwrap_err().is_retryable());
+    }
+
+    // ------------------------------------------------------------------
+    // HB-T03: url and status parsing
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn parses_url_parts() {
+        let b = HttpBuffer1::new("http://example.test").unwrap();
+        assert_eq!(
+            (b.host.as_str(), b.port, b.path.as_str()),
+            ("example.test", 80, "/")
+        );
+        let b = HttpBuffer1::new("http://localhost:8080/hooks/tx").unwrap();
+        assert_eq!(
+            (b.authority.as_str(), b.port, b.path.as_str()),
+            ("localhost:8080", 8080, "/hooks/tx")
+        );
+        for bad in ["https://example.test", "http://:80/", "http://host:port/"] {
+            assert!(
+                matches!(HttpBuffer1::new(bad), Err(WriteError::Backend(_))),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_status_code() {
+        assert_eq!(status_code("HTTP/1.1 204 No Content"), Some(204));
+        assert_eq!(status_code("HTTP/1.0 500"), Some(500));
+        assert_eq!(status_code("garbage"), None);
+        assert_eq!(status_code(""), None);
+    }
+}
@@ -0,0 +1,152 @@
+// Rust guideline compliant 2026-02-27
+
+//! In-memory adapter for the `Quarantine` port.
+//!
+//! Keeps quarantined transactions in a `Vec`, in record order; quarantining an
+//! id again replaces its entry and moves it last, like the `SQLite` adapter.
+//! Nothing survives the process. Intended for proof-of-concept runs and tests.
+
+use std::sync::{Arc, Mutex};
+
+use domain::{
+    Clock, Quarantine, QuarantineFuture, QuarantinedTransaction, SystemClock, Transaction,
+    TransactionId,
+};
+
+/// `Quarantine` adapter backed by an in-memory `Vec`.
+///
+/// A `Mutex` rather than a `RefCell`: the Consumer holds its quarantine as
+/// `Arc<dyn Quarantine>`, which must be `Send + Sync`. Never fails.
+#[derive(Debug)]
+pub struct InMemoryQuarantine {
+    rows: Mutex<Vec<QuarantinedTransaction>>,
+    /// Time source for `quarantined_at`.
+    clock: Arc<dyn Clock>,
+}
+
+impl InMemoryQuarantine {
+    /// Create an empty quarantine stamped from the system clock.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Create an empty quarantine stamping `quarantined_at` from `clock`.
+    #[must_use]
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { rows: Mutex::new(vec![]), clock }
+    }
+}
+
+impl Default for InMemoryQuarantine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Quarantine for InMemoryQuarantine {
+    /// Append `tx`, replacing any entry with the same id.
+    fn record<'a>(&'a self, tx: &'a Transaction, reason: &'a str) -> QuarantineFuture<'a, ()> {
+        let row = QuarantinedTransaction {
+            transaction: tx.clone(),
+            reason: reason.to_owned(),
+            quarantined_at: self.clock.now(),
+        };
+        let mut rows = self.rows.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        rows.retain(|r| r.transaction.id != tx.id);
+        rows.push(row);
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Return clones of up to `max` entries, oldest first.
+    fn quarantined(&self, max: usize) -> QuarantineFuture<'_, Vec<QuarantinedTransaction>> {
+        let rows = self.rows.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let rows = rows.iter().take(max).cloned().collect();
+        Box::pin(async { Ok(rows) })
+    }
+
+    /// Remove the entries of `ids`; unknown ids are ignored.
+    fn release<'a>(&'a self, ids: &'a [TransactionId]) -> QuarantineFuture<'a, ()> {
+        self.rows
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .retain(|r| !ids.contains(&r.transaction.id));
+        Box::pin(async { Ok(()) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::InMemoryQuarantine;
+    use domain::{
+        Currency, FixedClock, Quarantine as _, QuarantinedTransaction, Transaction, TransactionId,
+    };
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    fn make_tx() -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: 12.5,
+            last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_are_listed_oldest_first_with_reason_and_time() {
+        let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let quarantine = InMemoryQuarantine::with_clock(Arc::new(FixedClock(t)));
+        let (a, b) = (make_tx(), make_tx());
+        quarantine.record(&a, "inference failed: a").await.unwrap();
+        quarantine.record(&b, "inference failed: b").await.unwrap();
+
+        assert_eq!(
+            quarantine.quarantined(10).await.unwrap(),
+            [
+                QuarantinedTransaction {
+                    transaction: a,
+                    reason: "inference failed: a".to_owned(),
+                    quarantined_at: t,
+                },
+                QuarantinedTransaction {
+                    transaction: b.clone(),
+                    reason: "inference failed: b".to_owned(),
+                    quarantined_at: t,
+                },
+            ]
+        );
+        assert_eq!(quarantine.quarantined(1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn recording_again_replaces_and_moves_last() {
+        let quarantine = InMemoryQuarantine::new();
+        let (a, b) = (make_tx(), make_tx());
+        quarantine.record(&a, "old").await.unwrap();
+        quarantine.record(&b, "other").await.unwrap();
+        quarantine.record(&a, "new").await.unwrap();
+
+        let rows = quarantine.quarantined(10).await.unwrap();
+        let rows: Vec<_> = rows.iter().map(|r| (r.transaction.id, r.reason.as_str())).collect();
+        assert_eq!(rows, [(b.id, "other"), (a.id, "new")]);
+    }
+
+    #[tokio::test]
+    async fn release_removes_only_the_given_ids() {
+        let quarantine = InMemoryQuarantine::new();
+        let (a, b) = (make_tx(), make_tx());
+        quarantine.record(&a, "x").await.unwrap();
+        quarantine.record(&b, "x").await.unwrap();
+
+        quarantine.release(&[a.id, TransactionId::new_v4()]).await.unwrap();
+
+        let rows = quarantine.quarantined(10).await.unwrap();
+        assert_eq!(rows.iter().map(|r| r.transaction.id).collect::<Vec<_>>(), [b.id]);
+    }
+}
@@ -2,14 +2,20 @@
 
 //! In-memory adapter for the `Storage` port.
 //!
+//! Also implements `AlarmAudit`, keeping delivery records in a second vector.
 //! Intended for proof-of-concept runs and unit tests only.
 //! Returns `StorageError::CapacityExceeded` when the configured capacity is exceeded.
 //! `StorageError::Unavailable` is part of the Storage trait contract but is never
 //! returned by this adapter; it is reserved for future concrete backends.
 
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
 
-use domain::{PendingTransaction, Storage, StorageError};
+use domain::{
+    AlarmAudit, AlarmDelivery, BucketSink, Clock, MinuteBucket, PendingTransaction, Review,
+    ReviewOutcome, Storage, StorageError, StorageRead, StoredTransaction, SystemClock,
+};
 
 /// `Storage` adapter backed by an in-memory `Vec<PendingTransaction>`.
 ///
@@ -23,8 +29,12 @@ use domain::{PendingTransaction, Storage, StorageError};
 #[derive(Debug)]
 pub struct InMemoryStorage {
     inner: RefCell<Vec<PendingTransaction>>,
+    /// Audited alarm deliveries, in record order; not bounded by `capacity`.
+    deliveries: RefCell<Vec<AlarmDelivery>>,
     /// Maximum number of pending transactions the storage can hold.
     capacity: usize,
+    /// Time source for `reviewed_at`.
+    clock: Arc<dyn Clock>,
 }
 
 impl InMemoryStorage {
@@ -33,17 +43,52 @@ impl InMemoryStorage {
     #[allow(dead_code, reason = "used by fraud_detection binary; dead in fraud_detection_sqlite")]
     #[must_use]
     pub fn new(capacity: usize) -> Self {
-        Self { inner: RefCell::new(vec![]), capacity }
+        Self::with_clock(capacity, Arc::new(SystemClock))
+    }
+
+    /// Create an empty storage stamping `reviewed_at` from `clock`.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection binary; dead in fraud_detection_sqlite")]
+    #[must_use]
+    pub fn with_clock(capacity: usize, clock: Arc<dyn Clock>) -> Self {
+        Self { inner: RefCell::new(vec![]), deliveries: RefCell::new(vec![]), capacity, clock }
     }
 
     /// Return the number of stored items.
     ///
-    /// Used in tests to assert persistence counts.
-    #[cfg(test)]
+    /// Used in tests to assert persistence counts, and by the TUI summary.
+    #[cfg(any(test, feature = "tui"))]
+    #[allow(dead_code, reason = "used by fraud_detection_tui; dead in the other binaries")]
     #[must_use]
     pub fn len(&self) -> usize {
         self.inner.borrow().len()
     }
+
+    /// Return the audited alarm deliveries, in record order.
+    #[cfg(test)]
+    #[must_use]
+    pub fn deliveries(&self) -> Vec<AlarmDelivery> {
+        self.deliveries.borrow().clone()
+    }
+
+    /// Write every stored item to a Parquet file at `path`, in insertion order.
+    ///
+    /// Columns and null handling are described in
+    /// [`parquet_export`](crate::adapters::parquet_export). Returns the number
+    /// of rows written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParquetError` if the file cannot be created or written.
+    // See struct-level allow(dead_code) comment above.
+    #[cfg(feature = "arrow")]
+    #[allow(dead_code, reason = "used by fraud_detection binary; dead in fraud_detection_sqlite")]
+    pub fn export_parquet(
+        &self,
+        path: &std::path::Path,
+    ) -> Result<usize, parquet::errors::ParquetError> {
+        crate::adapters::parquet_export::write_parquet(&self.inner.borrow(), path)
+    }
 }
 
 impl Storage for InMemoryStorage {
@@ -52,11 +97,13 @@ impl Storage for InMemoryStorage {
     /// # Errors
     ///
     /// Returns `StorageError::CapacityExceeded` when `current_count + batch.len()`
-    /// exceeds the configured capacity.
+    /// exceeds the configured capacity; nothing is written and `remaining` is
+    /// `capacity - current_count`.
     async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
         let inner = self.inner.borrow();
-        if inner.len() + batch.len() > self.capacity {
-            return Err(StorageError::CapacityExceeded { capacity: self.capacity });
+        let remaining = self.capacity.saturating_sub(inner.len());
+        if batch.len() > remaining {
+            return Err(StorageError::CapacityExceeded { capacity: self.capacity, remaining });
         }
         drop(inner);
         self.inner.borrow_mut().extend(batch);
@@ -64,6 +111,58 @@ impl Storage for InMemoryStorage {
     }
 }
 
+impl StorageRead for InMemoryStorage {
+    /// Page through stored items; `position` is the 1-based insertion index.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        let skip = usize::try_from(after).unwrap_or(usize::MAX);
+        Ok(self
+            .inner
+            .borrow()
+            .iter()
+            .zip(1u64..)
+            .skip(skip)
+            .take(limit)
+            .map(|(pending, position)| StoredTransaction { position, pending: pending.clone() })
+            .collect())
+    }
+}
+
+impl Review for InMemoryStorage {
+    /// Mark matching items reviewed at the clock's current time; unknown ids
+    /// are ignored.
+    async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError> {
+        let verdicts: HashMap<_, _> = outcomes.iter().map(|o| (o.id, o.actual_fraud)).collect();
+        let now = self.clock.now();
+        for pt in self.inner.borrow_mut().iter_mut() {
+            if let Some(&actual_fraud) = verdicts.get(&pt.id()) {
+                pt.is_reviewed = true;
+                pt.actual_fraud = Some(actual_fraud);
+                pt.reviewed_at = Some(now);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BucketSink for InMemoryStorage {
+    /// No-op: in memory, buckets are read from the `AggregatingStorage` decorator.
+    async fn upsert_buckets(&self, _buckets: &[MinuteBucket]) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+impl AlarmAudit for InMemoryStorage {
+    /// Append `delivery` to the in-memory audit trail.
+    async fn record_delivery(&self, delivery: &AlarmDelivery) -> Result<(), StorageError> {
+        self.deliveries.borrow_mut().push(delivery.clone());
+        Ok(())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -72,25 +171,31 @@ impl Storage for InMemoryStorage {
 mod tests {
     use super::InMemoryStorage;
     use domain::{
-        InferredTransaction, PendingTransaction, Storage as _, StorageError, Transaction,
+        AlarmAudit as _, AlarmDelivery, AlarmSeverity, Currency, DeliveryOutcome, FixedClock,
+        InferredTransaction, PendingTransaction, Review as _, ReviewOutcome, Storage as _,
+        StorageError, StorageRead as _, Transaction, TransactionId,
     };
+    use reviewer::{Reviewer, ReviewerConfig};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+    #[cfg(feature = "arrow")]
     use uuid::Uuid;
 
     fn make_pending() -> PendingTransaction {
-        PendingTransaction {
-            inferred_transaction: InferredTransaction {
-                transaction: Transaction {
-                    id: Uuid::new_v4(),
-                    amount: 1.00_f64,
-                    last_name: "Test".to_owned(),
-                },
-                predicted_fraud: false,
-                model_name: "DEMO".to_owned(),
-                model_version: "4".to_owned(),
+        PendingTransaction::new(InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: 1.00_f64,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
             },
-            is_reviewed: false,
-            actual_fraud: None,
-        }
+            predicted_fraud: false,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        })
     }
 
     fn make_batch(n: usize) -> Vec<PendingTransaction> {
@@ -105,15 +210,17 @@ mod tests {
         assert_eq!(storage.len(), 5);
     }
 
-    // IMS-T02: CapacityExceeded returned with correct capacity when full.
+    // IMS-T02: CapacityExceeded returned with correct capacity and headroom.
     #[tokio::test]
     async fn capacity_exceeded_correct_value() {
         let storage = InMemoryStorage::new(3);
         let result = storage.write_batch(make_batch(4)).await;
-        assert!(
-            matches!(result, Err(StorageError::CapacityExceeded { capacity: 3 })),
-            "expected CapacityExceeded(3), got {result:?}"
-        );
+        assert_eq!(result, Err(StorageError::CapacityExceeded { capacity: 3, remaining: 3 }));
+
+        storage.write_batch(make_batch(2)).await.unwrap();
+        let result = storage.write_batch(make_batch(2)).await;
+        assert_eq!(result, Err(StorageError::CapacityExceeded { capacity: 3, remaining: 1 }));
+        assert_eq!(storage.len(), 2);
     }
 
     // IMS-T03: multiple batches accumulate.
@@ -124,4 +231,123 @@ mod tests {
         storage.write_batch(make_batch(4)).await.unwrap();
         assert_eq!(storage.len(), 7);
     }
+
+    // IMS-T04: read_page pages through items in insertion order.
+    #[tokio::test]
+    async fn read_page_pages_in_order() {
+        let storage = InMemoryStorage::new(100);
+        let items = make_batch(5);
+        storage.write_batch(items.clone()).await.unwrap();
+
+        let first = storage.read_page(0, 2).await.unwrap();
+        let rest = storage.read_page(first[1].position, 10).await.unwrap();
+        let end = storage.read_page(rest[2].position, 10).await.unwrap();
+
+        let positions: Vec<u64> = first.iter().chain(&rest).map(|s| s.position).collect();
+        assert_eq!(positions, vec![1, 2, 3, 4, 5]);
+        let read: Vec<_> = first.into_iter().chain(rest).map(|s| s.pending).collect();
+        assert_eq!(read, items);
+        assert!(end.is_empty());
+    }
+
+    // IMS-T05: record_reviews updates matching items and ignores unknown ids.
+    #[tokio::test]
+    async fn record_reviews_marks_matching_items() {
+        let storage = InMemoryStorage::new(100);
+        let items = make_batch(3);
+        storage.write_batch(items.clone()).await.unwrap();
+
+        let outcomes = [
+            ReviewOutcome { id: items[1].id(), actual_fraud: true },
+            ReviewOutcome { id: TransactionId::new_v4(), actual_fraud: false },
+        ];
+        storage.record_reviews(&outcomes).await.unwrap();
+
+        let read: Vec<_> =
+            storage.read_page(0, 10).await.unwrap().into_iter().map(|s| s.pending).collect();
+        assert_eq!((read[1].is_reviewed, read[1].actual_fraud), (true, Some(true)));
+        assert_eq!((read[0].is_reviewed, read[0].actual_fraud), (false, None));
+        assert_eq!(read[2], items[2]);
+    }
+
+    // IMS-T06: a Reviewer run leaves every stored item reviewed.
+    #[tokio::test]
+    async fn reviewer_reviews_every_item() {
+        let storage = InMemoryStorage::new(100);
+        storage.write_batch(make_batch(30)).await.unwrap();
+        let reviewer = Reviewer::new(
+            ReviewerConfig::builder(8).seed(7).poll_interval(Duration::ZERO).build().unwrap(),
+        );
+
+        reviewer.run(&storage).await.unwrap();
+
+        let read = storage.read_page(0, 100).await.unwrap();
+        assert!(read.iter().all(|s| s.pending.is_reviewed && s.pending.actual_fraud.is_some()));
+        assert_eq!(reviewer.stats().reviewed, 30);
+    }
+
+    // IMS-T07: record_reviews stamps reviewed_at and keeps persisted_at.
+    #[tokio::test]
+    async fn record_reviews_stamps_reviewed_at() {
+        let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let storage = InMemoryStorage::with_clock(100, Arc::new(FixedClock(t)));
+        let mut items = make_batch(2);
+        items[0].persisted_at = Some(t - Duration::from_mins(1));
+        storage.write_batch(items.clone()).await.unwrap();
+
+        storage
+            .record_reviews(&[ReviewOutcome { id: items[0].id(), actual_fraud: false }])
+            .await
+            .unwrap();
+
+        let read = storage.read_page(0, 10).await.unwrap();
+        assert_eq!(read[0].pending.reviewed_at, Some(t));
+        assert_eq!(read[0].pending.persisted_at, items[0].persisted_at);
+        assert_eq!(read[1].pending.reviewed_at, None);
+    }
+
+    // IMS-T08: export_parquet writes every stored item.
+    #[cfg(feature = "arrow")]
+    #[tokio::test]
+    async fn export_parquet_writes_every_item() {
+        let storage = InMemoryStorage::new(100);
+        storage.write_batch(make_batch(3)).await.unwrap();
+        let path = std::env::temp_dir()
+            .join(format!("fraud_detection_test_{}.parquet", Uuid::new_v4()));
+
+        let written = storage.export_parquet(&path).unwrap();
+
+        let size = std::fs::metadata(&path).map(|m| m.len());
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written, 3);
+        assert!(size.is_ok_and(|len| len > 0));
+    }
+
+    // IMS-T09: record_delivery keeps every delivery in order, beyond capacity.
+    #[tokio::test]
+    async fn record_delivery_keeps_every_delivery() {
+        let storage = InMemoryStorage::new(0);
+        let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let deliveries = [
+            AlarmDelivery {
+                transaction_id: TransactionId::new_v4(),
+                severity: AlarmSeverity::High,
+                delivered_at: t,
+                outcome: DeliveryOutcome::Delivered,
+            },
+            AlarmDelivery {
+                transaction_id: TransactionId::new_v4(),
+                severity: AlarmSeverity::Low,
+                delivered_at: t,
+                outcome: DeliveryOutcome::Failed { reason: "webhook down".to_owned() },
+            },
+        ];
+
+        for delivery in &deliveries {
+            storage.record_delivery(delivery).await.unwrap();
+        }
+
+        assert_eq!(storage.deliveries(), deliveries);
+        assert_eq!(storage.len(), 0);
+    }
 }
@@ -0,0 +1,212 @@
+// Rust guideline compliant 2026-02-27
+
+//! JSON Lines sink adapter for the `Buffer1` port.
+//!
+//! Writes each transaction as one JSON object per line to any `io::Write`
+//! (stdout, a file). Write-only: there is no `Buffer1Read` side, so the
+//! Producer can run on its own against it (see `fraud_load_gen`).
+//!
+//! Also hosts the hand-rolled JSON encoding shared with `HttpBuffer1`; the
+//! workspace has no serde dependency and a transaction has only three fields.
+
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::io::Write;
+
+use domain::{Buffer1, Transaction, WriteError};
+
+// ---------------------------------------------------------------------------
+// JSON encoding
+// ---------------------------------------------------------------------------
+
+/// Encode `tx` as a single-line JSON object:
+/// `{"id":"<uuid>","amount":12.34,"last_name":"Smith","currency":"EUR"}`,
+/// followed by `"tenant_id"` when the transaction has one.
+///
+/// Non-finite amounts (never produced by the Producer) encode as `null`.
+pub fn transaction_json(tx: &Transaction) -> String {
+    let mut out = String::with_capacity(80);
+    out.push_str("{\"id\":\"");
+    // Alternate Display of a TransactionId is the full hyphenated form; no
+    // escaping needed.
+    let _ = write!(out, "{:#}", tx.id);
+    out.push_str("\",\"amount\":");
+    if tx.amount.is_finite() {
+        let _ = write!(out, "{}", tx.amount);
+    } else {
+        out.push_str("null");
+    }
+    out.push_str(",\"last_name\":");
+    push_json_string(&mut out, &tx.last_name);
+    out.push_str(",\"currency\":");
+    push_json_string(&mut out, tx.currency.code());
+    if let Some(tenant) = &tx.tenant_id {
+        out.push_str(",\"tenant_id\":");
+        push_json_string(&mut out, tenant);
+    }
+    out.push('}');
+    out
+}
+
+/// Encode `batch` as a JSON array of [`transaction_json`] objects.
+pub fn batch_json(batch: &[Transaction]) -> String {
+    let mut out = String::with_capacity(2 + batch.len() * 80);
+    out.push('[');
+    for (i, tx) in batch.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&transaction_json(tx));
+    }
+    out.push(']');
+    out
+}
+
+/// Append `s` to `out` as a quoted JSON string (RFC 8259 escaping).
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// ---------------------------------------------------------------------------
+// JsonlBuffer1
+// ---------------------------------------------------------------------------
+
+/// `Buffer1` adapter writing one JSON object per transaction, one per line.
+///
+/// Each batch is written then flushed, so a reader tailing the output sees
+/// whole batches. I/O failures map to [`WriteError::Backend`].
+#[derive(Debug)]
+pub struct JsonlBuffer1<W: Write> {
+    out: RefCell<W>,
+}
+
+impl<W: Write> JsonlBuffer1<W> {
+    /// Wrap `out`. Wrap files in a `BufWriter`; each batch is flushed anyway.
+    #[must_use]
+    pub fn new(out: W) -> Self {
+        Self {
+            out: RefCell::new(out),
+        }
+    }
+}
+
+impl<W: Write> Buffer1 for JsonlBuffer1<W> {
+    /// Write `batch` as JSON lines and flush.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WriteError::Backend`] when writing or flushing fails; never
+    /// `Full` or `Closed`.
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+        let mut out = self.out.borrow_mut();
+        for tx in &batch {
+            writeln!(out, "{}", transaction_json(tx))
+                .map_err(|e| WriteError::Backend(e.to_string()))?;
+        }
+        out.flush().map_err(|e| WriteError::Backend(e.to_string()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonlBuffer1, batch_json, transaction_json};
+    use domain::{Buffer1 as _, Currency, Transaction, TransactionId};
+
+    fn tx(amount: f64, last_name: &str) -> Transaction {
+        Transaction {
+            id: TransactionId::from_uuid(uuid::Uuid::from_u128(1)),
+            amount,
+            last_name: last_name.to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // JL-T01: encoding
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn encodes_transaction_as_json_object() {
+        assert_eq!(
+            transaction_json(&tx(12.5, "Smith")),
+            concat!(
+                r#"{"id":"00000000-0000-0000-0000-000000000001","amount":12.5,"#,
+                r#""last_name":"Smith","currency":"EUR"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn encodes_codes_outside_the_enum_verbatim() {
+        let json = transaction_json(&Transaction {
+            currency: Currency::Other("JPY".to_owned()),
+            ..tx(1.0, "A")
+        });
+        assert!(json.ends_with(r#""currency":"JPY"}"#), "{json}");
+    }
+
+    #[test]
+    fn encodes_tenant_only_when_present() {
+        let json = transaction_json(&Transaction {
+            tenant_id: Some("bank-a".to_owned()),
+            ..tx(1.0, "A")
+        });
+        assert!(json.ends_with(r#""currency":"EUR","tenant_id":"bank-a"}"#), "{json}");
+        assert!(!transaction_json(&tx(1.0, "A")).contains("tenant_id"));
+    }
+
+    #[test]
+    fn escapes_quotes_backslashes_and_control_chars() {
+        let json = transaction_json(&tx(1.0, "O\"Brien\\\n\u{1}"));
+        assert!(
+            json.ends_with(r#""last_name":"O\"Brien\\\n\u0001","currency":"EUR"}"#),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn encodes_batch_as_array() {
+        assert_eq!(batch_json(&[]), "[]");
+        let json = batch_json(&[tx(1.0, "A"), tx(2.0, "B")]);
+        assert!(json.starts_with("[{") && json.ends_with("}]"));
+        assert_eq!(json.matches("},{").count(), 1);
+    }
+
+    // ------------------------------------------------------------------
+    // JL-T02: writes one line per transaction
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn writes_one_line_per_transaction() {
+        let mut out = Vec::new();
+        let buffer = JsonlBuffer1::new(&mut out);
+        buffer
+            .write_batch(vec![tx(1.0, "A"), tx(2.0, "B")])
+            .await
+            .unwrap();
+        buffer.write_batch(vec![tx(3.0, "C")]).await.unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].contains(r#""last_name":"C""#));
+    }
+}
@@ -0,0 +1,192 @@
+// Rust guideline compliant 2026-02-27
+
+//! JSON Lines adapter for the `Storage` and `StorageRead` ports, over the
+//! spill-file format.
+//!
+//! [`JsonlStorage`] reads and appends the one-record-per-line format of the
+//! `spill` module, so spill files (and exports made with this adapter) can be
+//! drained into another backend by `fraud_detection_migrate`, or filled from
+//! one. The file is read once by [`JsonlStorage::open`] and kept in memory
+//! for paging; each batch is then appended and flushed in one write, and
+//! added to the in-memory copy only once the write succeeded.
+//!
+//! Write failures carry the file as `StorageError::Io`.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use domain::{PendingTransaction, Storage, StorageError, StorageRead, StoredTransaction};
+use tokio::io::AsyncWriteExt as _;
+
+use crate::spill::{pending_json, read_spill};
+
+/// `tracing` target of every storage event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::STORAGE;
+
+/// `Storage` adapter over a JSON Lines file in the spill format.
+#[derive(Debug)]
+pub struct JsonlStorage {
+    path: PathBuf,
+    /// Every record of the file, in line order.
+    records: RefCell<Vec<PendingTransaction>>,
+}
+
+impl JsonlStorage {
+    /// Read every record of `path`; a missing file opens empty and is created
+    /// on the first write.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read, or a line
+    /// cannot be decoded.
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let records = if path.exists() { read_spill(&path)? } else { Vec::new() };
+        Ok(Self { path, records: RefCell::new(records) })
+    }
+
+    /// File the records are read from and appended to.
+    #[cfg(test)]
+    #[must_use]
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl Storage for JsonlStorage {
+    /// Append `batch`, one line per record, then flush.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Io` when the file cannot be opened, written or
+    /// flushed; the in-memory copy is left unchanged.
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for pending in &batch {
+            lines.push_str(&pending_json(pending));
+            lines.push('\n');
+        }
+
+        let io = |e: std::io::Error| StorageError::Io { path: self.path.clone(), kind: e.kind() };
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(io)?;
+        file.write_all(lines.as_bytes()).await.map_err(io)?;
+        file.flush().await.map_err(io)?;
+
+        tracing::debug!(
+            target: TARGET,
+            path = %self.path.display(),
+            rows = batch.len(),
+            "jsonl_storage.appended"
+        );
+        self.records.borrow_mut().extend(batch);
+        Ok(())
+    }
+}
+
+impl StorageRead for JsonlStorage {
+    /// Page through the records; `position` is the 1-based line index among
+    /// non-blank lines.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        let skip = usize::try_from(after).unwrap_or(usize::MAX);
+        Ok(self
+            .records
+            .borrow()
+            .iter()
+            .zip(1u64..)
+            .skip(skip)
+            .take(limit)
+            .map(|(pending, position)| StoredTransaction { position, pending: pending.clone() })
+            .collect())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::JsonlStorage;
+    use domain::{
+        Currency, InferredTransaction, PendingTransaction, Storage as _, StorageError,
+        StorageRead as _, Transaction, TransactionId,
+    };
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jsonl-storage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn pending(n: u128) -> PendingTransaction {
+        PendingTransaction::new(InferredTransaction::new(
+            Transaction {
+                id: TransactionId::from_uuid(uuid::Uuid::from_u128(n)),
+                amount: 12.5,
+                last_name: "Doe".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            false,
+            "DEMO",
+            "4",
+        ))
+    }
+
+    // JS-T01: appended records page back in order, and again after reopening.
+    #[tokio::test]
+    async fn appended_records_page_back_after_reopening() {
+        let path = temp_dir().join("out.jsonl");
+        let storage = JsonlStorage::open(&path).unwrap();
+        assert!(storage.read_page(0, 10).await.unwrap().is_empty());
+
+        storage.write_batch(vec![pending(1), pending(2)]).await.unwrap();
+        storage.write_batch(vec![pending(3)]).await.unwrap();
+
+        let reopened = JsonlStorage::open(&path).unwrap();
+        let page = reopened.read_page(1, 10).await.unwrap();
+        assert_eq!(page.iter().map(|s| s.position).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(page[1].pending, pending(3));
+        assert_eq!(reopened.all_ids(10).await.unwrap(), storage.all_ids(10).await.unwrap());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    // JS-T02: an unwritable file fails with Io and leaves the records as they were.
+    #[tokio::test]
+    async fn failed_append_is_io_and_keeps_the_records() {
+        let dir = temp_dir();
+        let storage = JsonlStorage::open(dir.join("missing").join("out.jsonl")).unwrap();
+
+        let err = storage.write_batch(vec![pending(1)]).await.unwrap_err();
+
+        assert!(matches!(err, StorageError::Io { ref path, .. } if path == storage.path()));
+        assert!(storage.read_page(0, 10).await.unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // JS-T03: an undecodable line fails the open, naming the line.
+    #[test]
+    fn undecodable_line_fails_the_open() {
+        let dir = temp_dir();
+        let path = dir.join("bad.jsonl");
+        std::fs::write(&path, "{\"id\":1}\n").unwrap();
+
+        let err = JsonlStorage::open(&path).unwrap_err();
+
+        assert!(format!("{err:#}").contains("bad.jsonl:1"), "{err:#}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -7,9 +7,13 @@
 
 use domain::{Alarm, AlarmError, InferredTransaction};
 
+/// `tracing` target of every alarm event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ALARM;
+
 /// `Alarm` adapter that emits a warning log for each fraudulent transaction.
 ///
 /// Always returns `Ok(())`; use a custom implementation for real alerting.
+/// The `transaction_id` field carries the short 8-character id form.
 #[derive(Debug)]
 pub struct LogAlarm;
 
@@ -29,7 +33,7 @@ impl Default for LogAlarm {
 
 impl Alarm for LogAlarm {
     async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
-        tracing::warn!(transaction_id = %transaction.id(), "log_alarm.fraud_alert");
+        tracing::warn!(target: TARGET, transaction_id = %transaction.id(), "log_alarm.fraud_alert");
         Ok(())
     }
 }
@@ -0,0 +1,432 @@
+// Rust guideline compliant 2026-02-27
+
+//! Decorator for the `Storage` port that mirrors every write to a second
+//! backend, e.g. while migrating from one database to another.
+//!
+//! [`MirroredStorage`] writes each batch, bucket upsert and review to the
+//! primary first and, once the primary accepted it, to the secondary. The
+//! primary is the source of truth: its errors fail the write and the
+//! secondary is then not attempted. A secondary failure is counted and logged
+//! as `mirrored_storage.secondary_failed`, and the write still succeeds,
+//! unless an escalation threshold is set: after that many consecutive
+//! secondary failures the write fails with the non-retryable
+//! `StorageError::MirrorFailed`. Reads and the run lifecycle only reach the
+//! primary; mirrored rows keep the primary's `run_id`.
+//!
+//! # Reconciliation
+//!
+//! When both backends implement `StorageRead`,
+//! [`MirroredStorage::reconcile`] compares their row counts and logs any
+//! difference as `mirrored_storage.drift`;
+//! [`MirroredStorage::reconcile_during`] repeats it on a period while another
+//! future (typically the Logger) runs. A batch written between the two counts
+//! shows as a one-off drift; only a drift that persists across checks points
+//! at lost writes.
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use domain::{
+    BucketSink, MinuteBucket, PendingTransaction, Review, ReviewOutcome, RunId, RunMeta,
+    RunSummary, Storage, StorageError, StorageRead, StoredTransaction, TransactionId,
+};
+
+/// `tracing` target of every storage event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::STORAGE;
+
+/// Row counts of the two backends when they differ, as found by
+/// [`MirroredStorage::reconcile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MirrorDrift {
+    /// Rows in the primary.
+    pub primary: usize,
+    /// Rows in the secondary.
+    pub secondary: usize,
+}
+
+/// `Storage` decorator writing to a primary, then to a secondary backend.
+#[derive(Debug)]
+pub struct MirroredStorage<A, B> {
+    primary: A,
+    secondary: Option<B>,
+    escalate_after: Option<u32>,
+    secondary_failures: Cell<u64>,
+    consecutive_failures: Cell<u32>,
+}
+
+impl<A, B> MirroredStorage<A, B> {
+    /// Mirror `primary` to `secondary`; `None` writes to the primary only, so
+    /// mirroring can be a runtime choice without changing the storage type.
+    ///
+    /// Secondary failures are logged and never fail a write; see
+    /// [`escalate_after`](Self::escalate_after).
+    #[must_use]
+    pub fn new(primary: A, secondary: Option<B>) -> Self {
+        Self {
+            primary,
+            secondary,
+            escalate_after: None,
+            secondary_failures: Cell::new(0),
+            consecutive_failures: Cell::new(0),
+        }
+    }
+
+    /// Fail the write with `StorageError::MirrorFailed` once the secondary
+    /// has failed `failures` writes in a row (at least 1).
+    #[must_use]
+    pub fn escalate_after(mut self, failures: u32) -> Self {
+        self.escalate_after = Some(failures.max(1));
+        self
+    }
+
+    /// Secondary writes that failed since construction.
+    #[must_use]
+    pub fn secondary_failures(&self) -> u64 {
+        self.secondary_failures.get()
+    }
+
+    /// The backend every write reaches first.
+    #[must_use]
+    pub fn primary(&self) -> &A {
+        &self.primary
+    }
+
+    /// The mirror, if any.
+    #[must_use]
+    pub fn secondary(&self) -> Option<&B> {
+        self.secondary.as_ref()
+    }
+
+    /// Account for the outcome of a secondary write of `items` items.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::MirrorFailed` when the failure reaches the
+    /// escalation threshold.
+    fn settle(&self, items: usize, result: Result<(), StorageError>) -> Result<(), StorageError> {
+        let Err(error) = result else {
+            self.consecutive_failures.set(0);
+            return Ok(());
+        };
+        let consecutive = self.consecutive_failures.get().saturating_add(1);
+        self.consecutive_failures.set(consecutive);
+        self.secondary_failures.set(self.secondary_failures.get() + 1);
+        tracing::warn!(
+            target: TARGET,
+            %error,
+            items,
+            consecutive,
+            "mirrored_storage.secondary_failed"
+        );
+        match self.escalate_after {
+            Some(limit) if consecutive >= limit => {
+                tracing::error!(target: TARGET, consecutive, "mirrored_storage.escalated");
+                Err(StorageError::MirrorFailed { failures: consecutive })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<A: StorageRead, B: StorageRead> MirroredStorage<A, B> {
+    /// Compare the row counts of both backends, logging a difference as a
+    /// `mirrored_storage.drift` warning.
+    ///
+    /// Returns `None` when the counts match or there is no secondary.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of a backend that cannot be read.
+    pub async fn reconcile(&self) -> Result<Option<MirrorDrift>, StorageError> {
+        let Some(secondary) = &self.secondary else {
+            return Ok(None);
+        };
+        let primary = self.primary.all_ids(usize::MAX).await?.len();
+        let secondary = secondary.all_ids(usize::MAX).await?.len();
+        if primary == secondary {
+            tracing::debug!(target: TARGET, rows = primary, "mirrored_storage.in_sync");
+            return Ok(None);
+        }
+        tracing::warn!(target: TARGET, primary, secondary, "mirrored_storage.drift");
+        Ok(Some(MirrorDrift { primary, secondary }))
+    }
+
+    /// Run `future` to completion, reconciling every `period` meanwhile.
+    ///
+    /// A failed reconciliation is logged and does not affect `future`.
+    pub async fn reconcile_during<F: Future>(&self, period: Duration, future: F) -> F::Output {
+        let reconcile = async {
+            let mut ticks = tokio::time::interval(period);
+            // The first tick is immediate: wait a full period first.
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                if let Err(e) = self.reconcile().await {
+                    tracing::warn!(target: TARGET, error = %e, "mirrored_storage.reconcile_failed");
+                }
+            }
+        };
+        tokio::select! {
+            output = future => output,
+            () = reconcile => unreachable!("reconciliation loops forever"),
+        }
+    }
+}
+
+impl<A: Storage, B: Storage> Storage for MirroredStorage<A, B> {
+    /// Write `batch` to the primary, then to the secondary.
+    ///
+    /// # Errors
+    ///
+    /// Returns the primary's error, or `StorageError::MirrorFailed` once the
+    /// secondary reaches the escalation threshold.
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        let Some(secondary) = &self.secondary else {
+            return self.primary.write_batch(batch).await;
+        };
+        let items = batch.len();
+        self.primary.write_batch(batch.clone()).await?;
+        self.settle(items, secondary.write_batch(batch).await)
+    }
+
+    /// Forward to the primary only.
+    async fn begin_run(&self, meta: RunMeta) -> Result<RunId, StorageError> {
+        self.primary.begin_run(meta).await
+    }
+
+    /// Forward to the primary only.
+    async fn end_run(&self, id: RunId, summary: RunSummary) -> Result<(), StorageError> {
+        self.primary.end_run(id, summary).await
+    }
+}
+
+impl<A: BucketSink, B: BucketSink> BucketSink for MirroredStorage<A, B> {
+    /// Upsert `buckets` into the primary, then into the secondary.
+    ///
+    /// # Errors
+    ///
+    /// As [`write_batch`](Storage::write_batch).
+    async fn upsert_buckets(&self, buckets: &[MinuteBucket]) -> Result<(), StorageError> {
+        self.primary.upsert_buckets(buckets).await?;
+        let Some(secondary) = &self.secondary else {
+            return Ok(());
+        };
+        self.settle(buckets.len(), secondary.upsert_buckets(buckets).await)
+    }
+}
+
+impl<A: StorageRead, B> StorageRead for MirroredStorage<A, B> {
+    /// Forward to the primary only.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        self.primary.read_page(after, limit).await
+    }
+
+    /// Forward to the primary only.
+    async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+        self.primary.all_ids(limit).await
+    }
+}
+
+impl<A: Review, B: Review> Review for MirroredStorage<A, B> {
+    /// Record `outcomes` in the primary, then in the secondary.
+    ///
+    /// # Errors
+    ///
+    /// As [`write_batch`](Storage::write_batch).
+    async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError> {
+        self.primary.record_reviews(outcomes).await?;
+        let Some(secondary) = &self.secondary else {
+            return Ok(());
+        };
+        self.settle(outcomes.len(), secondary.record_reviews(outcomes).await)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{MirrorDrift, MirroredStorage};
+    use domain::{
+        Currency, InferredTransaction, PendingTransaction, Storage, StorageError, StorageRead,
+        StoredTransaction, Transaction, TransactionId,
+    };
+    use std::cell::{Cell, RefCell};
+    use std::time::Duration;
+
+    /// Storage keeping every written batch; fails writes while `failing`.
+    #[derive(Debug, Default)]
+    struct RecordingStorage {
+        batches: RefCell<Vec<Vec<PendingTransaction>>>,
+        failing: Cell<bool>,
+    }
+
+    impl RecordingStorage {
+        fn failing() -> Self {
+            Self { failing: Cell::new(true), ..Self::default() }
+        }
+    }
+
+    impl Storage for RecordingStorage {
+        async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            if self.failing.get() {
+                return Err(StorageError::Unavailable);
+            }
+            self.batches.borrow_mut().push(batch);
+            Ok(())
+        }
+    }
+
+    impl StorageRead for RecordingStorage {
+        async fn read_page(
+            &self,
+            _after: u64,
+            _limit: usize,
+        ) -> Result<Vec<StoredTransaction>, StorageError> {
+            Ok(Vec::new())
+        }
+
+        async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+            let batches = self.batches.borrow();
+            Ok(batches.iter().flatten().map(PendingTransaction::id).take(limit).collect())
+        }
+    }
+
+    fn make_batch(n: usize) -> Vec<PendingTransaction> {
+        (0..n)
+            .map(|_| {
+                PendingTransaction::new(InferredTransaction {
+                    transaction: Transaction {
+                        id: TransactionId::new_v4(),
+                        amount: 1.00_f64,
+                        last_name: "Test".to_owned(),
+                        currency: Currency::Eur,
+                        tenant_id: None,
+                    },
+                    predicted_fraud: false,
+                    model_name: "DEMO".to_owned(),
+                    model_version: "4".to_owned(),
+                    delivery_attempts: 1,
+                    cached: false,
+                })
+            })
+            .collect()
+    }
+
+    type Mirrored = MirroredStorage<RecordingStorage, RecordingStorage>;
+
+    fn mirrored(secondary: RecordingStorage) -> Mirrored {
+        MirroredStorage::new(RecordingStorage::default(), Some(secondary))
+    }
+
+    // MS-T01: both backends receive the same batches, in order.
+    #[tokio::test]
+    async fn both_backends_receive_identical_batches() {
+        let storage = mirrored(RecordingStorage::default());
+        let (first, second) = (make_batch(3), make_batch(2));
+
+        storage.write_batch(first.clone()).await.unwrap();
+        storage.write_batch(second.clone()).await.unwrap();
+
+        let expected = vec![first, second];
+        assert_eq!(*storage.primary().batches.borrow(), expected);
+        assert_eq!(*storage.secondary().unwrap().batches.borrow(), expected);
+        assert_eq!(storage.secondary_failures(), 0);
+    }
+
+    // MS-T02: without escalation, secondary failures are counted and the
+    // writes still succeed on the primary.
+    #[tokio::test]
+    async fn secondary_failures_are_counted_not_raised() {
+        let storage = mirrored(RecordingStorage::failing());
+
+        for _ in 0..5 {
+            storage.write_batch(make_batch(1)).await.unwrap();
+        }
+
+        assert_eq!(storage.secondary_failures(), 5);
+        assert_eq!(storage.primary().batches.borrow().len(), 5);
+    }
+
+    // MS-T03: with escalate_after(3), the third consecutive failure fails the
+    // write; a success in between resets the streak.
+    #[tokio::test]
+    async fn consecutive_secondary_failures_escalate() {
+        let storage = mirrored(RecordingStorage::failing()).escalate_after(3);
+        let secondary = storage.secondary().unwrap();
+
+        storage.write_batch(make_batch(1)).await.unwrap();
+        storage.write_batch(make_batch(1)).await.unwrap();
+        secondary.failing.set(false);
+        storage.write_batch(make_batch(1)).await.unwrap();
+        secondary.failing.set(true);
+        storage.write_batch(make_batch(1)).await.unwrap();
+        storage.write_batch(make_batch(1)).await.unwrap();
+        let result = storage.write_batch(make_batch(1)).await;
+
+        assert_eq!(result, Err(StorageError::MirrorFailed { failures: 3 }));
+        assert!(!result.unwrap_err().is_retryable());
+        assert_eq!(storage.secondary_failures(), 5);
+    }
+
+    // MS-T04: a primary failure fails the write before the secondary is tried.
+    #[tokio::test]
+    async fn primary_failure_skips_the_secondary() {
+        let storage =
+            MirroredStorage::new(RecordingStorage::failing(), Some(RecordingStorage::default()));
+
+        let result = storage.write_batch(make_batch(2)).await;
+
+        assert_eq!(result, Err(StorageError::Unavailable));
+        assert!(storage.secondary().unwrap().batches.borrow().is_empty());
+    }
+
+    // MS-T05: reconciliation reports a row injected into one backend only.
+    #[tokio::test]
+    async fn reconciliation_detects_a_seeded_discrepancy() {
+        let storage = mirrored(RecordingStorage::default());
+        storage.write_batch(make_batch(4)).await.unwrap();
+        assert_eq!(storage.reconcile().await, Ok(None));
+
+        storage.primary().batches.borrow_mut().push(make_batch(1));
+
+        assert_eq!(
+            storage.reconcile().await,
+            Ok(Some(MirrorDrift { primary: 5, secondary: 4 }))
+        );
+    }
+
+    // MS-T06: reconcile_during returns the future's output, unaffected by the
+    // periodic checks.
+    #[tokio::test(start_paused = true)]
+    async fn reconcile_during_returns_the_future_output() {
+        let storage = mirrored(RecordingStorage::default());
+
+        let output = storage
+            .reconcile_during(Duration::from_secs(1), async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                storage.write_batch(make_batch(1)).await
+            })
+            .await;
+
+        assert_eq!(output, Ok(()));
+        assert_eq!(storage.reconcile().await, Ok(None));
+    }
+
+    // MS-T07: without a secondary, writes reach the primary alone.
+    #[tokio::test]
+    async fn no_secondary_writes_primary_only() {
+        let storage: Mirrored = MirroredStorage::new(RecordingStorage::default(), None);
+
+        storage.write_batch(make_batch(2)).await.unwrap();
+
+        assert_eq!(storage.primary().batches.borrow().len(), 1);
+        assert_eq!(storage.reconcile().await, Ok(None));
+    }
+}
@@ -6,8 +6,56 @@
 //! `domain` crate. Adapters are intentionally isolated from domain and producer
 //! logic.
 
+pub mod aggregating_storage;
+// Not wired into a binary yet: every binary still triggers its alarm inline.
+#[allow(dead_code, reason = "background alarm delivery; not yet used by a binary")]
+pub mod async_alarm_dispatcher;
+pub mod auditing_alarm;
+pub mod bench_model;
+// Only fraud_detection_bench uses this; the other binaries share this tree.
+#[cfg(feature = "bench")]
+#[allow(dead_code, reason = "used by fraud_detection_bench; dead in the other binaries")]
+pub mod bench_storage;
+// Not wired into a binary yet: the Logger still reads a ConcurrentBuffer2.
+#[allow(dead_code, reason = "fan-out Buffer2 adapter; not yet used by a binary")]
+pub mod broadcast_buffer2;
 pub mod concurrent_buffer;
 pub mod concurrent_buffer2;
+// Not wired into a binary yet: every binary persists to memory or SQLite.
+#[allow(dead_code, reason = "spreadsheet-friendly storage; not yet used by a binary")]
+pub mod csv_storage;
+// Only fraud_detection_sqlite evaluates datasets; the other binaries share this tree.
+#[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in the other binaries")]
+pub mod csv_source;
+// Only fraud_detection_sqlite bounds its writes; the other binaries share this tree.
+#[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in the other binaries")]
+pub mod deadline_storage;
 pub mod demo_model;
+// Only fraud_detection_sqlite has a --dry-run; the other binaries share this tree.
+#[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in the other binaries")]
+pub mod dry_run;
+// Not wired into a binary yet: every binary has a single alarm channel.
+#[allow(dead_code, reason = "alarm fallback channel; not yet used by a binary")]
+pub mod escalating_alarm;
+// Resilience tests only: wraps a model to inject latency and failures.
+#[cfg(feature = "bench")]
+#[allow(dead_code, reason = "chaos Model decorator for resilience tests; not yet used by a binary")]
+pub mod flaky_model;
+// fraud_detection quarantines in memory; so does fraud_detection_sqlite under --dry-run.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+pub mod in_memory_quarantine;
 pub mod in_memory_storage;
 pub mod log_alarm;
+// Only fraud_detection_sqlite mirrors its writes; the other binaries share this tree.
+#[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in the other binaries")]
+pub mod mirrored_storage;
+#[cfg(feature = "arrow")]
+pub mod parquet_export;
+// Not wired into a binary yet: Buffer1 stays a ConcurrentBuffer everywhere.
+#[allow(dead_code, reason = "priority lane adapter; not yet used by a binary")]
+pub mod priority_buffer;
+pub mod queue_latency;
+pub mod sampling_alarm;
+// Only fraud_detection_sqlite replays storage; the other binaries share this tree.
+#[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in the other binaries")]
+pub mod storage_source;
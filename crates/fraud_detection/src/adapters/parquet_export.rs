@@ -0,0 +1,217 @@
+// Rust guideline compliant 2026-02-27
+
+//! Arrow / Parquet export of pending transactions (feature `arrow`).
+//!
+//! [`to_record_batch`] flattens `PendingTransaction`s into one Arrow
+//! `RecordBatch` with the columns of [`schema`]; [`write_parquet`] writes that
+//! batch to a Parquet file readable by pandas or polars. `actual_fraud` is the
+//! only nullable column: `None` (not reviewed yet) is written as null.
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, StringArray, UInt8Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use domain::PendingTransaction;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+
+/// Column layout of an export, in order.
+#[must_use]
+pub fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("amount", DataType::Float64, false),
+        Field::new("last_name", DataType::Utf8, false),
+        Field::new("predicted_fraud", DataType::Boolean, false),
+        Field::new("model_name", DataType::Utf8, false),
+        Field::new("model_version", DataType::Utf8, false),
+        Field::new("is_reviewed", DataType::Boolean, false),
+        Field::new("actual_fraud", DataType::Boolean, true),
+        Field::new("delivery_attempts", DataType::UInt8, false),
+    ]))
+}
+
+/// Convert `items` into a single `RecordBatch`, one row per item, in order.
+///
+/// # Errors
+///
+/// Returns `ArrowError` if the columns do not match [`schema`]; this does
+/// not happen for well-formed input.
+pub fn to_record_batch(items: &[PendingTransaction]) -> Result<RecordBatch, ArrowError> {
+    let tx = items.iter().map(|p| &p.inferred_transaction.transaction);
+    let inferred = items.iter().map(|p| &p.inferred_transaction);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(items.iter().map(|p| p.id().full()))),
+        Arc::new(Float64Array::from_iter_values(tx.clone().map(|t| t.amount))),
+        Arc::new(StringArray::from_iter_values(tx.map(|t| &t.last_name))),
+        Arc::new(inferred.clone().map(|i| Some(i.predicted_fraud)).collect::<BooleanArray>()),
+        Arc::new(StringArray::from_iter_values(inferred.clone().map(|i| &i.model_name))),
+        Arc::new(StringArray::from_iter_values(inferred.clone().map(|i| &i.model_version))),
+        Arc::new(items.iter().map(|p| Some(p.is_reviewed)).collect::<BooleanArray>()),
+        Arc::new(items.iter().map(|p| p.actual_fraud).collect::<BooleanArray>()),
+        Arc::new(UInt8Array::from_iter_values(inferred.map(|i| i.delivery_attempts))),
+    ];
+    RecordBatch::try_new(schema(), columns)
+}
+
+/// Write `items` to a new Parquet file at `path`, replacing any existing file.
+///
+/// Returns the number of rows written.
+///
+/// # Errors
+///
+/// Returns `ParquetError` if the file cannot be created or written.
+pub fn write_parquet(items: &[PendingTransaction], path: &Path) -> Result<usize, ParquetError> {
+    let batch = to_record_batch(items)?;
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(batch.num_rows())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{schema, to_record_batch, write_parquet};
+    use arrow::array::Array as _;
+    use arrow::array::AsArray as _;
+    use arrow::datatypes::Float64Type;
+    use arrow::record_batch::RecordBatch;
+    use domain::{Currency, InferredTransaction, PendingTransaction, Transaction, TransactionId};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    /// One exported row: `id`, `amount`, `last_name`, `predicted_fraud`,
+    /// `model_name`, `model_version`, `is_reviewed`, `actual_fraud`.
+    type Row = (String, f64, String, bool, String, String, bool, Option<bool>);
+
+    fn make_pending(amount: f64, predicted: bool, actual: Option<bool>) -> PendingTransaction {
+        let mut pending = PendingTransaction::new(InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount,
+                last_name: format!("Name{amount}"),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud: predicted,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        });
+        pending.is_reviewed = actual.is_some();
+        pending.actual_fraud = actual;
+        pending
+    }
+
+    /// Reviewed and unreviewed items, so `actual_fraud` holds true, false and null.
+    fn known_set() -> Vec<PendingTransaction> {
+        vec![
+            make_pending(12.5, false, None),
+            make_pending(9_000.0, true, Some(true)),
+            make_pending(0.01, true, Some(false)),
+            make_pending(42.0, false, None),
+        ]
+    }
+
+    fn expected_rows(items: &[PendingTransaction]) -> Vec<Row> {
+        items
+            .iter()
+            .map(|p| {
+                let it = &p.inferred_transaction;
+                (
+                    p.id().full(),
+                    it.transaction.amount,
+                    it.transaction.last_name.clone(),
+                    it.predicted_fraud,
+                    it.model_name.clone(),
+                    it.model_version.clone(),
+                    p.is_reviewed,
+                    p.actual_fraud,
+                )
+            })
+            .collect()
+    }
+
+    fn rows(batch: &RecordBatch) -> Vec<Row> {
+        let string = |i: usize| batch.column(i).as_string::<i32>();
+        let boolean = |i: usize| batch.column(i).as_boolean();
+        let amount = batch.column(1).as_primitive::<Float64Type>();
+        (0..batch.num_rows())
+            .map(|r| {
+                (
+                    string(0).value(r).to_owned(),
+                    amount.value(r),
+                    string(2).value(r).to_owned(),
+                    boolean(3).value(r),
+                    string(4).value(r).to_owned(),
+                    string(5).value(r).to_owned(),
+                    boolean(6).value(r),
+                    boolean(7).is_valid(r).then(|| boolean(7).value(r)),
+                )
+            })
+            .collect()
+    }
+
+    fn temp_parquet_path() -> PathBuf {
+        std::env::temp_dir().join(format!("fraud_detection_test_{}.parquet", Uuid::new_v4()))
+    }
+
+    // PQ-T01: the record batch has the export schema and one row per item.
+    #[test]
+    fn record_batch_matches_items() {
+        let items = known_set();
+
+        let batch = to_record_batch(&items).unwrap();
+
+        assert_eq!(batch.schema(), schema());
+        assert_eq!(rows(&batch), expected_rows(&items));
+        assert_eq!(batch.column(7).null_count(), 2);
+    }
+
+    // PQ-T02: a Parquet file read back matches row by row, nulls included.
+    #[test]
+    fn parquet_round_trip_preserves_rows_and_nulls() {
+        let items = known_set();
+        let path = temp_parquet_path();
+
+        let written = write_parquet(&items, &path).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let read: Vec<Row> = reader.flat_map(|batch| rows(&batch.unwrap())).collect();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written, items.len());
+        assert_eq!(read, expected_rows(&items));
+    }
+
+    // PQ-T03: an empty set still writes a readable file with the schema.
+    #[test]
+    fn empty_export_keeps_the_schema() {
+        let path = temp_parquet_path();
+
+        assert_eq!(write_parquet(&[], &path).unwrap(), 0);
+
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let file_schema = Arc::clone(builder.schema());
+        let read = builder.build().unwrap().count();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(file_schema.fields(), schema().fields());
+        assert_eq!(read, 0);
+    }
+}
@@ -0,0 +1,341 @@
+// Rust guideline compliant 2026-02-27
+
+//! Two-lane adapter for the `Buffer1` and `Buffer1Read` ports.
+//!
+//! [`PriorityBuffer`] routes each written transaction by amount: at or above
+//! the threshold it goes to the high-priority lane, below it to the normal
+//! lane. Reads drain the high-priority lane first, so large transactions are
+//! scored without waiting behind a backlog of small ones. Each lane is FIFO.
+//!
+//! With a starvation guard of `k`, at most `k - 1` consecutive reads may leave
+//! waiting normal items untouched; the `k`-th takes at least one of them.
+//!
+//! Like `ConcurrentBuffer`, an empty open buffer cooperatively yields rather
+//! than signaling `Closed`, and `close()` applies to both lanes.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use domain::{Buffer1, Buffer1Read, BufferDepth, ReadError, Transaction, WriteError};
+
+// ---------------------------------------------------------------------------
+// Inner state
+// ---------------------------------------------------------------------------
+
+/// Both lanes, the starvation counter and the close flag.
+#[derive(Debug)]
+struct PriorityBufferInner {
+    high: VecDeque<Transaction>,
+    normal: VecDeque<Transaction>,
+    /// Consecutive reads that left normal items waiting without taking any.
+    reads_without_normal: usize,
+    closed: bool,
+}
+
+impl PriorityBufferInner {
+    /// Take up to `max` items, high lane first, honoring `guard`.
+    fn take(&mut self, max: usize, guard: Option<usize>) -> Vec<Transaction> {
+        let count = max.min(self.high.len() + self.normal.len());
+        let starving = !self.normal.is_empty()
+            && guard.is_some_and(|k| self.reads_without_normal + 1 >= k);
+        // Keep one slot for the normal lane when the guard kicks in.
+        let high_slots = if starving { count.saturating_sub(1) } else { count };
+        let from_high = high_slots.min(self.high.len());
+        let mut batch: Vec<_> = self.high.drain(..from_high).collect();
+        batch.extend(self.normal.drain(..count - from_high));
+
+        self.reads_without_normal = if from_high < count || self.normal.is_empty() {
+            0
+        } else {
+            self.reads_without_normal + 1
+        };
+        batch
+    }
+}
+
+/// Per-lane occupancy of a [`PriorityBuffer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LaneDepths {
+    /// Transactions at or above the threshold waiting to be read.
+    pub high: usize,
+    /// Transactions below the threshold waiting to be read.
+    pub normal: usize,
+}
+
+// ---------------------------------------------------------------------------
+// PriorityBuffer
+// ---------------------------------------------------------------------------
+
+/// `Buffer1` and `Buffer1Read` adapter serving high-amount transactions first.
+///
+/// Shares a single `RefCell` across both trait impls; borrows are always
+/// dropped before the `yield_now` point inside `read_batch`.
+#[derive(Debug)]
+pub struct PriorityBuffer {
+    inner: RefCell<PriorityBufferInner>,
+    threshold: f64,
+    starvation_guard: Option<usize>,
+}
+
+impl PriorityBuffer {
+    /// Create an empty, open buffer routing amounts `>= threshold` to the
+    /// high-priority lane. No starvation guard: the normal lane waits until
+    /// the high-priority lane is empty.
+    ///
+    /// A NaN `threshold` routes everything to the normal lane.
+    #[must_use]
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            inner: RefCell::new(PriorityBufferInner {
+                high: VecDeque::new(),
+                normal: VecDeque::new(),
+                reads_without_normal: 0,
+                closed: false,
+            }),
+            threshold,
+            starvation_guard: None,
+        }
+    }
+
+    /// Take at least one waiting normal item every `k` reads (`0` counts as `1`).
+    #[must_use]
+    pub fn with_starvation_guard(mut self, k: usize) -> Self {
+        self.starvation_guard = Some(k.max(1));
+        self
+    }
+
+    /// Signal end-of-data on both lanes. Idempotent: safe to call multiple times.
+    pub fn close(&self) {
+        self.inner.borrow_mut().closed = true;
+    }
+
+    /// Number of transactions waiting in each lane.
+    #[must_use]
+    pub fn lane_depths(&self) -> LaneDepths {
+        let inner = self.inner.borrow();
+        LaneDepths { high: inner.high.len(), normal: inner.normal.len() }
+    }
+}
+
+impl Buffer1 for PriorityBuffer {
+    /// Route each transaction of `batch` to its lane if open.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WriteError::Closed`] if the buffer has been closed.
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.closed {
+            return Err(WriteError::Closed);
+        }
+        for tx in batch {
+            if tx.amount >= self.threshold {
+                inner.high.push_back(tx);
+            } else {
+                inner.normal.push_back(tx);
+            }
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.borrow().closed
+    }
+}
+
+impl Buffer1Read for PriorityBuffer {
+    /// Drain up to `max` transactions, high-priority lane first; yield and
+    /// retry if both lanes are empty and the buffer is open.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadError::Closed`] when both lanes are empty and closed.
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+        loop {
+            // Scope the borrow so it is dropped before yield_now().await.
+            let result = {
+                let mut inner = self.inner.borrow_mut();
+                if !(inner.high.is_empty() && inner.normal.is_empty()) {
+                    Some(Ok(inner.take(max, self.starvation_guard)))
+                } else if inner.closed {
+                    Some(Err(ReadError::Closed))
+                } else {
+                    None
+                }
+            };
+
+            match result {
+                Some(r) => return r,
+                None => tokio::task::yield_now().await,
+            }
+        }
+    }
+}
+
+impl BufferDepth for PriorityBuffer {
+    /// Number of buffered transactions not yet read, both lanes combined.
+    fn depth(&self) -> usize {
+        let inner = self.inner.borrow();
+        inner.high.len() + inner.normal.len()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{LaneDepths, PriorityBuffer};
+    use domain::{
+        Buffer1 as _, Buffer1Read as _, BufferDepth as _, Currency, ReadError, Transaction,
+        TransactionId, WriteError,
+    };
+
+    const THRESHOLD: f64 = 100.0;
+
+    fn make_tx(amount: f64) -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount,
+            last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    fn amounts(txs: &[Transaction]) -> Vec<f64> {
+        txs.iter().map(|tx| tx.amount).collect()
+    }
+
+    /// Write `values` one transaction per batch, in order.
+    async fn write_amounts(buffer: &PriorityBuffer, values: &[f64]) {
+        for &amount in values {
+            buffer.write_batch(vec![make_tx(amount)]).await.unwrap();
+        }
+    }
+
+    // PB-T01: interleaved writes are read with every high-priority item first.
+    #[tokio::test]
+    async fn high_priority_items_are_read_first() {
+        let buffer = PriorityBuffer::new(THRESHOLD);
+        write_amounts(&buffer, &[1.0, 500.0, 2.0, 3.0, 200.0, 4.0]).await;
+        buffer.close();
+
+        let read = buffer.read_batch(10).await.unwrap();
+
+        assert_eq!(amounts(&read), [500.0, 200.0, 1.0, 2.0, 3.0, 4.0]);
+    }
+
+    // PB-T02: each lane is FIFO across reads; amount == threshold is high priority.
+    #[tokio::test]
+    async fn each_lane_is_fifo() {
+        let buffer = PriorityBuffer::new(THRESHOLD);
+        let batch = [10.0, 100.0, 20.0, 300.0, 30.0, 200.0].into_iter().map(make_tx).collect();
+        buffer.write_batch(batch).await.unwrap();
+        buffer.close();
+
+        assert_eq!(amounts(&buffer.read_batch(2).await.unwrap()), [100.0, 300.0]);
+        assert_eq!(amounts(&buffer.read_batch(2).await.unwrap()), [200.0, 10.0]);
+        assert_eq!(amounts(&buffer.read_batch(2).await.unwrap()), [20.0, 30.0]);
+    }
+
+    // PB-T03: a late high-priority write overtakes the normal backlog.
+    #[tokio::test]
+    async fn late_high_priority_item_skips_the_backlog() {
+        let buffer = PriorityBuffer::new(THRESHOLD);
+        write_amounts(&buffer, &[1.0, 2.0, 3.0]).await;
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [1.0]);
+
+        write_amounts(&buffer, &[900.0]).await;
+
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [900.0]);
+        assert_eq!(amounts(&buffer.read_batch(5).await.unwrap()), [2.0, 3.0]);
+    }
+
+    // PB-T04: without a guard, normal items wait for the high lane to empty.
+    #[tokio::test]
+    async fn no_guard_starves_normal_lane_while_high_is_busy() {
+        let buffer = PriorityBuffer::new(THRESHOLD);
+        write_amounts(&buffer, &[1.0, 500.0, 501.0, 502.0]).await;
+
+        for expected in [500.0, 501.0, 502.0, 1.0] {
+            assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [expected]);
+        }
+    }
+
+    // PB-T05: with a guard of 3, every third read takes a waiting normal item.
+    #[tokio::test]
+    async fn starvation_guard_takes_a_normal_item_every_k_reads() {
+        let buffer = PriorityBuffer::new(THRESHOLD).with_starvation_guard(3);
+        write_amounts(&buffer, &[1.0, 2.0, 500.0, 501.0, 502.0, 503.0, 504.0, 505.0]).await;
+
+        for expected in [500.0, 501.0, 1.0, 502.0, 503.0, 2.0] {
+            assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [expected]);
+        }
+        assert_eq!(buffer.lane_depths(), LaneDepths { high: 2, normal: 0 });
+    }
+
+    // PB-T06: a guarded multi-item read keeps one slot for the normal lane.
+    #[tokio::test]
+    async fn guard_of_one_reserves_a_slot_in_every_read() {
+        let buffer = PriorityBuffer::new(THRESHOLD).with_starvation_guard(1);
+        write_amounts(&buffer, &[1.0, 2.0, 500.0, 501.0, 502.0]).await;
+
+        assert_eq!(amounts(&buffer.read_batch(3).await.unwrap()), [500.0, 501.0, 1.0]);
+        assert_eq!(amounts(&buffer.read_batch(3).await.unwrap()), [502.0, 2.0]);
+    }
+
+    // PB-T07: the guard only counts reads that left normal items waiting.
+    #[tokio::test]
+    async fn guard_counter_ignores_reads_with_empty_normal_lane() {
+        let buffer = PriorityBuffer::new(THRESHOLD).with_starvation_guard(2);
+        write_amounts(&buffer, &[500.0, 501.0, 502.0]).await;
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [500.0]);
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [501.0]);
+
+        write_amounts(&buffer, &[1.0, 503.0]).await;
+
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [502.0]);
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [1.0]);
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [503.0]);
+    }
+
+    // PB-T08: close applies to both lanes; reads drain both, then report Closed.
+    #[tokio::test]
+    async fn close_drains_both_lanes_then_reports_closed() {
+        let buffer = PriorityBuffer::new(THRESHOLD);
+        write_amounts(&buffer, &[1.0, 500.0]).await;
+        buffer.close();
+
+        assert_eq!(buffer.write_batch(vec![make_tx(600.0)]).await, Err(WriteError::Closed));
+        assert_eq!(buffer.write_batch(vec![make_tx(6.0)]).await, Err(WriteError::Closed));
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [500.0]);
+        assert_eq!(amounts(&buffer.read_batch(1).await.unwrap()), [1.0]);
+        assert_eq!(buffer.read_batch(1).await, Err(ReadError::Closed));
+    }
+
+    // PB-T09: lane depths track writes and reads; depth() is their sum.
+    #[tokio::test]
+    async fn lane_depths_track_writes_and_reads() {
+        let buffer = PriorityBuffer::new(THRESHOLD);
+        assert_eq!(buffer.lane_depths(), LaneDepths::default());
+
+        write_amounts(&buffer, &[1.0, 2.0, 3.0, 500.0, 501.0]).await;
+        assert_eq!(buffer.lane_depths(), LaneDepths { high: 2, normal: 3 });
+        assert_eq!(buffer.depth(), 5);
+
+        let _ = buffer.read_batch(3).await.unwrap();
+        assert_eq!(buffer.lane_depths(), LaneDepths { high: 0, normal: 2 });
+        assert_eq!(buffer.depth(), 2);
+    }
+
+    // PB-T10: a NaN threshold routes everything to the normal lane.
+    #[tokio::test]
+    async fn nan_threshold_routes_everything_to_normal_lane() {
+        let buffer = PriorityBuffer::new(f64::NAN);
+        write_amounts(&buffer, &[1.0, 1_000.0]).await;
+
+        assert_eq!(buffer.lane_depths(), LaneDepths { high: 0, normal: 2 });
+    }
+}
@@ -0,0 +1,167 @@
+// Rust guideline compliant 2026-02-27
+
+//! Per-transaction queue delay of the concurrent buffers.
+//!
+//! A [`QueueLatency`] stamps each written transaction id with the tokio
+//! `Instant` of its write and, when a read drains it, records how long it
+//! waited. Memory stays bounded: an id leaves the side map as soon as it is
+//! read, so the map never outgrows the buffer, and delays go to a
+//! `domain::stats::StreamingPercentiles`. The percentiles of [`LatencyStats`]
+//! are never above the true delay and less than 1% below it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use domain::TransactionId;
+use domain::stats::StreamingPercentiles;
+use tokio::time::Instant;
+
+// ---------------------------------------------------------------------------
+// LatencyStats
+// ---------------------------------------------------------------------------
+
+/// Queue delay percentiles of a buffer (nearest rank, see
+/// `StreamingPercentiles::quantile`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencyStats {
+    /// Number of transactions read with a known write time.
+    pub count: u64,
+    /// Median delay.
+    pub p50: Duration,
+    /// 95th percentile delay.
+    pub p95: Duration,
+    /// 99th percentile delay.
+    pub p99: Duration,
+    /// Longest delay.
+    pub max: Duration,
+}
+
+impl fmt::Display for LatencyStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} reads, p50 {:?}, p95 {:?}, p99 {:?}, max {:?}",
+            self.count, self.p50, self.p95, self.p99, self.max
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// QueueLatency
+// ---------------------------------------------------------------------------
+
+/// Write times of the buffered ids and a histogram of their delays.
+#[derive(Debug)]
+pub struct QueueLatency {
+    enqueued: HashMap<TransactionId, Instant>,
+    delays: StreamingPercentiles,
+}
+
+impl QueueLatency {
+    /// Create an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { enqueued: HashMap::new(), delays: StreamingPercentiles::new() }
+    }
+
+    /// Stamp `ids` with the current time.
+    pub fn enqueue(&mut self, ids: impl IntoIterator<Item = TransactionId>) {
+        let now = Instant::now();
+        for id in ids {
+            self.enqueued.insert(id, now);
+        }
+    }
+
+    /// Record the delay of each of `ids` stamped by [`enqueue`](Self::enqueue).
+    pub fn dequeue(&mut self, ids: impl IntoIterator<Item = TransactionId>) {
+        let now = Instant::now();
+        for id in ids {
+            if let Some(written) = self.enqueued.remove(&id) {
+                self.delays.record(now.duration_since(written));
+            }
+        }
+    }
+
+    /// Percentiles of the delays recorded so far; all zero before any read.
+    #[must_use]
+    pub fn stats(&self) -> LatencyStats {
+        LatencyStats {
+            count: self.delays.count(),
+            p50: self.delays.p50(),
+            p95: self.delays.p95(),
+            p99: self.delays.p99(),
+            max: self.delays.max(),
+        }
+    }
+}
+
+impl Default for QueueLatency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::QueueLatency;
+    use domain::TransactionId;
+    use std::time::Duration;
+
+    // QL-T01: delays of 1..=10 ms give exact nearest-rank percentiles.
+    #[tokio::test(start_paused = true)]
+    async fn small_samples_give_exact_percentiles() {
+        let mut latency = QueueLatency::new();
+        let ids: Vec<TransactionId> = (0..10).map(|_| TransactionId::new_v4()).collect();
+        latency.enqueue(ids.iter().copied());
+
+        for id in &ids {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            latency.dequeue([*id]);
+        }
+
+        let stats = latency.stats();
+        assert_eq!(stats.count, 10);
+        assert_eq!(stats.p50, Duration::from_millis(5));
+        assert_eq!(stats.p95, Duration::from_millis(10));
+        assert_eq!(stats.p99, Duration::from_millis(10));
+        assert_eq!(stats.max, Duration::from_millis(10));
+    }
+
+    // QL-T02: unknown ids are ignored and read ids leave the side map.
+    #[tokio::test(start_paused = true)]
+    async fn unknown_and_repeated_ids_are_ignored() {
+        let mut latency = QueueLatency::new();
+        let id = TransactionId::new_v4();
+        latency.dequeue([TransactionId::new_v4()]);
+        assert_eq!(latency.stats(), super::LatencyStats::default());
+
+        latency.enqueue([id]);
+        latency.dequeue([id]);
+        latency.dequeue([id]);
+
+        assert_eq!(latency.stats().count, 1);
+        assert!(latency.enqueued.is_empty());
+    }
+
+    // QL-T03: many reads keep counting every delay.
+    #[tokio::test(start_paused = true)]
+    async fn many_reads_count_every_delay() {
+        let mut latency = QueueLatency::new();
+        let n = 12_288;
+        let ids: Vec<TransactionId> = (0..n).map(|_| TransactionId::new_v4()).collect();
+        latency.enqueue(ids.iter().copied());
+        tokio::time::sleep(Duration::from_millis(2)).await;
+
+        latency.dequeue(ids);
+
+        let stats = latency.stats();
+        assert_eq!(stats.count, u64::try_from(n).unwrap());
+        assert!(latency.enqueued.is_empty());
+        assert_eq!(stats.p50, Duration::from_millis(2));
+    }
+}
@@ -0,0 +1,441 @@
+// Rust guideline compliant 2026-02-27
+
+//! Decorator for the `Alarm` port that samples low-value fraud alerts.
+//!
+//! [`SamplingAlarm`] forwards every alert whose amount is at or above the
+//! threshold. Below it, an alert is forwarded with probability `sample_rate`
+//! and suppressed otherwise, so low-value fraud stays statistically visible
+//! without paging on every occurrence. Suppressed alerts are counted and
+//! summarized in an info log every [`SUMMARY_EVERY`] suppressions.
+//!
+//! The threshold is in EUR. With [`SamplingAlarm::with_converter`], non-EUR
+//! amounts are converted before the comparison, and an amount without a rate
+//! counts as above the threshold: it is never sampled out.
+//!
+//! The RNG is drawn once per below-threshold alert, in call order, so a fixed
+//! seed gives a reproducible sample.
+//!
+//! Sampling drops real fraud alerts, so the binaries only sample on request:
+//! [`MaybeSampled`] forwards every alert unless a sample rate is given.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use domain::{Alarm, AlarmError, CurrencyConverter, InferredTransaction, Transaction};
+use rand::{Rng as _, SeedableRng as _, rngs::StdRng};
+
+/// `tracing` target of every alarm event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ALARM;
+
+/// Suppressed alerts between two `sampling_alarm.summary` logs.
+// See SamplingAlarm allow(dead_code) comment below.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+pub const SUMMARY_EVERY: u64 = 100;
+
+/// Find `--alarm-sample-rate <rate>` or `--alarm-sample-rate=<rate>` in
+/// `args`: the share of below-threshold alerts to forward, in `[0, 1]`.
+///
+/// # Errors
+///
+/// Returns an error if the flag has no value or the value is not a number in
+/// `[0, 1]`.
+// See SamplingAlarm allow(dead_code) comment below.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+pub fn sample_rate_from_args(
+    args: impl IntoIterator<Item = String>,
+) -> anyhow::Result<Option<f64>> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = if arg == "--alarm-sample-rate" {
+            let Some(value) = args.next() else {
+                anyhow::bail!("--alarm-sample-rate requires a value")
+            };
+            value
+        } else if let Some(value) = arg.strip_prefix("--alarm-sample-rate=") {
+            value.to_owned()
+        } else {
+            continue;
+        };
+        return value
+            .parse::<f64>()
+            .ok()
+            .filter(|rate| (0.0..=1.0).contains(rate))
+            .map(Some)
+            .with_context(|| format!("--alarm-sample-rate {value}: expected a number in [0, 1]"));
+    }
+    Ok(None)
+}
+
+/// Alert counters of a [`SamplingAlarm`].
+// See SamplingAlarm allow(dead_code) comment below.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SamplingStats {
+    /// At-or-above-threshold alerts, always forwarded.
+    pub forwarded_high: u64,
+    /// Below-threshold alerts that passed the sample and were forwarded.
+    pub forwarded_sampled: u64,
+    /// Below-threshold alerts dropped by the sample.
+    pub suppressed: u64,
+}
+
+impl fmt::Display for SamplingStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "alarms: {} high-value forwarded, {} low-value sampled in, {} suppressed",
+            self.forwarded_high, self.forwarded_sampled, self.suppressed
+        )
+    }
+}
+
+/// `Alarm` decorator forwarding all high-value and a sample of low-value alerts.
+// #[allow] not #[expect]: dead_code fires in fraud_detection_bench only.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+#[derive(Debug)]
+pub struct SamplingAlarm<A> {
+    inner: A,
+    /// Threshold in EUR.
+    threshold: f64,
+    sample_rate: f64,
+    /// Converts non-EUR amounts for the threshold check; `None` compares them as they are.
+    converter: Option<Arc<dyn CurrencyConverter>>,
+    rng: RefCell<StdRng>,
+    stats: Cell<SamplingStats>,
+}
+
+impl<A> SamplingAlarm<A> {
+    /// Wrap `inner`: alerts below `threshold` are forwarded with probability
+    /// `sample_rate` (clamped to `[0, 1]`).
+    ///
+    /// Seeds the RNG from `seed` if set, otherwise from the OS.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    #[must_use]
+    pub fn new(inner: A, threshold: f64, sample_rate: f64, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        Self {
+            inner,
+            threshold,
+            // NaN clamps to NaN; treat it as "suppress everything".
+            sample_rate: if sample_rate.is_nan() { 0.0 } else { sample_rate.clamp(0.0, 1.0) },
+            rng: RefCell::new(rng),
+            stats: Cell::new(SamplingStats::default()),
+            converter: None,
+        }
+    }
+
+    /// Convert non-EUR amounts to EUR through `converter` before comparing
+    /// them with the threshold.
+    // #[allow] not #[expect]: only tests call this.
+    #[allow(dead_code, reason = "the binaries configure no currency converter")]
+    #[must_use]
+    pub fn with_converter(mut self, converter: Arc<dyn CurrencyConverter>) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Counters so far.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+    #[must_use]
+    pub fn stats(&self) -> SamplingStats {
+        self.stats.get()
+    }
+
+    /// The wrapped alarm.
+    // See struct-level allow(dead_code) comment above.
+    #[allow(dead_code, reason = "used by fraud_detection_sqlite; dead in the other binaries")]
+    #[must_use]
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Whether `transaction` is at or above the threshold, in EUR.
+    fn is_high_value(&self, transaction: &Transaction) -> bool {
+        let amount = match &self.converter {
+            Some(converter) => converter.to_eur(transaction.amount, &transaction.currency),
+            None => Some(transaction.amount),
+        };
+        amount.is_none_or(|amount| amount >= self.threshold)
+    }
+}
+
+impl<A: Alarm> Alarm for SamplingAlarm<A> {
+    /// Forward `transaction` unless it is below the threshold and not sampled.
+    ///
+    /// # Errors
+    ///
+    /// Returns the wrapped alarm's error for forwarded alerts; a suppressed
+    /// alert always succeeds.
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        let mut stats = self.stats.get();
+        if self.is_high_value(&transaction.transaction) {
+            stats.forwarded_high += 1;
+        } else if self.rng.borrow_mut().random_bool(self.sample_rate) {
+            stats.forwarded_sampled += 1;
+        } else {
+            stats.suppressed += 1;
+            self.stats.set(stats);
+            tracing::debug!(
+                target: TARGET,
+                transaction_id = %transaction.id(),
+                "sampling_alarm.suppressed"
+            );
+            if stats.suppressed.is_multiple_of(SUMMARY_EVERY) {
+                tracing::info!(
+                    target: TARGET,
+                    suppressed = stats.suppressed,
+                    sampled_in = stats.forwarded_sampled,
+                    threshold = self.threshold,
+                    "sampling_alarm.summary"
+                );
+            }
+            return Ok(());
+        }
+        self.stats.set(stats);
+        self.inner.trigger(transaction).await
+    }
+}
+
+/// An alarm forwarding every alert, or a [`SamplingAlarm`] over it when a
+/// sample rate was asked for (`--alarm-sample-rate`).
+// See SamplingAlarm allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+#[expect(clippy::large_enum_variant, reason = "one value built at startup, never moved")]
+#[derive(Debug)]
+pub enum MaybeSampled<A> {
+    /// Every alert reaches the wrapped alarm.
+    All(A),
+    /// Alerts below the threshold are sampled.
+    Sampled(SamplingAlarm<A>),
+}
+
+// See SamplingAlarm allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection and fraud_detection_sqlite; dead in fraud_detection_bench")]
+impl<A> MaybeSampled<A> {
+    /// Sample `inner` as [`SamplingAlarm::new`] does when `sample_rate` is
+    /// set; otherwise forward every alert to it.
+    #[must_use]
+    pub fn new(inner: A, threshold: f64, sample_rate: Option<f64>, seed: Option<u64>) -> Self {
+        match sample_rate {
+            Some(rate) => Self::Sampled(SamplingAlarm::new(inner, threshold, rate, seed)),
+            None => Self::All(inner),
+        }
+    }
+
+    /// Sampling counters so far; `None` when nothing is sampled.
+    #[must_use]
+    pub fn stats(&self) -> Option<SamplingStats> {
+        match self {
+            Self::All(_) => None,
+            Self::Sampled(alarm) => Some(alarm.stats()),
+        }
+    }
+
+    /// The wrapped alarm.
+    #[must_use]
+    pub fn inner(&self) -> &A {
+        match self {
+            Self::All(alarm) => alarm,
+            Self::Sampled(alarm) => alarm.inner(),
+        }
+    }
+}
+
+impl<A: Alarm> Alarm for MaybeSampled<A> {
+    /// Forward `transaction` to the wrapped alarm, through the sampler if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns the wrapped alarm's error for forwarded alerts.
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        match self {
+            Self::All(alarm) => alarm.trigger(transaction).await,
+            Self::Sampled(alarm) => alarm.trigger(transaction).await,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{MaybeSampled, SamplingAlarm, SamplingStats, sample_rate_from_args};
+    use domain::{
+        Alarm, AlarmError, Currency, CurrencyConverter, InferredTransaction, Transaction,
+        TransactionId,
+    };
+    use rand::{Rng as _, SeedableRng as _, rngs::StdRng};
+    use std::cell::Cell;
+    use std::sync::Arc;
+
+    /// Counts delivered alerts.
+    #[derive(Default)]
+    struct CountingAlarm {
+        delivered: Cell<u64>,
+    }
+
+    impl Alarm for CountingAlarm {
+        async fn trigger(&self, _transaction: &InferredTransaction) -> Result<(), AlarmError> {
+            self.delivered.set(self.delivered.get() + 1);
+            Ok(())
+        }
+    }
+
+    /// Knows a USD rate only.
+    #[derive(Debug)]
+    struct HalfDollar;
+
+    impl CurrencyConverter for HalfDollar {
+        fn rate_to_eur(&self, currency: &Currency) -> Option<f64> {
+            (*currency == Currency::Usd).then_some(0.5)
+        }
+    }
+
+    fn make_fraud(amount: f64) -> InferredTransaction {
+        make_priced_fraud(amount, Currency::Eur)
+    }
+
+    fn make_priced_fraud(amount: f64, currency: Currency) -> InferredTransaction {
+        InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount,
+                last_name: "Test".to_owned(),
+                currency,
+                tenant_id: None,
+            },
+            predicted_fraud: true,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        }
+    }
+
+    // SA-T01: below the threshold, the pass-through count is exactly the
+    // number of successes the seeded RNG draws.
+    #[tokio::test]
+    async fn low_value_alerts_are_sampled_deterministically() {
+        let alarm = SamplingAlarm::new(CountingAlarm::default(), 10.0, 0.1, Some(42));
+        for _ in 0..1_000 {
+            alarm.trigger(&make_fraud(9.99)).await.unwrap();
+        }
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let expected = (0..1_000).filter(|_| rng.random_bool(0.1)).count() as u64;
+        assert_eq!(alarm.inner.delivered.get(), expected);
+        // Sanity: ~10%, far from all or nothing.
+        assert!((50..=150).contains(&expected), "expected ~100, got {expected}");
+        assert_eq!(
+            alarm.stats(),
+            SamplingStats {
+                forwarded_high: 0,
+                forwarded_sampled: expected,
+                suppressed: 1_000 - expected,
+            }
+        );
+    }
+
+    // SA-T02: at or above the threshold every alert is delivered.
+    #[tokio::test]
+    async fn high_value_alerts_always_delivered() {
+        let alarm = SamplingAlarm::new(CountingAlarm::default(), 10.0, 0.0, Some(1));
+        alarm.trigger(&make_fraud(10.0)).await.unwrap();
+        for _ in 0..99 {
+            alarm.trigger(&make_fraud(250.0)).await.unwrap();
+        }
+
+        assert_eq!(alarm.inner.delivered.get(), 100);
+        assert_eq!(alarm.stats().forwarded_high, 100);
+    }
+
+    // SA-T03: counters split a mixed stream correctly.
+    #[tokio::test]
+    async fn counters_track_each_path() {
+        // Rate 0: every low-value alert is suppressed.
+        let alarm = SamplingAlarm::new(CountingAlarm::default(), 10.0, 0.0, Some(7));
+        for amount in [1.0, 50.0, 2.0, 3.0, 99.0] {
+            alarm.trigger(&make_fraud(amount)).await.unwrap();
+        }
+
+        assert_eq!(
+            alarm.stats(),
+            SamplingStats { forwarded_high: 2, forwarded_sampled: 0, suppressed: 3 }
+        );
+        assert_eq!(alarm.inner.delivered.get(), 2);
+        assert_eq!(
+            alarm.stats().to_string(),
+            "alarms: 2 high-value forwarded, 0 low-value sampled in, 3 suppressed"
+        );
+    }
+
+    // SA-T04: without a sample rate nothing is suppressed, and there are no
+    // sampling counters to report.
+    #[tokio::test]
+    async fn unsampled_alarm_forwards_every_alert() {
+        let alarm = MaybeSampled::new(CountingAlarm::default(), 10.0, None, Some(7));
+        for amount in [1.0, 50.0, 2.0] {
+            alarm.trigger(&make_fraud(amount)).await.unwrap();
+        }
+
+        assert_eq!(alarm.inner().delivered.get(), 3);
+        assert_eq!(alarm.stats(), None);
+    }
+
+    // SA-T05: with a sample rate it samples as a SamplingAlarm does.
+    #[tokio::test]
+    async fn sampled_alarm_reports_its_counters() {
+        let alarm = MaybeSampled::new(CountingAlarm::default(), 10.0, Some(0.0), Some(7));
+        for amount in [1.0, 50.0, 2.0] {
+            alarm.trigger(&make_fraud(amount)).await.unwrap();
+        }
+
+        assert_eq!(alarm.inner().delivered.get(), 1);
+        assert_eq!(
+            alarm.stats(),
+            Some(SamplingStats { forwarded_high: 1, forwarded_sampled: 0, suppressed: 2 })
+        );
+    }
+
+    // SA-T06: `--alarm-sample-rate` takes a number in [0, 1], in either form.
+    #[test]
+    fn sample_rate_from_args_parses_both_forms() {
+        let rate = |list: &[&str]| sample_rate_from_args(list.iter().map(|s| (*s).to_owned()));
+
+        assert_eq!(rate(&[]).unwrap(), None);
+        assert_eq!(rate(&["--alarm-sample-rate", "0.25"]).unwrap(), Some(0.25));
+        assert_eq!(rate(&["--dry-run", "--alarm-sample-rate=1"]).unwrap(), Some(1.0));
+        let missing = ["--alarm-sample-rate"];
+        for bad in [&missing[..], &["--alarm-sample-rate=1.5"], &["--alarm-sample-rate", "x"]] {
+            assert!(rate(bad).is_err(), "{bad:?} must be rejected");
+        }
+    }
+
+    // SA-T07: with a converter the threshold applies to the EUR amount, and
+    // an amount without a rate is never sampled out.
+    #[tokio::test]
+    async fn threshold_applies_to_the_eur_amount() {
+        let alarm = SamplingAlarm::new(CountingAlarm::default(), 10.0, 0.0, Some(7))
+            .with_converter(Arc::new(HalfDollar));
+        // USD 15 is EUR 7.50: below the threshold although 15 is not.
+        alarm.trigger(&make_priced_fraud(15.0, Currency::Usd)).await.unwrap();
+        alarm.trigger(&make_priced_fraud(20.0, Currency::Usd)).await.unwrap();
+        alarm.trigger(&make_priced_fraud(1.0, Currency::Gbp)).await.unwrap();
+        alarm.trigger(&make_fraud(9.99)).await.unwrap();
+
+        assert_eq!(
+            alarm.stats(),
+            SamplingStats { forwarded_high: 2, forwarded_sampled: 0, suppressed: 2 }
+        );
+        assert_eq!(alarm.inner.delivered.get(), 2);
+    }
+}
@@ -20,8 +20,231 @@
 //! for a demo adapter where idempotency is preferred over strict append-only
 //! semantics. A production adapter should use plain `INSERT` and propagate
 //! the constraint-violation error.
+//!
+//! # Connection tuning
+//!
+//! [`SqliteStorage::with_options`] applies [`SqliteStorageOptions`] (WAL journal,
+//! busy timeout, pool size, synchronous level). WAL lets an external reader
+//! (e.g. a `SQLite` browser) coexist with the writer instead of producing
+//! `database is locked` errors. [`SqliteStorage::new`] uses the defaults.
+//!
+//! # Record versions
+//!
+//! Each row stores the `record_version` of its `PendingTransaction`. Databases
+//! created before versioning get the column added on open; their rows keep
+//! `NULL` there and are read back as version 1, with defaults for any field
+//! introduced later. [`SqliteStorage::open_read_only`] adds nothing: the
+//! columns such a database lacks read back as `NULL` all the same.
+//!
+//! `persisted_at` and `reviewed_at` (v3) are nullable millisecond Unix
+//! timestamps, added on open to older databases. `NULL` reads back as `None`.
+//! `reviewed_at` is stamped from the storage's [`Clock`] by `record_reviews`.
+//!
+//! `currency` (v4) is a nullable ISO 4217 code, added on open to older
+//! databases. `NULL` reads back as EUR, the only currency before v4.
+//!
+//! `run_id` (v5) is the nullable id of the `runs` row the record was persisted
+//! under, added on open to older databases. `NULL` reads back as `None`.
+//!
+//! `delivery_attempts` (v6) is the nullable number of Buffer2 writes the
+//! Consumer needed, added on open to older databases. `NULL` reads back as 1.
+//!
+//! `tenant_id` (v7) is the nullable upstream source of the transaction, added
+//! on open to older databases, `quarantined_transactions` included. `NULL`
+//! reads back as `None`.
+//!
+//! `cached` (v8) is the nullable flag of a verdict served from the Consumer's
+//! inference cache, added on open to older databases. `NULL` reads back as
+//! `false`.
+//!
+//! # Write verification
+//!
+//! [`SqliteStorage::with_verification`] turns on a diagnostic read-your-writes
+//! check for chasing suspected silent write failures: after each batch,
+//! `write_batch` selects the just-written ids again and compares every column
+//! with what was sent, failing with `StorageError::VerificationFailed` on the
+//! first difference. It doubles the I/O of every write, so it is off by default
+//! and not meant for production runs.
+//!
+//! # Indexes
+//!
+//! `pending_transactions` is indexed on `is_reviewed` (review queue, see
+//! [`SqliteStorage::fetch_unreviewed`]), `predicted_fraud` (flagged rows) and
+//! `persisted_at` (time ranges; the table has no separate `created_at`).
+//! With `debug` logging on, opening the storage runs `EXPLAIN QUERY PLAN` for
+//! one canonical query per index and logs whether the index is used.
+//!
+//! # Rescoring
+//!
+//! Backfill predictions go to `rescored_predictions`, keyed by transaction id
+//! and model; `pending_transactions` rows are never modified. Each backfill
+//! job's high-water mark (last processed rowid) lives in `rescore_progress`
+//! and is advanced in the same database transaction as the predictions.
+//!
+//! # Runs
+//!
+//! `begin_run` inserts a `runs` row holding the start time, the seeds and the
+//! configuration snapshot (both JSON) and returns its `id`; `end_run` fills
+//! the end time and final counts. A run that did not end cleanly keeps `NULL`
+//! there. Rows of `pending_transactions` join on `run_id`.
+//!
+//! # Alarm audit
+//!
+//! Every audited alarm delivery attempt is appended to `alarm_deliveries`:
+//! transaction id, severity (`"low"` / `"high"`), `delivered_at` in
+//! milliseconds since the Unix epoch, a `delivered` flag (0 / 1) and the
+//! failure reason (`NULL` when delivered). Rows are never updated.
+//!
+//! # Quarantine
+//!
+//! Transactions the Modelizer could not score are kept in
+//! `quarantined_transactions`, keyed by transaction id, with the inference
+//! error as `reason` and `quarantined_at` in milliseconds since the Unix
+//! epoch. Quarantining an id again replaces its row. Rows are listed oldest
+//! (lowest rowid) first and deleted when released.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
 
-use domain::{PendingTransaction, Storage, StorageError};
+use domain::{
+    AlarmAudit, AlarmDelivery, AlarmSeverity, BucketSink, Clock, ConfigError, Currency,
+    DeliveryOutcome, InferredTransaction, MinuteBucket, PendingTransaction, Quarantine,
+    QuarantineFuture, QuarantinedTransaction, RescoreSink, RescoredPrediction, Review,
+    ReviewOutcome, RunId, RunMeta, RunSummary, Storage, StorageError, StorageRead,
+    StoredTransaction, SystemClock, Transaction, TransactionId,
+};
+use sqlx::Row as _;
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow, SqliteSynchronous,
+};
+
+/// `tracing` target of every storage event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::STORAGE;
+
+/// Columns added to `pending_transactions` after v1, as `(column, type)`, in
+/// the order older databases get them on open.
+const PENDING_MIGRATIONS: &[(&str, &str)] = &[
+    ("record_version", "INTEGER"),
+    ("persisted_at", "INTEGER"),
+    ("reviewed_at", "INTEGER"),
+    ("run_id", "INTEGER"),
+    ("delivery_attempts", "INTEGER"),
+    ("cached", "INTEGER"),
+    ("currency", "TEXT"),
+    ("tenant_id", "TEXT"),
+];
+
+/// Secondary indexes on `pending_transactions`, as `(name, column)`.
+const PENDING_INDEXES: &[(&str, &str)] = &[
+    ("idx_pending_is_reviewed", "is_reviewed"),
+    ("idx_pending_predicted_fraud", "predicted_fraud"),
+    ("idx_pending_persisted_at", "persisted_at"),
+];
+
+/// Tables beside `pending_transactions`, created on open.
+const TABLES: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS fraud_counts_by_minute (
+        minute  INTEGER PRIMARY KEY,  -- minutes since the Unix epoch
+        total   INTEGER NOT NULL,
+        flagged INTEGER NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS rescored_predictions (
+        id              TEXT    NOT NULL,
+        model_name      TEXT    NOT NULL,
+        model_version   TEXT    NOT NULL,
+        predicted_fraud INTEGER NOT NULL,
+        scored_at       INTEGER NOT NULL,  -- milliseconds since the Unix epoch
+        PRIMARY KEY (id, model_name, model_version)
+    )",
+    "CREATE TABLE IF NOT EXISTS rescore_progress (
+        job        TEXT    PRIMARY KEY,
+        last_rowid INTEGER NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS alarm_deliveries (
+        transaction_id TEXT    NOT NULL,
+        severity       TEXT    NOT NULL,  -- 'low' / 'high'
+        delivered_at   INTEGER NOT NULL,  -- milliseconds since the Unix epoch
+        delivered      INTEGER NOT NULL,  -- 0 / 1
+        error          TEXT               -- NULL when delivered
+    )",
+    "CREATE TABLE IF NOT EXISTS runs (
+        id         INTEGER PRIMARY KEY AUTOINCREMENT,
+        started_at INTEGER NOT NULL,  -- milliseconds since the Unix epoch
+        seeds      TEXT    NOT NULL,  -- JSON object, seed per stage or model
+        config     TEXT    NOT NULL,  -- JSON configuration snapshot
+        ended_at   INTEGER,           -- NULL until end_run
+        produced   INTEGER,
+        inferred   INTEGER,
+        flagged    INTEGER,
+        persisted  INTEGER
+    )",
+    "CREATE TABLE IF NOT EXISTS quarantined_transactions (
+        id             TEXT    PRIMARY KEY,
+        amount         REAL    NOT NULL,
+        last_name      TEXT    NOT NULL,
+        currency       TEXT    NOT NULL,  -- ISO 4217 code
+        reason         TEXT    NOT NULL,  -- inference error
+        quarantined_at INTEGER NOT NULL,  -- milliseconds since the Unix epoch
+        tenant_id      TEXT               -- upstream source; NULL before v7
+    )",
+];
+
+/// Canonical hot-path queries checked by [`log_query_plans`], as `(name, sql)`.
+const CANONICAL_QUERIES: &[(&str, &str)] = &[
+    (
+        "unreviewed",
+        "SELECT rowid FROM pending_transactions WHERE is_reviewed = 0 ORDER BY rowid LIMIT 100",
+    ),
+    ("flagged", "SELECT rowid FROM pending_transactions WHERE predicted_fraud = 1"),
+    (
+        "time_range",
+        "SELECT rowid FROM pending_transactions WHERE persisted_at >= 0 AND persisted_at < 60000",
+    ),
+];
+
+/// Ids re-selected per verification query, well below `SQLite`'s bound
+/// parameter limit.
+const VERIFY_CHUNK: usize = 500;
+
+/// Connection and pool tuning for [`SqliteStorage::with_options`].
+#[derive(Debug, Clone)]
+pub struct SqliteStorageOptions {
+    /// `PRAGMA journal_mode`; default `Wal`. In-memory databases ignore it.
+    pub journal_mode: SqliteJournalMode,
+    /// How long a connection waits on a locked database before failing; default 5 s, must be > 0.
+    pub busy_timeout: Duration,
+    /// Maximum number of pooled connections; default 4, must be >= 1.
+    pub max_connections: u32,
+    /// `PRAGMA synchronous`; default `Normal`, the usual durability trade-off with WAL.
+    pub synchronous: SqliteSynchronous,
+}
+
+impl Default for SqliteStorageOptions {
+    fn default() -> Self {
+        Self {
+            journal_mode: SqliteJournalMode::Wal,
+            busy_timeout: Duration::from_secs(5),
+            // The Logger writes sequentially; a small pool leaves room for readers.
+            max_connections: 4,
+            synchronous: SqliteSynchronous::Normal,
+        }
+    }
+}
+
+/// One `runs` row, as read back by [`SqliteStorage::runs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunRecord {
+    /// Id assigned by `begin_run`.
+    pub id: RunId,
+    /// What the run recorded when it started.
+    pub meta: RunMeta,
+    /// Final counts; `None` until `end_run`, or if the run did not end cleanly.
+    pub summary: Option<RunSummary>,
+}
 
 /// `Storage` adapter backed by a `SQLite` database file via `sqlx`.
 ///
@@ -31,25 +254,82 @@ use domain::{PendingTransaction, Storage, StorageError};
 #[derive(Debug, Clone)]
 pub struct SqliteStorage {
     pool: sqlx::SqlitePool,
+    /// Time source for `reviewed_at` and `quarantined_at`.
+    clock: Arc<dyn Clock>,
+    /// Read every batch back after writing it (see module-level note).
+    verify: bool,
+    /// Rows read back by the verification, shared by clones.
+    verified_rows: Arc<AtomicU64>,
+    /// `read_page` select list for the [`PENDING_MIGRATIONS`] columns, with
+    /// `NULL` standing in for those a read-only older database lacks.
+    migrated_columns: Arc<str>,
 }
 
 impl SqliteStorage {
-    /// Open or create a `SQLite` database and initialize the schema.
-    ///
-    /// Passes `create_if_missing(true)` so the database file is created on
-    /// first run without manual setup. The `pending_transactions` table is
-    /// created via `CREATE TABLE IF NOT EXISTS`, making repeated calls safe.
+    /// Open or create a `SQLite` database with default [`SqliteStorageOptions`].
     ///
     /// # Errors
     ///
     /// Returns `sqlx::Error` when the connection or schema creation fails.
     pub async fn new(db_url: &str) -> Result<Self, sqlx::Error> {
+        Self::with_options(db_url, SqliteStorageOptions::default()).await
+    }
+
+    /// Open or create a `SQLite` database with explicit tuning and initialize the schema.
+    ///
+    /// Passes `create_if_missing(true)` so the database file is created on
+    /// first run without manual setup. The `pending_transactions`,
+    /// `fraud_counts_by_minute`, `rescored_predictions`, `rescore_progress`,
+    /// `alarm_deliveries`, `runs` and `quarantined_transactions` tables are
+    /// created via `CREATE TABLE IF NOT EXISTS`, making repeated calls safe;
+    /// so are the `pending_transactions` indexes. The effective options are
+    /// logged at `info` level, and the canonical query plans at `debug` level
+    /// (see module-level note).
+    ///
+    /// # Errors
+    ///
+    /// Returns `sqlx::Error::Configuration` wrapping a [`ConfigError`] when
+    /// `max_connections` or `busy_timeout` is zero, otherwise `sqlx::Error`
+    /// when the connection or schema creation fails.
+    pub async fn with_options(
+        db_url: &str,
+        options: SqliteStorageOptions,
+    ) -> Result<Self, sqlx::Error> {
+        if options.max_connections == 0 {
+            return Err(sqlx::Error::Configuration(Box::new(ConfigError::new(
+                "max_connections",
+                options.max_connections,
+                "must be >= 1",
+            ))));
+        }
+        if options.busy_timeout.is_zero() {
+            return Err(sqlx::Error::Configuration(Box::new(ConfigError::new(
+                "busy_timeout",
+                format!("{:?}", options.busy_timeout),
+                "must be > 0",
+            ))));
+        }
+        let max_connections = options.max_connections;
+        tracing::info!(
+            target: TARGET,
+            journal_mode = ?options.journal_mode,
+            busy_timeout = ?options.busy_timeout,
+            max_connections,
+            synchronous = ?options.synchronous,
+            "sqlite.options"
+        );
         // create_if_missing: sqlx 0.8 defaults to false for file databases;
         // enable explicitly so the demo works out of the box on first run.
         let opts = db_url
-            .parse::<sqlx::sqlite::SqliteConnectOptions>()?
-            .create_if_missing(true);
-        let pool = sqlx::SqlitePool::connect_with(opts).await?;
+            .parse::<SqliteConnectOptions>()?
+            .create_if_missing(true)
+            .journal_mode(options.journal_mode)
+            .busy_timeout(options.busy_timeout)
+            .synchronous(options.synchronous);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(opts)
+            .await?;
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS pending_transactions (
                 id              TEXT    PRIMARY KEY,
@@ -59,12 +339,275 @@ impl SqliteStorage {
                 model_name      TEXT    NOT NULL,
                 model_version   TEXT    NOT NULL,
                 is_reviewed     INTEGER NOT NULL DEFAULT 0,
-                actual_fraud    INTEGER           -- NULL / 0 / 1
+                actual_fraud    INTEGER,          -- NULL / 0 / 1
+                record_version  INTEGER,          -- NULL = v1 (pre-versioning row)
+                persisted_at    INTEGER,          -- ms since the Unix epoch; NULL before v3
+                reviewed_at     INTEGER,          -- ms since the Unix epoch; NULL until reviewed
+                currency        TEXT,             -- ISO 4217 code; NULL before v4 (EUR)
+                run_id          INTEGER,          -- runs.id; NULL before v5 or outside a run
+                delivery_attempts INTEGER,        -- Buffer2 write attempts; NULL before v6 (1)
+                tenant_id       TEXT,             -- upstream source; NULL before v7 or untagged
+                cached          INTEGER           -- inference cache hit; NULL before v8 (0)
             )",
         )
         .execute(&pool)
         .await?;
-        Ok(Self { pool })
+        // Forward migrations for databases created before the columns existed.
+        for (column, decl) in PENDING_MIGRATIONS {
+            add_column_if_missing(&pool, "pending_transactions", column, decl).await?;
+        }
+        // After the migrations: persisted_at may only just have been added.
+        for (name, column) in PENDING_INDEXES {
+            sqlx::query(&format!(
+                "CREATE INDEX IF NOT EXISTS {name} ON pending_transactions ({column})"
+            ))
+            .execute(&pool)
+            .await?;
+        }
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            log_query_plans(&pool).await;
+        }
+        for sql in TABLES {
+            sqlx::query(sql).execute(&pool).await?;
+        }
+        add_column_if_missing(&pool, "quarantined_transactions", "tenant_id", "TEXT").await?;
+        let columns: Vec<_> = PENDING_MIGRATIONS.iter().map(|(column, _)| *column).collect();
+        Ok(Self::from_pool(pool, &columns.join(", ")))
+    }
+
+    /// Open an existing `SQLite` database file read-only (`mode=ro`), for
+    /// reading `pending_transactions` without touching the file.
+    ///
+    /// Unlike [`with_options`](Self::with_options), nothing is created or
+    /// migrated and the journal mode is left as it is: the columns an older
+    /// database lacks read back as `NULL`, with the defaults described in the
+    /// module-level note. Every write fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns `sqlx::Error` when the file is missing or cannot be opened.
+    pub async fn open_read_only(path: &Path) -> Result<Self, sqlx::Error> {
+        let opts = SqliteConnectOptions::new().filename(path).read_only(true);
+        let pool = SqlitePoolOptions::new().max_connections(1).connect_with(opts).await?;
+        let existing: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('pending_transactions')")
+                .fetch_all(&pool)
+                .await?;
+        let columns: Vec<_> = PENDING_MIGRATIONS
+            .iter()
+            .map(|(column, _)| {
+                if existing.iter().any(|c| c == column) {
+                    (*column).to_owned()
+                } else {
+                    format!("NULL AS {column}")
+                }
+            })
+            .collect();
+        Ok(Self::from_pool(pool, &columns.join(", ")))
+    }
+
+    /// Wrap a connected `pool`; see the `migrated_columns` field.
+    fn from_pool(pool: sqlx::SqlitePool, migrated_columns: &str) -> Self {
+        Self {
+            pool,
+            clock: Arc::new(SystemClock),
+            verify: false,
+            verified_rows: Arc::new(AtomicU64::new(0)),
+            migrated_columns: migrated_columns.into(),
+        }
+    }
+
+    /// Stamp `reviewed_at` and `quarantined_at` from `clock` instead of the system clock.
+    // #[allow] not #[expect]: only tests call this, so dead_code fires in the
+    // non-test build of fraud_detection_sqlite only.
+    #[allow(dead_code, reason = "clock injection for tests; the binary uses SystemClock")]
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Read each written batch back and compare it column by column with
+    /// what was sent; diagnostic only, it doubles the I/O of every write
+    /// (see module-level note). Off by default.
+    #[must_use]
+    pub fn with_verification(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Rows read back by the write verification so far; always zero with it off.
+    #[allow(dead_code, reason = "inspected by tests; the binary only logs mismatches")]
+    #[must_use]
+    pub fn verified_rows(&self) -> u64 {
+        self.verified_rows.load(Ordering::Relaxed)
+    }
+
+    /// Select the rows of `batch` again and compare them with what was sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::VerificationFailed` naming the first column that
+    /// differs (`"id"` for a missing row), after logging both values at
+    /// `error` level, or `StorageError::Unavailable` on any `sqlx` error.
+    async fn verify_written(&self, batch: &[PendingTransaction]) -> Result<(), StorageError> {
+        for chunk in batch.chunks(VERIFY_CHUNK) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "SELECT rowid, id, amount, last_name, predicted_fraud, model_name,
+                        model_version, is_reviewed, actual_fraud, record_version,
+                        persisted_at, reviewed_at, currency, run_id, delivery_attempts, tenant_id,
+                        cached
+                 FROM pending_transactions
+                 WHERE id IN ({placeholders})"
+            );
+            let mut query = sqlx::query(&sql);
+            for pt in chunk {
+                query = query.bind(pt.id().full());
+            }
+            let stored: BTreeMap<TransactionId, PendingTransaction> = query
+                .fetch_all(&self.pool)
+                .await
+                .and_then(|rows| rows.iter().map(decode_row).collect::<Result<Vec<_>, _>>())
+                .map_err(|e| {
+                    tracing::error!(target: TARGET, "sqlite.verify: {e}");
+                    StorageError::Unavailable
+                })?
+                .into_iter()
+                .map(|row| (row.pending.id(), row.pending))
+                .collect();
+            self.verified_rows.fetch_add(stored.len() as u64, Ordering::Relaxed);
+            for sent in chunk {
+                let id = sent.id();
+                let diff = match stored.get(&id) {
+                    Some(stored) => first_difference(sent, stored),
+                    None => Some(("id", id.full(), "missing".to_owned())),
+                };
+                if let Some((field, sent, stored)) = diff {
+                    tracing::error!(
+                        target: TARGET,
+                        id = %id.full(),
+                        field,
+                        sent,
+                        stored,
+                        "sqlite.verify: mismatch"
+                    );
+                    return Err(StorageError::VerificationFailed { id, field });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return up to `limit` unreviewed rows, oldest (lowest rowid) first.
+    ///
+    /// Served by `idx_pending_is_reviewed`, so the cost does not grow with
+    /// the number of already-reviewed rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error, including a row
+    /// that cannot be decoded. The underlying error is logged at `error` level.
+    // #[allow] not #[expect]: no binary reads the review queue yet, only tests.
+    #[allow(dead_code, reason = "read API exercised by tests; no binary caller yet")]
+    pub async fn fetch_unreviewed(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        sqlx::query(
+            "SELECT rowid, id, amount, last_name, predicted_fraud, model_name,
+                    model_version, is_reviewed, actual_fraud, record_version,
+                    persisted_at, reviewed_at, currency, run_id, delivery_attempts, tenant_id,
+                    cached
+             FROM pending_transactions
+             WHERE is_reviewed = 0
+             ORDER BY rowid
+             LIMIT ?",
+        )
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await
+        .and_then(|rows| rows.iter().map(decode_row).collect::<Result<Vec<_>, _>>())
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.fetch_unreviewed: {e}");
+            StorageError::Unavailable
+        })
+    }
+
+    /// Count transactions whose prediction by `model_name`/`model_version` in
+    /// `rescored_predictions` differs from the original one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error.
+    pub async fn rescore_disagreements(
+        &self,
+        model_name: &str,
+        model_version: &str,
+    ) -> Result<u64, StorageError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*)
+             FROM rescored_predictions r
+             JOIN pending_transactions p ON p.id = r.id
+             WHERE r.model_name = ? AND r.model_version = ?
+               AND r.predicted_fraud != p.predicted_fraud",
+        )
+        .bind(model_name)
+        .bind(model_version)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.rescore_disagreements: {e}");
+            StorageError::Unavailable
+        })?;
+        Ok(u64::try_from(count).unwrap_or(0))
+    }
+
+    /// Return every `alarm_deliveries` row, in insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error, including a row
+    /// that cannot be decoded. The underlying error is logged at `error` level.
+    // #[allow] not #[expect]: no binary reads the audit trail yet, only tests.
+    #[allow(dead_code, reason = "read API exercised by tests; no binary caller yet")]
+    pub async fn alarm_deliveries(&self) -> Result<Vec<AlarmDelivery>, StorageError> {
+        sqlx::query(
+            "SELECT transaction_id, severity, delivered_at, delivered, error
+             FROM alarm_deliveries
+             ORDER BY rowid",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .and_then(|rows| rows.iter().map(decode_delivery).collect::<Result<Vec<_>, _>>())
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.alarm_deliveries: {e}");
+            StorageError::Unavailable
+        })
+    }
+
+    /// Return every `runs` row, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error, including a row
+    /// that cannot be decoded. The underlying error is logged at `error` level.
+    // #[allow] not #[expect]: no binary lists the runs yet, only tests.
+    #[allow(dead_code, reason = "read API exercised by tests; no binary caller yet")]
+    pub async fn runs(&self) -> Result<Vec<RunRecord>, StorageError> {
+        sqlx::query(
+            "SELECT id, started_at, seeds, config, ended_at, produced, inferred, flagged,
+                    persisted
+             FROM runs
+             ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .and_then(|rows| rows.iter().map(decode_run).collect::<Result<Vec<_>, _>>())
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.runs: {e}");
+            StorageError::Unavailable
+        })
     }
 }
 
@@ -81,7 +624,7 @@ impl Storage for SqliteStorage {
     /// failure, disk full, constraint violation, etc.). The underlying error
     /// is logged at `error` level before mapping.
     async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
-        for pt in batch {
+        for pt in &batch {
             let tx = &pt.inferred_transaction.transaction;
             let it = &pt.inferred_transaction;
             // Map Option<bool> -> Option<i64> for the nullable INTEGER column:
@@ -90,10 +633,12 @@ impl Storage for SqliteStorage {
             sqlx::query(
                 "INSERT OR REPLACE INTO pending_transactions
                  (id, amount, last_name, predicted_fraud, model_name,
-                  model_version, is_reviewed, actual_fraud)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                  model_version, is_reviewed, actual_fraud, record_version,
+                  persisted_at, reviewed_at, currency, run_id, delivery_attempts,
+                  tenant_id, cached)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             )
-            .bind(tx.id.to_string())
+            .bind(tx.id.full())
             .bind(tx.amount)
             .bind(&tx.last_name)
             .bind(i64::from(it.predicted_fraud))
@@ -101,10 +646,171 @@ impl Storage for SqliteStorage {
             .bind(&it.model_version)
             .bind(i64::from(pt.is_reviewed))
             .bind(actual_fraud)
+            .bind(i64::from(pt.record_version))
+            .bind(pt.persisted_at.map(unix_millis))
+            .bind(pt.reviewed_at.map(unix_millis))
+            .bind(tx.currency.code())
+            .bind(pt.run_id.map(|id| i64::try_from(id.0).unwrap_or(i64::MAX)))
+            .bind(i64::from(it.delivery_attempts))
+            .bind(tx.tenant_id.as_deref())
+            .bind(i64::from(it.cached))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!(target: TARGET, "sqlite.write_batch: {e}");
+                StorageError::Unavailable
+            })?;
+        }
+        if self.verify {
+            self.verify_written(&batch).await?;
+        }
+        Ok(())
+    }
+
+    /// Insert a `runs` row for `meta` and return its id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error, or if the
+    /// seeds cannot be encoded. The underlying error is logged at `error` level.
+    async fn begin_run(&self, meta: RunMeta) -> Result<RunId, StorageError> {
+        let seeds = serde_json::to_string(&meta.seeds).map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.begin_run: {e}");
+            StorageError::Unavailable
+        })?;
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO runs (started_at, seeds, config) VALUES (?, ?, ?) RETURNING id",
+        )
+        .bind(unix_millis(meta.started_at))
+        .bind(seeds)
+        .bind(&meta.config)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.begin_run: {e}");
+            StorageError::Unavailable
+        })?;
+        let id = RunId(u64::try_from(id).unwrap_or(0));
+        tracing::info!(target: TARGET, run_id = id.0, "sqlite.begin_run");
+        Ok(id)
+    }
+
+    /// Set the end time and final counts of the `runs` row `id`.
+    ///
+    /// An unknown id updates nothing and is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error. The underlying
+    /// error is logged at `error` level.
+    async fn end_run(&self, id: RunId, summary: RunSummary) -> Result<(), StorageError> {
+        let count = |n: u64| i64::try_from(n).unwrap_or(i64::MAX);
+        sqlx::query(
+            "UPDATE runs
+             SET ended_at = ?, produced = ?, inferred = ?, flagged = ?, persisted = ?
+             WHERE id = ?",
+        )
+        .bind(unix_millis(summary.ended_at))
+        .bind(count(summary.produced))
+        .bind(count(summary.inferred))
+        .bind(count(summary.flagged))
+        .bind(count(summary.persisted))
+        .bind(count(id.0))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.end_run: {e}");
+            StorageError::Unavailable
+        })?;
+        Ok(())
+    }
+}
+
+impl StorageRead for SqliteStorage {
+    /// Page through `pending_transactions` by `rowid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error, including a row
+    /// that cannot be decoded. The underlying error is logged at `error` level.
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        let sql = format!(
+            "SELECT rowid, id, amount, last_name, predicted_fraud, model_name,
+                    model_version, is_reviewed, actual_fraud, {}
+             FROM pending_transactions
+             WHERE rowid > ?
+             ORDER BY rowid
+             LIMIT ?",
+            self.migrated_columns
+        );
+        let rows = sqlx::query(&sql)
+            .bind(i64::try_from(after).unwrap_or(i64::MAX))
+            .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+            .fetch_all(&self.pool)
+            .await
+            .and_then(|rows| rows.iter().map(decode_row).collect::<Result<Vec<_>, _>>())
+            .map_err(|e| {
+                tracing::error!(target: TARGET, "sqlite.read_page: {e}");
+                StorageError::Unavailable
+            })?;
+        Ok(rows)
+    }
+
+    /// Select only the `id` column, by `rowid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error, including an
+    /// id that does not parse. The underlying error is logged at `error` level.
+    async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+        let ids: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM pending_transactions ORDER BY rowid LIMIT ?")
+                .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| {
+                    tracing::error!(target: TARGET, "sqlite.all_ids: {e}");
+                    StorageError::Unavailable
+                })?;
+        ids.iter()
+            .map(|id| id.parse::<TransactionId>())
+            .collect::<Result<_, _>>()
+            .map_err(|e| {
+                tracing::error!(target: TARGET, "sqlite.all_ids: {e}");
+                StorageError::Unavailable
+            })
+    }
+}
+
+impl Review for SqliteStorage {
+    /// Set `is_reviewed = 1`, `actual_fraud` and `reviewed_at` (the clock's
+    /// current time, shared by the whole call) on each matching row.
+    ///
+    /// Ids with no row update nothing and are not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error. The underlying
+    /// error is logged at `error` level.
+    async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError> {
+        let reviewed_at = unix_millis(self.clock.now());
+        for o in outcomes {
+            sqlx::query(
+                "UPDATE pending_transactions
+                 SET is_reviewed = 1, actual_fraud = ?, reviewed_at = ?
+                 WHERE id = ?",
+            )
+            .bind(i64::from(o.actual_fraud))
+            .bind(reviewed_at)
+            .bind(o.id.full())
             .execute(&self.pool)
             .await
             .map_err(|e| {
-                tracing::error!("sqlite.write_batch: {e}");
+                tracing::error!(target: TARGET, "sqlite.record_reviews: {e}");
                 StorageError::Unavailable
             })?;
         }
@@ -112,14 +818,482 @@ impl Storage for SqliteStorage {
     }
 }
 
+/// Decode one `pending_transactions` row, branching on its record version.
+fn decode_row(row: &SqliteRow) -> Result<StoredTransaction, sqlx::Error> {
+    // NULL: the row predates versioning and is a v1 record.
+    let record_version = match row.try_get::<Option<i64>, _>("record_version")? {
+        None => 1,
+        Some(v) => u32::try_from(v).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "record_version".to_owned(),
+            source: Box::new(e),
+        })?,
+    };
+    let id: String = row.try_get("id")?;
+    let id = id.parse::<TransactionId>().map_err(|e| sqlx::Error::ColumnDecode {
+        index: "id".to_owned(),
+        source: Box::new(e),
+    })?;
+    // v1 and v2 share the same columns; fields added by later versions are
+    // read here only when `record_version` says the row has them. The v3
+    // timestamps are nullable, so older rows simply read back as `None`;
+    // a v4 `currency` of NULL is EUR, the implicit currency of older rows.
+    let currency = match row.try_get::<Option<String>, _>("currency")? {
+        Some(code) => {
+            let Ok(currency) = code.parse::<Currency>();
+            currency
+        }
+        None => Currency::Eur,
+    };
+    let run_id = row.try_get::<Option<i64>, _>("run_id")?;
+    let run_id = run_id.map(|v| RunId(u64::try_from(v).unwrap_or(0)));
+    // NULL: the row predates v6, when every write took a single attempt.
+    let delivery_attempts = row.try_get::<Option<i64>, _>("delivery_attempts")?;
+    let delivery_attempts = delivery_attempts.map_or(1, |v| u8::try_from(v).unwrap_or(u8::MAX));
+    let pending = PendingTransaction {
+        inferred_transaction: InferredTransaction {
+            transaction: Transaction {
+                id,
+                amount: row.try_get("amount")?,
+                last_name: row.try_get("last_name")?,
+                currency,
+                tenant_id: row.try_get("tenant_id")?,
+            },
+            predicted_fraud: row.try_get::<i64, _>("predicted_fraud")? != 0,
+            model_name: row.try_get("model_name")?,
+            model_version: row.try_get("model_version")?,
+            delivery_attempts,
+            // NULL: the row predates v8, when every verdict came from the model.
+            cached: row.try_get::<Option<i64>, _>("cached")?.is_some_and(|v| v != 0),
+        },
+        is_reviewed: row.try_get::<i64, _>("is_reviewed")? != 0,
+        actual_fraud: row.try_get::<Option<i64>, _>("actual_fraud")?.map(|v| v != 0),
+        record_version,
+        persisted_at: row.try_get::<Option<i64>, _>("persisted_at")?.map(from_unix_millis),
+        reviewed_at: row.try_get::<Option<i64>, _>("reviewed_at")?.map(from_unix_millis),
+        run_id,
+    };
+    let rowid: i64 = row.try_get("rowid")?;
+    Ok(StoredTransaction { position: u64::try_from(rowid).unwrap_or(0), pending })
+}
+
+/// First column of `stored` that differs from `sent`, with both values
+/// formatted for the log. Timestamps compare at the stored millisecond.
+fn first_difference(
+    sent: &PendingTransaction,
+    stored: &PendingTransaction,
+) -> Option<(&'static str, String, String)> {
+    fn differs<T: PartialEq + fmt::Debug>(
+        field: &'static str,
+        sent: &T,
+        stored: &T,
+    ) -> Option<(&'static str, String, String)> {
+        (sent != stored).then(|| (field, format!("{sent:?}"), format!("{stored:?}")))
+    }
+    let (s, t) = (&sent.inferred_transaction, &stored.inferred_transaction);
+    let millis = |at: Option<SystemTime>| at.map(unix_millis);
+    differs("amount", &s.transaction.amount, &t.transaction.amount)
+        .or_else(|| differs("last_name", &s.transaction.last_name, &t.transaction.last_name))
+        .or_else(|| differs("currency", &s.transaction.currency, &t.transaction.currency))
+        .or_else(|| differs("tenant_id", &s.transaction.tenant_id, &t.transaction.tenant_id))
+        .or_else(|| differs("predicted_fraud", &s.predicted_fraud, &t.predicted_fraud))
+        .or_else(|| differs("model_name", &s.model_name, &t.model_name))
+        .or_else(|| differs("model_version", &s.model_version, &t.model_version))
+        .or_else(|| differs("delivery_attempts", &s.delivery_attempts, &t.delivery_attempts))
+        .or_else(|| differs("cached", &s.cached, &t.cached))
+        .or_else(|| differs("is_reviewed", &sent.is_reviewed, &stored.is_reviewed))
+        .or_else(|| differs("actual_fraud", &sent.actual_fraud, &stored.actual_fraud))
+        .or_else(|| differs("record_version", &sent.record_version, &stored.record_version))
+        .or_else(|| {
+            differs("persisted_at", &millis(sent.persisted_at), &millis(stored.persisted_at))
+        })
+        .or_else(|| differs("reviewed_at", &millis(sent.reviewed_at), &millis(stored.reviewed_at)))
+        .or_else(|| differs("run_id", &sent.run_id, &stored.run_id))
+}
+
+/// Decode one `runs` row; the summary is `None` while `ended_at` is `NULL`.
+fn decode_run(row: &SqliteRow) -> Result<RunRecord, sqlx::Error> {
+    let seeds: String = row.try_get("seeds")?;
+    let seeds: BTreeMap<String, u64> =
+        serde_json::from_str(&seeds).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "seeds".to_owned(),
+            source: Box::new(e),
+        })?;
+    let count = |column: &str| -> Result<u64, sqlx::Error> {
+        let n = row.try_get::<Option<i64>, _>(column)?.unwrap_or(0);
+        Ok(u64::try_from(n).unwrap_or(0))
+    };
+    let summary = match row.try_get::<Option<i64>, _>("ended_at")? {
+        None => None,
+        Some(ended_at) => Some(RunSummary {
+            ended_at: from_unix_millis(ended_at),
+            produced: count("produced")?,
+            inferred: count("inferred")?,
+            flagged: count("flagged")?,
+            persisted: count("persisted")?,
+        }),
+    };
+    let id: i64 = row.try_get("id")?;
+    Ok(RunRecord {
+        id: RunId(u64::try_from(id).unwrap_or(0)),
+        meta: RunMeta {
+            started_at: from_unix_millis(row.try_get("started_at")?),
+            seeds,
+            config: row.try_get("config")?,
+        },
+        summary,
+    })
+}
+
+/// Decode one `alarm_deliveries` row.
+fn decode_delivery(row: &SqliteRow) -> Result<AlarmDelivery, sqlx::Error> {
+    let id: String = row.try_get("transaction_id")?;
+    let transaction_id = id.parse::<TransactionId>().map_err(|e| sqlx::Error::ColumnDecode {
+        index: "transaction_id".to_owned(),
+        source: Box::new(e),
+    })?;
+    let severity: String = row.try_get("severity")?;
+    let severity = AlarmSeverity::parse(&severity).ok_or_else(|| sqlx::Error::ColumnDecode {
+        index: "severity".to_owned(),
+        source: format!("unknown severity {severity:?}").into(),
+    })?;
+    let outcome = if row.try_get::<i64, _>("delivered")? != 0 {
+        DeliveryOutcome::Delivered
+    } else {
+        // A failed row always has a reason; an empty one only if written by hand.
+        DeliveryOutcome::Failed {
+            reason: row.try_get::<Option<String>, _>("error")?.unwrap_or_default(),
+        }
+    };
+    Ok(AlarmDelivery {
+        transaction_id,
+        severity,
+        delivered_at: from_unix_millis(row.try_get("delivered_at")?),
+        outcome,
+    })
+}
+
+/// Decode one `quarantined_transactions` row.
+fn decode_quarantined(row: &SqliteRow) -> Result<QuarantinedTransaction, sqlx::Error> {
+    let id: String = row.try_get("id")?;
+    let id = id.parse::<TransactionId>().map_err(|e| sqlx::Error::ColumnDecode {
+        index: "id".to_owned(),
+        source: Box::new(e),
+    })?;
+    let currency: String = row.try_get("currency")?;
+    let Ok(currency) = currency.parse::<Currency>();
+    Ok(QuarantinedTransaction {
+        transaction: Transaction {
+            id,
+            amount: row.try_get("amount")?,
+            last_name: row.try_get("last_name")?,
+            currency,
+            tenant_id: row.try_get("tenant_id")?,
+        },
+        reason: row.try_get("reason")?,
+        quarantined_at: from_unix_millis(row.try_get("quarantined_at")?),
+    })
+}
+
+/// Milliseconds since the Unix epoch; pre-epoch times clamp to 0.
+fn unix_millis(t: SystemTime) -> i64 {
+    let ms = t.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis();
+    i64::try_from(ms).unwrap_or(i64::MAX)
+}
+
+/// Inverse of [`unix_millis`]; negative values clamp to the epoch.
+fn from_unix_millis(ms: i64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis(u64::try_from(ms).unwrap_or(0))
+}
+
+/// Run `EXPLAIN QUERY PLAN` for each of [`CANONICAL_QUERIES`]; return
+/// `(name, uses_index, plan)` per query, `plan` joining the detail lines.
+async fn query_plans(
+    pool: &sqlx::SqlitePool,
+) -> Result<Vec<(&'static str, bool, String)>, sqlx::Error> {
+    let mut plans = Vec::with_capacity(CANONICAL_QUERIES.len());
+    for &(name, sql) in CANONICAL_QUERIES {
+        let details: Vec<String> = sqlx::query(&format!("EXPLAIN QUERY PLAN {sql}"))
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| row.try_get("detail"))
+            .collect::<Result<_, _>>()?;
+        // e.g. "SEARCH pending_transactions USING COVERING INDEX idx_... (is_reviewed=?)"
+        let uses_index = details.iter().any(|d| d.contains(" INDEX idx_pending_"));
+        plans.push((name, uses_index, details.join("; ")));
+    }
+    Ok(plans)
+}
+
+/// Log at `debug` level whether each canonical query uses an index.
+///
+/// A diagnostic only: failures are logged as warnings, never returned.
+async fn log_query_plans(pool: &sqlx::SqlitePool) {
+    match query_plans(pool).await {
+        Ok(plans) => {
+            for (query, uses_index, plan) in plans {
+                tracing::debug!(target: TARGET, query, uses_index, %plan, "sqlite.query_plan");
+            }
+        }
+        Err(e) => tracing::warn!(target: TARGET, "sqlite.query_plan: {e}"),
+    }
+}
+
+/// Add `column` to `table` when an older database lacks it.
+async fn add_column_if_missing(
+    pool: &sqlx::SqlitePool,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> Result<(), sqlx::Error> {
+    let columns: Vec<String> =
+        sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{table}')"))
+            .fetch_all(pool)
+            .await?;
+    if !columns.iter().any(|c| c == column) {
+        tracing::info!(target: TARGET, table, column, "sqlite.migrate: adding column");
+        sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+impl BucketSink for SqliteStorage {
+    /// Upsert each bucket into `fraud_counts_by_minute`, keyed by `minute`.
+    ///
+    /// `ON CONFLICT ... DO UPDATE` replaces the counts of an existing minute,
+    /// so flushing the same cumulative bucket twice leaves one row.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error.
+    async fn upsert_buckets(&self, buckets: &[MinuteBucket]) -> Result<(), StorageError> {
+        // SQLite INTEGER is i64; counts never approach i64::MAX, saturate defensively.
+        let to_i64 = |v: u64| i64::try_from(v).unwrap_or(i64::MAX);
+        for b in buckets {
+            sqlx::query(
+                "INSERT INTO fraud_counts_by_minute (minute, total, flagged)
+                 VALUES (?, ?, ?)
+                 ON CONFLICT(minute) DO UPDATE SET
+                    total = excluded.total, flagged = excluded.flagged",
+            )
+            .bind(to_i64(b.minute))
+            .bind(to_i64(b.total))
+            .bind(to_i64(b.flagged))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!(target: TARGET, "sqlite.upsert_buckets: {e}");
+                StorageError::Unavailable
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl RescoreSink for SqliteStorage {
+    /// Read `job`'s `last_rowid` from `rescore_progress`; `0` when absent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error.
+    async fn rescore_high_water(&self, job: &str) -> Result<u64, StorageError> {
+        let last: Option<i64> =
+            sqlx::query_scalar("SELECT last_rowid FROM rescore_progress WHERE job = ?")
+                .bind(job)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    tracing::error!(target: TARGET, "sqlite.rescore_high_water: {e}");
+                    StorageError::Unavailable
+                })?;
+        Ok(last.and_then(|v| u64::try_from(v).ok()).unwrap_or(0))
+    }
+
+    /// Upsert `rows` into `rescored_predictions` and `job`'s high-water mark
+    /// into `rescore_progress`, in one database transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error; nothing is
+    /// committed in that case.
+    async fn write_rescored(
+        &self,
+        job: &str,
+        rows: &[RescoredPrediction],
+        high_water: u64,
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.write_rescored: {e}");
+            StorageError::Unavailable
+        })?;
+        for r in rows {
+            sqlx::query(
+                "INSERT OR REPLACE INTO rescored_predictions
+                 (id, model_name, model_version, predicted_fraud, scored_at)
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(r.id.full())
+            .bind(&r.model_name)
+            .bind(&r.model_version)
+            .bind(i64::from(r.predicted_fraud))
+            .bind(unix_millis(r.scored_at))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!(target: TARGET, "sqlite.write_rescored: {e}");
+                StorageError::Unavailable
+            })?;
+        }
+        sqlx::query(
+            "INSERT INTO rescore_progress (job, last_rowid) VALUES (?, ?)
+             ON CONFLICT(job) DO UPDATE SET last_rowid = excluded.last_rowid",
+        )
+        .bind(job)
+        .bind(i64::try_from(high_water).unwrap_or(i64::MAX))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.write_rescored: {e}");
+            StorageError::Unavailable
+        })?;
+        // Dropping `tx` on an early return above rolls it back.
+        tx.commit().await.map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.write_rescored: {e}");
+            StorageError::Unavailable
+        })
+    }
+}
+
+impl AlarmAudit for SqliteStorage {
+    /// Append `delivery` to `alarm_deliveries`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error.
+    async fn record_delivery(&self, delivery: &AlarmDelivery) -> Result<(), StorageError> {
+        let error = match &delivery.outcome {
+            DeliveryOutcome::Delivered => None,
+            DeliveryOutcome::Failed { reason } => Some(reason.as_str()),
+        };
+        sqlx::query(
+            "INSERT INTO alarm_deliveries
+             (transaction_id, severity, delivered_at, delivered, error)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(delivery.transaction_id.full())
+        .bind(delivery.severity.as_str())
+        .bind(unix_millis(delivery.delivered_at))
+        .bind(i64::from(delivery.outcome.is_delivered()))
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(target: TARGET, "sqlite.record_delivery: {e}");
+            StorageError::Unavailable
+        })?;
+        Ok(())
+    }
+}
+
+impl Quarantine for SqliteStorage {
+    /// Insert or replace the `quarantined_transactions` row for `tx`, stamped
+    /// with the clock's current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error.
+    fn record<'a>(&'a self, tx: &'a Transaction, reason: &'a str) -> QuarantineFuture<'a, ()> {
+        Box::pin(async move {
+            sqlx::query(
+                "INSERT OR REPLACE INTO quarantined_transactions
+                 (id, amount, last_name, currency, reason, quarantined_at, tenant_id)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(tx.id.full())
+            .bind(tx.amount)
+            .bind(&tx.last_name)
+            .bind(tx.currency.code())
+            .bind(reason)
+            .bind(unix_millis(self.clock.now()))
+            .bind(tx.tenant_id.as_deref())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!(target: TARGET, "sqlite.record_quarantine: {e}");
+                StorageError::Unavailable
+            })?;
+            Ok(())
+        })
+    }
+
+    /// Return up to `max` rows, oldest (lowest rowid) first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error, including a row
+    /// that cannot be decoded.
+    fn quarantined(&self, max: usize) -> QuarantineFuture<'_, Vec<QuarantinedTransaction>> {
+        Box::pin(async move {
+            sqlx::query(
+                "SELECT id, amount, last_name, currency, reason, quarantined_at, tenant_id
+                 FROM quarantined_transactions
+                 ORDER BY rowid
+                 LIMIT ?",
+            )
+            .bind(i64::try_from(max).unwrap_or(i64::MAX))
+            .fetch_all(&self.pool)
+            .await
+            .and_then(|rows| rows.iter().map(decode_quarantined).collect::<Result<Vec<_>, _>>())
+            .map_err(|e| {
+                tracing::error!(target: TARGET, "sqlite.quarantined: {e}");
+                StorageError::Unavailable
+            })
+        })
+    }
+
+    /// Delete the rows of `ids`; ids with no row are not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` on any `sqlx` error.
+    fn release<'a>(&'a self, ids: &'a [TransactionId]) -> QuarantineFuture<'a, ()> {
+        Box::pin(async move {
+            for id in ids {
+                sqlx::query("DELETE FROM quarantined_transactions WHERE id = ?")
+                    .bind(id.full())
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| {
+                        tracing::error!(target: TARGET, "sqlite.release_quarantine: {e}");
+                        StorageError::Unavailable
+                    })?;
+            }
+            Ok(())
+        })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
 
 #[cfg(test)]
 mod tests {
-    use super::SqliteStorage;
-    use domain::{InferredTransaction, PendingTransaction, Storage as _, Transaction};
+    use super::{PENDING_INDEXES, RunRecord, SqliteStorage, SqliteStorageOptions, query_plans};
+    use domain::{
+        AlarmAudit as _, AlarmDelivery, AlarmSeverity, BucketSink as _, ConfigError, Currency,
+        DeliveryOutcome, FixedClock, InferredTransaction, MinuteBucket, PendingTransaction,
+        Quarantine as _, QuarantinedTransaction, RECORD_VERSION, Review as _, ReviewOutcome, RunId,
+        RunMeta, RunSummary, Storage as _, StorageError, StorageRead as _, Transaction,
+        TransactionId,
+    };
+    use sqlx::Connection as _;
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
     use uuid::Uuid;
 
     // Each test calls make_storage() which opens a fresh SqlitePool backed by
@@ -132,20 +1306,28 @@ mod tests {
             .expect("in-memory `SQLite` should open")
     }
 
-    fn make_pending(id: Uuid, actual_fraud: Option<bool>) -> PendingTransaction {
+    fn make_pending(id: TransactionId, actual_fraud: Option<bool>) -> PendingTransaction {
         PendingTransaction {
             inferred_transaction: InferredTransaction {
                 transaction: Transaction {
                     id,
                     amount: 1.00_f64,
                     last_name: "Test".to_owned(),
+                    currency: Currency::Eur,
+                    tenant_id: None,
                 },
                 predicted_fraud: false,
                 model_name: "DEMO".to_owned(),
                 model_version: "4".to_owned(),
+                delivery_attempts: 1,
+                cached: false,
             },
             is_reviewed: false,
             actual_fraud,
+            record_version: RECORD_VERSION,
+            persisted_at: None,
+            reviewed_at: None,
+            run_id: None,
         }
     }
 
@@ -155,8 +1337,8 @@ mod tests {
         let storage = make_storage().await;
         storage
             .write_batch(vec![
-                make_pending(Uuid::new_v4(), None),
-                make_pending(Uuid::new_v4(), None),
+                make_pending(TransactionId::new_v4(), None),
+                make_pending(TransactionId::new_v4(), None),
             ])
             .await
             .unwrap();
@@ -172,11 +1354,11 @@ mod tests {
     #[tokio::test]
     async fn actual_fraud_null_when_none() {
         let storage = make_storage().await;
-        let id = Uuid::new_v4();
+        let id = TransactionId::new_v4();
         storage.write_batch(vec![make_pending(id, None)]).await.unwrap();
         let val: Option<i64> =
             sqlx::query_scalar("SELECT actual_fraud FROM pending_transactions WHERE id = ?")
-                .bind(id.to_string())
+                .bind(id.full())
                 .fetch_one(&storage.pool)
                 .await
                 .unwrap();
@@ -187,11 +1369,11 @@ mod tests {
     #[tokio::test]
     async fn actual_fraud_1_when_some_true() {
         let storage = make_storage().await;
-        let id = Uuid::new_v4();
+        let id = TransactionId::new_v4();
         storage.write_batch(vec![make_pending(id, Some(true))]).await.unwrap();
         let val: Option<i64> =
             sqlx::query_scalar("SELECT actual_fraud FROM pending_transactions WHERE id = ?")
-                .bind(id.to_string())
+                .bind(id.full())
                 .fetch_one(&storage.pool)
                 .await
                 .unwrap();
@@ -202,11 +1384,11 @@ mod tests {
     #[tokio::test]
     async fn actual_fraud_0_when_some_false() {
         let storage = make_storage().await;
-        let id = Uuid::new_v4();
+        let id = TransactionId::new_v4();
         storage.write_batch(vec![make_pending(id, Some(false))]).await.unwrap();
         let val: Option<i64> =
             sqlx::query_scalar("SELECT actual_fraud FROM pending_transactions WHERE id = ?")
-                .bind(id.to_string())
+                .bind(id.full())
                 .fetch_one(&storage.pool)
                 .await
                 .unwrap();
@@ -217,7 +1399,7 @@ mod tests {
     #[tokio::test]
     async fn duplicate_id_is_overwritten() {
         let storage = make_storage().await;
-        let id = Uuid::new_v4();
+        let id = TransactionId::new_v4();
         // First write: actual_fraud = NULL.
         storage.write_batch(vec![make_pending(id, None)]).await.unwrap();
         // Second write: same UUID, actual_fraud = Some(true).
@@ -232,7 +1414,7 @@ mod tests {
         // The surviving row must carry the second write's value.
         let val: Option<i64> =
             sqlx::query_scalar("SELECT actual_fraud FROM pending_transactions WHERE id = ?")
-                .bind(id.to_string())
+                .bind(id.full())
                 .fetch_one(&storage.pool)
                 .await
                 .unwrap();
@@ -251,4 +1433,701 @@ mod tests {
                 .unwrap();
         assert_eq!(count, 0);
     }
+
+    // ------------------------------------------------------------------
+    // Connection tuning (file-backed: WAL and locking need a real file)
+    // ------------------------------------------------------------------
+
+    /// Unique database path in the OS temp directory.
+    fn temp_db_path() -> PathBuf {
+        std::env::temp_dir().join(format!("fraud_detection_test_{}.db", Uuid::new_v4()))
+    }
+
+    fn db_url(path: &Path) -> String {
+        format!("sqlite:{}", path.display())
+    }
+
+    /// Close the pool and remove the database plus its WAL side files.
+    async fn cleanup(storage: SqliteStorage, path: &Path) {
+        storage.pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            // Best effort: a leftover temp file is harmless.
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    /// Second, independent connection used to hold a write lock.
+    async fn lock_holder(path: &Path) -> sqlx::sqlite::SqliteConnection {
+        let opts = db_url(path).parse::<sqlx::sqlite::SqliteConnectOptions>().unwrap();
+        let mut conn = sqlx::sqlite::SqliteConnection::connect_with(&opts).await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE").execute(&mut conn).await.unwrap();
+        conn
+    }
+
+    // SS-T07: default options enable WAL on a file database.
+    #[tokio::test]
+    async fn default_options_enable_wal() {
+        let path = temp_db_path();
+        let storage = SqliteStorage::new(&db_url(&path)).await.unwrap();
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(mode.to_lowercase(), "wal");
+        cleanup(storage, &path).await;
+    }
+
+    // SS-T08: a write waits for a competing lock released within busy_timeout.
+    #[tokio::test]
+    async fn busy_timeout_waits_for_lock_release() {
+        let path = temp_db_path();
+        let options = SqliteStorageOptions { busy_timeout: Duration::from_secs(5), ..Default::default() };
+        let storage = SqliteStorage::with_options(&db_url(&path), options).await.unwrap();
+        let mut holder = lock_holder(&path).await;
+
+        let (write, ()) = tokio::join!(
+            storage.write_batch(vec![make_pending(TransactionId::new_v4(), None)]),
+            async {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                sqlx::query("COMMIT").execute(&mut holder).await.unwrap();
+            }
+        );
+
+        assert!(write.is_ok(), "write must succeed once the lock is released: {write:?}");
+        holder.close().await.unwrap();
+        cleanup(storage, &path).await;
+    }
+
+    // SS-T09: a lock held past busy_timeout surfaces as Unavailable.
+    #[tokio::test]
+    async fn busy_timeout_expiry_is_unavailable() {
+        let path = temp_db_path();
+        let options = SqliteStorageOptions { busy_timeout: Duration::from_millis(50), ..Default::default() };
+        let storage = SqliteStorage::with_options(&db_url(&path), options).await.unwrap();
+        let mut holder = lock_holder(&path).await;
+
+        let result = storage.write_batch(vec![make_pending(TransactionId::new_v4(), None)]).await;
+
+        assert_eq!(result, Err(StorageError::Unavailable));
+        sqlx::query("ROLLBACK").execute(&mut holder).await.unwrap();
+        holder.close().await.unwrap();
+        cleanup(storage, &path).await;
+    }
+
+    // SS-T10: upserting the same minute twice keeps one row with the latest counts.
+    #[tokio::test]
+    async fn upsert_buckets_is_idempotent() {
+        let storage = make_storage().await;
+        let first = MinuteBucket { minute: 28_333_334, total: 3, flagged: 1 };
+        let later = MinuteBucket { total: 5, flagged: 2, ..first };
+        let next = MinuteBucket { minute: 28_333_335, total: 1, flagged: 0 };
+
+        storage.upsert_buckets(&[first]).await.unwrap();
+        storage.upsert_buckets(&[later, next]).await.unwrap();
+        storage.upsert_buckets(&[later, next]).await.unwrap();
+
+        let rows: Vec<(i64, i64, i64)> = sqlx::query_as(
+            "SELECT minute, total, flagged FROM fraud_counts_by_minute ORDER BY minute",
+        )
+        .fetch_all(&storage.pool)
+        .await
+        .unwrap();
+        assert_eq!(rows, vec![(28_333_334, 5, 2), (28_333_335, 1, 0)]);
+    }
+
+    // ------------------------------------------------------------------
+    // Record versions and reads
+    // ------------------------------------------------------------------
+
+    // SS-T11: current-version rows round-trip losslessly through read_page.
+    #[tokio::test]
+    async fn current_version_round_trips() {
+        let storage = make_storage().await;
+        // Timestamps are stored with millisecond precision.
+        let t = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let mut reviewed = make_pending(TransactionId::new_v4(), Some(true));
+        reviewed.is_reviewed = true;
+        reviewed.inferred_transaction.predicted_fraud = true;
+        reviewed.persisted_at = Some(t);
+        reviewed.reviewed_at = Some(t + Duration::from_secs(90));
+        let written = vec![make_pending(TransactionId::new_v4(), None), reviewed];
+        storage.write_batch(written.clone()).await.unwrap();
+
+        let read = storage.read_page(0, 10).await.unwrap();
+
+        let pending: Vec<_> = read.into_iter().map(|s| s.pending).collect();
+        assert_eq!(pending, written);
+        assert!(pending.iter().all(|p| p.record_version == RECORD_VERSION));
+    }
+
+    // SS-T12: a v1 row in a pre-versioning database reads back with defaults.
+    #[tokio::test]
+    async fn v1_row_reads_with_defaults() {
+        let path = temp_db_path();
+        // Handcraft the pre-versioning schema and row directly with SQL.
+        let opts = db_url(&path)
+            .parse::<sqlx::sqlite::SqliteConnectOptions>()
+            .unwrap()
+            .create_if_missing(true);
+        let mut conn = sqlx::sqlite::SqliteConnection::connect_with(&opts).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE pending_transactions (
+                id TEXT PRIMARY KEY, amount REAL NOT NULL, last_name TEXT NOT NULL,
+                predicted_fraud INTEGER NOT NULL, model_name TEXT NOT NULL,
+                model_version TEXT NOT NULL, is_reviewed INTEGER NOT NULL DEFAULT 0,
+                actual_fraud INTEGER
+            )",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO pending_transactions
+             (id, amount, last_name, predicted_fraud, model_name, model_version)
+             VALUES ('6f1c2a4e-0000-4000-8000-000000000001', 12.5, 'Legacy', 1, 'DEMO', '3')",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        conn.close().await.unwrap();
+
+        let storage = SqliteStorage::new(&db_url(&path)).await.unwrap();
+        let read = storage.read_page(0, 10).await.unwrap();
+
+        assert_eq!(read.len(), 1);
+        let pending = &read[0].pending;
+        assert_eq!(pending.record_version, 1);
+        assert_eq!(pending.id().full(), "6f1c2a4e-0000-4000-8000-000000000001");
+        assert!(pending.inferred_transaction.predicted_fraud);
+        assert!(!pending.is_reviewed);
+        assert!(pending.actual_fraud.is_none());
+        // Columns added by the migration read back as None, EUR and 1 attempt.
+        assert_eq!((pending.persisted_at, pending.reviewed_at), (None, None));
+        assert_eq!(pending.inferred_transaction.transaction.currency, Currency::Eur);
+        assert_eq!(pending.run_id, None);
+        assert_eq!(pending.inferred_transaction.delivery_attempts, 1);
+        assert_eq!(pending.inferred_transaction.transaction.tenant_id, None);
+        assert!(!pending.inferred_transaction.cached);
+        cleanup(storage, &path).await;
+    }
+
+    // SS-T13: read_page resumes after the last returned position.
+    #[tokio::test]
+    async fn read_page_pages_by_rowid() {
+        let storage = make_storage().await;
+        let written: Vec<_> = (0..5).map(|_| make_pending(TransactionId::new_v4(), None)).collect();
+        storage.write_batch(written.clone()).await.unwrap();
+
+        let first = storage.read_page(0, 3).await.unwrap();
+        let rest = storage.read_page(first[2].position, 3).await.unwrap();
+        let end = storage.read_page(rest[1].position, 3).await.unwrap();
+
+        assert_eq!((first.len(), rest.len()), (3, 2));
+        let read: Vec<_> = first.into_iter().chain(rest).map(|s| s.pending).collect();
+        assert_eq!(read, written);
+        assert!(end.is_empty());
+    }
+
+    // SS-T14: a zero pool size or busy timeout is rejected before connecting.
+    #[tokio::test]
+    async fn zero_options_are_rejected() {
+        for options in [
+            SqliteStorageOptions { max_connections: 0, ..Default::default() },
+            SqliteStorageOptions { busy_timeout: Duration::ZERO, ..Default::default() },
+        ] {
+            let err = SqliteStorage::with_options("sqlite::memory:", options).await.unwrap_err();
+            let sqlx::Error::Configuration(source) = &err else {
+                panic!("expected Configuration, got {err:?}");
+            };
+            assert!(source.downcast_ref::<ConfigError>().is_some());
+        }
+    }
+
+    // SS-T15: record_reviews updates the matching row only.
+    #[tokio::test]
+    async fn record_reviews_updates_matching_row() {
+        let storage = make_storage().await;
+        let (a, b) = (TransactionId::new_v4(), TransactionId::new_v4());
+        storage
+            .write_batch(vec![make_pending(a, None), make_pending(b, None)])
+            .await
+            .unwrap();
+
+        let outcomes = [
+            ReviewOutcome { id: b, actual_fraud: false },
+            ReviewOutcome { id: TransactionId::new_v4(), actual_fraud: true },
+        ];
+        storage.record_reviews(&outcomes).await.unwrap();
+
+        let read = storage.read_page(0, 10).await.unwrap();
+        assert_eq!((read[0].pending.is_reviewed, read[0].pending.actual_fraud), (false, None));
+        assert_eq!((read[1].pending.is_reviewed, read[1].pending.actual_fraud), (true, Some(false)));
+    }
+
+    // SS-T16: record_reviews stamps reviewed_at from the injected clock.
+    #[tokio::test]
+    async fn record_reviews_stamps_reviewed_at() {
+        let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let storage = make_storage().await.with_clock(Arc::new(FixedClock(t)));
+        let (a, b) = (TransactionId::new_v4(), TransactionId::new_v4());
+        storage
+            .write_batch(vec![make_pending(a, None), make_pending(b, None)])
+            .await
+            .unwrap();
+
+        storage.record_reviews(&[ReviewOutcome { id: a, actual_fraud: true }]).await.unwrap();
+
+        let read = storage.read_page(0, 10).await.unwrap();
+        assert_eq!(read[0].pending.reviewed_at, Some(t));
+        assert_eq!(read[1].pending.reviewed_at, None);
+    }
+
+    // SS-T17: initialization creates the hot-path indexes.
+    #[tokio::test]
+    async fn indexes_exist_after_init() {
+        let storage = make_storage().await;
+        let names: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_index_list('pending_transactions')")
+                .fetch_all(&storage.pool)
+                .await
+                .unwrap();
+        for (index, _) in PENDING_INDEXES {
+            assert!(names.iter().any(|n| n == *index), "{index} missing from {names:?}");
+        }
+    }
+
+    // SS-T18: every canonical query is planned on an index.
+    #[tokio::test]
+    async fn canonical_queries_use_indexes() {
+        let storage = make_storage().await;
+        for (query, uses_index, plan) in query_plans(&storage.pool).await.unwrap() {
+            assert!(uses_index, "{query} does not use an index: {plan}");
+        }
+    }
+
+    // SS-T19: fetch_unreviewed skips reviewed rows and keeps insertion order.
+    #[tokio::test]
+    async fn fetch_unreviewed_returns_oldest_unreviewed_first() {
+        let storage = make_storage().await;
+        let written: Vec<_> = (0..5).map(|_| make_pending(TransactionId::new_v4(), None)).collect();
+        storage.write_batch(written.clone()).await.unwrap();
+        let reviewed = [written[0].id(), written[2].id()]
+            .map(|id| ReviewOutcome { id, actual_fraud: false });
+        storage.record_reviews(&reviewed).await.unwrap();
+
+        let page = storage.fetch_unreviewed(2).await.unwrap();
+
+        let ids: Vec<_> = page.iter().map(|s| s.pending.id()).collect();
+        assert_eq!(ids, [written[1].id(), written[3].id()]);
+        assert!(page.iter().all(|s| !s.pending.is_reviewed));
+    }
+
+    // SS-T20: coarse performance bound on a 50k-row table, 1% unreviewed.
+    #[tokio::test]
+    async fn fetch_unreviewed_is_fast_on_50k_rows() {
+        let storage = make_storage().await;
+        // One statement instead of 50k write_batch inserts keeps setup quick.
+        sqlx::query(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 50000)
+             INSERT INTO pending_transactions
+             (id, amount, last_name, predicted_fraud, model_name, model_version,
+              is_reviewed, record_version)
+             SELECT printf('00000000-0000-4000-8000-%012d', i), 1.0, 'Perf', i % 25 = 0,
+                    'DEMO', '4', i % 100 != 0, 3
+             FROM n",
+        )
+        .execute(&storage.pool)
+        .await
+        .unwrap();
+
+        let start = std::time::Instant::now();
+        let page = storage.fetch_unreviewed(100).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(page.len(), 100);
+        assert!(page.iter().all(|s| !s.pending.is_reviewed));
+        // Generous: an index lookup takes milliseconds even in a debug build.
+        assert!(elapsed < Duration::from_secs(2), "fetch_unreviewed(100) took {elapsed:?}");
+    }
+
+    // SS-T21: initialization creates alarm_deliveries with the audit columns.
+    #[tokio::test]
+    async fn alarm_deliveries_table_exists_after_init() {
+        let storage = make_storage().await;
+        let columns: Vec<(String, String, i64)> = sqlx::query_as(
+            "SELECT name, type, \"notnull\" FROM pragma_table_info('alarm_deliveries')
+             ORDER BY cid",
+        )
+        .fetch_all(&storage.pool)
+        .await
+        .unwrap();
+        let expected: [(&str, &str, i64); 5] = [
+            ("transaction_id", "TEXT", 1),
+            ("severity", "TEXT", 1),
+            ("delivered_at", "INTEGER", 1),
+            ("delivered", "INTEGER", 1),
+            ("error", "TEXT", 0),
+        ];
+        let columns: Vec<_> =
+            columns.iter().map(|(n, t, nn)| (n.as_str(), t.as_str(), *nn)).collect();
+        assert_eq!(columns, expected);
+    }
+
+    // SS-T22: record_delivery maps both outcomes to columns and reads back losslessly.
+    #[tokio::test]
+    async fn record_delivery_round_trips_both_outcomes() {
+        let storage = make_storage().await;
+        let t = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let deliveries = [
+            AlarmDelivery {
+                transaction_id: TransactionId::new_v4(),
+                severity: AlarmSeverity::High,
+                delivered_at: t,
+                outcome: DeliveryOutcome::Delivered,
+            },
+            AlarmDelivery {
+                transaction_id: TransactionId::new_v4(),
+                severity: AlarmSeverity::Low,
+                delivered_at: t + Duration::from_secs(1),
+                outcome: DeliveryOutcome::Failed { reason: "webhook down".to_owned() },
+            },
+        ];
+        for delivery in &deliveries {
+            storage.record_delivery(delivery).await.unwrap();
+        }
+
+        let raw: Vec<(String, String, i64, i64, Option<String>)> = sqlx::query_as(
+            "SELECT transaction_id, severity, delivered_at, delivered, error
+             FROM alarm_deliveries ORDER BY rowid",
+        )
+        .fetch_all(&storage.pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            raw,
+            [
+                (
+                    deliveries[0].transaction_id.full(),
+                    "high".to_owned(),
+                    1_700_000_000_123,
+                    1,
+                    None,
+                ),
+                (
+                    deliveries[1].transaction_id.full(),
+                    "low".to_owned(),
+                    1_700_000_001_123,
+                    0,
+                    Some("webhook down".to_owned()),
+                ),
+            ]
+        );
+        assert_eq!(storage.alarm_deliveries().await.unwrap(), deliveries);
+    }
+
+    // SS-T23: an unknown severity is a decode error, surfaced as Unavailable.
+    #[tokio::test]
+    async fn unknown_severity_is_unavailable() {
+        let storage = make_storage().await;
+        sqlx::query(
+            "INSERT INTO alarm_deliveries (transaction_id, severity, delivered_at, delivered)
+             VALUES (?, 'critical', 0, 1)",
+        )
+        .bind(TransactionId::new_v4().full())
+        .execute(&storage.pool)
+        .await
+        .unwrap();
+
+        assert_eq!(storage.alarm_deliveries().await, Err(StorageError::Unavailable));
+    }
+
+    // SS-T24: all_ids returns ids in rowid order, capped at limit.
+    #[tokio::test]
+    async fn all_ids_selects_ids_by_rowid() {
+        let storage = make_storage().await;
+        let written: Vec<_> = (0..5).map(|_| make_pending(TransactionId::new_v4(), None)).collect();
+        storage.write_batch(written.clone()).await.unwrap();
+        let ids: Vec<_> = written.iter().map(PendingTransaction::id).collect();
+
+        assert_eq!(storage.all_ids(3).await.unwrap(), ids[..3]);
+        assert_eq!(storage.all_ids(10).await.unwrap(), ids);
+        assert!(storage.all_ids(0).await.unwrap().is_empty());
+    }
+
+    // SS-T25: an unparsable stored id surfaces as Unavailable.
+    #[tokio::test]
+    async fn all_ids_with_bad_id_is_unavailable() {
+        let storage = make_storage().await;
+        let pending = make_pending(TransactionId::new_v4(), None);
+        storage.write_batch(vec![pending]).await.unwrap();
+        sqlx::query("UPDATE pending_transactions SET id = 'not-a-uuid'")
+            .execute(&storage.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(storage.all_ids(10).await, Err(StorageError::Unavailable));
+    }
+
+    // SS-T26: the currency round-trips, including codes outside the enum.
+    #[tokio::test]
+    async fn currency_round_trips_including_other_codes() {
+        let storage = make_storage().await;
+        let written: Vec<_> = [Currency::Usd, Currency::Other("JPY".to_owned())]
+            .into_iter()
+            .map(|currency| {
+                let mut pending = make_pending(TransactionId::new_v4(), None);
+                pending.inferred_transaction.transaction.currency = currency;
+                pending
+            })
+            .collect();
+        storage.write_batch(written.clone()).await.unwrap();
+
+        let read: Vec<_> =
+            storage.read_page(0, 10).await.unwrap().into_iter().map(|s| s.pending).collect();
+        assert_eq!(read, written);
+        let stored: Vec<String> =
+            sqlx::query_scalar("SELECT currency FROM pending_transactions ORDER BY rowid")
+                .fetch_all(&storage.pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, ["USD", "JPY"]);
+    }
+
+    // SS-T27: begin_run and end_run round-trip through runs(), and run_id
+    // attributes each row to its run.
+    #[tokio::test]
+    async fn runs_round_trip_and_attribute_rows() {
+        let storage = make_storage().await;
+        let started_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let meta = RunMeta {
+            started_at,
+            seeds: BTreeMap::from([("producer".to_owned(), 7)]),
+            config: r#"{"n1_max":100}"#.to_owned(),
+        };
+        let first = storage.begin_run(meta.clone()).await.unwrap();
+        let second = storage.begin_run(meta.clone()).await.unwrap();
+        let summary = RunSummary {
+            ended_at: started_at + Duration::from_mins(1),
+            produced: 12,
+            inferred: 12,
+            flagged: 1,
+            persisted: 11,
+        };
+        storage.end_run(first, summary).await.unwrap();
+        let mut attributed = make_pending(TransactionId::new_v4(), None);
+        attributed.run_id = Some(second);
+        storage.write_batch(vec![attributed.clone()]).await.unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(
+            storage.runs().await.unwrap(),
+            [
+                RunRecord { id: first, meta: meta.clone(), summary: Some(summary) },
+                RunRecord { id: second, meta, summary: None },
+            ]
+        );
+        let read = storage.read_page(0, 10).await.unwrap();
+        assert_eq!(read[0].pending, attributed);
+        // Ending an unknown run is not an error.
+        assert_eq!(storage.end_run(RunId(99), summary).await, Ok(()));
+    }
+
+    // SS-T28: quarantined rows round-trip in record order, stamped by the clock,
+    // and release deletes only the given ids.
+    #[tokio::test]
+    async fn quarantine_round_trips_and_releases() {
+        let t = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let storage = make_storage().await.with_clock(Arc::new(FixedClock(t)));
+        let txs: Vec<Transaction> = (0..3)
+            .map(|_| make_pending(TransactionId::new_v4(), None).inferred_transaction.transaction)
+            .collect();
+        for tx in &txs {
+            storage.record(tx, "inference failed: timeout").await.unwrap();
+        }
+
+        let rows = storage.quarantined(2).await.unwrap();
+        let expected: Vec<_> = txs[..2]
+            .iter()
+            .map(|tx| QuarantinedTransaction {
+                transaction: tx.clone(),
+                reason: "inference failed: timeout".to_owned(),
+                quarantined_at: t,
+            })
+            .collect();
+        assert_eq!(rows, expected);
+
+        storage.release(&[txs[0].id, TransactionId::new_v4()]).await.unwrap();
+        let left: Vec<_> =
+            storage.quarantined(10).await.unwrap().into_iter().map(|row| row.transaction).collect();
+        assert_eq!(left, txs[1..]);
+    }
+
+    // SS-T29: quarantining an id again replaces its row and moves it last.
+    #[tokio::test]
+    async fn requarantine_replaces_the_row() {
+        let storage = make_storage().await;
+        let first = make_pending(TransactionId::new_v4(), None).inferred_transaction.transaction;
+        let second = make_pending(TransactionId::new_v4(), None).inferred_transaction.transaction;
+        storage.record(&first, "old").await.unwrap();
+        storage.record(&second, "other").await.unwrap();
+        storage.record(&first, "new").await.unwrap();
+
+        let rows = storage.quarantined(10).await.unwrap();
+        let rows: Vec<_> =
+            rows.iter().map(|row| (row.transaction.id, row.reason.as_str())).collect();
+        assert_eq!(rows, [(second.id, "other"), (first.id, "new")]);
+    }
+
+    // SS-T30: with verification on, a clean batch reads back row for row,
+    // persisted_at compared at the stored millisecond.
+    #[tokio::test]
+    async fn verified_writes_read_back_clean() {
+        let storage = make_storage().await.with_verification(true);
+        let mut flagged = make_pending(TransactionId::new_v4(), Some(true));
+        flagged.inferred_transaction.transaction.currency = Currency::Usd;
+        flagged.persisted_at = Some(SystemTime::UNIX_EPOCH + Duration::from_nanos(1_234_567_891));
+        flagged.run_id = Some(RunId(7));
+        let batch = vec![flagged, make_pending(TransactionId::new_v4(), None)];
+
+        storage.write_batch(batch).await.unwrap();
+
+        assert_eq!(storage.verified_rows(), 2);
+    }
+
+    // SS-T31: a row changed between write and verify fails the write and
+    // names the column; a trigger does the tampering.
+    #[tokio::test]
+    async fn tampered_row_fails_verification_naming_the_field() {
+        let storage = make_storage().await.with_verification(true);
+        sqlx::query(
+            "CREATE TRIGGER tamper AFTER INSERT ON pending_transactions BEGIN
+                UPDATE pending_transactions SET model_version = 'X' WHERE id = NEW.id;
+             END",
+        )
+        .execute(&storage.pool)
+        .await
+        .unwrap();
+        let id = TransactionId::new_v4();
+
+        let result = storage.write_batch(vec![make_pending(id, None)]).await;
+
+        assert_eq!(result, Err(StorageError::VerificationFailed { id, field: "model_version" }));
+    }
+
+    // SS-T32: a row that vanished between write and verify is reported as "id".
+    #[tokio::test]
+    async fn missing_row_fails_verification_on_id() {
+        let storage = make_storage().await.with_verification(true);
+        sqlx::query(
+            "CREATE TRIGGER vanish AFTER INSERT ON pending_transactions BEGIN
+                DELETE FROM pending_transactions WHERE id = NEW.id;
+             END",
+        )
+        .execute(&storage.pool)
+        .await
+        .unwrap();
+        let id = TransactionId::new_v4();
+
+        let result = storage.write_batch(vec![make_pending(id, None)]).await;
+
+        assert_eq!(result, Err(StorageError::VerificationFailed { id, field: "id" }));
+    }
+
+    // SS-T33: with verification off (the default), nothing is read back, so
+    // tampering goes unnoticed.
+    #[tokio::test]
+    async fn verification_off_reads_nothing_back() {
+        let storage = make_storage().await;
+        sqlx::query(
+            "CREATE TRIGGER tamper AFTER INSERT ON pending_transactions BEGIN
+                UPDATE pending_transactions SET amount = -1 WHERE id = NEW.id;
+             END",
+        )
+        .execute(&storage.pool)
+        .await
+        .unwrap();
+
+        storage.write_batch(vec![make_pending(TransactionId::new_v4(), None)]).await.unwrap();
+
+        assert_eq!(storage.verified_rows(), 0);
+    }
+
+    // SS-T34: delivery_attempts is stored as an INTEGER and read back.
+    #[tokio::test]
+    async fn delivery_attempts_round_trip() {
+        let storage = make_storage().await;
+        let written: Vec<_> = [1, 3]
+            .into_iter()
+            .map(|attempts| {
+                let mut pending = make_pending(TransactionId::new_v4(), None);
+                pending.inferred_transaction.delivery_attempts = attempts;
+                pending
+            })
+            .collect();
+        storage.write_batch(written.clone()).await.unwrap();
+
+        let read: Vec<_> =
+            storage.read_page(0, 10).await.unwrap().into_iter().map(|s| s.pending).collect();
+        assert_eq!(read, written);
+        let stored: Vec<i64> =
+            sqlx::query_scalar("SELECT delivery_attempts FROM pending_transactions ORDER BY rowid")
+                .fetch_all(&storage.pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, [1, 3]);
+    }
+
+    // SS-T35: tenant_id is stored as TEXT, NULL for an untagged transaction,
+    // and read back from both the pending and the quarantine tables.
+    #[tokio::test]
+    async fn tenant_id_round_trips() {
+        let storage = make_storage().await;
+        let written: Vec<_> = [Some("bank-a"), None]
+            .into_iter()
+            .map(|tenant| {
+                let mut pending = make_pending(TransactionId::new_v4(), None);
+                pending.inferred_transaction.transaction.tenant_id = tenant.map(str::to_owned);
+                pending
+            })
+            .collect();
+        storage.write_batch(written.clone()).await.unwrap();
+        storage.record(&written[0].inferred_transaction.transaction, "held").await.unwrap();
+
+        let read: Vec<_> =
+            storage.read_page(0, 10).await.unwrap().into_iter().map(|s| s.pending).collect();
+        assert_eq!(read, written);
+        let stored: Vec<Option<String>> =
+            sqlx::query_scalar("SELECT tenant_id FROM pending_transactions ORDER BY rowid")
+                .fetch_all(&storage.pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, [Some("bank-a".to_owned()), None]);
+        let quarantined = storage.quarantined(10).await.unwrap();
+        assert_eq!(quarantined[0].transaction.tenant_id.as_deref(), Some("bank-a"));
+    }
+
+    // SS-T36: the inference cache flag is stored as an INTEGER and read back.
+    #[tokio::test]
+    async fn cached_flag_round_trips() {
+        let storage = make_storage().await;
+        let written: Vec<_> = [false, true]
+            .into_iter()
+            .map(|cached| {
+                let mut pending = make_pending(TransactionId::new_v4(), None);
+                pending.inferred_transaction.cached = cached;
+                pending
+            })
+            .collect();
+        storage.write_batch(written.clone()).await.unwrap();
+
+        let read: Vec<_> =
+            storage.read_page(0, 10).await.unwrap().into_iter().map(|s| s.pending).collect();
+        assert_eq!(read, written);
+        let stored: Vec<i64> =
+            sqlx::query_scalar("SELECT cached FROM pending_transactions ORDER BY rowid")
+                .fetch_all(&storage.pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, [0, 1]);
+    }
 }
@@ -0,0 +1,239 @@
+// Rust guideline compliant 2026-02-27
+
+//! `Buffer1Read` adapter replaying persisted transactions from storage.
+//!
+//! [`StorageSource`] pages through the `StorageRead` port in storage order
+//! and hands each record's original `Transaction` (same id, amount, name and
+//! currency) to the Consumer, so a new model can be regression-tested against
+//! real traffic. Once a page comes back empty the source is exhausted and
+//! every later read returns `ReadError::Closed`. Rows written after that
+//! are not picked up.
+//!
+//! Reads are serialized: while one page is in flight, a concurrent reader
+//! yields until the cursor has moved past it, so several Consumers may share
+//! one source without duplicates (see `Buffer1Read`'s exclusive drain).
+
+use std::cell::Cell;
+
+use domain::{Buffer1Read, ReadError, StorageRead, Transaction};
+
+/// `tracing` target of every buffer event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::BUFFER;
+
+/// `Buffer1Read` adapter streaming the transactions persisted in `S`.
+#[derive(Debug)]
+pub struct StorageSource<S> {
+    storage: S,
+    /// Position of the last record handed out; 0 before the first read.
+    after: Cell<u64>,
+    /// A `read_page` is in flight.
+    reading: Cell<bool>,
+    closed: Cell<bool>,
+}
+
+impl<S> StorageSource<S> {
+    /// Replay every record of `storage`, from the first.
+    #[must_use]
+    pub fn new(storage: S) -> Self {
+        Self { storage, after: Cell::new(0), reading: Cell::new(false), closed: Cell::new(false) }
+    }
+
+    /// Position of the last record handed out; 0 before the first read.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.after.get()
+    }
+}
+
+/// Clears the in-flight flag on drop, even if the read is cancelled.
+struct ReadingGuard<'a>(&'a Cell<bool>);
+
+impl Drop for ReadingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+impl<S: StorageRead> Buffer1Read for StorageSource<S> {
+    /// Read the next page of at most `max` records as transactions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReadError::Closed` once every record was read, or
+    /// `ReadError::Backend` when the page cannot be read (the cursor does
+    /// not move, so the next read retries it).
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+        loop {
+            if self.closed.get() {
+                return Err(ReadError::Closed);
+            }
+            if max == 0 {
+                return Ok(vec![]);
+            }
+            if self.reading.replace(true) {
+                tokio::task::yield_now().await;
+                continue;
+            }
+            let _guard = ReadingGuard(&self.reading);
+            let page = self
+                .storage
+                .read_page(self.after.get(), max)
+                .await
+                .map_err(|e| ReadError::Backend(e.to_string()))?;
+            let Some(last) = page.last() else {
+                self.closed.set(true);
+                tracing::info!(
+                    target: TARGET,
+                    position = self.after.get(),
+                    "storage_source.exhausted"
+                );
+                return Err(ReadError::Closed);
+            };
+            self.after.set(last.position);
+            let txs = page.into_iter().map(|s| s.pending.inferred_transaction.transaction);
+            return Ok(txs.collect());
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::StorageSource;
+    use crate::adapters::in_memory_storage::InMemoryStorage;
+    use domain::{
+        Buffer1Read as _, Currency, InferredTransaction, PendingTransaction, ReadError, Storage,
+        StorageError, StorageRead, StoredTransaction, Transaction, TransactionId,
+    };
+    use std::cell::Cell;
+    use std::collections::HashSet;
+
+    fn make_tx(i: u32) -> Transaction {
+        Transaction {
+            id: TransactionId::new_v4(),
+            amount: f64::from(i) + 0.25,
+            last_name: format!("Name{i}"),
+            currency: Currency::Usd,
+            tenant_id: None,
+        }
+    }
+
+    /// Storage holding `n` records, and their transactions in storage order.
+    async fn populated(n: u32) -> (InMemoryStorage, Vec<Transaction>) {
+        let storage = InMemoryStorage::new(usize::MAX);
+        let txs: Vec<Transaction> = (0..n).map(make_tx).collect();
+        let batch = txs
+            .iter()
+            .map(|tx| {
+                PendingTransaction::new(InferredTransaction {
+                    transaction: tx.clone(),
+                    predicted_fraud: false,
+                    model_name: "DEMO".to_owned(),
+                    model_version: "4".to_owned(),
+                    delivery_attempts: 1,
+                    cached: false,
+                })
+            })
+            .collect();
+        storage.write_batch(batch).await.unwrap();
+        (storage, txs)
+    }
+
+    /// Every transaction until `Closed`, one `Vec` per read.
+    async fn drain(source: &StorageSource<impl StorageRead>, max: usize) -> Vec<Vec<Transaction>> {
+        let mut reads = vec![];
+        loop {
+            match source.read_batch(max).await {
+                Ok(batch) => reads.push(batch),
+                Err(ReadError::Closed) => return reads,
+                Err(e) => panic!("unexpected {e}"),
+            }
+        }
+    }
+
+    // SS-T01: every record is replayed once, in storage order, with its
+    // original id and amount.
+    #[tokio::test]
+    async fn replays_every_record_once_in_order() {
+        let (storage, txs) = populated(7).await;
+        let source = StorageSource::new(storage);
+
+        let replayed: Vec<Transaction> = drain(&source, 100).await.concat();
+
+        assert_eq!(replayed, txs);
+        assert_eq!(source.position(), 7);
+    }
+
+    // SS-T02: pages split across read boundaries: 25 records at 10 per read
+    // come back as 10, 10 and 5.
+    #[tokio::test]
+    async fn pages_across_boundaries() {
+        let (storage, txs) = populated(25).await;
+        let source = StorageSource::new(storage);
+
+        let reads = drain(&source, 10).await;
+
+        let sizes: Vec<usize> = reads.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [10, 10, 5]);
+        assert_eq!(reads.concat(), txs);
+    }
+
+    // SS-T03: the end of data closes the source for good, including for an
+    // empty storage.
+    #[tokio::test]
+    async fn end_of_data_closes() {
+        let source = StorageSource::new(InMemoryStorage::new(10));
+        assert_eq!(source.read_batch(10).await, Err(ReadError::Closed));
+
+        let (storage, _) = populated(3).await;
+        let source = StorageSource::new(storage);
+        assert_eq!(source.read_batch(10).await.unwrap().len(), 3);
+        assert_eq!(source.read_batch(10).await, Err(ReadError::Closed));
+        assert_eq!(source.read_batch(10).await, Err(ReadError::Closed));
+    }
+
+    // SS-T04: concurrent readers never receive the same record twice.
+    #[tokio::test]
+    async fn concurrent_readers_split_the_records() {
+        let (storage, txs) = populated(50).await;
+        let source = StorageSource::new(storage);
+
+        let (a, b) = tokio::join!(drain(&source, 4), drain(&source, 4));
+
+        let ids: Vec<TransactionId> =
+            a.concat().iter().chain(b.concat().iter()).map(|tx| tx.id).collect();
+        let unique: HashSet<TransactionId> = ids.iter().copied().collect();
+        assert_eq!(ids.len(), 50);
+        assert_eq!(unique, txs.iter().map(|tx| tx.id).collect());
+    }
+
+    /// `StorageRead` failing its first `read_page`, then empty.
+    #[derive(Debug, Default)]
+    struct FailingOnce(Cell<bool>);
+
+    impl StorageRead for FailingOnce {
+        async fn read_page(
+            &self,
+            _after: u64,
+            _limit: usize,
+        ) -> Result<Vec<StoredTransaction>, StorageError> {
+            if self.0.replace(true) { Ok(vec![]) } else { Err(StorageError::Unavailable) }
+        }
+
+        async fn all_ids(&self, _limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+            Ok(vec![])
+        }
+    }
+
+    // SS-T05: a read failure is a Backend error, not the end of data.
+    #[tokio::test]
+    async fn read_failure_is_a_backend_error() {
+        let source = StorageSource::new(FailingOnce::default());
+
+        assert!(matches!(source.read_batch(10).await, Err(ReadError::Backend(_))));
+        assert_eq!(source.read_batch(10).await, Err(ReadError::Closed));
+    }
+}
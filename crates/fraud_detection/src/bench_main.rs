@@ -4,7 +4,8 @@
 //!
 //! Measures end-to-end pipeline throughput (transactions processed per second)
 //! across a range of batch sizes.  Each batch size is run `ROUNDS` times;
-//! min/avg/max throughput is printed to stdout.
+//! min/avg/max throughput and its relative standard deviation (`rsd`, the
+//! sample standard deviation over the mean) are printed to stdout.
 //!
 //! # Measurement scope
 //!
@@ -17,7 +18,8 @@
 //! - Logger: buffer read, `PendingTransaction` construction, storage call
 //! - Both `ConcurrentBuffer` instances: interior-mutability yield loops
 //!
-//! What is **not** measured: any real I/O, storage allocation, alarm delivery.
+//! What is **not** measured: any real I/O, storage allocation, alarm delivery
+//! (with `--fraud-rate`, only the alarm call itself; see below).
 //!
 //! No `tracing_subscriber` init: tracing macros compile to no-ops, eliminating
 //! log I/O overhead from measurements.
@@ -26,32 +28,127 @@
 //!
 //! ```text
 //! # Quick sanity check (debug build)
-//! cargo build --bin fraud_detection_bench
+//! cargo build --bin fraud_detection_bench --features bench
 //!
 //! # Accurate throughput numbers (release build)
-//! cargo run --bin fraud_detection_bench --release
+//! cargo run --bin fraud_detection_bench --features bench --release
+//!
+//! # Scaling: N Consumers sharing buffer1 and buffer2
+//! cargo run --bin fraud_detection_bench --features bench --release -- --consumers 4
+//! ```
+//!
+//! With `--consumers N` (default 1), N Consumers seeded `42..42+N` drain the
+//! same buffer1; exclusive drain (see `Buffer1Read`) splits the work between
+//! them. buffer2 closes once the last Consumer stops.
+//!
+//! # Pre-generated transactions
+//!
+//! ```text
+//! cargo run --bin fraud_detection_bench --features bench --release -- --pregenerate
+//! ```
+//!
+//! `--pregenerate` builds each batch size's dataset once, before its rounds,
+//! with `ProducerConfigBuilder::pregenerate`; every round streams a copy made
+//! before the timer starts, so UUID generation and amount sampling drop out of
+//! the measurement. Batches are then always full (`batch_size` transactions)
+//! and a dataset holds at most [`PREGENERATE_MAX_TX`] transactions, so large
+//! batch sizes stop before `ITERATIONS`.
+//!
+//! # Fraud-rate sweep
+//!
+//! ```text
+//! cargo run --bin fraud_detection_bench --features bench --release -- --fraud-rate sweep
+//! cargo run --bin fraud_detection_bench --features bench --release -- --fraud-rate 0.01,0.2
 //! ```
+//!
+//! `--fraud-rate` replaces [`BenchModel`] with the seeded `pipeline`
+//! [`RateModel`] and runs the whole table once per rate (`sweep` = 0%, 5%,
+//! 50%), so the fraud branch and the per-transaction alarm loop are measured.
+//! Alarms go to a [`CountingAlarm`] (a counter, no tracing); after each table
+//! the alarm count is cross-checked against the Consumers' flagged count and a
+//! mismatch aborts the run.
+//!
+//! # Simulated latency
+//!
+//! ```text
+//! cargo run --bin fraud_detection_bench --features bench --release -- --storage-latency-us 2000
+//! cargo run --bin fraud_detection_bench --features bench --release -- --model-latency-us 50
+//! ```
+//!
+//! `--storage-latency-us` makes [`BenchStorage`] sleep that long per Logger
+//! batch and `--model-latency-us` makes [`BenchModel`] charge that much per
+//! transaction, to model a slower backend without real I/O. Both default to 0
+//! (no sleep at all); `--model-latency-us` cannot be combined with
+//! `--fraud-rate`, which replaces [`BenchModel`].
+//!
+//! # Batch-size modes
+//!
+//! ```text
+//! cargo run --bin fraud_detection_bench --features bench --release -- --batch-size-mode compare
+//! cargo run --bin fraud_detection_bench --features bench --release -- --batch-size-mode max
+//! ```
+//!
+//! `--batch-size-mode` sets how the Consumers and the Logger size their reads
+//! (`uniform`, the default, `max`, or `geometric:P`) and runs the whole table
+//! once per mode; `compare` runs [`COMPARE_MODES`]. The Producer keeps writing
+//! full batches.
+//!
+//! # Queue latency
+//!
+//! ```text
+//! cargo run --bin fraud_detection_bench --features bench --release -- --queue-latency
+//! ```
+//!
+//! `--queue-latency` builds both buffers with latency tracing and prints, under
+//! each row, the p50/p95/p99 queue delay of buffer1 and buffer2 in the first
+//! round. Tracing stamps every transaction id, so it is off by default to keep
+//! the throughput figures comparable.
+//!
+//! # Stable numbers
+//!
+//! ```text
+//! cargo run --bin fraud_detection_bench --features bench --release -- --warmup-rounds 2 --trim
+//! ```
+//!
+//! `--warmup-rounds N` runs N extra rounds per batch size before the measured
+//! ones and leaves them out of the statistics: the first round pays for
+//! allocator warm-up and page faults. `--trim` reports the mean without the
+//! best and the worst round (from [`TRIM_MIN_ROUNDS`] measured rounds up) as
+//! `avg`. `--verbose` prints every round, warm-up included. An `rsd` well
+//! above the difference between two runs means that difference is noise.
+//!
+//! # Per-operation breakdown
+//!
+//! ```text
+//! cargo run --bin fraud_detection_bench --features bench-profile --release
+//! ```
+//!
+//! The `bench-profile` feature times the hot inner operations of the stages
+//! (see `domain::profile`): UUID fill, amount sampling and name cloning in the
+//! Producer, `classify` in the Modelizer and `PendingTransaction` construction
+//! in the Logger. Under each row, the first measured round's calls, time and
+//! share of the round are printed per operation. The timers themselves cost a
+//! few tens of nanoseconds per call, so compare throughput without the
+//! feature; without it the hooks compile to plain calls.
 
 mod adapters;
 
-// Load bench-only adapters into this binary's module tree only.
-// Same #[path] technique as main_sqlite.rs / sqlite_storage:
-// avoids dead_code warnings in the other binaries.
-#[path = "adapters/bench_model.rs"]
-mod bench_model;
-#[path = "adapters/bench_storage.rs"]
-mod bench_storage;
-
-use std::time::Instant;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use adapters::concurrent_buffer::ConcurrentBuffer;
 use adapters::concurrent_buffer2::ConcurrentBuffer2;
+use adapters::bench_model::BenchModel;
+use adapters::bench_storage::BenchStorage;
 use adapters::log_alarm::LogAlarm;
-use bench_model::BenchModel;
-use bench_storage::BenchStorage;
-use consumer::{Consumer, ConsumerConfig};
+use adapters::queue_latency::LatencyStats;
+use consumer::{Consumer, ConsumerConfig, ConsumerError};
+use domain::profile::{self, Op, Profile};
+use domain::{Alarm, BatchSizeMode, Model, Transaction};
 use logger::{Logger, LoggerConfig};
 use modelizer::Modelizer;
+use pipeline::memory::{CountingAlarm, RateModel};
 use producer::{Producer, ProducerConfig};
 
 // ---------------------------------------------------------------------------
@@ -67,57 +164,141 @@ const ITERATIONS: u64 = 1_000;
 /// Number of pipeline runs averaged per batch size.
 const ROUNDS: u32 = 5;
 
+/// Fewest measured rounds for which `--trim` drops the best and worst one.
+const TRIM_MIN_ROUNDS: usize = 4;
+
 /// Batch sizes exercised. Applied uniformly to `n1_max`, `n2_max`, and `n3_max`.
 const BATCH_SIZES: &[usize] = &[1_000, 2_000, 5_000, 10_000, 20_000, 50_000, 100_000];
 
+/// Fraud rates run by `--fraud-rate sweep`.
+const SWEEP_RATES: &[f64] = &[0.0, 0.05, 0.5];
+
+/// Upper bound on a `--pregenerate` dataset (about 100 MB of transactions).
+const PREGENERATE_MAX_TX: usize = 1_000_000;
+
+/// Seed of the [`RateModel`] used by `--fraud-rate`.
+const RATE_MODEL_SEED: u64 = 42;
+
+/// Modes run by `--batch-size-mode compare`. With `p = 0.001` a geometric
+/// read averages 1 000 items, the smallest batch size of the table.
+const COMPARE_MODES: &[BatchSizeMode] =
+    &[BatchSizeMode::Uniform, BatchSizeMode::AlwaysMax, BatchSizeMode::Geometric { p: 0.001 }];
+
 // ---------------------------------------------------------------------------
 // Single pipeline run
 // ---------------------------------------------------------------------------
 
-/// Run the full pipeline once with the given `batch_size`; return `(total_tx, elapsed)`.
+/// Settings of the downstream stages, shared by every run of a table.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct StageOptions {
+    /// [`BenchStorage`] cost per Logger batch.
+    storage_latency: Duration,
+    /// How the Consumers and the Logger size their reads.
+    batch_size_mode: BatchSizeMode,
+    /// Trace the queue delay of both buffers.
+    queue_latency: bool,
+}
+
+/// Outcome of one pipeline run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BenchRun {
+    /// Transactions that reached storage.
+    total_tx: usize,
+    /// Transactions flagged, summed over all Consumers.
+    flagged: u64,
+    elapsed: std::time::Duration,
+    /// Queue delay of buffer1 and buffer2, with `--queue-latency`.
+    queue_latency: Option<(LatencyStats, LatencyStats)>,
+    /// Time spent per hot operation; empty without `bench-profile`.
+    profile: Profile,
+}
+
+/// Run the full pipeline once with the given `batch_size` and `consumers`
+/// Consumers, classifying with `model` and alerting through `alarm`.
+///
+/// With a `dataset`, the Producer streams a copy of it (made before the timer
+/// starts) instead of generating batches.
+///
+/// `alarm` is borrowed so the caller can read its state (e.g. a count) after
+/// the run; `model` is consumed by the run's Modelizer. `stages` sets the
+/// storage latency and the batch-size mode of the Consumers and the Logger.
 ///
 /// # Errors
 ///
 /// Returns an error if any config builder or pipeline stage fails.
-async fn run_bench(batch_size: usize) -> anyhow::Result<(usize, std::time::Duration)> {
+async fn run_bench<M: Model, A: Alarm>(
+    batch_size: usize,
+    consumers: usize,
+    dataset: Option<Rc<[Transaction]>>,
+    model: M,
+    alarm: &A,
+    stages: StageOptions,
+) -> anyhow::Result<BenchRun> {
+    run_bench_iterations(batch_size, consumers, ITERATIONS, dataset, model, alarm, stages).await
+}
+
+/// [`run_bench`] with an explicit Producer iteration count (tests use a few).
+///
+/// # Errors
+///
+/// Returns an error if any config builder or pipeline stage fails.
+async fn run_bench_iterations<M: Model, A: Alarm>(
+    batch_size: usize,
+    consumers: usize,
+    iterations: u64,
+    dataset: Option<Rc<[Transaction]>>,
+    model: M,
+    alarm: &A,
+    stages: StageOptions,
+) -> anyhow::Result<BenchRun> {
     let producer_config = ProducerConfig::builder(batch_size)
         // Duration::ZERO: no artificial delay -- maximum throughput.
         .poll_interval1(std::time::Duration::ZERO)
-        .iterations(ITERATIONS)
+        .iterations(iterations)
         .seed(42)
         .build()?;
 
-    let consumer_config = ConsumerConfig::builder(batch_size)
-        .poll_interval2(std::time::Duration::ZERO)
-        // No .iterations(): drain until buffer closes.
-        .seed(42)
-        .build()?;
+    let consumer_configs = (42_u64..)
+        .take(consumers)
+        .map(|seed| {
+            ConsumerConfig::builder(batch_size)
+                .poll_interval2(std::time::Duration::ZERO)
+                // No .iterations(): drain until buffer closes.
+                .seed(seed)
+                .batch_size_mode(stages.batch_size_mode)
+                .build()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     let logger_config = LoggerConfig::builder(batch_size)
         .poll_interval3(std::time::Duration::ZERO)
         // No .iterations(): drain until buffer closes.
         .seed(42)
+        .batch_size_mode(stages.batch_size_mode)
         .build()?;
 
-    let buffer1 = ConcurrentBuffer::new();
-    let buffer2 = ConcurrentBuffer2::new();
-    let model = BenchModel::new();
+    let buffer1 = ConcurrentBuffer::with_latency_tracing(stages.queue_latency);
+    let buffer2 = ConcurrentBuffer2::with_latency_tracing(stages.queue_latency);
     let modelizer = Modelizer::new(model);
-    let alarm = LogAlarm::new();
     // BenchStorage: counts transactions, discards immediately -- no allocation.
-    let storage = BenchStorage::new();
+    let storage = BenchStorage::new().with_latency(stages.storage_latency);
 
-    let producer = Producer::new(producer_config);
-    let consumer = Consumer::new(consumer_config);
+    let producer = match dataset {
+        Some(dataset) => Producer::pregenerated(producer_config, dataset.to_vec()),
+        None => Producer::new(producer_config),
+    };
+    let consumers: Vec<Consumer> = consumer_configs.into_iter().map(Consumer::new).collect();
     let logger = Logger::new(logger_config);
 
+    // Drop what building the stages (e.g. a pregenerated dataset) recorded.
+    let _ = profile::take();
     let start = Instant::now();
 
     // Shutdown cascade identical to main.rs:
-    //   Producer completes -> buffer1.close() -> Consumer drains+stops
+    //   Producer completes -> buffer1.close() -> every Consumer drains+stops
     //   -> buffer2.close() -> Logger drains+stops.
     let consumer_then_close = async {
-        let r = consumer.run(&buffer1, &modelizer, &alarm, &buffer2).await;
+        let r = run_consumers(&consumers, &buffer1, &modelizer, alarm, &buffer2).await;
         buffer2.close();
         r
     };
@@ -136,58 +317,456 @@ async fn run_bench(batch_size: usize) -> anyhow::Result<(usize, std::time::Durat
     l?;
 
     let elapsed = start.elapsed();
-    Ok((storage.count(), elapsed))
+    let profile = profile::take();
+    let flagged = consumers.iter().map(|c| c.stats().flagged).sum();
+    let queue_latency = buffer1.latency_stats().zip(buffer2.latency_stats());
+    Ok(BenchRun { total_tx: storage.count(), flagged, elapsed, queue_latency, profile })
+}
+
+/// Generate the `--pregenerate` dataset for `batch_size`: enough for
+/// `ITERATIONS` full batches, capped at [`PREGENERATE_MAX_TX`].
+///
+/// # Errors
+///
+/// Returns an error if the Producer config is rejected.
+fn pregenerate_dataset(batch_size: usize) -> anyhow::Result<Rc<[Transaction]>> {
+    let iterations = usize::try_from(ITERATIONS)?;
+    let total = batch_size.saturating_mul(iterations).min(PREGENERATE_MAX_TX);
+    let config = ProducerConfig::builder(batch_size).seed(42).pregenerate(total).build()?;
+    Ok(Producer::new(config).dataset().into())
+}
+
+/// Run every Consumer in `consumers` concurrently on the shared buffers.
+///
+/// Recursive `join!` keeps all of them on the current task, like the single
+/// Consumer case; the future is boxed because it recurses.
+///
+/// # Errors
+///
+/// Returns the first Consumer error, in slice order, once all have stopped.
+fn run_consumers<'a, M: Model, A: Alarm>(
+    consumers: &'a [Consumer],
+    buffer1: &'a ConcurrentBuffer,
+    modelizer: &'a Modelizer<M>,
+    alarm: &'a A,
+    buffer2: &'a ConcurrentBuffer2,
+) -> Pin<Box<dyn Future<Output = Result<(), ConsumerError>> + 'a>> {
+    Box::pin(async move {
+        let Some((first, rest)) = consumers.split_first() else {
+            return Ok(());
+        };
+        let (head, tail) = tokio::join!(
+            first.run(buffer1, modelizer, alarm, buffer2),
+            run_consumers(rest, buffer1, modelizer, alarm, buffer2)
+        );
+        head?;
+        tail
+    })
 }
 
 // ---------------------------------------------------------------------------
 // Entry point
 // ---------------------------------------------------------------------------
 
-#[tokio::main(flavor = "current_thread")]
-async fn main() -> anyhow::Result<()> {
-    println!("bench: ITERATIONS={ITERATIONS}  ROUNDS={ROUNDS}  (storage cost excluded)");
+/// Command-line options.
+#[derive(Debug, Clone, PartialEq)]
+#[expect(clippy::struct_excessive_bools, reason = "one field per command-line switch")]
+struct BenchArgs {
+    /// `--consumers N`; default 1.
+    consumers: usize,
+    /// `--fraud-rate`; `None` keeps [`BenchModel`] (no fraud).
+    fraud_rates: Option<Vec<f64>>,
+    /// `--pregenerate`: stream pre-generated datasets.
+    pregenerate: bool,
+    /// `--model-latency-us`: [`BenchModel`] cost per transaction; default 0.
+    model_latency: Duration,
+    /// `--storage-latency-us`: [`BenchStorage`] cost per batch; default 0.
+    storage_latency: Duration,
+    /// `--batch-size-mode`: one table per mode; default `[Uniform]`.
+    batch_size_modes: Vec<BatchSizeMode>,
+    /// `--queue-latency`: print per-buffer queue delay percentiles.
+    queue_latency: bool,
+    /// `--warmup-rounds N`: unmeasured rounds per batch size; default 0.
+    warmup_rounds: u32,
+    /// `--trim`: report the trimmed mean as `avg`.
+    trim: bool,
+    /// `--verbose`: print every round.
+    verbose: bool,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        Self {
+            consumers: 1,
+            fraud_rates: None,
+            pregenerate: false,
+            model_latency: Duration::ZERO,
+            storage_latency: Duration::ZERO,
+            batch_size_modes: vec![BatchSizeMode::Uniform],
+            queue_latency: false,
+            warmup_rounds: 0,
+            trim: false,
+            verbose: false,
+        }
+    }
+}
+
+/// Parse `--consumers N`, `--fraud-rate R[,R...]|sweep`, `--pregenerate`,
+/// `--model-latency-us US`, `--storage-latency-us US`,
+/// `--batch-size-mode MODE[,MODE...]|compare`, `--queue-latency`,
+/// `--warmup-rounds N`, `--trim` and `--verbose` from `args` (program name
+/// excluded). Other arguments (e.g. libtest's) are ignored.
+///
+/// # Errors
+///
+/// Returns an error if a value is missing or malformed, `consumers` is zero,
+/// a rate is outside `[0, 1]`, a geometric `p` is outside `(0, 1]`, or a
+/// model latency is combined with `--fraud-rate`.
+fn parse_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<BenchArgs> {
+    let mut args = args.into_iter();
+    let mut parsed = BenchArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--consumers" => {
+                let Some(value) = args.next() else {
+                    anyhow::bail!("--consumers requires a value");
+                };
+                parsed.consumers = value.parse()?;
+                anyhow::ensure!(parsed.consumers > 0, "--consumers must be at least 1");
+            }
+            "--fraud-rate" => {
+                let Some(value) = args.next() else {
+                    anyhow::bail!("--fraud-rate requires a value");
+                };
+                parsed.fraud_rates = Some(parse_fraud_rates(&value)?);
+            }
+            "--pregenerate" => parsed.pregenerate = true,
+            "--queue-latency" => parsed.queue_latency = true,
+            "--trim" => parsed.trim = true,
+            "--verbose" => parsed.verbose = true,
+            "--warmup-rounds" => {
+                let Some(value) = args.next() else {
+                    anyhow::bail!("--warmup-rounds requires a value");
+                };
+                parsed.warmup_rounds = value.parse()?;
+            }
+            "--batch-size-mode" => {
+                let Some(value) = args.next() else {
+                    anyhow::bail!("--batch-size-mode requires a value");
+                };
+                parsed.batch_size_modes = parse_batch_size_modes(&value)?;
+            }
+            "--model-latency-us" | "--storage-latency-us" => {
+                let Some(value) = args.next() else {
+                    anyhow::bail!("{arg} requires a value");
+                };
+                let latency = Duration::from_micros(value.parse()?);
+                if arg == "--model-latency-us" {
+                    parsed.model_latency = latency;
+                } else {
+                    parsed.storage_latency = latency;
+                }
+            }
+            _ => {}
+        }
+    }
+    anyhow::ensure!(
+        parsed.model_latency.is_zero() || parsed.fraud_rates.is_none(),
+        "--model-latency-us applies to BenchModel and cannot be combined with --fraud-rate"
+    );
+    Ok(parsed)
+}
+
+/// Parse `sweep` or a comma-separated list of rates in `[0, 1]`.
+fn parse_fraud_rates(value: &str) -> anyhow::Result<Vec<f64>> {
+    if value == "sweep" {
+        return Ok(SWEEP_RATES.to_vec());
+    }
+    value
+        .split(',')
+        .map(|rate| {
+            let rate: f64 = rate.trim().parse()?;
+            anyhow::ensure!((0.0..=1.0).contains(&rate), "--fraud-rate {rate} is outside [0, 1]");
+            Ok(rate)
+        })
+        .collect()
+}
+
+/// Parse `compare` or a comma-separated list of `uniform`, `max` and
+/// `geometric:P`.
+fn parse_batch_size_modes(value: &str) -> anyhow::Result<Vec<BatchSizeMode>> {
+    if value == "compare" {
+        return Ok(COMPARE_MODES.to_vec());
+    }
+    value
+        .split(',')
+        .map(|mode| {
+            let mode = match mode.trim() {
+                "uniform" => BatchSizeMode::Uniform,
+                "max" => BatchSizeMode::AlwaysMax,
+                other => {
+                    let Some(p) = other.strip_prefix("geometric:") else {
+                        anyhow::bail!("unknown --batch-size-mode {other}");
+                    };
+                    BatchSizeMode::Geometric { p: p.parse()? }
+                }
+            };
+            mode.validate()?;
+            Ok(mode)
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Round statistics
+// ---------------------------------------------------------------------------
+
+/// Throughput of the measured rounds of one batch size.
+///
+/// The first `warmup` recorded rounds are discarded; every later one counts.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BenchStats {
+    /// Warm-up rounds still to discard.
+    warmup: u32,
+    /// Throughput of each measured round, in tx/s.
+    tps: Vec<f64>,
+}
+
+impl BenchStats {
+    /// Empty statistics discarding the first `warmup` rounds.
+    fn with_warmup(warmup: u32) -> Self {
+        Self { warmup, tps: Vec::new() }
+    }
+
+    /// Record one round; returns `false` when it was a warm-up round.
+    fn record(&mut self, tps: f64) -> bool {
+        if self.warmup > 0 {
+            self.warmup -= 1;
+            return false;
+        }
+        self.tps.push(tps);
+        true
+    }
+
+    /// Slowest measured round; 0 without any.
+    fn min(&self) -> f64 {
+        self.tps.iter().copied().reduce(f64::min).unwrap_or(0.0)
+    }
+
+    /// Fastest measured round; 0 without any.
+    fn max(&self) -> f64 {
+        self.tps.iter().copied().reduce(f64::max).unwrap_or(0.0)
+    }
+
+    /// Mean of the measured rounds; 0 without any.
+    fn mean(&self) -> f64 {
+        mean(&self.tps)
+    }
+
+    /// Mean without the best and the worst round, from [`TRIM_MIN_ROUNDS`]
+    /// measured rounds up; the plain mean below that.
+    fn trimmed_mean(&self) -> f64 {
+        if self.tps.len() < TRIM_MIN_ROUNDS {
+            return self.mean();
+        }
+        let mut sorted = self.tps.clone();
+        sorted.sort_by(f64::total_cmp);
+        mean(&sorted[1..sorted.len() - 1])
+    }
+
+    /// Relative standard deviation in percent: sample standard deviation
+    /// over the mean. 0 with fewer than two rounds or a zero mean.
+    fn rsd(&self) -> f64 {
+        let mean = self.mean();
+        if self.tps.len() < 2 || mean <= 0.0 {
+            return 0.0;
+        }
+        let squares: f64 = self.tps.iter().map(|tps| (tps - mean).powi(2)).sum();
+        #[expect(clippy::cast_precision_loss, reason = "round counts are tiny")]
+        let variance = squares / (self.tps.len() - 1) as f64;
+        variance.sqrt() / mean * 100.0
+    }
+}
+
+/// Arithmetic mean of `values`; 0 when empty.
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    #[expect(clippy::cast_precision_loss, reason = "round counts are tiny")]
+    let len = values.len() as f64;
+    values.iter().sum::<f64>() / len
+}
+
+/// Print the results table: one row per batch size, `ROUNDS` measured runs
+/// each, after `args.warmup_rounds` unmeasured ones.
+///
+/// `make_run(batch_size, dataset)` performs one run; its alarm is the
+/// caller's, so the caller can cross-check counts afterwards (warm-up rounds
+/// included in the returned flagged total). With `args.pregenerate`, each
+/// batch size's dataset is built once, before its rounds.
+///
+/// # Errors
+///
+/// Returns the first failing run's error.
+async fn print_table<F, Fut>(args: &BenchArgs, mut make_run: F) -> anyhow::Result<u64>
+where
+    F: FnMut(usize, Option<Rc<[Transaction]>>) -> Fut,
+    Fut: Future<Output = anyhow::Result<BenchRun>>,
+{
+    if args.trim {
+        println!("avg: trimmed mean (best and worst round dropped)");
+    }
     println!(
-        "{:>10} | {:>10} | {:>10} | {:>10} | {:>10}",
-        "batch_size", "total_tx", "min tx/s", "avg tx/s", "max tx/s"
+        "{:>10} | {:>10} | {:>10} | {:>10} | {:>10} | {:>6}",
+        "batch_size", "total_tx", "min tx/s", "avg tx/s", "max tx/s", "rsd %"
     );
-    println!("{:-<11}+{:-<12}+{:-<12}+{:-<12}+{:-<11}", "", "", "", "", "");
+    println!("{:-<11}+{:-<12}+{:-<12}+{:-<12}+{:-<12}+{:-<7}", "", "", "", "", "", "");
 
+    let mut flagged = 0u64;
     for &batch_size in BATCH_SIZES {
-        let mut total_tx_first = 0usize;
-        let mut min_tps = f64::MAX;
-        let mut max_tps = 0.0_f64;
-        let mut sum_tps = 0.0_f64;
+        let mut first_measured = None;
+        let mut stats = BenchStats::with_warmup(args.warmup_rounds);
+        let dataset = if args.pregenerate { Some(pregenerate_dataset(batch_size)?) } else { None };
 
-        for round in 0..ROUNDS {
-            let (total_tx, elapsed) = run_bench(batch_size).await?;
+        for round in 0..args.warmup_rounds + ROUNDS {
+            let run = make_run(batch_size, dataset.clone()).await?;
+            flagged += run.flagged;
             #[expect(clippy::cast_precision_loss, reason = "total_tx count fits in f64 mantissa for realistic benchmarks")]
-            let tps = total_tx as f64 / elapsed.as_secs_f64();
-            if round == 0 {
-                total_tx_first = total_tx;
+            let tps = run.total_tx as f64 / run.elapsed.as_secs_f64();
+            let measured = stats.record(tps);
+            if measured && first_measured.is_none() {
+                first_measured = Some(run);
             }
-            if tps < min_tps {
-                min_tps = tps;
+            if args.verbose {
+                let tag = if measured { "" } else { " (warm-up)" };
+                #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "throughput values always positive and within usize range")]
+                let tps = fmt_number(tps as usize);
+                println!(
+                    "{:>10}   round {round}{tag}: {} tx in {:?}, {tps} tx/s",
+                    "",
+                    fmt_number(run.total_tx),
+                    run.elapsed
+                );
             }
-            if tps > max_tps {
-                max_tps = tps;
-            }
-            sum_tps += tps;
         }
 
-        let avg_tps = sum_tps / f64::from(ROUNDS);
+        let avg_tps = if args.trim { stats.trimmed_mean() } else { stats.mean() };
+        let total_tx_first = first_measured.map_or(0, |run| run.total_tx);
 
         #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "throughput values always positive and within usize range")]
-        {
-            println!(
-                "{:>10} | {:>10} | {:>10} | {:>10} | {:>10}",
-                fmt_number(batch_size),
-                fmt_number(total_tx_first),
-                fmt_number(min_tps as usize),
-                fmt_number(avg_tps as usize),
-                fmt_number(max_tps as usize),
-            );
+        let (min_tps, avg_tps, max_tps) =
+            (stats.min() as usize, avg_tps as usize, stats.max() as usize);
+        println!(
+            "{:>10} | {:>10} | {:>10} | {:>10} | {:>10} | {:>6.1}",
+            fmt_number(batch_size),
+            fmt_number(total_tx_first),
+            fmt_number(min_tps),
+            fmt_number(avg_tps),
+            fmt_number(max_tps),
+            stats.rsd(),
+        );
+        if let Some((buffer1, buffer2)) = first_measured.and_then(|run| run.queue_latency) {
+            println!("{:>10}   buffer1 queue delay: {buffer1}", "");
+            println!("{:>10}   buffer2 queue delay: {buffer2}", "");
+        }
+        if let Some(run) = first_measured.filter(|run| !run.profile.is_empty()) {
+            print_profile(&run.profile, run.elapsed);
         }
     }
+    Ok(flagged)
+}
+
+/// Print the per-operation breakdown of one round that took `elapsed`.
+fn print_profile(profile: &Profile, elapsed: Duration) {
+    for op in Op::ALL {
+        let time = profile.get(op);
+        let ns_per_call = time.total.as_nanos().checked_div(u128::from(time.calls)).unwrap_or(0);
+        let share = time.total.as_secs_f64() / elapsed.as_secs_f64() * 100.0;
+        #[expect(clippy::cast_possible_truncation, reason = "call counts fit in usize")]
+        let calls = fmt_number(time.calls as usize);
+        let ms = time.total.as_secs_f64() * 1_000.0;
+        println!(
+            "{:>10}   {:<9} {:<13} {calls:>11} calls {ms:>9.1} ms {ns_per_call:>6} ns/call \
+             {share:>5.1} %",
+            "",
+            op.stage(),
+            op.label(),
+        );
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let args = parse_args(std::env::args().skip(1))?;
+    let consumers = args.consumers;
+    println!(
+        "bench: ITERATIONS={ITERATIONS}  ROUNDS={ROUNDS}  CONSUMERS={consumers}  (storage cost excluded)"
+    );
+    let storage_latency = args.storage_latency;
+    if !(args.model_latency.is_zero() && storage_latency.is_zero()) {
+        println!(
+            "latency: model {} us/tx, storage {} us/batch (simulated with tokio::time::sleep)",
+            args.model_latency.as_micros(),
+            storage_latency.as_micros()
+        );
+    }
+    if args.warmup_rounds > 0 {
+        println!("warm-up: {} unmeasured rounds per batch size", args.warmup_rounds);
+    }
+    if args.pregenerate {
+        println!(
+            "producer: pregenerated, up to {} tx per batch size (generation not timed)",
+            fmt_number(PREGENERATE_MAX_TX)
+        );
+    }
+
+    let compare = args.batch_size_modes != [BatchSizeMode::Uniform];
+    for &batch_size_mode in &args.batch_size_modes {
+        if compare {
+            println!();
+            println!("batch_size_mode={batch_size_mode:?}  (Consumers and Logger)");
+        }
+        let queue_latency = args.queue_latency;
+        let stages = StageOptions { storage_latency, batch_size_mode, queue_latency };
+        run_tables(&args, stages).await?;
+    }
+    Ok(())
+}
+
+/// Print the table for `args`, once per fraud rate with `--fraud-rate`.
+///
+/// # Errors
+///
+/// Returns the first failing run's error, or an error when the alarm count
+/// of a fraud-rate table does not match the flagged count.
+async fn run_tables(args: &BenchArgs, stages: StageOptions) -> anyhow::Result<()> {
+    let consumers = args.consumers;
+    let Some(rates) = &args.fraud_rates else {
+        let alarm = LogAlarm::new();
+        print_table(args, |batch_size, dataset| {
+            let model = BenchModel::new().with_latency(args.model_latency);
+            run_bench(batch_size, consumers, dataset, model, &alarm, stages)
+        })
+        .await?;
+        return Ok(());
+    };
+
+    for &rate in rates {
+        println!();
+        let percent = rate * 100.0;
+        println!("fraud_rate={percent:.1}%  (RateModel seed {RATE_MODEL_SEED}, CountingAlarm)");
+        let alarm = CountingAlarm::new();
+        let flagged = print_table(args, |batch_size, dataset| {
+            let model = RateModel::new(rate, RATE_MODEL_SEED);
+            run_bench(batch_size, consumers, dataset, model, &alarm, stages)
+        })
+        .await?;
+        let alarms = alarm.count();
+        let verdict = if alarms == flagged { "OK" } else { "MISMATCH" };
+        println!("check: {alarms} alarms, {flagged} flagged -- {verdict}");
+        anyhow::ensure!(alarms == flagged, "alarm count {alarms} != flagged count {flagged}");
+    }
 
     Ok(())
 }
@@ -204,3 +783,267 @@ fn fmt_number(n: usize) -> String {
     }
     out.chars().rev().collect()
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchSizeMode, BenchArgs, BenchModel, BenchStats, CountingAlarm, RateModel};
+    use super::{COMPARE_MODES, StageOptions, SWEEP_RATES, parse_args, run_bench_iterations};
+    #[cfg(feature = "bench-profile")]
+    use domain::profile::Op;
+    use producer::{Producer, ProducerConfig};
+    use std::time::Duration;
+
+    fn args(list: &[&str]) -> anyhow::Result<BenchArgs> {
+        parse_args(list.iter().map(|&a| a.to_owned()))
+    }
+
+    #[test]
+    fn parses_consumers_and_fraud_rates() {
+        let parsed = args(&[]).unwrap();
+        assert_eq!(parsed, BenchArgs::default());
+        let parsed = args(&["--consumers", "3", "--fraud-rate", "0.1, 0.25"]).unwrap();
+        assert_eq!(parsed.consumers, 3);
+        assert_eq!(parsed.fraud_rates, Some(vec![0.1, 0.25]));
+        assert!(args(&["--pregenerate"]).unwrap().pregenerate);
+        let parsed = args(&["--fraud-rate", "sweep"]).unwrap();
+        assert_eq!(parsed.fraud_rates.as_deref(), Some(SWEEP_RATES));
+    }
+
+    #[test]
+    fn parses_latencies_in_microseconds() {
+        assert_eq!(args(&[]).unwrap().model_latency, Duration::ZERO);
+        let parsed = args(&["--model-latency-us", "50", "--storage-latency-us", "2000"]).unwrap();
+        assert_eq!(parsed.model_latency, Duration::from_micros(50));
+        assert_eq!(parsed.storage_latency, Duration::from_millis(2));
+        // Storage latency still applies to a RateModel run.
+        let parsed = args(&["--fraud-rate", "sweep", "--storage-latency-us", "10"]).unwrap();
+        assert_eq!(parsed.storage_latency, Duration::from_micros(10));
+    }
+
+    #[test]
+    fn parses_warmup_trim_and_verbose() {
+        let parsed = args(&["--warmup-rounds", "2", "--trim", "--verbose"]).unwrap();
+        assert_eq!((parsed.warmup_rounds, parsed.trim, parsed.verbose), (2, true, true));
+        let parsed = args(&[]).unwrap();
+        assert_eq!((parsed.warmup_rounds, parsed.trim, parsed.verbose), (0, false, false));
+    }
+
+    #[test]
+    fn bench_stats_exclude_warmup_rounds() {
+        let mut stats = BenchStats::with_warmup(2);
+        // Cold rounds are the slowest; they must not reach min or the mean.
+        assert!(!stats.record(10.0));
+        assert!(!stats.record(20.0));
+        for tps in [100.0, 200.0, 300.0] {
+            assert!(stats.record(tps));
+        }
+
+        assert_eq!(stats.tps.len(), 3);
+        assert!((stats.min() - 100.0).abs() < 1e-9);
+        assert!((stats.max() - 300.0).abs() < 1e-9);
+        assert!((stats.mean() - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bench_stats_trim_best_and_worst_from_four_rounds() {
+        let mut stats = BenchStats::default();
+        for tps in [100.0, 1_000.0, 110.0] {
+            stats.record(tps);
+        }
+        // Three rounds: too few to trim, the outlier stays.
+        assert!((stats.trimmed_mean() - stats.mean()).abs() < 1e-9);
+
+        stats.record(10.0);
+        // 10 and 1 000 dropped: mean of 100 and 110.
+        assert!((stats.trimmed_mean() - 105.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bench_stats_rsd_is_sample_deviation_over_mean() {
+        let mut stats = BenchStats::default();
+        stats.record(100.0);
+        assert!(stats.rsd().abs() < 1e-9, "one round has no spread");
+        for tps in [100.0, 100.0, 100.0] {
+            stats.record(tps);
+        }
+        assert!(stats.rsd().abs() < 1e-9);
+
+        let mut stats = BenchStats::default();
+        for tps in [90.0, 100.0, 110.0] {
+            stats.record(tps);
+        }
+        // Sample variance (100 + 0 + 100) / 2 = 100: standard deviation 10.
+        assert!((stats.rsd() - 10.0).abs() < 1e-9, "{}", stats.rsd());
+        assert!(BenchStats::default().rsd().abs() < 1e-9);
+    }
+
+    #[test]
+    fn parses_batch_size_modes() {
+        assert_eq!(args(&[]).unwrap().batch_size_modes, [BatchSizeMode::Uniform]);
+        let parsed = args(&["--batch-size-mode", "max, geometric:0.5,uniform"]).unwrap();
+        assert_eq!(
+            parsed.batch_size_modes,
+            [BatchSizeMode::AlwaysMax, BatchSizeMode::Geometric { p: 0.5 }, BatchSizeMode::Uniform]
+        );
+        let parsed = args(&["--batch-size-mode", "compare"]).unwrap();
+        assert_eq!(parsed.batch_size_modes, COMPARE_MODES);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        for bad in [
+            &["--consumers"][..],
+            &["--consumers", "0"],
+            &["--fraud-rate"],
+            &["--fraud-rate", "1.5"],
+            &["--fraud-rate", "abc"],
+            &["--model-latency-us"],
+            &["--storage-latency-us", "-1"],
+            &["--model-latency-us", "5", "--fraud-rate", "0.1"],
+            &["--batch-size-mode"],
+            &["--batch-size-mode", "largest"],
+            &["--batch-size-mode", "geometric:0"],
+            &["--batch-size-mode", "geometric:1.5"],
+            &["--warmup-rounds"],
+            &["--warmup-rounds", "-1"],
+        ] {
+            if let Ok(parsed) = args(bad) {
+                panic!("{bad:?} parsed as {parsed:?}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn counting_alarm_matches_flagged() {
+        let alarm = CountingAlarm::new();
+        let model = RateModel::new(0.5, 42);
+        let stages = StageOptions::default();
+        let run = run_bench_iterations(100, 2, 5, None, model, &alarm, stages).await.unwrap();
+        assert!(run.total_tx > 0);
+        assert!(run.flagged > 0 && run.flagged < run.total_tx as u64, "{run:?}");
+        assert_eq!(alarm.count(), run.flagged);
+    }
+
+    #[tokio::test]
+    async fn bench_model_raises_no_alarm() {
+        let alarm = CountingAlarm::new();
+        let stages = StageOptions::default();
+        let run = run_bench_iterations(100, 1, 3, None, BenchModel::new(), &alarm, stages)
+            .await
+            .unwrap();
+        assert!(run.total_tx > 0);
+        assert_eq!((run.flagged, alarm.count()), (0, 0));
+    }
+
+    // The mode changes how the stages read, not what reaches storage: the
+    // Producer is seeded, so every mode persists the same total.
+    #[tokio::test]
+    async fn every_batch_size_mode_delivers_everything() {
+        let mut totals = vec![];
+        for &batch_size_mode in COMPARE_MODES {
+            let alarm = CountingAlarm::new();
+            let stages = StageOptions { batch_size_mode, ..StageOptions::default() };
+            let run = run_bench_iterations(100, 2, 4, None, BenchModel::new(), &alarm, stages)
+                .await
+                .unwrap();
+            totals.push(run.total_tx);
+        }
+        assert!(totals[0] > 0);
+        assert!(totals.iter().all(|&t| t == totals[0]), "{totals:?}");
+    }
+
+    #[tokio::test]
+    async fn pregenerated_run_streams_the_whole_dataset() {
+        let config = ProducerConfig::builder(100).seed(1).pregenerate(250).build().unwrap();
+        let dataset = Producer::new(config).dataset().into();
+        let alarm = CountingAlarm::new();
+        // 5 iterations allowed, 3 batches (100 + 100 + 50) exhaust the dataset.
+        let model = BenchModel::new();
+        let stages = StageOptions::default();
+        let run = run_bench_iterations(100, 2, 5, Some(dataset), model, &alarm, stages)
+            .await
+            .unwrap();
+        assert_eq!(run.total_tx, 250);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn storage_latency_is_awaited_per_logger_batch() {
+        let alarm = CountingAlarm::new();
+        let stages = StageOptions::default();
+        let fast = run_bench_iterations(10, 1, 4, None, BenchModel::new(), &alarm, stages)
+            .await
+            .unwrap();
+        let latency = Duration::from_millis(5);
+        let stages = StageOptions { storage_latency: latency, ..stages };
+        // BenchRun::elapsed is wall-clock time; the paused clock only moves
+        // tokio's Instant.
+        let start = tokio::time::Instant::now();
+        let slow = run_bench_iterations(10, 1, 4, None, BenchModel::new(), &alarm, stages)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+
+        // Same seeds: the latency changes timing, not what reaches storage.
+        assert_eq!(slow.total_tx, fast.total_tx);
+        assert!(elapsed >= latency, "{elapsed:?}");
+    }
+
+    #[tokio::test]
+    async fn queue_latency_counts_every_transaction_through_both_buffers() {
+        assert!(args(&["--queue-latency"]).unwrap().queue_latency);
+        let alarm = CountingAlarm::new();
+        let stages = StageOptions::default();
+        let untraced = run_bench_iterations(10, 1, 4, None, BenchModel::new(), &alarm, stages)
+            .await
+            .unwrap();
+        assert_eq!(untraced.queue_latency, None);
+
+        let stages = StageOptions { queue_latency: true, ..stages };
+        let run = run_bench_iterations(10, 2, 4, None, BenchModel::new(), &alarm, stages)
+            .await
+            .unwrap();
+
+        let (buffer1, buffer2) = run.queue_latency.unwrap();
+        let total = u64::try_from(run.total_tx).unwrap();
+        assert_eq!((buffer1.count, buffer2.count), (total, total));
+        assert!(buffer1.p50 <= buffer1.p99 && buffer1.p99 <= buffer1.max);
+    }
+
+    #[tokio::test]
+    async fn profile_is_empty_without_the_feature() {
+        let alarm = CountingAlarm::new();
+        let stages = StageOptions::default();
+        let run = run_bench_iterations(10, 1, 2, None, BenchModel::new(), &alarm, stages)
+            .await
+            .unwrap();
+
+        assert_eq!(run.profile.is_empty(), !domain::profile::ENABLED);
+    }
+
+    #[cfg(feature = "bench-profile")]
+    #[tokio::test]
+    async fn profile_times_every_operation_within_the_round() {
+        let alarm = CountingAlarm::new();
+        let stages = StageOptions::default();
+        let run = run_bench_iterations(100, 1, 5, None, BenchModel::new(), &alarm, stages)
+            .await
+            .unwrap();
+
+        // A single call can take less than the clock's resolution, so only
+        // the calls are checked per operation.
+        for op in Op::ALL {
+            let time = run.profile.get(op);
+            assert!(time.calls > 0, "{op:?}: {time:?}");
+        }
+        assert!(!run.profile.total().is_zero(), "{:?}", run.profile);
+        // One UUID and one PendingTransaction per persisted transaction.
+        let total = u64::try_from(run.total_tx).unwrap();
+        assert_eq!(run.profile.get(Op::UuidFill).calls, total);
+        assert_eq!(run.profile.get(Op::PendingBuild).calls, total);
+        assert!(run.profile.total() < run.elapsed, "{:?} >= {:?}", run.profile, run.elapsed);
+    }
+}
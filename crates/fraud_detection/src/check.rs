@@ -0,0 +1,199 @@
+// Rust guideline compliant 2026-02-27
+
+//! `--check`: validate a binary's configuration without running the pipeline.
+//!
+//! [`run_checks`] builds every stage configuration, opens the storage (which
+//! runs its schema initialization) and sends one probe transaction through
+//! the model. Each step becomes a PASS or FAIL line of a [`CheckReport`]; a
+//! failed step does not stop the later ones, so a single run lists every
+//! problem. Nothing is written to a buffer and no transaction is produced.
+
+use std::fmt;
+
+use consumer::ConsumerConfigBuilder;
+use domain::{Currency, ErrorChain, Model, Modelizer as _, Transaction, TransactionId};
+use logger::LoggerConfigBuilder;
+use modelizer::Modelizer;
+use producer::ProducerConfigBuilder;
+
+// ---------------------------------------------------------------------------
+// CheckReport
+// ---------------------------------------------------------------------------
+
+/// Outcome of one checked component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckLine {
+    /// Component name, e.g. `"producer"` or `"storage"`.
+    pub component: &'static str,
+    /// Details on success, the error on failure.
+    pub outcome: Result<String, String>,
+}
+
+/// PASS/FAIL lines of a [`run_checks`] run, in check order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckReport {
+    lines: Vec<CheckLine>,
+}
+
+impl CheckReport {
+    /// Record `result` under `component`, describing a success with `detail`
+    /// and a failure with the error's whole source chain.
+    pub fn record<T, E: std::error::Error + 'static>(
+        &mut self,
+        component: &'static str,
+        result: Result<T, E>,
+        detail: impl FnOnce(&T) -> String,
+    ) {
+        let outcome = match result {
+            Ok(value) => Ok(detail(&value)),
+            Err(e) => Err(ErrorChain(&e).to_string()),
+        };
+        self.lines.push(CheckLine { component, outcome });
+    }
+
+    /// Whether every recorded component passed.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.lines.iter().all(|line| line.outcome.is_ok())
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            let (status, detail) = match &line.outcome {
+                Ok(detail) => ("PASS", detail),
+                Err(error) => ("FAIL", error),
+            };
+            writeln!(f, "check: {status} {:<9} {detail}", line.component)?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// run_checks
+// ---------------------------------------------------------------------------
+
+/// Stage configuration builders, shared by a binary's run and its `--check`.
+#[derive(Debug)]
+pub struct StageBuilders {
+    /// Producer settings.
+    pub producer: ProducerConfigBuilder,
+    /// Consumer settings.
+    pub consumer: ConsumerConfigBuilder,
+    /// Logger settings.
+    pub logger: LoggerConfigBuilder,
+}
+
+/// Check `stages`, the storage opened by `open_storage`, and `model`.
+///
+/// `open_storage` is awaited once; for a database adapter that includes the
+/// connection and schema initialization. The opened storage is dropped
+/// before returning. The model check classifies one probe transaction.
+pub async fn run_checks<M, S, E>(
+    stages: StageBuilders,
+    open_storage: impl Future<Output = Result<S, E>>,
+    model: M,
+) -> CheckReport
+where
+    M: Model,
+    E: std::error::Error + 'static,
+{
+    let mut report = CheckReport::default();
+    report.record("producer", stages.producer.build(), |c| {
+        format!("n1_max={}, poll_interval1={:?}", c.n1_max, c.poll_interval1)
+    });
+    report.record("consumer", stages.consumer.build(), |c| {
+        format!("n2_max={}, poll_interval2={:?}", c.n2_max, c.poll_interval2)
+    });
+    report.record("logger", stages.logger.build(), |c| {
+        format!("n3_max={}, poll_interval3={:?}", c.n3_max, c.poll_interval3)
+    });
+    report.record("storage", open_storage.await, |_| "opened, schema ready".to_owned());
+
+    let (name, version) = (model.name().to_owned(), model.active_version().to_owned());
+    let probe = Transaction {
+        id: TransactionId::new_v4(),
+        amount: 1.00_f64,
+        last_name: "check".to_owned(),
+        currency: Currency::Eur,
+        tenant_id: None,
+    };
+    let inferred = Modelizer::new(model).infer(vec![probe]).await;
+    report.record("model", inferred, |batch| {
+        format!("{name} v{version} classified {} probe transaction", batch.len())
+    });
+    report
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{CheckReport, StageBuilders, run_checks};
+    use crate::adapters::demo_model::DemoModel;
+    use crate::adapters::in_memory_storage::InMemoryStorage;
+    use consumer::ConsumerConfig;
+    use domain::StorageError;
+    use logger::LoggerConfig;
+    use producer::ProducerConfig;
+
+    fn stages(n1_max: usize) -> StageBuilders {
+        StageBuilders {
+            producer: ProducerConfig::builder(n1_max),
+            consumer: ConsumerConfig::builder(50),
+            logger: LoggerConfig::builder(10),
+        }
+    }
+
+    fn components(report: &CheckReport) -> Vec<(&'static str, bool)> {
+        report.lines.iter().map(|l| (l.component, l.outcome.is_ok())).collect()
+    }
+
+    // CK-T01: a valid configuration passes every component.
+    #[tokio::test]
+    async fn passing_configuration() {
+        let storage = async { Ok::<_, StorageError>(InMemoryStorage::new(usize::MAX)) };
+
+        let report = run_checks(stages(100), storage, DemoModel::new(Some(1))).await;
+
+        assert!(report.passed(), "{report}");
+        let expected = [("producer", true), ("consumer", true), ("logger", true)];
+        assert_eq!(components(&report)[..3], expected);
+        assert_eq!(components(&report)[3..], [("storage", true), ("model", true)]);
+        let text = report.to_string();
+        assert!(text.contains("check: PASS producer  n1_max=100"), "{text}");
+        assert!(text.contains("DEMO v4 classified 1 probe transaction"), "{text}");
+    }
+
+    // CK-T02: a zero n1_max fails at the config stage; later checks still run.
+    #[tokio::test]
+    async fn zero_n1_max_fails_the_producer_check() {
+        let storage = async { Ok::<_, StorageError>(InMemoryStorage::new(usize::MAX)) };
+
+        let report = run_checks(stages(0), storage, DemoModel::new(Some(1))).await;
+
+        assert!(!report.passed());
+        let Err(error) = &report.lines[0].outcome else {
+            panic!("producer check must fail: {report}");
+        };
+        assert!(error.contains("n1_max"), "{error}");
+        assert!(report.lines[1..].iter().all(|l| l.outcome.is_ok()), "{report}");
+        assert!(report.to_string().starts_with("check: FAIL producer"));
+    }
+
+    // CK-T03: a storage that cannot be opened fails the storage check.
+    #[tokio::test]
+    async fn storage_failure_fails_the_storage_check() {
+        let storage = async { Err::<InMemoryStorage, _>(StorageError::Unavailable) };
+
+        let report = run_checks(stages(100), storage, DemoModel::new(Some(1))).await;
+
+        assert!(!report.passed());
+        assert_eq!(components(&report)[3..], [("storage", false), ("model", true)]);
+        assert!(report.to_string().contains("check: FAIL storage   storage unavailable"));
+    }
+}
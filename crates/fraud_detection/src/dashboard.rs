@@ -0,0 +1,387 @@
+// Rust guideline compliant 2026-02-27
+
+//! Dashboard state of `fraud_detection_tui`, kept apart from the rendering.
+//!
+//! [`Dashboard::apply`] folds the [`PipelineEvent`]s of the broadcast channel
+//! into cumulative counts, and [`Dashboard::tick`] closes one refresh period:
+//! it turns the transactions counted since the previous tick into one tx/s
+//! sample per stage and records the buffer depths. Nothing here touches the
+//! terminal, so scripted event sequences can drive it in tests.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use domain::{PipelineEvent, Stage, TransactionId};
+
+/// Throughput samples kept per stage, one per tick.
+pub const RATE_HISTORY: usize = 300;
+
+/// Alarms kept for the alarm pane, newest first.
+pub const RECENT_ALARMS: usize = 20;
+
+// ---------------------------------------------------------------------------
+// Parts
+// ---------------------------------------------------------------------------
+
+/// Throughput of one stage: the count of the current tick and past samples.
+#[derive(Debug, Clone, Default)]
+struct Throughput {
+    /// Transactions counted since the last tick.
+    pending: u64,
+    /// tx/s per past tick, oldest first, at most [`RATE_HISTORY`].
+    history: VecDeque<u64>,
+    /// Transactions counted since the start.
+    total: u64,
+}
+
+impl Throughput {
+    fn add(&mut self, size: usize) {
+        self.pending += size as u64;
+        self.total += size as u64;
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        reason = "a per-tick count fits f64; the rate is rounded to a non-negative integer"
+    )]
+    fn tick(&mut self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let rate = if secs > 0.0 { (self.pending as f64 / secs).round() as u64 } else { 0 };
+        if self.history.len() == RATE_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(rate);
+        self.pending = 0;
+    }
+}
+
+/// Current and highest depth seen of one buffer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Depth {
+    /// Depth at the last tick.
+    pub current: usize,
+    /// Highest depth seen at any tick.
+    pub peak: usize,
+}
+
+impl Depth {
+    /// `current / peak` in `[0, 1]`, `0` before anything was buffered.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss, reason = "a buffer depth fits f64 closely enough")]
+    pub fn ratio(self) -> f64 {
+        if self.peak == 0 { 0.0 } else { self.current as f64 / self.peak as f64 }
+    }
+
+    fn set(&mut self, depth: usize) {
+        self.current = depth;
+        self.peak = self.peak.max(depth);
+    }
+}
+
+/// One entry of the alarm pane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlarmEntry {
+    /// 1-based position among all alarms of the run.
+    pub seq: u64,
+    /// Flagged transaction.
+    pub id: TransactionId,
+    /// Its amount; `None` when the delivery failed (the event carries only the id).
+    pub amount: Option<f64>,
+    /// Whether the alarm was delivered.
+    pub delivered: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Dashboard
+// ---------------------------------------------------------------------------
+
+/// Everything the TUI displays, built from events and depth samples.
+#[derive(Debug, Clone, Default)]
+pub struct Dashboard {
+    producer: Throughput,
+    consumer: Throughput,
+    logger: Throughput,
+    flagged: u64,
+    alarms: u64,
+    alarms_failed: u64,
+    recent: VecDeque<AlarmEntry>,
+    buffer1: Depth,
+    buffer2: Depth,
+    stopped: Vec<(Stage, String)>,
+    lagged: u64,
+}
+
+impl Dashboard {
+    /// Empty dashboard: no event, no tick.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold one event into the counts.
+    pub fn apply(&mut self, event: &PipelineEvent) {
+        match event {
+            PipelineEvent::BatchProduced { size } => self.producer.add(*size),
+            PipelineEvent::BatchInferred { size, flagged, .. } => {
+                self.consumer.add(*size);
+                self.flagged += *flagged as u64;
+            }
+            PipelineEvent::BatchDiscarded { .. } => {}
+            PipelineEvent::AlarmTriggered { id, amount } => self.push_alarm(*id, Some(*amount)),
+            PipelineEvent::AlarmFailed { id } => {
+                self.alarms_failed += 1;
+                self.push_alarm(*id, None);
+            }
+            PipelineEvent::BatchPersisted { size } => self.logger.add(*size),
+            PipelineEvent::StageStopped { stage, reason } => {
+                self.stopped.push((*stage, reason.clone()));
+            }
+        }
+    }
+
+    /// Account for `skipped` events the receiver lagged behind and lost.
+    pub fn lagged(&mut self, skipped: u64) {
+        self.lagged += skipped;
+    }
+
+    /// Close a refresh period of `elapsed`: one tx/s sample per stage, and
+    /// the buffer depths sampled now.
+    pub fn tick(&mut self, elapsed: Duration, buffer1: usize, buffer2: usize) {
+        for throughput in [&mut self.producer, &mut self.consumer, &mut self.logger] {
+            throughput.tick(elapsed);
+        }
+        self.buffer1.set(buffer1);
+        self.buffer2.set(buffer2);
+    }
+
+    /// tx/s samples of `stage`, oldest first.
+    #[must_use]
+    pub fn rates(&self, stage: Stage) -> &VecDeque<u64> {
+        &self.throughput(stage).history
+    }
+
+    /// Last tx/s sample of `stage`, `0` before the first tick.
+    #[must_use]
+    pub fn rate(&self, stage: Stage) -> u64 {
+        self.rates(stage).back().copied().unwrap_or(0)
+    }
+
+    /// Transactions `stage` handled since the start.
+    #[must_use]
+    pub fn total(&self, stage: Stage) -> u64 {
+        self.throughput(stage).total
+    }
+
+    /// Share of inferred transactions predicted fraudulent, `None` before any.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss, reason = "transaction counts fit f64 closely enough")]
+    pub fn fraud_rate(&self) -> Option<f64> {
+        let inferred = self.consumer.total;
+        (inferred > 0).then(|| self.flagged as f64 / inferred as f64)
+    }
+
+    /// Transactions predicted fraudulent since the start.
+    #[must_use]
+    pub fn flagged(&self) -> u64 {
+        self.flagged
+    }
+
+    /// Alarms seen since the start, delivered or not.
+    #[must_use]
+    pub fn alarms(&self) -> u64 {
+        self.alarms
+    }
+
+    /// Alarms whose delivery failed since the start.
+    #[must_use]
+    pub fn alarms_failed(&self) -> u64 {
+        self.alarms_failed
+    }
+
+    /// The last [`RECENT_ALARMS`] alarms, newest first.
+    pub fn recent_alarms(&self) -> impl Iterator<Item = &AlarmEntry> {
+        self.recent.iter()
+    }
+
+    /// Depth of Buffer1 at the last tick.
+    #[must_use]
+    pub fn buffer1(&self) -> Depth {
+        self.buffer1
+    }
+
+    /// Depth of Buffer2 at the last tick.
+    #[must_use]
+    pub fn buffer2(&self) -> Depth {
+        self.buffer2
+    }
+
+    /// Why `stage` stopped, if it did.
+    #[must_use]
+    pub fn stopped(&self, stage: Stage) -> Option<&str> {
+        self.stopped.iter().find(|(s, _)| *s == stage).map(|(_, reason)| reason.as_str())
+    }
+
+    /// Events lost because the receiver lagged.
+    #[must_use]
+    pub fn lost_events(&self) -> u64 {
+        self.lagged
+    }
+
+    fn throughput(&self, stage: Stage) -> &Throughput {
+        match stage {
+            Stage::Producer => &self.producer,
+            Stage::Consumer => &self.consumer,
+            Stage::Logger => &self.logger,
+        }
+    }
+
+    fn push_alarm(&mut self, id: TransactionId, amount: Option<f64>) {
+        self.alarms += 1;
+        if self.recent.len() == RECENT_ALARMS {
+            self.recent.pop_back();
+        }
+        let delivered = amount.is_some();
+        self.recent.push_front(AlarmEntry { seq: self.alarms, id, amount, delivered });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(100);
+
+    fn produced(size: usize) -> PipelineEvent {
+        PipelineEvent::BatchProduced { size }
+    }
+
+    fn inferred(size: usize, flagged: usize) -> PipelineEvent {
+        PipelineEvent::BatchInferred { size, flagged, version: "v4".to_owned() }
+    }
+
+    fn persisted(size: usize) -> PipelineEvent {
+        PipelineEvent::BatchPersisted { size }
+    }
+
+    fn triggered(amount: f64) -> PipelineEvent {
+        PipelineEvent::AlarmTriggered { id: TransactionId::new_v4(), amount }
+    }
+
+    fn apply_all(dashboard: &mut Dashboard, events: &[PipelineEvent]) {
+        for event in events {
+            dashboard.apply(event);
+        }
+    }
+
+    #[test]
+    #[expect(clippy::float_cmp, reason = "exact value: nothing buffered yet")]
+    fn new_dashboard_is_empty() {
+        let dashboard = Dashboard::new();
+
+        assert_eq!(dashboard.rate(Stage::Producer), 0);
+        assert!(dashboard.rates(Stage::Logger).is_empty());
+        assert_eq!(dashboard.fraud_rate(), None);
+        assert_eq!(dashboard.recent_alarms().count(), 0);
+        assert_eq!(dashboard.buffer1().ratio(), 0.0);
+    }
+
+    #[test]
+    fn tick_turns_batch_sizes_into_per_stage_rates() {
+        let mut dashboard = Dashboard::new();
+
+        apply_all(&mut dashboard, &[produced(10), produced(20), inferred(10, 0), persisted(5)]);
+        dashboard.tick(TICK, 0, 0);
+        apply_all(&mut dashboard, &[produced(4)]);
+        dashboard.tick(TICK, 0, 0);
+
+        // 30 transactions in 100 ms, then 4: 300 then 40 tx/s.
+        assert_eq!(dashboard.rates(Stage::Producer), &[300, 40]);
+        assert_eq!(dashboard.rates(Stage::Consumer), &[100, 0]);
+        assert_eq!(dashboard.rate(Stage::Logger), 0);
+        assert_eq!(dashboard.total(Stage::Producer), 34);
+        assert_eq!(dashboard.total(Stage::Logger), 5);
+    }
+
+    #[test]
+    fn rate_history_is_bounded() {
+        let mut dashboard = Dashboard::new();
+
+        for i in 0..RATE_HISTORY + 10 {
+            dashboard.apply(&produced(i));
+            dashboard.tick(Duration::from_secs(1), 0, 0);
+        }
+
+        let rates = dashboard.rates(Stage::Producer);
+        assert_eq!(rates.len(), RATE_HISTORY);
+        assert_eq!(rates.front(), Some(&10), "the 10 oldest samples are dropped");
+        assert_eq!(dashboard.rate(Stage::Producer), (RATE_HISTORY + 9) as u64);
+    }
+
+    #[test]
+    fn fraud_rate_is_cumulative() {
+        let mut dashboard = Dashboard::new();
+
+        apply_all(&mut dashboard, &[inferred(100, 4), inferred(100, 0)]);
+        dashboard.tick(TICK, 0, 0);
+        apply_all(&mut dashboard, &[inferred(200, 8)]);
+
+        assert_eq!(dashboard.flagged(), 12);
+        assert_eq!(dashboard.fraud_rate(), Some(0.03));
+    }
+
+    #[test]
+    fn recent_alarms_keep_the_newest_first() {
+        let mut dashboard = Dashboard::new();
+        let failed = TransactionId::new_v4();
+
+        // RECENT_ALARMS + 4 delivered alarms, amounts 0.0 to 23.0.
+        for amount in 0..24_u32 {
+            dashboard.apply(&triggered(f64::from(amount)));
+        }
+        dashboard.apply(&PipelineEvent::AlarmFailed { id: failed });
+
+        let recent: Vec<_> = dashboard.recent_alarms().collect();
+        assert_eq!(recent.len(), RECENT_ALARMS);
+        assert_eq!(
+            *recent[0],
+            AlarmEntry { seq: 25, id: failed, amount: None, delivered: false }
+        );
+        assert_eq!(recent[1].amount, Some(23.0));
+        assert_eq!(recent[RECENT_ALARMS - 1].seq, 6);
+        assert_eq!((dashboard.alarms(), dashboard.alarms_failed()), (25, 1));
+    }
+
+    #[test]
+    #[expect(clippy::float_cmp, reason = "10 / 40 is exact in f64")]
+    fn depths_track_the_peak() {
+        let mut dashboard = Dashboard::new();
+
+        dashboard.tick(TICK, 40, 10);
+        dashboard.tick(TICK, 10, 0);
+
+        assert_eq!(dashboard.buffer1(), Depth { current: 10, peak: 40 });
+        assert_eq!(dashboard.buffer1().ratio(), 0.25);
+        assert_eq!(dashboard.buffer2(), Depth { current: 0, peak: 10 });
+    }
+
+    #[test]
+    fn stopped_stages_and_lost_events_are_reported() {
+        let mut dashboard = Dashboard::new();
+
+        dashboard.apply(&PipelineEvent::StageStopped {
+            stage: Stage::Producer,
+            reason: "cancelled".to_owned(),
+        });
+        dashboard.lagged(7);
+
+        assert_eq!(dashboard.stopped(Stage::Producer), Some("cancelled"));
+        assert_eq!(dashboard.stopped(Stage::Logger), None);
+        assert_eq!(dashboard.lost_events(), 7);
+    }
+}
@@ -0,0 +1,350 @@
+// Rust guideline compliant 2026-02-27
+
+//! Exit codes and machine-readable failure output of the pipeline binaries.
+//!
+//! [`classify`] maps the error a binary stops on to a [`FailureKind`] and the
+//! component that raised it, from the typed errors in its source chain:
+//!
+//! | kind      | exit code | raised by                                              |
+//! |-----------|-----------|--------------------------------------------------------|
+//! | `runtime` | 1         | a stage failing while the pipeline runs                |
+//! | `config`  | 2         | a stage builder (`InvalidConfig`) or a bad argument    |
+//! | `startup` | 3         | opening storage, preloading ids, loading the model     |
+//!
+//! Errors without a typed cause (argument parsing, `anyhow::bail!`) are
+//! tagged where they are raised with a [`Tagged`] context. With
+//! `--error-format json` the failure is written to stderr as one
+//! `{"kind": ..., "component": ..., "message": ...}` line instead of text.
+//!
+//! `fraud_detection_sqlite` also exits with 3 after spilling a storage outage
+//! to disk; see its `EXIT_STORAGE_UNAVAILABLE`.
+
+use std::fmt;
+use std::process::ExitCode;
+
+use consumer::ConsumerError;
+use domain::{ConfigError, StorageError};
+use logger::LoggerError;
+use producer::ProducerError;
+use reviewer::ReviewerError;
+
+// ---------------------------------------------------------------------------
+// FailureKind
+// ---------------------------------------------------------------------------
+
+/// What kind of failure stopped a binary; selects its exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// A stage failed while the pipeline was running.
+    Runtime,
+    /// A configuration value or command-line argument is invalid.
+    Config,
+    /// A dependency (storage, model backend) could not be reached at startup.
+    Startup,
+}
+
+impl FailureKind {
+    /// Process exit code: 1, 2 or 3.
+    #[must_use]
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Runtime => 1,
+            Self::Config => 2,
+            Self::Startup => 3,
+        }
+    }
+
+    /// Name used in the JSON output.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Runtime => "runtime",
+            Self::Config => "config",
+            Self::Startup => "startup",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tagged
+// ---------------------------------------------------------------------------
+
+/// `anyhow` context giving an untyped error its kind and component.
+///
+/// Displays as `message`, like a plain string context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tagged {
+    /// Kind of the wrapped failure.
+    pub kind: FailureKind,
+    /// Component reported in the JSON output, e.g. `"model"`.
+    pub component: &'static str,
+    /// Context message.
+    pub message: &'static str,
+}
+
+impl Tagged {
+    /// Context for an invalid argument or setting of `component`.
+    #[must_use]
+    pub fn config(component: &'static str, message: &'static str) -> Self {
+        Self { kind: FailureKind::Config, component, message }
+    }
+
+    /// Context for a startup or connectivity failure of `component`.
+    #[must_use]
+    pub fn startup(component: &'static str, message: &'static str) -> Self {
+        Self { kind: FailureKind::Startup, component, message }
+    }
+}
+
+impl fmt::Display for Tagged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// classify
+// ---------------------------------------------------------------------------
+
+/// A classified failure, as reported on stderr.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// Kind of failure; selects the exit code.
+    pub kind: FailureKind,
+    /// Component that failed, e.g. `"producer"` or `"storage"`.
+    pub component: &'static str,
+    /// The whole error chain, outermost first.
+    pub message: String,
+}
+
+/// Classify `error` from its [`Tagged`] context, else from the outermost
+/// typed error of its chain; anything else is a `runtime` failure of `main`.
+#[must_use]
+pub fn classify(error: &anyhow::Error) -> Failure {
+    let (kind, component) = match error.downcast_ref::<Tagged>() {
+        Some(tagged) => (tagged.kind, tagged.component),
+        None => error
+            .chain()
+            .find_map(classify_typed)
+            .unwrap_or((FailureKind::Runtime, "main")),
+    };
+    Failure { kind, component, message: format!("{error:#}") }
+}
+
+/// Kind and component of `error` when it is one of the pipeline's error types.
+fn classify_typed(
+    error: &(dyn std::error::Error + 'static),
+) -> Option<(FailureKind, &'static str)> {
+    use FailureKind::{Config, Runtime, Startup};
+
+    if let Some(e) = error.downcast_ref::<ProducerError>() {
+        let kind = if matches!(e, ProducerError::InvalidConfig(_)) { Config } else { Runtime };
+        return Some((kind, "producer"));
+    }
+    if let Some(e) = error.downcast_ref::<ConsumerError>() {
+        let kind = if matches!(e, ConsumerError::InvalidConfig(_)) { Config } else { Runtime };
+        return Some((kind, "consumer"));
+    }
+    if let Some(e) = error.downcast_ref::<LoggerError>() {
+        let kind = match e {
+            LoggerError::InvalidConfig(_) => Config,
+            LoggerError::Preload(_) => Startup,
+            _ => Runtime,
+        };
+        return Some((kind, "logger"));
+    }
+    if let Some(e) = error.downcast_ref::<ReviewerError>() {
+        let kind = if matches!(e, ReviewerError::InvalidConfig(_)) { Config } else { Runtime };
+        return Some((kind, "reviewer"));
+    }
+    if error.is::<ConfigError>() {
+        return Some((Config, "config"));
+    }
+    if error.is::<sqlx::Error>() {
+        return Some((Startup, "storage"));
+    }
+    if error.is::<StorageError>() {
+        return Some((Runtime, "storage"));
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------
+// ErrorFormat
+// ---------------------------------------------------------------------------
+
+/// How a failure is written to stderr, set by `--error-format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// `Error: ...` with the source chain, as `anyhow` prints it (default).
+    #[default]
+    Text,
+    /// One JSON object per failure.
+    Json,
+}
+
+impl ErrorFormat {
+    /// Parse `--error-format text|json` (or `--error-format=...`) from `args`
+    /// (program name excluded). Other arguments are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `config` error if the value is missing or unknown.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.into_iter();
+        let mut format = Self::default();
+        while let Some(arg) = args.next() {
+            let value = match arg.strip_prefix("--error-format") {
+                Some("") => args.next(),
+                Some(rest) => rest.strip_prefix('=').map(str::to_owned),
+                None => continue,
+            };
+            format = match value.as_deref() {
+                Some("text") => Self::Text,
+                Some("json") => Self::Json,
+                _ => {
+                    return Err(anyhow::anyhow!("expected `text` or `json`, got {value:?}")
+                        .context(Tagged::config("cli", "invalid --error-format")));
+                }
+            };
+        }
+        Ok(format)
+    }
+
+    /// Render `failure` for stderr, without a trailing newline.
+    #[must_use]
+    pub fn render(self, failure: &Failure, error: &anyhow::Error) -> String {
+        match self {
+            Self::Text => format!("Error: {error:?}"),
+            Self::Json => serde_json::json!({
+                "kind": failure.kind.as_str(),
+                "component": failure.component,
+                "message": failure.message,
+            })
+            .to_string(),
+        }
+    }
+}
+
+/// Classify `error`, write it to stderr in `format` and return its exit code.
+#[must_use]
+pub fn report(error: &anyhow::Error, format: ErrorFormat) -> ExitCode {
+    let failure = classify(error);
+    eprintln!("{}", format.render(&failure, error));
+    ExitCode::from(failure.kind.exit_code())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorFormat, Failure, FailureKind, Tagged, classify};
+    use anyhow::Context as _;
+    use consumer::ConsumerError;
+    use domain::{ConfigError, ModelizerError, ReadError, StorageError, WriteError};
+    use logger::LoggerError;
+    use producer::ProducerError;
+    use reviewer::ReviewerError;
+
+    fn config_error() -> ConfigError {
+        ConfigError::new("n1_max", 0, "must be >= 1")
+    }
+
+    fn kind_and_component(error: &anyhow::Error) -> (FailureKind, &'static str) {
+        let Failure { kind, component, .. } = classify(error);
+        (kind, component)
+    }
+
+    // EX-T01: every stage's InvalidConfig is a config failure of that stage.
+    #[test]
+    fn invalid_configs_exit_with_2() {
+        let cases = [
+            (anyhow::Error::new(ProducerError::InvalidConfig(config_error())), "producer"),
+            (anyhow::Error::new(ConsumerError::InvalidConfig(config_error())), "consumer"),
+            (anyhow::Error::new(LoggerError::InvalidConfig(config_error())), "logger"),
+            (anyhow::Error::new(ReviewerError::InvalidConfig(config_error())), "reviewer"),
+            (anyhow::Error::new(config_error()), "config"),
+        ];
+        for (error, component) in cases {
+            let error = error.context("failed to build config");
+            assert_eq!(kind_and_component(&error), (FailureKind::Config, component));
+            assert_eq!(FailureKind::Config.exit_code(), 2);
+        }
+    }
+
+    // EX-T02: errors raised by running stages are runtime failures.
+    #[test]
+    fn stage_failures_exit_with_1() {
+        let inference = ModelizerError::InferenceFailed { reason: "x".to_owned() };
+        let cases = [
+            (anyhow::Error::new(ProducerError::Buffer { source: WriteError::Closed }), "producer"),
+            (anyhow::Error::new(ConsumerError::Read(ReadError::Closed)), "consumer"),
+            (anyhow::Error::new(ConsumerError::Inference(inference)), "consumer"),
+            (anyhow::Error::new(ConsumerError::Write(WriteError::Closed)), "consumer"),
+            (anyhow::Error::new(ConsumerError::DeadLetter(StorageError::Unavailable)), "consumer"),
+            (anyhow::Error::new(LoggerError::Read(ReadError::Closed)), "logger"),
+            (anyhow::Error::new(LoggerError::Write(StorageError::Unavailable)), "logger"),
+            (anyhow::Error::new(ReviewerError::Read(StorageError::Unavailable)), "reviewer"),
+            (anyhow::Error::new(ReviewerError::Write(StorageError::Unavailable)), "reviewer"),
+            (anyhow::Error::new(StorageError::Unavailable), "storage"),
+            (anyhow::anyhow!("shutdown grace period expired"), "main"),
+        ];
+        for (error, component) in cases {
+            let error = error.context("stage failed");
+            assert_eq!(kind_and_component(&error), (FailureKind::Runtime, component), "{error:#}");
+        }
+        assert_eq!(FailureKind::Runtime.exit_code(), 1);
+    }
+
+    // EX-T03: storage that cannot be opened or preloaded is a startup failure.
+    #[test]
+    fn startup_failures_exit_with_3() {
+        let open = anyhow::Error::new(sqlx::Error::PoolClosed).context("failed to open storage");
+        assert_eq!(kind_and_component(&open), (FailureKind::Startup, "storage"));
+        let preload = anyhow::Error::new(LoggerError::Preload(StorageError::Unavailable));
+        assert_eq!(kind_and_component(&preload), (FailureKind::Startup, "logger"));
+        let model: anyhow::Result<()> = Err(anyhow::anyhow!("connection refused"));
+        let model = model.context(Tagged::startup("model", "failed to load the model"));
+        assert_eq!(kind_and_component(&model.unwrap_err()), (FailureKind::Startup, "model"));
+        assert_eq!(FailureKind::Startup.exit_code(), 3);
+    }
+
+    // EX-T04: a Tagged context wins over the typed errors below it.
+    #[test]
+    fn tagged_context_takes_precedence() {
+        let error = anyhow::Error::new(StorageError::Unavailable)
+            .context(Tagged::startup("recover", "failed to recover spill file"))
+            .context("outer");
+        let failure = classify(&error);
+        assert_eq!((failure.kind, failure.component), (FailureKind::Startup, "recover"));
+        assert_eq!(failure.message, "outer: failed to recover spill file: storage unavailable");
+    }
+
+    #[test]
+    fn error_format_parses_and_renders_json() {
+        let parse = |list: &[&str]| ErrorFormat::from_args(list.iter().map(|&a| a.to_owned()));
+        assert_eq!(parse(&[]).unwrap(), ErrorFormat::Text);
+        assert_eq!(parse(&["--check", "--error-format", "json"]).unwrap(), ErrorFormat::Json);
+        assert_eq!(parse(&["--error-format=text"]).unwrap(), ErrorFormat::Text);
+        for bad in [&["--error-format"][..], &["--error-format", "yaml"]] {
+            let error = parse(bad).unwrap_err();
+            assert_eq!(kind_and_component(&error), (FailureKind::Config, "cli"));
+        }
+
+        let error = anyhow::Error::new(ProducerError::InvalidConfig(config_error()))
+            .context("failed to build producer config");
+        let json = ErrorFormat::Json.render(&classify(&error), &error);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["kind"], "config");
+        assert_eq!(value["component"], "producer");
+        assert_eq!(
+            value["message"],
+            "failed to build producer config: invalid producer configuration: \
+             n1_max must be >= 1 (got 0)"
+        );
+        let text = ErrorFormat::Text.render(&classify(&error), &error);
+        assert!(text.starts_with("Error: failed to build producer config"), "{text}");
+    }
+}
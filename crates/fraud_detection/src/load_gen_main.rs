@@ -0,0 +1,464 @@
+// Rust guideline compliant 2026-02-27
+
+//! Standalone load generator: the Producer alone, writing to an external sink.
+//!
+//! Runs only the Producer for a fixed duration and writes its batches to one
+//! `Buffer1` adapter, for load-testing downstream systems without the rest of
+//! the pipeline. Sinks:
+//!
+//! - `stdout`: JSON Lines on stdout ([`JsonlBuffer1`])
+//! - `file:PATH`: JSON Lines into `PATH`, truncated first ([`JsonlBuffer1`])
+//! - `http://host[:port]/path`: one POST per batch ([`HttpBuffer1`])
+//!
+//! A final report (batches, transactions, elapsed, achieved tps) goes to
+//! stderr, as do logs, so stdout carries only transactions.
+//!
+//! # Usage
+//!
+//! ```text
+//! cargo run --bin fraud_load_gen -- --profile steady --sink file:load.jsonl
+//! cargo run --bin fraud_load_gen -- --tps 500 --batch-size 50 --duration-secs 30 \
+//!     --amounts exp:80 --fraud-rate 0.02 --sink http://127.0.0.1:8080/ingest
+//! ```
+//!
+//! # Options
+//!
+//! | Option              | Meaning                                               |
+//! |---------------------|-------------------------------------------------------|
+//! | `--profile NAME`    | `smoke` (default), `steady` or `burst`: base values   |
+//! | `--tps N`           | Target transactions per second                        |
+//! | `--interval-ms N`   | Fixed delay between batches (instead of `--tps`)      |
+//! | `--batch-size N`    | Maximum batch size (`n1_max`)                         |
+//! | `--duration-secs N` | Run time                                              |
+//! | `--seed N`          | RNG seed for reproducible output                      |
+//! | `--amounts D`       | `uniform` or `exp:MEAN`                               |
+//! | `--fraud-rate R`    | Share of injected fraud-like transactions, in `[0, 1]`|
+//! | `--sink S`          | `stdout` (default), `file:PATH` or an `http://` URL   |
+//!
+//! Explicit options override the profile, whatever their position.
+
+// Load sink adapters into this binary's module tree only.
+// Same #[path] technique as bench_main.rs: the other binaries never use them.
+#[path = "adapters/http_buffer.rs"]
+mod http_buffer;
+#[path = "adapters/jsonl_buffer.rs"]
+mod jsonl_buffer;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use domain::Buffer1;
+use http_buffer::HttpBuffer1;
+use jsonl_buffer::JsonlBuffer1;
+use producer::{AmountDistribution, Producer, ProducerConfig, ProducerError, ProducerStats};
+
+/// `tracing` target of every load generator event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::PRODUCER;
+
+// ---------------------------------------------------------------------------
+// Arguments
+// ---------------------------------------------------------------------------
+
+/// Named starting points for the other options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Profile {
+    /// 100 tx/s, batches up to 10, 5 s: checks the sink is wired up.
+    Smoke,
+    /// 1 000 tx/s, batches up to 100, 60 s: sustained nominal load.
+    Steady,
+    /// 20 000 tx/s, batches up to 2 000, 30 s: short overload.
+    Burst,
+}
+
+impl Profile {
+    fn parse(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "smoke" => Ok(Self::Smoke),
+            "steady" => Ok(Self::Steady),
+            "burst" => Ok(Self::Burst),
+            _ => anyhow::bail!("unknown profile {name:?} (expected smoke, steady or burst)"),
+        }
+    }
+
+    /// Arguments before any explicit option is applied.
+    fn defaults(self) -> LoadGenArgs {
+        let (tps, batch_size, secs) = match self {
+            Self::Smoke => (100.0, 10, 5),
+            Self::Steady => (1_000.0, 100, 60),
+            Self::Burst => (20_000.0, 2_000, 30),
+        };
+        LoadGenArgs {
+            pace: Pace::Tps(tps),
+            batch_size,
+            duration: Duration::from_secs(secs),
+            seed: None,
+            amounts: AmountDistribution::Uniform,
+            fraud_rate: 0.0,
+            sink: Sink::Stdout,
+        }
+    }
+}
+
+/// How the delay between batches is chosen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pace {
+    /// Target throughput; converted to an interval from the mean batch size.
+    Tps(f64),
+    /// Fixed delay between batches.
+    Interval(Duration),
+}
+
+/// Where generated batches are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Sink {
+    Stdout,
+    File(PathBuf),
+    Http(String),
+}
+
+impl Sink {
+    fn parse(spec: &str) -> anyhow::Result<Self> {
+        if spec == "stdout" {
+            return Ok(Self::Stdout);
+        }
+        if let Some(path) = spec.strip_prefix("file:") {
+            anyhow::ensure!(!path.is_empty(), "--sink file: requires a path");
+            return Ok(Self::File(PathBuf::from(path)));
+        }
+        if spec.starts_with("http://") {
+            return Ok(Self::Http(spec.to_owned()));
+        }
+        anyhow::bail!("unknown sink {spec:?} (expected stdout, file:PATH or http://...)")
+    }
+}
+
+/// Parsed command line.
+#[derive(Debug, Clone, PartialEq)]
+struct LoadGenArgs {
+    pace: Pace,
+    batch_size: usize,
+    duration: Duration,
+    seed: Option<u64>,
+    amounts: AmountDistribution,
+    fraud_rate: f64,
+    sink: Sink,
+}
+
+impl LoadGenArgs {
+    /// Parse `args` (program name excluded): profile defaults, then overrides.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown option, a missing or malformed value,
+    /// or a non-positive `--tps`.
+    fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let args: Vec<String> = args.into_iter().collect();
+        anyhow::ensure!(args.len().is_multiple_of(2), "every option takes exactly one value");
+        let pairs: Vec<(&str, &str)> = args
+            .chunks(2)
+            .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+            .collect();
+
+        let profile = match pairs.iter().rfind(|(flag, _)| *flag == "--profile") {
+            Some((_, name)) => Profile::parse(name)?,
+            None => Profile::Smoke,
+        };
+        let mut parsed = profile.defaults();
+        for (flag, value) in pairs {
+            let context = || format!("invalid value {value:?} for {flag}");
+            match flag {
+                "--profile" => {}
+                "--tps" => {
+                    let tps: f64 = value.parse().with_context(context)?;
+                    anyhow::ensure!(tps.is_finite() && tps > 0.0, "--tps must be > 0");
+                    parsed.pace = Pace::Tps(tps);
+                }
+                "--interval-ms" => {
+                    let ms = value.parse().with_context(context)?;
+                    parsed.pace = Pace::Interval(Duration::from_millis(ms));
+                }
+                "--batch-size" => parsed.batch_size = value.parse().with_context(context)?,
+                "--duration-secs" => {
+                    parsed.duration = Duration::from_secs(value.parse().with_context(context)?);
+                }
+                "--seed" => parsed.seed = Some(value.parse().with_context(context)?),
+                "--amounts" => parsed.amounts = parse_amounts(value).with_context(context)?,
+                "--fraud-rate" => parsed.fraud_rate = value.parse().with_context(context)?,
+                "--sink" => parsed.sink = Sink::parse(value)?,
+                _ => anyhow::bail!("unknown option {flag:?}"),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Delay between batches. For `Tps`, batch sizes are uniform in
+    /// `[1, batch_size]`, so the mean batch is `(batch_size + 1) / 2`.
+    fn interval(&self) -> Duration {
+        match self.pace {
+            Pace::Interval(interval) => interval,
+            Pace::Tps(tps) => {
+                #[expect(
+                    clippy::cast_precision_loss,
+                    reason = "batch sizes fit in f64 mantissa"
+                )]
+                let mean_batch = f64::midpoint(self.batch_size as f64, 1.0);
+                Duration::from_secs_f64(mean_batch / tps)
+            }
+        }
+    }
+
+    /// Translate into a Producer configuration; the duration is enforced by
+    /// the caller, so `iterations` stays unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProducerError::InvalidConfig`] when the builder rejects a value.
+    fn producer_config(&self) -> Result<ProducerConfig, ProducerError> {
+        let mut builder = ProducerConfig::builder(self.batch_size)
+            .poll_interval1(self.interval())
+            .amount_distribution(self.amounts)
+            .fraud_rate(self.fraud_rate);
+        if let Some(seed) = self.seed {
+            builder = builder.seed(seed);
+        }
+        builder.build()
+    }
+}
+
+/// Parse `uniform` or `exp:MEAN`.
+fn parse_amounts(spec: &str) -> anyhow::Result<AmountDistribution> {
+    if spec == "uniform" {
+        return Ok(AmountDistribution::Uniform);
+    }
+    let Some(mean) = spec.strip_prefix("exp:") else {
+        anyhow::bail!("expected uniform or exp:MEAN");
+    };
+    Ok(AmountDistribution::Exponential {
+        mean: mean.parse()?,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Run + report
+// ---------------------------------------------------------------------------
+
+/// Run `producer` against `sink` until `duration` elapses.
+///
+/// # Errors
+///
+/// Returns the first sink error; the run stops there.
+async fn drive<B: Buffer1>(
+    producer: &Producer,
+    sink: &B,
+    duration: Duration,
+) -> Result<(), ProducerError> {
+    // No iteration limit: run() only returns early on a sink error.
+    match tokio::time::timeout(duration, producer.run(sink)).await {
+        Ok(Ok(reason)) => {
+            tracing::info!(target: TARGET, %reason, "load_gen.producer.stopped");
+            Ok(())
+        }
+        Ok(Err(e)) => Err(e),
+        Err(_elapsed) => Ok(()),
+    }
+}
+
+/// One-line summary: batches, transactions, elapsed and achieved tps.
+fn report(stats: &ProducerStats, elapsed: Duration) -> String {
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "transaction counts fit in f64 mantissa"
+    )]
+    let tps = stats.transactions as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+    format!(
+        "load_gen: {} batches, {} transactions ({} injected fraud) in {:.2} s -- {tps:.0} tx/s",
+        stats.batches,
+        stats.transactions,
+        stats.injected,
+        elapsed.as_secs_f64()
+    )
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    // Logs go to stderr: stdout may be the JSONL sink.
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args = LoadGenArgs::parse(std::env::args().skip(1))?;
+    let producer = Producer::new(
+        args.producer_config()
+            .context("failed to build producer config")?,
+    );
+    tracing::info!(target: TARGET, ?args, interval = ?args.interval(), "load_gen.start");
+
+    let start = Instant::now();
+    let result = match &args.sink {
+        Sink::Stdout => {
+            let sink = JsonlBuffer1::new(std::io::stdout().lock());
+            drive(&producer, &sink, args.duration).await
+        }
+        Sink::File(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            drive(
+                &producer,
+                &JsonlBuffer1::new(BufWriter::new(file)),
+                args.duration,
+            )
+            .await
+        }
+        Sink::Http(url) => drive(&producer, &HttpBuffer1::new(url)?, args.duration).await,
+    };
+    eprintln!("{}", report(&producer.stats(), start.elapsed()));
+    result.context("load generation stopped early")
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadGenArgs, Pace, Profile, Sink, report};
+    use producer::{AmountDistribution, ProducerError, ProducerStats};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn parse(args: &[&str]) -> anyhow::Result<LoadGenArgs> {
+        LoadGenArgs::parse(args.iter().map(|&a| a.to_owned()))
+    }
+
+    // ------------------------------------------------------------------
+    // LG-T01: argument parsing
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn no_args_is_smoke_profile_on_stdout() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args, Profile::Smoke.defaults());
+        assert_eq!(args.sink, Sink::Stdout);
+    }
+
+    #[test]
+    fn explicit_options_override_profile_in_any_order() {
+        let args = parse(&["--batch-size", "7", "--profile", "burst", "--seed", "9"]).unwrap();
+        assert_eq!(args.batch_size, 7);
+        assert_eq!(args.seed, Some(9));
+        assert_eq!(args.duration, Profile::Burst.defaults().duration);
+        assert_eq!(args.pace, Profile::Burst.defaults().pace);
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let args = parse(&[
+            "--interval-ms",
+            "250",
+            "--duration-secs",
+            "3",
+            "--amounts",
+            "exp:80",
+            "--fraud-rate",
+            "0.25",
+            "--sink",
+            "file:out.jsonl",
+        ])
+        .unwrap();
+        assert_eq!(args.pace, Pace::Interval(Duration::from_millis(250)));
+        assert_eq!(args.duration, Duration::from_secs(3));
+        assert_eq!(args.amounts, AmountDistribution::Exponential { mean: 80.0 });
+        assert!((args.fraud_rate - 0.25).abs() < f64::EPSILON);
+        assert_eq!(args.sink, Sink::File(PathBuf::from("out.jsonl")));
+
+        let args = parse(&["--sink", "http://127.0.0.1:8080/in"]).unwrap();
+        assert_eq!(args.sink, Sink::Http("http://127.0.0.1:8080/in".to_owned()));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        for bad in [
+            &["--tps"][..],
+            &["--tps", "0"],
+            &["--tps", "fast"],
+            &["--profile", "huge"],
+            &["--amounts", "normal"],
+            &["--sink", "ftp://host"],
+            &["--sink", "file:"],
+            &["--verbose", "1"],
+        ] {
+            if let Ok(args) = parse(bad) {
+                panic!("{bad:?} must be rejected, got {args:?}");
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // LG-T02: translation to ProducerConfig
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn tps_translates_to_interval_from_mean_batch() {
+        // batch_size 99 -> mean batch 50; 500 tx/s -> one batch every 100 ms.
+        let args = parse(&["--tps", "500", "--batch-size", "99", "--seed", "4"]).unwrap();
+        let config = args.producer_config().unwrap();
+        assert_eq!(config.n1_max, 99);
+        assert_eq!(config.poll_interval1, Duration::from_millis(100));
+        assert_eq!(config.seed, Some(4));
+        assert_eq!(config.iterations, None);
+    }
+
+    #[test]
+    fn amounts_and_fraud_rate_reach_the_config() {
+        let args = parse(&[
+            "--interval-ms",
+            "5",
+            "--amounts",
+            "exp:20",
+            "--fraud-rate",
+            "1",
+        ]);
+        let config = args.unwrap().producer_config().unwrap();
+        assert_eq!(config.poll_interval1, Duration::from_millis(5));
+        assert_eq!(
+            config.amount_distribution,
+            AmountDistribution::Exponential { mean: 20.0 }
+        );
+        assert!((config.fraud_rate - 1.0).abs() < f64::EPSILON);
+        assert_eq!(config.seed, None);
+    }
+
+    #[test]
+    fn invalid_values_surface_as_config_errors() {
+        let args = parse(&["--fraud-rate", "2"]).unwrap();
+        let err = args.producer_config().unwrap_err();
+        assert!(matches!(err, ProducerError::InvalidConfig(ref e) if e.field == "fraud_rate"));
+        let args = parse(&["--batch-size", "0"]).unwrap();
+        let err = args.producer_config().unwrap_err();
+        assert!(matches!(err, ProducerError::InvalidConfig(ref e) if e.field == "n1_max"));
+    }
+
+    // ------------------------------------------------------------------
+    // LG-T03: report
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn report_shows_counts_and_achieved_tps() {
+        let stats = ProducerStats {
+            batches: 4,
+            transactions: 300,
+            duplicates: 0,
+            injected: 6,
+            ..ProducerStats::default()
+        };
+        let line = report(&stats, Duration::from_secs(2));
+        assert_eq!(
+            line,
+            "load_gen: 4 batches, 300 transactions (6 injected fraud) in 2.00 s -- 150 tx/s"
+        );
+    }
+}
@@ -3,8 +3,8 @@
 //! Fraud-detection pipeline entry point.
 //!
 //! Wires all pipeline components (Producer, Consumer, Modelizer, Logger) to their
-//! concurrent-buffer, storage, and DEMO adapters and runs a proof-of-concept
-//! concurrent end-to-end pipeline.
+//! concurrent-buffer, storage, and model adapters (DEMO unless `--model` picks
+//! another) and runs a proof-of-concept concurrent end-to-end pipeline.
 //!
 //! # Usage
 //!
@@ -14,109 +14,611 @@
 //!
 //! # Also show per-transaction debug output
 //! $env:RUST_LOG='debug'; cargo run; Remove-Item env:RUST_LOG
+//!
+//! # Debug one stage, warnings only elsewhere. Stage targets: pipeline::producer,
+//! # pipeline::consumer, pipeline::modelizer, pipeline::logger, pipeline::buffer,
+//! # plus pipeline::storage, pipeline::alarm and pipeline::orchestrator;
+//! # pipeline=debug covers them all
+//! $env:RUST_LOG='pipeline::consumer=debug,warn'; cargo run; Remove-Item env:RUST_LOG
+//!
+//! # Validate the configuration and adapters, then exit (non-zero on failure)
+//! cargo run -- --check
+//!
+//! # Start the stages from a preset: demo (default, readable logs), stress
+//! # (full batches, 1 ms polls) or low-latency (small batches, short polls)
+//! cargo run -- --profile stress
+//!
+//! # Pick the model: demo[:seed] (default), bench, onnx:<path> or http:<url>
+//! cargo run -- --model demo:42
+//!
+//! # Also export the finished run to fraud_detection.parquet
+//! cargo run --features arrow
+//!
+//! # Report a failure as one JSON line on stderr
+//! cargo run -- --error-format json
+//!
+//! # Reload intervals, batch size mode and alarm threshold from a file on edit
+//! cargo run -- --settings live.toml
+//!
+//! # Keep at most 10 000 transactions; when full, stop producing and exit
+//! # cleanly (the default, --on-storage-full fail, fails the run instead)
+//! cargo run -- --storage-capacity 10000 --on-storage-full stop
+//!
+//! # Simulate two banks, 70/30; the summary counts transactions per tenant
+//! cargo run -- --tenants bank-a:0.7,bank-b:0.3
+//!
+//! # Compare DEMO 4 with DEMO 3 over the same 10 000 transactions, then exit
+//! cargo run -- --ab-compare 10000 --model demo:7
+//!
+//! # Forward every alert from 10.00 up and 10% of lower-value ones (every
+//! # alert is forwarded by default)
+//! cargo run -- --alarm-sample-rate 0.1
 //! ```
+//!
+//! Exits with 1 on a runtime failure, 2 on an invalid configuration or
+//! argument and 3 when a dependency cannot be reached at startup; see the
+//! `exit` module.
 
+mod ab_compare;
 mod adapters;
+mod check;
+mod exit;
+mod model_backend;
+mod orchestrator;
+mod settings_file;
 
+use adapters::aggregating_storage::AggregatingStorage;
 use adapters::concurrent_buffer::ConcurrentBuffer;
 use adapters::concurrent_buffer2::ConcurrentBuffer2;
-use adapters::demo_model::DemoModel;
+use adapters::in_memory_quarantine::InMemoryQuarantine;
 use adapters::in_memory_storage::InMemoryStorage;
 use adapters::log_alarm::LogAlarm;
+use adapters::sampling_alarm::{self, MaybeSampled};
 use anyhow::Context as _;
+use check::{StageBuilders, run_checks};
+use exit::{ErrorFormat, Tagged};
 use consumer::{Consumer, ConsumerConfig};
 use logger::{Logger, LoggerConfig};
 use modelizer::Modelizer;
+use model_backend::{ModelBackend, ModelSpec};
+use domain::obs::target;
+use domain::{BufferDepth as _, Preset};
+use orchestrator::{
+    CapacityPolicy, LoggerStop, RestartPolicy, Shutdown, StageFailure, StageTask, Watchdog,
+    on_capacity, shutdown_gracefully, storage_full, supervise,
+};
 use producer::{Producer, ProducerConfig};
+use std::convert::Infallible;
+use std::process::ExitCode;
+use std::rc::Rc;
 use std::time::Duration;
+use tokio::task::JoinHandle;
 use tracing::Instrument as _;
 
-#[tokio::main(flavor = "current_thread")]
-async fn main() -> anyhow::Result<()> {
+/// How often the `--settings` file is checked for edits.
+const SETTINGS_POLL: Duration = Duration::from_secs(1);
+
+/// Time allowed after CTRL+C for the pipeline to drain both buffers.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// A stage with work pending and no batch for this long fails the run.
+const STALL_AFTER: Duration = Duration::from_secs(30);
+
+/// How often the watchdog looks at the buffers.
+const STALL_CHECK: Duration = Duration::from_secs(5);
+
+/// Stage events buffered for the watchdog before it lags.
+const EVENT_CAPACITY: usize = 1024;
+
+/// With the `arrow` feature, the finished run is exported here for notebooks.
+#[cfg(feature = "arrow")]
+const PARQUET_PATH: &str = "fraud_detection.parquet";
+
+/// Stage settings from `preset`, shared by the pipeline run and `--check`.
+fn stage_builders(preset: Preset) -> StageBuilders {
+    StageBuilders {
+        // Infinite mode by default; add .iterations(10) for a finite demo run.
+        producer: ProducerConfig::from_preset(preset),
+        // Transactions the model cannot score are kept aside, not lost.
+        consumer: ConsumerConfig::from_preset(preset).quarantine(InMemoryQuarantine::new()),
+        // Failed writes stay retained, so the Logger restarted by supervise
+        // retries them instead of dropping the batch; past 1 000 it stops
+        // reading and the backlog waits in Buffer2.
+        logger: LoggerConfig::from_preset(preset).split_on_capacity(true).max_retained(1_000),
+    }
+}
+
+fn main() -> ExitCode {
     // Initialize the tracing subscriber before any async work.
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    // -- Producer: infinite mode by default; press CTRL+C to stop --
-    // Set .iterations(10) here for a finite demo run.
-    let producer_config = ProducerConfig::builder(100)
-        // 500 ms between batches keeps logs readable in real time.
-        .poll_interval1(Duration::from_millis(500))
-        // .iterations(10)
-        .build()
-        .context("failed to build producer config")?;
+    let format = match ErrorFormat::from_args(std::env::args().skip(1)) {
+        Ok(format) => format,
+        Err(e) => return exit::report(&e, ErrorFormat::Text),
+    };
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => exit::report(&e, format),
+    }
+}
 
-    // ConcurrentBuffer: shared by Producer (write) and Consumer (read).
-    let buffer1 = ConcurrentBuffer::new();
-    let producer = Producer::new(producer_config);
+#[tokio::main(flavor = "current_thread")]
+#[expect(clippy::too_many_lines, reason = "wires every stage of the binary in one place")]
+async fn run() -> anyhow::Result<()> {
+    let model_spec = ModelSpec::from_args(std::env::args().skip(1))
+        .context(Tagged::config("model", "invalid --model"))?;
+    if std::env::args().any(|arg| arg == "--check") {
+        return run_check(&model_spec).await;
+    }
+    let ab = ab_compare::AbArgs::from_args(std::env::args().skip(1))
+        .context(Tagged::config("cli", "invalid --ab-compare arguments"))?;
+    if let Some(ab) = ab {
+        return ab_compare::run(&model_spec, &ab).await;
+    }
+
+    let (mut producer_config, mut consumer_config, mut logger_config, settings_watcher) =
+        stage_configs()?;
+    // Batch events of every stage, followed by the stall watchdog.
+    let (events, progress) = tokio::sync::broadcast::channel(EVENT_CAPACITY);
+    producer_config.events = Some(events.clone());
+    consumer_config.events = Some(events.clone());
+    logger_config.events = Some(events);
+    let (capacity, capacity_policy) = storage_limit()?;
 
-    // -- Consumer: drain Buffer1 -> Modelizer<DemoModel> -> Buffer2 --
-    let consumer_config = ConsumerConfig::builder(50)
-        // 25 ms ensures Consumer yields regularly so Producer gets CPU time.
-        .poll_interval2(Duration::from_millis(25))
-        .build()
-        .context("failed to build consumer config")?;
+    // -- Producer: infinite mode by default; press CTRL+C to stop --
+    // ConcurrentBuffer: shared by Producer (write) and Consumer (read).
+    // Latency tracing: queue delay percentiles in the summary.
+    let buffer1 = Rc::new(ConcurrentBuffer::with_latency_tracing(true));
+    let producer = Rc::new(Producer::new(producer_config));
 
+    // -- Consumer: drain Buffer1 -> Modelizer<ModelBackend> -> Buffer2 --
     // ConcurrentBuffer2: shared by Consumer (write) and Logger (read).
-    let buffer2 = ConcurrentBuffer2::new();
-    // DEMO model: OS-seeded RNG, starts at version N (version 4, ~4% fraud rate).
-    let model = DemoModel::new(None);
-    let modelizer = Modelizer::new(model);
-    let alarm = LogAlarm::new();
-    let consumer = Consumer::new(consumer_config);
+    let buffer2 = Rc::new(ConcurrentBuffer2::with_latency_tracing(true));
+    // --model, DEMO by default: OS-seeded RNG, version N (version 4, ~4% fraud rate).
+    let model = ModelBackend::from_spec(&model_spec)
+        .context(Tagged::startup("model", "failed to load the model"))?;
+    let modelizer = Rc::new(Modelizer::new(model));
+    // Every alert by default; --alarm-sample-rate samples those below 10.00.
+    let sample_rate = sampling_alarm::sample_rate_from_args(std::env::args().skip(1))
+        .context(Tagged::config("cli", "invalid --alarm-sample-rate"))?;
+    let alarm = Rc::new(MaybeSampled::new(LogAlarm::new(), 10.0, sample_rate, None));
+    let consumer = Rc::new(Consumer::new(consumer_config));
 
     // -- Logger: drain Buffer2 -> InMemoryStorage --
-    let logger_config = LoggerConfig::builder(10)
-        // 25 ms matches Consumer cadence.
-        .poll_interval3(Duration::from_millis(25))
-        .build()
-        .context("failed to build logger config")?;
-
-    // usize::MAX capacity: effectively unbounded for proof-of-concept.
-    let storage = InMemoryStorage::new(usize::MAX);
-    let logger = Logger::new(logger_config);
-
-    // Shutdown cascade: Consumer.run completes -> buffer2.close() -> Logger drains+stops.
-    // On CTRL+C, only buffer1.close() is needed; buffer2 cascade follows automatically.
-    let consumer_then_close = async {
-        let r = consumer.run(&buffer1, &modelizer, &alarm, &buffer2).await;
-        // Close buffer2 so Logger exits cleanly after draining (cascade shutdown).
-        buffer2.close();
-        r
+    // --storage-capacity, unbounded by default; --on-storage-full decides what a full one does.
+    // AggregatingStorage: per-minute total/flagged counts, read back via buckets().
+    let storage = Rc::new(AggregatingStorage::new(
+        InMemoryStorage::new(capacity),
+        Duration::from_secs(10),
+    ));
+    let logger = Rc::new(Logger::new(logger_config));
+
+    // Each stage runs as its own task (StageTask), so a panic is caught by
+    // its JoinHandle and fails the run instead of the process. The tasks are
+    // spawn_local: the stages are !Send.
+    let outcome = tokio::task::LocalSet::new()
+        .run_until(async {
+            // Retryable stage failures restart the stage after a backoff, inside
+            // its task; fatal ones end the task with the error.
+            let policy = RestartPolicy::default();
+            let (stage, b1) = (Rc::clone(&producer), Rc::clone(&buffer1));
+            let mut producer_task = StageTask::spawn("producer", move || {
+                let (stage, b1) = (Rc::clone(&stage), Rc::clone(&b1));
+                async move {
+                    let r = supervise("producer", policy, || stage.run(&*b1)).await;
+                    // Close buffer1 so Consumer exits cleanly after draining.
+                    b1.close();
+                    r
+                }
+                .instrument(tracing::info_span!(target: target::PRODUCER, "producer"))
+            });
+            // Shutdown cascade: Consumer.run completes -> buffer2.close() -> Logger
+            // drains+stops. On CTRL+C, only buffer1.close() is needed; buffer2
+            // cascade follows automatically.
+            let (stage, b1, model, alarm, b2) = (
+                Rc::clone(&consumer),
+                Rc::clone(&buffer1),
+                Rc::clone(&modelizer),
+                Rc::clone(&alarm),
+                Rc::clone(&buffer2),
+            );
+            let mut consumer_task = StageTask::spawn("consumer", move || {
+                let (stage, b1, model, alarm, b2) = (
+                    Rc::clone(&stage),
+                    Rc::clone(&b1),
+                    Rc::clone(&model),
+                    Rc::clone(&alarm),
+                    Rc::clone(&b2),
+                );
+                async move {
+                    let r = supervise("consumer", policy, || {
+                        stage.run(&*b1, &*model, &*alarm, &*b2)
+                    })
+                    .await;
+                    // Close buffer2 so Logger exits cleanly after draining (cascade shutdown).
+                    b2.close();
+                    r
+                }
+                .instrument(tracing::info_span!(target: target::CONSUMER, "consumer"))
+            });
+            let (stage, b2, sink) = (Rc::clone(&logger), Rc::clone(&buffer2), Rc::clone(&storage));
+            let mut logger_task = StageTask::spawn("logger", move || {
+                let (stage, b2, sink) = (Rc::clone(&stage), Rc::clone(&b2), Rc::clone(&sink));
+                async move { supervise("logger", policy, || stage.run(&*b2, &*sink)).await }
+                    .instrument(tracing::info_span!(target: target::LOGGER, "logger"))
+            });
+            // Under --on-storage-full stop, the Logger reports a full storage here.
+            let (full_tx, full_rx) = tokio::sync::oneshot::channel();
+
+            let stages = async {
+                // tokio::try_join! returns on the first error; dropping the
+                // other StageTasks aborts them (fatal error aborts the pipeline).
+                tokio::try_join!(
+                    async { producer_task.join().await.map_err(anyhow::Error::new) },
+                    async { consumer_task.join().await.map_err(anyhow::Error::new) },
+                    async {
+                        match logger_task.join().await {
+                            Ok(reason) => Ok(LoggerStop::Stopped(reason)),
+                            Err(StageFailure::Failed { source, .. }) => {
+                                on_capacity(capacity_policy, Err(source), full_tx)
+                                    .context("logger failed")
+                            }
+                            Err(failure) => Err(anyhow::Error::new(failure)),
+                        }
+                    },
+                )
+            };
+            // A deadlocked stage would otherwise hang the process without a word.
+            let watchdog = Watchdog { stall_after: STALL_AFTER, check_every: STALL_CHECK };
+            let pipeline = async {
+                tokio::select! {
+                    stops = stages => stops,
+                    stalled = watchdog.watch(progress, &*buffer1, &*buffer2) => {
+                        Err(anyhow::Error::new(stalled).context("pipeline stalled"))
+                    }
+                }
+            };
+
+            // CTRL+C or a full storage only closes buffer1; the pipeline future keeps
+            // running so the cascade drains buffer1 and buffer2 before the summary. Past
+            // SHUTDOWN_GRACE the remaining stages are aborted and the abandoned count is
+            // reported.
+            let signal = async {
+                tokio::select! {
+                    // A failure to install the handler is treated like a CTRL+C.
+                    _ = tokio::signal::ctrl_c() => {}
+                    () = storage_full(full_rx) => {}
+                }
+            };
+            // Boxed: the three joined stages and the watchdog make a large future.
+            shutdown_gracefully(
+                Box::pin(pipeline),
+                signal,
+                || buffer1.close(),
+                SHUTDOWN_GRACE,
+                || buffer1.depth() + buffer2.depth(),
+            )
+            .await
+        })
+        .await;
+    if let Some(watcher) = settings_watcher {
+        watcher.abort();
+    }
+    let (stops, abandoned) = match outcome {
+        Shutdown::Completed(result) => (Some(result?), None),
+        Shutdown::Abandoned { abandoned } => (None, Some(abandoned)),
     };
 
-    let pipeline = async {
-        // tokio::join! polls all three futures concurrently and returns the tuple directly.
-        let (p, c, l) = tokio::join!(
-            async {
-                let r = producer.run(&buffer1).await;
-                // Close buffer1 so Consumer exits cleanly after draining.
-                buffer1.close();
-                r
-            }
-            .instrument(tracing::info_span!("producer")),
-            consumer_then_close.instrument(tracing::info_span!("consumer")),
-            logger
-                .run(&buffer2, &storage)
-                .instrument(tracing::info_span!("logger"))
+    // Pipeline summary: totals plus per-model-version breakdown.
+    println!("{}", producer.stats());
+    println!("{}", consumer.stats());
+    if let Some(stats) = alarm.stats() {
+        println!("{stats}");
+    }
+    println!("{}", logger.histogram());
+    println!("buffer1 queue delay: {}", buffer1.latency_stats().unwrap_or_default());
+    println!("buffer2 queue delay: {}", buffer2.latency_stats().unwrap_or_default());
+    for b in storage.buckets() {
+        println!("  minute {}: {} transactions, {} flagged", b.minute, b.total, b.flagged);
+    }
+    #[cfg(feature = "arrow")]
+    println!(
+        "exported {} transactions to {PARQUET_PATH}",
+        storage
+            .inner()
+            .export_parquet(std::path::Path::new(PARQUET_PATH))
+            .context("failed to export the run to Parquet")?
+    );
+
+    if let Some((producer_stop, consumer_stop, logger_stop)) = stops {
+        println!(
+            "stopped: producer {producer_stop}, consumer {consumer_stop}, logger {logger_stop}"
         );
-        p.context("producer failed")
-            .and(c.context("consumer failed"))
-            .and(l.context("logger failed"))
+        if let LoggerStop::StorageFull { .. } = logger_stop {
+            println!("  {} transactions left in buffer2 were not persisted", buffer2.depth());
+        }
+    }
+
+    if let Some(abandoned) = abandoned {
+        anyhow::bail!("shutdown grace period expired: {abandoned} transactions abandoned");
+    }
+    Ok(())
+}
+
+/// Build the stage configurations; with `--settings <file>`, the file
+/// overrides their live settings and a task re-reads it on edit.
+///
+/// # Errors
+///
+/// Returns an error if a configuration is invalid or the settings file
+/// cannot be loaded.
+fn stage_configs()
+-> anyhow::Result<(ProducerConfig, ConsumerConfig, LoggerConfig, Option<JoinHandle<()>>)> {
+    let settings_path = settings_file::path_from_args(std::env::args().skip(1))
+        .context(Tagged::config("settings", "invalid --settings"))?;
+    let mut stages = stage_builders(profile()?);
+    if let Some(tenants) = tenants()? {
+        stages.producer = stages.producer.tenants(tenants);
+    }
+    let mut producer = stages.producer.build().context("failed to build producer config")?;
+    let mut consumer = stages.consumer.build().context("failed to build consumer config")?;
+    let mut logger = stages.logger.build().context("failed to build logger config")?;
+    let watcher = settings_path
+        .map(|path| {
+            settings_file::follow(path, (&mut producer, &mut consumer, &mut logger), SETTINGS_POLL)
+        })
+        .transpose()
+        .context(Tagged::config("settings", "invalid --settings file"))?;
+    Ok((producer, consumer, logger, watcher))
+}
+
+/// `--storage-capacity <n>` (unbounded by default) and `--on-storage-full
+/// <fail|stop>` (`fail` by default).
+///
+/// # Errors
+///
+/// Returns an error if either value is missing or invalid.
+fn storage_limit() -> anyhow::Result<(usize, CapacityPolicy)> {
+    let context = || Tagged::config("cli", "invalid storage arguments");
+    let capacity = arg_value("--storage-capacity")?
+        .map(|value| value.parse().with_context(|| format!("--storage-capacity {value}")))
+        .transpose()
+        .with_context(context)?
+        .unwrap_or(usize::MAX);
+    let policy = match arg_value("--on-storage-full")?.as_deref() {
+        None | Some("fail") => CapacityPolicy::Fail,
+        Some("stop") => CapacityPolicy::Stop,
+        Some(other) => {
+            return Err(anyhow::anyhow!("--on-storage-full {other}: expected fail or stop"))
+                .with_context(context);
+        }
     };
+    Ok((capacity, policy))
+}
 
-    // Race the pipeline against CTRL+C.
-    // CTRL+C: close buffer1 only; buffer2 cascade follows from consumer_then_close.
-    tokio::select! {
-        _ = tokio::signal::ctrl_c() => {
-            tracing::info!("main.shutdown: ctrl_c received, closing buffers");
-            buffer1.close();
+/// `--profile <demo|stress|low-latency>`: the preset the stage builders start
+/// from (`demo` by default).
+///
+/// # Errors
+///
+/// Returns an error if the value is not a preset name.
+fn profile() -> anyhow::Result<Preset> {
+    arg_value("--profile")?
+        .map_or(Ok(Preset::Demo), |value| value.parse())
+        .context(Tagged::config("cli", "invalid --profile"))
+}
+
+/// `--tenants <name:weight,...>`: tag transactions with weighted tenants.
+///
+/// # Errors
+///
+/// Returns an error if an entry is not `name:weight` or a weight is not a
+/// number; the weights themselves are checked by the producer config.
+fn tenants() -> anyhow::Result<Option<Vec<(String, f64)>>> {
+    let Some(value) = arg_value("--tenants")? else {
+        return Ok(None);
+    };
+    value
+        .split(',')
+        .map(|entry| {
+            let (name, weight) = entry.split_once(':').context("expected name:weight")?;
+            let weight = weight.parse().with_context(|| format!("weight {weight}"))?;
+            Ok((name.to_owned(), weight))
+        })
+        .collect::<anyhow::Result<_>>()
+        .map(Some)
+        .with_context(|| format!("--tenants {value}"))
+        .context(Tagged::config("cli", "invalid --tenants"))
+}
+
+/// Value following `name` on the command line (`name <value>` or `name=<value>`).
+///
+/// # Errors
+///
+/// Returns an error if `name` is the last argument.
+fn arg_value(name: &str) -> anyhow::Result<Option<String>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            let Some(value) = args.next() else {
+                return Err(anyhow::anyhow!("{name} requires a value"))
+                    .context(Tagged::config("cli", "invalid arguments"));
+            };
+            return Ok(Some(value));
         }
-        result = pipeline => {
-            result?;
+        if let Some(value) = arg.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+            return Ok(Some(value.to_owned()));
         }
     }
+    Ok(None)
+}
 
+/// `--check`: build every stage configuration and probe the `--model`
+/// backend, printing one PASS/FAIL line per component. No transaction is
+/// produced.
+///
+/// # Errors
+///
+/// Returns an error if the model cannot be built or any component fails.
+async fn run_check(model_spec: &ModelSpec) -> anyhow::Result<()> {
+    let model = ModelBackend::from_spec(model_spec)
+        .context(Tagged::startup("model", "failed to load the model"))?;
+    // In-memory storage cannot fail to open; it is listed for parity with SQLite.
+    let storage = async { Ok::<_, Infallible>(InMemoryStorage::new(usize::MAX)) };
+    let report = run_checks(stage_builders(profile()?), storage, model).await;
+    print!("{report}");
+    if !report.passed() {
+        return Err(anyhow::anyhow!("see the FAIL lines above"))
+            .context(Tagged::config("check", "configuration check failed"));
+    }
     Ok(())
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    //! Golden-file regression test for the seeded DEMO pipeline.
+    //!
+    //! Which transactions are produced and flagged for a given seed depends on
+    //! the order in which the Producer, the Consumer and the DEMO model draw
+    //! from their RNGs. GF-T01 pins that order: the persisted records of a
+    //! small seeded run must match `tests/golden/demo_pipeline.json`.
+    //!
+    //! When a change to RNG consumption is intended, regenerate the snapshot,
+    //! review its diff and commit it with the change:
+    //!
+    //! ```text
+    //! $env:UPDATE_GOLDEN='1'
+    //! cargo test -p fraud_detection --bin fraud_detection golden
+    //! Remove-Item env:UPDATE_GOLDEN
+    //! ```
+    //!
+    //! A missing snapshot is written on the first run, which then fails until
+    //! the new file is committed.
+
+    use crate::adapters::demo_model::DemoModel;
+    use crate::adapters::in_memory_storage::InMemoryStorage;
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{FixedClock, PendingTransaction, StorageRead as _};
+    use logger::{Logger, LoggerConfig};
+    use modelizer::Modelizer;
+    use pipeline::Pipeline;
+    use pipeline::memory::{CountingAlarm, MemoryBuffer};
+    use producer::{Producer, ProducerConfig};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    /// Checked-in snapshot of the persisted records of [`run_demo`].
+    const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/demo_pipeline.json");
+
+    /// Set (to any value) to rewrite [`GOLDEN`] instead of comparing against it.
+    const UPDATE_ENV: &str = "UPDATE_GOLDEN";
+
+    /// At most this many differing lines are printed on a mismatch.
+    const DIFF_LINES: usize = 20;
+
+    /// Seeded 5-iteration DEMO run with zero intervals; returns what the
+    /// Logger persisted, in storage order.
+    async fn run_demo() -> Vec<PendingTransaction> {
+        let producer = ProducerConfig::builder(10)
+            .poll_interval1(Duration::ZERO)
+            .iterations(5)
+            .seed(42);
+        let consumer = ConsumerConfig::builder(10).poll_interval2(Duration::ZERO).seed(43);
+        let logger = LoggerConfig::builder(10)
+            .poll_interval3(Duration::ZERO)
+            .seed(44)
+            .clock(FixedClock(SystemTime::UNIX_EPOCH));
+        let pipeline = Pipeline::new(
+            Producer::new(producer.build().unwrap()),
+            Consumer::new(consumer.build().unwrap()),
+            Logger::new(logger.build().unwrap()),
+        );
+        let modelizer = Modelizer::new(DemoModel::new(Some(45)));
+        let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+        let storage = InMemoryStorage::new(usize::MAX);
+
+        pipeline
+            .run(&buffer1, &modelizer, &CountingAlarm::new(), &buffer2, &storage)
+            .await
+            .unwrap();
+        let rows = storage.read_page(0, usize::MAX).await.unwrap();
+        rows.into_iter().map(|row| row.pending).collect()
+    }
+
+    /// Pretty JSON array with sorted keys and a trailing newline: ids, amounts,
+    /// names and model outcome of every record. Clock-dependent fields are left out.
+    fn canonical_json(records: &[PendingTransaction]) -> String {
+        let rows: Vec<_> = records
+            .iter()
+            .map(|record| {
+                let inferred = &record.inferred_transaction;
+                serde_json::json!({
+                    "id": inferred.id().full(),
+                    "amount": inferred.transaction.amount,
+                    "last_name": inferred.transaction.last_name,
+                    "predicted_fraud": inferred.predicted_fraud,
+                    "model_version": inferred.model_version,
+                })
+            })
+            .collect();
+        let mut json = serde_json::to_string_pretty(&rows).expect("JSON values always serialize");
+        json.push('\n');
+        json
+    }
+
+    /// Line-by-line differences between two snapshots, at most [`DIFF_LINES`].
+    fn line_diff(expected: &str, actual: &str) -> String {
+        let expected: Vec<_> = expected.lines().collect();
+        let actual: Vec<_> = actual.lines().collect();
+        (0..expected.len().max(actual.len()))
+            .filter_map(|i| {
+                let (e, a) = (expected.get(i), actual.get(i));
+                (e != a).then(|| {
+                    let (e, a) = (e.unwrap_or(&"<none>"), a.unwrap_or(&"<none>"));
+                    format!("line {}:\n  - {e}\n  + {a}", i + 1)
+                })
+            })
+            .take(DIFF_LINES)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // GF-T01: the seeded DEMO run persists exactly the checked-in records.
+    #[tokio::test]
+    async fn demo_pipeline_matches_golden_file() {
+        let actual = canonical_json(&run_demo().await);
+        let path = Path::new(GOLDEN);
+        let update = std::env::var_os(UPDATE_ENV).is_some();
+
+        if update || !path.exists() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, &actual).unwrap();
+            assert!(update, "no snapshot at {GOLDEN}: wrote one, review and commit it");
+            return;
+        }
+        // A Windows checkout may have turned the newlines into CRLF.
+        let expected = std::fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+        assert!(
+            expected == actual,
+            "the seeded DEMO run no longer matches {GOLDEN}; did the RNG call order change?\n\
+             {}\n\
+             If the change is intended, rerun with {UPDATE_ENV}=1 and commit the new snapshot.",
+            line_diff(&expected, &actual)
+        );
+    }
+
+    // GF-T02: the run itself is reproducible, so GF-T01 can only fail on a real change.
+    #[tokio::test]
+    async fn demo_run_is_deterministic() {
+        let first = canonical_json(&run_demo().await);
+
+        assert_eq!(first, canonical_json(&run_demo().await));
+        assert!(first.contains("\"predicted_fraud\""));
+    }
+}
@@ -16,12 +16,86 @@
 //!
 //! # Also show per-transaction debug output
 //! $env:RUST_LOG='debug'; cargo run --bin fraud_detection_sqlite; Remove-Item env:RUST_LOG
+//!
+//! # Debug one stage, warnings only elsewhere. Stage targets: pipeline::producer,
+//! # pipeline::consumer, pipeline::modelizer, pipeline::logger, pipeline::reviewer,
+//! # pipeline::buffer, plus pipeline::storage, pipeline::alarm and
+//! # pipeline::orchestrator; pipeline=debug covers them all
+//! $env:RUST_LOG='pipeline::consumer=debug,warn'; cargo run --bin fraud_detection_sqlite; Remove-Item env:RUST_LOG
+//!
+//! # Backfill: re-score stored rows with the current model, then exit
+//! $env:RUST_LOG='info'; cargo run --bin fraud_detection_sqlite -- --rescore; Remove-Item env:RUST_LOG
+//!
+//! # Validate the configuration, database and model, then exit (non-zero on failure)
+//! cargo run --bin fraud_detection_sqlite -- --check
+//!
+//! # Start the stages from a preset: demo (default), stress or low-latency
+//! cargo run --bin fraud_detection_sqlite -- --profile low-latency
+//!
+//! # Re-infer every transaction of another database with the --model backend,
+//! # into fraud_detection_replay.db (or --replay-to <db>), then exit
+//! cargo run --bin fraud_detection_sqlite -- --replay-from yesterday.db --model demo:7
+//!
+//! # Score a labelled CSV dataset (Kaggle creditcard.csv layout) with the
+//! # --model backend and report accuracy against its Class column, then exit
+//! cargo run --bin fraud_detection_sqlite -- --evaluate creditcard.csv --model bench
+//!
+//! # Replay a spill file left by a storage outage, then run as usual
+//! cargo run --bin fraud_detection_sqlite -- --recover spill/spill-1767225600000.jsonl
+//!
+//! # Pick the model (all modes): demo[:seed] (default), bench, onnx:<path> or http:<url>
+//! cargo run --bin fraud_detection_sqlite -- --model bench
+//!
+//! # Read every persisted batch back and compare it with what was written
+//! # (diagnostic; doubles the database I/O)
+//! cargo run --bin fraud_detection_sqlite -- --verify-writes
+//!
+//! # Also write every batch to a second database, e.g. during a migration;
+//! # row-count drift between the two is logged every 30 s
+//! cargo run --bin fraud_detection_sqlite -- --mirror-to mirror.db
+//!
+//! # Run as usual, with every other option, but log the database writes and
+//! # alerts instead of making them; the summary reports what each would have
+//! # received. The database is opened read-only, and left alone if missing
+//! cargo run --bin fraud_detection_sqlite -- --dry-run
+//!
+//! # Forward every alert from 10.00 up and 10% of lower-value ones (every
+//! # alert is forwarded and audited by default)
+//! cargo run --bin fraud_detection_sqlite -- --alarm-sample-rate 0.1
+//!
+//! # Simulate two banks, 70/30; each row keeps its tenant_id
+//! cargo run --bin fraud_detection_sqlite -- --tenants bank-a:0.7,bank-b:0.3
+//!
+//! # Report a failure as one JSON line on stderr (all modes)
+//! cargo run --bin fraud_detection_sqlite -- --error-format json
 //! ```
 //!
 //! The file `fraud_detection.db` is created on first run. Inspect rows with
 //! any `SQLite` browser (e.g., DB Browser for `SQLite`).
+//!
+//! Each pipeline run is recorded in the `runs` table (start and end time,
+//! seeds, configuration snapshot, final counts), and every row the Logger
+//! persists carries its `run_id`.
+//!
+//! With [`RUN_REVIEWER`] set, a fourth stage simulates human review and fills
+//! `is_reviewed` / `actual_fraud` on persisted rows.
+//!
+//! When the Logger gives up on storage, the transactions it could not persist
+//! and the rest of Buffer2 are written to a spill file under [`SPILL_DIR`]
+//! (or `--spill-dir <dir>`) and the process exits with
+//! [`EXIT_STORAGE_UNAVAILABLE`]; see the `spill` module.
+//!
+//! Other failures exit with 1 (runtime), 2 (invalid configuration or
+//! argument) or 3 (storage or model unreachable at startup); see the `exit`
+//! module.
 
 mod adapters;
+mod check;
+mod exit;
+mod model_backend;
+mod orchestrator;
+mod rescore;
+mod spill;
 
 // Load sqlite_storage directly so it only enters this binary's module tree,
 // avoiding dead_code warnings in the `fraud_detection` binary (which uses
@@ -29,114 +103,916 @@ mod adapters;
 #[path = "adapters/sqlite_storage.rs"]
 mod sqlite_storage;
 
+use adapters::aggregating_storage::AggregatingStorage;
+use adapters::deadline_storage::DeadlineStorage;
+use adapters::auditing_alarm::AuditingAlarm;
 use adapters::concurrent_buffer::ConcurrentBuffer;
 use adapters::concurrent_buffer2::ConcurrentBuffer2;
-use adapters::demo_model::DemoModel;
+use adapters::csv_source::{CsvDataset, Evaluation};
+use adapters::dry_run::DryRun;
+use adapters::in_memory_quarantine::InMemoryQuarantine;
 use adapters::log_alarm::LogAlarm;
+use adapters::mirrored_storage::MirroredStorage;
+use adapters::sampling_alarm::{self, MaybeSampled};
+use adapters::storage_source::StorageSource;
 use sqlite_storage::SqliteStorage;
 use anyhow::Context as _;
-use consumer::{Consumer, ConsumerConfig};
-use logger::{Logger, LoggerConfig};
+use check::{CheckReport, StageBuilders, run_checks};
+use exit::{ErrorFormat, Failure, FailureKind, Tagged};
+use consumer::{Consumer, ConsumerConfig, ConsumerStats};
+use logger::{Logger, LoggerConfig, LoggerError};
 use modelizer::Modelizer;
+use model_backend::{ModelBackend, ModelSpec};
+use domain::obs::target;
+use domain::{
+    Buffer2Read as _, BufferDepth as _, InferredTransaction, Model, Preset, RunMeta, RunSummary,
+    Storage as _,
+};
+use orchestrator::{RestartPolicy, Shutdown, shutdown_gracefully, supervise};
+use pipeline::Close as _;
+use pipeline::memory::{CountingAlarm, MemoryBuffer};
+use rescore::{RescoreConfig, rescore};
 use producer::{Producer, ProducerConfig};
-use std::time::Duration;
+use reviewer::{Reviewer, ReviewerConfig, ReviewerConfigBuilder};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
 use tracing::Instrument as _;
 
 /// Database file created in the current working directory on first run.
 ///
 /// Using the current working directory is acceptable for a demo adapter.
 /// A production adapter would read this from configuration or environment.
-const DB_URL: &str = "sqlite:fraud_detection.db";
+const DB_PATH: &str = "fraud_detection.db";
 
-#[tokio::main(flavor = "current_thread")]
-async fn main() -> anyhow::Result<()> {
+/// Default `--replay-to` database of `--replay-from`.
+const REPLAY_OUTPUT: &str = "fraud_detection_replay.db";
+
+/// Transactions read from the source database per Consumer batch in
+/// `--replay-from` mode.
+const REPLAY_BATCH: usize = 500;
+
+/// Run the simulated `Reviewer` as a fourth stage; set to `false` to leave
+/// persisted rows unreviewed.
+const RUN_REVIEWER: bool = true;
+
+/// High-water mark key of the `--rescore` backfill; rerunning it resumes.
+const RESCORE_JOB: &str = "demo-rescore";
+
+/// Time allowed after CTRL+C for the pipeline to drain both buffers.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Most persisted ids the Logger loads at startup to skip rows a previous run
+/// already wrote; a larger database runs without the skip.
+const DEDUP_PRELOAD_MAX_IDS: usize = 1_000_000;
+
+/// Directory, relative to the working directory, of the spill files written
+/// when storage is unavailable; `--spill-dir <dir>` overrides it.
+const SPILL_DIR: &str = "spill";
+
+/// Longest a `SQLite` write may take before it fails as unavailable.
+///
+/// Generous: a healthy write takes milliseconds, so only a hung one (e.g. a
+/// file lock held by a reader) reaches it.
+const WRITE_DEADLINE: Duration = Duration::from_secs(30);
+
+/// How often the `--mirror-to` database is reconciled with the main one.
+const MIRROR_RECONCILE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Consecutive `--mirror-to` write failures tolerated before the Logger fails.
+const MIRROR_ESCALATE_AFTER: u32 = 10;
+
+/// Process exit code after a storage outage was spilled to disk: that of a
+/// `startup` failure, as storage is unreachable.
+const EXIT_STORAGE_UNAVAILABLE: i32 = 3;
+
+/// Stage settings from `preset`, shared by the pipeline run and `--check`.
+fn stage_builders(preset: Preset) -> StageBuilders {
+    StageBuilders {
+        // Infinite mode by default; add .iterations(10) for a finite demo run.
+        producer: ProducerConfig::from_preset(preset),
+        consumer: ConsumerConfig::from_preset(preset),
+        // A restart over the same data skips the rows already in the
+        // database. Failed writes stay retained, so a storage outage can spill
+        // them; past 1 000 the Logger stops reading and the backlog waits in
+        // Buffer2, which is spilled too.
+        logger: LoggerConfig::from_preset(preset)
+            .dedup_preload(DEDUP_PRELOAD_MAX_IDS)
+            .split_on_capacity(true)
+            .max_retained(1_000),
+    }
+}
+
+/// Reviewer settings, shared by the pipeline run and `--check`.
+///
+/// Stops once storage has been idle for 5 s, i.e. after the Logger is done.
+fn reviewer_builder() -> ReviewerConfigBuilder {
+    ReviewerConfig::builder(100).poll_interval(Duration::from_millis(500)).idle_polls(10)
+}
+
+fn main() -> ExitCode {
     // Initialize the tracing subscriber before any async work.
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
+    let format = match ErrorFormat::from_args(std::env::args().skip(1)) {
+        Ok(format) => format,
+        Err(e) => return exit::report(&e, ErrorFormat::Text),
+    };
+    match run(format) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => exit::report(&e, format),
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+#[expect(clippy::too_many_lines, reason = "wires every stage of the binary in one place")]
+async fn run(format: ErrorFormat) -> anyhow::Result<()> {
+    let model_spec = ModelSpec::from_args(std::env::args().skip(1))
+        .context(Tagged::config("model", "invalid --model"))?;
+    if std::env::args().any(|arg| arg == "--rescore") {
+        return run_rescore(&model_spec).await;
+    }
+    if std::env::args().any(|arg| arg == "--check") {
+        return run_check(&model_spec).await;
+    }
+    if let Some(source) = arg_value("--replay-from")? {
+        let output = arg_value("--replay-to")?.unwrap_or_else(|| REPLAY_OUTPUT.to_owned());
+        return run_replay(&model_spec, &source, &output).await;
+    }
+    if let Some(dataset) = arg_value("--evaluate")? {
+        return run_evaluate(&model_spec, &dataset).await;
+    }
+    let dry_run = std::env::args().any(|arg| arg == "--dry-run");
+    let recover_from = arg_value("--recover")?.map(PathBuf::from);
+    if dry_run && recover_from.is_some() {
+        return Err(anyhow::anyhow!("--recover renames the spill file it replays"))
+            .context(Tagged::config("cli", "--recover cannot be combined with --dry-run"));
+    }
+    let spill_dir = PathBuf::from(arg_value("--spill-dir")?.as_deref().unwrap_or(SPILL_DIR));
+
+    let mut stages = stage_builders(profile()?);
+    if let Some(tenants) = tenants()? {
+        stages.producer = stages.producer.tenants(tenants);
+    }
+
+    // SqliteStorage: opens or creates fraud_detection.db in the working directory.
+    // INSERT OR REPLACE: duplicate UUIDs are silently overwritten (demo adapter).
+    // --verify-writes: read-your-writes check after every batch (diagnostic).
+    let sqlite = open_sqlite(DB_PATH, dry_run)
+        .await
+        .context("failed to open SQLite storage")?
+        .with_verification(std::env::args().any(|arg| arg == "--verify-writes"));
+    // --mirror-to <db>: every write is repeated there once the main database accepted it.
+    let mirror = match arg_value("--mirror-to")? {
+        Some(path) => Some(
+            open_sqlite(&path, dry_run)
+                .await
+                .with_context(|| format!("failed to open mirror database {path}"))
+                .context(Tagged::startup("storage", "failed to open the --mirror-to database"))?,
+        ),
+        None => None,
+    };
+
     // -- Producer: infinite mode by default; press CTRL+C to stop --
-    // Set .iterations(10) here for a finite demo run.
-    let producer_config = ProducerConfig::builder(100)
-        // 500 ms between batches keeps logs readable in real time.
-        .poll_interval1(Duration::from_millis(500))
-        // .iterations(10)
-        .build()
-        .context("failed to build producer config")?;
+    let producer_config = stages.producer.build().context("failed to build producer config")?;
+    // Transactions the model cannot score wait in quarantined_transactions
+    // (the clone shares the connection pool), or in memory under --dry-run.
+    let consumer = if dry_run {
+        stages.consumer.quarantine(InMemoryQuarantine::new())
+    } else {
+        stages.consumer.quarantine(sqlite.clone())
+    };
+    let consumer_config = consumer.build().context("failed to build consumer config")?;
+    let logger_config = stages.logger.build().context("failed to build logger config")?;
+    let meta = run_meta(&producer_config, &consumer_config, &logger_config, &model_spec);
 
     // ConcurrentBuffer: shared by Producer (write) and Consumer (read).
-    let buffer1 = ConcurrentBuffer::new();
+    // Latency tracing: queue delay percentiles in the summary.
+    let buffer1 = ConcurrentBuffer::with_latency_tracing(true);
     let producer = Producer::new(producer_config);
 
-    // -- Consumer: drain Buffer1 -> Modelizer<DemoModel> -> Buffer2 --
-    let consumer_config = ConsumerConfig::builder(50)
-        // 25 ms ensures Consumer yields regularly so Producer gets CPU time.
-        .poll_interval2(Duration::from_millis(25))
-        .build()
-        .context("failed to build consumer config")?;
-
+    // -- Consumer: drain Buffer1 -> Modelizer<ModelBackend> -> Buffer2 --
     // ConcurrentBuffer2: shared by Consumer (write) and Logger (read).
-    let buffer2 = ConcurrentBuffer2::new();
-    // DEMO model: OS-seeded RNG, starts at version N (version 4, ~4% fraud rate).
-    let model = DemoModel::new(None);
+    let buffer2 = ConcurrentBuffer2::with_latency_tracing(true);
+    // --model, DEMO by default: OS-seeded RNG, version N (version 4, ~4% fraud rate).
+    let model = ModelBackend::from_spec(&model_spec)
+        .context(Tagged::startup("model", "failed to load the model"))?;
     let modelizer = Modelizer::new(model);
-    let alarm = LogAlarm::new();
+
+    // Every alert by default; --alarm-sample-rate samples those below 10.00.
+    // AuditingAlarm inside the sampler: only alerts actually sent reach
+    // alarm_deliveries (the clone shares the connection pool).
+    // --dry-run: the alerts the sampler lets through are logged and counted
+    // instead, and none is audited.
+    let sample_rate = sampling_alarm::sample_rate_from_args(std::env::args().skip(1))
+        .context(Tagged::config("cli", "invalid --alarm-sample-rate"))?;
+    let alarm = MaybeSampled::new(
+        DryRun::new("alerts", AuditingAlarm::new(LogAlarm::new(), sqlite.clone(), 10.0))
+            .enabled(dry_run),
+        10.0,
+        sample_rate,
+        None,
+    );
     let consumer = Consumer::new(consumer_config);
 
     // -- Logger: drain Buffer2 -> SqliteStorage --
-    let logger_config = LoggerConfig::builder(10)
-        // 25 ms matches Consumer cadence.
-        .poll_interval3(Duration::from_millis(25))
-        .build()
-        .context("failed to build logger config")?;
-
-    // SqliteStorage: opens or creates fraud_detection.db in the working directory.
-    // INSERT OR REPLACE: duplicate UUIDs are silently overwritten (demo adapter).
-    let storage = SqliteStorage::new(DB_URL)
+    // AggregatingStorage: per-minute counts upserted into fraud_counts_by_minute every 10 s.
+    // MirroredStorage: --mirror-to failures are logged; MIRROR_ESCALATE_AFTER in a row fail.
+    // DryRun: under --dry-run, every write (rows, reviews, counts, runs) is
+    // logged and counted instead; reads still reach the database.
+    // DeadlineStorage: a hung write fails as Unavailable after WRITE_DEADLINE.
+    let storage = AggregatingStorage::new(
+        MirroredStorage::new(
+            DryRun::new("sqlite", DeadlineStorage::new(sqlite, WRITE_DEADLINE)).enabled(dry_run),
+            mirror.map(|mirror| {
+                DryRun::new("mirror", DeadlineStorage::new(mirror, WRITE_DEADLINE))
+                    .enabled(dry_run)
+            }),
+        )
+        .escalate_after(MIRROR_ESCALATE_AFTER),
+        Duration::from_secs(10),
+    );
+    // One runs row per pipeline run; every persisted row carries its id.
+    let run_id = storage
+        .begin_run(meta)
         .await
-        .context("failed to open SQLite storage")?;
-    let logger = Logger::new(logger_config);
+        .context(Tagged::startup("storage", "failed to record the run"))?;
+    let logger = Logger::new(LoggerConfig { run_id: Some(run_id), ..logger_config });
+    // --recover: replay before the preload so the replayed ids are skipped too.
+    if let Some(path) = &recover_from {
+        let replayed = spill::recover(path, &storage)
+            .await
+            .with_context(|| format!("failed to recover {}", path.display()))
+            .context(Tagged::startup("recover", "--recover failed"))?;
+        println!("recover: {replayed} transactions replayed from {}", path.display());
+    }
+    logger
+        .preload_seen_ids(&storage)
+        .await
+        .context("failed to preload persisted transaction ids")?;
+
+    // -- Reviewer: SqliteStorage -> simulated verdicts -> SqliteStorage --
+    let reviewer_config = reviewer_builder().build().context("failed to build reviewer config")?;
+    let reviewer = Reviewer::new(reviewer_config);
+
+    // Retryable stage failures restart the stage after a backoff; fatal ones abort.
+    let policy = RestartPolicy::default();
+    let (buf1, buf2) = (&buffer1, &buffer2);
 
     // Shutdown cascade: Consumer.run completes -> buffer2.close() -> Logger drains+stops.
     // On CTRL+C, only buffer1.close() is needed; buffer2 cascade follows automatically.
     let consumer_then_close = async {
-        let r = consumer.run(&buffer1, &modelizer, &alarm, &buffer2).await;
+        let r = supervise("consumer", policy, || {
+            consumer.run(buf1, &modelizer, &alarm, buf2)
+        })
+        .await;
         // Close buffer2 so Logger exits cleanly after draining (cascade shutdown).
         buffer2.close();
-        r
+        r.context("consumer failed")
     };
 
     let pipeline = async {
-        // tokio::join! polls all three futures concurrently and returns the tuple directly.
-        let (p, c, l) = tokio::join!(
+        // tokio::try_join! polls all four futures concurrently and returns on the
+        // first error, dropping the other stages (fatal error aborts the pipeline).
+        tokio::try_join!(
             async {
-                let r = producer.run(&buffer1).await;
+                let r = supervise("producer", policy, || producer.run(buf1)).await;
                 // Close buffer1 so Consumer exits cleanly after draining.
                 buffer1.close();
-                r
+                r.context("producer failed")
             }
-            .instrument(tracing::info_span!("producer")),
-            consumer_then_close.instrument(tracing::info_span!("consumer")),
-            logger
-                .run(&buffer2, &storage)
-                .instrument(tracing::info_span!("logger"))
+            .instrument(tracing::info_span!(target: target::PRODUCER, "producer")),
+            consumer_then_close
+                .instrument(tracing::info_span!(target: target::CONSUMER, "consumer")),
+            async {
+                let logger_run = supervise("logger", policy, || logger.run(buf2, &storage));
+                storage
+                    .inner()
+                    .reconcile_during(MIRROR_RECONCILE_PERIOD, logger_run)
+                    .await
+                    .context("logger failed")
+            }
+            .instrument(tracing::info_span!(target: target::LOGGER, "logger")),
+            async {
+                if !RUN_REVIEWER {
+                    return Ok(());
+                }
+                supervise("reviewer", policy, || reviewer.run(&storage))
+                    .await
+                    .context("reviewer failed")
+            }
+            .instrument(tracing::info_span!(target: target::REVIEWER, "reviewer"))
+        )
+    };
+
+    // CTRL+C only closes buffer1; the pipeline future keeps running so the
+    // cascade drains buffer1 and buffer2 before the summary. Past SHUTDOWN_GRACE
+    // the remaining stages are dropped and the abandoned count is reported.
+    let ctrl_c = async {
+        // A failure to install the handler is treated like a CTRL+C.
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    // Boxed: the four joined stages make a large future.
+    let outcome = shutdown_gracefully(
+        Box::pin(pipeline),
+        ctrl_c,
+        || buffer1.close(),
+        SHUTDOWN_GRACE,
+        || buffer1.depth() + buffer2.depth(),
+    )
+    .await;
+    let (stops, abandoned) = match outcome {
+        // Storage is gone for good: keep what is still in memory, then stop.
+        Shutdown::Completed(Err(e)) if e.downcast_ref::<LoggerError>().is_some() => {
+            let spilled = spill::spill_unpersisted(&logger, &buffer2, &spill_dir).await;
+            exit_storage_unavailable(e, spilled, format)
+        }
+        Shutdown::Completed(result) => (Some(result?), None),
+        Shutdown::Abandoned { abandoned } => (None, Some(abandoned)),
+    };
+
+    // Pipeline summary: totals plus per-model-version breakdown.
+    if dry_run {
+        println!("DRY RUN: nothing was written to {DB_PATH} and no alert was sent");
+    }
+    println!("{}", producer.stats());
+    println!("{}", consumer.stats());
+    if let Some(stats) = alarm.stats() {
+        println!("{stats}");
+    }
+    println!("{}", logger.histogram());
+    println!("buffer1 queue delay: {}", buffer1.latency_stats().unwrap_or_default());
+    println!("buffer2 queue delay: {}", buffer2.latency_stats().unwrap_or_default());
+    println!("logger: {} already-persisted transactions skipped", logger.skipped());
+    if RUN_REVIEWER {
+        println!("{}", reviewer.stats());
+    }
+
+    // Final flush so the last partial minute reaches fraud_counts_by_minute.
+    storage
+        .flush()
+        .await
+        .context("failed to flush per-minute counts")?;
+    let mirrored = storage.inner();
+    if mirrored.secondary().is_some() {
+        let drift = match mirrored.reconcile().await {
+            Ok(None) => "in sync".to_owned(),
+            Ok(Some(drift)) => {
+                format!("drift ({} rows, {} in the main database)", drift.secondary, drift.primary)
+            }
+            Err(e) => format!("not reconciled ({e})"),
+        };
+        println!("mirror: {drift}, {} failed writes", mirrored.secondary_failures());
+    }
+    storage
+        .end_run(run_id, run_summary(&producer, &consumer, &logger))
+        .await
+        .context("failed to record the run end")?;
+    if dry_run {
+        println!("{}", mirrored.primary().stats());
+        if let Some(mirror) = mirrored.secondary() {
+            println!("{}", mirror.stats());
+        }
+        println!("{}", alarm.inner().stats());
+    } else {
+        println!("{run_id} recorded");
+    }
+
+    if let Some((producer_stop, consumer_stop, logger_stop, ())) = stops {
+        println!(
+            "stopped: producer {producer_stop}, consumer {consumer_stop}, logger {logger_stop}"
         );
-        p.context("producer failed")
-            .and(c.context("consumer failed"))
-            .and(l.context("logger failed"))
+    }
+
+    if let Some(abandoned) = abandoned {
+        anyhow::bail!("shutdown grace period expired: {abandoned} transactions abandoned");
+    }
+    Ok(())
+}
+
+/// Open the `SQLite` database at `path`, creating and migrating it if needed.
+///
+/// Under `--dry-run` the file is left exactly as it is: an existing one is
+/// opened read-only, and an empty in-memory database stands in for a missing one.
+///
+/// # Errors
+///
+/// Returns `sqlx::Error` when the database cannot be opened or created.
+async fn open_sqlite(path: &str, dry_run: bool) -> Result<SqliteStorage, sqlx::Error> {
+    match (dry_run, Path::new(path).exists()) {
+        (false, _) => SqliteStorage::new(&format!("sqlite:{path}")).await,
+        (true, true) => SqliteStorage::open_read_only(Path::new(path)).await,
+        (true, false) => SqliteStorage::new("sqlite::memory:").await,
+    }
+}
+
+/// What the `runs` row of a pipeline run records at its start: the seeds of
+/// the stages and model that have one, and the stage config summaries.
+fn run_meta(
+    producer: &ProducerConfig,
+    consumer: &ConsumerConfig,
+    logger: &LoggerConfig,
+    model_spec: &ModelSpec,
+) -> RunMeta {
+    let model_seed = match model_spec {
+        ModelSpec::Demo { seed } => *seed,
+        _ => None,
     };
+    let seeds = [
+        ("producer", producer.seed),
+        ("consumer", consumer.seed),
+        ("logger", logger.seed),
+        ("model", model_seed),
+    ];
+    let config = serde_json::json!({
+        "model": model_spec.to_string(),
+        "producer": producer.summary(),
+        "consumer": consumer.summary(),
+        "logger": logger.summary(),
+        "reviewer": RUN_REVIEWER,
+    });
+    RunMeta {
+        started_at: SystemTime::now(),
+        seeds: seeds
+            .into_iter()
+            .filter_map(|(name, seed)| Some((name.to_owned(), seed?)))
+            .collect(),
+        config: config.to_string(),
+    }
+}
 
-    // Race the pipeline against CTRL+C.
-    // CTRL+C: close buffer1 only; buffer2 cascade follows from consumer_then_close.
-    tokio::select! {
-        _ = tokio::signal::ctrl_c() => {
-            tracing::info!("main.shutdown: ctrl_c received, closing buffers");
-            buffer1.close();
+/// Final counts of a pipeline run, as recorded by `end_run`; `persisted` is
+/// what the Logger handed to storage.
+fn run_summary(producer: &Producer, consumer: &Consumer, logger: &Logger) -> RunSummary {
+    let consumer = consumer.stats();
+    RunSummary {
+        ended_at: SystemTime::now(),
+        produced: producer.stats().transactions,
+        inferred: consumer.transactions,
+        flagged: consumer.flagged,
+        persisted: logger.histogram().total(),
+    }
+}
+
+/// `--profile <demo|stress|low-latency>`: the preset the stage builders start
+/// from (`demo` by default).
+///
+/// # Errors
+///
+/// Returns an error if the value is not a preset name.
+fn profile() -> anyhow::Result<Preset> {
+    arg_value("--profile")?
+        .map_or(Ok(Preset::Demo), |value| value.parse())
+        .context(Tagged::config("cli", "invalid --profile"))
+}
+
+/// `--tenants <name:weight,...>`: tag transactions with weighted tenants.
+///
+/// # Errors
+///
+/// Returns an error if an entry is not `name:weight` or a weight is not a
+/// number; the weights themselves are checked by the producer config.
+fn tenants() -> anyhow::Result<Option<Vec<(String, f64)>>> {
+    let Some(value) = arg_value("--tenants")? else {
+        return Ok(None);
+    };
+    value
+        .split(',')
+        .map(|entry| {
+            let (name, weight) = entry.split_once(':').context("expected name:weight")?;
+            let weight = weight.parse().with_context(|| format!("weight {weight}"))?;
+            Ok((name.to_owned(), weight))
+        })
+        .collect::<anyhow::Result<_>>()
+        .map(Some)
+        .with_context(|| format!("--tenants {value}"))
+        .context(Tagged::config("cli", "invalid --tenants"))
+}
+
+/// Value following `name` on the command line (`name <value>` or `name=<value>`).
+///
+/// # Errors
+///
+/// Returns an error if `name` is the last argument.
+fn arg_value(name: &str) -> anyhow::Result<Option<String>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            let Some(value) = args.next() else {
+                return Err(anyhow::anyhow!("{name} requires a value"))
+                    .context(Tagged::config("cli", "invalid arguments"));
+            };
+            return Ok(Some(value));
         }
-        result = pipeline => {
-            result?;
+        if let Some(value) = arg.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+            return Ok(Some(value.to_owned()));
         }
     }
+    Ok(None)
+}
+
+/// Report the Logger `error` and the outcome of spilling what it could not
+/// persist in `format`, then exit: [`EXIT_STORAGE_UNAVAILABLE`] once spilled,
+/// the usual runtime code 1 if the spill itself failed.
+fn exit_storage_unavailable(
+    error: anyhow::Error,
+    spilled: anyhow::Result<spill::StorageUnavailable>,
+    format: ErrorFormat,
+) -> ! {
+    match spilled {
+        Ok(spilled) => {
+            tracing::error!(
+                target: target::STORAGE,
+                path = %spilled.spill.display(),
+                spilled = spilled.spilled,
+                "pipeline.storage.spilled"
+            );
+            let error = error.context(spilled);
+            let failure = Failure {
+                kind: FailureKind::Startup,
+                component: "storage",
+                message: format!("{error:#}"),
+            };
+            eprintln!("{}", format.render(&failure, &error));
+            std::process::exit(EXIT_STORAGE_UNAVAILABLE);
+        }
+        Err(spill_error) => {
+            let error = error.context(format!("{spill_error:#}"));
+            let failure = exit::classify(&error);
+            eprintln!("{}", format.render(&failure, &error));
+            std::process::exit(i32::from(failure.kind.exit_code()));
+        }
+    }
+}
+
+/// `--check`: print one PASS/FAIL line per component of [`check_components`]
+/// for [`DB_PATH`] and the `--model` backend. No transaction is produced.
+///
+/// # Errors
+///
+/// Returns an error if the model cannot be built or any component fails.
+async fn run_check(model_spec: &ModelSpec) -> anyhow::Result<()> {
+    let model = ModelBackend::from_spec(model_spec)
+        .context(Tagged::startup("model", "failed to load the model"))?;
+    let report = check_components(&format!("sqlite:{DB_PATH}"), model, profile()?).await;
+    print!("{report}");
+    if !report.passed() {
+        return Err(anyhow::anyhow!("see the FAIL lines above"))
+            .context(Tagged::config("check", "configuration check failed"));
+    }
+    Ok(())
+}
+
+/// Build every stage configuration from `preset`, open `db_url` (creating the
+/// schema if needed) and probe `model`.
+async fn check_components(db_url: &str, model: ModelBackend, preset: Preset) -> CheckReport {
+    let storage = SqliteStorage::new(db_url);
+    let mut report = run_checks(stage_builders(preset), storage, model).await;
+    report.record("reviewer", reviewer_builder().build(), |c| {
+        format!("batch_size={}, poll_interval={:?}", c.batch_size, c.poll_interval)
+    });
+    report
+}
+
+/// `--rescore`: score every stored row not yet covered by [`RESCORE_JOB`]
+/// with the `--model` backend and report how many predictions changed.
+///
+/// # Errors
+///
+/// Returns an error if the model cannot be built, the database cannot be
+/// opened or the backfill fails.
+async fn run_rescore(model_spec: &ModelSpec) -> anyhow::Result<()> {
+    let model = ModelBackend::from_spec(model_spec)
+        .context(Tagged::startup("model", "failed to load the model"))?;
+    let modelizer = Modelizer::new(model);
+    let storage = SqliteStorage::new(&format!("sqlite:{DB_PATH}"))
+        .await
+        .context("failed to open SQLite storage")?;
+    let report = rescore(&storage, &modelizer, &RescoreConfig::new(RESCORE_JOB)).await?;
+    println!(
+        "rescore: {} rows scored (resumed after rowid {}, high-water mark {})",
+        report.processed, report.resumed_from, report.high_water
+    );
+    if report.processed > 0 {
+        let changed = storage
+            .rescore_disagreements(&report.model_name, &report.model_version)
+            .await
+            .context("failed to count disagreements")?;
+        println!(
+            "rescore: model {} version {} disagrees on {changed} rows",
+            report.model_name, report.model_version
+        );
+    }
+    Ok(())
+}
+
+/// `--replay-from <db>`: run every transaction persisted in `source`
+/// through the Consumer with the `--model` backend and persist the new
+/// inferences in `output`. No Producer runs and no alarm is sent.
+///
+/// # Errors
+///
+/// Returns an error if `source` and `output` are the same file, the model
+/// cannot be built, either database cannot be opened or a stage fails.
+async fn run_replay(model_spec: &ModelSpec, source: &str, output: &str) -> anyhow::Result<()> {
+    if source == output {
+        return Err(anyhow::anyhow!("--replay-from and --replay-to are both {source}"))
+            .context(Tagged::config("cli", "invalid arguments"));
+    }
+    let model = ModelBackend::from_spec(model_spec)
+        .context(Tagged::startup("model", "failed to load the model"))?;
+    let source_db = SqliteStorage::new(&format!("sqlite:{source}"))
+        .await
+        .with_context(|| format!("failed to open {source}"))?;
+    let output_db = SqliteStorage::new(&format!("sqlite:{output}"))
+        .await
+        .with_context(|| format!("failed to open {output}"))?;
+    let stats = replay_storage(source_db, &output_db, &Modelizer::new(model)).await?;
+    println!("replay: {source} re-inferred into {output}");
+    println!("{stats}");
+    Ok(())
+}
+
+/// Re-infer every transaction of `source` with `modelizer` into `output`;
+/// returns the Consumer statistics.
+///
+/// # Errors
+///
+/// Returns an error if a config is rejected or the Consumer or Logger fails.
+async fn replay_storage<M: Model>(
+    source: SqliteStorage,
+    output: &SqliteStorage,
+    modelizer: &Modelizer<M>,
+) -> anyhow::Result<ConsumerStats> {
+    // No .iterations(): both stages drain until the source is exhausted.
+    let consumer_config = ConsumerConfig::builder(REPLAY_BATCH)
+        .poll_interval2(Duration::ZERO)
+        .build()
+        .context("failed to build consumer config")?;
+    let logger_config = LoggerConfig::builder(REPLAY_BATCH)
+        .poll_interval3(Duration::ZERO)
+        .build()
+        .context("failed to build logger config")?;
+    let (consumer, logger) = (Consumer::new(consumer_config), Logger::new(logger_config));
+    let source = StorageSource::new(source);
+    let buffer2 = ConcurrentBuffer2::new();
+    // Flagged replays are counted by the Consumer; nobody is paged.
+    let alarm = CountingAlarm::new();
+
+    let (consumer_run, logger_run) = tokio::join!(
+        async {
+            let r = consumer.run(&source, modelizer, &alarm, &buffer2).await;
+            buffer2.close();
+            r
+        },
+        logger.run(&buffer2, output)
+    );
+    consumer_run.context("consumer failed")?;
+    logger_run.context("logger failed")?;
+    Ok(consumer.stats())
+}
 
+/// `--evaluate <csv>`: run every row of a labelled dataset through the
+/// Consumer with the `--model` backend and compare the predictions with the
+/// `Class` column. Nothing is persisted and no alarm is sent.
+///
+/// # Errors
+///
+/// Returns an error if the model cannot be built, the file cannot be parsed
+/// or the Consumer fails.
+async fn run_evaluate(model_spec: &ModelSpec, path: &str) -> anyhow::Result<()> {
+    let model = ModelBackend::from_spec(model_spec)
+        .context(Tagged::startup("model", "failed to load the model"))?;
+    let dataset = CsvDataset::open(path.as_ref())
+        .context(Tagged::config("cli", "invalid --evaluate dataset"))?;
+    let evaluation = evaluate_dataset(&dataset, &Modelizer::new(model)).await?;
+    println!(
+        "evaluate: {path}: {} rows, {} malformed skipped",
+        dataset.rows().len(),
+        dataset.skipped()
+    );
+    println!("{evaluation}");
     Ok(())
 }
+
+/// Infer every transaction of `dataset` with `modelizer` and evaluate the
+/// predictions against its labels.
+///
+/// # Errors
+///
+/// Returns an error if the config is rejected or the Consumer fails.
+async fn evaluate_dataset<M: Model>(
+    dataset: &CsvDataset,
+    modelizer: &Modelizer<M>,
+) -> anyhow::Result<Evaluation> {
+    // No .iterations(): the Consumer drains until the source is exhausted.
+    let consumer_config = ConsumerConfig::builder(REPLAY_BATCH)
+        .poll_interval2(Duration::ZERO)
+        .build()
+        .context("failed to build consumer config")?;
+    let consumer = Consumer::new(consumer_config);
+    // Unbounded: the whole dataset is held until the Consumer stops.
+    let inferred = MemoryBuffer::<InferredTransaction>::new();
+    let alarm = CountingAlarm::new();
+
+    consumer
+        .run(&dataset.source(), modelizer, &alarm, &inferred)
+        .await
+        .context("consumer failed")?;
+    // Closed first, so an empty dataset reads Closed instead of waiting.
+    inferred.close();
+    let predictions = inferred.read_batch(usize::MAX).await.unwrap_or_default();
+    Ok(dataset.evaluate(&predictions))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SqliteStorage, check_components, evaluate_dataset, replay_storage, run_meta, run_summary,
+    };
+    use crate::adapters::csv_source::CsvDataset;
+    use crate::model_backend::{ModelBackend, ModelSpec};
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{FixedClock, Preset, RunId, Storage as _, StorageRead as _};
+    use logger::{Logger, LoggerConfig};
+    use modelizer::Modelizer;
+    use pipeline::Pipeline;
+    use pipeline::memory::{CountingAlarm, MemoryBuffer, RateModel};
+    use producer::{Producer, ProducerConfig};
+    use std::time::{Duration, SystemTime};
+
+    fn demo() -> ModelBackend {
+        ModelBackend::from_spec(&ModelSpec::Demo { seed: Some(1) }).unwrap()
+    }
+
+    /// Feed the same seeded 3-iteration run into `storage`, stamping rows
+    /// `persisted_at = at`; return how many transactions the Logger skipped.
+    async fn replay(storage: &SqliteStorage, at: SystemTime) -> u64 {
+        let producer = ProducerConfig::builder(20).poll_interval1(Duration::ZERO).iterations(3);
+        let consumer = ConsumerConfig::builder(20).poll_interval2(Duration::ZERO).seed(8);
+        let logger = LoggerConfig::builder(20)
+            .poll_interval3(Duration::ZERO)
+            .seed(9)
+            .dedup_preload(1_000)
+            .clock(FixedClock(at));
+        let logger = Logger::new(logger.build().unwrap());
+        logger.preload_seen_ids(storage).await.unwrap();
+        let pipeline = Pipeline::new(
+            Producer::new(producer.seed(7).build().unwrap()),
+            Consumer::new(consumer.build().unwrap()),
+            logger,
+        );
+        let modelizer = Modelizer::new(RateModel::new(0.1, 10));
+        let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+
+        pipeline
+            .run(&buffer1, &modelizer, &CountingAlarm::new(), &buffer2, storage)
+            .await
+            .unwrap();
+        pipeline.logger.skipped()
+    }
+
+    /// Run a seeded 3-iteration pipeline into `storage` between `begin_run`
+    /// and `end_run`, as the binary does; return the run id.
+    async fn recorded_run(storage: &SqliteStorage, seed: u64) -> RunId {
+        let producer = ProducerConfig::builder(20).poll_interval1(Duration::ZERO).iterations(3);
+        let producer = producer.seed(seed).build().unwrap();
+        let consumer = ConsumerConfig::builder(20).poll_interval2(Duration::ZERO).seed(seed);
+        let consumer = consumer.build().unwrap();
+        let logger = LoggerConfig::builder(20).poll_interval3(Duration::ZERO).seed(seed);
+        let logger = logger.build().unwrap();
+        let meta = run_meta(&producer, &consumer, &logger, &ModelSpec::Demo { seed: Some(seed) });
+        let run_id = storage.begin_run(meta).await.unwrap();
+        let pipeline = Pipeline::new(
+            Producer::new(producer),
+            Consumer::new(consumer),
+            Logger::new(LoggerConfig { run_id: Some(run_id), ..logger }),
+        );
+        let modelizer = Modelizer::new(RateModel::new(0.1, 10));
+        let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+
+        pipeline
+            .run(&buffer1, &modelizer, &CountingAlarm::new(), &buffer2, storage)
+            .await
+            .unwrap();
+        let summary = run_summary(&pipeline.producer, &pipeline.consumer, &pipeline.logger);
+        storage.end_run(run_id, summary).await.unwrap();
+        run_id
+    }
+
+    // MS-T01: --check passes with an openable database.
+    #[tokio::test]
+    async fn check_passes_with_in_memory_database() {
+        let report = check_components("sqlite::memory:", demo(), Preset::Demo).await;
+
+        assert!(report.passed(), "{report}");
+        assert!(report.to_string().contains("check: PASS reviewer"), "{report}");
+    }
+
+    // MS-T02: an unreachable database path fails the storage check only.
+    #[tokio::test]
+    async fn check_fails_on_unreachable_database_path() {
+        let dir = std::env::temp_dir().join(format!("missing-{}", uuid::Uuid::new_v4()));
+        let url = format!("sqlite:{}", dir.join("fraud_detection.db").display());
+
+        let report = check_components(&url, demo(), Preset::Demo).await;
+
+        assert!(!report.passed());
+        let text = report.to_string();
+        assert_eq!(text.matches("check: FAIL").count(), 1, "{text}");
+        assert!(text.contains("check: FAIL storage"), "{text}");
+        assert!(!dir.exists(), "the check must not create the missing directory");
+    }
+
+    // MS-T03: replaying a seeded run skips every row the first run persisted.
+    #[tokio::test]
+    async fn seeded_replay_twice_persists_nothing_new() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let first = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        assert_eq!(replay(&storage, first).await, 0);
+        let rows = storage.read_page(0, usize::MAX).await.unwrap();
+        assert!(!rows.is_empty());
+
+        let skipped = replay(&storage, first + Duration::from_mins(1)).await;
+
+        assert_eq!(skipped, rows.len() as u64);
+        // Same rowids and persisted_at: nothing was rewritten.
+        assert_eq!(storage.read_page(0, usize::MAX).await.unwrap(), rows);
+    }
+
+    // MS-T04: --replay-from re-infers every source row exactly once, in
+    // storage order, keeping the original ids and amounts.
+    #[tokio::test]
+    async fn replay_from_storage_reinfers_every_row_once() {
+        let source = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        replay(&source, SystemTime::UNIX_EPOCH).await;
+        let rows = source.read_page(0, usize::MAX).await.unwrap();
+        let output = SqliteStorage::new("sqlite::memory:").await.unwrap();
+
+        let stats = replay_storage(source, &output, &Modelizer::new(RateModel::new(1.0, 3)))
+            .await
+            .unwrap();
+
+        let replayed = output.read_page(0, usize::MAX).await.unwrap();
+        let original: Vec<_> =
+            rows.iter().map(|r| &r.pending.inferred_transaction.transaction).collect();
+        let reinferred: Vec<_> =
+            replayed.iter().map(|r| &r.pending.inferred_transaction.transaction).collect();
+        assert_eq!(reinferred, original);
+        assert_eq!(stats.transactions, rows.len() as u64);
+        // RateModel at 1.0: every replayed row carries the new verdict.
+        assert_eq!(stats.flagged, stats.transactions);
+        assert!(replayed.iter().all(|r| r.pending.inferred_transaction.predicted_fraud));
+    }
+
+    // MS-T05: two sequential runs leave two runs rows with their seeds and
+    // final counts, and every persisted row is attributed to its run.
+    #[tokio::test]
+    async fn sequential_runs_are_recorded_and_attributed() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+
+        let first = recorded_run(&storage, 1).await;
+        let second = recorded_run(&storage, 2).await;
+
+        let runs = storage.runs().await.unwrap();
+        let rows = storage.read_page(0, usize::MAX).await.unwrap();
+        assert_eq!(runs.iter().map(|r| r.id).collect::<Vec<_>>(), [first, second]);
+        for (run, seed) in runs.iter().zip([1, 2]) {
+            assert_eq!(run.meta.seeds["producer"], seed);
+            assert_eq!(run.meta.seeds["model"], seed);
+            let config: serde_json::Value = serde_json::from_str(&run.meta.config).unwrap();
+            assert_eq!(config["producer"]["seed"], seed.to_string());
+            assert_eq!(config["logger"]["n3_max"], "20");
+            let summary = run.summary.expect("the run ended");
+            let attributed = rows.iter().filter(|r| r.pending.run_id == Some(run.id)).count();
+            assert!(summary.produced > 0);
+            assert_eq!(summary.inferred, summary.produced);
+            assert_eq!(summary.persisted, summary.produced);
+            assert_eq!(attributed as u64, summary.persisted);
+        }
+        assert!(rows.iter().all(|r| r.pending.run_id.is_some()));
+    }
+
+    // MS-T06: --evaluate scores every well-formed dataset row once against
+    // its label.
+    #[tokio::test]
+    async fn evaluate_scores_every_dataset_row() {
+        let csv = include_str!("../tests/fixtures/creditcard_sample.csv");
+        let dataset = CsvDataset::parse(csv.as_bytes()).unwrap();
+
+        // RateModel at 1.0 flags everything: every fraud is caught.
+        let evaluation =
+            evaluate_dataset(&dataset, &Modelizer::new(RateModel::new(1.0, 3))).await.unwrap();
+
+        assert_eq!(evaluation.labelled(), 20);
+        assert_eq!((evaluation.true_positives, evaluation.false_positives), (4, 16));
+        assert_eq!(evaluation.recall(), Some(1.0));
+    }
+}
@@ -0,0 +1,789 @@
+// Rust guideline compliant 2026-02-27
+
+//! Standalone drain utility: copy persisted transactions from one storage
+//! backend to another.
+//!
+//! Pages through a `StorageRead` source and writes each page to a `Storage`
+//! destination as one batch, skipping ids the destination already holds, then
+//! prints a reconciliation: rows read, written and skipped as duplicates, and
+//! the destination row count before and after. Storage specs:
+//!
+//! - `sqlite:PATH`, or a bare path ending in `.db`, `.sqlite` or `.sqlite3`
+//!   ([`SqliteStorage`])
+//! - `jsonl:PATH`, or a bare path ending in `.jsonl`: spill-format JSON Lines
+//!   ([`JsonlStorage`]), e.g. a spill file left by a storage outage
+//!
+//! There is no Postgres adapter in this tree, so `postgres:` specs are
+//! rejected. Both backends above are always compiled in, so the binary needs
+//! no feature.
+//!
+//! # Usage
+//!
+//! ```text
+//! # Drain a spill file into the pipeline database
+//! cargo run --bin fraud_detection_migrate -- --from spill/spill-1767225600000.jsonl \
+//!     --to fraud_detection.db
+//!
+//! # Count what would be copied, without writing
+//! cargo run --bin fraud_detection_migrate -- --from old.db --to new.db --dry-run
+//!
+//! # Resume an interrupted copy after the last id it reported
+//! cargo run --bin fraud_detection_migrate -- --from old.db --to new.db \
+//!     --start-after 0b6f2c1e-3d4a-4f5b-8c6d-7e8f9a0b1c2d
+//! ```
+//!
+//! # Options
+//!
+//! | Option             | Meaning                                                |
+//! |--------------------|--------------------------------------------------------|
+//! | `--from SPEC`      | Source storage (required)                              |
+//! | `--to SPEC`        | Destination storage (required)                         |
+//! | `--page-size N`    | Rows per page and per written batch (default 500)      |
+//! | `--start-after ID` | Skip source rows up to and including this id           |
+//! | `--dry-run`        | Read and reconcile, but write nothing                  |
+//!
+//! A `SQLite` source, and the destination of a dry run, are opened read-only:
+//! neither is created, migrated to the current schema nor switched to WAL.
+//!
+//! A failed read or write stops the copy and names the id to pass to
+//! `--start-after`; rows the destination already holds are skipped either way,
+//! so rerunning from the start is also safe, only slower.
+
+// spill and its own dependencies come along for JsonlStorage, which encodes
+// with spill::pending_json; most of the three modules is dead here.
+#[allow(dead_code, reason = "shared with the pipeline binaries; only storage is used here")]
+mod adapters;
+#[allow(dead_code, reason = "needed by the spill tests; unused by this binary")]
+mod orchestrator;
+#[allow(dead_code, reason = "only the spill-file encoding is used by this binary")]
+mod spill;
+
+// Load the storage adapters into this binary's module tree directly, as
+// main_sqlite.rs does for sqlite_storage.
+#[path = "adapters/jsonl_storage.rs"]
+mod jsonl_storage;
+#[allow(dead_code, reason = "rescore and verification are used by fraud_detection_sqlite only")]
+#[path = "adapters/sqlite_storage.rs"]
+mod sqlite_storage;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use domain::{
+    PendingTransaction, Storage, StorageError, StorageRead, StoredTransaction, TransactionId,
+};
+use jsonl_storage::JsonlStorage;
+use sqlite_storage::SqliteStorage;
+
+/// `tracing` target of every migration event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::STORAGE;
+
+/// Rows per page when `--page-size` is not given.
+const DEFAULT_PAGE_SIZE: usize = 500;
+
+// ---------------------------------------------------------------------------
+// Arguments
+// ---------------------------------------------------------------------------
+
+/// A storage backend and where it lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Spec {
+    Sqlite(PathBuf),
+    Jsonl(PathBuf),
+}
+
+impl Spec {
+    fn parse(spec: &str) -> anyhow::Result<Self> {
+        let lower = spec.to_ascii_lowercase();
+        if lower.starts_with("postgres:") || lower.starts_with("postgresql:") {
+            anyhow::bail!("{spec:?}: there is no Postgres storage adapter in this tree");
+        }
+        let path = |path: &str| {
+            anyhow::ensure!(!path.is_empty(), "{spec:?} requires a path");
+            Ok(PathBuf::from(path))
+        };
+        if let Some(rest) = spec.strip_prefix("sqlite:") {
+            return path(rest).map(Self::Sqlite);
+        }
+        if let Some(rest) = spec.strip_prefix("jsonl:") {
+            return path(rest).map(Self::Jsonl);
+        }
+        let extension = Path::new(spec).extension().and_then(|e| e.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("db" | "sqlite" | "sqlite3") => path(spec).map(Self::Sqlite),
+            Some("jsonl") => path(spec).map(Self::Jsonl),
+            _ => anyhow::bail!(
+                "unknown storage {spec:?} (expected sqlite:PATH, jsonl:PATH, \
+                 or a .db, .sqlite or .jsonl path)"
+            ),
+        }
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            Self::Sqlite(path) | Self::Jsonl(path) => path,
+        }
+    }
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sqlite(path) => write!(f, "sqlite:{}", path.display()),
+            Self::Jsonl(path) => write!(f, "jsonl:{}", path.display()),
+        }
+    }
+}
+
+/// How the copy loop runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MigrateOptions {
+    /// Rows per source page, and at most per destination batch; `>= 1`.
+    page_size: usize,
+    /// Skip source rows up to and including this id.
+    start_after: Option<TransactionId>,
+    /// Read and reconcile, but write nothing.
+    dry_run: bool,
+}
+
+impl Default for MigrateOptions {
+    fn default() -> Self {
+        Self { page_size: DEFAULT_PAGE_SIZE, start_after: None, dry_run: false }
+    }
+}
+
+/// Parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MigrateArgs {
+    from: Spec,
+    to: Spec,
+    options: MigrateOptions,
+}
+
+impl MigrateArgs {
+    /// Parse `args` (program name excluded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown option, a missing or malformed value,
+    /// a missing `--from` or `--to`, a zero `--page-size`, or a source that is
+    /// also the destination.
+    fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let (mut from, mut to) = (None, None);
+        let mut options = MigrateOptions::default();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            if flag == "--dry-run" {
+                options.dry_run = true;
+                continue;
+            }
+            let value = args.next().with_context(|| format!("{flag} requires a value"))?;
+            let context = || format!("invalid value {value:?} for {flag}");
+            match flag.as_str() {
+                "--from" => from = Some(Spec::parse(&value)?),
+                "--to" => to = Some(Spec::parse(&value)?),
+                "--page-size" => {
+                    options.page_size = value.parse().with_context(context)?;
+                    anyhow::ensure!(options.page_size > 0, "--page-size must be >= 1");
+                }
+                "--start-after" => options.start_after = Some(value.parse().with_context(context)?),
+                _ => anyhow::bail!("unknown option {flag:?}"),
+            }
+        }
+        let from = from.context("--from is required")?;
+        let to = to.context("--to is required")?;
+        anyhow::ensure!(
+            from.path() != to.path(),
+            "--from and --to are both {}",
+            from.path().display()
+        );
+        Ok(Self { from, to, options })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Backends
+// ---------------------------------------------------------------------------
+
+/// One of the storage backends a spec can name.
+#[derive(Debug)]
+enum Backend {
+    Sqlite(SqliteStorage),
+    Jsonl(JsonlStorage),
+}
+
+impl Backend {
+    /// Open `spec` as a source; it must exist. A `SQLite` source is opened
+    /// read-only and left exactly as it was, schema included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is missing or cannot be opened.
+    async fn open_source(spec: &Spec) -> anyhow::Result<Self> {
+        anyhow::ensure!(spec.path().exists(), "source {spec} does not exist");
+        Self::open(spec, true).await
+    }
+
+    /// Open `spec`, creating and migrating it if needed. Under `read_only` a
+    /// `SQLite` file is neither created nor migrated: an existing one is
+    /// opened read-only, and an empty in-memory database stands in for a
+    /// missing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or created.
+    async fn open(spec: &Spec, read_only: bool) -> anyhow::Result<Self> {
+        match spec {
+            Spec::Sqlite(path) => {
+                let storage = match (read_only, path.exists()) {
+                    (false, _) => SqliteStorage::new(&format!("sqlite:{}", path.display())).await,
+                    (true, true) => SqliteStorage::open_read_only(path).await,
+                    (true, false) => SqliteStorage::new("sqlite::memory:").await,
+                };
+                let storage = storage.with_context(|| format!("failed to open {spec}"))?;
+                Ok(Self::Sqlite(storage))
+            }
+            Spec::Jsonl(path) => Ok(Self::Jsonl(
+                JsonlStorage::open(path).with_context(|| format!("failed to open {spec}"))?,
+            )),
+        }
+    }
+}
+
+impl Storage for Backend {
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        match self {
+            Self::Sqlite(storage) => storage.write_batch(batch).await,
+            Self::Jsonl(storage) => storage.write_batch(batch).await,
+        }
+    }
+}
+
+impl StorageRead for Backend {
+    async fn read_page(
+        &self,
+        after: u64,
+        limit: usize,
+    ) -> Result<Vec<StoredTransaction>, StorageError> {
+        match self {
+            Self::Sqlite(storage) => storage.read_page(after, limit).await,
+            Self::Jsonl(storage) => storage.read_page(after, limit).await,
+        }
+    }
+
+    async fn all_ids(&self, limit: usize) -> Result<Vec<TransactionId>, StorageError> {
+        match self {
+            Self::Sqlite(storage) => storage.all_ids(limit).await,
+            Self::Jsonl(storage) => storage.all_ids(limit).await,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Copy loop
+// ---------------------------------------------------------------------------
+
+/// What a migration did, for the final report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Reconciliation {
+    /// Source rows read, the skipped ones included.
+    read: u64,
+    /// Rows up to and including the `--start-after` id.
+    skipped: u64,
+    /// Rows written (or, in a dry run, that would have been).
+    written: u64,
+    /// Rows left out because the destination already held their id, or an
+    /// earlier source row had it.
+    duplicates: u64,
+    /// Destination rows before the copy.
+    destination_before: u64,
+    /// Destination rows after the copy; equal to `destination_before` in a
+    /// dry run.
+    destination_after: u64,
+    /// Last source id fully handled; resume with `--start-after` it.
+    last_id: Option<TransactionId>,
+    dry_run: bool,
+}
+
+impl Reconciliation {
+    /// Whether the destination grew by exactly the rows written.
+    fn reconciled(&self) -> bool {
+        let expected = if self.dry_run { 0 } else { self.written };
+        self.destination_after == self.destination_before + expected
+    }
+}
+
+impl fmt::Display for Reconciliation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run { "would write" } else { "written" };
+        write!(
+            f,
+            "migrate: {} source rows read ({} before the cursor), {} {verb}, {} duplicates\n\
+             migrate: destination {} -> {} rows, {}",
+            self.read,
+            self.skipped,
+            self.written,
+            self.duplicates,
+            self.destination_before,
+            self.destination_after,
+            if self.reconciled() { "reconciled" } else { "MISMATCH" },
+        )
+    }
+}
+
+/// Why a migration stopped early.
+#[derive(Debug)]
+enum MigrateError {
+    /// A read or write failed; `progress` covers the pages done before it.
+    Interrupted { progress: Reconciliation, error: StorageError },
+    /// The source holds no row with the `--start-after` id; nothing was written.
+    CursorNotFound(TransactionId),
+}
+
+impl fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interrupted { progress, error } => {
+                let written = progress.written;
+                write!(f, "migration interrupted after {written} rows written: {error}")?;
+                match progress.last_id {
+                    Some(id) => write!(f, "; resume with --start-after {id:#}"),
+                    None => write!(f, "; rerun from the start"),
+                }
+            }
+            Self::CursorNotFound(id) => write!(f, "--start-after {id:#} is not in the source"),
+        }
+    }
+}
+
+impl std::error::Error for MigrateError {}
+
+/// Copy every row of `source` missing from `destination`, one page per batch.
+///
+/// # Errors
+///
+/// Returns [`MigrateError::Interrupted`] on the first failed read or write;
+/// pages before it are in `destination`. Returns
+/// [`MigrateError::CursorNotFound`] when `options.start_after` is never met.
+async fn migrate<S: StorageRead, D: Storage + StorageRead>(
+    source: &S,
+    destination: &D,
+    options: &MigrateOptions,
+) -> Result<Reconciliation, MigrateError> {
+    let mut progress = Reconciliation { dry_run: options.dry_run, ..Reconciliation::default() };
+    let interrupted = |progress, error| MigrateError::Interrupted { progress, error };
+    let existing =
+        destination.all_ids(usize::MAX).await.map_err(|e| interrupted(progress, e))?;
+    progress.destination_before = existing.len() as u64;
+    let mut seen: HashSet<TransactionId> = existing.into_iter().collect();
+
+    let mut cursor_met = options.start_after.is_none();
+    let mut after = 0;
+    loop {
+        let page = source
+            .read_page(after, options.page_size)
+            .await
+            .map_err(|e| interrupted(progress, e))?;
+        let Some(last) = page.last() else { break };
+        after = last.position;
+
+        // Counted into a copy first: a failed write leaves `progress` as it
+        // was after the previous page.
+        let mut next = progress;
+        let mut fresh = Vec::new();
+        let mut fresh_ids = Vec::new();
+        for stored in page {
+            let id = stored.pending.id();
+            next.read += 1;
+            if !cursor_met {
+                next.skipped += 1;
+                cursor_met = options.start_after == Some(id);
+                continue;
+            }
+            next.last_id = Some(id);
+            if seen.contains(&id) || fresh_ids.contains(&id) {
+                next.duplicates += 1;
+            } else {
+                fresh_ids.push(id);
+                fresh.push(stored.pending);
+            }
+        }
+        next.written += fresh.len() as u64;
+        if !options.dry_run && !fresh.is_empty() {
+            destination.write_batch(fresh).await.map_err(|e| interrupted(progress, e))?;
+        }
+        seen.extend(fresh_ids);
+        progress = next;
+        tracing::info!(
+            target: TARGET,
+            read = progress.read,
+            written = progress.written,
+            duplicates = progress.duplicates,
+            last_id = ?progress.last_id,
+            "migrate.progress"
+        );
+    }
+    if let Some(id) = options.start_after.filter(|_| !cursor_met) {
+        return Err(MigrateError::CursorNotFound(id));
+    }
+
+    progress.destination_after = if options.dry_run {
+        progress.destination_before
+    } else {
+        destination.all_ids(usize::MAX).await.map_err(|e| interrupted(progress, e))?.len() as u64
+    };
+    Ok(progress)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = MigrateArgs::parse(std::env::args().skip(1))?;
+    let source = Backend::open_source(&args.from).await?;
+    let destination = Backend::open(&args.to, args.options.dry_run).await?;
+    tracing::info!(
+        target: TARGET,
+        from = %args.from,
+        to = %args.to,
+        options = ?args.options,
+        "migrate.start"
+    );
+
+    let reconciliation = migrate(&source, &destination, &args.options).await?;
+    println!("{reconciliation}");
+    anyhow::ensure!(
+        reconciliation.reconciled(),
+        "{} does not hold the rows written; inspect it before retrying",
+        args.to
+    );
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, MigrateArgs, MigrateError, MigrateOptions, Spec, migrate};
+    use crate::adapters::in_memory_storage::InMemoryStorage;
+    use domain::{
+        Currency, InferredTransaction, PendingTransaction, Storage, StorageError, StorageRead as _,
+        Transaction, TransactionId,
+    };
+    use std::cell::Cell;
+    use std::path::PathBuf;
+
+    fn parse(args: &[&str]) -> anyhow::Result<MigrateArgs> {
+        MigrateArgs::parse(args.iter().map(|&a| a.to_owned()))
+    }
+
+    fn id(n: u128) -> TransactionId {
+        TransactionId::from_uuid(uuid::Uuid::from_u128(n))
+    }
+
+    fn pending(n: u128) -> PendingTransaction {
+        PendingTransaction::new(InferredTransaction::new(
+            Transaction {
+                id: id(n),
+                amount: 10.0,
+                last_name: "Doe".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            n.is_multiple_of(3),
+            "DEMO",
+            "4",
+        ))
+    }
+
+    async fn storage_with(ids: impl IntoIterator<Item = u128>) -> InMemoryStorage {
+        let storage = InMemoryStorage::new(1_000);
+        storage.write_batch(ids.into_iter().map(pending).collect()).await.unwrap();
+        storage
+    }
+
+    fn options(page_size: usize) -> MigrateOptions {
+        MigrateOptions { page_size, ..MigrateOptions::default() }
+    }
+
+    /// Storage whose `fail_on`-th write fails, until `fail_on` is reset.
+    struct FailingStorage {
+        inner: InMemoryStorage,
+        writes: Cell<u32>,
+        fail_on: Cell<u32>,
+    }
+
+    impl Storage for FailingStorage {
+        async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            self.writes.set(self.writes.get() + 1);
+            if self.writes.get() == self.fail_on.get() {
+                return Err(StorageError::Unavailable);
+            }
+            self.inner.write_batch(batch).await
+        }
+    }
+
+    impl domain::StorageRead for FailingStorage {
+        async fn read_page(
+            &self,
+            after: u64,
+            limit: usize,
+        ) -> Result<Vec<domain::StoredTransaction>, StorageError> {
+            self.inner.read_page(after, limit).await
+        }
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("migrate-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // ------------------------------------------------------------------
+    // MG-T01: argument parsing
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn parses_prefixed_and_bare_specs() {
+        assert_eq!(Spec::parse("sqlite:a.bin").unwrap(), Spec::Sqlite("a.bin".into()));
+        assert_eq!(Spec::parse("jsonl:spill/x.txt").unwrap(), Spec::Jsonl("spill/x.txt".into()));
+        assert_eq!(Spec::parse("old.db").unwrap(), Spec::Sqlite("old.db".into()));
+        assert_eq!(Spec::parse("old.SQLite3").unwrap(), Spec::Sqlite("old.SQLite3".into()));
+        assert_eq!(Spec::parse("spill-1.jsonl").unwrap(), Spec::Jsonl("spill-1.jsonl".into()));
+        assert_eq!(Spec::parse("jsonl:x.jsonl").unwrap().to_string(), "jsonl:x.jsonl");
+    }
+
+    #[test]
+    fn postgres_spec_names_the_missing_adapter() {
+        let err = Spec::parse("postgres://user@host/fraud").unwrap_err();
+        assert!(err.to_string().contains("no Postgres storage adapter"), "{err}");
+    }
+
+    #[test]
+    fn defaults_and_every_option() {
+        let args = parse(&["--from", "a.db", "--to", "jsonl:b.jsonl"]).unwrap();
+        assert_eq!(args.from, Spec::Sqlite("a.db".into()));
+        assert_eq!(args.to, Spec::Jsonl("b.jsonl".into()));
+        assert_eq!(args.options, MigrateOptions::default());
+
+        let args = parse(&[
+            "--dry-run",
+            "--start-after",
+            &id(7).full(),
+            "--to",
+            "b.db",
+            "--page-size",
+            "50",
+            "--from",
+            "a.jsonl",
+        ])
+        .unwrap();
+        let expected = MigrateOptions { page_size: 50, start_after: Some(id(7)), dry_run: true };
+        assert_eq!(args.options, expected);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        for bad in [
+            &[][..],
+            &["--from", "a.db"],
+            &["--to", "a.db"],
+            &["--from", "a.db", "--to", "sqlite:a.db"],
+            &["--from", "a.db", "--to", "b.db", "--page-size", "0"],
+            &["--from", "a.db", "--to", "b.db", "--page-size"],
+            &["--from", "a.db", "--to", "b.db", "--start-after", "not-an-id"],
+            &["--from", "a.csv", "--to", "b.db"],
+            &["--from", "sqlite:", "--to", "b.db"],
+            &["--from", "postgresql://h/db", "--to", "b.db"],
+            &["--from", "a.db", "--to", "b.db", "--verbose", "1"],
+        ] {
+            if let Ok(args) = parse(bad) {
+                panic!("{bad:?} must be rejected, got {args:?}");
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // MG-T02: copy loop
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn copies_every_row_in_source_order_across_pages() {
+        let source = storage_with(1..=10).await;
+        let destination = InMemoryStorage::new(1_000);
+
+        let report = migrate(&source, &destination, &options(3)).await.unwrap();
+
+        assert_eq!((report.read, report.written, report.duplicates), (10, 10, 0));
+        assert_eq!((report.destination_before, report.destination_after), (0, 10));
+        assert_eq!(report.last_id, Some(id(10)));
+        assert!(report.reconciled());
+        assert_eq!(destination.all_ids(100).await.unwrap(), source.all_ids(100).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rows_already_in_the_destination_are_counted_as_duplicates() {
+        // id 4 appears twice in the source; ids 2 and 5 are already copied.
+        let source = storage_with([1, 2, 3, 4, 4, 5, 6]).await;
+        let destination = storage_with([2, 5]).await;
+
+        let report = migrate(&source, &destination, &options(2)).await.unwrap();
+
+        assert_eq!((report.read, report.written, report.duplicates), (7, 4, 3));
+        assert_eq!((report.destination_before, report.destination_after), (2, 6));
+        assert!(report.reconciled(), "{report}");
+        let ids = destination.all_ids(100).await.unwrap();
+        assert_eq!(ids, [2, 5, 1, 3, 4, 6].map(id));
+    }
+
+    #[tokio::test]
+    async fn dry_run_reconciles_without_writing() {
+        let source = storage_with(1..=5).await;
+        let destination = storage_with([3]).await;
+        let options = MigrateOptions { dry_run: true, ..options(2) };
+
+        let report = migrate(&source, &destination, &options).await.unwrap();
+
+        assert_eq!((report.written, report.duplicates), (4, 1));
+        assert_eq!((report.destination_before, report.destination_after), (1, 1));
+        assert!(report.reconciled());
+        assert!(report.to_string().contains("4 would write"), "{report}");
+        assert_eq!(destination.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn interrupted_copy_resumes_after_the_reported_id() {
+        let source = storage_with(1..=7).await;
+        let destination = FailingStorage {
+            inner: InMemoryStorage::new(1_000),
+            writes: Cell::new(0),
+            fail_on: Cell::new(3),
+        };
+
+        let Err(err) = migrate(&source, &destination, &options(2)).await else {
+            panic!("the third write must interrupt the copy");
+        };
+        let MigrateError::Interrupted { progress, error } = &err else { panic!("{err}") };
+        assert_eq!(*error, StorageError::Unavailable);
+        assert_eq!((progress.read, progress.written, progress.last_id), (4, 4, Some(id(4))));
+        assert!(err.to_string().ends_with(&format!("--start-after {}", id(4).full())), "{err}");
+
+        destination.fail_on.set(0);
+        let resume = MigrateOptions { start_after: progress.last_id, ..options(2) };
+        let report = migrate(&source, &destination, &resume).await.unwrap();
+
+        assert_eq!((report.read, report.skipped, report.written), (7, 4, 3));
+        assert_eq!(report.duplicates, 0);
+        assert_eq!((report.destination_before, report.destination_after), (4, 7));
+        let ids = destination.inner.all_ids(100).await.unwrap();
+        assert_eq!(ids, (1..=7).map(id).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn cursor_missing_from_the_source_is_rejected() {
+        let source = storage_with(1..=3).await;
+        let destination = InMemoryStorage::new(1_000);
+        let options = MigrateOptions { start_after: Some(id(9)), ..options(2) };
+
+        let err = migrate(&source, &destination, &options).await.unwrap_err();
+
+        assert!(matches!(err, MigrateError::CursorNotFound(cursor) if cursor == id(9)));
+        assert_eq!(destination.len(), 0);
+    }
+
+    // ------------------------------------------------------------------
+    // MG-T03: file-backed backends
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn drains_a_spill_file_into_sqlite_and_back() {
+        let dir = temp_dir();
+        let spill = Spec::Jsonl(dir.join("spill.jsonl"));
+        let db = Spec::Sqlite(dir.join("out.db"));
+        let copy = Spec::Jsonl(dir.join("copy.jsonl"));
+        let jsonl = Backend::open(&spill, false).await.unwrap();
+        jsonl.write_batch((1..=5).map(pending).collect()).await.unwrap();
+
+        let source = Backend::open_source(&spill).await.unwrap();
+        let sqlite = Backend::open(&db, false).await.unwrap();
+        let report = migrate(&source, &sqlite, &options(2)).await.unwrap();
+        assert_eq!((report.written, report.destination_after), (5, 5));
+
+        let back = Backend::open(&copy, false).await.unwrap();
+        migrate(&Backend::open_source(&db).await.unwrap(), &back, &options(4)).await.unwrap();
+        let reopened = Backend::open_source(&copy).await.unwrap();
+        assert_eq!(reopened.all_ids(10).await.unwrap(), (1..=5).map(id).collect::<Vec<_>>());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn dry_run_does_not_create_a_missing_database() {
+        let dir = temp_dir();
+        let db = Spec::Sqlite(dir.join("new.db"));
+
+        let destination = Backend::open(&db, true).await.unwrap();
+
+        assert!(destination.all_ids(10).await.unwrap().is_empty());
+        assert!(!db.path().exists());
+        Backend::open_source(&db).await.unwrap_err();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Create a pre-versioning (v1) database at `path` holding `rows` rows.
+    async fn v1_database(path: &std::path::Path, rows: u128) {
+        use sqlx::Connection as _;
+        let opts = sqlx::sqlite::SqliteConnectOptions::new().filename(path).create_if_missing(true);
+        let mut conn = sqlx::sqlite::SqliteConnection::connect_with(&opts).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE pending_transactions (
+                id TEXT PRIMARY KEY, amount REAL NOT NULL, last_name TEXT NOT NULL,
+                predicted_fraud INTEGER NOT NULL, model_name TEXT NOT NULL,
+                model_version TEXT NOT NULL, is_reviewed INTEGER NOT NULL DEFAULT 0,
+                actual_fraud INTEGER
+            )",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        for n in 1..=rows {
+            sqlx::query(
+                "INSERT INTO pending_transactions
+                 (id, amount, last_name, predicted_fraud, model_name, model_version)
+                 VALUES (?, 10.0, 'Doe', 0, 'DEMO', '3')",
+            )
+            .bind(format!("{:#}", id(n)))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        }
+        conn.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dry_run_leaves_older_databases_byte_identical() {
+        let dir = temp_dir();
+        let (from, to) = (dir.join("old.db"), dir.join("older.db"));
+        v1_database(&from, 3).await;
+        v1_database(&to, 1).await;
+        let before = (std::fs::read(&from).unwrap(), std::fs::read(&to).unwrap());
+
+        let source = Backend::open_source(&Spec::Sqlite(from.clone())).await.unwrap();
+        let destination = Backend::open(&Spec::Sqlite(to.clone()), true).await.unwrap();
+        let options = MigrateOptions { dry_run: true, ..options(2) };
+        let report = migrate(&source, &destination, &options).await.unwrap();
+        drop((source, destination));
+
+        assert_eq!((report.read, report.written, report.duplicates), (3, 2, 1));
+        assert_eq!((std::fs::read(&from).unwrap(), std::fs::read(&to).unwrap()), before);
+        let names: Vec<_> =
+            std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names.len(), 2, "no journal left behind: {names:?}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,346 @@
+// Rust guideline compliant 2026-02-27
+
+//! `--model`: pick the `Model` adapter at startup instead of in source.
+//!
+//! [`ModelSpec::from_args`] parses `--model demo[:seed]|bench|onnx:<path>|http:<url>`
+//! (default `demo`), checking each variant's argument, and
+//! [`ModelBackend::from_spec`] builds the adapter. [`ModelBackend`] implements
+//! the `Model` port by enum dispatch, so `Modelizer<ModelBackend>` and the
+//! stages wired to it stay generic and monomorphic.
+//!
+//! `onnx` and `http` specs are validated but rejected when building the
+//! backend: no ONNX or HTTP model adapter exists yet. Each will get a
+//! feature-gated variant when it lands.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use domain::{Model, ModelVersion, ModelizerError, Transaction};
+
+use crate::adapters::bench_model::BenchModel;
+use crate::adapters::demo_model::DemoModel;
+
+// ---------------------------------------------------------------------------
+// ModelSpec
+// ---------------------------------------------------------------------------
+
+/// A parsed, validated `--model` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelSpec {
+    /// `demo` or `demo:<seed>`: [`DemoModel`], OS-seeded unless a seed is given.
+    Demo {
+        /// RNG seed for a reproducible run.
+        seed: Option<u64>,
+    },
+    /// `bench`: [`BenchModel`], never flags a transaction.
+    Bench,
+    /// `onnx:<path>`: an existing `.onnx` model file.
+    Onnx {
+        /// Path to the model file.
+        path: PathBuf,
+    },
+    /// `http:<url>`: a remote model served over `http://` or `https://`.
+    Http {
+        /// Endpoint URL, scheme included.
+        url: String,
+    },
+}
+
+impl Default for ModelSpec {
+    fn default() -> Self {
+        Self::Demo { seed: None }
+    }
+}
+
+impl ModelSpec {
+    /// Parse one `--model` value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown backend, a seed that is not a `u64`, an
+    /// `onnx` path that is not an existing `.onnx` file, or an `http` URL
+    /// without an `http://`/`https://` scheme and a host.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let (backend, arg) = match spec.split_once(':') {
+            Some((backend, arg)) => (backend, Some(arg)),
+            None => (spec, None),
+        };
+        match (backend, arg) {
+            ("demo", None) => Ok(Self::Demo { seed: None }),
+            ("demo", Some(seed)) => {
+                let seed = seed
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("--model demo:{seed}: seed must be a u64 ({e})"))?;
+                Ok(Self::Demo { seed: Some(seed) })
+            }
+            ("bench", None) => Ok(Self::Bench),
+            ("bench", Some(_)) => anyhow::bail!("--model bench takes no argument"),
+            ("onnx", arg) => {
+                let path = PathBuf::from(arg.unwrap_or_default());
+                anyhow::ensure!(
+                    path.extension().is_some_and(|ext| ext == "onnx"),
+                    "--model onnx:<path>: expected a .onnx file, got {:?}",
+                    path.display().to_string()
+                );
+                anyhow::ensure!(path.is_file(), "--model onnx: no such file {}", path.display());
+                Ok(Self::Onnx { path })
+            }
+            ("http", arg) => {
+                let url = arg.unwrap_or_default();
+                let host = url
+                    .strip_prefix("http://")
+                    .or_else(|| url.strip_prefix("https://"))
+                    .map(|rest| rest.split('/').next().unwrap_or_default());
+                anyhow::ensure!(
+                    host.is_some_and(|h| !h.is_empty()) && !url.contains(char::is_whitespace),
+                    "--model http:<url>: expected http://host[/path] or https://..., got {url:?}"
+                );
+                Ok(Self::Http { url: url.to_owned() })
+            }
+            _ => anyhow::bail!(
+                "unknown --model {spec:?} (expected demo[:seed], bench, onnx:<path> or http:<url>)"
+            ),
+        }
+    }
+
+    /// Find `--model <spec>` or `--model=<spec>` in `args`; [`Default`] when absent.
+    ///
+    /// Other arguments are ignored; each binary handles its own flags.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `--model` has no value or the value fails [`parse`](Self::parse).
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--model" {
+                let Some(spec) = args.next() else { anyhow::bail!("--model requires a value") };
+                return Self::parse(&spec);
+            }
+            if let Some(spec) = arg.strip_prefix("--model=") {
+                return Self::parse(spec);
+            }
+        }
+        Ok(Self::default())
+    }
+}
+
+impl fmt::Display for ModelSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Demo { seed: None } => f.write_str("demo"),
+            Self::Demo { seed: Some(seed) } => write!(f, "demo:{seed}"),
+            Self::Bench => f.write_str("bench"),
+            Self::Onnx { path } => write!(f, "onnx:{}", path.display()),
+            Self::Http { url } => write!(f, "http:{url}"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ModelBackend
+// ---------------------------------------------------------------------------
+
+/// `Model` adapter selected at startup, dispatching to the wrapped adapter.
+#[derive(Debug)]
+pub enum ModelBackend {
+    /// Probabilistic DEMO model (~4% fraud at version N).
+    Demo(DemoModel),
+    /// Zero-overhead model that never flags fraud.
+    Bench(BenchModel),
+}
+
+impl ModelBackend {
+    /// Build the adapter described by `spec`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for `onnx` and `http` specs: no such adapter in this build.
+    pub fn from_spec(spec: &ModelSpec) -> anyhow::Result<Self> {
+        match spec {
+            ModelSpec::Demo { seed } => Ok(Self::Demo(DemoModel::new(*seed))),
+            ModelSpec::Bench => Ok(Self::Bench(BenchModel::new())),
+            ModelSpec::Onnx { .. } | ModelSpec::Http { .. } => {
+                anyhow::bail!("--model {spec}: no such model adapter in this build")
+            }
+        }
+    }
+}
+
+impl Model for ModelBackend {
+    async fn classify(&self, tx: &Transaction) -> Result<bool, ModelizerError> {
+        match self {
+            Self::Demo(model) => model.classify(tx).await,
+            Self::Bench(model) => model.classify(tx).await,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Demo(model) => model.name(),
+            Self::Bench(model) => model.name(),
+        }
+    }
+
+    fn active_version(&self) -> &str {
+        match self {
+            Self::Demo(model) => model.active_version(),
+            Self::Bench(model) => model.active_version(),
+        }
+    }
+
+    async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+        match self {
+            Self::Demo(model) => model.switch_version(version).await,
+            Self::Bench(model) => model.switch_version(version).await,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{ModelBackend, ModelSpec};
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::Model as _;
+    use logger::{Logger, LoggerConfig};
+    use modelizer::Modelizer;
+    use pipeline::Pipeline;
+    use pipeline::memory::{CountingAlarm, MemoryBuffer, MemoryStorage};
+    use producer::{Producer, ProducerConfig};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn args(list: &[&str]) -> anyhow::Result<ModelSpec> {
+        ModelSpec::from_args(list.iter().map(|s| (*s).to_owned()))
+    }
+
+    fn parse_error(spec: &str) -> String {
+        ModelSpec::parse(spec).unwrap_err().to_string()
+    }
+
+    // MB-T01: no --model means the OS-seeded DEMO model.
+    #[test]
+    fn missing_flag_defaults_to_demo() {
+        assert_eq!(args(&["--check"]).unwrap(), ModelSpec::Demo { seed: None });
+    }
+
+    // MB-T02: every backend parses, in both flag forms.
+    #[test]
+    fn parses_every_backend() {
+        let file = std::env::temp_dir().join(format!("model-{}.onnx", uuid::Uuid::new_v4()));
+        std::fs::write(&file, b"").unwrap();
+        let onnx = format!("onnx:{}", file.display());
+
+        assert_eq!(args(&["--model", "demo"]).unwrap(), ModelSpec::Demo { seed: None });
+        assert_eq!(args(&["--model=demo:42"]).unwrap(), ModelSpec::Demo { seed: Some(42) });
+        assert_eq!(args(&["--check", "--model", "bench"]).unwrap(), ModelSpec::Bench);
+        assert_eq!(args(&["--model", &onnx]).unwrap(), ModelSpec::Onnx { path: file.clone() });
+        assert_eq!(
+            args(&["--model", "http:https://models.local:8080/score"]).unwrap(),
+            ModelSpec::Http { url: "https://models.local:8080/score".to_owned() }
+        );
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    // MB-T03: parsing round-trips through Display.
+    #[test]
+    fn display_round_trips() {
+        for spec in ["demo", "demo:7", "bench", "http:http://127.0.0.1/infer"] {
+            assert_eq!(ModelSpec::parse(spec).unwrap().to_string(), spec);
+        }
+    }
+
+    // MB-T04: unknown backends, bad seeds and stray arguments are rejected.
+    #[test]
+    fn rejects_unknown_backends_and_bad_arguments() {
+        assert!(parse_error("tensorflow").contains("unknown --model"));
+        assert!(parse_error("").contains("unknown --model"));
+        assert!(parse_error("demo:-1").contains("seed must be a u64"));
+        assert!(parse_error("demo:").contains("seed must be a u64"));
+        assert!(parse_error("bench:3").contains("takes no argument"));
+        assert!(args(&["--model"]).unwrap_err().to_string().contains("requires a value"));
+    }
+
+    // MB-T05: onnx needs an existing .onnx file.
+    #[test]
+    fn rejects_bad_onnx_paths() {
+        let missing = std::env::temp_dir().join(format!("missing-{}.onnx", uuid::Uuid::new_v4()));
+
+        assert!(parse_error("onnx").contains("expected a .onnx file"));
+        assert!(parse_error("onnx:").contains("expected a .onnx file"));
+        assert!(parse_error("onnx:model.pt").contains("expected a .onnx file"));
+        let error = parse_error(&format!("onnx:{}", missing.display()));
+        assert!(error.contains("no such file"), "{error}");
+    }
+
+    // MB-T06: http needs an http(s) scheme and a host.
+    #[test]
+    fn rejects_bad_http_urls() {
+        for url in ["http", "http:", "http:models.local", "http:ftp://host", "http:http://"] {
+            assert!(parse_error(url).contains("expected http://host"), "{url}");
+        }
+        assert!(parse_error("http:http://host/a b").contains("expected http://host"));
+    }
+
+    // MB-T07: demo and bench build; onnx and http have no adapter yet.
+    #[test]
+    fn builds_only_the_available_backends() {
+        let demo = ModelBackend::from_spec(&ModelSpec::Demo { seed: Some(1) }).unwrap();
+        assert_eq!((demo.name(), demo.active_version()), ("DEMO", "4"));
+        let bench = ModelBackend::from_spec(&ModelSpec::Bench).unwrap();
+        assert_eq!((bench.name(), bench.active_version()), ("BENCH", "1"));
+
+        let onnx = ModelSpec::Onnx { path: PathBuf::from("model.onnx") };
+        let error = ModelBackend::from_spec(&onnx).unwrap_err().to_string();
+        assert!(error.contains("no such model adapter"), "{error}");
+        let http = ModelSpec::Http { url: "http://host".to_owned() };
+        ModelBackend::from_spec(&http).unwrap_err();
+    }
+
+    // MB-T08: a 3-iteration run with --model bench flags nothing.
+    #[tokio::test]
+    async fn bench_backend_run_flags_no_fraud() {
+        let spec = args(&["--model", "bench"]).unwrap();
+        let modelizer = Modelizer::new(ModelBackend::from_spec(&spec).unwrap());
+        let pipeline = Pipeline::new(
+            Producer::new(
+                ProducerConfig::builder(20)
+                    .poll_interval1(Duration::ZERO)
+                    .iterations(3)
+                    .seed(1)
+                    .build()
+                    .unwrap(),
+            ),
+            Consumer::new(
+                ConsumerConfig::builder(20).poll_interval2(Duration::ZERO).seed(2).build().unwrap(),
+            ),
+            Logger::new(
+                LoggerConfig::builder(20).poll_interval3(Duration::ZERO).seed(3).build().unwrap(),
+            ),
+        );
+        let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+        let (alarm, storage) = (CountingAlarm::new(), MemoryStorage::new());
+
+        let stops = pipeline.run(&buffer1, &modelizer, &alarm, &buffer2, &storage).await.unwrap();
+        let report = pipeline.report(storage.len(), stops);
+
+        assert!(report.produced > 0);
+        assert_eq!(report.persisted, report.produced);
+        assert_eq!(report.flagged, 0);
+        assert_eq!(alarm.count(), 0);
+        assert!(storage.items().iter().all(|p| p.inferred_transaction.model_name == "BENCH"));
+    }
+
+    // MB-T09: every available backend returns one result per input, in order.
+    #[tokio::test]
+    async fn available_backends_keep_the_modelizer_contract() {
+        for spec in [ModelSpec::Demo { seed: Some(3) }, ModelSpec::Bench] {
+            let modelizer = Modelizer::new(ModelBackend::from_spec(&spec).unwrap());
+            test_support::contract::modelizer_one_result_per_input(&modelizer).await;
+        }
+    }
+}
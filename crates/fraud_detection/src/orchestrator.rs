@@ -0,0 +1,1266 @@
+// Rust guideline compliant 2026-02-27
+
+//! Stage supervision for the pipeline binaries.
+//!
+//! [`supervise`] runs a stage's `run` loop and, when it fails with a
+//! retryable error, restarts it after an exponential backoff up to
+//! [`RestartPolicy::max_restarts`] times. Fatal errors are returned
+//! immediately so the caller can abort the whole pipeline.
+//!
+//! [`shutdown_gracefully`] turns a shutdown signal into a close of the first
+//! buffer and keeps awaiting the pipeline so both buffers drain, bounded by a
+//! grace period.
+//!
+//! [`on_capacity`] applies a [`CapacityPolicy`] to the Logger's result: a full
+//! storage either fails the pipeline or, like a shutdown signal, closes the
+//! first buffer so the stages upstream drain and stop.
+//!
+//! [`Watchdog`] follows the stages' `PipelineEvent`s and reports a stage that
+//! stopped completing batches while its input buffer holds items, so a
+//! deadlocked pipeline fails with a diagnostic instead of hanging.
+//!
+//! [`StageTask`] runs one stage as its own task, from a closure over owned
+//! (`Rc`) stages and adapters, so a single stage can be aborted, restarted or
+//! found to have panicked while the others keep running. `fraud_detection`
+//! runs its three stages this way and fails the run when one panics.
+
+use consumer::ConsumerError;
+use domain::{BufferDepth, ErrorChain, PipelineEvent, Stage, StopReason, StorageError};
+use logger::LoggerError;
+use producer::ProducerError;
+use reviewer::ReviewerError;
+use std::any::Any;
+use std::fmt;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::oneshot;
+use tokio::task::{JoinError, JoinHandle};
+
+/// `tracing` target of every orchestrator event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ORCHESTRATOR;
+
+// ---------------------------------------------------------------------------
+// Retryable
+// ---------------------------------------------------------------------------
+
+/// Stage error that can be classified as retryable or fatal.
+///
+/// Forwards to the inherent `is_retryable` of each component error so
+/// [`supervise`] stays generic over the stage it runs.
+pub trait Retryable {
+    /// Whether restarting the stage after a backoff may succeed.
+    fn is_retryable(&self) -> bool;
+}
+
+impl Retryable for ProducerError {
+    fn is_retryable(&self) -> bool {
+        Self::is_retryable(self)
+    }
+}
+
+impl Retryable for ConsumerError {
+    fn is_retryable(&self) -> bool {
+        Self::is_retryable(self)
+    }
+}
+
+impl Retryable for LoggerError {
+    fn is_retryable(&self) -> bool {
+        Self::is_retryable(self)
+    }
+}
+
+impl Retryable for ReviewerError {
+    fn is_retryable(&self) -> bool {
+        Self::is_retryable(self)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RestartPolicy
+// ---------------------------------------------------------------------------
+
+/// Bounds on how often and how fast a failed stage is restarted.
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    /// Restarts allowed before the last retryable error is returned.
+    pub max_restarts: u32,
+    /// Delay before the first restart; doubled after each restart.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between restarts.
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    /// 3 restarts, 100 ms initial backoff, capped at 2 s.
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// supervise
+// ---------------------------------------------------------------------------
+
+/// Run `run` until it succeeds, restarting it on retryable errors, and
+/// return its output (a stage's `StopReason`).
+///
+/// Each call to `run` starts the stage afresh (e.g. `|| logger.run(b, s)`);
+/// the stage's own iteration counter restarts with it.
+///
+/// # Errors
+///
+/// Returns the stage error as soon as it is fatal, or once
+/// `policy.max_restarts` restarts have been spent.
+pub async fn supervise<F, Fut, T, E>(stage: &'static str, policy: RestartPolicy, mut run: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Retryable + std::error::Error + 'static,
+{
+    let mut restarts = 0u32;
+    let mut backoff = policy.initial_backoff;
+    loop {
+        match run().await {
+            Ok(output) => return Ok(output),
+            Err(e) if e.is_retryable() && restarts < policy.max_restarts => {
+                restarts += 1;
+                tracing::warn!(
+                    target: TARGET,
+                    stage,
+                    restarts,
+                    ?backoff,
+                    error = %ErrorChain(&e),
+                    "orchestrator.stage.restarting"
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(policy.max_backoff);
+            }
+            Err(e) => {
+                tracing::error!(
+                    target: TARGET,
+                    stage,
+                    restarts,
+                    retryable = e.is_retryable(),
+                    error = %ErrorChain(&e),
+                    "orchestrator.stage.failed"
+                );
+                return Err(e);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// shutdown_gracefully
+// ---------------------------------------------------------------------------
+
+/// How [`shutdown_gracefully`] ended.
+#[derive(Debug)]
+pub enum Shutdown<O> {
+    /// The pipeline ran to completion, on its own or while draining after the signal.
+    Completed(O),
+    /// The grace period expired before the pipeline drained.
+    Abandoned {
+        /// Transactions still buffered when the pipeline was dropped.
+        abandoned: usize,
+    },
+}
+
+/// Await `pipeline`; on `signal`, call `close` and keep awaiting it for up to `grace`.
+///
+/// `close` should only close the first buffer: the stages' shutdown cascade
+/// then drains everything downstream. If the pipeline is still running when
+/// `grace` expires it is dropped, and `abandoned` (typically the summed buffer
+/// depths) is logged at `warn` level and returned.
+pub async fn shutdown_gracefully<O>(
+    pipeline: impl Future<Output = O>,
+    signal: impl Future<Output = ()>,
+    close: impl FnOnce(),
+    grace: Duration,
+    abandoned: impl FnOnce() -> usize,
+) -> Shutdown<O> {
+    tokio::pin!(pipeline);
+    tokio::select! {
+        out = &mut pipeline => return Shutdown::Completed(out),
+        () = signal => {}
+    }
+    tracing::info!(
+        target: TARGET,
+        ?grace,
+        "main.shutdown: signal received, closing buffer1 and draining"
+    );
+    close();
+    if let Ok(out) = tokio::time::timeout(grace, pipeline).await {
+        return Shutdown::Completed(out);
+    }
+    let abandoned = abandoned();
+    tracing::warn!(
+        target: TARGET,
+        abandoned,
+        ?grace,
+        "main.shutdown: grace period expired, transactions abandoned"
+    );
+    Shutdown::Abandoned { abandoned }
+}
+
+// ---------------------------------------------------------------------------
+// CapacityPolicy
+// ---------------------------------------------------------------------------
+
+/// What the pipeline does when the Logger's storage reports `CapacityExceeded`.
+// #[allow] not #[expect]: only fraud_detection bounds its storage.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CapacityPolicy {
+    /// Fail the pipeline with the Logger's error.
+    #[default]
+    Fail,
+    /// Close buffer1 as on CTRL+C, so the Producer stops and the Consumer
+    /// drains, and report the Logger as stopped on a full storage.
+    Stop,
+}
+
+/// How the Logger stopped under a [`CapacityPolicy`].
+// See CapacityPolicy allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoggerStop {
+    /// The Logger stopped on its own.
+    Stopped(StopReason),
+    /// The storage was full under [`CapacityPolicy::Stop`].
+    StorageFull {
+        /// Capacity reported by the storage.
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for LoggerStop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stopped(reason) => reason.fmt(f),
+            Self::StorageFull { capacity } => write!(f, "storage full (capacity {capacity})"),
+        }
+    }
+}
+
+/// Apply `policy` to the Logger's `result`.
+///
+/// Under [`CapacityPolicy::Stop`], `CapacityExceeded` is logged at `warn`
+/// level, `full` is sent so the caller can start the shutdown cascade (see
+/// [`storage_full`]) and [`LoggerStop::StorageFull`] is returned. Any other
+/// result is passed through; `full` is then dropped unsent.
+///
+/// # Errors
+///
+/// Returns the Logger error unless `policy` turned it into a stop.
+// See CapacityPolicy allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+pub fn on_capacity(
+    policy: CapacityPolicy,
+    result: Result<StopReason, LoggerError>,
+    full: oneshot::Sender<()>,
+) -> Result<LoggerStop, LoggerError> {
+    match result {
+        Ok(reason) => Ok(LoggerStop::Stopped(reason)),
+        Err(LoggerError::Write(StorageError::CapacityExceeded { capacity, .. }))
+            if policy == CapacityPolicy::Stop =>
+        {
+            tracing::warn!(
+                target: TARGET,
+                capacity,
+                "orchestrator.storage.full: stopping the pipeline"
+            );
+            // No receiver left means the pipeline is already shutting down.
+            let _ = full.send(());
+            Ok(LoggerStop::StorageFull { capacity })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Resolve once [`on_capacity`] reports a full storage; never if `full` is
+/// dropped unsent. Meant as (part of) the `signal` of [`shutdown_gracefully`].
+// See CapacityPolicy allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+pub async fn storage_full(full: oneshot::Receiver<()>) {
+    if full.await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Watchdog
+// ---------------------------------------------------------------------------
+
+/// A stage that stopped completing batches with work pending.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stalled {
+    /// The stalled stage: the Consumer or the Logger.
+    pub stage: Stage,
+    /// Time since its last batch, or since its input buffer filled if later.
+    pub idle: Duration,
+    /// Items waiting in its input buffer.
+    pub pending: usize,
+}
+
+impl fmt::Display for Stalled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} stalled: no batch for {:?} with {} transactions pending",
+            self.stage, self.idle, self.pending
+        )
+    }
+}
+
+impl std::error::Error for Stalled {}
+
+/// What the [`Watchdog`] knows of one stage.
+#[derive(Debug, Clone, Copy)]
+struct Progress {
+    /// Last batch, or the start of the watch.
+    last_batch: tokio::time::Instant,
+    /// Batches seen so far.
+    batches: u64,
+    /// First check that found the input buffer non-empty, reset when it empties.
+    pending_since: Option<tokio::time::Instant>,
+    /// Whether the stage published `StageStopped` since its last batch.
+    stopped: bool,
+}
+
+/// Stall detector for the Consumer and the Logger.
+///
+/// A stage progresses when it publishes its batch event: `BatchInferred` or
+/// `BatchDiscarded` for the Consumer, `BatchPersisted` for the Logger. Every
+/// `check_every`, a live stage whose input buffer has held items for
+/// `stall_after` without such an event is reported. A closed buffer still
+/// holding items counts: the stage must drain it. A stage that published
+/// `StageStopped` is live again from its next batch, as when [`supervise`]
+/// restarts it. The Producer has no input buffer and is not watched; its
+/// batches only appear in the diagnostic.
+// See CapacityPolicy allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+#[derive(Debug, Clone, Copy)]
+pub struct Watchdog {
+    /// Time a stage may go without a batch while items wait for it.
+    pub stall_after: Duration,
+    /// Interval between two checks of the buffers.
+    pub check_every: Duration,
+}
+
+// See CapacityPolicy allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+impl Watchdog {
+    /// Follow `events` and return the first stall, once logged at `error`
+    /// level with both buffer depths and the batches of every stage.
+    ///
+    /// Never returns otherwise, so race it against the pipeline. Lost events
+    /// (a lagging receiver) count as progress of every stage; once every
+    /// sender is gone the stages are done and nothing is reported.
+    pub async fn watch(
+        self,
+        mut events: broadcast::Receiver<PipelineEvent>,
+        buffer1: &impl BufferDepth,
+        buffer2: &impl BufferDepth,
+    ) -> Stalled {
+        let start = tokio::time::Instant::now();
+        let fresh = Progress { last_batch: start, batches: 0, pending_since: None, stopped: false };
+        let mut stages = [fresh; 3];
+        let mut ticker = tokio::time::interval_at(start + self.check_every, self.check_every);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) => record(&mut stages, &event),
+                    Err(RecvError::Lagged(_)) => {
+                        let now = tokio::time::Instant::now();
+                        for stage in &mut stages {
+                            stage.last_batch = now;
+                        }
+                    }
+                    Err(RecvError::Closed) => std::future::pending::<()>().await,
+                },
+                now = ticker.tick() => {
+                    let depths = [buffer1.depth(), buffer2.depth()];
+                    if let Some(stalled) = self.check(&mut stages, depths, now) {
+                        let [producer, consumer, logger] = stages.map(|stage| stage.batches);
+                        tracing::error!(
+                            target: TARGET,
+                            stage = ?stalled.stage,
+                            idle = ?stalled.idle,
+                            buffer1 = depths[0],
+                            buffer2 = depths[1],
+                            producer_batches = producer,
+                            consumer_batches = consumer,
+                            logger_batches = logger,
+                            "orchestrator.watchdog.stalled"
+                        );
+                        return stalled;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Update when each input buffer started holding items and return the
+    /// first live stage idle for `stall_after` with items pending.
+    fn check(
+        self,
+        stages: &mut [Progress; 3],
+        depths: [usize; 2],
+        now: tokio::time::Instant,
+    ) -> Option<Stalled> {
+        let watched = [(Stage::Consumer, 1), (Stage::Logger, 2)];
+        for ((stage, index), pending) in watched.into_iter().zip(depths) {
+            let progress = &mut stages[index];
+            if pending == 0 {
+                progress.pending_since = None;
+                continue;
+            }
+            let since = *progress.pending_since.get_or_insert(now);
+            let idle = now.duration_since(progress.last_batch.max(since));
+            if !progress.stopped && idle >= self.stall_after {
+                return Some(Stalled { stage, idle, pending });
+            }
+        }
+        None
+    }
+}
+
+/// Fold `event` into the progress of its stage.
+fn record(stages: &mut [Progress; 3], event: &PipelineEvent) {
+    let index = |stage| match stage {
+        Stage::Producer => 0,
+        Stage::Consumer => 1,
+        Stage::Logger => 2,
+    };
+    match event {
+        PipelineEvent::BatchProduced { .. } => stages[0].batch(),
+        PipelineEvent::BatchInferred { .. } | PipelineEvent::BatchDiscarded { .. } => {
+            stages[1].batch();
+        }
+        PipelineEvent::BatchPersisted { .. } => stages[2].batch(),
+        PipelineEvent::StageStopped { stage, .. } => stages[index(*stage)].stopped = true,
+        PipelineEvent::AlarmTriggered { .. } | PipelineEvent::AlarmFailed { .. } => {}
+    }
+}
+
+impl Progress {
+    fn batch(&mut self) {
+        self.last_batch = tokio::time::Instant::now();
+        self.batches += 1;
+        self.stopped = false;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StageTask
+// ---------------------------------------------------------------------------
+
+/// Why a [`StageTask`] did not return its stage's output.
+// #[allow] not #[expect]: only fraud_detection runs its stages as tasks.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+#[derive(Debug)]
+pub enum StageFailure<E> {
+    /// The stage returned an error (after any restarts done inside the task).
+    Failed {
+        /// Stage name given to [`StageTask::spawn`].
+        stage: &'static str,
+        /// The stage error.
+        source: E,
+    },
+    /// The stage panicked.
+    Panicked {
+        /// Stage name given to [`StageTask::spawn`].
+        stage: &'static str,
+        /// The panic payload when it is a string.
+        message: String,
+    },
+    /// The task was aborted before the stage finished.
+    Aborted {
+        /// Stage name given to [`StageTask::spawn`].
+        stage: &'static str,
+    },
+}
+
+impl<E> fmt::Display for StageFailure<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed { stage, .. } => write!(f, "{stage} failed"),
+            Self::Panicked { stage, message } => write!(f, "{stage} panicked: {message}"),
+            Self::Aborted { stage } => write!(f, "{stage} was aborted"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for StageFailure<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Failed { source, .. } => Some(source),
+            Self::Panicked { .. } | Self::Aborted { .. } => None,
+        }
+    }
+}
+
+/// Boxed `'static` run of one stage, as built by a [`StageTask`]'s start closure.
+// See StageFailure allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+type StageRun<T, E> = Pin<Box<dyn Future<Output = Result<T, E>>>>;
+
+/// One pipeline stage running as a `spawn_local` task.
+///
+/// `start` builds a fresh `'static` run each time it is called, typically by
+/// cloning `Rc`s of the stage and its adapters into an `async move` block.
+/// Wrap the run in [`supervise`] to restart it on retryable errors inside the
+/// task; [`restart`](Self::restart) restarts it from outside. Stages are
+/// `!Send`, so tasks are spawned on the current `LocalSet`.
+// See StageFailure allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+pub struct StageTask<T, E> {
+    stage: &'static str,
+    start: Box<dyn Fn() -> StageRun<T, E>>,
+    handle: Option<JoinHandle<Result<T, E>>>,
+}
+
+impl<T, E> fmt::Debug for StageTask<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StageTask")
+            .field("stage", &self.stage)
+            .field("running", &self.handle.as_ref().is_some_and(|h| !h.is_finished()))
+            .finish_non_exhaustive()
+    }
+}
+
+// See StageFailure allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+impl<T: 'static, E: 'static> StageTask<T, E> {
+    /// Spawn the first run of `stage`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside a `LocalSet`.
+    pub fn spawn<F, Fut>(stage: &'static str, start: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let start: Box<dyn Fn() -> StageRun<T, E>> =
+            Box::new(move || -> StageRun<T, E> { Box::pin(start()) });
+        let handle = tokio::task::spawn_local(start());
+        Self { stage, start, handle: Some(handle) }
+    }
+
+    /// Request cancellation of the current run; [`join`](Self::join) then
+    /// reports [`StageFailure::Aborted`] unless it already finished.
+    pub fn abort(&self) {
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+
+    /// Wait for the current run and return its output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StageFailure::Failed`] with the stage error,
+    /// [`StageFailure::Panicked`] if the stage panicked, or
+    /// [`StageFailure::Aborted`] if the run was aborted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current run was already joined and not restarted since.
+    pub async fn join(&mut self) -> Result<T, StageFailure<E>> {
+        let stage = self.stage;
+        let handle = self.handle.take().expect("stage joined twice without a restart");
+        match handle.await {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(source)) => Err(StageFailure::Failed { stage, source }),
+            Err(e) if e.is_panic() => {
+                let message = panic_message(e);
+                tracing::error!(
+                    target: TARGET,
+                    stage,
+                    panic = %message,
+                    "orchestrator.stage.panicked"
+                );
+                Err(StageFailure::Panicked { stage, message })
+            }
+            Err(_) => Err(StageFailure::Aborted { stage }),
+        }
+    }
+
+    /// Whether the current run has finished, or was already joined.
+    // #[allow] not #[expect]: no binary polls a single stage yet; the tests do.
+    #[allow(dead_code, reason = "used by the orchestrator tests only")]
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Abort the current run, if any, and spawn a fresh one from `start`.
+    ///
+    /// The other stages keep running: they only see the stage stop and
+    /// resume reading or writing its buffers.
+    // #[allow] not #[expect]: no binary restarts a single stage yet; the tests do.
+    #[allow(dead_code, reason = "used by the orchestrator tests only")]
+    pub fn restart(&mut self) {
+        self.abort();
+        tracing::warn!(target: TARGET, stage = self.stage, "orchestrator.stage.respawned");
+        self.handle = Some(tokio::task::spawn_local((self.start)()));
+    }
+}
+
+impl<T, E> Drop for StageTask<T, E> {
+    /// Abort the current run: a stage never outlives its handle, so dropping
+    /// the pipeline (first error, grace period expired) stops every stage.
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+}
+
+/// The payload of a panicked task when it is a string.
+// See StageFailure allow(dead_code) comment above.
+#[allow(dead_code, reason = "used by fraud_detection; dead in the other binaries")]
+fn panic_message(error: JoinError) -> String {
+    let payload: Box<dyn Any + Send> = error.into_panic();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_owned())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CapacityPolicy, LoggerStop, RestartPolicy, Shutdown, StageFailure, StageTask, Stalled,
+        Watchdog, on_capacity, shutdown_gracefully, storage_full, supervise,
+    };
+    use crate::adapters::concurrent_buffer::ConcurrentBuffer;
+    use crate::adapters::concurrent_buffer2::ConcurrentBuffer2;
+    use crate::adapters::demo_model::DemoModel;
+    use crate::adapters::in_memory_storage::InMemoryStorage;
+    use crate::adapters::log_alarm::LogAlarm;
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{
+        Buffer2 as _, BufferDepth as _, Currency, InferredTransaction, PendingTransaction,
+        PipelineEvent, StopReason, Storage, StorageError, Transaction, TransactionId,
+    };
+    use logger::{Logger, LoggerConfig, LoggerError};
+    use modelizer::Modelizer;
+    use producer::{Producer, ProducerConfig};
+    use std::cell::Cell;
+    use std::convert::Infallible;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    /// Fails the first `failures` writes with `Unavailable`, then accepts.
+    struct FlakyStorage {
+        failures: Cell<u32>,
+        stored: Cell<usize>,
+    }
+
+    impl FlakyStorage {
+        fn new(failures: u32) -> Self {
+            Self { failures: Cell::new(failures), stored: Cell::new(0) }
+        }
+    }
+
+    impl Storage for FlakyStorage {
+        async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            if self.failures.get() > 0 {
+                self.failures.set(self.failures.get() - 1);
+                return Err(StorageError::Unavailable);
+            }
+            self.stored.set(self.stored.get() + batch.len());
+            Ok(())
+        }
+    }
+
+    fn make_inferred() -> InferredTransaction {
+        InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: 1.00_f64,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud: false,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        }
+    }
+
+    fn fast_policy() -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+        }
+    }
+
+    /// Logger keeping failed writes retained across restarts, as `main_sqlite` does.
+    fn fast_logger() -> Logger {
+        let config = LoggerConfig::builder(5)
+            .poll_interval3(Duration::from_millis(1))
+            .seed(7)
+            .split_on_capacity(true)
+            .max_retained(1_000)
+            .build()
+            .unwrap();
+        Logger::new(config)
+    }
+
+    // OR-T01: Unavailable twice -> Logger restarted twice, buffer fully drained.
+    #[tokio::test]
+    async fn unavailable_restarts_logger_and_drains() {
+        let buffer = ConcurrentBuffer2::new();
+        buffer.write_batch((0..30).map(|_| make_inferred()).collect()).await.unwrap();
+        buffer.close();
+        let storage = FlakyStorage::new(2);
+        let logger = fast_logger();
+        let runs = Cell::new(0u32);
+
+        let (l, b, s, r) = (&logger, &buffer, &storage, &runs);
+        let result = supervise("logger", fast_policy(), move || {
+            r.set(r.get() + 1);
+            l.run(b, s)
+        })
+        .await;
+
+        // The last run's reason: the restarted Logger's own count.
+        assert!(matches!(result, Ok(StopReason::BufferClosed { .. })), "{result:?}");
+        assert_eq!(runs.get(), 3, "initial run plus two restarts");
+        assert_eq!(buffer.depth(), 0, "buffer must be drained");
+        // The batches rejected by the failed writes stay retained and are
+        // persisted by the restarted run: nothing is lost.
+        assert_eq!(storage.stored.get(), 30);
+        assert_eq!(logger.retained(), 0);
+    }
+
+    // OR-T02: fatal error aborts without restart.
+    #[tokio::test]
+    async fn fatal_error_aborts_immediately() {
+        let runs = Cell::new(0u32);
+        let result = supervise("logger", fast_policy(), || {
+            runs.set(runs.get() + 1);
+            async { Err::<(), _>(LoggerError::Write(StorageError::CapacityExceeded { capacity: 1, remaining: 0 })) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(LoggerError::Write(StorageError::CapacityExceeded { .. }))));
+        assert_eq!(runs.get(), 1, "fatal error must not restart the stage");
+    }
+
+    // OR-T03: retryable error persisting past the cap is returned.
+    #[tokio::test]
+    async fn restart_cap_returns_last_error() {
+        let runs = Cell::new(0u32);
+        let result = supervise("logger", fast_policy(), || {
+            runs.set(runs.get() + 1);
+            async { Err::<(), _>(LoggerError::Write(StorageError::Unavailable)) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(LoggerError::Write(StorageError::Unavailable))));
+        assert_eq!(runs.get(), 4, "initial run plus max_restarts restarts");
+    }
+
+    // ------------------------------------------------------------------
+    // Graceful shutdown
+    // ------------------------------------------------------------------
+
+    /// Run a seeded infinite pipeline, signal shutdown after 50 ms, and return
+    /// (outcome is completed, produced, persisted, abandoned).
+    ///
+    /// "Produced" sums `BatchProduced` events, i.e. transactions actually
+    /// written to Buffer1 (the batch refused after the close is excluded).
+    async fn run_signalled_pipeline(
+        logger_interval: Duration,
+        grace: Duration,
+    ) -> (bool, usize, usize, usize) {
+        let (events, mut rx) = tokio::sync::broadcast::channel(4096);
+        let producer = Producer::new(
+            ProducerConfig::builder(20)
+                .seed(1)
+                .poll_interval1(Duration::from_millis(1))
+                .events(events)
+                .build()
+                .unwrap(),
+        );
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(2)
+                .poll_interval2(Duration::from_millis(1))
+                .build()
+                .unwrap(),
+        );
+        let logger = Logger::new(
+            LoggerConfig::builder(10).seed(3).poll_interval3(logger_interval).build().unwrap(),
+        );
+        let (buffer1, buffer2) = (ConcurrentBuffer::new(), ConcurrentBuffer2::new());
+        let modelizer = Modelizer::new(DemoModel::new(Some(4)));
+        let alarm = LogAlarm::new();
+        let storage = InMemoryStorage::new(usize::MAX);
+
+        let pipeline = async {
+            tokio::join!(
+                async {
+                    producer.run(&buffer1).await.unwrap();
+                    buffer1.close();
+                },
+                async {
+                    consumer.run(&buffer1, &modelizer, &alarm, &buffer2).await.unwrap();
+                    buffer2.close();
+                },
+                async { logger.run(&buffer2, &storage).await.unwrap() },
+            );
+        };
+        let outcome = shutdown_gracefully(
+            pipeline,
+            tokio::time::sleep(Duration::from_millis(50)),
+            || buffer1.close(),
+            grace,
+            || buffer1.depth() + buffer2.depth(),
+        )
+        .await;
+
+        let (completed, abandoned) = match outcome {
+            Shutdown::Completed(()) => (true, 0),
+            Shutdown::Abandoned { abandoned } => (false, abandoned),
+        };
+        let mut generated = 0;
+        while let Ok(event) = rx.try_recv() {
+            if let PipelineEvent::BatchProduced { size } = event {
+                generated += size;
+            }
+        }
+        (completed, generated, storage.len(), abandoned)
+    }
+
+    // OR-T04: with enough grace, everything produced before the signal is persisted.
+    #[tokio::test]
+    async fn signal_drains_both_buffers() {
+        let (completed, produced, persisted, _) =
+            run_signalled_pipeline(Duration::from_millis(1), Duration::from_secs(10)).await;
+
+        assert!(completed, "pipeline must drain within the grace period");
+        assert!(produced > 0);
+        assert_eq!(persisted, produced);
+    }
+
+    // OR-T05: grace expiry reports what was still buffered.
+    #[tokio::test]
+    async fn grace_expiry_reports_abandoned() {
+        // A Logger sleeping 10 s between batches cannot drain within 20 ms.
+        let (completed, produced, persisted, abandoned) =
+            run_signalled_pipeline(Duration::from_secs(10), Duration::from_millis(20)).await;
+
+        assert!(!completed);
+        assert!(abandoned > 0);
+        assert_eq!(persisted + abandoned, produced);
+    }
+
+    // ------------------------------------------------------------------
+    // Stage tasks
+    // ------------------------------------------------------------------
+
+    /// Panics on the first write.
+    struct PanickingStorage;
+
+    impl Storage for PanickingStorage {
+        async fn write_batch(&self, _batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            panic!("disk on fire");
+        }
+    }
+
+    /// The three stages of a seeded 20-iteration run and their adapters,
+    /// owned so each stage can run as its own task.
+    struct Owned<S> {
+        producer: Rc<Producer>,
+        consumer: Rc<Consumer>,
+        logger: Rc<Logger>,
+        buffer1: Rc<ConcurrentBuffer>,
+        buffer2: Rc<ConcurrentBuffer2>,
+        modelizer: Rc<Modelizer<DemoModel>>,
+        alarm: Rc<LogAlarm>,
+        storage: Rc<S>,
+    }
+
+    fn owned<S>(storage: S) -> Owned<S> {
+        let interval = Duration::from_millis(1);
+        Owned {
+            producer: Rc::new(Producer::new(
+                ProducerConfig::builder(10)
+                    .seed(1)
+                    .iterations(20)
+                    .poll_interval1(interval)
+                    .build()
+                    .unwrap(),
+            )),
+            consumer: Rc::new(Consumer::new(
+                ConsumerConfig::builder(10).seed(2).poll_interval2(interval).build().unwrap(),
+            )),
+            logger: Rc::new(Logger::new(
+                LoggerConfig::builder(10).seed(3).poll_interval3(interval).build().unwrap(),
+            )),
+            buffer1: Rc::new(ConcurrentBuffer::new()),
+            buffer2: Rc::new(ConcurrentBuffer2::new()),
+            modelizer: Rc::new(Modelizer::new(DemoModel::new(Some(4)))),
+            alarm: Rc::new(LogAlarm::new()),
+            storage: Rc::new(storage),
+        }
+    }
+
+    impl<S: Storage + 'static> Owned<S> {
+        /// Spawn the three stages, each closing its output buffer when done.
+        fn spawn(
+            &self,
+        ) -> (
+            StageTask<StopReason, producer::ProducerError>,
+            StageTask<StopReason, consumer::ConsumerError>,
+            StageTask<StopReason, LoggerError>,
+        ) {
+            let (stage, b1) = (Rc::clone(&self.producer), Rc::clone(&self.buffer1));
+            let producer = StageTask::spawn("producer", move || {
+                let (stage, b1) = (Rc::clone(&stage), Rc::clone(&b1));
+                async move {
+                    let result = stage.run(&*b1).await;
+                    b1.close();
+                    result
+                }
+            });
+            let (stage, b1, model, alarm, b2) = (
+                Rc::clone(&self.consumer),
+                Rc::clone(&self.buffer1),
+                Rc::clone(&self.modelizer),
+                Rc::clone(&self.alarm),
+                Rc::clone(&self.buffer2),
+            );
+            let consumer = StageTask::spawn("consumer", move || {
+                let (stage, b1, model, alarm, b2) = (
+                    Rc::clone(&stage),
+                    Rc::clone(&b1),
+                    Rc::clone(&model),
+                    Rc::clone(&alarm),
+                    Rc::clone(&b2),
+                );
+                async move {
+                    let result = stage.run(&*b1, &*model, &*alarm, &*b2).await;
+                    b2.close();
+                    result
+                }
+            });
+            let (stage, b2, storage) =
+                (Rc::clone(&self.logger), Rc::clone(&self.buffer2), Rc::clone(&self.storage));
+            let logger = StageTask::spawn("logger", move || {
+                let (stage, b2, storage) = (Rc::clone(&stage), Rc::clone(&b2), Rc::clone(&storage));
+                async move { stage.run(&*b2, &*storage).await }
+            });
+            (producer, consumer, logger)
+        }
+    }
+
+    // OR-T06: aborting the Consumer mid-run and restarting it loses nothing;
+    // the Producer and the Logger keep running throughout.
+    #[tokio::test]
+    async fn aborted_consumer_restarts_and_pipeline_drains() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let run = owned(InMemoryStorage::new(usize::MAX));
+                let (mut producer, mut consumer, mut logger) = run.spawn();
+
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                consumer.abort();
+                let aborted = consumer.join().await;
+                assert!(
+                    matches!(aborted, Err(StageFailure::Aborted { stage: "consumer" })),
+                    "{aborted:?}"
+                );
+                assert!(!producer.is_finished() && !logger.is_finished());
+
+                consumer.restart();
+                producer.join().await.unwrap();
+                consumer.join().await.unwrap();
+                logger.join().await.unwrap();
+
+                let transactions = run.producer.stats().transactions;
+                assert!(transactions > 0);
+                assert_eq!(run.storage.len() as u64, transactions);
+                assert_eq!(run.buffer1.depth() + run.buffer2.depth(), 0);
+            })
+            .await;
+    }
+
+    // OR-T07: a panicking Logger is caught by its JoinHandle and reported as
+    // a stage failure instead of taking the pipeline down.
+    #[tokio::test]
+    async fn panicking_logger_is_reported() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let run = owned(PanickingStorage);
+                let (mut producer, mut consumer, mut logger) = run.spawn();
+
+                let result = logger.join().await;
+
+                let Err(failure) = result else { panic!("logger must not succeed") };
+                assert_eq!(failure.to_string(), "logger panicked: disk on fire");
+                assert!(matches!(failure, StageFailure::Panicked { stage: "logger", .. }));
+                producer.join().await.unwrap();
+                consumer.join().await.unwrap();
+            })
+            .await;
+    }
+
+    // OR-T08: dropping a StageTask aborts its run, so a pipeline dropped on
+    // its first error or after the grace period leaves no stage running.
+    #[tokio::test(start_paused = true)]
+    async fn dropped_stage_task_aborts_its_run() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let finished = Rc::new(Cell::new(false));
+                let flag = Rc::clone(&finished);
+                let task = StageTask::spawn("producer", move || {
+                    let flag = Rc::clone(&flag);
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        flag.set(true);
+                        Ok::<(), Infallible>(())
+                    }
+                });
+
+                drop(task);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+
+                assert!(!finished.get());
+            })
+            .await;
+    }
+
+    // ------------------------------------------------------------------
+    // Capacity policy
+    // ------------------------------------------------------------------
+
+    /// Run a seeded infinite pipeline into a storage of `capacity` under
+    /// `policy`, with a full storage as the only shutdown signal, and return
+    /// the outcome and the rows persisted.
+    async fn run_capped_pipeline(
+        capacity: usize,
+        policy: CapacityPolicy,
+    ) -> (Shutdown<anyhow::Result<(StopReason, StopReason, LoggerStop)>>, usize) {
+        let producer = Producer::new(
+            ProducerConfig::builder(20)
+                .seed(1)
+                .poll_interval1(Duration::from_millis(1))
+                .build()
+                .unwrap(),
+        );
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(10)
+                .seed(2)
+                .poll_interval2(Duration::from_millis(1))
+                .build()
+                .unwrap(),
+        );
+        let logger = Logger::new(
+            LoggerConfig::builder(10)
+                .seed(3)
+                .poll_interval3(Duration::from_millis(1))
+                .build()
+                .unwrap(),
+        );
+        let (buffer1, buffer2) = (ConcurrentBuffer::new(), ConcurrentBuffer2::new());
+        let modelizer = Modelizer::new(DemoModel::new(Some(4)));
+        let alarm = LogAlarm::new();
+        let storage = InMemoryStorage::new(capacity);
+        let (full_tx, full_rx) = tokio::sync::oneshot::channel();
+
+        let pipeline = async {
+            tokio::try_join!(
+                async {
+                    let r = producer.run(&buffer1).await;
+                    buffer1.close();
+                    Ok::<_, anyhow::Error>(r?)
+                },
+                async {
+                    let r = consumer.run(&buffer1, &modelizer, &alarm, &buffer2).await;
+                    buffer2.close();
+                    Ok(r?)
+                },
+                async { Ok(on_capacity(policy, logger.run(&buffer2, &storage).await, full_tx)?) },
+            )
+        };
+        let outcome = shutdown_gracefully(
+            pipeline,
+            storage_full(full_rx),
+            || buffer1.close(),
+            Duration::from_secs(10),
+            || buffer1.depth() + buffer2.depth(),
+        )
+        .await;
+        (outcome, storage.len())
+    }
+
+    // OR-T09: Stop -> a full storage closes buffer1 and the pipeline exits cleanly.
+    #[tokio::test]
+    async fn storage_full_stops_the_pipeline_under_stop() {
+        let (outcome, persisted) = run_capped_pipeline(50, CapacityPolicy::Stop).await;
+
+        let Shutdown::Completed(Ok((producer, consumer, logger))) = outcome else {
+            panic!("expected a clean exit, got {outcome:?}");
+        };
+        assert!(matches!(producer, StopReason::BufferClosed { .. }), "{producer}");
+        assert!(matches!(consumer, StopReason::BufferClosed { .. }), "{consumer}");
+        assert_eq!(logger, LoggerStop::StorageFull { capacity: 50 });
+        assert_eq!(logger.to_string(), "storage full (capacity 50)");
+        assert!((1..=50).contains(&persisted), "{persisted}");
+    }
+
+    // OR-T10: Fail -> the Logger's CapacityExceeded fails the pipeline, as before.
+    #[tokio::test]
+    async fn storage_full_fails_the_pipeline_under_fail() {
+        let (outcome, persisted) = run_capped_pipeline(50, CapacityPolicy::Fail).await;
+
+        let Shutdown::Completed(Err(e)) = outcome else {
+            panic!("expected the Logger error, got {outcome:?}");
+        };
+        let logger = e.downcast_ref::<LoggerError>();
+        assert!(
+            matches!(
+                logger,
+                Some(LoggerError::Write(StorageError::CapacityExceeded { capacity: 50, .. }))
+            ),
+            "{e:#}"
+        );
+        assert!(persisted <= 50);
+    }
+
+    // ------------------------------------------------------------------
+    // Watchdog
+    // ------------------------------------------------------------------
+
+    /// Fixed buffer depth.
+    struct Depth(usize);
+
+    impl domain::BufferDepth for Depth {
+        fn depth(&self) -> usize {
+            self.0
+        }
+    }
+
+    fn watchdog() -> Watchdog {
+        Watchdog { stall_after: Duration::from_secs(5), check_every: Duration::from_secs(1) }
+    }
+
+    // OR-T11: a Consumer that stops completing batches with Buffer1 non-empty
+    // is reported within stall_after plus one check.
+    #[tokio::test(start_paused = true)]
+    async fn watchdog_reports_a_stage_stalled_with_pending_input() {
+        let (events, rx) = tokio::sync::broadcast::channel(16);
+        let heartbeat = async {
+            for _ in 0..3 {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                let event =
+                    PipelineEvent::BatchInferred { size: 10, flagged: 0, version: "4".to_owned() };
+                events.send(event).unwrap();
+            }
+            // The stage now hangs: no more events, Buffer1 keeps 40 items.
+            std::future::pending::<()>().await;
+        };
+        let start = tokio::time::Instant::now();
+
+        let stalled = tokio::select! {
+            stalled = watchdog().watch(rx, &Depth(40), &Depth(0)) => stalled,
+            () = heartbeat => unreachable!(),
+        };
+
+        let last_batch = Duration::from_millis(1_500);
+        assert_eq!(stalled.stage, domain::Stage::Consumer);
+        assert_eq!(stalled.pending, 40);
+        assert!(stalled.idle >= Duration::from_secs(5), "{stalled}");
+        let elapsed = start.elapsed();
+        assert!(elapsed <= last_batch + Duration::from_secs(6), "{elapsed:?}");
+        assert_eq!(
+            Stalled { idle: Duration::from_secs(5), ..stalled }.to_string(),
+            "Consumer stalled: no batch for 5s with 40 transactions pending"
+        );
+    }
+
+    // OR-T12: idle stages with empty input buffers, or a stopped stage, are
+    // never reported.
+    #[tokio::test(start_paused = true)]
+    async fn watchdog_ignores_idle_empty_and_stopped_stages() {
+        let (events, rx) = tokio::sync::broadcast::channel(16);
+        let stopped = PipelineEvent::StageStopped {
+            stage: domain::Stage::Logger,
+            reason: StopReason::BufferClosed { iterations: 2 }.label().to_owned(),
+        };
+        events.send(stopped).unwrap();
+
+        let watch = watchdog().watch(rx, &Depth(0), &Depth(12));
+        let result = tokio::time::timeout(Duration::from_mins(10), watch).await;
+
+        assert!(result.is_err(), "{result:?}");
+    }
+
+    // OR-T13: a stage that stopped on a failure is watched again from its
+    // next batch, as when supervise restarts it.
+    #[tokio::test(start_paused = true)]
+    async fn watchdog_watches_a_restarted_stage_again() {
+        let (events, rx) = tokio::sync::broadcast::channel(16);
+        let failed = PipelineEvent::StageStopped {
+            stage: domain::Stage::Consumer,
+            reason: "Buffer2 write failed: closed".to_owned(),
+        };
+        events.send(failed).unwrap();
+        let restarted =
+            PipelineEvent::BatchInferred { size: 10, flagged: 0, version: "4".to_owned() };
+        events.send(restarted).unwrap();
+
+        let watch = watchdog().watch(rx, &Depth(40), &Depth(0));
+        let stalled = tokio::time::timeout(Duration::from_mins(1), watch).await.unwrap();
+
+        assert_eq!(stalled.stage, domain::Stage::Consumer);
+        assert_eq!(stalled.pending, 40);
+    }
+
+    // OR-T14: a Consumer discarding whole batches (all rejected or
+    // quarantined) still progresses.
+    #[tokio::test(start_paused = true)]
+    async fn watchdog_counts_discarded_batches_as_progress() {
+        let (events, rx) = tokio::sync::broadcast::channel(16);
+        let discarding = async {
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                events.send(PipelineEvent::BatchDiscarded { size: 10 }).unwrap();
+            }
+        };
+
+        let watch = watchdog().watch(rx, &Depth(40), &Depth(0));
+        let result = tokio::time::timeout(Duration::from_mins(10), async {
+            tokio::select! {
+                stalled = watch => stalled,
+                () = discarding => unreachable!(),
+            }
+        })
+        .await;
+
+        assert!(result.is_err(), "{result:?}");
+    }
+}
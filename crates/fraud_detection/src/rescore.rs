@@ -0,0 +1,316 @@
+// Rust guideline compliant 2026-02-27
+
+//! Backfill: re-score stored transactions with another model.
+//!
+//! [`rescore`] pages through the `StorageRead` port starting after the job's
+//! high-water mark, runs every page through a `Modelizer`, and writes the new
+//! predictions through the `RescoreSink` port. Stored records are never
+//! modified. The high-water mark is committed together with each page of
+//! predictions, so a run interrupted mid-way resumes after the last committed
+//! page without scoring anything twice.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use domain::{Clock, Modelizer, RescoreSink, RescoredPrediction, StorageRead, SystemClock};
+
+/// `tracing` target of every rescore event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ORCHESTRATOR;
+
+// ---------------------------------------------------------------------------
+// RescoreConfig / RescoreReport
+// ---------------------------------------------------------------------------
+
+/// Settings for a [`rescore`] run.
+#[derive(Debug, Clone)]
+pub struct RescoreConfig {
+    /// High-water mark key: rerun the same job to resume, use a new one to start over.
+    pub job: String,
+    /// Records read and scored per page.
+    pub batch_size: usize,
+    /// Log progress each time this many more records have been processed.
+    pub progress_every: u64,
+    /// Source of `scored_at`.
+    pub clock: Arc<dyn Clock>,
+}
+
+impl RescoreConfig {
+    /// Config for `job` with 500-record pages, progress every 10 000 records
+    /// and the system clock.
+    #[must_use]
+    pub fn new(job: impl Into<String>) -> Self {
+        Self {
+            job: job.into(),
+            batch_size: 500,
+            progress_every: 10_000,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+/// Outcome of a [`rescore`] run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RescoreReport {
+    /// High-water mark found at start; `0` for a fresh job.
+    pub resumed_from: u64,
+    /// Records scored by this run.
+    pub processed: u64,
+    /// High-water mark at the end of the run.
+    pub high_water: u64,
+    /// Model name reported by the Modelizer; empty if nothing was scored.
+    pub model_name: String,
+    /// Model version reported by the Modelizer; empty if nothing was scored.
+    pub model_version: String,
+}
+
+// ---------------------------------------------------------------------------
+// rescore
+// ---------------------------------------------------------------------------
+
+/// Score every stored record after `config.job`'s high-water mark with
+/// `modelizer` and persist the new predictions.
+///
+/// # Errors
+///
+/// Returns an error when a page cannot be read, scored or written. Pages
+/// committed before the failure stay committed; rerunning the same job
+/// resumes after them.
+#[tracing::instrument(
+    name = "rescore",
+    target = "pipeline::orchestrator",
+    skip_all,
+    fields(job = %config.job)
+)]
+pub async fn rescore<S, M>(
+    storage: &S,
+    modelizer: &M,
+    config: &RescoreConfig,
+) -> anyhow::Result<RescoreReport>
+where
+    S: StorageRead + RescoreSink,
+    M: Modelizer,
+{
+    let resumed_from = storage
+        .rescore_high_water(&config.job)
+        .await
+        .context("failed to read rescore high-water mark")?;
+    if resumed_from > 0 {
+        tracing::info!(target: TARGET, resumed_from, "rescore.resumed");
+    }
+    let progress_every = config.progress_every.max(1);
+    let mut report =
+        RescoreReport { resumed_from, high_water: resumed_from, ..RescoreReport::default() };
+
+    loop {
+        let page = storage
+            .read_page(report.high_water, config.batch_size)
+            .await
+            .context("failed to read stored transactions")?;
+        let Some(last) = page.last().map(|stored| stored.position) else {
+            break;
+        };
+        let size = page.len();
+        let batch = page
+            .into_iter()
+            .map(|stored| stored.pending.inferred_transaction.transaction)
+            .collect();
+        let inferred = modelizer.infer(batch).await.context("rescoring inference failed")?;
+        anyhow::ensure!(
+            inferred.len() == size,
+            "modelizer returned {} predictions for {size} transactions",
+            inferred.len()
+        );
+
+        let scored_at = config.clock.now();
+        let rows: Vec<RescoredPrediction> = inferred
+            .into_iter()
+            .map(|it| RescoredPrediction {
+                id: it.id(),
+                model_name: it.model_name,
+                model_version: it.model_version,
+                predicted_fraud: it.predicted_fraud,
+                scored_at,
+            })
+            .collect();
+        storage
+            .write_rescored(&config.job, &rows, last)
+            .await
+            .context("failed to write rescored predictions")?;
+
+        let before = report.processed;
+        report.processed += size as u64;
+        report.high_water = last;
+        if let Some(row) = rows.last() {
+            report.model_name.clone_from(&row.model_name);
+            report.model_version.clone_from(&row.model_version);
+        }
+        if report.processed / progress_every > before / progress_every {
+            tracing::info!(
+                target: TARGET,
+                processed = report.processed,
+                high_water = last,
+                "rescore.progress"
+            );
+        }
+    }
+
+    tracing::info!(
+        target: TARGET,
+        processed = report.processed,
+        high_water = report.high_water,
+        "rescore.completed"
+    );
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{RescoreConfig, rescore};
+    use crate::sqlite_storage::SqliteStorage;
+    use domain::{
+        Currency, InferredTransaction, Modelizer, ModelizerError, ModelVersion, PendingTransaction,
+        RescoreSink as _, Storage as _, Transaction, TransactionId,
+    };
+    use std::cell::Cell;
+
+    /// Modelizer predicting with `predict`; fails its `fail_on`-th infer call.
+    struct MockModelizer {
+        predict: fn(&Transaction) -> bool,
+        fail_on: Option<u32>,
+        calls: Cell<u32>,
+        scored: Cell<usize>,
+    }
+
+    impl MockModelizer {
+        fn new(predict: fn(&Transaction) -> bool) -> Self {
+            Self { predict, fail_on: None, calls: Cell::new(0), scored: Cell::new(0) }
+        }
+    }
+
+    impl Modelizer for MockModelizer {
+        async fn infer(
+            &self,
+            batch: Vec<Transaction>,
+        ) -> Result<Vec<InferredTransaction>, ModelizerError> {
+            self.calls.set(self.calls.get() + 1);
+            if self.fail_on == Some(self.calls.get()) {
+                return Err(ModelizerError::InferenceFailed { reason: "crash".to_owned() });
+            }
+            self.scored.set(self.scored.get() + batch.len());
+            Ok(batch
+                .into_iter()
+                .map(|tx| InferredTransaction {
+                    predicted_fraud: (self.predict)(&tx),
+                    model_name: "NEXT".to_owned(),
+                    model_version: "5".to_owned(),
+                    delivery_attempts: 1,
+                    transaction: tx,
+                    cached: false,
+                })
+                .collect())
+        }
+
+        async fn switch_version(&self, _version: ModelVersion) -> Result<(), ModelizerError> {
+            Ok(())
+        }
+    }
+
+    /// Original prediction: every other stored row is named, and flagged, "Even".
+    fn originally_flagged(tx: &Transaction) -> bool {
+        tx.last_name == "Even"
+    }
+
+    /// 100 stored rows; 50 originally flagged.
+    async fn make_storage() -> SqliteStorage {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let rows = (0..100u32)
+            .map(|i| {
+                let transaction = Transaction {
+                    id: TransactionId::new_v4(),
+                    amount: f64::from(i),
+                    last_name: if i % 2 == 0 { "Even" } else { "Odd" }.to_owned(),
+                    currency: Currency::Eur,
+                    tenant_id: None,
+                };
+                PendingTransaction::new(InferredTransaction {
+                    predicted_fraud: originally_flagged(&transaction),
+                    model_name: "DEMO".to_owned(),
+                    model_version: "4".to_owned(),
+                    delivery_attempts: 1,
+                    transaction,
+                    cached: false,
+                })
+            })
+            .collect();
+        storage.write_batch(rows).await.unwrap();
+        storage
+    }
+
+    fn make_config() -> RescoreConfig {
+        RescoreConfig { batch_size: 10, progress_every: 25, ..RescoreConfig::new("test") }
+    }
+
+    // RS-T01: every stored row is rescored once.
+    #[tokio::test]
+    async fn rescores_every_row() {
+        let storage = make_storage().await;
+        // Always disagrees, so the disagreement count equals the rows written.
+        let modelizer = MockModelizer::new(|tx| !originally_flagged(tx));
+
+        let report = rescore(&storage, &modelizer, &make_config()).await.unwrap();
+
+        assert_eq!((report.resumed_from, report.processed), (0, 100));
+        assert_eq!((report.model_name.as_str(), report.model_version.as_str()), ("NEXT", "5"));
+        assert_eq!(modelizer.scored.get(), 100);
+        assert_eq!(storage.rescore_disagreements("NEXT", "5").await.unwrap(), 100);
+        assert_eq!(storage.rescore_high_water("test").await.unwrap(), report.high_water);
+    }
+
+    // RS-T02: resuming after a mid-run failure does not score a row twice.
+    #[tokio::test]
+    async fn resume_after_crash_skips_committed_pages() {
+        let storage = make_storage().await;
+        let crashing =
+            MockModelizer { fail_on: Some(3), ..MockModelizer::new(|tx| !originally_flagged(tx)) };
+
+        rescore(&storage, &crashing, &make_config()).await.unwrap_err();
+        assert_eq!(crashing.scored.get(), 20);
+
+        let modelizer = MockModelizer::new(|tx| !originally_flagged(tx));
+        let report = rescore(&storage, &modelizer, &make_config()).await.unwrap();
+
+        assert_eq!(report.processed, 80);
+        assert_eq!(modelizer.scored.get(), 80);
+        assert_eq!(storage.rescore_disagreements("NEXT", "5").await.unwrap(), 100);
+    }
+
+    // RS-T03: the disagreement query counts only changed predictions.
+    #[tokio::test]
+    async fn disagreement_count_matches_changed_predictions() {
+        let storage = make_storage().await;
+        // Flags everything: the 50 originally unflagged rows disagree.
+        let modelizer = MockModelizer::new(|_| true);
+
+        rescore(&storage, &modelizer, &make_config()).await.unwrap();
+
+        assert_eq!(storage.rescore_disagreements("NEXT", "5").await.unwrap(), 50);
+        assert_eq!(storage.rescore_disagreements("NEXT", "6").await.unwrap(), 0);
+    }
+
+    // RS-T04: a finished job finds nothing new on rerun.
+    #[tokio::test]
+    async fn rerun_of_finished_job_is_noop() {
+        let storage = make_storage().await;
+        rescore(&storage, &MockModelizer::new(|_| true), &make_config()).await.unwrap();
+
+        let modelizer = MockModelizer::new(|_| true);
+        let report = rescore(&storage, &modelizer, &make_config()).await.unwrap();
+
+        assert_eq!(report.processed, 0);
+        assert_eq!(modelizer.calls.get(), 0);
+    }
+}
@@ -0,0 +1,289 @@
+// Rust guideline compliant 2026-02-27
+
+//! Settings file reloaded while `fraud_detection` runs.
+//!
+//! `--settings <file>` names a file of `key = value` lines, a flat subset of
+//! TOML:
+//!
+//! ```text
+//! # Every key is optional; a missing key keeps the value in force.
+//! poll_interval1_ms = 500
+//! poll_interval2_ms = 25
+//! poll_interval3_ms = 25
+//! batch_size_mode = "geometric:0.2"   # uniform, max or geometric:P
+//! slow_alarm_threshold_ms = 250       # 0 never warns
+//! ```
+//!
+//! The file is read once at startup, where an invalid file is a
+//! configuration error, then polled by [`watch`]: each edit is parsed over the
+//! settings in force and published on the stages' `DynamicSettings` channel.
+//! An invalid edit is logged and ignored; the stages keep the old values.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context as _;
+use consumer::ConsumerConfig;
+use domain::{BatchSizeMode, DynamicSettings};
+use logger::LoggerConfig;
+use producer::ProducerConfig;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// `tracing` target of every settings reload event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::ORCHESTRATOR;
+
+/// Find `--settings <file>` or `--settings=<file>` in `args`.
+///
+/// # Errors
+///
+/// Returns an error if `--settings` has no value.
+pub fn path_from_args(
+    args: impl IntoIterator<Item = String>,
+) -> anyhow::Result<Option<PathBuf>> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--settings" {
+            let Some(path) = args.next() else { anyhow::bail!("--settings requires a value") };
+            return Ok(Some(PathBuf::from(path)));
+        }
+        if let Some(path) = arg.strip_prefix("--settings=") {
+            return Ok(Some(PathBuf::from(path)));
+        }
+    }
+    Ok(None)
+}
+
+/// Apply the `key = value` lines of `text` over `base`.
+///
+/// # Errors
+///
+/// Returns an error naming the line on an unknown key or a malformed value,
+/// or when the result fails [`DynamicSettings::validate`].
+pub fn parse(text: &str, base: DynamicSettings) -> anyhow::Result<DynamicSettings> {
+    let mut settings = base;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let context = || format!("line {}: {line}", index + 1);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(anyhow::anyhow!("expected key = value")).with_context(context);
+        };
+        let value = value.trim().trim_matches('"');
+        let millis = || value.parse::<u64>().map(Duration::from_millis);
+        match key.trim() {
+            "poll_interval1_ms" => settings.poll_interval1 = millis().with_context(context)?,
+            "poll_interval2_ms" => settings.poll_interval2 = millis().with_context(context)?,
+            "poll_interval3_ms" => settings.poll_interval3 = millis().with_context(context)?,
+            "slow_alarm_threshold_ms" => {
+                let threshold = millis().with_context(context)?;
+                settings.slow_alarm_threshold = (!threshold.is_zero()).then_some(threshold);
+            }
+            "batch_size_mode" => {
+                settings.batch_size_mode = parse_batch_size_mode(value).with_context(context)?;
+            }
+            other => {
+                return Err(anyhow::anyhow!("unknown key {other}")).with_context(context);
+            }
+        }
+    }
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// `uniform`, `max` or `geometric:P`, as `fraud_detection_bench` spells them.
+fn parse_batch_size_mode(value: &str) -> anyhow::Result<BatchSizeMode> {
+    match value {
+        "uniform" => Ok(BatchSizeMode::Uniform),
+        "max" => Ok(BatchSizeMode::AlwaysMax),
+        other => {
+            let Some(p) = other.strip_prefix("geometric:") else {
+                anyhow::bail!("unknown batch_size_mode {other}");
+            };
+            Ok(BatchSizeMode::Geometric { p: p.parse()? })
+        }
+    }
+}
+
+/// Read `path` and [`parse`] it over `base`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails [`parse`].
+pub fn load(path: &Path, base: DynamicSettings) -> anyhow::Result<DynamicSettings> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read settings file {}", path.display()))?;
+    parse(&text, base).with_context(|| format!("invalid settings file {}", path.display()))
+}
+
+/// Load `path` over the values the stage configs hold, hand each config the
+/// receiving end of the settings channel and spawn [`watch`] on `path`.
+///
+/// The Consumer's `batch_size_mode` seeds the shared one.
+///
+/// # Errors
+///
+/// Returns an error if the file fails [`load`]; the configs are untouched.
+pub fn follow(
+    path: PathBuf,
+    (producer, consumer, logger): (&mut ProducerConfig, &mut ConsumerConfig, &mut LoggerConfig),
+    every: Duration,
+) -> anyhow::Result<JoinHandle<()>> {
+    let seed = DynamicSettings {
+        poll_interval1: producer.poll_interval1,
+        poll_interval2: consumer.poll_interval2,
+        poll_interval3: logger.poll_interval3,
+        batch_size_mode: consumer.batch_size_mode,
+        slow_alarm_threshold: consumer.slow_alarm_threshold,
+    };
+    let (tx, rx) = watch::channel(load(&path, seed)?);
+    producer.settings = Some(rx.clone());
+    consumer.settings = Some(rx.clone());
+    logger.settings = Some(rx);
+    Ok(tokio::spawn(watch(path, tx, every)))
+}
+
+/// Poll `path` every `every` and publish each valid edit on `tx`.
+///
+/// An edit is parsed over the settings currently published, so removing a
+/// key keeps its value. Unreadable or invalid contents are logged once and
+/// ignored. Runs until `tx` has no receiver left.
+pub async fn watch(path: PathBuf, tx: watch::Sender<DynamicSettings>, every: Duration) {
+    let mut last: Option<String> = None;
+    let mut readable = true;
+    while !tx.is_closed() {
+        tokio::time::sleep(every).await;
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                if std::mem::replace(&mut readable, false) {
+                    tracing::warn!(
+                        target: TARGET,
+                        path = %path.display(),
+                        error = %e,
+                        "settings.file.unreadable"
+                    );
+                }
+                continue;
+            }
+        };
+        readable = true;
+        if last.as_ref() == Some(&text) {
+            continue;
+        }
+        // The first pass re-reads what `load` already applied: nothing changes.
+        let current = *tx.borrow();
+        match parse(&text, current) {
+            Ok(settings) => {
+                if tx.send_if_modified(|current| std::mem::replace(current, settings) != settings) {
+                    tracing::info!(
+                        target: TARGET,
+                        path = %path.display(),
+                        ?settings,
+                        "settings.file.reloaded"
+                    );
+                }
+            }
+            Err(e) => {
+                let error = format!("{e:#}");
+                tracing::warn!(
+                    target: TARGET,
+                    path = %path.display(),
+                    error,
+                    "settings.file.rejected"
+                );
+            }
+        }
+        last = Some(text);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, path_from_args, watch};
+    use domain::{BatchSizeMode, DynamicSettings};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|&arg| arg.to_owned()).collect()
+    }
+
+    // SF-T01: --settings takes the next argument or an `=` value.
+    #[test]
+    fn settings_path_from_args() {
+        let path = path_from_args(args(&["--check", "--settings", "live.toml"])).unwrap();
+        assert_eq!(path, Some(PathBuf::from("live.toml")));
+        let path = path_from_args(args(&["--settings=live.toml"])).unwrap();
+        assert_eq!(path, Some(PathBuf::from("live.toml")));
+        assert_eq!(path_from_args(args(&["--model", "bench"])).unwrap(), None);
+        path_from_args(args(&["--settings"])).unwrap_err();
+    }
+
+    // SF-T02: keys overlay the base; comments, quotes and blank lines are fine.
+    #[test]
+    fn parse_overlays_the_base() {
+        let text = "# live\n\npoll_interval1_ms = 5\n\
+                    batch_size_mode = \"geometric:0.5\" # mostly 1-2\n\
+                    slow_alarm_threshold_ms = 250\n";
+        let base = DynamicSettings::default();
+        let settings = parse(text, base).unwrap();
+        assert_eq!(
+            settings,
+            DynamicSettings {
+                poll_interval1: Duration::from_millis(5),
+                batch_size_mode: BatchSizeMode::Geometric { p: 0.5 },
+                slow_alarm_threshold: Some(Duration::from_millis(250)),
+                ..base
+            }
+        );
+        let text = "slow_alarm_threshold_ms = 0\nbatch_size_mode = max";
+        let settings = parse(text, settings).unwrap();
+        assert_eq!(settings.slow_alarm_threshold, None);
+        assert_eq!(settings.batch_size_mode, BatchSizeMode::AlwaysMax);
+    }
+
+    // SF-T03: unknown keys, malformed values and invalid settings are rejected.
+    #[test]
+    fn parse_rejects_invalid_lines() {
+        let base = DynamicSettings::default();
+        for (text, expected) in [
+            ("poll_interval9_ms = 5", "line 1: poll_interval9_ms = 5: unknown key"),
+            ("\npoll_interval2_ms = -5", "line 2: poll_interval2_ms = -5: invalid digit"),
+            ("batch_size_mode", "line 1: batch_size_mode: expected key = value"),
+            ("batch_size_mode = geometric:2", "p must be in (0, 1] (got 2)"),
+        ] {
+            let err = format!("{:#}", parse(text, base).unwrap_err());
+            assert!(err.starts_with(expected), "{text:?}: {err}");
+        }
+    }
+
+    // SF-T04: watch publishes a valid edit and ignores an invalid one.
+    #[tokio::test(start_paused = true)]
+    async fn watch_publishes_valid_edits_only() {
+        let name = format!("settings-{}.toml", uuid::Uuid::new_v4());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, "poll_interval1_ms = 100\n").unwrap();
+        let (tx, mut rx) = tokio::sync::watch::channel(DynamicSettings::default());
+        let watcher = tokio::spawn(watch(path.clone(), tx, Duration::from_secs(1)));
+
+        std::fs::write(&path, "poll_interval1_ms = 7\n").unwrap();
+        rx.changed().await.unwrap();
+        assert_eq!(rx.borrow_and_update().poll_interval1, Duration::from_millis(7));
+
+        std::fs::write(&path, "poll_interval1_ms = 1\nbatch_size_mode = geometric:0\n").unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert!(!rx.has_changed().unwrap(), "an invalid edit is not published");
+        assert_eq!(rx.borrow().poll_interval1, Duration::from_millis(7));
+
+        watcher.abort();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
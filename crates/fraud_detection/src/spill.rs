@@ -0,0 +1,513 @@
+// Rust guideline compliant 2026-02-27
+
+//! Spill file for transactions the Logger could not persist.
+//!
+//! When storage stays unavailable after every restart, `fraud_detection_sqlite`
+//! gathers what is still in memory -- the Logger's retained items and the rest
+//! of Buffer2 -- with [`collect_unpersisted`], writes it to a JSON Lines file
+//! with [`write_spill`] and exits with [`StorageUnavailable`]. A later run
+//! started with `--recover <spill-file>` feeds the file back through
+//! [`recover`] before the pipeline starts.
+//!
+//! One [`PendingTransaction`] per line, every field included; times are
+//! nanoseconds since the Unix epoch, or `null`.
+
+use std::fmt;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context as _;
+use domain::{
+    Buffer2Read as _, Currency, InferredTransaction, PendingTransaction, RunId, Storage,
+    Transaction, TransactionId,
+};
+use logger::Logger;
+use serde_json::{Value, json};
+
+use crate::adapters::concurrent_buffer2::ConcurrentBuffer2;
+
+/// Suffix appended to a spill file once [`recover`] has replayed it.
+pub const RECOVERED_SUFFIX: &str = "recovered";
+
+// ---------------------------------------------------------------------------
+// StorageUnavailable
+// ---------------------------------------------------------------------------
+
+/// Pipeline stopped because storage could not be reached; the unpersisted
+/// transactions were spilled to `spill`.
+#[derive(Debug)]
+pub struct StorageUnavailable {
+    /// Spill file to pass to `--recover`.
+    pub spill: PathBuf,
+    /// Transactions written to the spill file.
+    pub spilled: usize,
+}
+
+impl fmt::Display for StorageUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "storage unavailable: {} transactions spilled to {}",
+            self.spilled,
+            self.spill.display()
+        )
+    }
+}
+
+impl std::error::Error for StorageUnavailable {}
+
+// ---------------------------------------------------------------------------
+// Encoding
+// ---------------------------------------------------------------------------
+
+/// Nanoseconds since the Unix epoch, `null` for `None`.
+fn time_json(t: Option<SystemTime>) -> Value {
+    t.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .and_then(|d| u64::try_from(d.as_nanos()).ok())
+        .map_or(Value::Null, Value::from)
+}
+
+/// Encode `p` as a single-line JSON object.
+pub fn pending_json(p: &PendingTransaction) -> String {
+    let inferred = &p.inferred_transaction;
+    json!({
+        "id": inferred.transaction.id.full(),
+        "amount": inferred.transaction.amount,
+        "last_name": inferred.transaction.last_name,
+        "currency": inferred.transaction.currency.code(),
+        "tenant_id": inferred.transaction.tenant_id,
+        "predicted_fraud": inferred.predicted_fraud,
+        "model_name": inferred.model_name,
+        "model_version": inferred.model_version,
+        "delivery_attempts": inferred.delivery_attempts,
+        "cached": inferred.cached,
+        "is_reviewed": p.is_reviewed,
+        "actual_fraud": p.actual_fraud,
+        "record_version": p.record_version,
+        "persisted_at": time_json(p.persisted_at),
+        "reviewed_at": time_json(p.reviewed_at),
+        "run_id": p.run_id.map(|id| id.0),
+    })
+    .to_string()
+}
+
+/// Decode one line written by [`pending_json`].
+///
+/// # Errors
+///
+/// Returns an error if the line is not valid JSON or a field is missing or
+/// of the wrong type.
+pub fn parse_pending(line: &str) -> anyhow::Result<PendingTransaction> {
+    let v: Value = serde_json::from_str(line).context("invalid JSON")?;
+    let field = |name: &str| v.get(name).with_context(|| format!("missing field `{name}`"));
+    let string = |name: &str| {
+        field(name)?
+            .as_str()
+            .map(str::to_owned)
+            .with_context(|| format!("`{name}` is not a string"))
+    };
+    let boolean = |name: &str| {
+        field(name)?.as_bool().with_context(|| format!("`{name}` is not a boolean"))
+    };
+    let time = |name: &str| -> anyhow::Result<Option<SystemTime>> {
+        let value = field(name)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        let nanos = value.as_u64().with_context(|| format!("`{name}` is not a timestamp"))?;
+        Ok(Some(UNIX_EPOCH + Duration::from_nanos(nanos)))
+    };
+
+    let id = uuid::Uuid::parse_str(&string("id")?).context("`id` is not a UUID")?;
+    let actual_fraud = match field("actual_fraud")? {
+        Value::Null => None,
+        v => Some(v.as_bool().context("`actual_fraud` is not a boolean")?),
+    };
+    // Spills written before the currency existed hold EUR amounts.
+    let currency = match v.get("currency") {
+        None => Currency::Eur,
+        Some(code) => {
+            let code = code.as_str().context("`currency` is not a string")?;
+            let Ok(currency) = code.parse::<Currency>();
+            currency
+        }
+    };
+    // Spills written before tenants existed carry none.
+    let tenant_id = match v.get("tenant_id") {
+        None | Some(Value::Null) => None,
+        Some(t) => Some(t.as_str().context("`tenant_id` is not a string")?.to_owned()),
+    };
+    // Spills written before runs were recorded belong to none.
+    let run_id = match v.get("run_id") {
+        None | Some(Value::Null) => None,
+        Some(id) => Some(RunId(id.as_u64().context("`run_id` is not a u64")?)),
+    };
+    // Spills written before retries were recorded took one attempt.
+    let delivery_attempts = match v.get("delivery_attempts") {
+        None => 1,
+        Some(n) => n
+            .as_u64()
+            .and_then(|n| u8::try_from(n).ok())
+            .context("`delivery_attempts` is not a u8")?,
+    };
+    // Spills written before the inference cache existed hold model verdicts.
+    let cached = match v.get("cached") {
+        None => false,
+        Some(flag) => flag.as_bool().context("`cached` is not a boolean")?,
+    };
+    let record_version = field("record_version")?
+        .as_u64()
+        .and_then(|n| u32::try_from(n).ok())
+        .context("`record_version` is not a u32")?;
+    Ok(PendingTransaction {
+        inferred_transaction: InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::from_uuid(id),
+                amount: field("amount")?.as_f64().context("`amount` is not a number")?,
+                last_name: string("last_name")?,
+                currency,
+                tenant_id,
+            },
+            predicted_fraud: boolean("predicted_fraud")?,
+            model_name: string("model_name")?,
+            model_version: string("model_version")?,
+            delivery_attempts,
+            cached,
+        },
+        is_reviewed: boolean("is_reviewed")?,
+        actual_fraud,
+        record_version,
+        persisted_at: time("persisted_at")?,
+        reviewed_at: time("reviewed_at")?,
+        run_id,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Spill / recover
+// ---------------------------------------------------------------------------
+
+/// Spill everything the Logger has not persisted -- its retained items, then
+/// the rest of `buffer2`, which is closed -- to a new file in `dir`.
+///
+/// Transactions still in Buffer1 have no prediction yet and are not spilled.
+///
+/// # Errors
+///
+/// Returns an error if the spill file cannot be written (see [`write_spill`]).
+pub async fn spill_unpersisted(
+    logger: &Logger,
+    buffer2: &ConcurrentBuffer2,
+    dir: &Path,
+) -> anyhow::Result<StorageUnavailable> {
+    let at = SystemTime::now();
+    let items = collect_unpersisted(logger, buffer2, at).await;
+    let spill = write_spill(dir, &items, at)?;
+    Ok(StorageUnavailable { spill, spilled: items.len() })
+}
+
+/// Take the Logger's retained items, then close and drain `buffer2`.
+///
+/// Drained items were never seen by the Logger; they are stamped
+/// `persisted_at = at` as the Logger would have.
+async fn collect_unpersisted(
+    logger: &Logger,
+    buffer2: &ConcurrentBuffer2,
+    at: SystemTime,
+) -> Vec<PendingTransaction> {
+    let mut items = logger.take_retained();
+    buffer2.close();
+    // A closed buffer returns what it holds, then Closed.
+    while let Ok(batch) = buffer2.read_batch(usize::MAX).await {
+        items.extend(batch.into_iter().map(|it| PendingTransaction {
+            persisted_at: Some(at),
+            ..PendingTransaction::new(it)
+        }));
+    }
+    items
+}
+
+/// Write `items` to a new `spill-<unix ms>.jsonl` file in `dir`, creating
+/// `dir` if needed, and return its path.
+///
+/// # Errors
+///
+/// Returns an error if the directory or the file cannot be created or written;
+/// an existing file is never overwritten.
+pub fn write_spill(
+    dir: &Path,
+    items: &[PendingTransaction],
+    at: SystemTime,
+) -> anyhow::Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create spill directory {}", dir.display()))?;
+    let millis = at.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let path = dir.join(format!("spill-{millis}.jsonl"));
+    let mut out = String::new();
+    for p in items {
+        out.push_str(&pending_json(p));
+        out.push('\n');
+    }
+    fs::File::create_new(&path)
+        .and_then(|mut file| {
+            file.write_all(out.as_bytes())?;
+            file.sync_all()
+        })
+        .with_context(|| format!("failed to write spill file {}", path.display()))?;
+    Ok(path)
+}
+
+/// Read every transaction of the spill file at `path`; blank lines are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or a line cannot be decoded.
+pub fn read_spill(path: &Path) -> anyhow::Result<Vec<PendingTransaction>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read spill file {}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_pending(line)
+                .with_context(|| format!("{}:{}: invalid spill record", path.display(), i + 1))
+        })
+        .collect()
+}
+
+/// Replay the spill file at `path` into `storage` in one batch, then rename it
+/// to `<path>.recovered` so it is not replayed again. Returns the number of
+/// transactions written.
+///
+/// On any error the file is left in place for another attempt.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or decoded, the write fails or
+/// the file cannot be renamed.
+pub async fn recover<S: Storage>(path: &Path, storage: &S) -> anyhow::Result<usize> {
+    let items = read_spill(path)?;
+    let count = items.len();
+    if count > 0 {
+        storage
+            .write_batch(items)
+            .await
+            .with_context(|| format!("failed to replay spill file {}", path.display()))?;
+    }
+    let mut done = path.as_os_str().to_owned();
+    done.push(".");
+    done.push(RECOVERED_SUFFIX);
+    fs::rename(path, &done)
+        .with_context(|| format!("failed to rename recovered spill file {}", path.display()))?;
+    Ok(count)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RECOVERED_SUFFIX, parse_pending, pending_json, read_spill, recover, spill_unpersisted,
+        write_spill,
+    };
+    use crate::adapters::concurrent_buffer2::ConcurrentBuffer2;
+    use crate::orchestrator::{RestartPolicy, supervise};
+    use domain::{
+        Buffer2 as _, Currency, InferredTransaction, PendingTransaction, RunId, Storage,
+        StorageError, Transaction, TransactionId,
+    };
+    use logger::{Logger, LoggerConfig};
+    use pipeline::memory::MemoryStorage;
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// Storage that is never reachable.
+    struct DownStorage;
+
+    impl Storage for DownStorage {
+        async fn write_batch(&self, _: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            Err(StorageError::Unavailable)
+        }
+    }
+
+    fn inferred(i: u32) -> InferredTransaction {
+        InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: f64::from(i) + 0.25,
+                last_name: format!("Name \"{i}\"\n"),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud: i.is_multiple_of(3),
+            model_name: "DEMO".into(),
+            model_version: "4".into(),
+            delivery_attempts: 1,
+            cached: false,
+        }
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("spill-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn pending_round_trips_through_json() {
+        let at = UNIX_EPOCH + Duration::from_nanos(1_700_000_000_123_456_789);
+        let mut pending = PendingTransaction {
+            is_reviewed: true,
+            actual_fraud: Some(false),
+            persisted_at: Some(at),
+            reviewed_at: Some(at + Duration::from_secs(1)),
+            run_id: Some(RunId(7)),
+            ..PendingTransaction::new(inferred(3))
+        };
+        pending.inferred_transaction.transaction.currency = Currency::Other("JPY".into());
+        pending.inferred_transaction.delivery_attempts = 4;
+        pending.inferred_transaction.cached = true;
+        pending.inferred_transaction.transaction.tenant_id = Some("bank-a".into());
+
+        let line = pending_json(&pending);
+
+        assert!(!line.contains('\n'));
+        assert_eq!(parse_pending(&line).unwrap(), pending);
+    }
+
+    #[test]
+    fn record_without_currency_reads_as_eur() {
+        let mut pending = PendingTransaction::new(inferred(2));
+        pending.inferred_transaction.transaction.currency = Currency::Usd;
+        let line = pending_json(&pending);
+        let mut value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        value.as_object_mut().unwrap().remove("currency");
+
+        let parsed = parse_pending(&value.to_string()).unwrap();
+
+        assert_eq!(parsed.inferred_transaction.transaction.currency, Currency::Eur);
+    }
+
+    #[test]
+    fn record_without_delivery_attempts_reads_as_one() {
+        let mut pending = PendingTransaction::new(inferred(2));
+        pending.inferred_transaction.delivery_attempts = 5;
+        let line = pending_json(&pending);
+        let mut value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        value.as_object_mut().unwrap().remove("delivery_attempts");
+
+        let parsed = parse_pending(&value.to_string()).unwrap();
+
+        assert_eq!(parsed.inferred_transaction.delivery_attempts, 1);
+    }
+
+    #[test]
+    fn record_without_cached_flag_reads_as_inferred() {
+        let mut pending = PendingTransaction::new(inferred(2));
+        pending.inferred_transaction.cached = true;
+        let line = pending_json(&pending);
+        let mut value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        value.as_object_mut().unwrap().remove("cached");
+
+        let parsed = parse_pending(&value.to_string()).unwrap();
+
+        assert!(!parsed.inferred_transaction.cached);
+    }
+
+    #[test]
+    fn record_without_tenant_reads_as_none() {
+        let mut pending = PendingTransaction::new(inferred(2));
+        pending.inferred_transaction.transaction.tenant_id = Some("bank-b".into());
+        let line = pending_json(&pending);
+        let mut value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        value.as_object_mut().unwrap().remove("tenant_id");
+
+        let parsed = parse_pending(&value.to_string()).unwrap();
+
+        assert_eq!(parsed.inferred_transaction.transaction.tenant_id, None);
+    }
+
+    #[test]
+    fn malformed_record_is_rejected() {
+        let line = pending_json(&PendingTransaction::new(inferred(1)));
+
+        parse_pending(&line.replace("\"id\"", "\"uuid\"")).unwrap_err();
+        parse_pending("{not json").unwrap_err();
+    }
+
+    // Storage down for good: the Logger gives up after its restarts, and the
+    // spill holds every transaction that never reached storage. The four runs
+    // retain 40 items and leave 5 in Buffer2: an open, empty ConcurrentBuffer2
+    // would keep the last run waiting.
+    #[tokio::test(start_paused = true)]
+    async fn forced_storage_failure_spills_every_unpersisted_transaction() {
+        let buffer2 = ConcurrentBuffer2::new();
+        let sent: Vec<InferredTransaction> = (0..45).map(inferred).collect();
+        buffer2.write_batch(sent.clone()).await.unwrap();
+        let config = LoggerConfig::builder(10)
+            .fixed_batch_size(true)
+            .split_on_capacity(true)
+            .poll_interval3(Duration::ZERO)
+            .build()
+            .unwrap();
+        let logger = Logger::new(config);
+
+        let result =
+            supervise("logger", RestartPolicy::default(), || logger.run(&buffer2, &DownStorage))
+                .await;
+        result.unwrap_err();
+        assert_eq!(logger.retained(), 40);
+
+        let dir = temp_dir();
+        let spill = spill_unpersisted(&logger, &buffer2, &dir).await.unwrap();
+        let spilled = read_spill(&spill.spill).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(spill.spilled, 45);
+        assert_eq!(spilled.len(), 45);
+        let spilled: HashSet<_> = spilled.iter().map(PendingTransaction::id).collect();
+        let sent: HashSet<_> = sent.iter().map(InferredTransaction::id).collect();
+        assert_eq!(spilled, sent);
+        assert_eq!(logger.retained(), 0);
+    }
+
+    #[tokio::test]
+    async fn recovery_replays_once_and_renames_the_file() {
+        let items: Vec<_> = (0..5).map(|i| PendingTransaction::new(inferred(i))).collect();
+        let dir = temp_dir();
+        let path = write_spill(&dir, &items, SystemTime::now()).unwrap();
+        let storage = MemoryStorage::new();
+
+        let replayed = recover(&path, &storage).await.unwrap();
+        let again = recover(&path, &storage).await;
+
+        let mut done = path.clone().into_os_string();
+        done.push(format!(".{RECOVERED_SUFFIX}"));
+        let renamed = PathBuf::from(done).exists();
+        let still_there = path.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(replayed, 5);
+        again.expect_err("a recovered spill must not be replayed");
+        assert_eq!(storage.items(), items);
+        assert!(renamed);
+        assert!(!still_there);
+    }
+
+    #[tokio::test]
+    async fn failed_recovery_keeps_the_file() {
+        let items = vec![PendingTransaction::new(inferred(1))];
+        let dir = temp_dir();
+        let path = write_spill(&dir, &items, SystemTime::now()).unwrap();
+
+        let result = recover(&path, &DownStorage).await;
+        let still_there = path.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        result.unwrap_err();
+        assert!(still_there);
+    }
+}
@@ -0,0 +1,318 @@
+// Rust guideline compliant 2026-02-27
+
+//! Live terminal dashboard over the in-memory pipeline, for demos.
+//!
+//! Runs the same pipeline as `fraud_detection` (concurrent buffers, the
+//! `--model` backend, in-memory storage) and, instead of logs, renders at
+//! [`REFRESH`] intervals:
+//!
+//! - a tx/s sparkline per stage (Producer, Consumer, Logger)
+//! - Buffer1 and Buffer2 depth gauges, relative to the highest depth seen
+//! - the cumulative fraud rate
+//! - the last [`RECENT_ALARMS`] alarms, newest first
+//!
+//! Everything comes from the [`PipelineEvent`] broadcast channel and the
+//! buffers' `depth()`, folded into a [`Dashboard`] (see the `dashboard`
+//! module) that [`draw`] only reads.
+//!
+//! `q` (or `Esc`, or CTRL+C, which raw mode delivers as a key) takes the
+//! graceful shutdown path of `fraud_detection`: Buffer1 is closed and the
+//! stages drain both buffers, for up to [`SHUTDOWN_GRACE`]. No tracing
+//! subscriber is installed, since log lines would overwrite the dashboard.
+//!
+//! # Usage
+//!
+//! ```text
+//! cargo run --bin fraud_detection_tui --features tui
+//!
+//! # Pick the model as for fraud_detection: demo[:seed] (default) or bench
+//! cargo run --bin fraud_detection_tui --features tui -- --model demo:42
+//! ```
+//!
+//! On exit, the stage statistics and the queue delays of both buffers are
+//! printed once the terminal is restored.
+
+mod adapters;
+mod dashboard;
+mod model_backend;
+mod orchestrator;
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use adapters::concurrent_buffer::ConcurrentBuffer;
+use adapters::concurrent_buffer2::ConcurrentBuffer2;
+use adapters::in_memory_storage::InMemoryStorage;
+use adapters::log_alarm::LogAlarm;
+use anyhow::Context as _;
+use consumer::{Consumer, ConsumerConfig};
+use dashboard::{Dashboard, Depth, RECENT_ALARMS};
+use domain::{BufferDepth as _, PipelineEvent, Stage};
+use logger::{Logger, LoggerConfig};
+use model_backend::{ModelBackend, ModelSpec};
+use modelizer::Modelizer;
+use orchestrator::{RestartPolicy, Shutdown, shutdown_gracefully, supervise};
+use producer::{Producer, ProducerConfig};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, List, ListItem, Paragraph, Sparkline};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+/// Dashboard refresh period (~10 Hz).
+const REFRESH: Duration = Duration::from_millis(100);
+
+/// Time allowed after `q` for the pipeline to drain both buffers.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Events buffered for the dashboard between two refreshes before it lags.
+const EVENT_CAPACITY: usize = 4096;
+
+/// The three stages, in pipeline order.
+const STAGES: [Stage; 3] = [Stage::Producer, Stage::Consumer, Stage::Logger];
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let model_spec = ModelSpec::from_args(std::env::args().skip(1)).context("invalid --model")?;
+    let (events, rx) = broadcast::channel(EVENT_CAPACITY);
+
+    // Same cadence as fraud_detection: 100 tx every 500 ms, drained every 25 ms.
+    let producer_config = ProducerConfig::builder(100)
+        .poll_interval1(Duration::from_millis(500))
+        .events(events.clone())
+        .build()
+        .context("failed to build producer config")?;
+    let consumer_config = ConsumerConfig::builder(50)
+        .poll_interval2(Duration::from_millis(25))
+        .events(events.clone())
+        .build()
+        .context("failed to build consumer config")?;
+    let logger_config = LoggerConfig::builder(10)
+        .poll_interval3(Duration::from_millis(25))
+        .events(events)
+        .build()
+        .context("failed to build logger config")?;
+
+    let producer = Producer::new(producer_config);
+    let consumer = Consumer::new(consumer_config);
+    let logger = Logger::new(logger_config);
+    // Latency tracing: queue delay percentiles in the exit summary.
+    let buffer1 = ConcurrentBuffer::with_latency_tracing(true);
+    let buffer2 = ConcurrentBuffer2::with_latency_tracing(true);
+    let model = ModelBackend::from_spec(&model_spec).context("failed to load the model")?;
+    let modelizer = Modelizer::new(model);
+    // No subscriber: LogAlarm's log lines go nowhere, the alarm pane shows them.
+    let alarm = LogAlarm::new();
+    let storage = InMemoryStorage::new(usize::MAX);
+
+    let policy = RestartPolicy::default();
+    let (buf1, buf2) = (&buffer1, &buffer2);
+    let pipeline = async {
+        tokio::try_join!(
+            async {
+                let r = supervise("producer", policy, || producer.run(buf1)).await;
+                buffer1.close();
+                r.context("producer failed")
+            },
+            async {
+                let r = supervise("consumer", policy, || {
+                    consumer.run(buf1, &modelizer, &alarm, buf2)
+                })
+                .await;
+                buffer2.close();
+                r.context("consumer failed")
+            },
+            async {
+                supervise("logger", policy, || logger.run(buf2, &storage))
+                    .await
+                    .context("logger failed")
+            },
+        )
+    };
+
+    // ratatui::init installs a panic hook that restores the terminal first.
+    let mut terminal = ratatui::init();
+    let mut ui = Ok(());
+    let quit = async { ui = monitor(&mut terminal, rx, buf1, buf2).await };
+    let outcome = shutdown_gracefully(
+        pipeline,
+        quit,
+        || buffer1.close(),
+        SHUTDOWN_GRACE,
+        || buffer1.depth() + buffer2.depth(),
+    )
+    .await;
+    ratatui::restore();
+    ui.context("terminal I/O failed")?;
+
+    println!("{}", producer.stats());
+    println!("{}", consumer.stats());
+    println!("persisted: {} transactions", storage.len());
+    println!("buffer1 queue delay: {}", buffer1.latency_stats().unwrap_or_default());
+    println!("buffer2 queue delay: {}", buffer2.latency_stats().unwrap_or_default());
+    match outcome {
+        Shutdown::Completed(result) => {
+            let (producer_stop, consumer_stop, logger_stop) = result?;
+            println!(
+                "stopped: producer {producer_stop}, consumer {consumer_stop}, logger {logger_stop}"
+            );
+            Ok(())
+        }
+        Shutdown::Abandoned { abandoned } => {
+            anyhow::bail!("shutdown grace period expired: {abandoned} transactions abandoned")
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Event loop
+// ---------------------------------------------------------------------------
+
+/// Refresh the dashboard every [`REFRESH`] until a quit key is pressed.
+///
+/// Each refresh drains the pending events, samples the buffer depths, checks
+/// the keyboard without blocking (the stages share this thread) and redraws.
+/// Returns after drawing a last frame announcing the shutdown.
+///
+/// # Errors
+///
+/// Returns the first terminal read or write error.
+async fn monitor(
+    terminal: &mut DefaultTerminal,
+    mut rx: broadcast::Receiver<PipelineEvent>,
+    buffer1: &ConcurrentBuffer,
+    buffer2: &ConcurrentBuffer2,
+) -> io::Result<()> {
+    let mut dashboard = Dashboard::new();
+    let mut interval = tokio::time::interval(REFRESH);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut last = Instant::now();
+    loop {
+        interval.tick().await;
+        loop {
+            match rx.try_recv() {
+                Ok(event) => dashboard.apply(&event),
+                Err(TryRecvError::Lagged(skipped)) => dashboard.lagged(skipped),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+        let now = Instant::now();
+        dashboard.tick(now - last, buffer1.depth(), buffer2.depth());
+        last = now;
+
+        let quit = quit_pressed()?;
+        terminal.draw(|frame| draw(frame, &dashboard, quit))?;
+        if quit {
+            return Ok(());
+        }
+    }
+}
+
+/// Whether a quit key is waiting; consumes every pending terminal event.
+fn quit_pressed() -> io::Result<bool> {
+    let mut quit = false;
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            let ctrl_c =
+                key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            quit |= matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c;
+        }
+    }
+    Ok(quit)
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+/// Render `dashboard`: header, one sparkline per stage, the two buffer
+/// gauges, then the alarm pane.
+fn draw(frame: &mut Frame<'_>, dashboard: &Dashboard, quitting: bool) {
+    let [header, rates, gauges, alarms] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(12),
+        Constraint::Length(3),
+        Constraint::Min(4),
+    ])
+    .areas(frame.area());
+
+    draw_header(frame, header, dashboard, quitting);
+    let rows: [Rect; 3] = Layout::vertical([Constraint::Length(4); 3]).areas(rates);
+    for (stage, area) in STAGES.into_iter().zip(rows) {
+        draw_rate(frame, area, dashboard, stage);
+    }
+    let [buffer1, buffer2] = Layout::horizontal([Constraint::Percentage(50); 2]).areas(gauges);
+    draw_depth(frame, buffer1, "buffer1", dashboard.buffer1());
+    draw_depth(frame, buffer2, "buffer2", dashboard.buffer2());
+    draw_alarms(frame, alarms, dashboard);
+}
+
+fn draw_header(frame: &mut Frame<'_>, area: Rect, dashboard: &Dashboard, quitting: bool) {
+    let fraud_rate = dashboard
+        .fraud_rate()
+        .map_or_else(|| "-".to_owned(), |rate| format!("{:.2} %", rate * 100.0));
+    let status = if quitting { "draining buffers..." } else { "q: quit" };
+    let text = format!(
+        "fraud rate {fraud_rate} ({} of {} inferred)   alarms {} ({} failed)   \
+         lost events {}   {status}",
+        dashboard.flagged(),
+        dashboard.total(Stage::Consumer),
+        dashboard.alarms(),
+        dashboard.alarms_failed(),
+        dashboard.lost_events(),
+    );
+    let block = Block::bordered().title(" fraud_detection_tui ");
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
+
+fn draw_rate(frame: &mut Frame<'_>, area: Rect, dashboard: &Dashboard, stage: Stage) {
+    let name = match stage {
+        Stage::Producer => "producer",
+        Stage::Consumer => "consumer",
+        Stage::Logger => "logger",
+    };
+    let stopped =
+        dashboard.stopped(stage).map(|reason| format!("-- stopped: {reason} ")).unwrap_or_default();
+    let (rate, total) = (dashboard.rate(stage), dashboard.total(stage));
+    let title = format!(" {name}: {rate} tx/s, {total} total {stopped}");
+    // Only the newest samples that fit inside the borders.
+    let rates = dashboard.rates(stage);
+    let width = usize::from(area.width.saturating_sub(2));
+    let data: Vec<u64> = rates.iter().skip(rates.len().saturating_sub(width)).copied().collect();
+    let sparkline = Sparkline::default()
+        .block(Block::bordered().title(title))
+        .data(&data)
+        .style(Style::default().fg(Color::Cyan));
+    frame.render_widget(sparkline, area);
+}
+
+fn draw_depth(frame: &mut Frame<'_>, area: Rect, name: &str, depth: Depth) {
+    let gauge = Gauge::default()
+        .block(Block::bordered().title(format!(" {name} depth ")))
+        .gauge_style(Style::default().fg(Color::Yellow))
+        .ratio(depth.ratio())
+        .label(format!("{} (peak {})", depth.current, depth.peak));
+    frame.render_widget(gauge, area);
+}
+
+fn draw_alarms(frame: &mut Frame<'_>, area: Rect, dashboard: &Dashboard) {
+    let items: Vec<ListItem<'_>> = dashboard
+        .recent_alarms()
+        .map(|alarm| {
+            let amount = alarm.amount.map_or_else(|| "-".to_owned(), |a| format!("{a:.2}"));
+            let (status, color) = if alarm.delivered {
+                ("delivered", Color::Red)
+            } else {
+                ("FAILED", Color::Magenta)
+            };
+            let line = format!("#{:<6} {}  {amount:>10}  {status}", alarm.seq, alarm.id);
+            ListItem::new(Line::styled(line, Style::default().fg(color)))
+        })
+        .collect();
+    let title = format!(" last {RECENT_ALARMS} alarms ");
+    frame.render_widget(List::new(items).block(Block::bordered().title(title)), area);
+}
@@ -0,0 +1,178 @@
+// Rust guideline compliant 2026-02-27
+
+//! Amount histogram of the transactions a [`Logger`] persists.
+//!
+//! Buckets are half-open: with edges `[1, 10]` the buckets are `< 1`,
+//! `[1, 10)` and `>= 10`, so an amount exactly on an edge falls into the
+//! bucket that starts there. Counts are kept separately for transactions
+//! predicted legitimate and predicted fraudulent.
+//!
+//! [`Logger`]: crate::Logger
+
+use std::fmt;
+
+use domain::ConfigError;
+
+/// Default bucket edges: one bucket per order of magnitude up to 10 000.
+pub const DEFAULT_HISTOGRAM_EDGES: [f64; 5] = [1.0, 10.0, 100.0, 1_000.0, 10_000.0];
+
+// ---------------------------------------------------------------------------
+// Histogram
+// ---------------------------------------------------------------------------
+
+/// Amount counts per bucket, split by `predicted_fraud`.
+///
+/// `n` edges make `n + 1` buckets; see the [module docs](self) for the
+/// half-open rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    edges: Vec<f64>,
+    legit: Vec<u64>,
+    fraud: Vec<u64>,
+}
+
+impl Histogram {
+    /// Create an empty histogram over `edges`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] on `histogram_edges` when `edges` is empty,
+    /// holds a non-finite value, or is not strictly ascending.
+    pub fn new(edges: Vec<f64>) -> Result<Self, ConfigError> {
+        if edges.is_empty() {
+            return Err(ConfigError::new("histogram_edges", "[]", "must not be empty"));
+        }
+        let ascending = edges.windows(2).all(|w| w[0] < w[1]);
+        if !ascending || !edges.iter().all(|e| e.is_finite()) {
+            return Err(ConfigError::new(
+                "histogram_edges",
+                format!("{edges:?}"),
+                "must be finite and strictly ascending",
+            ));
+        }
+        let buckets = edges.len() + 1;
+        Ok(Self { edges, legit: vec![0; buckets], fraud: vec![0; buckets] })
+    }
+
+    /// Bucket edges, ascending.
+    #[must_use]
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    /// Index of the bucket holding `amount`: the number of edges `<= amount`.
+    ///
+    /// `NaN` compares below every edge and lands in bucket 0.
+    #[must_use]
+    pub fn bucket_of(&self, amount: f64) -> usize {
+        self.edges.partition_point(|&edge| edge <= amount)
+    }
+
+    /// Count one transaction of `amount`.
+    pub fn record(&mut self, amount: f64, predicted_fraud: bool) {
+        let bucket = self.bucket_of(amount);
+        let counts = if predicted_fraud { &mut self.fraud } else { &mut self.legit };
+        counts[bucket] += 1;
+    }
+
+    /// Per-bucket counts of transactions with this `predicted_fraud`.
+    #[must_use]
+    pub fn counts(&self, predicted_fraud: bool) -> &[u64] {
+        if predicted_fraud { &self.fraud } else { &self.legit }
+    }
+
+    /// Total transactions recorded.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.legit.iter().chain(&self.fraud).sum()
+    }
+
+    /// Label of bucket `i`: `< e0`, `[e0, e1)`, ..., `>= en`.
+    fn label(&self, i: usize) -> String {
+        match (i.checked_sub(1).map(|j| self.edges[j]), self.edges.get(i)) {
+            (None, Some(hi)) => format!("< {hi}"),
+            (Some(lo), Some(hi)) => format!("[{lo}, {hi})"),
+            (Some(lo), None) => format!(">= {lo}"),
+            (None, None) => unreachable!("a histogram has at least one edge"),
+        }
+    }
+}
+
+impl fmt::Display for Histogram {
+    /// ASCII table: a header, then one row per bucket.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<String> = (0..self.legit.len()).map(|i| self.label(i)).collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0).max("amount".len());
+        write!(f, "{:<width$}  {:>10}  {:>10}", "amount", "legit", "fraud")?;
+        for (i, label) in labels.iter().enumerate() {
+            write!(f, "\n{label:<width$}  {:>10}  {:>10}", self.legit[i], self.fraud[i])?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_HISTOGRAM_EDGES, Histogram};
+
+    fn default_histogram() -> Histogram {
+        Histogram::new(DEFAULT_HISTOGRAM_EDGES.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn amounts_land_in_their_buckets() {
+        let mut h = default_histogram();
+        for amount in [0.5, 5.0, 50.0, 500.0, 5_000.0, 9_999.99, 10_000.0] {
+            h.record(amount, false);
+        }
+        assert_eq!(h.counts(false), [1, 1, 1, 1, 2, 1]);
+        assert_eq!(h.counts(true), [0; 6]);
+        assert_eq!(h.total(), 7);
+    }
+
+    #[test]
+    fn fraud_and_legit_are_counted_apart() {
+        let mut h = default_histogram();
+        h.record(20.0, true);
+        h.record(20.0, false);
+        h.record(9_500.0, true);
+        assert_eq!(h.counts(false), [0, 0, 1, 0, 0, 0]);
+        assert_eq!(h.counts(true), [0, 0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn edge_values_open_the_next_bucket() {
+        let h = default_histogram();
+        assert_eq!(h.bucket_of(0.99), 0);
+        assert_eq!(h.bucket_of(1.0), 1);
+        assert_eq!(h.bucket_of(99.99), 2);
+        assert_eq!(h.bucket_of(100.0), 3);
+        assert_eq!(h.bucket_of(10_000.0), 5);
+    }
+
+    #[test]
+    fn invalid_edges_are_rejected() {
+        for edges in [vec![], vec![10.0, 1.0], vec![1.0, 1.0], vec![1.0, f64::NAN]] {
+            let e = Histogram::new(edges).unwrap_err();
+            assert_eq!(e.field, "histogram_edges");
+        }
+    }
+
+    #[test]
+    fn display_is_one_row_per_bucket() {
+        let mut h = Histogram::new(vec![1.0, 10.0]).unwrap();
+        h.record(5.0, true);
+        h.record(10.0, false);
+        assert_eq!(
+            h.to_string(),
+            "amount        legit       fraud\n\
+             < 1               0           0\n\
+             [1, 10)           0           1\n\
+             >= 10             1           0"
+        );
+    }
+}
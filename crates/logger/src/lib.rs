@@ -4,32 +4,105 @@
 //!
 //! Entry points: [`Logger::log_once`], [`Logger::run`].
 //! Configuration via [`LoggerConfig::builder`].
+//!
+//! With [`LoggerConfigBuilder::split_on_capacity`], a batch rejected with
+//! `CapacityExceeded { remaining > 0 }` is split: the first `remaining` items
+//! are persisted and the rest are retained and prepended to the next batch.
+//! [`LoggerConfigBuilder::max_retained`] bounds that backlog during a storage
+//! outage: past the cap the Logger stops reading Buffer2 ([`RetainedOverflow`]).
+//!
+//! With [`LoggerConfigBuilder::adaptive_interval`], the sleep between
+//! iterations follows how full the last read was; see `AdaptiveInterval`.
+//!
+//! With [`LoggerConfigBuilder::dedup_preload`], [`Logger::preload_seen_ids`]
+//! loads the ids already in storage so a restarted run over the same data
+//! skips them instead of writing them again.
+//!
+//! [`Logger::histogram`] counts the persisted amounts per bucket, split by
+//! `predicted_fraud`; edges via [`LoggerConfigBuilder::histogram_edges`].
+//!
+//! With [`LoggerConfigBuilder::settings`], `run` reads `poll_interval3` and
+//! `batch_size_mode` from a live `DynamicSettings` channel at the top of each
+//! iteration, so they can change without a restart.
+
+mod histogram;
 
-use domain::{Buffer2Read, BufferError, InferredTransaction, PendingTransaction, Storage, StorageError};
+pub use histogram::{DEFAULT_HISTOGRAM_EDGES, Histogram};
+
+use domain::profile::{self, Op};
+use domain::{
+    AdaptiveInterval, BatchSizeMode, Buffer2Read, Clock, ConfigError, ConfigSummary,
+    DynamicSettings, ErrorChain, EventSender, InferredTransaction, LiveSettings, PacingStats,
+    PendingTransaction, PipelineEvent, Preset, ReadError, RngPort, RunId, SettingsReceiver, Sleeper,
+    Stage, StopReason, Storage, StorageError, StorageRead, StreamDigest, SystemClock, TokioSleeper,
+    TransactionId,
+};
 use rand::{SeedableRng, rngs::StdRng};
-use rand::Rng as _;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
+/// `tracing` target of every Logger event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::LOGGER;
+
 // ---------------------------------------------------------------------------
 // LoggerError
 // ---------------------------------------------------------------------------
 
 /// Errors that can occur during logger operation.
+///
+/// `Display` gives only this layer's context; the wrapped error is the
+/// [`source`](std::error::Error::source). Log with [`ErrorChain`] to see both.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum LoggerError {
     /// The supplied configuration is invalid.
-    #[error("invalid logger configuration: {reason}")]
-    InvalidConfig {
-        /// Human-readable description of the problem.
-        reason: String,
-    },
+    #[error("invalid logger configuration")]
+    InvalidConfig(#[from] ConfigError),
     /// A buffer read failed.
-    #[error("buffer read error: {0}")]
-    Read(#[from] BufferError),
+    #[error("buffer read error")]
+    Read(#[from] ReadError),
     /// A storage write failed.
-    #[error("storage write error: {0}")]
+    #[error("storage write error")]
     Write(#[from] StorageError),
+    /// Reading the already-persisted ids failed (see [`Logger::preload_seen_ids`]).
+    #[error("storage preload error")]
+    Preload(#[source] StorageError),
+}
+
+impl LoggerError {
+    /// Whether restarting the run loop after a backoff may succeed.
+    ///
+    /// - `InvalidConfig`: fatal.
+    /// - `Read`: fatal (`Closed` is the normal end of data).
+    /// - `Write`: retryable for `Unavailable`, fatal for `CapacityExceeded`.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::InvalidConfig(_) | Self::Read(_) => false,
+            Self::Write(e) | Self::Preload(e) => e.is_retryable(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RetainedOverflow
+// ---------------------------------------------------------------------------
+
+/// What the Logger does once [`max_retained`](LoggerConfigBuilder::max_retained)
+/// items are retained, e.g. during a long storage outage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetainedOverflow {
+    /// Stop reading Buffer2 until the backlog drains (default): Buffer2 fills
+    /// up and pushes back on the Consumer. Reads are shortened to the room
+    /// left; each skipped read is counted in [`Logger::backpressured`].
+    #[default]
+    Backpressure,
+    /// Keep reading and drop the oldest retained items beyond the cap,
+    /// counted in [`Logger::dropped`]. Spill them first with
+    /// [`Logger::take_retained`] if they must not be lost.
+    DropOldest,
 }
 
 // ---------------------------------------------------------------------------
@@ -43,12 +116,44 @@ pub enum LoggerError {
 pub struct LoggerConfig {
     /// Maximum batch size drawn from Buffer2 per iteration (range: `[1, n3_max]`).
     pub n3_max: usize,
+    /// Whether every read requests exactly `n3_max` items instead of a random
+    /// size in `[1, n3_max]`.
+    pub fixed_batch_size: bool,
+    /// How read sizes are drawn; ignored when `fixed_batch_size` is set.
+    pub batch_size_mode: BatchSizeMode,
     /// Delay between successive iterations.
     pub poll_interval3: Duration,
     /// Optional upper bound on the number of iterations. `None` means infinite.
     pub iterations: Option<u64>,
     /// Optional RNG seed for reproducible batch sizing. `None` seeds from the OS.
     pub seed: Option<u64>,
+    /// Optional observer channel for [`PipelineEvent`]s.
+    pub events: Option<EventSender>,
+    /// Persist what still fits when storage reports spare capacity, retaining the rest.
+    pub split_on_capacity: bool,
+    /// Time source for `PendingTransaction::persisted_at`.
+    pub clock: Arc<dyn Clock>,
+    /// Waits out the inter-iteration delay.
+    pub sleeper: Arc<dyn Sleeper>,
+    /// Adapt the inter-iteration sleep to the last read. `None` sleeps
+    /// `poll_interval3` every time.
+    pub adaptive_interval: Option<AdaptiveInterval>,
+    /// Most ids [`Logger::preload_seen_ids`] loads to skip already-persisted
+    /// transactions. `None` disables the preload and the skip.
+    pub dedup_preload: Option<usize>,
+    /// Empty histogram whose buckets [`Logger::histogram`] fills.
+    pub histogram: Histogram,
+    /// Run stamped on every `PendingTransaction::run_id`. `None` leaves it unset.
+    pub run_id: Option<RunId>,
+    /// Most items retained between writes. `None` means unbounded.
+    pub max_retained: Option<usize>,
+    /// What happens once `max_retained` is reached.
+    pub retained_overflow: RetainedOverflow,
+    /// Live settings channel; `None` keeps `poll_interval3` and
+    /// `batch_size_mode` for the run.
+    pub settings: Option<SettingsReceiver>,
+    /// Preset the builder started from, if any.
+    pub preset: Option<Preset>,
 }
 
 /// Builder for [`LoggerConfig`].
@@ -57,28 +162,176 @@ pub struct LoggerConfig {
 #[derive(Debug)]
 pub struct LoggerConfigBuilder {
     n3_max: usize,
+    fixed_batch_size: bool,
+    batch_size_mode: BatchSizeMode,
     poll_interval3: Duration,
     iterations: Option<u64>,
     seed: Option<u64>,
+    events: Option<EventSender>,
+    split_on_capacity: bool,
+    clock: Arc<dyn Clock>,
+    sleeper: Arc<dyn Sleeper>,
+    adaptive_interval: Option<AdaptiveInterval>,
+    dedup_preload: Option<usize>,
+    histogram_edges: Vec<f64>,
+    run_id: Option<RunId>,
+    max_retained: Option<usize>,
+    retained_overflow: RetainedOverflow,
+    settings: Option<SettingsReceiver>,
+    preset: Option<Preset>,
 }
 
 impl LoggerConfig {
     /// Create a builder. `n3_max` is the only required parameter.
     ///
-    /// Default values: `poll_interval3 = 100 ms`, `iterations = None`, `seed = None`.
+    /// Default values: `fixed_batch_size = false`, `batch_size_mode = Uniform`,
+    /// `poll_interval3 = 100 ms`, `iterations = None`, `seed = None`, `events = None`,
+    /// `split_on_capacity = false`, `clock = SystemClock`, `sleeper = TokioSleeper`,
+    /// `adaptive_interval = None`, `dedup_preload = None`,
+    /// `histogram_edges = DEFAULT_HISTOGRAM_EDGES`, `run_id = None`,
+    /// `max_retained = None`, `retained_overflow = Backpressure`, `settings = None`,
+    /// `preset = None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logger::{LoggerConfig, LoggerError};
+    /// use std::time::Duration;
+    ///
+    /// let config = LoggerConfig::builder(10)
+    ///     .poll_interval3(Duration::from_millis(25))
+    ///     .split_on_capacity(true)
+    ///     .build()?;
+    /// assert_eq!((config.n3_max, config.split_on_capacity), (10, true));
+    ///
+    /// // `build` validates: a zero batch size is rejected.
+    /// let err = LoggerConfig::builder(0).build().unwrap_err();
+    /// assert!(matches!(err, LoggerError::InvalidConfig(e) if e.field == "n3_max"));
+    /// # Ok::<(), LoggerError>(())
+    /// ```
     #[must_use]
     pub fn builder(n3_max: usize) -> LoggerConfigBuilder {
         LoggerConfigBuilder {
             n3_max,
+            fixed_batch_size: false,
+            batch_size_mode: BatchSizeMode::Uniform,
             // 100 ms chosen as a reasonable demo cadence; lower for tests.
             poll_interval3: Duration::from_millis(100),
             iterations: None,
             seed: None,
+            events: None,
+            split_on_capacity: false,
+            clock: Arc::new(SystemClock),
+            sleeper: Arc::new(TokioSleeper),
+            adaptive_interval: None,
+            dedup_preload: None,
+            histogram_edges: DEFAULT_HISTOGRAM_EDGES.to_vec(),
+            run_id: None,
+            max_retained: None,
+            retained_overflow: RetainedOverflow::Backpressure,
+            settings: None,
+            preset: None,
         }
     }
+
+    /// Create a builder pre-populated with `preset`: `n3_max`,
+    /// `fixed_batch_size` and `poll_interval3` as listed on [`Preset`], every
+    /// other value as in [`builder`](Self::builder). Later builder calls
+    /// override the preset values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logger::{LoggerConfig, LoggerError};
+    ///
+    /// let config = LoggerConfig::demo().n3_max(20).split_on_capacity(true).build()?;
+    /// assert_eq!((config.n3_max, config.split_on_capacity), (20, true));
+    /// assert_eq!(config.summary().get("preset"), Some("demo"));
+    /// # Ok::<(), LoggerError>(())
+    /// ```
+    #[must_use]
+    pub fn from_preset(preset: Preset) -> LoggerConfigBuilder {
+        let builder = match preset {
+            Preset::Demo => Self::builder(10).poll_interval3(Duration::from_millis(25)),
+            Preset::Stress => Self::builder(1_000)
+                .fixed_batch_size(true)
+                .poll_interval3(Duration::from_millis(1)),
+            Preset::LowLatency => Self::builder(10).poll_interval3(Duration::from_millis(1)),
+        };
+        LoggerConfigBuilder { preset: Some(preset), ..builder }
+    }
+
+    /// [`Preset::Demo`]: writes of up to 10 every 25 ms, one small storage
+    /// write per Consumer batch or so.
+    #[must_use]
+    pub fn demo() -> LoggerConfigBuilder {
+        Self::from_preset(Preset::Demo)
+    }
+
+    /// [`Preset::Stress`]: full writes of 1 000 every millisecond, to load
+    /// the storage backend.
+    #[must_use]
+    pub fn stress() -> LoggerConfigBuilder {
+        Self::from_preset(Preset::Stress)
+    }
+
+    /// [`Preset::LowLatency`]: writes of up to 10 every millisecond, so a
+    /// scored transaction is persisted soon after it lands in Buffer2.
+    #[must_use]
+    pub fn low_latency() -> LoggerConfigBuilder {
+        Self::from_preset(Preset::LowLatency)
+    }
+
+    /// The effective settings, logged when [`Logger::run`] starts and embedded
+    /// in run reports. Clock, sleeper, observer and histogram are left out.
+    #[must_use]
+    pub fn summary(&self) -> ConfigSummary {
+        ConfigSummary::new("logger")
+            .optional("preset", self.preset)
+            .field("n3_max", self.n3_max)
+            .field("fixed_batch_size", self.fixed_batch_size)
+            .field("batch_size_mode", self.batch_size_mode)
+            .duration("poll_interval3", self.poll_interval3)
+            .optional("iterations", self.iterations)
+            .optional("seed", self.seed)
+            .field("split_on_capacity", self.split_on_capacity)
+            .optional("adaptive_interval", self.adaptive_interval)
+            .optional("dedup_preload", self.dedup_preload)
+            .optional("run_id", self.run_id.map(|id| id.0))
+            .optional("max_retained", self.max_retained)
+            .field("retained_overflow", match self.retained_overflow {
+                RetainedOverflow::Backpressure => "backpressure",
+                RetainedOverflow::DropOldest => "drop oldest",
+            })
+    }
 }
 
 impl LoggerConfigBuilder {
+    /// Override the maximum batch size, e.g. the one of a preset.
+    #[must_use]
+    pub fn n3_max(mut self, n3_max: usize) -> Self {
+        self.n3_max = n3_max;
+        self
+    }
+
+    /// Request exactly `n3_max` items per read instead of a random size in
+    /// `[1, n3_max]`, for reproducible capacity planning. A read may still
+    /// return fewer when Buffer2 holds less, and retained items (see
+    /// [`split_on_capacity`](Self::split_on_capacity)) come on top.
+    #[must_use]
+    pub fn fixed_batch_size(mut self, fixed: bool) -> Self {
+        self.fixed_batch_size = fixed;
+        self
+    }
+
+    /// Draw read sizes in `[1, n3_max]` with `mode` instead of uniformly.
+    /// [`fixed_batch_size`](Self::fixed_batch_size) takes precedence.
+    #[must_use]
+    pub fn batch_size_mode(mut self, mode: BatchSizeMode) -> Self {
+        self.batch_size_mode = mode;
+        self
+    }
+
     /// Override the inter-iteration delay.
     #[must_use]
     pub fn poll_interval3(mut self, poll_interval3: Duration) -> Self {
@@ -100,23 +353,144 @@ impl LoggerConfigBuilder {
         self
     }
 
+    /// Publish `BatchPersisted` and `StageStopped` events to `events`.
+    #[must_use]
+    pub fn events(mut self, events: EventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// On `CapacityExceeded { remaining > 0 }`, persist the first `remaining`
+    /// items and retain the rest for the next iteration instead of failing.
+    ///
+    /// Retained items are also kept when a write fails, so a restarted `run`
+    /// on the same `Logger` retries them.
+    #[must_use]
+    pub fn split_on_capacity(mut self, split: bool) -> Self {
+        self.split_on_capacity = split;
+        self
+    }
+
+    /// Inject the time source used to stamp `persisted_at`.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Inject the sleeper that waits between iterations (virtual-time simulations).
+    #[must_use]
+    pub fn sleeper(mut self, sleeper: impl Sleeper + 'static) -> Self {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    /// Replace the fixed `poll_interval3` sleep with `adaptive`: no sleep after
+    /// a full batch, a growing one after near-empty reads.
+    #[must_use]
+    pub fn adaptive_interval(mut self, adaptive: AdaptiveInterval) -> Self {
+        self.adaptive_interval = Some(adaptive);
+        self
+    }
+
+    /// Skip transactions whose id is already persisted, as loaded by
+    /// [`Logger::preload_seen_ids`], reading at most `max_ids` ids.
+    ///
+    /// The set is fixed at preload: only ids stored before the run are
+    /// skipped. A storage holding more than `max_ids` records disables the
+    /// skip with a warning rather than loading a partial set.
+    #[must_use]
+    pub fn dedup_preload(mut self, max_ids: usize) -> Self {
+        self.dedup_preload = Some(max_ids);
+        self
+    }
+
+    /// Bucket the amounts counted by [`Logger::histogram`] at `edges`, which
+    /// must be non-empty, finite and strictly ascending.
+    #[must_use]
+    pub fn histogram_edges(mut self, edges: Vec<f64>) -> Self {
+        self.histogram_edges = edges;
+        self
+    }
+
+    /// Stamp `run_id` (see `Storage::begin_run`) on every persisted transaction.
+    #[must_use]
+    pub fn run_id(mut self, run_id: RunId) -> Self {
+        self.run_id = Some(run_id);
+        self
+    }
+
+    /// Retain at most `max` items (see [`split_on_capacity`](Self::split_on_capacity)),
+    /// so a long storage outage cannot grow the backlog without bound. What
+    /// happens at the cap is set by [`retained_overflow`](Self::retained_overflow).
+    #[must_use]
+    pub fn max_retained(mut self, max: usize) -> Self {
+        self.max_retained = Some(max);
+        self
+    }
+
+    /// Stop reading (default) or drop the oldest items once `max_retained`
+    /// items are retained.
+    #[must_use]
+    pub fn retained_overflow(mut self, overflow: RetainedOverflow) -> Self {
+        self.retained_overflow = overflow;
+        self
+    }
+
+    /// Follow `settings` while running: `run` adopts the latest
+    /// `poll_interval3` and `batch_size_mode` published on it at the top of
+    /// each iteration. An invalid update is logged and ignored.
+    #[must_use]
+    pub fn settings(mut self, settings: SettingsReceiver) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
     /// Validate and build the configuration.
     ///
     /// # Errors
     ///
-    /// Returns [`LoggerError::InvalidConfig`] when `n3_max` is zero.
+    /// Returns [`LoggerError::InvalidConfig`] when `n3_max`, `iterations`,
+    /// `dedup_preload` or `max_retained` is zero, or `batch_size_mode`,
+    /// `adaptive_interval` or `histogram_edges` is invalid.
     #[must_use = "the Result must be checked; use ? or unwrap"]
     pub fn build(self) -> Result<LoggerConfig, LoggerError> {
         if self.n3_max == 0 {
-            return Err(LoggerError::InvalidConfig {
-                reason: "n3_max must be >= 1".to_owned(),
-            });
+            return Err(ConfigError::new("n3_max", self.n3_max, "must be >= 1").into());
+        }
+        if self.iterations == Some(0) {
+            return Err(ConfigError::new("iterations", 0, "must be >= 1").into());
+        }
+        self.batch_size_mode.validate()?;
+        if let Some(adaptive) = &self.adaptive_interval {
+            adaptive.validate()?;
+        }
+        if self.dedup_preload == Some(0) {
+            return Err(ConfigError::new("dedup_preload", 0, "must be >= 1").into());
         }
+        if self.max_retained == Some(0) {
+            return Err(ConfigError::new("max_retained", 0, "must be >= 1").into());
+        }
+        let histogram = Histogram::new(self.histogram_edges)?;
         Ok(LoggerConfig {
             n3_max: self.n3_max,
+            fixed_batch_size: self.fixed_batch_size,
+            batch_size_mode: self.batch_size_mode,
             poll_interval3: self.poll_interval3,
             iterations: self.iterations,
             seed: self.seed,
+            events: self.events,
+            split_on_capacity: self.split_on_capacity,
+            clock: self.clock,
+            sleeper: self.sleeper,
+            adaptive_interval: self.adaptive_interval,
+            dedup_preload: self.dedup_preload,
+            histogram,
+            run_id: self.run_id,
+            max_retained: self.max_retained,
+            retained_overflow: self.retained_overflow,
+            settings: self.settings,
+            preset: self.preset,
         })
     }
 }
@@ -134,87 +508,577 @@ impl LoggerConfigBuilder {
 pub struct Logger {
     config: LoggerConfig,
     /// Interior mutability required because all public methods take `&self`.
-    rng: RefCell<StdRng>,
+    rng: RefCell<Box<dyn RngPort>>,
+    /// Seed of the `StdRng`; `None` when the RNG was injected.
+    effective_seed: Option<u64>,
+    /// Items not yet persisted, written ahead of the next batch.
+    /// Always empty unless `split_on_capacity` is set; at most `max_retained`.
+    retained: RefCell<Vec<PendingTransaction>>,
+    /// Reads skipped because the retained backlog was full.
+    backpressured: Cell<u64>,
+    /// Retained items dropped by [`RetainedOverflow::DropOldest`].
+    dropped: Cell<u64>,
+    /// `(read, requested)` sizes of the last Buffer2 read, for the adaptive sleep.
+    last_read: Cell<(usize, usize)>,
+    /// Sleeps taken between run-loop iterations.
+    pacing: Cell<PacingStats>,
+    /// Ids persisted before this run; `None` until [`preload_seen_ids`](Self::preload_seen_ids)
+    /// loads them.
+    seen: RefCell<Option<HashSet<TransactionId>>>,
+    /// Transactions dropped because their id was in `seen`.
+    skipped: Cell<u64>,
+    /// Reads that returned nothing, with nothing retained to write instead.
+    empty_polls: Cell<u64>,
+    /// Amounts of the transactions handed to storage.
+    histogram: RefCell<Histogram>,
+    /// Digest of every transaction storage accepted.
+    digest: Cell<StreamDigest>,
+    /// Settings in force; see [`LoggerConfigBuilder::settings`].
+    settings: LiveSettings,
 }
 
 impl Logger {
     /// Create a new logger from `config`.
     ///
-    /// Seeds the RNG from `config.seed` if set, otherwise from the OS.
+    /// Seeds the RNG from `config.seed` if set, otherwise from a seed drawn
+    /// from the OS; either way it is kept as [`effective_seed`](Self::effective_seed).
     #[must_use]
     pub fn new(config: LoggerConfig) -> Self {
-        let rng = match config.seed {
-            Some(seed) => StdRng::seed_from_u64(seed),
-            None => StdRng::from_os_rng(),
+        let seed = config.seed.unwrap_or_else(rand::random);
+        tracing::info!(target: TARGET, seed, "logger.seed");
+        let mut logger = Self::with_rng(config, StdRng::seed_from_u64(seed));
+        logger.effective_seed = Some(seed);
+        logger
+    }
+
+    /// Like [`new`](Self::new), but draws batch sizes from `rng` instead of
+    /// a `StdRng`; `config.seed` is ignored. Lets tests inject a recording or
+    /// replaying generator.
+    #[must_use]
+    pub fn with_rng(config: LoggerConfig, rng: impl RngPort + 'static) -> Self {
+        let histogram = RefCell::new(config.histogram.clone());
+        let seed = DynamicSettings {
+            poll_interval3: config.poll_interval3,
+            batch_size_mode: config.batch_size_mode,
+            ..DynamicSettings::default()
+        };
+        let settings = LiveSettings::new(seed, config.settings.clone());
+        Self {
+            config,
+            rng: RefCell::new(Box::new(rng)),
+            effective_seed: None,
+            retained: RefCell::new(Vec::new()),
+            backpressured: Cell::new(0),
+            dropped: Cell::new(0),
+            last_read: Cell::new((0, 0)),
+            pacing: Cell::new(PacingStats::default()),
+            seen: RefCell::new(None),
+            skipped: Cell::new(0),
+            empty_polls: Cell::new(0),
+            histogram,
+            digest: Cell::new(StreamDigest::default()),
+            settings,
+        }
+    }
+
+    /// Sleeps taken between run-loop iterations so far.
+    #[must_use]
+    pub fn pacing(&self) -> PacingStats {
+        self.pacing.get()
+    }
+
+    /// Number of items retained for the next write (see
+    /// [`LoggerConfigBuilder::split_on_capacity`]).
+    #[must_use]
+    pub fn retained(&self) -> usize {
+        self.retained.borrow().len()
+    }
+
+    /// Buffer2 reads skipped so far because `max_retained` items were retained
+    /// (see [`RetainedOverflow::Backpressure`]).
+    #[must_use]
+    pub fn backpressured(&self) -> u64 {
+        self.backpressured.get()
+    }
+
+    /// Retained items dropped so far (see [`RetainedOverflow::DropOldest`]).
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.get()
+    }
+
+    /// Remove and return the retained items, e.g. to spill them to disk once
+    /// storage is given up on. Later writes start from an empty backlog.
+    #[must_use]
+    pub fn take_retained(&self) -> Vec<PendingTransaction> {
+        self.retained.take()
+    }
+
+    /// Transactions skipped so far because they were already persisted (see
+    /// [`LoggerConfigBuilder::dedup_preload`]).
+    #[must_use]
+    pub fn skipped(&self) -> u64 {
+        self.skipped.get()
+    }
+
+    /// The configuration this logger was built with.
+    #[must_use]
+    pub fn config(&self) -> &LoggerConfig {
+        &self.config
+    }
+
+    /// Seed the RNG was built from: `config.seed`, or the one drawn from the
+    /// OS when it was `None`. Passing it back as `config.seed` replays the
+    /// run. `None` for a logger built [`with_rng`](Self::with_rng).
+    #[must_use]
+    pub fn effective_seed(&self) -> Option<u64> {
+        self.effective_seed
+    }
+
+    /// Reads so far that returned no items while nothing was retained; no
+    /// storage write was attempted for them.
+    #[must_use]
+    pub fn empty_polls(&self) -> u64 {
+        self.empty_polls.get()
+    }
+
+    /// Snapshot of the amounts handed to storage so far, per bucket and
+    /// `predicted_fraud` (see [`LoggerConfigBuilder::histogram_edges`]).
+    ///
+    /// Each transaction is counted once, when first transformed; retained
+    /// items written again later are not counted twice. Already-persisted
+    /// ids skipped by the dedup are not counted.
+    #[must_use]
+    pub fn histogram(&self) -> Histogram {
+        self.histogram.borrow().clone()
+    }
+
+    /// Digest of every transaction persisted so far; compare it with the
+    /// Producer's `digest` to detect loss or corruption along the pipeline.
+    ///
+    /// Retained items count once written. Ids skipped by the dedup are not
+    /// counted.
+    #[must_use]
+    pub fn digest(&self) -> StreamDigest {
+        self.digest.get()
+    }
+
+    /// Fold `items`, just accepted by storage, into the digest.
+    fn record_persisted<'a>(&self, items: impl IntoIterator<Item = &'a PendingTransaction>) {
+        let mut digest = self.digest.get();
+        digest.extend(items.into_iter().map(|p| &p.inferred_transaction.transaction));
+        self.digest.set(digest);
+    }
+
+    /// Load the ids already in `storage` so that [`log_once`](Self::log_once)
+    /// skips them; call once before [`run`](Self::run).
+    ///
+    /// Returns the number of ids loaded, or `None` when the skip stays off:
+    /// `dedup_preload` is not set, or `storage` holds more than that many
+    /// records (logged as a warning).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoggerError::Preload`] if the ids cannot be read.
+    pub async fn preload_seen_ids<S: StorageRead>(
+        &self,
+        storage: &S,
+    ) -> Result<Option<usize>, LoggerError> {
+        let Some(max_ids) = self.config.dedup_preload else {
+            return Ok(None);
         };
-        Self { config, rng: RefCell::new(rng) }
+        // One id over the budget tells a full storage from an exact fit.
+        let ids = storage
+            .all_ids(max_ids.saturating_add(1))
+            .await
+            .map_err(LoggerError::Preload)?;
+        if ids.len() > max_ids {
+            tracing::warn!(
+                target: TARGET,
+                max_ids,
+                "logger.dedup.disabled: storage holds more ids than max_ids"
+            );
+            self.seen.replace(None);
+            return Ok(None);
+        }
+        let loaded = ids.len();
+        tracing::info!(target: TARGET, loaded, "logger.dedup.preloaded");
+        self.seen.replace(Some(ids.into_iter().collect()));
+        Ok(Some(loaded))
+    }
+
+    /// Drop the items of `batch` whose id was persisted before this run.
+    fn skip_seen(&self, mut batch: Vec<InferredTransaction>) -> Vec<InferredTransaction> {
+        if let Some(seen) = self.seen.borrow().as_ref() {
+            let before = batch.len();
+            batch.retain(|it| !seen.contains(&it.id()));
+            let skipped = (before - batch.len()) as u64;
+            if skipped > 0 {
+                tracing::debug!(target: TARGET, skipped, "logger.dedup.skipped");
+                self.skipped.set(self.skipped.get() + skipped);
+            }
+        }
+        batch
+    }
+
+    /// Publish `event` to the configured observer channel, if any.
+    fn emit(&self, event: PipelineEvent) {
+        if let Some(events) = &self.config.events {
+            // Err only means no receiver is subscribed right now.
+            let _ = events.send(event);
+        }
+    }
+
+    /// Publish `StageStopped` for this stage.
+    fn emit_stopped(&self, reason: impl Into<String>) {
+        self.emit(PipelineEvent::StageStopped { stage: Stage::Logger, reason: reason.into() });
+    }
+
+    /// Transactions counted into the histogram so far, and how many of them
+    /// were flagged; the run loop logs the difference per batch.
+    fn counted(&self) -> (u64, u64) {
+        let histogram = self.histogram.borrow();
+        (histogram.total(), histogram.counts(true).iter().sum())
+    }
+
+    /// Log and publish a clean stop; returns `reason` for `run` to return.
+    fn stopped(&self, reason: StopReason) -> StopReason {
+        let iterations = reason.iterations();
+        domain::log_stop!("logger", reason, iterations);
+        self.emit_stopped(reason.label());
+        reason
+    }
+
+    /// Adopt the latest live settings, if any were published; an invalid
+    /// update is logged and the settings in force are kept.
+    fn refresh_settings(&self) {
+        match self.settings.refresh() {
+            Ok(false) => {}
+            Ok(true) => {
+                let settings = self.settings.get();
+                tracing::info!(
+                    target: TARGET,
+                    poll_interval3 = ?settings.poll_interval3,
+                    batch_size_mode = %settings.batch_size_mode,
+                    "logger.settings.reloaded"
+                );
+            }
+            Err(e) => tracing::warn!(target: TARGET, error = %e, "logger.settings.rejected"),
+        }
+    }
+
+    /// Sleep between two iterations: `poll_interval3`, or the adaptive sleep
+    /// for the last read. Recorded in [`pacing`](Self::pacing).
+    async fn pause_between_iterations(&self) {
+        let base = self.settings.get().poll_interval3;
+        let mut pacing = self.pacing.get();
+        let sleep = self.config.adaptive_interval.map_or(base, |adaptive| {
+            let (read, requested) = self.last_read.get();
+            adaptive.next_sleep(base, pacing.last, read, requested)
+        });
+        pacing.record(sleep);
+        self.pacing.set(pacing);
+        tracing::debug!(target: TARGET, sleep_ms = sleep.as_millis(), "logger.sleep");
+        self.config.sleeper.sleep(sleep).await;
     }
 
     /// Read one batch from `buf2`, transform each item, and persist to `storage`.
     ///
     /// Batch size `n3` is uniformly distributed in `[1, config.n3_max]`.
     /// Each `InferredTransaction` becomes a `PendingTransaction` with
-    /// `is_reviewed = false` and `actual_fraud = None`.
+    /// `is_reviewed = false`, `actual_fraud = None`, the current
+    /// `RECORD_VERSION`, `persisted_at` read once per batch from the
+    /// configured clock and the configured `run_id`.
+    ///
+    /// Retained items (see [`LoggerConfigBuilder::split_on_capacity`]) are
+    /// written ahead of the new batch. A closed Buffer2 is only reported once
+    /// nothing is retained. Items already persisted before the run (see
+    /// [`preload_seen_ids`](Self::preload_seen_ids)) are skipped; a batch left
+    /// empty by the skip is not written.
+    ///
+    /// An empty read with nothing retained is counted in
+    /// [`empty_polls`](Self::empty_polls) and writes nothing. With
+    /// [`RetainedOverflow::Backpressure`], the read is shortened to the room
+    /// left under `max_retained`, or skipped when there is none.
     ///
     /// # Errors
     ///
     /// Returns [`LoggerError::Read`] on buffer errors, or
     /// [`LoggerError::Write`] on storage errors.
-    #[tracing::instrument(skip_all, level = "debug")]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use domain::{Buffer2 as _, Currency, InferredTransaction, Transaction, TransactionId};
+    /// use logger::{Logger, LoggerConfig};
+    /// use pipeline::memory::{MemoryBuffer, MemoryStorage};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let buffer2 = MemoryBuffer::new();
+    /// let inferred = (1..=3).map(|i| {
+    ///     let transaction = Transaction {
+    ///         id: TransactionId::new_v4(),
+    ///         amount: f64::from(i),
+    ///         last_name: "Doe".to_owned(),
+    ///         currency: Currency::Eur,
+    ///         tenant_id: None,
+    ///     };
+    ///     InferredTransaction::new(transaction, i == 3, "RATE", "1")
+    /// });
+    /// buffer2.write_batch(inferred.collect()).await?;
+    ///
+    /// let logger = Logger::new(LoggerConfig::builder(10).fixed_batch_size(true).build()?);
+    /// let storage = MemoryStorage::new();
+    /// logger.log_once(&buffer2, &storage).await?;
+    ///
+    /// let persisted = storage.items();
+    /// assert_eq!(persisted.len(), 3);
+    /// assert!(persisted.iter().all(|p| !p.is_reviewed && p.actual_fraud.is_none()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(target = "pipeline::logger", skip_all, level = "debug")]
     pub async fn log_once<B: Buffer2Read, S: Storage>(
         &self,
         buf2: &B,
         storage: &S,
     ) -> Result<(), LoggerError> {
-        let n3 = self.rng.borrow_mut().random_range(1..=self.config.n3_max);
-        tracing::debug!(batch_size = n3, "logger.log_once");
-        let batch: Vec<InferredTransaction> = buf2.read_batch(n3).await?;
-        let pending: Vec<PendingTransaction> = batch
-            .into_iter()
-            .map(|tx| PendingTransaction { inferred_transaction: tx, is_reviewed: false, actual_fraud: None })
-            .collect();
+        self.log_batch(buf2, storage).await.map(|_| ())
+    }
+
+    /// One batch, as `log_once`; returns whether the read was an empty poll.
+    async fn log_batch<B: Buffer2Read, S: Storage>(
+        &self,
+        buf2: &B,
+        storage: &S,
+    ) -> Result<bool, LoggerError> {
+        let n3 = if self.config.fixed_batch_size {
+            self.config.n3_max
+        } else {
+            let mode = self.settings.get().batch_size_mode;
+            mode.sample(self.config.n3_max, &mut **self.rng.borrow_mut())
+        };
+        tracing::debug!(target: TARGET, batch_size = n3, "logger.log_once");
+        let room = self.read_room(n3);
+        let batch: Vec<InferredTransaction> = if room == 0 {
+            // The backlog is full: retry it without taking more from Buffer2.
+            self.backpressured.set(self.backpressured.get() + 1);
+            tracing::warn!(
+                target: TARGET,
+                retained = self.retained(),
+                "logger.retained.backpressure"
+            );
+            Vec::new()
+        } else {
+            match buf2.read_batch(room).await {
+                Ok(batch) => batch,
+                // Flush retained items before reporting the end of data.
+                Err(ReadError::Closed) if self.retained() > 0 => Vec::new(),
+                Err(e) => return Err(e.into()),
+            }
+        };
+        self.last_read.set((batch.len(), n3));
+        let read = batch.len();
+        if read == 0 && self.retained() == 0 {
+            // Adapters fed by a channel or HTTP may time out with nothing to return.
+            self.empty_polls.set(self.empty_polls.get() + 1);
+            tracing::debug!(target: TARGET, max = n3, "logger.batch.empty");
+            return Ok(true);
+        }
+        let batch = self.skip_seen(batch);
+        if batch.is_empty() && self.retained() == 0 {
+            // Everything was already persisted: nothing to write.
+            return Ok(false);
+        }
+        let (persisted_at, run_id) = (Some(self.config.clock.now()), self.config.run_id);
+        {
+            let mut histogram = self.histogram.borrow_mut();
+            for it in &batch {
+                histogram.record(it.transaction.amount, it.predicted_fraud);
+            }
+        }
+        let mut pending = self.retained.take();
+        pending.extend(batch.into_iter().map(|it| {
+            profile::time(Op::PendingBuild, || PendingTransaction {
+                persisted_at,
+                run_id,
+                ..PendingTransaction::new(it)
+            })
+        }));
+        if self.config.split_on_capacity {
+            return self.persist_split(storage, pending).await.map(|()| false);
+        }
+        let size = pending.len();
+        // write_batch consumes its input; hash it beforehand.
+        let hashes: Vec<u64> =
+            pending.iter().map(|p| p.inferred_transaction.transaction.digest_hash()).collect();
         storage.write_batch(pending).await?;
+        let mut digest = self.digest.get();
+        for hash in hashes {
+            digest.add_hash(hash);
+        }
+        self.digest.set(digest);
+        self.emit(PipelineEvent::BatchPersisted { size });
+        Ok(false)
+    }
+
+    /// Items the next read may request: `n3`, shortened to the room left under
+    /// `max_retained` with [`RetainedOverflow::Backpressure`].
+    fn read_room(&self, n3: usize) -> usize {
+        match (self.config.max_retained, self.config.retained_overflow) {
+            (Some(max), RetainedOverflow::Backpressure) => {
+                n3.min(max.saturating_sub(self.retained()))
+            }
+            _ => n3,
+        }
+    }
+
+    /// Keep `items` for the next write. With [`RetainedOverflow::DropOldest`],
+    /// the oldest beyond `max_retained` are dropped and counted.
+    fn retain(&self, mut items: Vec<PendingTransaction>) {
+        if let (Some(max), RetainedOverflow::DropOldest) =
+            (self.config.max_retained, self.config.retained_overflow)
+            && items.len() > max
+        {
+            let dropped = items.len() - max;
+            items.drain(..dropped);
+            self.dropped.set(self.dropped.get() + dropped as u64);
+            tracing::warn!(target: TARGET, dropped, max_retained = max, "logger.retained.dropped");
+        }
+        self.retained.replace(items);
+    }
+
+    /// Write `pending`, splitting it on `CapacityExceeded { remaining > 0 }`.
+    ///
+    /// Whatever is not persisted ends up in `retained`.
+    async fn persist_split<S: Storage>(
+        &self,
+        storage: &S,
+        pending: Vec<PendingTransaction>,
+    ) -> Result<(), LoggerError> {
+        let size = pending.len();
+        // write_batch consumes its input; keep a copy to retain on failure.
+        let mut items = pending.clone();
+        let remaining = match storage.write_batch(pending).await {
+            Ok(()) => {
+                self.record_persisted(&items);
+                self.emit(PipelineEvent::BatchPersisted { size });
+                return Ok(());
+            }
+            Err(StorageError::CapacityExceeded { remaining, .. }) if remaining > 0 => {
+                remaining.min(size)
+            }
+            Err(e) => {
+                self.retain(items);
+                return Err(e.into());
+            }
+        };
+
+        let rest = items.split_off(remaining);
+        if let Err(e) = storage.write_batch(items.clone()).await {
+            items.extend(rest);
+            self.retain(items);
+            return Err(e.into());
+        }
+        self.record_persisted(&items);
+        tracing::warn!(
+            target: TARGET,
+            persisted = remaining,
+            retained = rest.len(),
+            "logger.capacity.split"
+        );
+        self.retain(rest);
+        self.emit(PipelineEvent::BatchPersisted { size: remaining });
         Ok(())
     }
 
     /// Run the read-transform-persist loop until stopped.
     ///
     /// Calls [`log_once`](Self::log_once) repeatedly, sleeping `config.poll_interval3`
-    /// between iterations. Stops cleanly when:
-    /// - Buffer2 signals [`BufferError::Closed`] (returns `Ok(())`), or
-    /// - `config.iterations` batches have been processed (returns `Ok(())`).
+    /// (or the adaptive sleep, see [`LoggerConfigBuilder::adaptive_interval`])
+    /// between iterations. Stops cleanly, returning the matching
+    /// [`StopReason`], when:
+    /// - Buffer2 signals [`ReadError::Closed`] (`BufferClosed`), or
+    /// - `config.iterations` batches have been processed (`IterationLimit`).
+    ///
+    /// An empty read counts as an iteration, so `iterations` still bounds a
+    /// loop over an adapter that keeps timing out; it is logged at `debug` only.
     ///
     /// # Errors
     ///
     /// Returns [`LoggerError::Write`] for any storage error.
-    #[tracing::instrument(name = "logger.run", skip_all)]
+    ///
+    /// # Examples
+    ///
+    /// Closing Buffer2 is how the stage upstream tells the Logger to drain and stop.
+    ///
+    /// ```
+    /// use domain::{Buffer2 as _, Currency, InferredTransaction, StopReason, Transaction};
+    /// use logger::{Logger, LoggerConfig};
+    /// use pipeline::Close as _;
+    /// use pipeline::memory::{MemoryBuffer, MemoryStorage};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let buffer2 = MemoryBuffer::new();
+    /// let transaction = Transaction {
+    ///     id: domain::TransactionId::new_v4(),
+    ///     amount: 12.5,
+    ///     last_name: "Doe".to_owned(),
+    ///     currency: Currency::Eur,
+    ///     tenant_id: None,
+    /// };
+    /// let inferred = InferredTransaction::new(transaction, false, "RATE", "1");
+    /// buffer2.write_batch(vec![inferred; 25]).await?;
+    /// buffer2.close();
+    ///
+    /// let config = LoggerConfig::builder(10).poll_interval3(Duration::ZERO).build()?;
+    /// let storage = MemoryStorage::new();
+    /// let reason = Logger::new(config).run(&buffer2, &storage).await?;
+    ///
+    /// assert!(matches!(reason, StopReason::BufferClosed { .. }));
+    /// assert_eq!(storage.len(), 25);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(target = "pipeline::logger", name = "logger.run", skip_all)]
     pub async fn run<B: Buffer2Read, S: Storage>(
         &self,
         buf2: &B,
         storage: &S,
-    ) -> Result<(), LoggerError> {
+    ) -> Result<StopReason, LoggerError> {
+        tracing::info!(target: TARGET, config = %self.config.summary(), "logger.run.config");
         let mut count = 0u64;
         loop {
-            match self.log_once(buf2, storage).await {
-                Ok(()) => {}
-                Err(LoggerError::Read(BufferError::Closed)) => {
-                    tracing::info!(count, "logger.run.stopped: buffer closed");
-                    return Ok(());
+            self.refresh_settings();
+            let before = self.counted();
+            let empty = match self.log_batch(buf2, storage).await {
+                Ok(empty) => empty,
+                Err(LoggerError::Read(ReadError::Closed)) => {
+                    return Ok(self.stopped(StopReason::BufferClosed { iterations: count }));
                 }
-                Err(e) => return Err(e),
-            }
+                Err(e) => {
+                    self.emit_stopped(ErrorChain(&e).to_string());
+                    return Err(e);
+                }
+            };
 
             count += 1;
-            tracing::info!(iteration = count, "logger.batch.persisted");
+            if !empty {
+                let (total, flagged) = self.counted();
+                let (size, flagged) = (total - before.0, flagged - before.1);
+                domain::log_batch!("logger", size, flagged, iteration = count);
+            }
 
             if let Some(max) = self.config.iterations
                 && count >= max
             {
-                tracing::info!("logger.run.stopped: iteration limit reached");
-                return Ok(());
+                return Ok(self.stopped(StopReason::IterationLimit { iterations: count }));
             }
 
-            tokio::time::sleep(self.config.poll_interval3).await;
+            self.pause_between_iterations().await;
         }
     }
 }
@@ -226,8 +1090,8 @@ impl Logger {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use domain::Transaction;
-    use uuid::Uuid;
+    use domain::{Currency, Transaction};
+    use std::collections::VecDeque;
 
     // ------------------------------------------------------------------
     // T011: Mock adapters
@@ -237,13 +1101,17 @@ mod tests {
     fn make_inferred(predicted_fraud: bool) -> InferredTransaction {
         InferredTransaction {
             transaction: Transaction {
-                id: Uuid::new_v4(),
+                id: TransactionId::new_v4(),
                 amount: 1.00_f64,
                 last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
             },
             predicted_fraud,
             model_name: "DEMO".to_owned(),
             model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
         }
     }
 
@@ -264,56 +1132,122 @@ mod tests {
     }
 
     impl Buffer2Read for MockBuffer2Read {
-        async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, BufferError> {
+        async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
             let mut items = self.items.borrow_mut();
             if items.is_empty() && *self.closed.borrow() {
-                return Err(BufferError::Closed);
+                return Err(ReadError::Closed);
             }
             let count = max.min(items.len());
             Ok(items.drain(..count).collect())
         }
     }
 
-    /// Mock write adapter: collects all writes; optional forced error.
+    /// Mock read adapter: returns pre-scripted batches whatever `max`; then `Closed`.
+    struct ScriptedBuffer2Read {
+        batches: RefCell<VecDeque<Vec<InferredTransaction>>>,
+    }
+
+    impl ScriptedBuffer2Read {
+        fn new(sizes: &[usize]) -> Self {
+            let batches = sizes
+                .iter()
+                .map(|&n| (0..n).map(|_| make_inferred(false)).collect())
+                .collect();
+            Self { batches: RefCell::new(batches) }
+        }
+    }
+
+    impl Buffer2Read for ScriptedBuffer2Read {
+        async fn read_batch(&self, _max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
+            self.batches.borrow_mut().pop_front().ok_or(ReadError::Closed)
+        }
+    }
+
+    /// Mock write adapter: collects all writes; optional forced error or capacity.
     struct MockStorage {
         items: RefCell<Vec<PendingTransaction>>,
         force_error: Option<StorageError>,
+        capacity: Cell<usize>,
+        write_calls: Cell<usize>,
     }
 
     impl MockStorage {
         fn new() -> Self {
-            Self { items: RefCell::new(vec![]), force_error: None }
+            Self {
+                items: RefCell::new(vec![]),
+                force_error: None,
+                capacity: Cell::new(usize::MAX),
+                write_calls: Cell::new(0),
+            }
         }
 
         fn with_error(err: StorageError) -> Self {
-            Self { items: RefCell::new(vec![]), force_error: Some(err) }
+            Self { force_error: Some(err), ..Self::new() }
+        }
+
+        fn with_capacity(capacity: usize) -> Self {
+            Self { capacity: Cell::new(capacity), ..Self::new() }
         }
     }
 
     impl Storage for MockStorage {
         async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            self.write_calls.set(self.write_calls.get() + 1);
             if let Some(ref e) = self.force_error {
                 return Err(e.clone());
             }
+            let capacity = self.capacity.get();
+            let remaining = capacity - self.items.borrow().len();
+            if batch.len() > remaining {
+                return Err(StorageError::CapacityExceeded { capacity, remaining });
+            }
             self.items.borrow_mut().extend(batch);
             Ok(())
         }
     }
 
+    impl StorageRead for MockStorage {
+        async fn read_page(
+            &self,
+            after: u64,
+            limit: usize,
+        ) -> Result<Vec<domain::StoredTransaction>, StorageError> {
+            if let Some(ref e) = self.force_error {
+                return Err(e.clone());
+            }
+            let skip = usize::try_from(after).unwrap();
+            let items = self.items.borrow();
+            let page = items.iter().zip(1u64..).skip(skip).take(limit);
+            Ok(page
+                .map(|(p, position)| domain::StoredTransaction { position, pending: p.clone() })
+                .collect())
+        }
+    }
+
     // ------------------------------------------------------------------
     // T019: LoggerConfig builder tests
     // ------------------------------------------------------------------
 
     #[test]
     fn config_n3_max_5_builds_ok() {
-        let cfg = LoggerConfig::builder(5).build();
-        assert!(cfg.is_ok());
+        LoggerConfig::builder(5).build().unwrap();
     }
 
     #[test]
     fn config_n3_max_0_returns_err() {
         let cfg = LoggerConfig::builder(0).build();
-        assert!(matches!(cfg, Err(LoggerError::InvalidConfig { .. })));
+        assert!(matches!(cfg, Err(LoggerError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn config_errors_name_the_field() {
+        let field = |r: Result<LoggerConfig, LoggerError>| match r {
+            Err(LoggerError::InvalidConfig(e)) => e,
+            other => panic!("expected InvalidConfig, got {other:?}"),
+        };
+        let e = field(LoggerConfig::builder(0).build());
+        assert_eq!((e.field, e.value.as_str(), e.constraint), ("n3_max", "0", "must be >= 1"));
+        assert_eq!(field(LoggerConfig::builder(10).iterations(0).build()).field, "iterations");
     }
 
     #[test]
@@ -341,13 +1275,51 @@ mod tests {
     }
 
     // ------------------------------------------------------------------
-    // T012: batch size in range
+    // Presets
     // ------------------------------------------------------------------
 
-    #[tokio::test]
-    async fn test_log_once_batch_size_in_range() {
-        // N3_MAX=10, buffer with 100 items, call log_once 20 times.
-        // Each batch must be in [1, 10].
+    #[test]
+    fn presets_fill_size_and_interval() {
+        let values = |builder: LoggerConfigBuilder| {
+            let c = builder.build().unwrap();
+            let preset = c.summary().get("preset").map(str::to_owned);
+            (c.n3_max, c.fixed_batch_size, c.poll_interval3, preset)
+        };
+        let ms = Duration::from_millis;
+        assert_eq!(values(LoggerConfig::demo()), (10, false, ms(25), Some("demo".to_owned())));
+        assert_eq!(values(LoggerConfig::stress()), (1_000, true, ms(1), Some("stress".to_owned())));
+        assert_eq!(
+            values(LoggerConfig::low_latency()),
+            (10, false, ms(1), Some("low-latency".to_owned()))
+        );
+        for preset in Preset::ALL {
+            assert_eq!(LoggerConfig::from_preset(preset).build().unwrap().preset, Some(preset));
+        }
+        assert_eq!(LoggerConfig::builder(10).build().unwrap().preset, None);
+    }
+
+    #[test]
+    fn builder_overrides_win_over_the_preset() {
+        let config = LoggerConfig::stress()
+            .n3_max(5)
+            .fixed_batch_size(false)
+            .poll_interval3(Duration::ZERO)
+            .split_on_capacity(true)
+            .build()
+            .unwrap();
+        assert_eq!((config.n3_max, config.fixed_batch_size), (5, false));
+        assert_eq!((config.poll_interval3, config.split_on_capacity), (Duration::ZERO, true));
+        assert_eq!(config.preset, Some(Preset::Stress));
+    }
+
+    // ------------------------------------------------------------------
+    // T012: batch size in range
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_log_once_batch_size_in_range() {
+        // N3_MAX=10, buffer with 100 items, call log_once 20 times.
+        // Each batch must be in [1, 10].
         let items: Vec<InferredTransaction> = (0..100).map(|_| make_inferred(false)).collect();
         let buf = MockBuffer2Read::new(items);
         let storage = MockStorage::new();
@@ -364,6 +1336,118 @@ mod tests {
         assert!((1..=100).contains(&stored));
     }
 
+    #[tokio::test]
+    async fn fixed_batch_size_persists_exactly_iterations_times_max() {
+        let items: Vec<InferredTransaction> = (0..1000).map(|_| make_inferred(false)).collect();
+        let buf = MockBuffer2Read::new(items);
+        let storage = MockStorage::new();
+        let cfg = LoggerConfig::builder(10)
+            .seed(7)
+            .iterations(10)
+            .poll_interval3(Duration::ZERO)
+            .fixed_batch_size(true)
+            .build()
+            .unwrap();
+        let debug = format!("{cfg:?}");
+        let logger = Logger::new(cfg);
+
+        logger.run(&buf, &storage).await.unwrap();
+
+        assert_eq!(storage.items.borrow().len(), 100);
+        assert!(debug.contains("fixed_batch_size: true"), "{debug}");
+    }
+
+    #[tokio::test]
+    async fn seeded_batch_sizes_follow_the_batch_size_mode() {
+        for mode in [BatchSizeMode::Uniform, BatchSizeMode::Geometric { p: 0.3 }] {
+            let cfg = LoggerConfig::builder(10).seed(5).batch_size_mode(mode).build().unwrap();
+            let logger = Logger::new(cfg);
+            let mut reference = StdRng::seed_from_u64(5);
+            let buf = MockBuffer2Read::new((0..1000).map(|_| make_inferred(false)).collect());
+            let storage = MockStorage::new();
+
+            let mut persisted = 0;
+            for _ in 0..20 {
+                logger.log_once(&buf, &storage).await.unwrap();
+                let stored = storage.items.borrow().len();
+                assert_eq!(stored - persisted, mode.sample(10, &mut reference), "{mode:?}");
+                persisted = stored;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn always_max_persists_n3_max_every_time() {
+        let cfg = LoggerConfig::builder(10)
+            .seed(5)
+            .iterations(10)
+            .poll_interval3(Duration::ZERO)
+            .batch_size_mode(BatchSizeMode::AlwaysMax)
+            .build()
+            .unwrap();
+        let logger = Logger::new(cfg);
+        let buf = MockBuffer2Read::new((0..1000).map(|_| make_inferred(false)).collect());
+        let storage = MockStorage::new();
+
+        logger.run(&buf, &storage).await.unwrap();
+
+        assert_eq!(storage.items.borrow().len(), 100);
+    }
+
+    #[test]
+    fn builder_rejects_a_geometric_p_outside_zero_one() {
+        for p in [0.0, 1.5, f64::NAN] {
+            let mode = BatchSizeMode::Geometric { p };
+            let result = LoggerConfig::builder(10).batch_size_mode(mode).build();
+            let Err(LoggerError::InvalidConfig(e)) = result else {
+                panic!("p = {p} must be rejected");
+            };
+            assert_eq!(e.field, "p");
+        }
+    }
+
+    #[tokio::test]
+    async fn fixed_batch_size_false_matches_default() {
+        let make = |fixed: Option<bool>| {
+            let builder = LoggerConfig::builder(10).seed(99);
+            let builder = match fixed {
+                Some(fixed) => builder.fixed_batch_size(fixed),
+                None => builder,
+            };
+            Logger::new(builder.build().unwrap())
+        };
+        let (plain, unfixed) = (make(None), make(Some(false)));
+        let items = || (0..1000).map(|_| make_inferred(false)).collect::<Vec<_>>();
+        let (buf_a, buf_b) = (MockBuffer2Read::new(items()), MockBuffer2Read::new(items()));
+        let (storage_a, storage_b) = (MockStorage::new(), MockStorage::new());
+
+        for _ in 0..20 {
+            plain.log_once(&buf_a, &storage_a).await.unwrap();
+            unfixed.log_once(&buf_b, &storage_b).await.unwrap();
+            assert_eq!(storage_a.items.borrow().len(), storage_b.items.borrow().len());
+        }
+    }
+
+    #[tokio::test]
+    async fn effective_seed_replays_an_unseeded_logger() {
+        let unseeded = Logger::new(LoggerConfig::builder(10).build().unwrap());
+        let seed = unseeded.effective_seed().unwrap();
+        let replay = Logger::new(LoggerConfig::builder(10).seed(seed).build().unwrap());
+        assert_eq!(replay.effective_seed(), Some(seed));
+        let items = || (0..1000).map(|_| make_inferred(false)).collect::<Vec<_>>();
+        let (buf_a, buf_b) = (MockBuffer2Read::new(items()), MockBuffer2Read::new(items()));
+        let (storage_a, storage_b) = (MockStorage::new(), MockStorage::new());
+
+        for _ in 0..20 {
+            unseeded.log_once(&buf_a, &storage_a).await.unwrap();
+            replay.log_once(&buf_b, &storage_b).await.unwrap();
+            assert_eq!(storage_a.items.borrow().len(), storage_b.items.borrow().len());
+        }
+        let config = LoggerConfig::builder(10).build().unwrap();
+        let injected = Logger::with_rng(config, StdRng::seed_from_u64(1));
+        assert_eq!(injected.effective_seed(), None);
+    }
+
     // ------------------------------------------------------------------
     // T013: batch capped at available
     // ------------------------------------------------------------------
@@ -392,7 +1476,7 @@ mod tests {
         let logger = Logger::new(cfg);
         let result = logger.log_once(&buf, &storage).await;
         assert!(
-            matches!(result, Err(LoggerError::Read(BufferError::Closed))),
+            matches!(result, Err(LoggerError::Read(ReadError::Closed))),
             "expected Err(Read(Closed)), got {result:?}"
         );
     }
@@ -421,9 +1505,30 @@ mod tests {
             assert_eq!(pt.inferred_transaction, orig_clone[i]);
             assert!(!pt.is_reviewed);
             assert!(pt.actual_fraud.is_none());
+            assert_eq!(pt.record_version, domain::RECORD_VERSION);
+            assert_eq!(pt.run_id, None);
         }
     }
 
+    #[tokio::test]
+    async fn configured_run_id_is_stamped_on_every_record() {
+        let items: Vec<InferredTransaction> = (0..4).map(|_| make_inferred(false)).collect();
+        let buf = MockBuffer2Read::new_closed(items);
+        let storage = MockStorage::new();
+        let cfg = LoggerConfig::builder(2)
+            .seed(1)
+            .poll_interval3(Duration::ZERO)
+            .run_id(RunId(3))
+            .build()
+            .unwrap();
+
+        Logger::new(cfg).run(&buf, &storage).await.unwrap();
+
+        let stored = storage.items.borrow();
+        assert_eq!(stored.len(), 4);
+        assert!(stored.iter().all(|pt| pt.run_id == Some(RunId(3))));
+    }
+
     // ------------------------------------------------------------------
     // T021: predicted_fraud=true preserved
     // ------------------------------------------------------------------
@@ -462,6 +1567,26 @@ mod tests {
         assert!(stored[0].actual_fraud.is_none());
     }
 
+    // ------------------------------------------------------------------
+    // persisted_at stamped from the injected clock
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn transform_stamps_persisted_at_from_clock() {
+        let t = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let buf = MockBuffer2Read::new_closed((0..3).map(|_| make_inferred(false)).collect());
+        let storage = MockStorage::new();
+        let cfg = LoggerConfig::builder(10)
+            .poll_interval3(Duration::ZERO)
+            .clock(domain::FixedClock(t))
+            .build()
+            .unwrap();
+        Logger::new(cfg).run(&buf, &storage).await.unwrap();
+        let stored = storage.items.borrow();
+        assert_eq!(stored.len(), 3);
+        assert!(stored.iter().all(|pt| pt.persisted_at == Some(t) && pt.reviewed_at.is_none()));
+    }
+
     // ------------------------------------------------------------------
     // T023: all items persisted
     // ------------------------------------------------------------------
@@ -490,14 +1615,14 @@ mod tests {
     async fn test_persist_capacity_exceeded_propagates() {
         let items = vec![make_inferred(false)];
         let buf = MockBuffer2Read::new(items);
-        let storage = MockStorage::with_error(StorageError::CapacityExceeded { capacity: 0 });
+        let storage = MockStorage::with_error(StorageError::CapacityExceeded { capacity: 0, remaining: 0 });
         let cfg = LoggerConfig::builder(1).build().unwrap();
         let logger = Logger::new(cfg);
         let result = logger.log_once(&buf, &storage).await;
         assert!(
             matches!(
                 result,
-                Err(LoggerError::Write(StorageError::CapacityExceeded { capacity: 0 }))
+                Err(LoggerError::Write(StorageError::CapacityExceeded { capacity: 0, remaining: 0 }))
             ),
             "expected CapacityExceeded, got {result:?}"
         );
@@ -538,7 +1663,7 @@ mod tests {
             .unwrap();
         let logger = Logger::new(cfg);
         let result = logger.run(&buf, &storage).await;
-        assert!(result.is_ok(), "run with iteration limit must return Ok: {result:?}");
+        assert_eq!(result.unwrap(), StopReason::IterationLimit { iterations: 3 });
         // At least 3 items persisted (3 iterations, each 1..=5).
         assert!((3..=15).contains(&storage.items.borrow().len()));
     }
@@ -549,7 +1674,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_run_stops_on_closed() {
-        // 5 items pre-loaded, then closed; Logger must drain and return Ok(()).
+        // 5 items pre-loaded, then closed; Logger must drain and return BufferClosed.
         let items: Vec<InferredTransaction> = (0..5).map(|_| make_inferred(false)).collect();
         let buf = MockBuffer2Read::new_closed(items);
         let storage = MockStorage::new();
@@ -560,7 +1685,11 @@ mod tests {
             .unwrap();
         let logger = Logger::new(cfg);
         let result = logger.run(&buf, &storage).await;
-        assert!(result.is_ok(), "run must stop cleanly on closed buffer: {result:?}");
+        assert!(
+            matches!(result, Ok(StopReason::BufferClosed { iterations: 1.. })),
+            "run must stop cleanly on closed buffer: {result:?}"
+        );
+        assert_eq!(storage.items.borrow().len(), 5);
     }
 
     // ------------------------------------------------------------------
@@ -580,6 +1709,563 @@ mod tests {
             .unwrap();
         let logger = Logger::new(cfg);
         let result = logger.run(&buf, &storage).await;
-        assert!(result.is_ok(), "zero-delay run must complete without panic: {result:?}");
+        assert_eq!(result.unwrap(), StopReason::IterationLimit { iterations: 2 });
+    }
+
+    // ------------------------------------------------------------------
+    // Split on capacity
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn capacity_exceeded_without_split_fails_whole_batch() {
+        let buf = ScriptedBuffer2Read::new(&[7, 6]);
+        let storage = MockStorage::with_capacity(10);
+        let logger = Logger::new(LoggerConfig::builder(10).build().unwrap());
+
+        logger.log_once(&buf, &storage).await.unwrap();
+        let result = logger.log_once(&buf, &storage).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(LoggerError::Write(StorageError::CapacityExceeded { capacity: 10, remaining: 3 }))
+            ),
+            "expected CapacityExceeded {{ 10, 3 }}, got {result:?}"
+        );
+        assert_eq!(storage.items.borrow().len(), 7);
+        assert_eq!(logger.retained(), 0);
+    }
+
+    #[tokio::test]
+    async fn split_persists_what_fits_and_retains_the_rest() {
+        let buf = ScriptedBuffer2Read::new(&[7, 6]);
+        let storage = MockStorage::with_capacity(10);
+        let config = LoggerConfig::builder(10).split_on_capacity(true).build().unwrap();
+        let logger = Logger::new(config);
+
+        logger.log_once(&buf, &storage).await.unwrap();
+        logger.log_once(&buf, &storage).await.unwrap();
+        assert_eq!(storage.items.borrow().len(), 10);
+        assert_eq!(logger.retained(), 3);
+
+        // Buffer2 is now closed but 3 items are retained: the storage is full,
+        // so flushing them fails and they stay retained.
+        let result = logger.log_once(&buf, &storage).await;
+        assert!(
+            matches!(
+                result,
+                Err(LoggerError::Write(StorageError::CapacityExceeded { capacity: 10, remaining: 0 }))
+            ),
+            "expected CapacityExceeded {{ 10, 0 }}, got {result:?}"
+        );
+        assert_eq!(logger.retained(), 3);
+        // Only what storage accepted is in the digest.
+        let mut persisted = StreamDigest::default();
+        persisted.extend(storage.items.borrow().iter().map(|p| &p.inferred_transaction.transaction));
+        assert_eq!(logger.digest(), persisted);
+        assert_eq!(logger.digest().count(), 10);
+    }
+
+    #[tokio::test]
+    async fn split_retained_items_are_flushed_before_closed() {
+        let buf = ScriptedBuffer2Read::new(&[7, 6]);
+        let storage = MockStorage::with_capacity(10);
+        let config = LoggerConfig::builder(10)
+            .split_on_capacity(true)
+            .poll_interval3(Duration::ZERO)
+            .build()
+            .unwrap();
+        let logger = Logger::new(config);
+        logger.log_once(&buf, &storage).await.unwrap();
+        logger.log_once(&buf, &storage).await.unwrap();
+
+        storage.capacity.set(20);
+        logger.run(&buf, &storage).await.unwrap();
+
+        assert_eq!(storage.items.borrow().len(), 13);
+        assert_eq!(logger.retained(), 0);
+    }
+
+    #[tokio::test]
+    async fn take_retained_empties_the_backlog() {
+        let buf = ScriptedBuffer2Read::new(&[7, 6]);
+        let storage = MockStorage::with_capacity(10);
+        let config = LoggerConfig::builder(10).split_on_capacity(true).build().unwrap();
+        let logger = Logger::new(config);
+        logger.log_once(&buf, &storage).await.unwrap();
+        logger.log_once(&buf, &storage).await.unwrap();
+
+        let taken = logger.take_retained();
+
+        assert_eq!(taken.len(), 3);
+        assert_eq!(logger.retained(), 0);
+        let persisted: Vec<_> = storage.items.borrow().iter().map(PendingTransaction::id).collect();
+        assert!(taken.iter().all(|p| !persisted.contains(&p.id())));
+    }
+
+    // ------------------------------------------------------------------
+    // Bounded retention under a storage outage
+    // ------------------------------------------------------------------
+
+    /// Buffer2 that always has `max` fresh items to read; records their ids.
+    struct EndlessBuffer2Read {
+        produced: RefCell<Vec<TransactionId>>,
+    }
+
+    impl EndlessBuffer2Read {
+        fn new() -> Self {
+            Self { produced: RefCell::new(vec![]) }
+        }
+    }
+
+    impl Buffer2Read for EndlessBuffer2Read {
+        async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
+            let batch: Vec<_> = (0..max).map(|_| make_inferred(false)).collect();
+            self.produced.borrow_mut().extend(batch.iter().map(InferredTransaction::id));
+            Ok(batch)
+        }
+    }
+
+    /// Storage that is `Unavailable` while `down`, then a [`MockStorage`].
+    struct OutageStorage {
+        inner: MockStorage,
+        down: Cell<bool>,
+    }
+
+    impl Storage for OutageStorage {
+        async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+            if self.down.get() {
+                return Err(StorageError::Unavailable);
+            }
+            self.inner.write_batch(batch).await
+        }
+    }
+
+    /// Logger reading 10 items at a time, retaining at most 25.
+    fn capped_logger(overflow: RetainedOverflow) -> Logger {
+        let config = LoggerConfig::builder(10)
+            .fixed_batch_size(true)
+            .split_on_capacity(true)
+            .max_retained(25)
+            .retained_overflow(overflow)
+            .build()
+            .unwrap();
+        Logger::new(config)
+    }
+
+    /// Retry `logger` 100 times against a storage that never comes back,
+    /// checking the cap after every attempt.
+    async fn long_outage(logger: &Logger, buf: &EndlessBuffer2Read, storage: &OutageStorage) {
+        storage.down.set(true);
+        for _ in 0..100 {
+            let result = logger.log_once(buf, storage).await;
+            assert!(matches!(result, Err(LoggerError::Write(StorageError::Unavailable))));
+            assert!(logger.retained() <= 25, "{} retained", logger.retained());
+        }
+    }
+
+    #[test]
+    fn max_retained_zero_is_rejected() {
+        let result = LoggerConfig::builder(10).max_retained(0).build();
+        assert!(matches!(result, Err(LoggerError::InvalidConfig(e)) if e.field == "max_retained"));
+    }
+
+    #[tokio::test]
+    async fn backpressure_stops_reading_at_the_cap() {
+        let logger = capped_logger(RetainedOverflow::Backpressure);
+        let buf = EndlessBuffer2Read::new();
+        let storage = OutageStorage { inner: MockStorage::new(), down: Cell::new(false) };
+
+        long_outage(&logger, &buf, &storage).await;
+
+        // Reads of 10, 10, then the 5 left under the cap; nothing after.
+        assert_eq!(buf.produced.borrow().len(), 25);
+        assert_eq!(logger.retained(), 25);
+        assert_eq!(logger.backpressured(), 97);
+        assert_eq!(logger.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_the_newest_within_the_cap() {
+        let logger = capped_logger(RetainedOverflow::DropOldest);
+        let buf = EndlessBuffer2Read::new();
+        let storage = OutageStorage { inner: MockStorage::new(), down: Cell::new(false) };
+
+        long_outage(&logger, &buf, &storage).await;
+
+        let produced = buf.produced.borrow();
+        assert_eq!(produced.len(), 1_000);
+        assert_eq!(logger.dropped(), 975);
+        assert_eq!(logger.backpressured(), 0);
+        let retained: Vec<_> = logger.take_retained().iter().map(PendingTransaction::id).collect();
+        assert_eq!(retained, produced[975..]);
+    }
+
+    #[tokio::test]
+    async fn recovery_drains_the_backlog_before_new_reads() {
+        let logger = capped_logger(RetainedOverflow::Backpressure);
+        let buf = EndlessBuffer2Read::new();
+        let storage = OutageStorage { inner: MockStorage::new(), down: Cell::new(false) };
+        long_outage(&logger, &buf, &storage).await;
+        let backlog: Vec<_> = buf.produced.borrow().clone();
+
+        storage.down.set(false);
+        logger.log_once(&buf, &storage).await.unwrap();
+
+        // Full backlog: the first write after the outage holds only retained items.
+        let persisted: Vec<_> =
+            storage.inner.items.borrow().iter().map(PendingTransaction::id).collect();
+        assert_eq!(persisted, backlog);
+        assert_eq!(logger.retained(), 0);
+
+        // Room again: the next iteration reads Buffer2.
+        logger.log_once(&buf, &storage).await.unwrap();
+        assert_eq!(buf.produced.borrow().len(), 35);
+        assert_eq!(storage.inner.items.borrow().len(), 35);
+    }
+
+    // ------------------------------------------------------------------
+    // Adaptive interval (paused clock: elapsed time == total sleep)
+    // ------------------------------------------------------------------
+
+    /// Logger with a 1 ms base interval backing off below 3 items, up to 10 ms.
+    fn adaptive_logger() -> Logger {
+        let cfg = LoggerConfig::builder(10)
+            .seed(5)
+            .poll_interval3(Duration::from_millis(1))
+            .adaptive_interval(AdaptiveInterval::new(3, Duration::from_millis(10)))
+            .build()
+            .unwrap();
+        Logger::new(cfg)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adaptive_deep_buffer_drains_with_zero_sleep() {
+        // Every read returns more than any requested n3: always a full batch.
+        let buf = ScriptedBuffer2Read::new(&[100; 5]);
+        let storage = MockStorage::new();
+        let logger = adaptive_logger();
+        let start = tokio::time::Instant::now();
+
+        logger.run(&buf, &storage).await.unwrap();
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(logger.pacing().sleeps, 5);
+        assert_eq!(logger.pacing().total, Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adaptive_empty_reads_back_off_to_the_cap() {
+        let buf = ScriptedBuffer2Read::new(&[0; 6]);
+        let logger = adaptive_logger();
+        let start = tokio::time::Instant::now();
+
+        logger.run(&buf, &MockStorage::new()).await.unwrap();
+
+        // 1, 2, 4, 8, then capped at 10, 10.
+        let pacing = logger.pacing();
+        assert_eq!(pacing.sleeps, 6);
+        assert_eq!(pacing.last, Duration::from_millis(10));
+        assert_eq!(pacing.total, Duration::from_millis(35));
+        assert_eq!(start.elapsed(), pacing.total);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adaptive_burst_resets_to_zero_sleep() {
+        let buf = ScriptedBuffer2Read::new(&[0, 0, 0, 100, 100]);
+        let logger = adaptive_logger();
+        let start = tokio::time::Instant::now();
+
+        logger.run(&buf, &MockStorage::new()).await.unwrap();
+
+        // 1, 2, 4 while idle, then 0, 0 once the burst arrives.
+        let pacing = logger.pacing();
+        assert_eq!(pacing.total, Duration::from_millis(7));
+        assert_eq!(pacing.last, Duration::ZERO);
+        assert_eq!(start.elapsed(), pacing.total);
+    }
+
+    // ------------------------------------------------------------------
+    // Empty reads
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn empty_reads_write_nothing_and_are_counted() {
+        let buf = ScriptedBuffer2Read::new(&[0, 0, 3]);
+        let storage = MockStorage::new();
+        let cfg = LoggerConfig::builder(10).poll_interval3(Duration::ZERO).build().unwrap();
+        let logger = Logger::new(cfg);
+
+        let stop = logger.run(&buf, &storage).await.unwrap();
+
+        assert_eq!(stop, StopReason::BufferClosed { iterations: 3 });
+        assert_eq!(logger.empty_polls(), 2);
+        assert_eq!(storage.write_calls.get(), 1, "no write for the empty reads");
+        assert_eq!(storage.items.borrow().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn empty_read_still_flushes_retained_items() {
+        let buf = ScriptedBuffer2Read::new(&[4, 0]);
+        let storage = MockStorage::with_capacity(2);
+        let config = LoggerConfig::builder(10).split_on_capacity(true).build().unwrap();
+        let logger = Logger::new(config);
+
+        logger.log_once(&buf, &storage).await.unwrap();
+        assert_eq!(logger.retained(), 2);
+        storage.capacity.set(usize::MAX);
+        logger.log_once(&buf, &storage).await.unwrap();
+
+        assert_eq!(logger.empty_polls(), 0);
+        assert_eq!((logger.retained(), storage.items.borrow().len()), (0, 4));
+    }
+
+    // ------------------------------------------------------------------
+    // Amount histogram
+    // ------------------------------------------------------------------
+
+    fn make_priced(amount: f64, predicted_fraud: bool) -> InferredTransaction {
+        let mut it = make_inferred(predicted_fraud);
+        it.transaction.amount = amount;
+        it
+    }
+
+    #[tokio::test]
+    async fn histogram_counts_persisted_amounts_by_prediction() {
+        let items = vec![
+            make_priced(0.5, false),
+            make_priced(10.0, false),
+            make_priced(10.0, true),
+            make_priced(9_999.0, true),
+        ];
+        let buf = MockBuffer2Read::new_closed(items);
+        let cfg = LoggerConfig::builder(10)
+            .poll_interval3(Duration::ZERO)
+            .histogram_edges(vec![1.0, 10.0, 1_000.0])
+            .build()
+            .unwrap();
+        let logger = Logger::new(cfg);
+
+        logger.run(&buf, &MockStorage::new()).await.unwrap();
+
+        let histogram = logger.histogram();
+        // Half-open buckets: 10.0 starts the [10, 1000) bucket.
+        assert_eq!(histogram.counts(false), [1, 0, 1, 0]);
+        assert_eq!(histogram.counts(true), [0, 0, 1, 1]);
+        assert_eq!(histogram.total(), 4);
+    }
+
+    #[tokio::test]
+    async fn retained_items_are_counted_once() {
+        let buf = ScriptedBuffer2Read::new(&[4, 0]);
+        let storage = MockStorage::with_capacity(2);
+        let config = LoggerConfig::builder(10).split_on_capacity(true).build().unwrap();
+        let logger = Logger::new(config);
+
+        logger.log_once(&buf, &storage).await.unwrap();
+        storage.capacity.set(usize::MAX);
+        logger.log_once(&buf, &storage).await.unwrap();
+
+        assert_eq!(storage.items.borrow().len(), 4);
+        assert_eq!(logger.histogram().total(), 4);
+    }
+
+    #[test]
+    fn unordered_histogram_edges_are_rejected() {
+        let e = LoggerConfig::builder(5).histogram_edges(vec![10.0, 1.0]).build().unwrap_err();
+        assert!(matches!(e, LoggerError::InvalidConfig(ref c) if c.field == "histogram_edges"));
+    }
+
+    // ------------------------------------------------------------------
+    // Restart dedup
+    // ------------------------------------------------------------------
+
+    /// Persist `items` into a fresh storage, as a previous run would have.
+    fn storage_holding(items: &[InferredTransaction]) -> MockStorage {
+        let storage = MockStorage::new();
+        storage.items.replace(items.iter().cloned().map(PendingTransaction::new).collect());
+        storage
+    }
+
+    #[test]
+    fn dedup_preload_zero_is_rejected() {
+        let e = LoggerConfig::builder(5).dedup_preload(0).build().unwrap_err();
+        assert!(matches!(e, LoggerError::InvalidConfig(ref c) if c.field == "dedup_preload"));
+    }
+
+    #[tokio::test]
+    async fn preloaded_ids_are_skipped_and_counted() {
+        let old: Vec<_> = (0..3).map(|_| make_inferred(false)).collect();
+        let storage = storage_holding(&old);
+        let config = LoggerConfig::builder(10).fixed_batch_size(true).dedup_preload(100);
+        let logger = Logger::new(config.build().unwrap());
+
+        assert_eq!(logger.preload_seen_ids(&storage).await.unwrap(), Some(3));
+        let fresh = make_inferred(true);
+        let mut replayed = old.clone();
+        replayed.insert(1, fresh.clone());
+        logger.log_once(&MockBuffer2Read::new(replayed), &storage).await.unwrap();
+
+        assert_eq!(logger.skipped(), 3);
+        let items = storage.items.borrow();
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[3].id(), fresh.id());
+    }
+
+    #[tokio::test]
+    async fn fully_skipped_batch_is_not_written() {
+        let old: Vec<_> = (0..4).map(|_| make_inferred(false)).collect();
+        let storage = storage_holding(&old);
+        let (events, mut rx) = tokio::sync::broadcast::channel(8);
+        let config = LoggerConfig::builder(10).fixed_batch_size(true).dedup_preload(4);
+        let logger = Logger::new(config.events(events).build().unwrap());
+        logger.preload_seen_ids(&storage).await.unwrap();
+
+        logger.log_once(&MockBuffer2Read::new(old), &storage).await.unwrap();
+
+        assert_eq!(logger.skipped(), 4);
+        assert_eq!(storage.items.borrow().len(), 4);
+        rx.try_recv().expect_err("no BatchPersisted for a skipped batch");
+    }
+
+    #[tokio::test]
+    async fn preload_over_budget_disables_the_skip() {
+        let old: Vec<_> = (0..5).map(|_| make_inferred(false)).collect();
+        let storage = storage_holding(&old);
+        let config = LoggerConfig::builder(10).fixed_batch_size(true).dedup_preload(4);
+        let logger = Logger::new(config.build().unwrap());
+
+        assert_eq!(logger.preload_seen_ids(&storage).await.unwrap(), None);
+        logger.log_once(&MockBuffer2Read::new(old), &storage).await.unwrap();
+
+        assert_eq!(logger.skipped(), 0);
+        assert_eq!(storage.items.borrow().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn preload_without_config_reads_nothing() {
+        let logger = Logger::new(LoggerConfig::builder(10).build().unwrap());
+        let storage = MockStorage::with_error(StorageError::Unavailable);
+
+        assert_eq!(logger.preload_seen_ids(&storage).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn preload_read_failure_is_a_preload_error() {
+        let logger = Logger::new(LoggerConfig::builder(10).dedup_preload(8).build().unwrap());
+        let storage = MockStorage::with_error(StorageError::Unavailable);
+
+        let e = logger.preload_seen_ids(&storage).await.unwrap_err();
+
+        assert!(matches!(e, LoggerError::Preload(StorageError::Unavailable)));
+        assert!(e.is_retryable());
+        assert_eq!(ErrorChain(&e).messages(), ["storage preload error", "storage unavailable"]);
+    }
+
+    // ------------------------------------------------------------------
+    // Retryable classification
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn error_retryable_classification() {
+        let invalid = LoggerConfig::builder(0).build().unwrap_err();
+        assert!(!invalid.is_retryable());
+        assert!(!LoggerError::Read(ReadError::Closed).is_retryable());
+        assert!(LoggerError::Write(StorageError::Unavailable).is_retryable());
+        assert!(!LoggerError::Write(StorageError::CapacityExceeded { capacity: 1, remaining: 0 }).is_retryable());
+    }
+
+    #[test]
+    fn error_display_leaves_the_cause_to_source() {
+        let write = LoggerError::Write(StorageError::Unavailable);
+        assert_eq!(write.to_string(), "storage write error");
+        assert_eq!(ErrorChain(&write).messages(), ["storage write error", "storage unavailable"]);
+        let read = LoggerError::Read(ReadError::Closed);
+        assert_eq!(ErrorChain(&read).messages(), ["buffer read error", "buffer closed"]);
+    }
+
+    // ------------------------------------------------------------------
+    // Pipeline events
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn run_publishes_events() {
+        let (events, mut rx) = tokio::sync::broadcast::channel(64);
+        let config = LoggerConfig::builder(4)
+            .seed(42)
+            .poll_interval3(Duration::ZERO)
+            .events(events)
+            .build()
+            .unwrap();
+        let logger = Logger::new(config);
+        let buf = MockBuffer2Read::new_closed((0..10).map(|_| make_inferred(false)).collect());
+        let storage = MockStorage::new();
+
+        logger.run(&buf, &storage).await.unwrap();
+
+        let mut persisted = 0;
+        loop {
+            match rx.try_recv().unwrap() {
+                PipelineEvent::BatchPersisted { size } => {
+                    assert!((1..=4).contains(&size), "size {size} out of [1, 4]");
+                    persisted += size;
+                }
+                PipelineEvent::StageStopped { stage, reason } => {
+                    assert_eq!(stage, Stage::Logger);
+                    assert_eq!(reason, "buffer closed");
+                    break;
+                }
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+        assert_eq!(persisted, 10);
+        assert_eq!(storage.items.borrow().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn storage_error_publishes_stage_stopped() {
+        let (events, mut rx) = tokio::sync::broadcast::channel(8);
+        let config = LoggerConfig::builder(4).events(events).build().unwrap();
+        let logger = Logger::new(config);
+        let buf = MockBuffer2Read::new_closed(vec![make_inferred(true)]);
+        let storage = MockStorage::with_error(StorageError::Unavailable);
+
+        logger.run(&buf, &storage).await.unwrap_err();
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            PipelineEvent::StageStopped {
+                stage: Stage::Logger,
+                reason: "storage write error: storage unavailable".to_owned(),
+            }
+        );
+    }
+
+    // ------------------------------------------------------------------
+    // Config summary
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn summary_reports_the_builder_values() {
+        let config = LoggerConfig::builder(6)
+            .batch_size_mode(BatchSizeMode::Geometric { p: 0.25 })
+            .poll_interval3(Duration::from_millis(50))
+            .iterations(9)
+            .seed(44)
+            .split_on_capacity(true)
+            .dedup_preload(1_000)
+            .run_id(RunId(3))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.summary().to_string(),
+            "logger: preset=none n3_max=6 fixed_batch_size=false batch_size_mode=geometric p 0.25 \
+             poll_interval3=50ms iterations=9 seed=44 split_on_capacity=true \
+             adaptive_interval=none dedup_preload=1000 run_id=3 max_retained=none \
+             retained_overflow=backpressure"
+        );
+        let logger = Logger::new(config);
+        assert_eq!(logger.config().summary().get("run_id"), Some("3"));
     }
 }
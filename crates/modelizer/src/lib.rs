@@ -6,8 +6,12 @@
 //! per-transaction classification to an injected `domain::Model` adapter.
 //! It owns no concrete model logic -- all fraud detection is in the adapter.
 
+use domain::profile::{self, Op};
 use domain::{InferredTransaction, Model, ModelVersion, ModelizerError, Transaction};
 
+/// `tracing` target of every Modelizer event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::MODELIZER;
+
 // ---------------------------------------------------------------------------
 // Modelizer
 // ---------------------------------------------------------------------------
@@ -23,6 +27,51 @@ pub struct Modelizer<M: Model> {
 
 impl<M: Model> Modelizer<M> {
     /// Create a new Modelizer wrapping `model`.
+    ///
+    /// # Examples
+    ///
+    /// A minimal `Model` adapter flagging large amounts:
+    ///
+    /// ```
+    /// use domain::{Currency, Model, ModelVersion, Modelizer as _, ModelizerError};
+    /// use domain::{Transaction, TransactionId};
+    /// use modelizer::Modelizer;
+    ///
+    /// struct Threshold(f64);
+    ///
+    /// impl Model for Threshold {
+    ///     async fn classify(&self, tx: &Transaction) -> Result<bool, ModelizerError> {
+    ///         Ok(tx.amount > self.0)
+    ///     }
+    ///     fn name(&self) -> &str {
+    ///         "THRESHOLD"
+    ///     }
+    ///     fn active_version(&self) -> &str {
+    ///         "1"
+    ///     }
+    ///     async fn switch_version(&self, _version: ModelVersion) -> Result<(), ModelizerError> {
+    ///         Err(ModelizerError::SwitchFailed { reason: "single version".to_owned() })
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), ModelizerError> {
+    /// let modelizer = Modelizer::new(Threshold(1_000.0));
+    /// let batch = [20.0, 5_000.0].map(|amount| Transaction {
+    ///     id: TransactionId::new_v4(),
+    ///     amount,
+    ///     last_name: "Doe".to_owned(),
+    ///     currency: Currency::Eur,
+    ///     tenant_id: None,
+    /// });
+    ///
+    /// let inferred = modelizer.infer(batch.to_vec()).await?;
+    /// let flags: Vec<_> = inferred.iter().map(|it| it.predicted_fraud).collect();
+    /// assert_eq!(flags, [false, true]);
+    /// assert_eq!(inferred[1].model_name, "THRESHOLD");
+    /// # Ok(())
+    /// # }
+    /// ```
     #[must_use]
     pub fn new(model: M) -> Self {
         Self { model }
@@ -38,7 +87,12 @@ impl<M: Model> domain::Modelizer for Modelizer<M> {
     /// # Errors
     ///
     /// Returns `ModelizerError::InferenceFailed` if any `classify` call fails.
-    #[tracing::instrument(skip_all, fields(batch.size = batch.len()), level = "debug")]
+    #[tracing::instrument(
+        target = "pipeline::modelizer",
+        skip_all,
+        fields(batch_size = batch.len()),
+        level = "debug"
+    )]
     async fn infer(
         &self,
         batch: Vec<Transaction>,
@@ -49,14 +103,17 @@ impl<M: Model> domain::Modelizer for Modelizer<M> {
 
         let mut results = Vec::with_capacity(batch.len());
         for tx in batch {
-            let predicted_fraud = self.model.classify(&tx).await?;
-            results.push(InferredTransaction {
-                transaction: tx,
+            let predicted_fraud =
+                profile::time_async(Op::Classify, self.model.classify(&tx)).await?;
+            results.push(InferredTransaction::new(
+                tx,
                 predicted_fraud,
-                model_name: model_name.clone(),
-                model_version: model_version.clone(),
-            });
+                model_name.clone(),
+                model_version.clone(),
+            ));
         }
+        let flagged = results.iter().filter(|it| it.predicted_fraud).count();
+        domain::log_batch!(DEBUG, "modelizer", results.len(), flagged);
         Ok(results)
     }
 
@@ -65,17 +122,20 @@ impl<M: Model> domain::Modelizer for Modelizer<M> {
     /// # Errors
     ///
     /// Returns `ModelizerError::SwitchFailed` if the adapter rejects the switch.
-    #[tracing::instrument(skip(self), fields(?version))]
+    #[tracing::instrument(target = "pipeline::modelizer", skip(self), fields(?version))]
     async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
-        tracing::info!("modelizer.switch_version");
+        tracing::info!(target: TARGET, "modelizer.switch_version");
         self.model.switch_version(version).await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use domain::{InferredTransaction, Model, ModelVersion, ModelizerError, Transaction};
-    use std::cell::Cell;
+    use domain::{
+        BasicFeatures, Currency, FeatureExtractor, Features, InferredTransaction, Model,
+        ModelVersion, ModelizerError, Transaction, TransactionId,
+    };
+    use std::cell::{Cell, RefCell};
 
     // ------------------------------------------------------------------
     // MockModel helper
@@ -100,11 +160,11 @@ mod tests {
             Ok(self.predicted_fraud)
         }
 
-        fn name(&self) -> &str {
+        fn name(&self) -> &'static str {
             "MOCK"
         }
 
-        fn active_version(&self) -> &str {
+        fn active_version(&self) -> &'static str {
             "v0"
         }
 
@@ -116,9 +176,11 @@ mod tests {
 
     fn make_tx() -> Transaction {
         Transaction {
-            id: uuid::Uuid::new_v4(),
+            id: TransactionId::new_v4(),
             amount: 1.00_f64,
             last_name: "Test".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
         }
     }
 
@@ -141,7 +203,7 @@ mod tests {
     #[tokio::test]
     async fn batch_inference_returns_same_count_in_order() {
         let txs: Vec<Transaction> = (0..5).map(|_| make_tx()).collect();
-        let ids: Vec<uuid::Uuid> = txs.iter().map(|t| t.id).collect();
+        let ids: Vec<TransactionId> = txs.iter().map(|t| t.id).collect();
 
         let model = MockModel::new(false);
         let modelizer = super::Modelizer::new(model);
@@ -193,4 +255,110 @@ mod tests {
             "switch_version must be forwarded to the model"
         );
     }
+
+    // ------------------------------------------------------------------
+    // Feature extraction happens in the model, not the Modelizer
+    // ------------------------------------------------------------------
+
+    /// Scores the first feature against a threshold, recording every input.
+    struct FeatureModel<E: FeatureExtractor> {
+        extractor: E,
+        seen: RefCell<Vec<Features>>,
+    }
+
+    impl<E: FeatureExtractor> Model for FeatureModel<E> {
+        async fn classify(&self, tx: &Transaction) -> Result<bool, ModelizerError> {
+            let features = self.extractor.extract(tx);
+            let flagged = features.as_slice().first().is_some_and(|&x| x > 50.0);
+            self.seen.borrow_mut().push(features);
+            Ok(flagged)
+        }
+
+        fn name(&self) -> &'static str {
+            "FEATURES"
+        }
+
+        fn active_version(&self) -> &'static str {
+            "v0"
+        }
+
+        async fn switch_version(&self, _version: ModelVersion) -> Result<(), ModelizerError> {
+            Ok(())
+        }
+    }
+
+    /// Name length only: a drop-in replacement for [`BasicFeatures`].
+    #[derive(Debug)]
+    struct NameLength;
+
+    impl FeatureExtractor for NameLength {
+        fn extract(&self, tx: &Transaction) -> Features {
+            Features(vec![f64::from(u8::try_from(tx.last_name.len()).unwrap())])
+        }
+
+        fn names(&self) -> &[&'static str] {
+            &["name_len"]
+        }
+    }
+
+    /// Run `extractor` through a Modelizer over one transaction; return the
+    /// model input and the verdict.
+    async fn infer_with<E: FeatureExtractor>(extractor: E) -> (Vec<Features>, bool) {
+        let model = FeatureModel { extractor, seen: RefCell::new(vec![]) };
+        let modelizer = super::Modelizer::new(model);
+        let tx = Transaction { amount: 60.0, ..make_tx() };
+        let result = domain::Modelizer::infer(&modelizer, vec![tx]).await.unwrap();
+        (modelizer.model.seen.take(), result[0].predicted_fraud)
+    }
+
+    #[tokio::test]
+    async fn swapping_extractors_changes_model_input_only() {
+        let (basic, basic_fraud) = infer_with(BasicFeatures::default()).await;
+        let (length, length_fraud) = infer_with(NameLength).await;
+
+        let expected = BasicFeatures::default().extract(&Transaction { amount: 60.0, ..make_tx() });
+        assert_eq!(basic, [expected]);
+        assert_eq!(length, [Features(vec![4.0])]);
+        // 60 > 50 on the amount, "Test".len() = 4 is not.
+        assert!(basic_fraud);
+        assert!(!length_fraud);
+    }
+
+    // ------------------------------------------------------------------
+    // Thread safety
+    // ------------------------------------------------------------------
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    /// The Modelizer adds no state of its own: it is `Send + Sync` whenever
+    /// its model is.
+    fn modelizer_is_send_sync<M: Model + Send + Sync>() {
+        assert_send_sync::<super::Modelizer<M>>();
+    }
+
+    /// Stateless model, `Send + Sync` by construction.
+    struct Constant;
+
+    impl Model for Constant {
+        async fn classify(&self, _tx: &Transaction) -> Result<bool, ModelizerError> {
+            Ok(false)
+        }
+
+        fn name(&self) -> &'static str {
+            "CONSTANT"
+        }
+
+        fn active_version(&self) -> &'static str {
+            "v0"
+        }
+
+        async fn switch_version(&self, _version: ModelVersion) -> Result<(), ModelizerError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn modelizer_over_a_send_sync_model_is_send_sync() {
+        modelizer_is_send_sync::<Constant>();
+    }
 }
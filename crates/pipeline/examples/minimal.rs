@@ -0,0 +1,37 @@
+// Rust guideline compliant 2026-02-27
+
+//! Minimal embedding of the pipeline with a custom alarm adapter.
+//!
+//! ```text
+//! cargo run -p pipeline --example minimal
+//! ```
+
+use pipeline::prelude::*;
+
+/// Alarm adapter printing every flagged transaction.
+struct PrintAlarm;
+
+impl Alarm for PrintAlarm {
+    async fn trigger(&self, transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        println!("ALARM {} ({:.2})", transaction.id(), transaction.transaction.amount);
+        Ok(())
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), PipelineError> {
+    let pipeline = Pipeline::new(
+        Producer::new(ProducerConfig::builder(10).iterations(5).seed(1).build()?),
+        Consumer::new(ConsumerConfig::builder(10).seed(2).build()?),
+        Logger::new(LoggerConfig::builder(10).seed(3).build()?),
+    );
+
+    let buffer1 = MemoryBuffer::new();
+    let buffer2 = MemoryBuffer::new();
+    let modelizer = Modelizer::new(RateModel::new(0.2, 4));
+    let storage = MemoryStorage::new();
+
+    let stops = pipeline.run(&buffer1, &modelizer, &PrintAlarm, &buffer2, &storage).await?;
+    println!("{}", pipeline.report(storage.len(), stops));
+    Ok(())
+}
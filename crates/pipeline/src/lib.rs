@@ -0,0 +1,869 @@
+// Rust guideline compliant 2026-02-27
+
+//! Embeddable fraud-detection pipeline.
+//!
+//! The binaries in `fraud_detection` wire the stages by hand. This crate makes
+//! the same wiring available to other applications:
+//!
+//! - [`Pipeline::run`] runs Producer, Consumer and Logger concurrently over any
+//!   adapters, with the usual shutdown cascade (the Producer finishing closes
+//!   Buffer1, the Consumer finishing closes Buffer2), and returns why each
+//!   stage stopped as [`StageStops`]; [`Pipeline::report`] adds the stage
+//!   counters and the [`StageConfigs`] the run used.
+//! - [`Pipeline::builder`] attaches each stage to its own adapters instead,
+//!   so that a swapped buffer or a missing stage fails to compile (see the
+//!   `wiring` module).
+//! - [`run_demo_pipeline`] does the whole thing with the in-memory adapters of
+//!   [`memory`] and returns a [`PipelineReport`].
+//! - [`prelude`] re-exports everything needed to embed the pipeline.
+//! - With the `runtime-metrics` feature, [`Pipeline::runtime`] tells how much
+//!   of the executor each stage used (see the `runtime` module).
+//!
+//! Stages and adapters are `!Send`; run on a `current_thread` runtime.
+//! `examples/minimal.rs` shows a hand-built run with a custom alarm:
+//!
+//! ```text
+//! cargo run -p pipeline --example minimal
+//! ```
+
+pub mod memory;
+pub mod prelude;
+mod runtime;
+mod wiring;
+
+#[cfg(feature = "runtime-metrics")]
+pub use runtime::{StageRuntime, StageRuntimes};
+pub use wiring::{
+    ConsumerStage, LoggerStage, Missing, PipelineBuilder, ProducerStage, WiredPipeline,
+};
+
+use std::fmt;
+use std::time::Duration;
+
+use consumer::{Consumer, ConsumerConfig, ConsumerError};
+use domain::{
+    Alarm, Buffer1, Buffer1Read, Buffer2, Buffer2Read, ConfigError, ConfigSummary, DigestMismatch,
+    Modelizer, Stage, Storage, StopReason, StreamDigest,
+};
+use logger::{Logger, LoggerConfig, LoggerError};
+use producer::{Producer, ProducerConfig, ProducerError};
+
+use crate::memory::{CountingAlarm, MemoryBuffer, MemoryStorage, RateModel};
+use crate::runtime::StageMonitors;
+
+// ---------------------------------------------------------------------------
+// Close
+// ---------------------------------------------------------------------------
+
+/// A buffer that can signal end-of-data to its readers.
+///
+/// [`Pipeline::run`] closes each buffer once its writer stage has returned.
+pub trait Close {
+    /// Signal end-of-data. Must be idempotent.
+    fn close(&self);
+}
+
+// ---------------------------------------------------------------------------
+// PipelineError
+// ---------------------------------------------------------------------------
+
+/// Errors that can occur while building or running a pipeline.
+///
+/// As with the stage errors it wraps, `Display` leaves the cause to
+/// [`source`](std::error::Error::source); `domain::ErrorChain` shows both.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum PipelineError {
+    /// The supplied configuration is invalid.
+    #[error("invalid pipeline configuration")]
+    InvalidConfig(#[from] ConfigError),
+    /// The Producer stage failed.
+    #[error("producer failed")]
+    Producer(#[from] ProducerError),
+    /// The Consumer stage failed.
+    #[error("consumer failed")]
+    Consumer(#[from] ConsumerError),
+    /// The Logger stage failed.
+    #[error("logger failed")]
+    Logger(#[from] LoggerError),
+}
+
+// ---------------------------------------------------------------------------
+// Pipeline
+// ---------------------------------------------------------------------------
+
+/// The three pipeline stages, ready to run over any set of adapters.
+#[derive(Debug)]
+pub struct Pipeline {
+    /// Generates transactions into Buffer1.
+    pub producer: Producer,
+    /// Infers Buffer1 into Buffer2, triggering alarms.
+    pub consumer: Consumer,
+    /// Persists Buffer2 into storage.
+    pub logger: Logger,
+    /// Poll timing of the stage futures (`runtime-metrics` feature).
+    monitors: StageMonitors,
+}
+
+impl Pipeline {
+    /// Bundle already-configured stages.
+    #[must_use]
+    pub fn new(producer: Producer, consumer: Consumer, logger: Logger) -> Self {
+        Self { producer, consumer, logger, monitors: StageMonitors::default() }
+    }
+
+    /// Start a [`PipelineBuilder`], attaching each stage with its adapters.
+    pub fn builder() -> PipelineBuilder {
+        PipelineBuilder::default()
+    }
+
+    /// Run the three stages concurrently until the shutdown cascade completes,
+    /// and return why each of them stopped.
+    ///
+    /// The Producer should have finite `iterations`, or `buf1` must be closed
+    /// from elsewhere; otherwise the run never ends. Each buffer is closed as
+    /// soon as its writer stage returns, whatever the outcome.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first stage error; the other stages are then dropped.
+    pub async fn run<B1, M, A, B2, S>(
+        &self,
+        buf1: &B1,
+        modelizer: &M,
+        alarm: &A,
+        buf2: &B2,
+        storage: &S,
+    ) -> Result<StageStops, PipelineError>
+    where
+        B1: Buffer1 + Buffer1Read + Close,
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2 + Buffer2Read + Close,
+        S: Storage,
+    {
+        let monitors = &self.monitors;
+        let (producer, consumer, logger) = tokio::try_join!(
+            monitors.instrument(Stage::Producer, async {
+                let r = self.producer.run(buf1).await;
+                buf1.close();
+                r.map_err(PipelineError::from)
+            }),
+            monitors.instrument(Stage::Consumer, async {
+                let r = self.consumer.run(buf1, modelizer, alarm, buf2).await;
+                buf2.close();
+                r.map_err(PipelineError::from)
+            }),
+            monitors.instrument(Stage::Logger, async {
+                self.logger.run(buf2, storage).await.map_err(PipelineError::from)
+            }),
+        )?;
+        Ok(StageStops { producer, consumer, logger })
+    }
+
+    /// Summarize the stage counters, configurations and the stop reasons
+    /// returned by [`run`](Self::run); `persisted` comes from the storage
+    /// adapter.
+    #[must_use]
+    pub fn report(&self, persisted: usize, stops: StageStops) -> PipelineReport {
+        let consumer = self.consumer.stats();
+        PipelineReport {
+            produced: self.producer.stats().transactions,
+            inferred: consumer.transactions,
+            flagged: consumer.flagged,
+            persisted: persisted as u64,
+            produced_digest: self.producer.digest(),
+            persisted_digest: self.logger.digest(),
+            stops,
+            configs: self.configs(),
+            seeds: self.seeds(),
+            #[cfg(feature = "runtime-metrics")]
+            runtime: self.runtime(),
+        }
+    }
+
+    /// Executor time of each stage over every [`run`](Self::run) so far.
+    #[cfg(feature = "runtime-metrics")]
+    #[must_use]
+    pub fn runtime(&self) -> StageRuntimes {
+        self.monitors.runtimes()
+    }
+
+    /// Seeds the stage RNGs were built from, drawn from the OS for stages
+    /// configured without one.
+    #[must_use]
+    pub fn seeds(&self) -> StageSeeds {
+        StageSeeds {
+            producer: self.producer.effective_seed(),
+            consumer: self.consumer.effective_seed(),
+            logger: self.logger.effective_seed(),
+        }
+    }
+
+    /// Summaries of the three stage configurations.
+    #[must_use]
+    pub fn configs(&self) -> StageConfigs {
+        StageConfigs {
+            producer: self.producer.config().summary(),
+            consumer: self.consumer.config().summary(),
+            logger: self.logger.config().summary(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StageStops
+// ---------------------------------------------------------------------------
+
+/// Why each stage's run loop stopped, as returned by [`Pipeline::run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageStops {
+    /// Producer stop reason.
+    pub producer: StopReason,
+    /// Consumer stop reason.
+    pub consumer: StopReason,
+    /// Logger stop reason.
+    pub logger: StopReason,
+}
+
+impl fmt::Display for StageStops {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "producer {}, consumer {}, logger {}",
+            self.producer, self.consumer, self.logger
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StageConfigs
+// ---------------------------------------------------------------------------
+
+/// The configuration each stage ran with, as returned by [`Pipeline::configs`].
+///
+/// Displays one stage per line, e.g. `producer: n1_max=10 ...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageConfigs {
+    /// Producer configuration.
+    pub producer: ConfigSummary,
+    /// Consumer configuration.
+    pub consumer: ConfigSummary,
+    /// Logger configuration.
+    pub logger: ConfigSummary,
+}
+
+impl fmt::Display for StageConfigs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}\n{}", self.producer, self.consumer, self.logger)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StageSeeds
+// ---------------------------------------------------------------------------
+
+/// The seed each stage's RNG was built from, as returned by
+/// [`Pipeline::seeds`]; `None` for a stage built with an injected RNG.
+///
+/// Passing them back as the stage config seeds replays the run. Displays as
+/// `producer=1 consumer=2 logger=3`, with `-` for a missing seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageSeeds {
+    /// Producer seed.
+    pub producer: Option<u64>,
+    /// Consumer seed.
+    pub consumer: Option<u64>,
+    /// Logger seed.
+    pub logger: Option<u64>,
+}
+
+impl fmt::Display for StageSeeds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages =
+            [("producer", self.producer), ("consumer", self.consumer), ("logger", self.logger)];
+        for (i, (stage, seed)) in stages.into_iter().enumerate() {
+            let sep = if i == 0 { "" } else { " " };
+            match seed {
+                Some(seed) => write!(f, "{sep}{stage}={seed}")?,
+                None => write!(f, "{sep}{stage}=-")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PipelineReport
+// ---------------------------------------------------------------------------
+
+/// End-of-run transaction counts per stage, why each stage stopped, and the
+/// configuration and seed it ran with.
+///
+/// The digests of what the Producer wrote and the Logger persisted are
+/// compared by [`digest_mismatch`](Self::digest_mismatch); they differ when a
+/// transaction was lost, altered, rejected or converted along the way.
+///
+/// `Display` shows the counts, stop reasons and any digest mismatch; print
+/// [`configs`](Self::configs) for the settings and [`seeds`](Self::seeds) to
+/// replay the run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// Transactions generated by the Producer.
+    pub produced: u64,
+    /// Transactions inferred by the Consumer.
+    pub inferred: u64,
+    /// Inferred transactions flagged as fraudulent.
+    pub flagged: u64,
+    /// Transactions persisted by the Logger.
+    pub persisted: u64,
+    /// Digest of the transactions the Producer wrote.
+    pub produced_digest: StreamDigest,
+    /// Digest of the transactions the Logger persisted.
+    pub persisted_digest: StreamDigest,
+    /// Stop reason of every stage.
+    pub stops: StageStops,
+    /// Configuration of every stage.
+    pub configs: StageConfigs,
+    /// Effective RNG seed of every stage.
+    pub seeds: StageSeeds,
+    /// Executor time of every stage (`runtime-metrics` feature).
+    #[cfg(feature = "runtime-metrics")]
+    pub runtime: StageRuntimes,
+}
+
+impl PipelineReport {
+    /// How the persisted stream differs from the produced one, if it does.
+    #[must_use]
+    pub fn digest_mismatch(&self) -> Option<DigestMismatch> {
+        self.produced_digest.check(&self.persisted_digest).err()
+    }
+}
+
+impl fmt::Display for PipelineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pipeline: {} produced, {} inferred, {} flagged, {} persisted ({})",
+            self.produced, self.inferred, self.flagged, self.persisted, self.stops
+        )?;
+        if let Some(mismatch) = self.digest_mismatch() {
+            write!(f, "; {mismatch}")?;
+        }
+        #[cfg(feature = "runtime-metrics")]
+        write!(f, "; {}", self.runtime)?;
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PipelineConfig + builder
+// ---------------------------------------------------------------------------
+
+/// Configuration for [`run_demo_pipeline`].
+///
+/// Construct via [`PipelineConfig::builder`].
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineConfig {
+    /// Number of Producer batches before the cascade shuts the pipeline down.
+    pub iterations: u64,
+    /// Maximum batch size, applied to `n1_max`, `n2_max` and `n3_max`.
+    pub batch_max: usize,
+    /// Delay between iterations of every stage.
+    pub poll_interval: Duration,
+    /// Probability that the demo model flags a transaction.
+    pub fraud_rate: f64,
+    /// Base seed; each stage and the model derive their own seed from it.
+    pub seed: u64,
+}
+
+/// Builder for [`PipelineConfig`].
+///
+/// Obtain via [`PipelineConfig::builder`]; finalize with [`build`](Self::build).
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineConfigBuilder {
+    iterations: u64,
+    batch_max: usize,
+    poll_interval: Duration,
+    fraud_rate: f64,
+    seed: u64,
+}
+
+impl PipelineConfig {
+    /// Create a builder. `iterations` is the only required parameter.
+    ///
+    /// Default values: `batch_max = 10`, `poll_interval = 1 ms`,
+    /// `fraud_rate = 0.04`, `seed = 0`.
+    #[must_use]
+    pub fn builder(iterations: u64) -> PipelineConfigBuilder {
+        PipelineConfigBuilder {
+            iterations,
+            batch_max: 10,
+            // Short but non-zero so every stage yields between iterations.
+            poll_interval: Duration::from_millis(1),
+            // Same rate as the DEMO model's latest version.
+            fraud_rate: 0.04,
+            seed: 0,
+        }
+    }
+}
+
+impl PipelineConfigBuilder {
+    /// Override the maximum batch size of every stage.
+    #[must_use]
+    pub fn batch_max(mut self, n: usize) -> Self {
+        self.batch_max = n;
+        self
+    }
+
+    /// Override the delay between iterations of every stage.
+    #[must_use]
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Probability in `[0, 1]` that the demo model flags a transaction.
+    #[must_use]
+    pub fn fraud_rate(mut self, rate: f64) -> Self {
+        self.fraud_rate = rate;
+        self
+    }
+
+    /// Base seed for every RNG in the run.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Validate and build the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineError::InvalidConfig`] when `iterations` or
+    /// `batch_max` is zero, or `fraud_rate` is outside `[0, 1]`.
+    #[must_use = "the Result must be checked; use ? or unwrap"]
+    pub fn build(self) -> Result<PipelineConfig, PipelineError> {
+        if self.iterations == 0 {
+            return Err(ConfigError::new("iterations", self.iterations, "must be >= 1").into());
+        }
+        if self.batch_max == 0 {
+            return Err(ConfigError::new("batch_max", self.batch_max, "must be >= 1").into());
+        }
+        // contains() is false for NaN, so NaN is rejected too.
+        if !(0.0..=1.0).contains(&self.fraud_rate) {
+            return Err(
+                ConfigError::new("fraud_rate", self.fraud_rate, "must be in [0, 1]").into(),
+            );
+        }
+        Ok(PipelineConfig {
+            iterations: self.iterations,
+            batch_max: self.batch_max,
+            poll_interval: self.poll_interval,
+            fraud_rate: self.fraud_rate,
+            seed: self.seed,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// run_demo_pipeline
+// ---------------------------------------------------------------------------
+
+/// Run a seeded pipeline over the in-memory adapters of [`memory`].
+///
+/// Same `config`, same report.
+///
+/// # Errors
+///
+/// Returns [`PipelineError`] if a stage configuration is rejected or a stage
+/// fails; the in-memory adapters themselves never fail.
+pub async fn run_demo_pipeline(config: PipelineConfig) -> Result<PipelineReport, PipelineError> {
+    let seed = config.seed;
+    let producer = Producer::new(
+        ProducerConfig::builder(config.batch_max)
+            .poll_interval1(config.poll_interval)
+            .iterations(config.iterations)
+            .seed(seed)
+            .build()?,
+    );
+    let consumer = Consumer::new(
+        ConsumerConfig::builder(config.batch_max)
+            .poll_interval2(config.poll_interval)
+            .seed(seed.wrapping_add(1))
+            .build()?,
+    );
+    let logger = Logger::new(
+        LoggerConfig::builder(config.batch_max)
+            .poll_interval3(config.poll_interval)
+            .seed(seed.wrapping_add(2))
+            .build()?,
+    );
+    let pipeline = Pipeline::new(producer, consumer, logger);
+
+    let buffer1 = MemoryBuffer::new();
+    let buffer2 = MemoryBuffer::new();
+    let model = RateModel::new(config.fraud_rate, seed.wrapping_add(3));
+    let modelizer = modelizer::Modelizer::new(model);
+    let alarm = CountingAlarm::new();
+    let storage = MemoryStorage::new();
+
+    let stops = pipeline.run(&buffer1, &modelizer, &alarm, &buffer2, &storage).await?;
+    Ok(pipeline.report(storage.len(), stops))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::{Currency, PendingTransaction, ReadError, Transaction, WriteError};
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn demo_run_persists_everything_produced() {
+        let config = PipelineConfig::builder(5).seed(42).build().unwrap();
+
+        let report = run_demo_pipeline(config).await.unwrap();
+
+        assert!(report.produced > 0);
+        assert_eq!(report.inferred, report.produced);
+        assert_eq!(report.persisted, report.produced);
+        // The Producer hits its limit; the cascade then closes the others' input.
+        assert_eq!(report.stops.producer, StopReason::IterationLimit { iterations: 5 });
+        assert!(matches!(report.stops.consumer, StopReason::BufferClosed { .. }));
+        assert!(matches!(report.stops.logger, StopReason::BufferClosed { .. }));
+    }
+
+    #[tokio::test]
+    async fn demo_report_embeds_the_stage_configs() {
+        let config = PipelineConfig::builder(3).batch_max(4).seed(10).build().unwrap();
+
+        let configs = run_demo_pipeline(config).await.unwrap().configs;
+
+        assert_eq!(configs.producer.get("n1_max"), Some("4"));
+        assert_eq!(configs.producer.get("iterations"), Some("3"));
+        assert_eq!(configs.producer.get("seed"), Some("10"));
+        assert_eq!(configs.consumer.get("seed"), Some("11"));
+        assert_eq!(configs.logger.get("seed"), Some("12"));
+        assert_eq!(configs.logger.get("poll_interval3"), Some("1ms"));
+    }
+
+    #[tokio::test]
+    async fn report_seeds_replay_an_unseeded_run() {
+        let stage_configs = |seeds: Option<StageSeeds>| {
+            let producer = ProducerConfig::builder(10).poll_interval1(Duration::ZERO).iterations(5);
+            let consumer = ConsumerConfig::builder(10).poll_interval2(Duration::ZERO);
+            let logger = LoggerConfig::builder(10).poll_interval3(Duration::ZERO);
+            match seeds {
+                Some(s) => (
+                    producer.seed(s.producer.unwrap()),
+                    consumer.seed(s.consumer.unwrap()),
+                    logger.seed(s.logger.unwrap()),
+                ),
+                None => (producer, consumer, logger),
+            }
+        };
+        let run = async |seeds| {
+            let (producer, consumer, logger) = stage_configs(seeds);
+            let pipeline = Pipeline::new(
+                Producer::new(producer.build().unwrap()),
+                Consumer::new(consumer.build().unwrap()),
+                Logger::new(logger.build().unwrap()),
+            );
+            let storage = MemoryStorage::new();
+            let modelizer = modelizer::Modelizer::new(RateModel::new(0.0, 5));
+            let (buf1, buf2) = (MemoryBuffer::new(), MemoryBuffer::new());
+            let alarm = CountingAlarm::new();
+            let stops = pipeline.run(&buf1, &modelizer, &alarm, &buf2, &storage).await.unwrap();
+            (pipeline.report(storage.len(), stops), storage.items())
+        };
+
+        let (first, first_items) = run(None).await;
+        let (replay, replay_items) = run(Some(first.seeds)).await;
+
+        assert_eq!(replay.seeds, first.seeds);
+        assert_eq!(replay.produced, first.produced);
+        let inferred = |items: Vec<PendingTransaction>| {
+            items.into_iter().map(|item| item.inferred_transaction).collect::<Vec<_>>()
+        };
+        assert_eq!(inferred(replay_items), inferred(first_items));
+    }
+
+    #[tokio::test]
+    async fn clean_run_digests_match() {
+        let config = PipelineConfig::builder(5).seed(42).build().unwrap();
+
+        let report = run_demo_pipeline(config).await.unwrap();
+
+        assert_eq!(report.digest_mismatch(), None);
+        assert_eq!(report.produced_digest.count(), report.produced);
+        assert!(!report.to_string().contains("mismatch"), "{report}");
+    }
+
+    /// Buffer1 losing the last transaction of the first batch it accepts.
+    #[derive(Default)]
+    struct LossyBuffer {
+        inner: MemoryBuffer<Transaction>,
+        dropped: Cell<bool>,
+    }
+
+    impl Buffer1 for LossyBuffer {
+        async fn write_batch(&self, mut batch: Vec<Transaction>) -> Result<(), WriteError> {
+            if !self.dropped.replace(true) {
+                batch.pop();
+            }
+            self.inner.write_batch(batch).await
+        }
+    }
+
+    impl Buffer1Read for LossyBuffer {
+        async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+            self.inner.read_batch(max).await
+        }
+    }
+
+    impl Close for LossyBuffer {
+        fn close(&self) {
+            self.inner.close();
+        }
+    }
+
+    #[tokio::test]
+    async fn a_lost_transaction_is_reported_as_a_digest_mismatch() {
+        let pipeline = Pipeline::new(
+            Producer::new(
+                ProducerConfig::builder(10)
+                    .poll_interval1(Duration::ZERO)
+                    .iterations(3)
+                    .seed(1)
+                    .build()
+                    .unwrap(),
+            ),
+            Consumer::new(
+                ConsumerConfig::builder(10).poll_interval2(Duration::ZERO).seed(2).build().unwrap(),
+            ),
+            Logger::new(
+                LoggerConfig::builder(10).poll_interval3(Duration::ZERO).seed(3).build().unwrap(),
+            ),
+        );
+        let (buffer1, buffer2) = (LossyBuffer::default(), MemoryBuffer::new());
+        let storage = MemoryStorage::new();
+        let modelizer = modelizer::Modelizer::new(RateModel::new(0.0, 4));
+
+        let stops = pipeline
+            .run(&buffer1, &modelizer, &CountingAlarm::new(), &buffer2, &storage)
+            .await
+            .unwrap();
+        let report = pipeline.report(storage.len(), stops);
+
+        let mismatch = report.digest_mismatch().unwrap();
+        assert_eq!(mismatch.upstream.count(), mismatch.downstream.count() + 1);
+        assert!(mismatch.to_string().ends_with("(1 missing)"), "{mismatch}");
+        assert!(report.to_string().contains("; digest mismatch: "), "{report}");
+    }
+
+    // Paused time: the 1 ms sleeps auto-advance in a fixed order, so the
+    // stages' iteration counts do not depend on the host's timer.
+    #[tokio::test(start_paused = true)]
+    async fn demo_run_is_reproducible() {
+        let config = PipelineConfig::builder(8).seed(7).fraud_rate(0.5).build().unwrap();
+
+        let first = run_demo_pipeline(config).await.unwrap();
+        let second = run_demo_pipeline(config).await.unwrap();
+        // Executor timings are wall-clock: only they may differ.
+        #[cfg(feature = "runtime-metrics")]
+        let (first, second) = (
+            PipelineReport { runtime: StageRuntimes::default(), ..first },
+            PipelineReport { runtime: StageRuntimes::default(), ..second },
+        );
+
+        assert_eq!(first, second);
+        assert!(first.flagged > 0);
+    }
+
+    /// Alarm that holds the executor for 2 ms per delivery.
+    #[cfg(feature = "runtime-metrics")]
+    struct BusyAlarm;
+
+    #[cfg(feature = "runtime-metrics")]
+    impl Alarm for BusyAlarm {
+        async fn trigger(
+            &self,
+            _transaction: &domain::InferredTransaction,
+        ) -> Result<(), domain::AlarmError> {
+            std::thread::sleep(Duration::from_millis(2));
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "runtime-metrics")]
+    #[tokio::test]
+    async fn runtime_metrics_single_out_a_busy_stage() {
+        let pipeline = Pipeline::new(
+            Producer::new(
+                ProducerConfig::builder(10)
+                    .poll_interval1(Duration::ZERO)
+                    .iterations(5)
+                    .seed(1)
+                    .build()
+                    .unwrap(),
+            ),
+            Consumer::new(
+                ConsumerConfig::builder(10).poll_interval2(Duration::ZERO).seed(2).build().unwrap(),
+            ),
+            Logger::new(
+                LoggerConfig::builder(10).poll_interval3(Duration::ZERO).seed(3).build().unwrap(),
+            ),
+        );
+        let storage = MemoryStorage::new();
+        let modelizer = modelizer::Modelizer::new(RateModel::new(1.0, 4));
+
+        let stops = pipeline
+            .run(&MemoryBuffer::new(), &modelizer, &BusyAlarm, &MemoryBuffer::new(), &storage)
+            .await
+            .unwrap();
+
+        let runtime = pipeline.runtime();
+        let alarms = pipeline.consumer.stats().flagged;
+        assert!(alarms >= 5, "one flagged transaction per batch at least");
+        assert!(runtime.consumer.busy >= Duration::from_millis(2 * alarms));
+        assert!(runtime.consumer.slow_polls > 0);
+        assert!(runtime.logger.polls > 0);
+        assert!(runtime.consumer.busy > runtime.logger.busy * 5, "{runtime}");
+        assert!(runtime.share(Stage::Consumer) > 0.8, "{runtime}");
+        let report = pipeline.report(storage.len(), stops);
+        assert!(report.to_string().contains("; executor: producer "), "{report}");
+    }
+
+    /// Converts USD at 0.5 and knows no other rate.
+    #[derive(Debug)]
+    struct HalfDollar;
+
+    impl domain::CurrencyConverter for HalfDollar {
+        fn rate_to_eur(&self, currency: &Currency) -> Option<f64> {
+            (*currency == Currency::Usd).then_some(0.5)
+        }
+    }
+
+    /// Run a mixed EUR/USD/GBP producer through the in-memory pipeline and
+    /// return the number produced and the persisted items.
+    async fn run_mixed_currencies(consumer: ConsumerConfig) -> (u64, Vec<PendingTransaction>) {
+        let currencies = vec![(Currency::Eur, 1), (Currency::Usd, 1), (Currency::Gbp, 1)];
+        let pipeline = Pipeline::new(
+            Producer::new(
+                ProducerConfig::builder(10)
+                    .poll_interval1(Duration::ZERO)
+                    .iterations(5)
+                    .seed(3)
+                    .currencies(currencies)
+                    .build()
+                    .unwrap(),
+            ),
+            Consumer::new(consumer),
+            Logger::new(
+                LoggerConfig::builder(10).poll_interval3(Duration::ZERO).seed(4).build().unwrap(),
+            ),
+        );
+        let storage = MemoryStorage::new();
+        let modelizer = modelizer::Modelizer::new(RateModel::new(0.0, 5));
+        pipeline
+            .run(
+                &MemoryBuffer::new(),
+                &modelizer,
+                &CountingAlarm::new(),
+                &MemoryBuffer::new(),
+                &storage,
+            )
+            .await
+            .unwrap();
+        (pipeline.producer.stats().transactions, storage.items())
+    }
+
+    fn currency_of(item: &PendingTransaction) -> &Currency {
+        &item.inferred_transaction.transaction.currency
+    }
+
+    #[tokio::test]
+    async fn currency_propagates_from_producer_to_storage() {
+        let config = ConsumerConfig::builder(10).poll_interval2(Duration::ZERO).seed(6);
+
+        let (produced, items) = run_mixed_currencies(config.build().unwrap()).await;
+
+        assert_eq!(items.len() as u64, produced);
+        for currency in [Currency::Eur, Currency::Usd, Currency::Gbp] {
+            assert!(items.iter().any(|item| *currency_of(item) == currency), "{currency}");
+        }
+    }
+
+    #[tokio::test]
+    async fn converter_keeps_the_currency_and_drops_unknown_rates() {
+        let config = ConsumerConfig::builder(10)
+            .poll_interval2(Duration::ZERO)
+            .seed(6)
+            .currency_converter(HalfDollar);
+
+        let (produced, items) = run_mixed_currencies(config.build().unwrap()).await;
+
+        // GBP has no rate: rejected by the Consumer, never persisted. USD is
+        // only converted for the checks: it is stored as USD.
+        assert!(!items.is_empty() && (items.len() as u64) < produced);
+        assert!(items.iter().all(|item| *currency_of(item) != Currency::Gbp));
+        assert!(items.iter().any(|item| *currency_of(item) == Currency::Usd));
+    }
+
+    #[test]
+    fn config_errors_name_the_field() {
+        let field = |r: Result<PipelineConfig, PipelineError>| match r {
+            Err(PipelineError::InvalidConfig(e)) => e.field,
+            other => panic!("expected InvalidConfig, got {other:?}"),
+        };
+        assert_eq!(field(PipelineConfig::builder(0).build()), "iterations");
+        assert_eq!(field(PipelineConfig::builder(5).batch_max(0).build()), "batch_max");
+        assert_eq!(field(PipelineConfig::builder(5).fraud_rate(-0.1).build()), "fraud_rate");
+    }
+
+    #[test]
+    fn report_display() {
+        let stops = StageStops {
+            producer: StopReason::IterationLimit { iterations: 2 },
+            consumer: StopReason::BufferClosed { iterations: 3 },
+            logger: StopReason::BufferClosed { iterations: 4 },
+        };
+        let configs = StageConfigs {
+            producer: ConfigSummary::new("producer").field("n1_max", 10),
+            consumer: ConfigSummary::new("consumer").field("n2_max", 10),
+            logger: ConfigSummary::new("logger").field("n3_max", 10),
+        };
+        let report = PipelineReport {
+            produced: 10,
+            inferred: 10,
+            flagged: 1,
+            persisted: 10,
+            produced_digest: StreamDigest::default(),
+            persisted_digest: StreamDigest::default(),
+            stops,
+            configs,
+            seeds: StageSeeds { producer: Some(1), consumer: None, logger: Some(3) },
+            #[cfg(feature = "runtime-metrics")]
+            runtime: StageRuntimes::default(),
+        };
+        let expected = "pipeline: 10 produced, 10 inferred, 1 flagged, 10 persisted \
+             (producer iteration limit reached after 2 iterations, \
+             consumer buffer closed after 3 iterations, logger buffer closed after 4 iterations)";
+        #[cfg(feature = "runtime-metrics")]
+        let expected = format!(
+            "{expected}; executor: producer 0% (0 polls, 0ns busy, 0 slow), \
+             consumer 0% (0 polls, 0ns busy, 0 slow), logger 0% (0 polls, 0ns busy, 0 slow)"
+        );
+        assert_eq!(report.to_string(), expected);
+        assert_eq!(
+            report.configs.to_string(),
+            "producer: n1_max=10\nconsumer: n2_max=10\nlogger: n3_max=10"
+        );
+        assert_eq!(report.seeds.to_string(), "producer=1 consumer=- logger=3");
+    }
+}
@@ -0,0 +1,564 @@
+// Rust guideline compliant 2026-02-27
+
+//! Fully in-memory adapters for every pipeline port.
+//!
+//! Meant for embedding demos and tests: nothing touches the network or disk,
+//! and every source of randomness is seeded.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use domain::{
+    Alarm, AlarmError, Buffer1, Buffer1Read, Buffer2, Buffer2Read, BufferDepth, InferredTransaction,
+    Model, ModelizerError, ModelVersion, PendingTransaction, ReadError, Storage, StorageError,
+    Transaction, WriteError,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use tokio::sync::Notify;
+
+use crate::Close;
+
+// ---------------------------------------------------------------------------
+// MemoryBuffer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct MemoryBufferInner<T> {
+    data: VecDeque<T>,
+    closed: bool,
+}
+
+/// FIFO buffer for either pipeline stage, unbounded unless built with
+/// [`with_capacity`](Self::with_capacity).
+///
+/// `MemoryBuffer<Transaction>` implements `Buffer1`/`Buffer1Read`;
+/// `MemoryBuffer<InferredTransaction>` implements `Buffer2`/`Buffer2Read`.
+/// Reading an empty open buffer waits until data arrives or it is closed, so
+/// both sides can run under `tokio::join!` on a `current_thread` runtime. It
+/// waits on a `Notify` rather than spinning, so the runtime can go idle and a
+/// paused clock (`tokio::time::pause`) still auto-advances.
+#[derive(Debug)]
+pub struct MemoryBuffer<T> {
+    inner: RefCell<MemoryBufferInner<T>>,
+    changed: Notify,
+    capacity: Option<usize>,
+}
+
+impl<T> MemoryBuffer<T> {
+    /// Create an empty, open, unbounded buffer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: RefCell::new(MemoryBufferInner { data: VecDeque::new(), closed: false }),
+            changed: Notify::new(),
+            capacity: None,
+        }
+    }
+
+    /// Create an empty, open buffer holding at most `capacity` items.
+    ///
+    /// A `write_batch` that would overflow is rejected whole with
+    /// [`WriteError::Full`] and leaves the buffer unchanged; it succeeds
+    /// again once a reader has drained enough room. A batch larger than
+    /// `capacity` never fits. `write_batch_partial` instead queues as much of
+    /// the batch as there is room for and fails with `Full` only when there
+    /// is none.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { capacity: Some(capacity), ..Self::new() }
+    }
+
+    /// Maximum number of queued items, `None` when unbounded.
+    #[must_use]
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    fn write(&self, batch: Vec<T>) -> Result<(), WriteError> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.closed {
+            return Err(WriteError::Closed);
+        }
+        if let Some(capacity) = self.capacity
+            && inner.data.len() + batch.len() > capacity
+        {
+            return Err(WriteError::Full { capacity, rejected: batch.len() });
+        }
+        inner.data.extend(batch);
+        self.changed.notify_waiters();
+        Ok(())
+    }
+
+    fn write_partial(&self, batch: &mut Vec<T>) -> Result<usize, WriteError> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.closed {
+            return Err(WriteError::Closed);
+        }
+        let room = self.capacity.map_or(usize::MAX, |c| c.saturating_sub(inner.data.len()));
+        if let Some(capacity) = self.capacity
+            && room == 0
+            && !batch.is_empty()
+        {
+            return Err(WriteError::Full { capacity, rejected: batch.len() });
+        }
+        let accepted = room.min(batch.len());
+        inner.data.extend(batch.drain(..accepted));
+        self.changed.notify_waiters();
+        Ok(accepted)
+    }
+
+    async fn read(&self, max: usize) -> Result<Vec<T>, ReadError> {
+        loop {
+            // Registered before the check, so a write or close in between is not missed.
+            let changed = self.changed.notified();
+            // Scope the borrow so it is dropped before the await.
+            let result = {
+                let mut inner = self.inner.borrow_mut();
+                if !inner.data.is_empty() {
+                    let count = max.min(inner.data.len());
+                    Some(Ok(inner.data.drain(..count).collect()))
+                } else if inner.closed {
+                    Some(Err(ReadError::Closed))
+                } else {
+                    None
+                }
+            };
+            match result {
+                Some(r) => return r,
+                None => changed.await,
+            }
+        }
+    }
+}
+
+impl<T> Default for MemoryBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Close for MemoryBuffer<T> {
+    fn close(&self) {
+        self.inner.borrow_mut().closed = true;
+        self.changed.notify_waiters();
+    }
+}
+
+impl<T> BufferDepth for MemoryBuffer<T> {
+    fn depth(&self) -> usize {
+        self.inner.borrow().data.len()
+    }
+}
+
+impl Buffer1 for MemoryBuffer<Transaction> {
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+        self.write(batch)
+    }
+
+    async fn write_batch_partial(&self, batch: &mut Vec<Transaction>) -> Result<usize, WriteError> {
+        self.write_partial(batch)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.borrow().closed
+    }
+}
+
+impl Buffer1Read for MemoryBuffer<Transaction> {
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+        self.read(max).await
+    }
+}
+
+impl Buffer2 for MemoryBuffer<InferredTransaction> {
+    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), WriteError> {
+        self.write(batch)
+    }
+
+    async fn write_batch_partial(
+        &self,
+        batch: &mut Vec<InferredTransaction>,
+    ) -> Result<usize, WriteError> {
+        self.write_partial(batch)
+    }
+}
+
+impl Buffer2Read for MemoryBuffer<InferredTransaction> {
+    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
+        self.read(max).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MemoryStorage
+// ---------------------------------------------------------------------------
+
+/// Unbounded `Storage` adapter keeping every persisted transaction in a `Vec`.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    items: RefCell<Vec<PendingTransaction>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty storage.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of persisted transactions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.borrow().len()
+    }
+
+    /// Whether nothing has been persisted yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.borrow().is_empty()
+    }
+
+    /// Copy of every persisted transaction, in write order.
+    #[must_use]
+    pub fn items(&self) -> Vec<PendingTransaction> {
+        self.items.borrow().clone()
+    }
+}
+
+impl Storage for MemoryStorage {
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        self.items.borrow_mut().extend(batch);
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RateModel
+// ---------------------------------------------------------------------------
+
+/// Single-version `Model` flagging each transaction with a fixed probability.
+///
+/// Named `"RATE"`, version `"1"`. Seeded, so runs are reproducible.
+#[derive(Debug)]
+pub struct RateModel {
+    fraud_rate: f64,
+    rng: RefCell<StdRng>,
+}
+
+impl RateModel {
+    /// Flag transactions with probability `fraud_rate`, clamped to `[0, 1]`.
+    #[must_use]
+    pub fn new(fraud_rate: f64, seed: u64) -> Self {
+        Self {
+            fraud_rate: fraud_rate.clamp(0.0, 1.0),
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl Model for RateModel {
+    async fn classify(&self, _tx: &Transaction) -> Result<bool, ModelizerError> {
+        Ok(self.rng.borrow_mut().random_bool(self.fraud_rate))
+    }
+
+    fn name(&self) -> &'static str {
+        "RATE"
+    }
+
+    fn active_version(&self) -> &'static str {
+        "1"
+    }
+
+    /// Always fails with `SwitchFailed`: there is no other version.
+    async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+        Err(ModelizerError::SwitchFailed {
+            reason: format!("RATE has a single version; cannot switch to {version:?}"),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CountingAlarm
+// ---------------------------------------------------------------------------
+
+/// `Alarm` adapter that only counts the alarms it receives.
+#[derive(Debug, Default)]
+pub struct CountingAlarm {
+    count: Cell<u64>,
+}
+
+impl CountingAlarm {
+    /// Create an alarm with a zero count.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of alarms triggered so far.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.count.get()
+    }
+}
+
+impl Alarm for CountingAlarm {
+    async fn trigger(&self, _transaction: &InferredTransaction) -> Result<(), AlarmError> {
+        self.count.set(self.count.get() + 1);
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Close as _, CountingAlarm, MemoryBuffer, MemoryStorage, RateModel};
+    use consumer::{Consumer, ConsumerConfig, ConsumerError};
+    use domain::{
+        Buffer1 as _, Buffer1Read as _, Buffer2 as _, Buffer2Read as _, BufferDepth as _, Currency,
+        InferredTransaction, ReadError, StopReason, Transaction, TransactionId, WriteError,
+    };
+    use logger::{Logger, LoggerConfig};
+    use modelizer::Modelizer;
+
+    fn make_txs(n: usize) -> Vec<Transaction> {
+        (0..n)
+            .map(|_| Transaction {
+                id: TransactionId::from_uuid(uuid::Uuid::nil()),
+                amount: 1.00_f64,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            })
+            .collect()
+    }
+
+    /// `n` inferred transactions whose amounts count up from `first`.
+    fn make_inferred(first: u32, n: u32) -> Vec<InferredTransaction> {
+        (first..first + n)
+            .map(|i| InferredTransaction {
+                transaction: Transaction {
+                    id: TransactionId::from_uuid(uuid::Uuid::nil()),
+                    amount: f64::from(i),
+                    last_name: "Test".to_owned(),
+                    currency: Currency::Eur,
+                    tenant_id: None,
+                },
+                predicted_fraud: false,
+                model_name: "RATE".to_owned(),
+                model_version: "1".to_owned(),
+                delivery_attempts: 1,
+                cached: false,
+            })
+            .collect()
+    }
+
+    fn amounts(items: &[InferredTransaction]) -> Vec<f64> {
+        items.iter().map(|tx| tx.transaction.amount).collect()
+    }
+
+    fn make_consumer(n2_max: usize) -> Consumer {
+        Consumer::new(
+            ConsumerConfig::builder(n2_max)
+                .poll_interval2(Duration::ZERO)
+                .seed(1)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn buffer_drains_then_reports_closed() {
+        let buffer = MemoryBuffer::<Transaction>::new();
+        buffer.write_batch(make_txs(5)).await.unwrap();
+        buffer.close();
+
+        assert_eq!(buffer.depth(), 5);
+        assert_eq!(buffer.read_batch(3).await.unwrap().len(), 3);
+        assert_eq!(buffer.read_batch(3).await.unwrap().len(), 2);
+        assert_eq!(buffer.read_batch(3).await, Err(ReadError::Closed));
+        assert_eq!(buffer.write_batch(make_txs(1)).await, Err(WriteError::Closed));
+    }
+
+    // -- Capacity -----------------------------------------------------------
+
+    #[tokio::test]
+    async fn bounded_buffer_accepts_writes_up_to_capacity() {
+        let buffer = MemoryBuffer::<InferredTransaction>::with_capacity(5);
+        assert_eq!(buffer.capacity(), Some(5));
+        assert_eq!(MemoryBuffer::<InferredTransaction>::new().capacity(), None);
+
+        buffer.write_batch(make_inferred(0, 3)).await.unwrap();
+        buffer.write_batch(make_inferred(3, 2)).await.unwrap();
+
+        assert_eq!(buffer.depth(), 5);
+    }
+
+    #[tokio::test]
+    async fn bounded_buffer_rejects_an_overflowing_batch_whole() {
+        let buffer = MemoryBuffer::<InferredTransaction>::with_capacity(5);
+        buffer.write_batch(make_inferred(0, 4)).await.unwrap();
+
+        let result = buffer.write_batch(make_inferred(4, 2)).await;
+
+        assert_eq!(result, Err(WriteError::Full { capacity: 5, rejected: 2 }));
+        // Nothing from the rejected batch was queued.
+        assert_eq!(buffer.depth(), 4);
+        assert_eq!(amounts(&buffer.read_batch(10).await.unwrap()), [0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[tokio::test]
+    async fn bounded_buffer_accepts_again_once_drained() {
+        let buffer = MemoryBuffer::<InferredTransaction>::with_capacity(3);
+        buffer.write_batch(make_inferred(0, 3)).await.unwrap();
+        assert_eq!(
+            buffer.write_batch(make_inferred(3, 2)).await,
+            Err(WriteError::Full { capacity: 3, rejected: 2 })
+        );
+
+        assert_eq!(buffer.read_batch(2).await.unwrap().len(), 2);
+        buffer.write_batch(make_inferred(3, 2)).await.unwrap();
+
+        assert_eq!(buffer.depth(), 3);
+    }
+
+    #[tokio::test]
+    async fn bounded_buffer_never_fits_an_oversized_batch() {
+        let buffer = MemoryBuffer::<InferredTransaction>::with_capacity(3);
+
+        let result = buffer.write_batch(make_inferred(0, 4)).await;
+
+        assert_eq!(result, Err(WriteError::Full { capacity: 3, rejected: 4 }));
+        assert_eq!(buffer.depth(), 0);
+    }
+
+    #[tokio::test]
+    async fn bounded_buffer_partial_write_accepts_what_fits() {
+        let buffer = MemoryBuffer::<InferredTransaction>::with_capacity(5);
+        buffer.write_batch(make_inferred(0, 1)).await.unwrap();
+
+        // One short of room for the whole batch: 4 accepted, 1 left to retry.
+        let mut batch = make_inferred(1, 5);
+        assert_eq!(buffer.write_batch_partial(&mut batch).await, Ok(4));
+        assert_eq!(amounts(&batch), [5.0]);
+        assert_eq!(
+            buffer.write_batch_partial(&mut batch).await,
+            Err(WriteError::Full { capacity: 5, rejected: 1 })
+        );
+        assert_eq!(batch.len(), 1, "a Full write leaves the batch untouched");
+
+        assert_eq!(buffer.read_batch(1).await.unwrap().len(), 1);
+        assert_eq!(buffer.write_batch_partial(&mut batch).await, Ok(1));
+        assert!(batch.is_empty());
+        assert_eq!(amounts(&buffer.read_batch(10).await.unwrap()), [1.0, 2.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[tokio::test]
+    async fn partial_write_on_an_unbounded_or_closed_buffer() {
+        let buffer = MemoryBuffer::<Transaction>::new();
+        let mut batch = make_txs(7);
+        assert_eq!(buffer.write_batch_partial(&mut batch).await, Ok(7));
+        assert!(batch.is_empty());
+
+        buffer.close();
+        let mut batch = make_txs(2);
+        assert_eq!(buffer.write_batch_partial(&mut batch).await, Err(WriteError::Closed));
+        assert_eq!(batch.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn bounded_buffer_reports_closed_before_full() {
+        let buffer = MemoryBuffer::<InferredTransaction>::with_capacity(2);
+        buffer.write_batch(make_inferred(0, 2)).await.unwrap();
+        buffer.close();
+
+        // A full, closed buffer refuses writes as Closed: retrying cannot help.
+        assert_eq!(buffer.write_batch(make_inferred(2, 1)).await, Err(WriteError::Closed));
+        assert_eq!(buffer.read_batch(5).await.unwrap().len(), 2);
+        assert_eq!(buffer.read_batch(5).await, Err(ReadError::Closed));
+    }
+
+    #[tokio::test]
+    async fn bounded_buffer2_reads_from_the_front() {
+        let buffer = MemoryBuffer::<InferredTransaction>::with_capacity(4);
+        buffer.write_batch(make_inferred(0, 2)).await.unwrap();
+        buffer.write_batch(make_inferred(2, 2)).await.unwrap();
+        buffer.close();
+
+        assert_eq!(amounts(&buffer.read_batch(3).await.unwrap()), [0.0, 1.0, 2.0]);
+        assert_eq!(amounts(&buffer.read_batch(3).await.unwrap()), [3.0]);
+        assert_eq!(buffer.read_batch(3).await, Err(ReadError::Closed));
+    }
+
+    // -- Consumer -> bounded Buffer2 -> Logger ------------------------------
+
+    // Regression: the Consumer does not retry a Full write itself. The run
+    // aborts with a retryable error and the batch it had read is dropped.
+    #[tokio::test]
+    async fn consumer_aborts_on_a_full_buffer2() {
+        let buffer1 = MemoryBuffer::<Transaction>::new();
+        buffer1.write_batch(make_txs(10)).await.unwrap();
+        buffer1.close();
+        let buffer2 = MemoryBuffer::<InferredTransaction>::with_capacity(2);
+        let modelizer = Modelizer::new(RateModel::new(0.0, 1));
+
+        let result =
+            make_consumer(2).run(&buffer1, &modelizer, &CountingAlarm::new(), &buffer2).await;
+
+        let Err(error) = result else {
+            panic!("expected Full, got {result:?}");
+        };
+        assert!(matches!(error, ConsumerError::Write(WriteError::Full { capacity: 2, .. })));
+        assert!(error.is_retryable());
+        assert!(buffer2.depth() <= 2);
+        assert!(buffer1.depth() + buffer2.depth() < 10, "the rejected batch is not requeued");
+    }
+
+    // Supervisor-style cycle: every Full aborts the Consumer run, one Logger
+    // step frees room, the Consumer is restarted; at the end the Logger drains
+    // Buffer2 to Closed.
+    #[tokio::test]
+    async fn consumer_full_retry_drain_cycle_through_bounded_buffer2() {
+        const TOTAL: usize = 20;
+        let buffer1 = MemoryBuffer::<Transaction>::new();
+        buffer1.write_batch(make_txs(TOTAL)).await.unwrap();
+        buffer1.close();
+        // n2_max <= capacity, so a Full buffer2 always holds something to drain.
+        let buffer2 = MemoryBuffer::<InferredTransaction>::with_capacity(4);
+        let consumer = make_consumer(3);
+        let logger_config = || LoggerConfig::builder(4).poll_interval3(Duration::ZERO).seed(2);
+        let stepper = Logger::new(logger_config().iterations(1).build().unwrap());
+        let logger = Logger::new(logger_config().build().unwrap());
+        let modelizer = Modelizer::new(RateModel::new(0.0, 1));
+        let alarm = CountingAlarm::new();
+        let storage = MemoryStorage::new();
+
+        let mut fulls = 0;
+        let mut dropped = 0;
+        let consumer_stop = loop {
+            let (depth1, depth2) = (buffer1.depth(), buffer2.depth());
+            match consumer.run(&buffer1, &modelizer, &alarm, &buffer2).await {
+                Ok(reason) => break reason,
+                Err(ConsumerError::Write(WriteError::Full { capacity: 4, .. })) => {
+                    fulls += 1;
+                    dropped += (depth1 - buffer1.depth()) - (buffer2.depth() - depth2);
+                    let step = stepper.run(&buffer2, &storage).await.unwrap();
+                    assert_eq!(step, StopReason::IterationLimit { iterations: 1 });
+                }
+                Err(e) => panic!("unexpected consumer error: {e:?}"),
+            }
+        };
+        buffer2.close();
+        let logger_stop = logger.run(&buffer2, &storage).await.unwrap();
+
+        assert!(fulls >= 1, "capacity 4 < {TOTAL} must fill up");
+        assert!(matches!(consumer_stop, StopReason::BufferClosed { .. }));
+        assert!(matches!(logger_stop, StopReason::BufferClosed { .. }));
+        assert_eq!(buffer2.depth(), 0);
+        assert_eq!(storage.len() + dropped, TOTAL);
+    }
+}
@@ -0,0 +1,31 @@
+// Rust guideline compliant 2026-02-27
+
+//! Everything needed to embed the pipeline, in one import.
+//!
+//! ```text
+//! use pipeline::prelude::*;
+//! ```
+//!
+//! The `Modelizer` port trait is re-exported as `ModelizerPort` so it does not
+//! clash with the concrete [`Modelizer`] wrapper.
+
+pub use crate::memory::{CountingAlarm, MemoryBuffer, MemoryStorage, RateModel};
+pub use crate::{
+    Close, Pipeline, PipelineBuilder, PipelineConfig, PipelineConfigBuilder, PipelineError,
+    PipelineReport, StageConfigs, StageSeeds, StageStops, WiredPipeline, run_demo_pipeline,
+};
+#[cfg(feature = "runtime-metrics")]
+pub use crate::{StageRuntime, StageRuntimes};
+
+pub use consumer::{Consumer, ConsumerConfig, ConsumerError};
+pub use logger::{Histogram, Logger, LoggerConfig, LoggerError};
+pub use modelizer::Modelizer;
+pub use producer::{Producer, ProducerConfig, ProducerError};
+
+pub use domain::Modelizer as ModelizerPort;
+pub use domain::{
+    AdaptiveInterval, Alarm, AlarmError, BatchSizeMode, Buffer1, Buffer1Read, Buffer2,
+    Buffer2Read, BufferDepth, ConfigError, ConfigSummary, Currency, CurrencyConverter,
+    InferredTransaction, Model, ModelVersion, ModelizerError, PendingTransaction, ReadError,
+    RngPort, StopReason, Storage, StorageError, Transaction, WriteError,
+};
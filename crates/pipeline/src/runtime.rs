@@ -0,0 +1,162 @@
+// Rust guideline compliant 2026-02-27
+
+//! Executor time of each stage, with the `runtime-metrics` feature.
+//!
+//! On a `current_thread` runtime a stage that holds the executor between two
+//! awaits delays the other two, and nothing in the stage counters shows it.
+//! With the feature on, [`Pipeline::run`](crate::Pipeline::run) wraps each
+//! stage future in a `tokio-metrics` `TaskMonitor`, which times every poll;
+//! [`Pipeline::runtime`](crate::Pipeline::runtime) sums them per stage as
+//! [`StageRuntimes`]. Without the feature the stage futures run unwrapped.
+
+use domain::Stage;
+
+#[cfg(feature = "runtime-metrics")]
+use std::fmt;
+#[cfg(feature = "runtime-metrics")]
+use std::time::Duration;
+#[cfg(feature = "runtime-metrics")]
+use tokio_metrics::TaskMonitor;
+
+// ---------------------------------------------------------------------------
+// StageRuntime / StageRuntimes
+// ---------------------------------------------------------------------------
+
+/// Polls of one stage future, summed over every [`Pipeline::run`](crate::Pipeline::run).
+#[cfg(feature = "runtime-metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageRuntime {
+    /// Times the executor polled the stage.
+    pub polls: u64,
+    /// Time spent inside those polls, i.e. holding the executor.
+    pub busy: Duration,
+    /// Polls longer than `tokio-metrics`' slow-poll threshold (50 µs).
+    pub slow_polls: u64,
+}
+
+/// Executor time of each stage, as returned by
+/// [`Pipeline::runtime`](crate::Pipeline::runtime).
+///
+/// Displays each stage's share of the busy time of the three, e.g.
+/// `executor: producer 4% (120 polls, 3ms busy, 0 slow), consumer 92% ...`.
+/// The timings are wall-clock, so two runs of the same seed differ.
+#[cfg(feature = "runtime-metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageRuntimes {
+    /// Producer polls.
+    pub producer: StageRuntime,
+    /// Consumer polls.
+    pub consumer: StageRuntime,
+    /// Logger polls.
+    pub logger: StageRuntime,
+}
+
+#[cfg(feature = "runtime-metrics")]
+impl StageRuntimes {
+    /// Share of the three stages' busy time spent in `stage`, in `[0, 1]`;
+    /// zero before any poll.
+    #[must_use]
+    pub fn share(&self, stage: Stage) -> f64 {
+        let total = self.producer.busy + self.consumer.busy + self.logger.busy;
+        if total.is_zero() {
+            return 0.0;
+        }
+        self.get(stage).busy.as_secs_f64() / total.as_secs_f64()
+    }
+
+    /// Runtime of `stage`.
+    #[must_use]
+    pub fn get(&self, stage: Stage) -> StageRuntime {
+        match stage {
+            Stage::Producer => self.producer,
+            Stage::Consumer => self.consumer,
+            Stage::Logger => self.logger,
+        }
+    }
+}
+
+#[cfg(feature = "runtime-metrics")]
+impl fmt::Display for StageRuntimes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("executor:")?;
+        let stages = [
+            ("producer", Stage::Producer),
+            ("consumer", Stage::Consumer),
+            ("logger", Stage::Logger),
+        ];
+        for (i, (name, stage)) in stages.into_iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            let runtime = self.get(stage);
+            write!(
+                f,
+                "{sep} {name} {:.0}% ({} polls, {:?} busy, {} slow)",
+                self.share(stage) * 100.0,
+                runtime.polls,
+                runtime.busy,
+                runtime.slow_polls
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StageMonitors
+// ---------------------------------------------------------------------------
+
+/// One poll monitor per stage; empty without the `runtime-metrics` feature.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StageMonitors {
+    #[cfg(feature = "runtime-metrics")]
+    producer: TaskMonitor,
+    #[cfg(feature = "runtime-metrics")]
+    consumer: TaskMonitor,
+    #[cfg(feature = "runtime-metrics")]
+    logger: TaskMonitor,
+}
+
+impl StageMonitors {
+    /// Time every poll of `future` as `stage`'s.
+    #[cfg(feature = "runtime-metrics")]
+    pub(crate) fn instrument<F: Future>(
+        &self,
+        stage: Stage,
+        future: F,
+    ) -> impl Future<Output = F::Output> {
+        self.monitor(stage).instrument(future)
+    }
+
+    /// Return `future` as is: the feature is off.
+    #[cfg(not(feature = "runtime-metrics"))]
+    #[expect(clippy::unused_self, reason = "same signature as with runtime-metrics")]
+    pub(crate) fn instrument<F: Future>(&self, _stage: Stage, future: F) -> F {
+        future
+    }
+
+    /// Totals of every poll timed so far.
+    #[cfg(feature = "runtime-metrics")]
+    pub(crate) fn runtimes(&self) -> StageRuntimes {
+        let runtime = |stage| {
+            let metrics = self.monitor(stage).cumulative();
+            StageRuntime {
+                polls: metrics.total_poll_count,
+                busy: metrics.total_poll_duration,
+                slow_polls: metrics.total_slow_poll_count,
+            }
+        };
+        StageRuntimes {
+            producer: runtime(Stage::Producer),
+            consumer: runtime(Stage::Consumer),
+            logger: runtime(Stage::Logger),
+        }
+    }
+
+    #[cfg(feature = "runtime-metrics")]
+    fn monitor(&self, stage: Stage) -> &TaskMonitor {
+        match stage {
+            Stage::Producer => &self.producer,
+            Stage::Consumer => &self.consumer,
+            Stage::Logger => &self.logger,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Rust guideline compliant 2026-02-27
+
+//! Typestate builder wiring the stages to their adapters.
+//!
+//! [`Pipeline::run`] takes its five adapters positionally, and Buffer1 and
+//! Buffer2 are both "some buffer": nothing but the trait bounds stops a
+//! caller from swapping two arguments of compatible types. With
+//! [`Pipeline::builder`] each stage is attached with the adapters it owns:
+//!
+//! ```text
+//! let wired = Pipeline::builder()
+//!     .producer(producer, &buffer1)
+//!     .consumer(consumer, &modelizer, &alarm, &buffer2)
+//!     .logger(logger, &storage)
+//!     .build();
+//! let stops = wired.run().await?;
+//! ```
+//!
+//! The Consumer reads the buffer the Producer writes and the Logger the one
+//! the Consumer writes, so a buffer cannot be attached to the wrong reader.
+//! Each type parameter of [`PipelineBuilder`] is [`Missing`] until its stage
+//! is attached; a stage cannot be attached twice, and
+//! [`build`](PipelineBuilder::build) only exists once all three are, so a
+//! wrong or incomplete wiring does not compile. The [`WiredPipeline`] runs
+//! the usual close cascade and exposes the shutdown signal.
+
+use consumer::Consumer;
+use domain::{Alarm, Buffer1, Buffer1Read, Buffer2, Buffer2Read, Modelizer, Storage};
+use logger::Logger;
+use producer::Producer;
+
+use crate::{Close, Pipeline, PipelineError, StageStops};
+
+/// Marker of a stage not attached yet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Missing;
+
+/// Producer attached with the Buffer1 it writes.
+#[derive(Debug)]
+pub struct ProducerStage<'a, B1> {
+    producer: Producer,
+    buf1: &'a B1,
+}
+
+/// Consumer attached with its modelizer, alarm and the Buffer2 it writes.
+#[derive(Debug)]
+pub struct ConsumerStage<'a, M, A, B2> {
+    consumer: Consumer,
+    modelizer: &'a M,
+    alarm: &'a A,
+    buf2: &'a B2,
+}
+
+/// Logger attached with the storage it writes.
+#[derive(Debug)]
+pub struct LoggerStage<'a, S> {
+    logger: Logger,
+    storage: &'a S,
+}
+
+/// Pipeline under construction, returned by [`Pipeline::builder`].
+///
+/// `P`, `C` and `L` are the Producer, Consumer and Logger stages, each
+/// [`Missing`] until attached.
+#[derive(Debug, Default)]
+#[must_use = "a pipeline builder does nothing until built and run"]
+pub struct PipelineBuilder<P = Missing, C = Missing, L = Missing> {
+    producer: P,
+    consumer: C,
+    logger: L,
+}
+
+impl<C, L> PipelineBuilder<Missing, C, L> {
+    /// Attach the Producer, writing into `buf1`; the Consumer reads it.
+    pub fn producer<B1>(
+        self,
+        producer: Producer,
+        buf1: &B1,
+    ) -> PipelineBuilder<ProducerStage<'_, B1>, C, L>
+    where
+        B1: Buffer1 + Buffer1Read + Close,
+    {
+        PipelineBuilder {
+            producer: ProducerStage { producer, buf1 },
+            consumer: self.consumer,
+            logger: self.logger,
+        }
+    }
+}
+
+impl<P, L> PipelineBuilder<P, Missing, L> {
+    /// Attach the Consumer, inferring with `modelizer`, raising `alarm` and
+    /// writing into `buf2`; the Logger reads it.
+    pub fn consumer<'a, M, A, B2>(
+        self,
+        consumer: Consumer,
+        modelizer: &'a M,
+        alarm: &'a A,
+        buf2: &'a B2,
+    ) -> PipelineBuilder<P, ConsumerStage<'a, M, A, B2>, L>
+    where
+        M: Modelizer,
+        A: Alarm,
+        B2: Buffer2 + Buffer2Read + Close,
+    {
+        PipelineBuilder {
+            producer: self.producer,
+            consumer: ConsumerStage { consumer, modelizer, alarm, buf2 },
+            logger: self.logger,
+        }
+    }
+}
+
+impl<P, C> PipelineBuilder<P, C, Missing> {
+    /// Attach the Logger, persisting into `storage`.
+    pub fn logger<S: Storage>(
+        self,
+        logger: Logger,
+        storage: &S,
+    ) -> PipelineBuilder<P, C, LoggerStage<'_, S>> {
+        PipelineBuilder {
+            producer: self.producer,
+            consumer: self.consumer,
+            logger: LoggerStage { logger, storage },
+        }
+    }
+}
+
+impl<'a, B1, M, A, B2, S>
+    PipelineBuilder<ProducerStage<'a, B1>, ConsumerStage<'a, M, A, B2>, LoggerStage<'a, S>>
+{
+    /// Bundle the three attached stages.
+    #[must_use]
+    pub fn build(self) -> WiredPipeline<'a, B1, M, A, B2, S> {
+        let ProducerStage { producer, buf1 } = self.producer;
+        let ConsumerStage { consumer, modelizer, alarm, buf2 } = self.consumer;
+        let LoggerStage { logger, storage } = self.logger;
+        WiredPipeline {
+            pipeline: Pipeline::new(producer, consumer, logger),
+            buf1,
+            modelizer,
+            alarm,
+            buf2,
+            storage,
+        }
+    }
+}
+
+/// The three stages bound to their adapters, as built by
+/// [`PipelineBuilder::build`].
+#[derive(Debug)]
+pub struct WiredPipeline<'a, B1, M, A, B2, S> {
+    pipeline: Pipeline,
+    buf1: &'a B1,
+    modelizer: &'a M,
+    alarm: &'a A,
+    buf2: &'a B2,
+    storage: &'a S,
+}
+
+impl<B1, M, A, B2, S> WiredPipeline<'_, B1, M, A, B2, S>
+where
+    B1: Buffer1 + Buffer1Read + Close,
+    M: Modelizer,
+    A: Alarm,
+    B2: Buffer2 + Buffer2Read + Close,
+    S: Storage,
+{
+    /// Run the stages until the shutdown cascade completes, as
+    /// [`Pipeline::run`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first stage error; the other stages are then dropped.
+    pub async fn run(&self) -> Result<StageStops, PipelineError> {
+        let Self { pipeline, buf1, modelizer, alarm, buf2, storage } = self;
+        pipeline.run(*buf1, *modelizer, *alarm, *buf2, *storage).await
+    }
+
+    /// Start the shutdown cascade of a running pipeline (e.g. on CTRL+C):
+    /// close Buffer1, so the Producer stops and the Consumer and Logger stop
+    /// once they have drained their input.
+    pub fn shutdown(&self) {
+        self.buf1.close();
+    }
+}
+
+impl<B1, M, A, B2, S> WiredPipeline<'_, B1, M, A, B2, S> {
+    /// The stages, for their counters and [`Pipeline::report`].
+    #[must_use]
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+}
@@ -0,0 +1,112 @@
+// Rust guideline compliant 2026-02-27
+
+//! Every stage log event carries the canonical fields of `domain::obs`, and
+//! the `tracing` target of its stage.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use domain::obs;
+use pipeline::prelude::*;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt as _};
+
+/// Target, level, message and field names of one event.
+#[derive(Debug)]
+struct Captured {
+    target: &'static str,
+    level: tracing::Level,
+    message: String,
+    fields: BTreeSet<&'static str>,
+}
+
+impl Visit for Captured {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields.insert(field.name());
+        }
+    }
+}
+
+/// Layer keeping every event, at every level.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<Captured>>>);
+
+impl<S: tracing::Subscriber> Layer<S> for Capture {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut captured = Captured {
+            target: event.metadata().target(),
+            level: *event.metadata().level(),
+            message: String::new(),
+            fields: BTreeSet::new(),
+        };
+        event.record(&mut captured);
+        self.0.lock().unwrap().push(captured);
+    }
+}
+
+// LF-T01: a seeded mini-run logs a batch and a stop event per stage, each
+// with the canonical field names.
+#[tokio::test]
+async fn stage_events_use_the_canonical_fields() {
+    let capture = Capture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let config = PipelineConfig::builder(3).fraud_rate(0.5).seed(7).build().unwrap();
+    run_demo_pipeline(config).await.unwrap();
+
+    let events = capture.0.lock().unwrap();
+    let batch = BTreeSet::from([obs::STAGE, obs::BATCH_SIZE, obs::FLAGGED]);
+    let stop = BTreeSet::from([obs::STAGE, obs::REASON, obs::ITERATIONS]);
+    let mut seen = BTreeSet::new();
+    for event in events.iter() {
+        let expected = match event.message.split_once('.') {
+            Some((_, "batch")) => &batch,
+            Some((_, "run.stopped")) => &stop,
+            _ => continue,
+        };
+        assert!(event.fields.is_superset(expected), "{event:?}");
+        seen.insert(event.message.as_str());
+    }
+    let expected = [
+        "consumer.batch",
+        "consumer.run.stopped",
+        "logger.batch",
+        "logger.run.stopped",
+        "modelizer.batch",
+        "producer.batch",
+        "producer.run.stopped",
+    ];
+    assert_eq!(seen, BTreeSet::from(expected));
+}
+
+// LF-T02: with `pipeline::consumer=debug,warn`, a seeded mini-run shows the
+// Consumer's debug events and nothing from another target below warn.
+#[tokio::test]
+async fn consumer_target_filters_one_stage() {
+    let capture = Capture::default();
+    let filter = EnvFilter::new("pipeline::consumer=debug,warn");
+    let subscriber = tracing_subscriber::registry().with(capture.clone().with_filter(filter));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let config = PipelineConfig::builder(3).fraud_rate(0.5).seed(7).build().unwrap();
+    run_demo_pipeline(config).await.unwrap();
+
+    let events = capture.0.lock().unwrap();
+    for event in events.iter() {
+        assert!(
+            event.target == obs::target::CONSUMER || event.level <= tracing::Level::WARN,
+            "{event:?}"
+        );
+    }
+    let consumer_debug = |message: &str| {
+        events.iter().any(|e| e.level == tracing::Level::DEBUG && e.message == message)
+    };
+    assert!(consumer_debug("consumer.batch.read"), "{events:?}");
+    assert!(events.iter().any(|e| e.message == "consumer.run.stopped"), "{events:?}");
+    assert!(!events.iter().any(|e| e.message.starts_with("producer.")), "{events:?}");
+}
@@ -0,0 +1,16 @@
+// `build` only exists once the Producer, Consumer and Logger are attached.
+
+use pipeline::prelude::*;
+
+fn main() {
+    let producer = Producer::new(ProducerConfig::builder(10).build().unwrap());
+    let consumer = Consumer::new(ConsumerConfig::builder(10).build().unwrap());
+    let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+    let modelizer = Modelizer::new(RateModel::new(0.0, 1));
+    let alarm = CountingAlarm::new();
+
+    let _wired = Pipeline::builder()
+        .producer(producer, &buffer1)
+        .consumer(consumer, &modelizer, &alarm, &buffer2)
+        .build();
+}
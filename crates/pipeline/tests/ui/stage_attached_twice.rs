@@ -0,0 +1,11 @@
+// Each stage is attached once: a second Producer has nowhere to go.
+
+use pipeline::prelude::*;
+
+fn main() {
+    let first = Producer::new(ProducerConfig::builder(10).build().unwrap());
+    let second = Producer::new(ProducerConfig::builder(10).build().unwrap());
+    let buffer1: MemoryBuffer<Transaction> = MemoryBuffer::new();
+
+    let _builder = Pipeline::builder().producer(first, &buffer1).producer(second, &buffer1);
+}
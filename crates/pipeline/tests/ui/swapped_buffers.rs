@@ -0,0 +1,15 @@
+// The Consumer writes Buffer2: a Buffer1 of raw transactions does not fit.
+
+use pipeline::prelude::*;
+
+fn main() {
+    let producer = Producer::new(ProducerConfig::builder(10).build().unwrap());
+    let consumer = Consumer::new(ConsumerConfig::builder(10).build().unwrap());
+    let buffer1: MemoryBuffer<Transaction> = MemoryBuffer::new();
+    let modelizer = Modelizer::new(RateModel::new(0.0, 1));
+    let alarm = CountingAlarm::new();
+
+    let _builder = Pipeline::builder()
+        .producer(producer, &buffer1)
+        .consumer(consumer, &modelizer, &alarm, &buffer1);
+}
@@ -0,0 +1,78 @@
+// Rust guideline compliant 2026-02-27
+
+//! Typed pipeline builder: a complete wiring runs like `Pipeline::run`, and
+//! the wrong or incomplete ones under `tests/ui` do not compile.
+
+use std::time::Duration;
+
+use pipeline::prelude::*;
+
+fn stages(iterations: Option<u64>) -> (Producer, Consumer, Logger) {
+    let producer = ProducerConfig::builder(10).poll_interval1(Duration::from_millis(1)).seed(1);
+    let producer = match iterations {
+        Some(n) => producer.iterations(n),
+        None => producer,
+    };
+    (
+        Producer::new(producer.build().unwrap()),
+        Consumer::new(ConsumerConfig::builder(10).seed(2).build().unwrap()),
+        Logger::new(LoggerConfig::builder(10).seed(3).build().unwrap()),
+    )
+}
+
+// WB-T01: a built pipeline runs the close cascade and persists everything.
+#[tokio::test]
+async fn built_pipeline_runs_to_completion() {
+    let (producer, consumer, logger) = stages(Some(5));
+    let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+    let modelizer = Modelizer::new(RateModel::new(0.2, 4));
+    let alarm = CountingAlarm::new();
+    let storage = MemoryStorage::new();
+
+    let wired = Pipeline::builder()
+        .producer(producer, &buffer1)
+        .consumer(consumer, &modelizer, &alarm, &buffer2)
+        .logger(logger, &storage)
+        .build();
+    let stops = wired.run().await.unwrap();
+
+    assert_eq!(stops.producer, StopReason::IterationLimit { iterations: 5 });
+    assert!(matches!(stops.consumer, StopReason::BufferClosed { .. }));
+    assert!(matches!(stops.logger, StopReason::BufferClosed { .. }));
+    let report = wired.pipeline().report(storage.len(), stops);
+    assert_eq!(report.persisted, report.produced);
+    assert_eq!(alarm.count(), report.flagged);
+}
+
+// WB-T02: stages attach in any order; shutdown stops an endless run.
+#[tokio::test(start_paused = true)]
+async fn shutdown_stops_an_endless_run() {
+    let (producer, consumer, logger) = stages(None);
+    let (buffer1, buffer2) = (MemoryBuffer::new(), MemoryBuffer::new());
+    let modelizer = Modelizer::new(RateModel::new(0.0, 4));
+    let alarm = CountingAlarm::new();
+    let storage = MemoryStorage::new();
+
+    let wired = Pipeline::builder()
+        .logger(logger, &storage)
+        .consumer(consumer, &modelizer, &alarm, &buffer2)
+        .producer(producer, &buffer1)
+        .build();
+    let (stops, ()) = tokio::join!(wired.run(), async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        wired.shutdown();
+    });
+
+    let stops = stops.unwrap();
+    assert!(matches!(stops.producer, StopReason::BufferClosed { .. }), "{stops}");
+    // Whatever the Consumer took in before the close reached storage.
+    assert_eq!(storage.len() as u64, wired.pipeline().consumer.stats().transactions);
+}
+
+// WB-T03: a missing stage or a buffer of the wrong item type is a compile
+// error, not a runtime one.
+#[test]
+fn wrong_wirings_do_not_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
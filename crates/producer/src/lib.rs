@@ -5,34 +5,136 @@
 //!
 //! Entry points: [`Producer::generate_batch`], [`Producer::produce_once`],
 //! [`Producer::run`]. Configuration via [`ProducerConfig::builder`].
+//!
+//! With [`ProducerConfigBuilder::pregenerate`] the whole dataset is generated
+//! in [`Producer::new`] and `run` only streams slices of it, keeping RNG and
+//! allocation cost out of a measured window (see [`Producer::pregenerated`]).
+//!
+//! With [`ProducerConfigBuilder::settings`], `run` reads `poll_interval1`
+//! from a live `DynamicSettings` channel at the top of each iteration, so it
+//! can change without a restart.
 
-use domain::{Buffer1, BufferError, Transaction};
-use rand::{Rng, RngCore, SeedableRng, rngs::StdRng};
-use std::cell::RefCell;
-use std::time::Duration;
+use domain::profile::{self, Op};
+use domain::{
+    Buffer1, Clock, ConfigError, ConfigSummary, Currency, DynamicSettings, ErrorChain,
+    EventSender, LiveSettings, PipelineEvent, Preset, RngPort, SettingsReceiver, Sleeper, Stage,
+    StopReason, StreamDigest, SystemClock, TokioSleeper, Transaction, TransactionId, WriteError,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// `tracing` target of every Producer event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::PRODUCER;
 
 // ---------------------------------------------------------------------------
 // ProducerError
 // ---------------------------------------------------------------------------
 
 /// Errors that can occur during transaction production.
+///
+/// `Display` gives only this layer's context; the wrapped error is the
+/// [`source`](std::error::Error::source). Log with [`ErrorChain`] to see both.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum ProducerError {
     /// The supplied configuration is invalid.
-    #[error("invalid producer configuration: {reason}")]
-    InvalidConfig {
-        /// Human-readable description of the problem.
-        reason: String,
-    },
+    #[error("invalid producer configuration")]
+    InvalidConfig(#[from] ConfigError),
     /// A buffer write failed.
-    #[error("buffer error: {source}")]
+    #[error("buffer error")]
     Buffer {
         /// The underlying buffer error.
         #[from]
-        source: BufferError,
+        source: WriteError,
+    },
+}
+
+impl ProducerError {
+    /// Whether restarting the run loop after a backoff may succeed.
+    ///
+    /// - `InvalidConfig`: fatal.
+    /// - `Buffer`: retryable when the buffer error is (`Full`, `Backend`); `Closed` is final.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::InvalidConfig(_) => false,
+            Self::Buffer { source } => source.is_retryable(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// IdStrategy
+// ---------------------------------------------------------------------------
+
+/// How [`Producer::generate_batch`] assigns transaction ids.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdStrategy {
+    /// UUID v4 built from RNG bytes (default; unchanged seeded output).
+    #[default]
+    RandomV4,
+    /// Time-ordered UUID v7: millisecond timestamp from the configured clock,
+    /// followed by a monotonic counter so ids never decrease, even when the
+    /// clock stands still. Deterministic when both seed and clock are fixed.
+    V7,
+    /// Plain counter: `start`, `start + 1`, ... (golden-file tests).
+    Sequential {
+        /// Value of the first id.
+        start: u128,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// AmountDistribution
+// ---------------------------------------------------------------------------
+
+/// Smallest generated amount, in cents.
+const MIN_CENTS: u32 = 1;
+/// Largest generated amount, in cents.
+const MAX_CENTS: u32 = 1_000_000;
+/// Injected fraud draws its amount from `[FRAUD_MIN_CENTS, MAX_CENTS]`.
+const FRAUD_MIN_CENTS: u32 = 900_000;
+
+/// How [`Producer::generate_batch`] draws transaction amounts.
+///
+/// Every distribution yields whole cents in `[0.01, 10_000.00]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum AmountDistribution {
+    /// Uniform over `[0.01, 10_000.00]` (default; unchanged seeded output).
+    #[default]
+    Uniform,
+    /// Exponential with the given mean, clamped to `[0.01, 10_000.00]`:
+    /// many small amounts and a long tail, closer to real card traffic.
+    Exponential {
+        /// Mean amount before clamping; must be in `(0, 10_000]`.
+        mean: f64,
     },
 }
 
+impl AmountDistribution {
+    /// Draw one amount in cents.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "clamped to [MIN_CENTS, MAX_CENTS] before the cast"
+    )]
+    fn sample_cents(self, rng: &mut dyn RngPort) -> u32 {
+        match self {
+            Self::Uniform => rng.random_range(MIN_CENTS..=MAX_CENTS),
+            Self::Exponential { mean } => {
+                // Inverse CDF; 1 - u is in (0, 1], so ln() stays finite.
+                let u: f64 = rng.random();
+                let cents = (-mean * (1.0 - u).ln() * 100.0).round();
+                cents.clamp(f64::from(MIN_CENTS), f64::from(MAX_CENTS)) as u32
+            }
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ProducerConfig + builder
 // ---------------------------------------------------------------------------
@@ -44,12 +146,47 @@ pub enum ProducerError {
 pub struct ProducerConfig {
     /// Maximum number of transactions per batch (range: `[1, n1_max]`).
     pub n1_max: usize,
+    /// Whether every batch has exactly `n1_max` transactions instead of a
+    /// random size in `[1, n1_max]`.
+    pub fixed_batch_size: bool,
     /// Delay between successive batch writes.
     pub poll_interval1: Duration,
     /// Optional upper bound on the number of iterations. `None` means infinite.
     pub iterations: Option<u64>,
     /// Optional RNG seed for reproducible batches. `None` seeds from the OS.
     pub seed: Option<u64>,
+    /// Iterations of the seeded sequence skipped before the first batch.
+    pub start_iteration: u64,
+    /// Transaction id generation scheme.
+    pub id_strategy: IdStrategy,
+    /// Time source for [`IdStrategy::V7`] timestamps.
+    pub clock: Arc<dyn Clock>,
+    /// Waits out [`poll_interval1`](Self::poll_interval1) between batches.
+    pub sleeper: Arc<dyn Sleeper>,
+    /// How many times a `Full` on the remainder of a partially accepted
+    /// batch is retried before the batch fails.
+    pub write_retries: u32,
+    /// Probability in `[0, 1]` that a transaction replays a recent one.
+    pub duplicate_rate: f64,
+    /// Number of recently generated transactions eligible for replay.
+    pub replay_window: usize,
+    /// How fresh transaction amounts are drawn.
+    pub amount_distribution: AmountDistribution,
+    /// Probability in `[0, 1]` that a fresh transaction is injected fraud.
+    pub fraud_rate: f64,
+    /// Number of transactions generated up front. `None` generates per batch.
+    pub pregenerate: Option<usize>,
+    /// Weighted currency distribution of fresh transactions.
+    pub currencies: Vec<(Currency, u32)>,
+    /// Tenants tagged on fresh transactions, with weights normalized to sum
+    /// to 1. Empty leaves `tenant_id` unset.
+    pub tenants: Vec<(String, f64)>,
+    /// Optional observer channel for [`PipelineEvent`]s.
+    pub events: Option<EventSender>,
+    /// Live settings channel; `None` keeps `poll_interval1` for the run.
+    pub settings: Option<SettingsReceiver>,
+    /// Preset the builder started from, if any.
+    pub preset: Option<Preset>,
 }
 
 /// Builder for [`ProducerConfig`].
@@ -58,28 +195,191 @@ pub struct ProducerConfig {
 #[derive(Debug)]
 pub struct ProducerConfigBuilder {
     n1_max: usize,
+    fixed_batch_size: bool,
     poll_interval1: Duration,
     iterations: Option<u64>,
     seed: Option<u64>,
+    start_iteration: u64,
+    id_strategy: IdStrategy,
+    clock: Arc<dyn Clock>,
+    sleeper: Arc<dyn Sleeper>,
+    write_retries: u32,
+    duplicate_rate: f64,
+    replay_window: usize,
+    amount_distribution: AmountDistribution,
+    fraud_rate: f64,
+    pregenerate: Option<usize>,
+    currencies: Vec<(Currency, u32)>,
+    tenants: Option<Vec<(String, f64)>>,
+    events: Option<EventSender>,
+    settings: Option<SettingsReceiver>,
+    preset: Option<Preset>,
 }
 
 impl ProducerConfig {
     /// Create a builder. `n1_max` is the only required parameter.
     ///
-    /// Default values: `poll_interval1 = 100 ms`, `iterations = None`, `seed = None`.
+    /// Default values: `fixed_batch_size = false`, `poll_interval1 = 100 ms`,
+    /// `iterations = None`, `seed = None`, `start_iteration = 0`, `id_strategy = RandomV4`,
+    /// `clock = SystemClock`, `sleeper = TokioSleeper`, `write_retries = 3`,
+    /// `duplicate_rate = 0.0`, `replay_window = 64`, `amount_distribution = Uniform`, `fraud_rate = 0.0`,
+    /// `pregenerate = None`, `currencies = [(EUR, 1)]`, `tenants = None`, `events = None`,
+    /// `settings = None`, `preset = None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use producer::{ProducerConfig, ProducerError};
+    /// use std::time::Duration;
+    ///
+    /// let config = ProducerConfig::builder(100)
+    ///     .poll_interval1(Duration::from_millis(10))
+    ///     .iterations(5)
+    ///     .seed(42)
+    ///     .build()?;
+    /// assert_eq!((config.n1_max, config.iterations), (100, Some(5)));
+    ///
+    /// // `build` validates: a zero batch size is rejected.
+    /// let err = ProducerConfig::builder(0).build().unwrap_err();
+    /// assert!(matches!(err, ProducerError::InvalidConfig(e) if e.field == "n1_max"));
+    /// # Ok::<(), ProducerError>(())
+    /// ```
     #[must_use]
     pub fn builder(n1_max: usize) -> ProducerConfigBuilder {
         ProducerConfigBuilder {
             n1_max,
+            fixed_batch_size: false,
             // 100 ms chosen as a reasonable demo cadence; lower for tests.
             poll_interval1: Duration::from_millis(100),
             iterations: None,
             seed: None,
+            start_iteration: 0,
+            id_strategy: IdStrategy::default(),
+            clock: Arc::new(SystemClock),
+            sleeper: Arc::new(TokioSleeper),
+            write_retries: 3,
+            duplicate_rate: 0.0,
+            replay_window: 64,
+            amount_distribution: AmountDistribution::default(),
+            fraud_rate: 0.0,
+            pregenerate: None,
+            currencies: vec![(Currency::Eur, 1)],
+            tenants: None,
+            events: None,
+            settings: None,
+            preset: None,
         }
     }
+
+    /// Create a builder pre-populated with `preset`: `n1_max`,
+    /// `fixed_batch_size` and `poll_interval1` as listed on [`Preset`], every
+    /// other value as in [`builder`](Self::builder). Later builder calls
+    /// override the preset values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use domain::Preset;
+    /// use producer::{ProducerConfig, ProducerError};
+    ///
+    /// let config = ProducerConfig::from_preset(Preset::Stress).n1_max(200).build()?;
+    /// assert_eq!((config.n1_max, config.fixed_batch_size), (200, true));
+    /// assert_eq!(config.summary().get("preset"), Some("stress"));
+    /// # Ok::<(), ProducerError>(())
+    /// ```
+    #[must_use]
+    pub fn from_preset(preset: Preset) -> ProducerConfigBuilder {
+        let builder = match preset {
+            Preset::Demo => Self::builder(100).poll_interval1(Duration::from_millis(500)),
+            Preset::Stress => Self::builder(1_000)
+                .fixed_batch_size(true)
+                .poll_interval1(Duration::from_millis(1)),
+            Preset::LowLatency => Self::builder(10).poll_interval1(Duration::from_millis(10)),
+        };
+        ProducerConfigBuilder { preset: Some(preset), ..builder }
+    }
+
+    /// [`Preset::Demo`]: batches of up to 100 every 500 ms, slow enough to
+    /// follow the logs.
+    #[must_use]
+    pub fn demo() -> ProducerConfigBuilder {
+        Self::from_preset(Preset::Demo)
+    }
+
+    /// [`Preset::Stress`]: full batches of 1 000 every millisecond, to load
+    /// the rest of the pipeline.
+    #[must_use]
+    pub fn stress() -> ProducerConfigBuilder {
+        Self::from_preset(Preset::Stress)
+    }
+
+    /// [`Preset::LowLatency`]: batches of up to 10 every 10 ms, so each
+    /// transaction waits little in Buffer1.
+    #[must_use]
+    pub fn low_latency() -> ProducerConfigBuilder {
+        Self::from_preset(Preset::LowLatency)
+    }
+
+    /// The effective settings, logged when [`Producer::run`] starts and
+    /// embedded in run reports. Clock, sleeper and observer are left out.
+    #[must_use]
+    pub fn summary(&self) -> ConfigSummary {
+        let id_strategy = match self.id_strategy {
+            IdStrategy::RandomV4 => "v4".to_owned(),
+            IdStrategy::V7 => "v7".to_owned(),
+            IdStrategy::Sequential { start } => format!("sequential from {start}"),
+        };
+        let amount_distribution = match self.amount_distribution {
+            AmountDistribution::Uniform => "uniform".to_owned(),
+            AmountDistribution::Exponential { mean } => format!("exponential mean {mean}"),
+        };
+        let currencies: Vec<String> = self
+            .currencies
+            .iter()
+            .map(|(currency, weight)| format!("{currency}:{weight}"))
+            .collect();
+        let tenants: Vec<String> =
+            self.tenants.iter().map(|(tenant, weight)| format!("{tenant}:{weight}")).collect();
+        ConfigSummary::new("producer")
+            .optional("preset", self.preset)
+            .field("n1_max", self.n1_max)
+            .field("fixed_batch_size", self.fixed_batch_size)
+            .duration("poll_interval1", self.poll_interval1)
+            .optional("iterations", self.iterations)
+            .optional("seed", self.seed)
+            .field("start_iteration", self.start_iteration)
+            .field("id_strategy", id_strategy)
+            .field("duplicate_rate", self.duplicate_rate)
+            .field("replay_window", self.replay_window)
+            .field("amount_distribution", amount_distribution)
+            .field("fraud_rate", self.fraud_rate)
+            .optional("pregenerate", self.pregenerate)
+            .field("currencies", currencies.join(","))
+            .optional("tenants", (!tenants.is_empty()).then(|| tenants.join(",")))
+            .field("write_retries", self.write_retries)
+    }
 }
 
 impl ProducerConfigBuilder {
+    /// Override the maximum batch size, e.g. the one of a preset.
+    #[must_use]
+    pub fn n1_max(mut self, n1_max: usize) -> Self {
+        self.n1_max = n1_max;
+        self
+    }
+
+    /// Generate exactly `n1_max` transactions per batch instead of a random
+    /// size in `[1, n1_max]`, for reproducible capacity planning.
+    ///
+    /// Amounts, names and ids are still drawn from the RNG; the seed then
+    /// only affects data content. No size is drawn, so the seeded content
+    /// differs from a run without the flag.
+    #[must_use]
+    pub fn fixed_batch_size(mut self, fixed: bool) -> Self {
+        self.fixed_batch_size = fixed;
+        self
+    }
+
     /// Override the inter-batch delay.
     #[must_use]
     pub fn poll_interval1(mut self, poll_interval1: Duration) -> Self {
@@ -102,27 +402,311 @@ impl ProducerConfigBuilder {
         self
     }
 
+    /// Resume a seeded run at iteration `k` (0-based): [`Producer::new`]
+    /// generates and discards the first `k` batches, so the first batch
+    /// written is the one the original run wrote at iteration `k`.
+    ///
+    /// Contract: the same configuration with `start_iteration(k)` produces
+    /// exactly the tail of the sequence from `k` on, ids included when they
+    /// do not depend on the wall clock (`RandomV4`, `Sequential`, or `V7`
+    /// with a replayed clock). `run` counts iterations from `k`, so
+    /// [`iterations`](Self::iterations) still marks the end of the original
+    /// run and must be greater than `k`. Skipping replays every draw of the
+    /// skipped batches, so it costs about as much CPU as generating them,
+    /// without the writes and the sleeps. Not combinable with
+    /// [`pregenerate`](Self::pregenerate).
+    #[must_use]
+    pub fn start_iteration(mut self, k: u64) -> Self {
+        self.start_iteration = k;
+        self
+    }
+
+    /// Select the transaction id scheme (default: [`IdStrategy::RandomV4`]).
+    #[must_use]
+    pub fn id_strategy(mut self, id_strategy: IdStrategy) -> Self {
+        self.id_strategy = id_strategy;
+        self
+    }
+
+    /// Inject the time source used for [`IdStrategy::V7`] timestamps.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Inject the sleeper that waits between batches (virtual-time simulations).
+    #[must_use]
+    pub fn sleeper(mut self, sleeper: impl Sleeper + 'static) -> Self {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    /// Retry a `Full` on the rejected remainder of a partially accepted batch
+    /// up to `n` times (default 3), `poll_interval1` apart.
+    ///
+    /// The remainder is written again after `poll_interval1` as long as the
+    /// buffer keeps accepting part of it; only attempts that accept nothing
+    /// use up a retry. A batch rejected whole is never retried here: the
+    /// write fails with `Full` as before.
+    #[must_use]
+    pub fn write_retries(mut self, n: u32) -> Self {
+        self.write_retries = n;
+        self
+    }
+
+    /// Replay a recent transaction (same id and fields) with probability `rate`
+    /// instead of generating a new one. Used to exercise downstream idempotency.
+    #[must_use]
+    pub fn duplicate_rate(mut self, rate: f64) -> Self {
+        self.duplicate_rate = rate;
+        self
+    }
+
+    /// Number of recent transactions kept as replay candidates (default 64).
+    #[must_use]
+    pub fn replay_window(mut self, n: usize) -> Self {
+        self.replay_window = n;
+        self
+    }
+
+    /// Select how amounts are drawn (default: [`AmountDistribution::Uniform`]).
+    #[must_use]
+    pub fn amount_distribution(mut self, distribution: AmountDistribution) -> Self {
+        self.amount_distribution = distribution;
+        self
+    }
+
+    /// Inject fraud-like transactions with probability `rate`: their amount is
+    /// drawn from the top of the range (`[9_000.00, 10_000.00]`) regardless of
+    /// the amount distribution. Used to load-test downstream alerting.
+    #[must_use]
+    pub fn fraud_rate(mut self, rate: f64) -> Self {
+        self.fraud_rate = rate;
+        self
+    }
+
+    /// Generate `total` transactions in [`Producer::new`] and have `run` stream
+    /// them in batches of `n1_max` (the last one may be shorter) instead of
+    /// generating each batch. The run stops once the dataset is exhausted, or
+    /// earlier at the iteration limit.
+    ///
+    /// The dataset is exactly what successive [`Producer::generate_batch`]
+    /// calls would have produced with the same configuration, truncated to
+    /// `total`.
+    #[must_use]
+    pub fn pregenerate(mut self, total: usize) -> Self {
+        self.pregenerate = Some(total);
+        self
+    }
+
+    /// Draw the currency of each fresh transaction from `currencies`, each
+    /// entry picked with probability proportional to its weight.
+    ///
+    /// With a single entry no draw is made, so the seeded output is unchanged.
+    #[must_use]
+    pub fn currencies(mut self, currencies: Vec<(Currency, u32)>) -> Self {
+        self.currencies = currencies;
+        self
+    }
+
+    /// Tag each fresh transaction with a tenant from `tenants`, each entry
+    /// picked with probability proportional to its weight, to simulate
+    /// several upstream banks feeding one pipeline.
+    ///
+    /// Weights are normalized by `build`. With a single entry no draw is
+    /// made, so the seeded output only gains the tag.
+    #[must_use]
+    pub fn tenants(mut self, tenants: Vec<(String, f64)>) -> Self {
+        self.tenants = Some(tenants);
+        self
+    }
+
+    /// Publish `BatchProduced` and `StageStopped` events to `events`.
+    #[must_use]
+    pub fn events(mut self, events: EventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Follow `settings` while running: `run` adopts the latest
+    /// `poll_interval1` published on it at the top of each iteration. An
+    /// invalid update is logged and ignored.
+    #[must_use]
+    pub fn settings(mut self, settings: SettingsReceiver) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
     /// Validate and build the configuration.
     ///
     /// # Errors
     ///
-    /// Returns [`ProducerError::InvalidConfig`] when `n1_max` is zero.
+    /// Returns [`ProducerError::InvalidConfig`] when `n1_max`, `iterations`,
+    /// `replay_window` or `pregenerate` is zero, `duplicate_rate` or
+    /// `fraud_rate` is outside `[0, 1]`, an exponential mean is outside
+    /// `(0, 10_000]`, the `currencies` weights sum to zero, `tenants` is
+    /// invalid (see [`normalize_tenants`]), or `start_iteration` is not below
+    /// `iterations` or is set with `pregenerate`.
     #[must_use = "the Result must be checked; use ? or unwrap"]
     pub fn build(self) -> Result<ProducerConfig, ProducerError> {
         if self.n1_max == 0 {
-            return Err(ProducerError::InvalidConfig {
-                reason: "n1_max must be >= 1".to_owned(),
-            });
+            return Err(ConfigError::new("n1_max", self.n1_max, "must be >= 1").into());
+        }
+        if self.iterations == Some(0) {
+            return Err(ConfigError::new("iterations", 0, "must be >= 1").into());
+        }
+        if self.iterations.is_some_and(|n| self.start_iteration >= n) {
+            return Err(ConfigError::new(
+                "start_iteration",
+                self.start_iteration,
+                "must be < iterations",
+            )
+            .into());
+        }
+        if self.start_iteration > 0 && self.pregenerate.is_some() {
+            return Err(ConfigError::new(
+                "start_iteration",
+                self.start_iteration,
+                "cannot be combined with pregenerate",
+            )
+            .into());
         }
+        // contains() is false for NaN, so NaN is rejected too.
+        if !(0.0..=1.0).contains(&self.duplicate_rate) {
+            return Err(
+                ConfigError::new("duplicate_rate", self.duplicate_rate, "must be in [0, 1]").into(),
+            );
+        }
+        if self.replay_window == 0 {
+            return Err(ConfigError::new("replay_window", self.replay_window, "must be >= 1").into());
+        }
+        if let AmountDistribution::Exponential { mean } = self.amount_distribution
+            && !(mean > 0.0 && mean <= 10_000.0)
+        {
+            return Err(
+                ConfigError::new("amount_distribution", mean, "mean must be in (0, 10000]").into(),
+            );
+        }
+        if !(0.0..=1.0).contains(&self.fraud_rate) {
+            return Err(ConfigError::new("fraud_rate", self.fraud_rate, "must be in [0, 1]").into());
+        }
+        if self.pregenerate == Some(0) {
+            return Err(ConfigError::new("pregenerate", 0, "must be >= 1").into());
+        }
+        let total_weight: u64 = self.currencies.iter().map(|(_, w)| u64::from(*w)).sum();
+        if total_weight == 0 {
+            return Err(
+                ConfigError::new("currencies", total_weight, "weights must sum to >= 1").into(),
+            );
+        }
+        let tenants = self.tenants.map(normalize_tenants).transpose()?.unwrap_or_default();
         Ok(ProducerConfig {
             n1_max: self.n1_max,
+            fixed_batch_size: self.fixed_batch_size,
             poll_interval1: self.poll_interval1,
             iterations: self.iterations,
             seed: self.seed,
+            start_iteration: self.start_iteration,
+            id_strategy: self.id_strategy,
+            clock: self.clock,
+            sleeper: self.sleeper,
+            write_retries: self.write_retries,
+            duplicate_rate: self.duplicate_rate,
+            replay_window: self.replay_window,
+            amount_distribution: self.amount_distribution,
+            fraud_rate: self.fraud_rate,
+            pregenerate: self.pregenerate,
+            currencies: self.currencies,
+            tenants,
+            events: self.events,
+            settings: self.settings,
+            preset: self.preset,
         })
     }
 }
 
+/// Check the `tenants` option and scale its weights to sum to 1.
+///
+/// # Errors
+///
+/// Returns a [`ConfigError`] on `tenants` when the list is empty, a name is
+/// empty or repeated, a weight is negative or not finite, or the weights sum
+/// to zero.
+fn normalize_tenants(tenants: Vec<(String, f64)>) -> Result<Vec<(String, f64)>, ConfigError> {
+    if tenants.is_empty() {
+        return Err(ConfigError::new("tenants", "[]", "must not be empty"));
+    }
+    for (index, (tenant, weight)) in tenants.iter().enumerate() {
+        if tenant.is_empty() {
+            return Err(ConfigError::new("tenants", "\"\"", "names must not be empty"));
+        }
+        if tenants[..index].iter().any(|(other, _)| other == tenant) {
+            return Err(ConfigError::new("tenants", tenant, "names must be unique"));
+        }
+        // Negative, infinite and NaN weights all fail this.
+        if !(weight.is_finite() && *weight >= 0.0) {
+            return Err(ConfigError::new("tenants", weight, "weights must be finite and >= 0"));
+        }
+    }
+    let total: f64 = tenants.iter().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return Err(ConfigError::new("tenants", total, "weights must sum to > 0"));
+    }
+    Ok(tenants.into_iter().map(|(tenant, weight)| (tenant, weight / total)).collect())
+}
+
+// ---------------------------------------------------------------------------
+// ProducerStats
+// ---------------------------------------------------------------------------
+
+/// Cumulative counters over the lifetime of a [`Producer`].
+///
+/// Obtain a snapshot via [`Producer::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProducerStats {
+    /// Number of batches generated.
+    pub batches: u64,
+    /// Total number of transactions generated, duplicates included.
+    pub transactions: u64,
+    /// Transactions replayed from the recent window (see `duplicate_rate`).
+    pub duplicates: u64,
+    /// Fresh transactions given a fraud-like amount (see `fraud_rate`).
+    pub injected: u64,
+    /// Transactions generated per tenant (see `tenants`), duplicates included.
+    pub per_tenant: BTreeMap<String, u64>,
+}
+
+impl ProducerStats {
+    /// Count one generated or streamed `batch`.
+    fn record_batch(&mut self, batch: &[Transaction]) {
+        self.batches += 1;
+        self.transactions += batch.len() as u64;
+        for tenant in batch.iter().filter_map(|tx| tx.tenant_id.as_ref()) {
+            // get_mut first: the name is only cloned for a new tenant.
+            if let Some(count) = self.per_tenant.get_mut(tenant) {
+                *count += 1;
+            } else {
+                self.per_tenant.insert(tenant.clone(), 1);
+            }
+        }
+    }
+}
+
+impl fmt::Display for ProducerStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "producer: {} batches, {} transactions, {} duplicates, {} injected",
+            self.batches, self.transactions, self.duplicates, self.injected
+        )?;
+        for (tenant, transactions) in &self.per_tenant {
+            write!(f, "\n  tenant {tenant}: {transactions} transactions")?;
+        }
+        Ok(())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Producer
 // ---------------------------------------------------------------------------
@@ -135,6 +719,87 @@ const LAST_NAMES: &[&str] = &[
     "Taylor",
 ];
 
+/// Mutable state behind [`IdStrategy`]; only the active strategy's fields are used.
+#[derive(Debug)]
+struct IdGenerator {
+    strategy: IdStrategy,
+    /// Next value handed out by `Sequential`.
+    next_seq: u128,
+    /// Millisecond timestamp embedded in the last `V7` id.
+    last_ms: u64,
+    /// 74-bit `V7` counter; re-randomized when the timestamp advances.
+    counter: u128,
+}
+
+impl IdGenerator {
+    fn new(strategy: IdStrategy) -> Self {
+        let next_seq = match strategy {
+            IdStrategy::Sequential { start } => start,
+            IdStrategy::RandomV4 | IdStrategy::V7 => 0,
+        };
+        Self { strategy, next_seq, last_ms: 0, counter: 0 }
+    }
+
+    /// Produce the next id, drawing from `rng` only when the strategy needs it.
+    fn next_id(&mut self, rng: &mut dyn RngPort, clock: &dyn Clock) -> TransactionId {
+        let id = match self.strategy {
+            IdStrategy::RandomV4 => {
+                // Build UUID from raw random bytes (no v4 fast-path needed).
+                let mut bytes = [0u8; 16];
+                rng.fill(&mut bytes);
+                uuid::Builder::from_random_bytes(bytes).into_uuid()
+            }
+            IdStrategy::V7 => {
+                let ms = unix_millis(clock.now());
+                if ms > self.last_ms {
+                    self.last_ms = ms;
+                    // 64 random bits leave 10 bits of headroom in the 74-bit
+                    // counter for increments within the same millisecond.
+                    self.counter = u128::from(rng.random::<u64>());
+                } else {
+                    // Clock stood still or went backwards: keep the previous
+                    // timestamp and bump the counter so ids stay increasing.
+                    self.counter += 1;
+                }
+                uuid_v7(self.last_ms, self.counter)
+            }
+            IdStrategy::Sequential { .. } => {
+                let id = uuid::Uuid::from_u128(self.next_seq);
+                self.next_seq = self.next_seq.wrapping_add(1);
+                id
+            }
+        };
+        TransactionId::from_uuid(id)
+    }
+}
+
+/// `seed` if set, otherwise a fresh one drawn from OS entropy; logged so an
+/// unseeded run can be replayed.
+fn effective_seed(seed: Option<u64>) -> u64 {
+    let seed = seed.unwrap_or_else(rand::random);
+    tracing::info!(target: TARGET, seed, "producer.seed");
+    seed
+}
+
+/// Milliseconds since the Unix epoch; times before the epoch map to 0.
+fn unix_millis(t: SystemTime) -> u64 {
+    t.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Assemble a RFC 9562 UUID v7 from a 48-bit timestamp and a 74-bit counter.
+///
+/// Layout: `unix_ts_ms(48) | ver(4) | rand_a(12) | var(2) | rand_b(62)`, with the
+/// counter split across `rand_a` (high 12 bits) and `rand_b` (low 62 bits), so
+/// numeric order of the result follows `(ms, counter)`.
+fn uuid_v7(ms: u64, counter: u128) -> uuid::Uuid {
+    let ts = u128::from(ms) & 0xFFFF_FFFF_FFFF;
+    let rand_a = (counter >> 62) & 0xFFF;
+    let rand_b = counter & ((1u128 << 62) - 1);
+    let value = (ts << 80) | (0x7u128 << 76) | (rand_a << 64) | (0b10u128 << 62) | rand_b;
+    uuid::Uuid::from_u128(value)
+}
+
 /// Generates random transaction batches and forwards them to a [`Buffer1`] port.
 ///
 /// Generic over `B: Buffer1` for zero-cost static dispatch. Holds no concrete
@@ -143,107 +808,474 @@ const LAST_NAMES: &[&str] = &[
 pub struct Producer {
     config: ProducerConfig,
     /// Interior mutability required because all public methods take `&self`.
-    rng: RefCell<StdRng>,
+    rng: RefCell<Box<dyn RngPort>>,
+    /// Id generation state; same interior-mutability rationale as `rng`.
+    ids: RefCell<IdGenerator>,
+    /// Replay candidates, oldest first; only filled when `duplicate_rate > 0`.
+    recent: RefCell<VecDeque<Transaction>>,
+    /// Pre-generated transactions streamed by `produce_once`, if any.
+    dataset: Option<Vec<Transaction>>,
+    /// Index of the next `dataset` transaction to stream.
+    cursor: Cell<usize>,
+    stats: RefCell<ProducerStats>,
+    /// Seed of the `StdRng`; `None` when the RNG was injected.
+    effective_seed: Option<u64>,
+    /// Iteration `run` counts from: `config.start_iteration` when generating.
+    first_iteration: u64,
+    /// Digest of every transaction a buffer accepted.
+    digest: Cell<StreamDigest>,
+    /// `poll_interval1` in force; see [`ProducerConfigBuilder::settings`].
+    settings: LiveSettings,
 }
 
 impl Producer {
     /// Create a new producer from `config`.
     ///
-    /// Seeds the RNG from `config.seed` if set, otherwise from the OS.
+    /// Seeds the RNG from `config.seed` if set, otherwise from a seed drawn
+    /// from the OS; either way it is kept as [`effective_seed`](Self::effective_seed).
+    /// With `config.start_iteration` set, skips that many batches now; with
+    /// `config.pregenerate` set, also generates the whole dataset now.
     #[must_use]
     pub fn new(config: ProducerConfig) -> Self {
-        let rng = match config.seed {
-            Some(seed) => StdRng::seed_from_u64(seed),
-            None => StdRng::from_os_rng(),
+        let seed = effective_seed(config.seed);
+        let mut producer = Self::with_rng(config, StdRng::seed_from_u64(seed));
+        producer.effective_seed = Some(seed);
+        producer
+    }
+
+    /// Like [`new`](Self::new), but draws everything from `rng` instead of a
+    /// `StdRng`; `config.seed` is ignored. Lets tests inject a recording or
+    /// replaying generator.
+    #[must_use]
+    pub fn with_rng(config: ProducerConfig, rng: impl RngPort + 'static) -> Self {
+        let total = config.pregenerate;
+        let mut producer = Self::from_config(config, Box::new(rng));
+        producer.fast_forward();
+        if let Some(total) = total {
+            let mut dataset = Vec::with_capacity(total);
+            while dataset.len() < total {
+                dataset.extend(producer.generate_batch());
+            }
+            dataset.truncate(total);
+            producer.set_dataset(dataset);
+        }
+        producer
+    }
+
+    /// Create a producer streaming `dataset`, e.g. the
+    /// [`dataset`](Self::dataset) of an earlier producer, so it is generated
+    /// once and reused across runs. `config.pregenerate` and
+    /// `config.start_iteration` are ignored.
+    ///
+    /// An empty `dataset` makes `run` stop before writing anything.
+    #[must_use]
+    pub fn pregenerated(config: ProducerConfig, dataset: Vec<Transaction>) -> Self {
+        let seed = effective_seed(config.seed);
+        let mut producer = Self::from_config(config, Box::new(StdRng::seed_from_u64(seed)));
+        producer.effective_seed = Some(seed);
+        producer.set_dataset(dataset);
+        producer
+    }
+
+    /// Producer generating per batch; shared by `with_rng` and `pregenerated`.
+    fn from_config(config: ProducerConfig, rng: Box<dyn RngPort>) -> Self {
+        let ids = IdGenerator::new(config.id_strategy);
+        let seed = DynamicSettings {
+            poll_interval1: config.poll_interval1,
+            ..DynamicSettings::default()
         };
+        let settings = LiveSettings::new(seed, config.settings.clone());
         Self {
             config,
             rng: RefCell::new(rng),
+            ids: RefCell::new(ids),
+            recent: RefCell::new(VecDeque::new()),
+            dataset: None,
+            cursor: Cell::new(0),
+            stats: RefCell::new(ProducerStats::default()),
+            effective_seed: None,
+            first_iteration: 0,
+            digest: Cell::new(StreamDigest::default()),
+            settings,
+        }
+    }
+
+    /// Generate and discard the first `config.start_iteration` batches, so
+    /// the RNG, id and replay state are where the original run left them.
+    /// The counters restart from zero: nothing skipped was produced.
+    fn fast_forward(&mut self) {
+        let skip = self.config.start_iteration;
+        if skip == 0 {
+            return;
+        }
+        for _ in 0..skip {
+            let _ = self.generate_batch();
+        }
+        *self.stats.get_mut() = ProducerStats::default();
+        self.first_iteration = skip;
+        tracing::info!(target: TARGET, start_iteration = skip, "producer.fast_forward");
+    }
+
+    /// Install `dataset`. Batch and transaction counters restart from zero so
+    /// they count what is streamed; `duplicates` and `injected` are kept
+    /// because they describe the dataset itself.
+    fn set_dataset(&mut self, dataset: Vec<Transaction>) {
+        let stats = self.stats.get_mut();
+        *stats = ProducerStats {
+            duplicates: stats.duplicates,
+            injected: stats.injected,
+            ..ProducerStats::default()
+        };
+        self.dataset = Some(dataset);
+    }
+
+    /// The pre-generated transactions, whether streamed yet or not; empty when
+    /// the producer generates per batch.
+    #[must_use]
+    pub fn dataset(&self) -> &[Transaction] {
+        self.dataset.as_deref().unwrap_or_default()
+    }
+
+    /// `true` once every pre-generated transaction has been streamed; always
+    /// `false` when the producer generates per batch.
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.dataset.as_ref().is_some_and(|dataset| self.cursor.get() >= dataset.len())
+    }
+
+    /// The configuration this producer was built with.
+    #[must_use]
+    pub fn config(&self) -> &ProducerConfig {
+        &self.config
+    }
+
+    /// Seed the RNG was built from: `config.seed`, or the one drawn from the
+    /// OS when it was `None`. Passing it back as `config.seed` replays the
+    /// run. `None` for a producer built [`with_rng`](Self::with_rng).
+    #[must_use]
+    pub fn effective_seed(&self) -> Option<u64> {
+        self.effective_seed
+    }
+
+    /// Snapshot of the counters accumulated so far.
+    #[must_use]
+    pub fn stats(&self) -> ProducerStats {
+        self.stats.borrow().clone()
+    }
+
+    /// Digest of every transaction written to the buffer so far, duplicates
+    /// included; compare it with the Logger's `digest` to detect loss or
+    /// corruption along the pipeline.
+    #[must_use]
+    pub fn digest(&self) -> StreamDigest {
+        self.digest.get()
+    }
+
+    /// Publish `event` to the configured observer channel, if any.
+    fn emit(&self, event: PipelineEvent) {
+        if let Some(events) = &self.config.events {
+            // Err only means no receiver is subscribed right now.
+            let _ = events.send(event);
         }
     }
 
+    /// Publish `StageStopped` for this stage.
+    fn emit_stopped(&self, reason: impl Into<String>) {
+        self.emit(PipelineEvent::StageStopped { stage: Stage::Producer, reason: reason.into() });
+    }
+
+    /// Log and publish a clean stop; returns `reason` for `run` to return.
+    fn stopped(&self, reason: StopReason) -> StopReason {
+        let iterations = reason.iterations();
+        domain::log_stop!("producer", reason, iterations);
+        self.emit_stopped(reason.label());
+        reason
+    }
+
     /// Generate one batch of random transactions.
     ///
-    /// Batch size is uniformly distributed in `[1, config.n1_max]`.
-    /// Each transaction has a random UUID, an amount in `[0.01, 10_000.00]`
-    /// (integer cents / 100), and a random last name from the built-in pool.
+    /// Batch size is uniformly distributed in `[1, config.n1_max]`, or exactly
+    /// `n1_max` with `fixed_batch_size`.
+    /// Each transaction has an id from the configured [`IdStrategy`], an amount
+    /// in `[0.01, 10_000.00]` (integer cents / 100) from the configured
+    /// [`AmountDistribution`], and a random last name from the built-in pool.
+    /// With a non-zero `fraud_rate`, a fresh transaction is, with that
+    /// probability, given a fraud-like amount from the top of the range.
+    ///
+    /// With a non-zero `duplicate_rate`, each transaction is instead, with that
+    /// probability, an exact copy of one drawn from the last `replay_window`
+    /// fresh transactions. At rate 0 the RNG sequence is unchanged.
     #[must_use]
     pub fn generate_batch(&self) -> Vec<Transaction> {
-        let mut rng = self.rng.borrow_mut();
-        let size = rng.random_range(1..=self.config.n1_max);
+        let mut rng_guard = self.rng.borrow_mut();
+        let rng: &mut dyn RngPort = &mut **rng_guard;
+        let mut ids = self.ids.borrow_mut();
+        let mut recent = self.recent.borrow_mut();
+        let mut stats = self.stats.borrow_mut();
+        let replay = self.config.duplicate_rate > 0.0;
+        let size = if self.config.fixed_batch_size {
+            self.config.n1_max
+        } else {
+            rng.random_range(1..=self.config.n1_max)
+        };
         let mut batch = Vec::with_capacity(size);
         for _ in 0..size {
-            // Build UUID from raw random bytes (no v4 fast-path needed).
-            let mut bytes = [0u8; 16];
-            rng.fill_bytes(&mut bytes);
-            let id = uuid::Builder::from_random_bytes(bytes).into_uuid();
+            if replay && !recent.is_empty() && rng.random_bool(self.config.duplicate_rate) {
+                let idx = rng.random_range(0..recent.len());
+                batch.push(recent[idx].clone());
+                stats.duplicates += 1;
+                continue;
+            }
+
+            let clock = self.config.clock.as_ref();
+            let id = profile::time(Op::UuidFill, || ids.next_id(rng, clock));
 
             // Integer cents avoids float-rounding during generation.
             // All values in [1, 1_000_000] are exactly representable as f64.
-            let amount = f64::from(rng.random_range(1u32..=1_000_000u32)) / 100.0;
+            // At fraud_rate 0 no extra draw is made, so seeded output is unchanged.
+            let cents = if self.config.fraud_rate > 0.0 && rng.random_bool(self.config.fraud_rate) {
+                stats.injected += 1;
+                rng.random_range(FRAUD_MIN_CENTS..=MAX_CENTS)
+            } else {
+                let distribution = self.config.amount_distribution;
+                profile::time(Op::AmountSample, || distribution.sample_cents(rng))
+            };
+            let amount = f64::from(cents) / 100.0;
 
             // Index is always in bounds: derived from len().
             let last_name_idx = rng.random_range(0..LAST_NAMES.len());
-            let last_name = LAST_NAMES[last_name_idx].to_owned();
+            let last_name = profile::time(Op::NameClone, || LAST_NAMES[last_name_idx].to_owned());
+
+            let currency = self.draw_currency(rng);
+            let tenant_id = self.draw_tenant(rng);
 
-            batch.push(Transaction {
+            let tx = Transaction {
                 id,
                 amount,
                 last_name,
-            });
+                currency,
+                tenant_id,
+            };
+            if replay {
+                if recent.len() == self.config.replay_window {
+                    recent.pop_front();
+                }
+                recent.push_back(tx.clone());
+            }
+            batch.push(tx);
         }
+        stats.record_batch(&batch);
         batch
     }
 
-    /// Generate one batch and write it to `buffer`.
+    /// Pick a currency from the configured weights.
     ///
-    /// # Errors
+    /// A single-entry distribution makes no draw, keeping seeded output stable.
+    fn draw_currency(&self, rng: &mut dyn RngPort) -> Currency {
+        let currencies = &self.config.currencies;
+        if let [(only, _)] = currencies.as_slice() {
+            return only.clone();
+        }
+        // build() guarantees a positive total weight.
+        let total: u64 = currencies.iter().map(|(_, w)| u64::from(*w)).sum();
+        let mut pick = rng.random_range(0..total);
+        for (currency, weight) in currencies {
+            let weight = u64::from(*weight);
+            if pick < weight {
+                return currency.clone();
+            }
+            pick -= weight;
+        }
+        unreachable!("pick is below the total weight")
+    }
+
+    /// Pick a tenant from the normalized weights; `None` without tenants.
     ///
-    /// Propagates any [`BufferError`] wrapped in [`ProducerError::Buffer`].
-    #[tracing::instrument(skip(self, buffer), level = "debug")]
-    pub async fn produce_once<B: Buffer1>(&self, buffer: &B) -> Result<(), ProducerError> {
-        let batch = self.generate_batch();
-        tracing::debug!(size = batch.len(), "producer.batch.generated");
-        buffer.write_batch(batch).await?;
-        Ok(())
+    /// Zero or one tenant makes no draw, keeping seeded output stable.
+    fn draw_tenant(&self, rng: &mut dyn RngPort) -> Option<String> {
+        match self.config.tenants.as_slice() {
+            [] => None,
+            [(only, _)] => Some(only.clone()),
+            tenants => {
+                let mut pick = rng.random_range(0.0..1.0);
+                for (tenant, weight) in tenants {
+                    if pick < *weight {
+                        return Some(tenant.clone());
+                    }
+                    pick -= weight;
+                }
+                // Rounding can leave the weights summing just below 1.
+                tenants.last().map(|(tenant, _)| tenant.clone())
+            }
+        }
     }
 
-    /// Run the production loop until stopped.
+    /// Copy the next slice of at most `n1_max` transactions out of `dataset`.
+    fn next_pregenerated(&self, dataset: &[Transaction]) -> Vec<Transaction> {
+        let start = self.cursor.get();
+        let end = dataset.len().min(start + self.config.n1_max);
+        self.cursor.set(end);
+        let batch = dataset[start..end].to_vec();
+        let mut stats = self.stats.borrow_mut();
+        stats.record_batch(&batch);
+        batch
+    }
+
+    /// Generate one batch (or take the next pre-generated one) and write it to
+    /// `buffer`.
     ///
-    /// Calls [`produce_once`](Self::produce_once) repeatedly, sleeping
-    /// `config.poll_interval1` between iterations. Stops cleanly when:
-    /// - the buffer signals [`BufferError::Closed`] (returns `Ok(())`), or
-    /// - `config.iterations` batches have been written (returns `Ok(())`).
+    /// If the buffer accepts only part of the batch, the remainder is retried;
+    /// see [`ProducerConfigBuilder::write_retries`]. A batch that fails after
+    /// a partial write leaves its accepted prefix in the buffer.
+    ///
+    /// Once a pre-generated dataset is exhausted this writes nothing and
+    /// returns `Ok(())`; see [`is_exhausted`](Self::is_exhausted). A buffer
+    /// known to be closed (see `Buffer1::is_closed`) fails before any batch
+    /// is generated, so shutdown wastes no generation work.
     ///
     /// # Errors
     ///
-    /// Returns [`ProducerError::Buffer`] for any buffer error other than `Closed`.
-    #[tracing::instrument(name = "producer.run", skip_all)]
-    pub async fn run<B: Buffer1>(&self, buffer: &B) -> Result<(), ProducerError> {
-        let mut count = 0u64;
-        loop {
-            match self.produce_once(buffer).await {
-                Ok(()) => {}
-                Err(ProducerError::Buffer {
-                    source: BufferError::Closed,
+    /// Propagates any [`WriteError`] wrapped in [`ProducerError::Buffer`].
+    #[tracing::instrument(target = "pipeline::producer", skip(self, buffer), level = "debug")]
+    pub async fn produce_once<B: Buffer1>(&self, buffer: &B) -> Result<(), ProducerError> {
+        let batch = match &self.dataset {
+            Some(_) if self.is_exhausted() => return Ok(()),
+            _ if buffer.is_closed() => return Err(WriteError::Closed.into()),
+            Some(dataset) => self.next_pregenerated(dataset),
+            None => self.generate_batch(),
+        };
+        let size = batch.len();
+        tracing::debug!(target: TARGET, batch_size = size, "producer.batch.generated");
+        self.write(buffer, batch).await?;
+        self.emit(PipelineEvent::BatchProduced { size });
+        Ok(())
+    }
+
+    /// Write `batch` with `write_batch_partial`, retrying the rejected
+    /// remainder as described in [`ProducerConfigBuilder::write_retries`].
+    async fn write<B: Buffer1>(
+        &self,
+        buffer: &B,
+        mut batch: Vec<Transaction>,
+    ) -> Result<(), WriteError> {
+        // Accepted transactions leave the batch; hash them beforehand.
+        let hashes: Vec<u64> = batch.iter().map(Transaction::digest_hash).collect();
+        let mut pending = hashes.into_iter();
+        let accepted = buffer.write_batch_partial(&mut batch).await?;
+        self.record_written(pending.by_ref().take(accepted));
+        let mut retries = 0;
+        while !batch.is_empty() {
+            tracing::debug!(
+                target: TARGET,
+                remaining = batch.len(),
+                retries,
+                "producer.batch.partial"
+            );
+            self.config.sleeper.sleep(self.settings.get().poll_interval1).await;
+            match buffer.write_batch_partial(&mut batch).await {
+                Ok(accepted) => self.record_written(pending.by_ref().take(accepted)),
+                Err(WriteError::Full { .. }) if retries < self.config.write_retries => retries += 1,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Adopt the latest live settings, if any were published; an invalid
+    /// update is logged and the settings in force are kept.
+    fn refresh_settings(&self) {
+        match self.settings.refresh() {
+            Ok(false) => {}
+            Ok(true) => {
+                let poll_interval1 = self.settings.get().poll_interval1;
+                tracing::info!(target: TARGET, ?poll_interval1, "producer.settings.reloaded");
+            }
+            Err(e) => tracing::warn!(target: TARGET, error = %e, "producer.settings.rejected"),
+        }
+    }
+
+    /// Fold the hashes of transactions a buffer accepted into the digest.
+    fn record_written(&self, hashes: impl Iterator<Item = u64>) {
+        let mut digest = self.digest.get();
+        for hash in hashes {
+            digest.add_hash(hash);
+        }
+        self.digest.set(digest);
+    }
+
+    /// Run the production loop until stopped.
+    ///
+    /// Calls [`produce_once`](Self::produce_once) repeatedly, sleeping
+    /// the `poll_interval1` in force between iterations. Stops cleanly, returning
+    /// the matching [`StopReason`], when:
+    /// - the buffer signals [`WriteError::Closed`] (`BufferClosed`),
+    /// - `config.iterations` batches have been written (`IterationLimit`), or
+    /// - a pre-generated dataset is exhausted (`Exhausted`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProducerError::Buffer`] for any buffer error other than `Closed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use domain::{BufferDepth as _, StopReason};
+    /// use pipeline::memory::MemoryBuffer;
+    /// use producer::{Producer, ProducerConfig};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), producer::ProducerError> {
+    /// let config = ProducerConfig::builder(10)
+    ///     .fixed_batch_size(true)
+    ///     .poll_interval1(Duration::ZERO)
+    ///     .iterations(3)
+    ///     .seed(1)
+    ///     .build()?;
+    /// let producer = Producer::new(config);
+    /// let buffer1 = MemoryBuffer::new();
+    ///
+    /// let reason = producer.run(&buffer1).await?;
+    /// assert_eq!(reason, StopReason::IterationLimit { iterations: 3 });
+    /// assert_eq!(buffer1.depth(), 30);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(target = "pipeline::producer", name = "producer.run", skip_all)]
+    pub async fn run<B: Buffer1>(&self, buffer: &B) -> Result<StopReason, ProducerError> {
+        tracing::info!(target: TARGET, config = %self.config.summary(), "producer.run.config");
+        let mut count = self.first_iteration;
+        loop {
+            self.refresh_settings();
+            if self.is_exhausted() {
+                return Ok(self.stopped(StopReason::Exhausted { iterations: count }));
+            }
+
+            let before = self.stats().transactions;
+            match self.produce_once(buffer).await {
+                Ok(()) => {}
+                Err(ProducerError::Buffer {
+                    source: WriteError::Closed,
                 }) => {
-                    tracing::info!(count, "producer.run.stopped: buffer closed");
-                    return Ok(());
+                    return Ok(self.stopped(StopReason::BufferClosed { iterations: count }));
+                }
+                Err(e) => {
+                    self.emit_stopped(ErrorChain(&e).to_string());
+                    return Err(e);
                 }
-                Err(e) => return Err(e),
             }
 
             count += 1;
-            tracing::info!(iteration = count, "producer.batch.written");
+            let size = self.stats().transactions - before;
+            domain::log_batch!("producer", size, 0, iteration = count);
 
             if let Some(max) = self.config.iterations
                 && count >= max
             {
-                tracing::info!("producer.run.stopped: iteration limit reached");
-                return Ok(());
+                return Ok(self.stopped(StopReason::IterationLimit { iterations: count }));
             }
 
-            tokio::time::sleep(self.config.poll_interval1).await;
+            self.config.sleeper.sleep(self.settings.get().poll_interval1).await;
         }
     }
 }
@@ -254,10 +1286,15 @@ impl Producer {
 
 #[cfg(test)]
 mod tests {
-    use super::{Producer, ProducerConfig, ProducerError};
-    use domain::{Buffer1, BufferError, Transaction};
+    use super::{AmountDistribution, IdStrategy, Producer, ProducerConfig, ProducerError};
+    use std::collections::HashMap;
+    use rand::{SeedableRng, rngs::StdRng};
+    use domain::{
+        Buffer1, Currency, ErrorChain, FixedClock, PipelineEvent, Preset, Stage, StopReason,
+        StreamDigest, Transaction, TransactionId, WriteError,
+    };
     use std::cell::RefCell;
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
 
     // ------------------------------------------------------------------
     // Test helpers
@@ -285,7 +1322,7 @@ mod tests {
     }
 
     impl Buffer1 for TestBuffer {
-        async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), BufferError> {
+        async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
             self.batches.borrow_mut().push(batch);
             Ok(())
         }
@@ -295,8 +1332,21 @@ mod tests {
     struct ClosedBuffer;
 
     impl Buffer1 for ClosedBuffer {
-        async fn write_batch(&self, _batch: Vec<Transaction>) -> Result<(), BufferError> {
-            Err(BufferError::Closed)
+        async fn write_batch(&self, _batch: Vec<Transaction>) -> Result<(), WriteError> {
+            Err(WriteError::Closed)
+        }
+    }
+
+    /// Buffer known to be closed: says so before any write.
+    struct ShutBuffer;
+
+    impl Buffer1 for ShutBuffer {
+        async fn write_batch(&self, _batch: Vec<Transaction>) -> Result<(), WriteError> {
+            Err(WriteError::Closed)
+        }
+
+        fn is_closed(&self) -> bool {
+            true
         }
     }
 
@@ -304,8 +1354,8 @@ mod tests {
     struct FullBuffer;
 
     impl Buffer1 for FullBuffer {
-        async fn write_batch(&self, _batch: Vec<Transaction>) -> Result<(), BufferError> {
-            Err(BufferError::Full { capacity: 0 })
+        async fn write_batch(&self, _batch: Vec<Transaction>) -> Result<(), WriteError> {
+            Err(WriteError::Full { capacity: 0, rejected: 0 })
         }
     }
 
@@ -316,7 +1366,33 @@ mod tests {
     #[test]
     fn config_rejects_zero() {
         let result = ProducerConfig::builder(0).build();
-        assert!(matches!(result, Err(ProducerError::InvalidConfig { .. })));
+        assert!(matches!(result, Err(ProducerError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn config_errors_name_the_field() {
+        let field = |r: Result<ProducerConfig, ProducerError>| match r {
+            Err(ProducerError::InvalidConfig(e)) => e,
+            other => panic!("expected InvalidConfig, got {other:?}"),
+        };
+        let e = field(ProducerConfig::builder(0).build());
+        assert_eq!((e.field, e.value.as_str(), e.constraint), ("n1_max", "0", "must be >= 1"));
+        let invalid = ProducerError::InvalidConfig(e);
+        assert_eq!(invalid.to_string(), "invalid producer configuration");
+        assert_eq!(
+            ErrorChain(&invalid).to_string(),
+            "invalid producer configuration: n1_max must be >= 1 (got 0)"
+        );
+        assert_eq!(field(ProducerConfig::builder(10).iterations(0).build()).field, "iterations");
+        let e = field(ProducerConfig::builder(10).duplicate_rate(f64::NAN).build());
+        assert_eq!((e.field, e.value.as_str()), ("duplicate_rate", "NaN"));
+        assert_eq!(field(ProducerConfig::builder(10).replay_window(0).build()).field, "replay_window");
+        assert_eq!(field(ProducerConfig::builder(10).pregenerate(0).build()).field, "pregenerate");
+        let e = field(ProducerConfig::builder(10).iterations(5).start_iteration(5).build());
+        assert_eq!((e.field, e.constraint), ("start_iteration", "must be < iterations"));
+        let e = field(ProducerConfig::builder(10).pregenerate(5).start_iteration(1).build());
+        assert_eq!(e.field, "start_iteration");
+        assert_eq!(e.constraint, "cannot be combined with pregenerate");
     }
 
     #[test]
@@ -348,8 +1424,8 @@ mod tests {
         let batch = producer.generate_batch();
         assert!(!batch.is_empty());
         for tx in &batch {
-            // UUID can always be round-tripped through its string form.
-            let parsed = tx.id.to_string().parse::<uuid::Uuid>().unwrap();
+            // The id can always be round-tripped through its full string form.
+            let parsed = tx.id.full().parse::<TransactionId>().unwrap();
             assert_eq!(parsed, tx.id, "id must be a valid UUID");
             assert!(
                 tx.amount >= 0.01_f64 && tx.amount <= 10_000.00_f64,
@@ -372,6 +1448,105 @@ mod tests {
         );
     }
 
+    /// Stateful generation: replays, injected fraud, weighted currencies and
+    /// sequential ids all carry state across batches.
+    fn resumable_config(start: u64) -> ProducerConfig {
+        ProducerConfig::builder(10)
+            .seed(21)
+            .iterations(10)
+            .start_iteration(start)
+            .poll_interval1(Duration::ZERO)
+            .id_strategy(IdStrategy::Sequential { start: 1 })
+            .duplicate_rate(0.2)
+            .fraud_rate(0.1)
+            .currencies(vec![(Currency::Eur, 3), (Currency::Usd, 1)])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn start_iteration_resumes_the_seeded_sequence() {
+        let original = Producer::new(resumable_config(0));
+        let all: Vec<_> = (0..10).map(|_| original.generate_batch()).collect();
+
+        let resumed = Producer::new(resumable_config(5));
+        let tail: Vec<_> = (5..10).map(|_| resumed.generate_batch()).collect();
+
+        assert_eq!(tail, all[5..]);
+        assert_eq!(resumed.stats().batches, 5, "skipped batches are not counted");
+    }
+
+    #[tokio::test]
+    async fn resumed_run_writes_the_tail_and_stops_at_the_same_iteration() {
+        let original = TestBuffer::new();
+        Producer::new(resumable_config(0)).run(&original).await.unwrap();
+        let resumed = TestBuffer::new();
+
+        let reason = Producer::new(resumable_config(5)).run(&resumed).await.unwrap();
+
+        assert_eq!(reason, StopReason::IterationLimit { iterations: 10 });
+        assert_eq!(*resumed.batches.borrow(), original.batches.borrow()[5..]);
+    }
+
+    #[test]
+    fn effective_seed_replays_an_unseeded_producer() {
+        let unseeded = Producer::new(ProducerConfig::builder(10).build().unwrap());
+        let seed = unseeded.effective_seed().unwrap();
+        let replay = Producer::new(ProducerConfig::builder(10).seed(seed).build().unwrap());
+
+        assert_eq!(replay.effective_seed(), Some(seed));
+        assert_eq!(unseeded.generate_batch(), replay.generate_batch());
+        let config = ProducerConfig::builder(10).build().unwrap();
+        let injected = Producer::with_rng(config, StdRng::seed_from_u64(1));
+        assert_eq!(injected.effective_seed(), None);
+    }
+
+    // ------------------------------------------------------------------
+    // Presets
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn presets_fill_size_and_interval() {
+        let values = |builder: super::ProducerConfigBuilder| {
+            let c = builder.build().unwrap();
+            let preset = c.summary().get("preset").map(str::to_owned);
+            (c.n1_max, c.fixed_batch_size, c.poll_interval1, preset)
+        };
+        let ms = Duration::from_millis;
+        assert_eq!(values(ProducerConfig::demo()), (100, false, ms(500), Some("demo".to_owned())));
+        assert_eq!(
+            values(ProducerConfig::stress()),
+            (1_000, true, ms(1), Some("stress".to_owned()))
+        );
+        assert_eq!(
+            values(ProducerConfig::low_latency()),
+            (10, false, ms(10), Some("low-latency".to_owned()))
+        );
+        for preset in Preset::ALL {
+            assert_eq!(ProducerConfig::from_preset(preset).build().unwrap().preset, Some(preset));
+        }
+        assert_eq!(ProducerConfig::builder(10).build().unwrap().preset, None);
+    }
+
+    #[test]
+    fn builder_overrides_win_over_the_preset() {
+        let config = ProducerConfig::stress()
+            .n1_max(5)
+            .fixed_batch_size(false)
+            .poll_interval1(Duration::ZERO)
+            .seed(7)
+            .build()
+            .unwrap();
+        assert_eq!((config.n1_max, config.fixed_batch_size), (5, false));
+        assert_eq!((config.poll_interval1, config.seed), (Duration::ZERO, Some(7)));
+        assert_eq!(config.preset, Some(Preset::Stress));
+        let e = match ProducerConfig::demo().n1_max(0).build() {
+            Err(ProducerError::InvalidConfig(e)) => e,
+            other => panic!("expected InvalidConfig, got {other:?}"),
+        };
+        assert_eq!(e.field, "n1_max");
+    }
+
     // ------------------------------------------------------------------
     // US2: produce_once + buffer write
     // ------------------------------------------------------------------
@@ -404,8 +1579,9 @@ mod tests {
         let producer = Producer::new(config);
         let buffer = TestBuffer::new();
 
-        producer.run(&buffer).await.unwrap();
+        let reason = producer.run(&buffer).await.unwrap();
 
+        assert_eq!(reason, StopReason::IterationLimit { iterations: 5 });
         assert_eq!(buffer.batch_count(), 5, "expected exactly 5 batches");
         let total = buffer.total_tx_count();
         // 5 batches, each 1..=10 transactions
@@ -423,7 +1599,35 @@ mod tests {
             .unwrap();
         let producer = Producer::new(config);
         let result = producer.run(&ClosedBuffer).await;
-        assert!(result.is_ok(), "Closed must terminate cleanly: {result:?}");
+        assert!(
+            matches!(result, Ok(StopReason::BufferClosed { iterations: 0 })),
+            "Closed must terminate cleanly: {result:?}"
+        );
+        // Without is_closed, the batch is generated before the write fails.
+        assert_eq!(producer.stats().batches, 1);
+    }
+
+    #[tokio::test]
+    async fn known_closed_buffer_skips_generation() {
+        let config = ProducerConfig::builder(10)
+            .poll_interval1(Duration::ZERO)
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+
+        let once = producer.produce_once(&ShutBuffer).await;
+        let result = producer.run(&ShutBuffer).await;
+
+        assert!(
+            matches!(once, Err(ProducerError::Buffer { source: WriteError::Closed })),
+            "{once:?}"
+        );
+        assert!(
+            matches!(result, Ok(StopReason::BufferClosed { iterations: 0 })),
+            "Closed must terminate cleanly: {result:?}"
+        );
+        assert_eq!(producer.stats().batches, 0);
+        assert_eq!(producer.stats().transactions, 0);
     }
 
     #[tokio::test]
@@ -438,10 +1642,745 @@ mod tests {
             matches!(
                 result,
                 Err(ProducerError::Buffer {
-                    source: BufferError::Full { .. }
+                    source: WriteError::Full { .. }
                 })
             ),
             "Full error must be propagated: {result:?}"
         );
     }
+
+    /// Records every requested sleep and returns at once.
+    #[derive(Debug, Clone, Default)]
+    struct RecordingSleeper(std::sync::Arc<std::sync::Mutex<Vec<Duration>>>);
+
+    impl domain::Sleeper for RecordingSleeper {
+        fn sleep(&self, duration: Duration) -> domain::SleepFuture<'_> {
+            self.0.lock().unwrap().push(duration);
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn run_sleeps_through_the_injected_sleeper() {
+        let sleeper = RecordingSleeper::default();
+        let config = ProducerConfig::builder(10)
+            .iterations(3)
+            .poll_interval1(Duration::from_millis(500))
+            .sleeper(sleeper.clone())
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+
+        producer.run(&TestBuffer::new()).await.unwrap();
+
+        // No sleep after the last batch.
+        assert_eq!(*sleeper.0.lock().unwrap(), [Duration::from_millis(500); 2]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn interval_published_mid_run_applies_from_the_next_iteration() {
+        let (tx, rx) = tokio::sync::watch::channel(domain::DynamicSettings {
+            poll_interval1: Duration::from_millis(100),
+            ..domain::DynamicSettings::default()
+        });
+        let config = ProducerConfig::builder(10)
+            .iterations(4)
+            .poll_interval1(Duration::from_millis(100))
+            .settings(rx)
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let start = tokio::time::Instant::now();
+
+        let publish = async {
+            // Lands during the second sleep, which keeps its 100 ms.
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            tx.send_modify(|s| s.poll_interval1 = Duration::from_millis(10));
+        };
+        let buffer = TestBuffer::new();
+        let (result, ()) = tokio::join!(producer.run(&buffer), publish);
+
+        result.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(210));
+    }
+
+    #[tokio::test]
+    async fn invalid_settings_update_is_ignored() {
+        let (tx, rx) = tokio::sync::watch::channel(domain::DynamicSettings::default());
+        tx.send_replace(domain::DynamicSettings {
+            poll_interval1: Duration::from_millis(1),
+            batch_size_mode: domain::BatchSizeMode::Geometric { p: 0.0 },
+            ..domain::DynamicSettings::default()
+        });
+        let sleeper = RecordingSleeper::default();
+        let config = ProducerConfig::builder(10)
+            .iterations(3)
+            .poll_interval1(Duration::from_millis(500))
+            .sleeper(sleeper.clone())
+            .settings(rx)
+            .build()
+            .unwrap();
+
+        Producer::new(config).run(&TestBuffer::new()).await.unwrap();
+
+        assert_eq!(*sleeper.0.lock().unwrap(), [Duration::from_millis(500); 2]);
+    }
+
+    // ------------------------------------------------------------------
+    // IdStrategy
+    // ------------------------------------------------------------------
+
+    fn fixed_clock() -> FixedClock {
+        FixedClock(SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))
+    }
+
+    #[test]
+    fn random_v4_is_default_and_matches_seeded_output() {
+        // Regression guard: the explicit default must not change RNG consumption.
+        let implicit = ProducerConfig::builder(10).seed(99).build().unwrap();
+        let explicit = ProducerConfig::builder(10)
+            .seed(99)
+            .id_strategy(IdStrategy::RandomV4)
+            .build()
+            .unwrap();
+        assert_eq!(implicit.id_strategy, IdStrategy::RandomV4);
+        let batch1 = Producer::new(implicit).generate_batch();
+        let batch2 = Producer::new(explicit).generate_batch();
+        assert_eq!(batch1, batch2);
+        for tx in &batch1 {
+            assert_eq!(tx.id.full().parse::<uuid::Uuid>().unwrap().get_version_num(), 4);
+        }
+    }
+
+    #[test]
+    fn v7_ids_from_fixed_clock_are_monotonic() {
+        let config = ProducerConfig::builder(10)
+            .seed(5)
+            .id_strategy(IdStrategy::V7)
+            .clock(fixed_clock())
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let ids: Vec<TransactionId> = (0..5)
+            .flat_map(|_| producer.generate_batch())
+            .map(|tx| tx.id)
+            .collect();
+        assert!(ids.len() >= 5);
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1], "{:#} must sort before {:#}", pair[0], pair[1]);
+        }
+        for id in ids.iter().map(|id| id.full().parse::<uuid::Uuid>().unwrap()) {
+            assert_eq!(id.get_version_num(), 7);
+            assert_eq!(id.get_variant(), uuid::Variant::RFC4122);
+            // Top 48 bits carry the clock's millisecond timestamp.
+            assert_eq!(id.as_u128() >> 80, 1_700_000_000_123);
+        }
+    }
+
+    #[test]
+    fn v7_is_deterministic_with_fixed_seed_and_clock() {
+        let make = || {
+            ProducerConfig::builder(10)
+                .seed(11)
+                .id_strategy(IdStrategy::V7)
+                .clock(fixed_clock())
+                .build()
+                .unwrap()
+        };
+        let batch1 = Producer::new(make()).generate_batch();
+        let batch2 = Producer::new(make()).generate_batch();
+        assert_eq!(batch1, batch2);
+    }
+
+    #[test]
+    fn sequential_ids_are_predictable() {
+        let config = ProducerConfig::builder(10)
+            .seed(3)
+            .id_strategy(IdStrategy::Sequential { start: 1_000 })
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let ids: Vec<u128> = (0..3)
+            .flat_map(|_| producer.generate_batch())
+            .map(|tx| tx.id.full().parse::<uuid::Uuid>().unwrap().as_u128())
+            .collect();
+        let expected: Vec<u128> = (1_000..).take(ids.len()).collect();
+        assert_eq!(ids, expected);
+    }
+
+    // ------------------------------------------------------------------
+    // Partial writes
+    // ------------------------------------------------------------------
+
+    /// Buffer accepting, per write, the next scripted number of transactions;
+    /// once the script runs out, nothing fits.
+    #[derive(Default)]
+    struct RoomBuffer {
+        room: RefCell<std::collections::VecDeque<usize>>,
+        /// Size of the batch handed to each write, in call order.
+        attempts: RefCell<Vec<usize>>,
+        written: RefCell<Vec<Transaction>>,
+    }
+
+    impl RoomBuffer {
+        fn with_room(room: &[usize]) -> Self {
+            Self { room: RefCell::new(room.iter().copied().collect()), ..Self::default() }
+        }
+    }
+
+    impl Buffer1 for RoomBuffer {
+        async fn write_batch(&self, _batch: Vec<Transaction>) -> Result<(), WriteError> {
+            unreachable!("the Producer writes through write_batch_partial")
+        }
+
+        async fn write_batch_partial(
+            &self,
+            batch: &mut Vec<Transaction>,
+        ) -> Result<usize, WriteError> {
+            self.attempts.borrow_mut().push(batch.len());
+            let room = self.room.borrow_mut().pop_front().unwrap_or(0);
+            if room == 0 {
+                return Err(WriteError::Full { capacity: 0, rejected: batch.len() });
+            }
+            let accepted = room.min(batch.len());
+            self.written.borrow_mut().extend(batch.drain(..accepted));
+            Ok(accepted)
+        }
+    }
+
+    fn partial_producer(write_retries: u32, sleeper: &RecordingSleeper) -> Producer {
+        let config = ProducerConfig::builder(5)
+            .fixed_batch_size(true)
+            .poll_interval1(Duration::from_millis(10))
+            .sleeper(sleeper.clone())
+            .write_retries(write_retries)
+            .seed(7)
+            .build()
+            .unwrap();
+        Producer::new(config)
+    }
+
+    #[tokio::test]
+    async fn partial_write_retries_only_the_remainder() {
+        let sleeper = RecordingSleeper::default();
+        let producer = partial_producer(3, &sleeper);
+        let buffer = RoomBuffer::with_room(&[4, 1]);
+
+        producer.produce_once(&buffer).await.unwrap();
+
+        assert_eq!(*buffer.attempts.borrow(), [5, 1]);
+        let expected = partial_producer(3, &sleeper).generate_batch();
+        assert_eq!(*buffer.written.borrow(), expected);
+        assert_eq!(*sleeper.0.lock().unwrap(), [Duration::from_millis(10)]);
+    }
+
+    #[tokio::test]
+    async fn remainder_full_past_write_retries_fails_the_batch() {
+        let sleeper = RecordingSleeper::default();
+        let producer = partial_producer(2, &sleeper);
+        // 3 fit, then nothing, then 1 more, then nothing for good.
+        let buffer = RoomBuffer::with_room(&[3, 0, 1]);
+
+        let result = producer.produce_once(&buffer).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(ProducerError::Buffer { source: WriteError::Full { rejected: 1, .. } })
+            ),
+            "{result:?}"
+        );
+        // 3 accepted, Full (retry 1), 1 accepted, Full (retry 2), Full: out of retries.
+        assert_eq!(*buffer.attempts.borrow(), [5, 2, 2, 1, 1]);
+        assert_eq!(buffer.written.borrow().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn digest_covers_only_the_accepted_transactions() {
+        let sleeper = RecordingSleeper::default();
+        let producer = partial_producer(0, &sleeper);
+        let buffer = RoomBuffer::with_room(&[3]);
+
+        producer.produce_once(&buffer).await.unwrap_err();
+
+        let mut expected = StreamDigest::default();
+        expected.extend(buffer.written.borrow().iter());
+        assert_eq!(producer.digest(), expected);
+        assert_eq!(producer.digest().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn batch_with_no_room_fails_without_retry() {
+        let sleeper = RecordingSleeper::default();
+        let producer = partial_producer(3, &sleeper);
+        let buffer = RoomBuffer::with_room(&[]);
+
+        let result = producer.produce_once(&buffer).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(ProducerError::Buffer { source: WriteError::Full { rejected: 5, .. } })
+            ),
+            "{result:?}"
+        );
+        assert_eq!(*buffer.attempts.borrow(), [5]);
+        assert!(sleeper.0.lock().unwrap().is_empty());
+    }
+
+    // ------------------------------------------------------------------
+    // Retryable classification
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn error_retryable_classification() {
+        let invalid = ProducerConfig::builder(0).build().unwrap_err();
+        assert!(!invalid.is_retryable());
+        assert!(ProducerError::from(WriteError::Full { capacity: 1, rejected: 1 }).is_retryable());
+        assert!(!ProducerError::from(WriteError::Closed).is_retryable());
+    }
+
+    #[test]
+    fn error_display_leaves_the_cause_to_source() {
+        let full = ProducerError::from(WriteError::Full { capacity: 4, rejected: 2 });
+        assert_eq!(
+            ErrorChain(&full).messages(),
+            ["buffer error", "buffer full (capacity: 4, rejected: 2)"]
+        );
+    }
+
+    // ------------------------------------------------------------------
+    // Duplicate injection
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn duplicate_rate_validation() {
+        for rate in [-0.1, 1.1, f64::NAN] {
+            let result = ProducerConfig::builder(10).duplicate_rate(rate).build();
+            assert!(matches!(result, Err(ProducerError::InvalidConfig(_))), "rate {rate}");
+        }
+        let result = ProducerConfig::builder(10).replay_window(0).build();
+        assert!(matches!(result, Err(ProducerError::InvalidConfig(_))));
+        ProducerConfig::builder(10).duplicate_rate(1.0).build().unwrap();
+    }
+
+    #[test]
+    fn duplicate_rate_zero_matches_default() {
+        let plain = Producer::new(ProducerConfig::builder(10).seed(42).build().unwrap());
+        let zero = Producer::new(
+            ProducerConfig::builder(10).seed(42).duplicate_rate(0.0).build().unwrap(),
+        );
+        for _ in 0..20 {
+            assert_eq!(plain.generate_batch(), zero.generate_batch());
+        }
+        assert_eq!(zero.stats().duplicates, 0);
+        assert_eq!(zero.stats().batches, 20);
+    }
+
+    #[test]
+    fn duplicate_rate_half_replays_exact_copies() {
+        let make = || {
+            let config = ProducerConfig::builder(10)
+                .seed(7)
+                .duplicate_rate(0.5)
+                .replay_window(8)
+                .build()
+                .unwrap();
+            Producer::new(config)
+        };
+        let producer = make();
+        let batches: Vec<Vec<Transaction>> = (0..20).map(|_| producer.generate_batch()).collect();
+
+        // A repeated id must carry exactly the fields of its first occurrence.
+        let mut first_seen: HashMap<TransactionId, Transaction> = HashMap::new();
+        let mut duplicates = 0u64;
+        for tx in batches.iter().flatten() {
+            match first_seen.get(&tx.id) {
+                Some(original) => {
+                    assert_eq!(tx, original, "duplicate must be an exact copy");
+                    duplicates += 1;
+                }
+                None => {
+                    first_seen.insert(tx.id, tx.clone());
+                }
+            }
+        }
+        let stats = producer.stats();
+        assert!(duplicates > 0, "rate 0.5 must produce duplicates");
+        assert_eq!(stats.duplicates, duplicates);
+        assert_eq!(stats.transactions, batches.iter().map(Vec::len).sum::<usize>() as u64);
+
+        // Deterministic under seed.
+        let again = make();
+        for batch in &batches {
+            assert_eq!(&again.generate_batch(), batch);
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Amount distribution and fraud injection
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn fraud_rate_zero_matches_default() {
+        let plain = Producer::new(ProducerConfig::builder(10).seed(42).build().unwrap());
+        let zero =
+            Producer::new(ProducerConfig::builder(10).seed(42).fraud_rate(0.0).build().unwrap());
+        for _ in 0..20 {
+            assert_eq!(plain.generate_batch(), zero.generate_batch());
+        }
+        assert_eq!(zero.stats().injected, 0);
+    }
+
+    #[test]
+    fn fraud_rate_one_injects_top_of_range_amounts() {
+        let producer =
+            Producer::new(ProducerConfig::builder(10).seed(3).fraud_rate(1.0).build().unwrap());
+        let batch = producer.generate_batch();
+        assert!(batch.iter().all(|tx| (9_000.0..=10_000.0).contains(&tx.amount)));
+        assert_eq!(producer.stats().injected, batch.len() as u64);
+    }
+
+    #[test]
+    fn exponential_amounts_stay_in_range_and_skew_low() {
+        let config = ProducerConfig::builder(100)
+            .seed(11)
+            .amount_distribution(AmountDistribution::Exponential { mean: 50.0 })
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let amounts: Vec<f64> =
+            (0..50).flat_map(|_| producer.generate_batch()).map(|tx| tx.amount).collect();
+        assert!(amounts.iter().all(|a| (0.01..=10_000.0).contains(a)));
+        let below_mean = amounts.iter().filter(|&&a| a < 50.0).count();
+        // P(X < mean) = 1 - 1/e ~ 63% for an exponential distribution.
+        assert!(below_mean * 2 > amounts.len(), "{below_mean} of {}", amounts.len());
+    }
+
+    #[test]
+    fn invalid_fraud_rate_or_mean_is_rejected() {
+        let err = ProducerConfig::builder(1).fraud_rate(1.5).build().unwrap_err();
+        assert!(matches!(err, ProducerError::InvalidConfig(ref e) if e.field == "fraud_rate"));
+        let err = ProducerConfig::builder(1)
+            .amount_distribution(AmountDistribution::Exponential { mean: 0.0 })
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(err, ProducerError::InvalidConfig(ref e) if e.field == "amount_distribution")
+        );
+    }
+
+    #[test]
+    fn currencies_follow_the_configured_weights() {
+        let config = ProducerConfig::builder(100)
+            .seed(5)
+            .currencies(vec![(Currency::Eur, 3), (Currency::Usd, 1), (Currency::Gbp, 0)])
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let txs: Vec<Transaction> = (0..20).flat_map(|_| producer.generate_batch()).collect();
+        let usd = txs.iter().filter(|tx| tx.currency == Currency::Usd).count();
+        assert!(txs.iter().all(|tx| tx.currency != Currency::Gbp), "zero weight never drawn");
+        // Expected share is 25%; allow a wide margin for the seeded sample.
+        assert!(usd * 8 > txs.len() && usd * 8 < txs.len() * 3, "{usd} of {}", txs.len());
+    }
+
+    #[test]
+    fn single_currency_keeps_the_seeded_sequence() {
+        let default = Producer::new(ProducerConfig::builder(10).seed(9).build().unwrap());
+        let gbp_only = ProducerConfig::builder(10).seed(9).currencies(vec![(Currency::Gbp, 5)]);
+        let gbp = Producer::new(gbp_only.build().unwrap());
+        let (a, b) = (default.generate_batch(), gbp.generate_batch());
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.amount.to_bits(), b.amount.to_bits());
+            assert_eq!(a.last_name, b.last_name);
+            assert_eq!((&a.currency, &b.currency), (&Currency::Eur, &Currency::Gbp));
+        }
+    }
+
+    #[test]
+    fn zero_total_currency_weight_is_rejected() {
+        for currencies in [vec![], vec![(Currency::Usd, 0)]] {
+            let err = ProducerConfig::builder(1).currencies(currencies).build().unwrap_err();
+            assert!(matches!(err, ProducerError::InvalidConfig(ref e) if e.field == "currencies"));
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Tenants
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn tenants_follow_the_normalized_weights() {
+        let config = ProducerConfig::builder(100)
+            .fixed_batch_size(true)
+            .seed(11)
+            .tenants(vec![("bank-a".to_owned(), 7.0), ("bank-b".to_owned(), 3.0)])
+            .build()
+            .unwrap();
+        assert_eq!(config.tenants, [("bank-a".to_owned(), 0.7), ("bank-b".to_owned(), 0.3)]);
+        let producer = Producer::new(config);
+        let txs: Vec<Transaction> = (0..100).flat_map(|_| producer.generate_batch()).collect();
+
+        assert_eq!(txs.len(), 10_000);
+        let a = txs.iter().filter(|tx| tx.tenant_id.as_deref() == Some("bank-a")).count();
+        let b = txs.iter().filter(|tx| tx.tenant_id.as_deref() == Some("bank-b")).count();
+        assert_eq!(a + b, txs.len(), "every transaction is tagged");
+        // 0.7 of 10_000 has a standard deviation near 46; 3% is over 6 sigma.
+        assert!((6_700..=7_300).contains(&a), "{a} bank-a of {}", txs.len());
+        let stats = producer.stats();
+        assert_eq!(stats.per_tenant.get("bank-a"), Some(&(a as u64)));
+        assert_eq!(stats.per_tenant.get("bank-b"), Some(&(b as u64)));
+        assert!(stats.to_string().ends_with(&format!("\n  tenant bank-b: {b} transactions")));
+    }
+
+    #[test]
+    fn no_tenants_leaves_transactions_untagged() {
+        let producer = Producer::new(ProducerConfig::builder(10).seed(3).build().unwrap());
+        let batch = producer.generate_batch();
+
+        assert!(batch.iter().all(|tx| tx.tenant_id.is_none()));
+        assert!(producer.stats().per_tenant.is_empty());
+        assert!(!producer.stats().to_string().contains("tenant"));
+    }
+
+    #[test]
+    fn single_tenant_keeps_the_seeded_sequence() {
+        let default = Producer::new(ProducerConfig::builder(10).seed(9).build().unwrap());
+        let one = ProducerConfig::builder(10).seed(9).tenants(vec![("solo".to_owned(), 2.0)]);
+        let one = Producer::new(one.build().unwrap());
+        let (a, b) = (default.generate_batch(), one.generate_batch());
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!((a.id, a.amount.to_bits()), (b.id, b.amount.to_bits()));
+            assert_eq!(b.tenant_id.as_deref(), Some("solo"));
+        }
+    }
+
+    #[test]
+    fn invalid_tenants_are_rejected() {
+        let named = |weights: &[f64]| -> Vec<(String, f64)> {
+            weights.iter().enumerate().map(|(i, w)| (format!("t{i}"), *w)).collect()
+        };
+        let cases = [
+            vec![],
+            named(&[1.0, -0.5]),
+            named(&[f64::NAN]),
+            named(&[f64::INFINITY, 1.0]),
+            named(&[0.0, 0.0]),
+            vec![(String::new(), 1.0)],
+            vec![("a".to_owned(), 1.0), ("a".to_owned(), 2.0)],
+        ];
+        for tenants in cases {
+            let err = ProducerConfig::builder(1).tenants(tenants.clone()).build().unwrap_err();
+            assert!(
+                matches!(err, ProducerError::InvalidConfig(ref e) if e.field == "tenants"),
+                "{tenants:?} -> {err}"
+            );
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Pregeneration
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn pregenerated_dataset_matches_generate_batch_sequence() {
+        let build = |pregenerate: Option<usize>| {
+            let builder = ProducerConfig::builder(10).seed(7).duplicate_rate(0.2).fraud_rate(0.1);
+            let builder = match pregenerate {
+                Some(total) => builder.pregenerate(total),
+                None => builder,
+            };
+            Producer::new(builder.build().unwrap())
+        };
+        let reference = build(None);
+        let mut expected = Vec::new();
+        while expected.len() < 95 {
+            expected.extend(reference.generate_batch());
+        }
+        expected.truncate(95);
+
+        let producer = build(Some(95));
+        assert_eq!(producer.dataset(), expected.as_slice());
+        let stats = producer.stats();
+        assert_eq!((stats.batches, stats.transactions), (0, 0), "streaming not started");
+        assert!(stats.injected > 0);
+        assert!(!producer.is_exhausted());
+        assert!(build(None).dataset().is_empty());
+    }
+
+    #[tokio::test]
+    async fn run_streams_pregenerated_batches_until_exhausted() {
+        let (events, mut rx) = tokio::sync::broadcast::channel(16);
+        let config = ProducerConfig::builder(10)
+            .seed(3)
+            .pregenerate(25)
+            .poll_interval1(Duration::ZERO)
+            .events(events)
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let buffer = TestBuffer::new();
+
+        let reason = producer.run(&buffer).await.unwrap();
+
+        assert_eq!(reason, StopReason::Exhausted { iterations: 3 });
+        let sizes: Vec<usize> = buffer.batches.borrow().iter().map(Vec::len).collect();
+        assert_eq!(sizes, [10, 10, 5]);
+        let streamed: Vec<Transaction> = buffer.batches.borrow().concat();
+        assert_eq!(streamed, producer.dataset());
+        assert!(producer.is_exhausted());
+        assert_eq!((producer.stats().batches, producer.stats().transactions), (3, 25));
+        for _ in 0..3 {
+            assert!(matches!(rx.try_recv().unwrap(), PipelineEvent::BatchProduced { .. }));
+        }
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            PipelineEvent::StageStopped {
+                stage: Stage::Producer,
+                reason: "dataset exhausted".to_owned(),
+            }
+        );
+
+        // Exhausted: produce_once writes nothing.
+        producer.produce_once(&buffer).await.unwrap();
+        assert_eq!(buffer.batch_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn pregenerated_dataset_is_reusable() {
+        let config = ProducerConfig::builder(4).seed(5).pregenerate(10).build().unwrap();
+        let first = Producer::new(config);
+        for _ in 0..2 {
+            let config = ProducerConfig::builder(4)
+                .iterations(2)
+                .poll_interval1(Duration::ZERO)
+                .build()
+                .unwrap();
+            let producer = Producer::pregenerated(config, first.dataset().to_vec());
+            let buffer = TestBuffer::new();
+            let reason = producer.run(&buffer).await.unwrap();
+            assert_eq!(reason, StopReason::IterationLimit { iterations: 2 });
+            assert_eq!(buffer.total_tx_count(), 8);
+            assert_eq!(buffer.batches.borrow().concat(), first.dataset()[..8]);
+        }
+        let empty = ProducerConfig::builder(4).poll_interval1(Duration::ZERO).build().unwrap();
+        let producer = Producer::pregenerated(empty, Vec::new());
+        let buffer = TestBuffer::new();
+        let reason = producer.run(&buffer).await.unwrap();
+        assert_eq!(reason, StopReason::Exhausted { iterations: 0 });
+        assert_eq!(buffer.batch_count(), 0);
+    }
+
+    // ------------------------------------------------------------------
+    // Fixed batch size
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn fixed_batch_size_produces_exactly_iterations_times_max() {
+        let config = ProducerConfig::builder(10)
+            .seed(7)
+            .iterations(10)
+            .poll_interval1(Duration::ZERO)
+            .fixed_batch_size(true)
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let buffer = TestBuffer::new();
+
+        let reason = producer.run(&buffer).await.unwrap();
+
+        assert_eq!(reason, StopReason::IterationLimit { iterations: 10 });
+        assert!(buffer.batches.borrow().iter().all(|b| b.len() == 10));
+        assert_eq!(buffer.total_tx_count(), 100);
+    }
+
+    #[test]
+    fn fixed_batch_size_still_randomizes_content() {
+        let fixed = |seed| {
+            let config =
+                ProducerConfig::builder(10).seed(seed).fixed_batch_size(true).build().unwrap();
+            Producer::new(config).generate_batch()
+        };
+        let (a, b) = (fixed(1), fixed(2));
+        assert_eq!((a.len(), b.len()), (10, 10));
+        assert_ne!(a, b, "the seed must still drive amounts, names and ids");
+        assert_eq!(fixed(1), a);
+    }
+
+    #[test]
+    fn fixed_batch_size_false_matches_default() {
+        let plain = Producer::new(ProducerConfig::builder(10).seed(42).build().unwrap());
+        let unfixed = Producer::new(
+            ProducerConfig::builder(10).seed(42).fixed_batch_size(false).build().unwrap(),
+        );
+        for _ in 0..20 {
+            assert_eq!(plain.generate_batch(), unfixed.generate_batch());
+        }
+        let debug = format!("{:?}", ProducerConfig::builder(10).fixed_batch_size(true).build());
+        assert!(debug.contains("fixed_batch_size: true"), "{debug}");
+    }
+
+    // ------------------------------------------------------------------
+    // Pipeline events
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn run_publishes_events() {
+        let (events, mut rx) = tokio::sync::broadcast::channel(16);
+        let config = ProducerConfig::builder(10)
+            .seed(42)
+            .iterations(3)
+            .poll_interval1(Duration::ZERO)
+            .events(events)
+            .build()
+            .unwrap();
+        let producer = Producer::new(config);
+        let buffer = TestBuffer::new();
+
+        producer.run(&buffer).await.unwrap();
+
+        for batch in buffer.batches.borrow().iter() {
+            assert_eq!(rx.try_recv().unwrap(), PipelineEvent::BatchProduced { size: batch.len() });
+        }
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            PipelineEvent::StageStopped {
+                stage: Stage::Producer,
+                reason: "iteration limit reached".to_owned(),
+            }
+        );
+        rx.try_recv().unwrap_err();
+    }
+
+    // ------------------------------------------------------------------
+    // Config summary
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn summary_reports_the_builder_values() {
+        let config = ProducerConfig::builder(25)
+            .poll_interval1(Duration::from_millis(5))
+            .iterations(7)
+            .seed(42)
+            .id_strategy(IdStrategy::Sequential { start: 100 })
+            .duplicate_rate(0.25)
+            .amount_distribution(AmountDistribution::Exponential { mean: 50.0 })
+            .currencies(vec![(Currency::Eur, 3), (Currency::Usd, 1)])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.summary().to_string(),
+            "producer: preset=none n1_max=25 fixed_batch_size=false poll_interval1=5ms \
+             iterations=7 seed=42 start_iteration=0 id_strategy=sequential from 100 \
+             duplicate_rate=0.25 replay_window=64 amount_distribution=exponential mean 50 \
+             fraud_rate=0 pregenerate=none currencies=EUR:3,USD:1 tenants=none write_retries=3"
+        );
+        let producer = Producer::new(config);
+        assert_eq!(producer.config().summary().get("seed"), Some("42"));
+    }
 }
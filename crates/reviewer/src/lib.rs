@@ -0,0 +1,600 @@
+// Rust guideline compliant 2026-02-27
+
+//! Reviewer component -- simulated review worker that reads persisted pending
+//! transactions through the `StorageRead` port and records `actual_fraud`
+//! verdicts through the `Review` port.
+//!
+//! Entry points: [`Reviewer::review_once`], [`Reviewer::run`], [`Reviewer::stats`].
+//! Configuration via [`ReviewerConfig::builder`].
+
+use domain::{ConfigError, PendingTransaction, Review, ReviewOutcome, StorageError, StorageRead};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::time::Duration;
+
+/// `tracing` target of every Reviewer event (see [`domain::obs::target`]).
+const TARGET: &str = domain::obs::target::REVIEWER;
+
+// ---------------------------------------------------------------------------
+// ReviewerError
+// ---------------------------------------------------------------------------
+
+/// Errors that can occur during review.
+///
+/// `Display` gives only this layer's context; the wrapped error is the
+/// [`source`](std::error::Error::source).
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ReviewerError {
+    /// The supplied configuration is invalid.
+    #[error("invalid reviewer configuration")]
+    InvalidConfig(#[from] ConfigError),
+    /// Reading a page from storage failed.
+    #[error("storage read error")]
+    Read(#[source] StorageError),
+    /// Recording review outcomes failed.
+    #[error("storage review error")]
+    Write(#[source] StorageError),
+}
+
+impl ReviewerError {
+    /// Whether restarting the run loop after a backoff may succeed.
+    ///
+    /// - `InvalidConfig`: fatal.
+    /// - `Read` / `Write`: retryable for `Unavailable`, fatal for `CapacityExceeded`.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::InvalidConfig(_) => false,
+            Self::Read(e) | Self::Write(e) => e.is_retryable(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ReviewerConfig + builder
+// ---------------------------------------------------------------------------
+
+/// Extracts a known ground-truth label from a record, if it carries one.
+pub type GroundTruth = fn(&PendingTransaction) -> Option<bool>;
+
+/// Runtime configuration for a [`Reviewer`].
+///
+/// Construct via [`ReviewerConfig::builder`].
+#[derive(Debug)]
+pub struct ReviewerConfig {
+    /// Maximum number of records fetched from storage per iteration.
+    pub batch_size: usize,
+    /// Delay between successive iterations.
+    pub poll_interval: Duration,
+    /// Optional upper bound on the number of iterations. `None` means until idle.
+    pub iterations: Option<u64>,
+    /// Optional RNG seed for reproducible verdicts. `None` seeds from the OS.
+    pub seed: Option<u64>,
+    /// Probability that a simulated verdict agrees with the model prediction.
+    pub agreement_rate: f64,
+    /// Optional ground-truth oracle, consulted before the simulated verdict.
+    pub ground_truth: Option<GroundTruth>,
+    /// Consecutive empty pages after which [`Reviewer::run`] stops.
+    pub idle_polls: usize,
+}
+
+/// Builder for [`ReviewerConfig`].
+///
+/// Obtain via [`ReviewerConfig::builder`]; finalize with [`build`](Self::build).
+#[derive(Debug)]
+pub struct ReviewerConfigBuilder {
+    batch_size: usize,
+    poll_interval: Duration,
+    iterations: Option<u64>,
+    seed: Option<u64>,
+    agreement_rate: f64,
+    ground_truth: Option<GroundTruth>,
+    idle_polls: usize,
+}
+
+impl ReviewerConfig {
+    /// Create a builder. `batch_size` is the only required parameter.
+    ///
+    /// Default values: `poll_interval = 100 ms`, `iterations = None`, `seed = None`,
+    /// `agreement_rate = 0.9`, `ground_truth = None`, `idle_polls = 3`.
+    #[must_use]
+    pub fn builder(batch_size: usize) -> ReviewerConfigBuilder {
+        ReviewerConfigBuilder {
+            batch_size,
+            // 100 ms chosen as a reasonable demo cadence; lower for tests.
+            poll_interval: Duration::from_millis(100),
+            iterations: None,
+            seed: None,
+            // A decent model: reviewers confirm nine predictions out of ten.
+            agreement_rate: 0.9,
+            ground_truth: None,
+            // A few polls give a concurrent logger a chance to land a last batch.
+            idle_polls: 3,
+        }
+    }
+}
+
+impl ReviewerConfigBuilder {
+    /// Override the inter-iteration delay.
+    #[must_use]
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Set a finite iteration count. Without this the reviewer runs until
+    /// storage has been idle for `idle_polls` iterations.
+    #[must_use]
+    pub fn iterations(mut self, n: u64) -> Self {
+        self.iterations = Some(n);
+        self
+    }
+
+    /// Fix the RNG seed for deterministic verdicts (useful in tests).
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Probability in `[0, 1]` that a simulated verdict confirms the model
+    /// prediction; otherwise the verdict contradicts it.
+    #[must_use]
+    pub fn agreement_rate(mut self, rate: f64) -> Self {
+        self.agreement_rate = rate;
+        self
+    }
+
+    /// Use `oracle` as the source of truth; records for which it returns
+    /// `None` fall back to the simulated verdict.
+    #[must_use]
+    pub fn ground_truth(mut self, oracle: GroundTruth) -> Self {
+        self.ground_truth = Some(oracle);
+        self
+    }
+
+    /// Number of consecutive empty pages after which [`Reviewer::run`] stops.
+    #[must_use]
+    pub fn idle_polls(mut self, k: usize) -> Self {
+        self.idle_polls = k;
+        self
+    }
+
+    /// Validate and build the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReviewerError::InvalidConfig`] when `batch_size`, `iterations`
+    /// or `idle_polls` is zero, or `agreement_rate` is outside `[0, 1]`.
+    #[must_use = "the Result must be checked; use ? or unwrap"]
+    pub fn build(self) -> Result<ReviewerConfig, ReviewerError> {
+        if self.batch_size == 0 {
+            return Err(ConfigError::new("batch_size", self.batch_size, "must be >= 1").into());
+        }
+        if self.iterations == Some(0) {
+            return Err(ConfigError::new("iterations", 0, "must be >= 1").into());
+        }
+        // contains() is false for NaN, so NaN is rejected too.
+        if !(0.0..=1.0).contains(&self.agreement_rate) {
+            return Err(
+                ConfigError::new("agreement_rate", self.agreement_rate, "must be in [0, 1]").into(),
+            );
+        }
+        if self.idle_polls == 0 {
+            return Err(ConfigError::new("idle_polls", self.idle_polls, "must be >= 1").into());
+        }
+        Ok(ReviewerConfig {
+            batch_size: self.batch_size,
+            poll_interval: self.poll_interval,
+            iterations: self.iterations,
+            seed: self.seed,
+            agreement_rate: self.agreement_rate,
+            ground_truth: self.ground_truth,
+            idle_polls: self.idle_polls,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ReviewerStats
+// ---------------------------------------------------------------------------
+
+/// Cumulative counters over the lifetime of a [`Reviewer`].
+///
+/// Obtain a snapshot via [`Reviewer::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReviewerStats {
+    /// Number of verdicts recorded.
+    pub reviewed: u64,
+    /// Verdicts that confirmed the model prediction.
+    pub agreed: u64,
+    /// Verdicts labelled as actual fraud.
+    pub confirmed_fraud: u64,
+}
+
+impl fmt::Display for ReviewerStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "reviewer: {} reviewed, {} agreed with the model, {} confirmed fraud",
+            self.reviewed, self.agreed, self.confirmed_fraud
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reviewer
+// ---------------------------------------------------------------------------
+
+/// Pages through persisted pending transactions and records a verdict for
+/// every record not yet reviewed.
+///
+/// Generic over `S: StorageRead + Review` for zero-cost static dispatch.
+/// Holds no concrete adapter references -- dependencies injected per call.
+#[derive(Debug)]
+pub struct Reviewer {
+    config: ReviewerConfig,
+    /// Interior mutability required because all public methods take `&self`.
+    rng: RefCell<StdRng>,
+    /// Position of the last record fetched; the next page starts after it.
+    cursor: Cell<u64>,
+    /// Cumulative counters; updated from each recorded page.
+    stats: Cell<ReviewerStats>,
+}
+
+impl Reviewer {
+    /// Create a new reviewer from `config`.
+    ///
+    /// Seeds the RNG from `config.seed` if set, otherwise from the OS.
+    #[must_use]
+    pub fn new(config: ReviewerConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        Self {
+            config,
+            rng: RefCell::new(rng),
+            cursor: Cell::new(0),
+            stats: Cell::new(ReviewerStats::default()),
+        }
+    }
+
+    /// Return a snapshot of the cumulative counters.
+    #[must_use]
+    pub fn stats(&self) -> ReviewerStats {
+        self.stats.get()
+    }
+
+    /// Decide `actual_fraud` for `pending`.
+    ///
+    /// The ground-truth oracle wins when it knows the answer; otherwise the
+    /// verdict agrees with the prediction with probability `agreement_rate`.
+    fn decide(&self, pending: &PendingTransaction) -> bool {
+        if let Some(truth) = self.config.ground_truth.and_then(|oracle| oracle(pending)) {
+            return truth;
+        }
+        let predicted = pending.inferred_transaction.predicted_fraud;
+        let agrees = self.rng.borrow_mut().random_bool(self.config.agreement_rate);
+        if agrees { predicted } else { !predicted }
+    }
+
+    /// Fetch the next page after the cursor and record a verdict for every
+    /// unreviewed record in it.
+    ///
+    /// Returns the number of records fetched; `0` means storage had nothing new.
+    /// Already-reviewed records advance the cursor but are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReviewerError::Read`] when the page cannot be fetched, or
+    /// [`ReviewerError::Write`] when the verdicts cannot be recorded; the
+    /// cursor then stays put so the page is retried.
+    #[tracing::instrument(target = "pipeline::reviewer", skip_all, level = "debug")]
+    pub async fn review_once<S: StorageRead + Review>(
+        &self,
+        storage: &S,
+    ) -> Result<usize, ReviewerError> {
+        let page = storage
+            .read_page(self.cursor.get(), self.config.batch_size)
+            .await
+            .map_err(ReviewerError::Read)?;
+        let Some(last) = page.last() else {
+            return Ok(0);
+        };
+
+        let mut stats = self.stats.get();
+        let outcomes: Vec<ReviewOutcome> = page
+            .iter()
+            .filter(|stored| !stored.pending.is_reviewed)
+            .map(|stored| {
+                let actual_fraud = self.decide(&stored.pending);
+                stats.reviewed += 1;
+                stats.agreed +=
+                    u64::from(actual_fraud == stored.pending.inferred_transaction.predicted_fraud);
+                stats.confirmed_fraud += u64::from(actual_fraud);
+                ReviewOutcome { id: stored.pending.id(), actual_fraud }
+            })
+            .collect();
+        if !outcomes.is_empty() {
+            storage.record_reviews(&outcomes).await.map_err(ReviewerError::Write)?;
+        }
+
+        self.cursor.set(last.position);
+        self.stats.set(stats);
+        tracing::debug!(
+            target: TARGET,
+            fetched = page.len(),
+            reviewed = outcomes.len(),
+            "reviewer.page.reviewed"
+        );
+        Ok(page.len())
+    }
+
+    /// Run the review loop until stopped.
+    ///
+    /// Calls [`review_once`](Self::review_once) repeatedly, sleeping
+    /// `config.poll_interval` between iterations. Stops cleanly when:
+    /// - `config.idle_polls` consecutive iterations fetched nothing (returns `Ok(())`), or
+    /// - `config.iterations` iterations have run (returns `Ok(())`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReviewerError`] for any storage error.
+    #[tracing::instrument(target = "pipeline::reviewer", name = "reviewer.run", skip_all)]
+    pub async fn run<S: StorageRead + Review>(&self, storage: &S) -> Result<(), ReviewerError> {
+        let mut count = 0u64;
+        let mut idle = 0usize;
+        loop {
+            if self.review_once(storage).await? == 0 {
+                idle += 1;
+                if idle >= self.config.idle_polls {
+                    tracing::info!(target: TARGET, count, "reviewer.run.stopped: storage idle");
+                    return Ok(());
+                }
+            } else {
+                idle = 0;
+            }
+
+            count += 1;
+            if let Some(max) = self.config.iterations
+                && count >= max
+            {
+                tracing::info!(target: TARGET, "reviewer.run.stopped: iteration limit reached");
+                return Ok(());
+            }
+
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::{Currency, InferredTransaction, StoredTransaction, Transaction, TransactionId};
+
+    // ------------------------------------------------------------------
+    // Mock adapters
+    // ------------------------------------------------------------------
+
+    fn make_pending(predicted_fraud: bool) -> PendingTransaction {
+        PendingTransaction::new(InferredTransaction {
+            transaction: Transaction {
+                id: TransactionId::new_v4(),
+                amount: 1.00_f64,
+                last_name: "Test".to_owned(),
+                currency: Currency::Eur,
+                tenant_id: None,
+            },
+            predicted_fraud,
+            model_name: "DEMO".to_owned(),
+            model_version: "4".to_owned(),
+            delivery_attempts: 1,
+            cached: false,
+        })
+    }
+
+    /// Vec-backed storage; `position` is the 1-based index. Counts page reads.
+    #[derive(Default)]
+    struct MockStorage {
+        items: RefCell<Vec<PendingTransaction>>,
+        reads: Cell<usize>,
+        fail_review: Option<StorageError>,
+    }
+
+    impl MockStorage {
+        fn with_items(items: Vec<PendingTransaction>) -> Self {
+            Self { items: RefCell::new(items), ..Self::default() }
+        }
+    }
+
+    impl StorageRead for MockStorage {
+        async fn read_page(
+            &self,
+            after: u64,
+            limit: usize,
+        ) -> Result<Vec<StoredTransaction>, StorageError> {
+            self.reads.set(self.reads.get() + 1);
+            Ok(self
+                .items
+                .borrow()
+                .iter()
+                .zip(1u64..)
+                .skip(usize::try_from(after).unwrap())
+                .take(limit)
+                .map(|(pending, position)| StoredTransaction { position, pending: pending.clone() })
+                .collect())
+        }
+    }
+
+    impl Review for MockStorage {
+        async fn record_reviews(&self, outcomes: &[ReviewOutcome]) -> Result<(), StorageError> {
+            if let Some(e) = &self.fail_review {
+                return Err(e.clone());
+            }
+            for o in outcomes {
+                for pt in self.items.borrow_mut().iter_mut().filter(|pt| pt.id() == o.id) {
+                    pt.is_reviewed = true;
+                    pt.actual_fraud = Some(o.actual_fraud);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn make_reviewer(batch_size: usize, seed: u64, agreement_rate: f64) -> Reviewer {
+        Reviewer::new(
+            ReviewerConfig::builder(batch_size)
+                .seed(seed)
+                .poll_interval(Duration::ZERO)
+                .agreement_rate(agreement_rate)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    // ------------------------------------------------------------------
+    // Configuration
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn config_errors_name_the_field() {
+        let field = |r: Result<ReviewerConfig, ReviewerError>| match r {
+            Err(ReviewerError::InvalidConfig(e)) => e.field,
+            other => panic!("expected InvalidConfig, got {other:?}"),
+        };
+        assert_eq!(field(ReviewerConfig::builder(0).build()), "batch_size");
+        assert_eq!(field(ReviewerConfig::builder(5).iterations(0).build()), "iterations");
+        assert_eq!(field(ReviewerConfig::builder(5).agreement_rate(1.5).build()), "agreement_rate");
+        assert_eq!(field(ReviewerConfig::builder(5).agreement_rate(f64::NAN).build()), "agreement_rate");
+        assert_eq!(field(ReviewerConfig::builder(5).idle_polls(0).build()), "idle_polls");
+    }
+
+    #[test]
+    fn error_retryable_classification() {
+        assert!(ReviewerError::Read(StorageError::Unavailable).is_retryable());
+        assert!(ReviewerError::Write(StorageError::Unavailable).is_retryable());
+        assert!(!ReviewerError::Write(StorageError::CapacityExceeded { capacity: 1, remaining: 0 }).is_retryable());
+        assert!(!ReviewerError::InvalidConfig(ConfigError::new("batch_size", 0, "must be >= 1"))
+            .is_retryable());
+    }
+
+    // ------------------------------------------------------------------
+    // Review loop
+    // ------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn run_reviews_every_row() {
+        let storage = MockStorage::with_items((0..25).map(|i| make_pending(i % 4 == 0)).collect());
+        let reviewer = make_reviewer(7, 1, 0.9);
+
+        reviewer.run(&storage).await.unwrap();
+
+        assert!(storage.items.borrow().iter().all(|pt| pt.is_reviewed && pt.actual_fraud.is_some()));
+        assert_eq!(reviewer.stats().reviewed, 25);
+    }
+
+    #[tokio::test]
+    async fn agreement_rate_is_honored_under_seed() {
+        let storage = MockStorage::with_items((0..2_000).map(|i| make_pending(i % 2 == 0)).collect());
+        let reviewer = make_reviewer(100, 42, 0.8);
+
+        reviewer.run(&storage).await.unwrap();
+
+        let agreed = storage
+            .items
+            .borrow()
+            .iter()
+            .filter(|pt| pt.actual_fraud == Some(pt.inferred_transaction.predicted_fraud))
+            .count();
+        // Binomial(2000, 0.8): sd ~ 18, so +/- 80 is over 4 sd.
+        assert!((1_520..=1_680).contains(&agreed), "agreed = {agreed}");
+        assert_eq!(reviewer.stats().agreed, u64::try_from(agreed).unwrap());
+    }
+
+    #[tokio::test]
+    async fn agreement_extremes_are_exact() {
+        let storage = MockStorage::with_items((0..50).map(|i| make_pending(i % 3 == 0)).collect());
+        make_reviewer(10, 3, 1.0).run(&storage).await.unwrap();
+        assert!(storage
+            .items
+            .borrow()
+            .iter()
+            .all(|pt| pt.actual_fraud == Some(pt.inferred_transaction.predicted_fraud)));
+
+        let storage = MockStorage::with_items((0..50).map(|i| make_pending(i % 3 == 0)).collect());
+        make_reviewer(10, 3, 0.0).run(&storage).await.unwrap();
+        assert!(storage
+            .items
+            .borrow()
+            .iter()
+            .all(|pt| pt.actual_fraud == Some(!pt.inferred_transaction.predicted_fraud)));
+    }
+
+    #[tokio::test]
+    async fn ground_truth_overrides_simulation() {
+        let storage = MockStorage::with_items((0..10).map(|_| make_pending(false)).collect());
+        let reviewer = Reviewer::new(
+            ReviewerConfig::builder(4)
+                .poll_interval(Duration::ZERO)
+                .agreement_rate(1.0)
+                .ground_truth(|_| Some(true))
+                .build()
+                .unwrap(),
+        );
+
+        reviewer.run(&storage).await.unwrap();
+
+        assert!(storage.items.borrow().iter().all(|pt| pt.actual_fraud == Some(true)));
+        assert_eq!(reviewer.stats().agreed, 0);
+    }
+
+    #[tokio::test]
+    async fn already_reviewed_rows_are_left_untouched() {
+        let mut done = make_pending(true);
+        done.is_reviewed = true;
+        done.actual_fraud = Some(false);
+        let storage = MockStorage::with_items(vec![done.clone(), make_pending(false)]);
+        let reviewer = make_reviewer(10, 1, 1.0);
+
+        assert_eq!(reviewer.review_once(&storage).await.unwrap(), 2);
+
+        assert_eq!(storage.items.borrow()[0], done);
+        assert_eq!(reviewer.stats().reviewed, 1);
+    }
+
+    #[tokio::test]
+    async fn stops_after_idle_polls() {
+        let storage = MockStorage::with_items((0..5).map(|_| make_pending(false)).collect());
+        let reviewer = Reviewer::new(
+            ReviewerConfig::builder(5).poll_interval(Duration::ZERO).idle_polls(4).build().unwrap(),
+        );
+
+        reviewer.run(&storage).await.unwrap();
+
+        // One productive page, then four empty ones.
+        assert_eq!(storage.reads.get(), 5);
+    }
+
+    #[tokio::test]
+    async fn failed_review_keeps_cursor() {
+        let storage = MockStorage {
+            fail_review: Some(StorageError::Unavailable),
+            ..MockStorage::with_items(vec![make_pending(true)])
+        };
+        let reviewer = make_reviewer(10, 1, 0.5);
+
+        let result = reviewer.review_once(&storage).await;
+
+        assert!(matches!(result, Err(ReviewerError::Write(StorageError::Unavailable))));
+        assert_eq!(reviewer.stats(), ReviewerStats::default());
+        assert_eq!(reviewer.cursor.get(), 0);
+    }
+}
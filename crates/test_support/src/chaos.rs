@@ -0,0 +1,486 @@
+// Rust guideline compliant 2026-02-27
+
+//! Chaos decorator for buffers: seeded delays, reordering and duplication.
+//!
+//! The stages are only ever tested over FIFO buffers. [`ChaosBuffer`] wraps
+//! any buffer implementing both sides of Buffer1 or Buffer2 and, per write,
+//! draws from a seeded [`ChaosScript`] whether to delay the batch by extra
+//! yields, hold it back behind a few later batches, or deliver it twice.
+//! Every injected [`Anomaly`] is recorded, so a failing assertion can print
+//! [`ChaosBuffer::anomaly_log`] and the run can be replayed from its seed.
+//!
+//! What holds under chaos (see the tests below): without duplication, every
+//! transaction is processed and persisted exactly once and the digests
+//! match, but storage order may differ from production order; a duplicated
+//! batch is persisted twice and shows up as a digest mismatch with extra
+//! transactions in the pipeline report.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+
+use domain::{
+    Buffer1, Buffer1Read, Buffer2, Buffer2Read, BufferDepth, InferredTransaction, ReadError,
+    Transaction, WriteError,
+};
+use pipeline::Close;
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng as _};
+
+// ---------------------------------------------------------------------------
+// ChaosScript
+// ---------------------------------------------------------------------------
+
+/// Seeded probabilities of each anomaly a [`ChaosBuffer`] may inject per write.
+///
+/// All rates default to 0: a fresh script injects nothing. Each write makes
+/// the same three draws whatever the rates, so enabling one anomaly does not
+/// shift when the others fire.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChaosScript {
+    seed: u64,
+    delay: f64,
+    max_yields: u32,
+    reorder: f64,
+    window: u64,
+    duplicate: f64,
+}
+
+impl ChaosScript {
+    /// A script injecting nothing, drawing from a `StdRng` seeded with `seed`.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self { seed, delay: 0.0, max_yields: 1, reorder: 0.0, window: 1, duplicate: 0.0 }
+    }
+
+    /// Delay a write by `1..=max_yields` extra yields with probability `rate`.
+    #[must_use]
+    pub fn delay(mut self, rate: f64, max_yields: u32) -> Self {
+        self.delay = rate;
+        self.max_yields = max_yields.max(1);
+        self
+    }
+
+    /// With probability `rate`, hold a batch back until `window` later
+    /// batches have been written (at least 1), then deliver it after them.
+    #[must_use]
+    pub fn reorder(mut self, rate: f64, window: u64) -> Self {
+        self.reorder = rate;
+        self.window = window.max(1);
+        self
+    }
+
+    /// Deliver a batch twice with probability `rate`.
+    #[must_use]
+    pub fn duplicate(mut self, rate: f64) -> Self {
+        self.duplicate = rate;
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Anomaly
+// ---------------------------------------------------------------------------
+
+/// One anomaly a [`ChaosBuffer`] injected; batches are numbered from 1 in
+/// write order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anomaly {
+    /// The write yielded this many extra times before reaching the buffer.
+    Delayed {
+        /// Write number.
+        batch: u64,
+        /// Extra yields.
+        yields: u32,
+    },
+    /// The batch was held back behind `window` later batches.
+    Reordered {
+        /// Write number.
+        batch: u64,
+        /// Later batches delivered first.
+        window: u64,
+    },
+    /// The batch was delivered twice.
+    Duplicated {
+        /// Write number.
+        batch: u64,
+        /// Items delivered a second time.
+        size: usize,
+    },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Delayed { batch, yields } => {
+                write!(f, "batch#{batch} delayed by {yields} yields")
+            }
+            Self::Reordered { batch, window } => {
+                write!(f, "batch#{batch} held back behind {window} batches")
+            }
+            Self::Duplicated { batch, size } => {
+                write!(f, "batch#{batch} duplicated ({size} items)")
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ChaosBuffer
+// ---------------------------------------------------------------------------
+
+/// A batch held back by a reorder, released once `release_after` is written.
+#[derive(Debug)]
+struct Held<T> {
+    release_after: u64,
+    items: Vec<T>,
+}
+
+/// Decorator injecting the anomalies of a [`ChaosScript`] into the writes of
+/// any Buffer1 or Buffer2 adapter, and recording them.
+///
+/// `T` is the item type, inferred from the ports used. Held-back batches are
+/// also handed to readers once the wrapped buffer reports closed, so closing
+/// never loses them. Implements [`Close`] and `BufferDepth` when the wrapped
+/// buffer does.
+#[derive(Debug)]
+pub struct ChaosBuffer<B, T> {
+    inner: B,
+    label: &'static str,
+    script: ChaosScript,
+    rng: RefCell<StdRng>,
+    writes: Cell<u64>,
+    held: RefCell<VecDeque<Held<T>>>,
+    anomalies: RefCell<Vec<Anomaly>>,
+}
+
+impl<B, T: Clone> ChaosBuffer<B, T> {
+    /// Wrap `inner`; the anomaly log lines are prefixed with `label`.
+    #[must_use]
+    pub fn new(inner: B, label: &'static str, script: ChaosScript) -> Self {
+        Self {
+            inner,
+            label,
+            script,
+            rng: RefCell::new(StdRng::seed_from_u64(script.seed)),
+            writes: Cell::new(0),
+            held: RefCell::new(VecDeque::new()),
+            anomalies: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The wrapped buffer.
+    #[must_use]
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Every anomaly injected so far, in order.
+    #[must_use]
+    pub fn anomalies(&self) -> Vec<Anomaly> {
+        self.anomalies.borrow().clone()
+    }
+
+    /// The anomalies as `"{label}: {anomaly}"` lines, with the seed, for
+    /// assertion messages: `assert!(ok, "{}", buffer.anomaly_log())`.
+    #[must_use]
+    pub fn anomaly_log(&self) -> String {
+        let header = format!("{} chaos (seed {}):", self.label, self.script.seed);
+        let anomalies = self.anomalies.borrow();
+        let lines = anomalies.iter().map(|anomaly| format!("  {}: {anomaly}", self.label));
+        std::iter::once(header).chain(lines).collect::<Vec<_>>().join("\n")
+    }
+
+    fn record(&self, anomaly: Anomaly) {
+        self.anomalies.borrow_mut().push(anomaly);
+    }
+
+    /// Apply the script to the next write of `batch`, forwarding what is due
+    /// through `write`: the batch itself unless held back, a duplicate first
+    /// if drawn, then the held batches whose window has passed.
+    async fn chaos_write(
+        &self,
+        batch: Vec<T>,
+        write: impl AsyncFn(Vec<T>) -> Result<(), WriteError>,
+    ) -> Result<(), WriteError> {
+        let n = self.writes.get() + 1;
+        self.writes.set(n);
+        let (delay, reorder, duplicate, yields) = {
+            let mut rng = self.rng.borrow_mut();
+            let draws = (rng.random::<f64>(), rng.random::<f64>(), rng.random::<f64>());
+            let yields = rng.random_range(1..=self.script.max_yields);
+            (
+                draws.0 < self.script.delay,
+                draws.1 < self.script.reorder,
+                draws.2 < self.script.duplicate,
+                yields,
+            )
+        };
+        if delay {
+            self.record(Anomaly::Delayed { batch: n, yields });
+            for _ in 0..yields {
+                tokio::task::yield_now().await;
+            }
+        }
+        if duplicate && !batch.is_empty() {
+            self.record(Anomaly::Duplicated { batch: n, size: batch.len() });
+            write(batch.clone()).await?;
+        }
+        if reorder && !batch.is_empty() {
+            let window = self.script.window;
+            self.record(Anomaly::Reordered { batch: n, window });
+            self.held.borrow_mut().push_back(Held { release_after: n + window, items: batch });
+        } else {
+            write(batch).await?;
+        }
+        loop {
+            // Pop before the await so the borrow is not held across it.
+            let due = {
+                let mut held = self.held.borrow_mut();
+                match held.front() {
+                    Some(front) if front.release_after <= n => held.pop_front(),
+                    _ => None,
+                }
+            };
+            let Some(due) = due else { return Ok(()) };
+            write(due.items).await?;
+        }
+    }
+
+    /// Forward a read; once the wrapped buffer is closed and drained, hand
+    /// out the batches still held back before reporting `Closed`.
+    fn read_or_release(
+        &self,
+        read: Result<Vec<T>, ReadError>,
+        max: usize,
+    ) -> Result<Vec<T>, ReadError> {
+        match read {
+            Err(ReadError::Closed) => {
+                let mut held = self.held.borrow_mut();
+                let Some(front) = held.front_mut() else { return Err(ReadError::Closed) };
+                let count = max.min(front.items.len());
+                let items: Vec<T> = front.items.drain(..count).collect();
+                if front.items.is_empty() {
+                    held.pop_front();
+                }
+                Ok(items)
+            }
+            other => other,
+        }
+    }
+}
+
+impl<B: Close, T> Close for ChaosBuffer<B, T> {
+    fn close(&self) {
+        self.inner.close();
+    }
+}
+
+impl<B: BufferDepth, T> BufferDepth for ChaosBuffer<B, T> {
+    fn depth(&self) -> usize {
+        let held: usize = self.held.borrow().iter().map(|h| h.items.len()).sum();
+        self.inner.depth() + held
+    }
+}
+
+impl<B: Buffer1> Buffer1 for ChaosBuffer<B, Transaction> {
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+        self.chaos_write(batch, async |b| self.inner.write_batch(b).await).await
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+}
+
+impl<B: Buffer1Read> Buffer1Read for ChaosBuffer<B, Transaction> {
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+        let read = self.inner.read_batch(max).await;
+        self.read_or_release(read, max)
+    }
+}
+
+impl<B: Buffer2> Buffer2 for ChaosBuffer<B, InferredTransaction> {
+    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), WriteError> {
+        self.chaos_write(batch, async |b| self.inner.write_batch(b).await).await
+    }
+}
+
+impl<B: Buffer2Read> Buffer2Read for ChaosBuffer<B, InferredTransaction> {
+    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
+        let read = self.inner.read_batch(max).await;
+        self.read_or_release(read, max)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{Anomaly, ChaosBuffer, ChaosScript};
+    use crate::run_paused;
+    use consumer::{Consumer, ConsumerConfig};
+    use domain::{
+        Buffer1 as _, Buffer1Read as _, InferredTransaction, ReadError, Transaction, TransactionId,
+    };
+    use logger::{Logger, LoggerConfig};
+    use pipeline::memory::{CountingAlarm, MemoryBuffer, MemoryStorage, RateModel};
+    use pipeline::{Close as _, Pipeline, PipelineReport};
+    use producer::{Producer, ProducerConfig};
+    use std::time::Duration;
+
+    type Chaos1 = ChaosBuffer<MemoryBuffer<Transaction>, Transaction>;
+    type Chaos2 = ChaosBuffer<MemoryBuffer<InferredTransaction>, InferredTransaction>;
+
+    fn tx(n: u128) -> Transaction {
+        Transaction {
+            id: TransactionId::from_uuid(uuid::Uuid::from_u128(n)),
+            amount: 1.0,
+            last_name: "Smith".to_owned(),
+            currency: domain::Currency::Eur,
+            tenant_id: None,
+        }
+    }
+
+    /// Run a seeded 10-iteration pipeline over the two chaos buffers; return
+    /// the report, the produced ids and the persisted ids, in order.
+    async fn run_chaos(
+        buffer1: &Chaos1,
+        buffer2: &Chaos2,
+    ) -> (PipelineReport, Vec<TransactionId>, Vec<TransactionId>) {
+        let stage1 = ProducerConfig::builder(8).poll_interval1(Duration::ZERO).iterations(10);
+        let stage2 = ConsumerConfig::builder(8).poll_interval2(Duration::ZERO).seed(2);
+        let stage3 = LoggerConfig::builder(8).poll_interval3(Duration::ZERO).seed(3);
+        let pipeline = Pipeline::new(
+            Producer::new(stage1.seed(1).build().unwrap()),
+            Consumer::new(stage2.build().unwrap()),
+            Logger::new(stage3.build().unwrap()),
+        );
+        // The same seed regenerates what the Producer wrote, in order.
+        let replay = Producer::new(
+            ProducerConfig::builder(8).iterations(10).seed(1).build().unwrap(),
+        );
+        let produced: Vec<_> =
+            (0..10).flat_map(|_| replay.generate_batch()).map(|tx| tx.id).collect();
+        let storage = MemoryStorage::new();
+        let modelizer = modelizer::Modelizer::new(RateModel::new(0.2, 4));
+
+        let stops = pipeline
+            .run(buffer1, &modelizer, &CountingAlarm::new(), buffer2, &storage)
+            .await
+            .unwrap();
+        let persisted = storage.items().iter().map(domain::PendingTransaction::id).collect();
+        (pipeline.report(storage.len(), stops), produced, persisted)
+    }
+
+    fn logs(buffer1: &Chaos1, buffer2: &Chaos2) -> String {
+        format!("{}\n{}", buffer1.anomaly_log(), buffer2.anomaly_log())
+    }
+
+    // CH-T01: the same seed injects the same anomalies.
+    #[test]
+    fn anomalies_are_reproducible_from_the_seed() {
+        let script = ChaosScript::new(9).delay(0.3, 3).reorder(0.3, 2).duplicate(0.3);
+        let run = || {
+            run_paused(async {
+                let buffer: Chaos1 = ChaosBuffer::new(MemoryBuffer::new(), "buffer1", script);
+                for n in 0..20 {
+                    buffer.write_batch(vec![tx(n)]).await.unwrap();
+                }
+                buffer.anomalies()
+            })
+        };
+
+        let first = run();
+        assert!(!first.is_empty());
+        assert_eq!(first, run());
+    }
+
+    // CH-T02: a held-back batch arrives after its window, and closing never
+    // loses one still held.
+    #[test]
+    fn held_batches_arrive_late_and_survive_close() {
+        run_paused(async {
+            let script = ChaosScript::new(0).reorder(1.0, 1);
+            let buffer: Chaos1 = ChaosBuffer::new(MemoryBuffer::new(), "buffer1", script);
+
+            for n in 0..3 {
+                buffer.write_batch(vec![tx(n)]).await.unwrap();
+            }
+            buffer.close();
+
+            let mut read = vec![];
+            loop {
+                match buffer.read_batch(10).await {
+                    Ok(batch) => read.extend(batch.iter().map(|t| t.id)),
+                    Err(ReadError::Closed) => break,
+                    Err(e) => panic!("{e}"),
+                }
+            }
+            // #1 is released after #2, #2 after #3, #3 on close.
+            assert_eq!(read, [tx(0).id, tx(1).id, tx(2).id], "{}", buffer.anomaly_log());
+            assert_eq!(buffer.anomalies().len(), 3);
+            assert!(matches!(buffer.anomalies()[0], Anomaly::Reordered { batch: 1, window: 1 }));
+        });
+    }
+
+    // CH-T03: reordering and delays keep exactly-once processing and matching
+    // digests; only the storage order changes.
+    #[test]
+    fn reordering_keeps_exactly_once_but_not_the_order() {
+        run_paused(async {
+            let script = ChaosScript::new(5).delay(0.5, 4).reorder(0.4, 2);
+            let buffer1: Chaos1 = ChaosBuffer::new(MemoryBuffer::new(), "buffer1", script);
+            let buffer2: Chaos2 =
+                ChaosBuffer::new(MemoryBuffer::new(), "buffer2", ChaosScript { seed: 6, ..script });
+
+            let (report, produced, persisted) = run_chaos(&buffer1, &buffer2).await;
+
+            let log = logs(&buffer1, &buffer2);
+            assert_eq!(report.digest_mismatch(), None, "{log}");
+            assert_eq!(report.inferred, report.produced, "{log}");
+            assert_eq!(report.persisted, report.produced, "{log}");
+            let mut sorted = persisted.clone();
+            sorted.sort();
+            let mut expected = produced.clone();
+            expected.sort();
+            assert_eq!(sorted, expected, "{log}");
+            assert!(
+                buffer1.anomalies().iter().any(|a| matches!(a, Anomaly::Reordered { .. })),
+                "{log}"
+            );
+            assert_ne!(persisted, produced, "storage order follows delivery order\n{log}");
+        });
+    }
+
+    // CH-T04: a duplicated batch is persisted twice and reported as a digest
+    // mismatch with extra transactions, never silently absorbed.
+    #[test]
+    fn duplication_is_reported_as_extra_transactions() {
+        run_paused(async {
+            let script = ChaosScript::new(7).duplicate(0.3);
+            let buffer1: Chaos1 = ChaosBuffer::new(MemoryBuffer::new(), "buffer1", script);
+            let buffer2: Chaos2 =
+                ChaosBuffer::new(MemoryBuffer::new(), "buffer2", ChaosScript::new(8));
+
+            let (report, _, persisted) = run_chaos(&buffer1, &buffer2).await;
+
+            let log = logs(&buffer1, &buffer2);
+            let extra: usize = buffer1
+                .anomalies()
+                .iter()
+                .map(|a| match a {
+                    Anomaly::Duplicated { size, .. } => *size,
+                    _ => 0,
+                })
+                .sum();
+            assert!(extra > 0, "{log}");
+            assert_eq!(persisted.len() as u64, report.produced + extra as u64, "{log}");
+            let mismatch = report.digest_mismatch().expect("duplicates must be reported");
+            let expected = format!("({extra} extra)");
+            assert!(mismatch.to_string().ends_with(&expected), "{mismatch}\n{log}");
+        });
+    }
+}
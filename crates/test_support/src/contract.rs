@@ -0,0 +1,184 @@
+// Rust guideline compliant 2026-02-27
+
+//! Contract checks shared by buffer and modelizer adapters.
+//!
+//! Each check drives an adapter through the public ports only, so any adapter
+//! can call it from its own tests.
+//!
+//! # Exclusive drain
+//!
+//! `Buffer1Read::read_batch` hands each written transaction to exactly one
+//! reader, so several Consumers can share one Buffer1 and split the work.
+//! [`buffer1_exclusive_drain`] checks this with concurrent readers. An adapter
+//! that cannot guarantee it (e.g. a broadcast-style buffer giving every reader
+//! a copy) must say so in its docs and must not run this check.
+//!
+//! # One result per input
+//!
+//! `Modelizer::infer` returns one result per transaction, in input order.
+//! The Consumer fails a batch that breaks this; [`modelizer_one_result_per_input`]
+//! lets an adapter check it from its own tests instead.
+
+use std::collections::HashSet;
+
+use domain::{
+    Buffer1, Buffer1Read, Currency, Modelizer, ReadError, Transaction, TransactionId,
+};
+
+/// Transactions written by [`buffer1_exclusive_drain`].
+pub const EXCLUSIVE_DRAIN_TOTAL: usize = 500;
+
+/// Check that concurrent readers of `buffer` drain every written transaction
+/// exactly once.
+///
+/// Writes [`EXCLUSIVE_DRAIN_TOTAL`] transactions in small batches, yielding in
+/// between, while three readers with different batch sizes drain the buffer
+/// until `Closed`. `close` must signal end-of-data; it is called once after
+/// the last write. `buffer` must be open and empty.
+///
+/// # Panics
+///
+/// Panics when a transaction is read twice, never read, or a reader sees an
+/// error other than `Closed`.
+pub async fn buffer1_exclusive_drain<B>(buffer: &B, close: impl FnOnce())
+where
+    B: Buffer1 + Buffer1Read,
+{
+    let written: Vec<Transaction> = (0..EXCLUSIVE_DRAIN_TOTAL)
+        .map(|i| Transaction {
+            id: id_from_index(i),
+            amount: 1.00_f64,
+            last_name: "Contract".to_owned(),
+            currency: Currency::Eur,
+            tenant_id: None,
+        })
+        .collect();
+    let expected: HashSet<TransactionId> = written.iter().map(|tx| tx.id).collect();
+
+    let writer = async {
+        for chunk in written.chunks(7) {
+            buffer.write_batch(chunk.to_vec()).await.expect("contract writes must succeed");
+            tokio::task::yield_now().await;
+        }
+        close();
+    };
+    let ((), a, b, c) =
+        tokio::join!(writer, drain(buffer, 1), drain(buffer, 5), drain(buffer, 64));
+
+    let mut seen = HashSet::with_capacity(EXCLUSIVE_DRAIN_TOTAL);
+    for id in a.iter().chain(&b).chain(&c) {
+        assert!(seen.insert(*id), "transaction {id} was read by more than one reader");
+    }
+    assert_eq!(seen, expected, "every written transaction must be read exactly once");
+}
+
+/// Batch sizes sent by [`modelizer_one_result_per_input`].
+pub const INFER_BATCH_SIZES: [usize; 4] = [1, 2, 17, 256];
+
+/// Check that `modelizer` returns one result per transaction, in order, with
+/// the transaction carried through unchanged.
+///
+/// Sends one batch of each of [`INFER_BATCH_SIZES`], with distinct ids and
+/// amounts.
+///
+/// # Panics
+///
+/// Panics when `infer` fails, returns a different number of results, or a
+/// result does not carry the transaction sent at its position.
+pub async fn modelizer_one_result_per_input<M: Modelizer>(modelizer: &M) {
+    let mut next = 0;
+    for size in INFER_BATCH_SIZES {
+        let batch: Vec<Transaction> = (next..next + size)
+            .map(|i| Transaction {
+                id: id_from_index(i),
+                amount: f64::from(u32::try_from(i).expect("few contract transactions")) + 1.25,
+                last_name: format!("Contract{i}"),
+                currency: Currency::Eur,
+                tenant_id: None,
+            })
+            .collect();
+        next += size;
+        let inferred = modelizer
+            .infer(batch.clone())
+            .await
+            .unwrap_or_else(|e| panic!("infer of {size} transactions failed: {e}"));
+        assert_eq!(inferred.len(), size, "infer must return one result per transaction");
+        for (position, (sent, result)) in batch.iter().zip(&inferred).enumerate() {
+            assert_eq!(
+                &result.transaction, sent,
+                "result {position} of a batch of {size} must carry the transaction sent there"
+            );
+        }
+    }
+}
+
+/// Read `buffer` in batches of `max` until `Closed`; return the ids read.
+async fn drain<B: Buffer1Read>(buffer: &B, max: usize) -> Vec<TransactionId> {
+    let mut ids = vec![];
+    loop {
+        match buffer.read_batch(max).await {
+            Ok(batch) => {
+                assert!(batch.len() <= max, "read {} > max {max}", batch.len());
+                ids.extend(batch.iter().map(|tx| tx.id));
+            }
+            Err(ReadError::Closed) => return ids,
+            Err(e) => panic!("unexpected read error: {e}"),
+        }
+        // Give the other readers a turn between batches.
+        tokio::task::yield_now().await;
+    }
+}
+
+/// Distinct, stable id for the `i`-th contract transaction.
+fn id_from_index(i: usize) -> TransactionId {
+    TransactionId::from_uuid(uuid::Uuid::from_u128(i as u128 + 1))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{buffer1_exclusive_drain, modelizer_one_result_per_input};
+    use domain::{InferredTransaction, ModelVersion, Modelizer, ModelizerError, Transaction};
+    use pipeline::Close as _;
+    use pipeline::memory::{MemoryBuffer, RateModel};
+
+    #[tokio::test]
+    async fn memory_buffer_drains_exclusively() {
+        let buffer = MemoryBuffer::new();
+        buffer1_exclusive_drain(&buffer, || buffer.close()).await;
+    }
+
+    #[tokio::test]
+    async fn rate_model_returns_one_result_per_input() {
+        let modelizer = modelizer::Modelizer::new(RateModel::new(0.5, 7));
+        modelizer_one_result_per_input(&modelizer).await;
+    }
+
+    /// Drops the last result of every batch.
+    struct Lossy(modelizer::Modelizer<RateModel>);
+
+    impl Modelizer for Lossy {
+        async fn infer(
+            &self,
+            batch: Vec<Transaction>,
+        ) -> Result<Vec<InferredTransaction>, ModelizerError> {
+            let mut inferred = self.0.infer(batch).await?;
+            inferred.pop();
+            Ok(inferred)
+        }
+
+        async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+            self.0.switch_version(version).await
+        }
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "one result per transaction")]
+    async fn lossy_modelizer_fails_the_contract() {
+        let lossy = Lossy(modelizer::Modelizer::new(RateModel::new(0.5, 7)));
+        modelizer_one_result_per_input(&lossy).await;
+    }
+}
@@ -0,0 +1,326 @@
+// Rust guideline compliant 2026-02-27
+
+//! Full three-stage pipeline wired over scripted in-memory adapters.
+//!
+//! [`Harness`] runs [`Pipeline::run`] (and so its shutdown cascade) with every
+//! adapter wrapped in [`Scripted`], all recording into one [`Trace`]. Run it
+//! inside [`run_paused`](crate::run_paused) so the interleaving only depends on
+//! the scripts and the seed.
+
+use std::time::Duration;
+
+use consumer::{Consumer, ConsumerConfig};
+use domain::{InferredTransaction, Transaction};
+use logger::{Logger, LoggerConfig};
+use pipeline::memory::{CountingAlarm, MemoryBuffer, MemoryStorage, RateModel};
+use pipeline::{Pipeline, PipelineError, PipelineReport, StageStops};
+use producer::{Producer, ProducerConfig};
+
+use crate::scripted::{Script, Scripted, Trace};
+
+// ---------------------------------------------------------------------------
+// Harness
+// ---------------------------------------------------------------------------
+
+/// A seeded pipeline whose adapters follow per-adapter [`Script`]s.
+///
+/// Trace labels are `buffer1`, `buffer2`, `modelizer` and `storage`.
+#[derive(Debug)]
+pub struct Harness {
+    /// Shared log of every scripted call.
+    pub trace: Trace,
+    /// Producer -> Consumer buffer.
+    pub buffer1: Scripted<MemoryBuffer<Transaction>>,
+    /// Consumer -> Logger buffer.
+    pub buffer2: Scripted<MemoryBuffer<InferredTransaction>>,
+    /// Seeded `RATE` model.
+    pub modelizer: Scripted<modelizer::Modelizer<RateModel>>,
+    /// Counts alarms; not scripted.
+    pub alarm: CountingAlarm,
+    /// Logger sink.
+    pub storage: Scripted<MemoryStorage>,
+    /// The three stages.
+    pub pipeline: Pipeline,
+}
+
+/// Builder for [`Harness`].
+///
+/// Obtain via [`Harness::builder`]; finalize with [`build`](Self::build).
+#[derive(Debug)]
+pub struct HarnessBuilder {
+    iterations: u64,
+    seed: u64,
+    batch_max: usize,
+    poll_interval: Duration,
+    fraud_rate: f64,
+    buffer1: Script,
+    buffer2: Script,
+    modelizer: Script,
+    storage: Script,
+}
+
+impl Harness {
+    /// Create a builder. `iterations` bounds the Producer; the cascade stops the rest.
+    ///
+    /// Default values: `seed = 0`, `batch_max = 10`, `poll_interval = 1 ms`,
+    /// `fraud_rate = 0.5`, empty scripts.
+    #[must_use]
+    pub fn builder(iterations: u64) -> HarnessBuilder {
+        HarnessBuilder {
+            iterations,
+            seed: 0,
+            batch_max: 10,
+            poll_interval: Duration::from_millis(1),
+            // High enough that alarms fire in a handful of batches.
+            fraud_rate: 0.5,
+            buffer1: Script::new(),
+            buffer2: Script::new(),
+            modelizer: Script::new(),
+            storage: Script::new(),
+        }
+    }
+
+    /// Run the pipeline to completion with the shutdown cascade; returns why
+    /// each stage stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first stage error, as [`Pipeline::run`] does.
+    pub async fn run(&self) -> Result<StageStops, PipelineError> {
+        self.pipeline
+            .run(&self.buffer1, &self.modelizer, &self.alarm, &self.buffer2, &self.storage)
+            .await
+    }
+
+    /// Stage counters plus the number of persisted transactions, with the
+    /// `stops` returned by [`run`](Self::run).
+    #[must_use]
+    pub fn report(&self, stops: StageStops) -> PipelineReport {
+        self.pipeline.report(self.storage.inner().len(), stops)
+    }
+}
+
+impl HarnessBuilder {
+    /// Base seed; the stages and the model derive their own seeds from it.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Maximum batch size of every stage.
+    #[must_use]
+    pub fn batch_max(mut self, n: usize) -> Self {
+        self.batch_max = n;
+        self
+    }
+
+    /// Delay between iterations of every stage.
+    #[must_use]
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Probability that the model flags a transaction.
+    #[must_use]
+    pub fn fraud_rate(mut self, rate: f64) -> Self {
+        self.fraud_rate = rate;
+        self
+    }
+
+    /// Script for Buffer1.
+    #[must_use]
+    pub fn buffer1(mut self, script: Script) -> Self {
+        self.buffer1 = script;
+        self
+    }
+
+    /// Script for Buffer2.
+    #[must_use]
+    pub fn buffer2(mut self, script: Script) -> Self {
+        self.buffer2 = script;
+        self
+    }
+
+    /// Script for the Modelizer.
+    #[must_use]
+    pub fn modelizer(mut self, script: Script) -> Self {
+        self.modelizer = script;
+        self
+    }
+
+    /// Script for the storage.
+    #[must_use]
+    pub fn storage(mut self, script: Script) -> Self {
+        self.storage = script;
+        self
+    }
+
+    /// Build the stages and adapters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineError`] when a stage configuration is rejected
+    /// (zero `iterations` or `batch_max`).
+    #[must_use = "the Result must be checked; use ? or unwrap"]
+    pub fn build(self) -> Result<Harness, PipelineError> {
+        let seed = self.seed;
+        let producer = Producer::new(
+            ProducerConfig::builder(self.batch_max)
+                .poll_interval1(self.poll_interval)
+                .iterations(self.iterations)
+                .seed(seed)
+                .build()?,
+        );
+        let consumer = Consumer::new(
+            ConsumerConfig::builder(self.batch_max)
+                .poll_interval2(self.poll_interval)
+                .seed(seed.wrapping_add(1))
+                .build()?,
+        );
+        let logger = Logger::new(
+            LoggerConfig::builder(self.batch_max)
+                .poll_interval3(self.poll_interval)
+                .seed(seed.wrapping_add(2))
+                .build()?,
+        );
+
+        let trace = Trace::new();
+        let model = RateModel::new(self.fraud_rate, seed.wrapping_add(3));
+        Ok(Harness {
+            buffer1: Scripted::new(MemoryBuffer::new(), "buffer1", self.buffer1, trace.clone()),
+            buffer2: Scripted::new(MemoryBuffer::new(), "buffer2", self.buffer2, trace.clone()),
+            modelizer: Scripted::new(
+                modelizer::Modelizer::new(model),
+                "modelizer",
+                self.modelizer,
+                trace.clone(),
+            ),
+            alarm: CountingAlarm::new(),
+            storage: Scripted::new(MemoryStorage::new(), "storage", self.storage, trace.clone()),
+            pipeline: Pipeline::new(producer, consumer, logger),
+            trace,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::Harness;
+    use crate::run_paused;
+    use crate::scripted::{Op, Script};
+    use domain::{ErrorChain, StopReason, StorageError};
+    use logger::LoggerError;
+    use pipeline::PipelineError;
+    use std::time::Duration;
+
+    /// Longer than the whole upstream run takes, so the delayed call is
+    /// still in flight when the cascade reaches it. Free under a paused clock.
+    const STALL: Duration = Duration::from_secs(1);
+
+    // IL-T01: Consumer finishes and closes Buffer2 while the Logger is mid-read;
+    // the Logger still drains everything before stopping.
+    #[test]
+    fn consumer_finishes_while_logger_mid_read() {
+        run_paused(async {
+            let harness = Harness::builder(3)
+                .seed(1)
+                .buffer2(Script::new().delay(Op::Read, 1, STALL))
+                .build()
+                .unwrap();
+
+            let stops = harness.run().await.unwrap();
+
+            let trace = &harness.trace;
+            assert!(trace.ordered("buffer2.read#1", "buffer2.close"), "{:?}", trace.entries());
+            assert!(trace.ordered("buffer2.close", "buffer2.read#1.done"), "{:?}", trace.entries());
+            let report = harness.report(stops);
+            assert!(report.produced > 0);
+            assert_eq!(report.persisted, report.produced);
+            assert!(matches!(report.stops.logger, StopReason::BufferClosed { .. }));
+        });
+    }
+
+    // IL-T02: Producer closes Buffer1 while the Consumer is mid-infer; the
+    // Consumer finishes the batch, drains the rest, then the cascade completes.
+    #[test]
+    fn producer_closes_while_consumer_mid_infer() {
+        run_paused(async {
+            let harness = Harness::builder(3)
+                .seed(2)
+                .modelizer(Script::new().delay(Op::Infer, 1, STALL))
+                .build()
+                .unwrap();
+
+            let stops = harness.run().await.unwrap();
+
+            let trace = &harness.trace;
+            assert!(trace.ordered("modelizer.infer#1", "buffer1.close"), "{:?}", trace.entries());
+            assert!(
+                trace.ordered("buffer1.close", "modelizer.infer#1.done"),
+                "{:?}",
+                trace.entries()
+            );
+            assert!(trace.ordered("modelizer.infer#1.done", "buffer2.close"));
+            let report = harness.report(stops);
+            assert_eq!(report.stops.producer, StopReason::IterationLimit { iterations: 3 });
+            assert!(matches!(report.stops.consumer, StopReason::BufferClosed { .. }));
+            assert_eq!(report.inferred, report.produced);
+            assert_eq!(report.persisted, report.produced);
+        });
+    }
+
+    // IL-T03: the Logger's storage write fails exactly while Buffer2 closes;
+    // the error is reported instead of being masked by the clean close.
+    #[test]
+    fn storage_error_as_buffer2_closes_is_reported() {
+        run_paused(async {
+            let harness = Harness::builder(3)
+                .seed(3)
+                .storage(Script::new().delay(Op::Write, 1, STALL).fail(Op::Write, 1))
+                .build()
+                .unwrap();
+
+            let result = harness.run().await;
+
+            assert!(
+                matches!(
+                    result,
+                    Err(PipelineError::Logger(LoggerError::Write(StorageError::Unavailable)))
+                ),
+                "expected Logger(Write(Unavailable)), got {result:?}"
+            );
+            let Err(error) = &result else { unreachable!() };
+            assert_eq!(
+                ErrorChain(error).messages(),
+                ["logger failed", "storage write error", "storage unavailable"]
+            );
+            let trace = &harness.trace;
+            assert!(trace.ordered("storage.write#1", "buffer2.close"), "{:?}", trace.entries());
+            assert!(trace.ordered("buffer2.close", "storage.write#1.done"), "{:?}", trace.entries());
+            assert!(harness.storage.inner().is_empty());
+        });
+    }
+
+    // IL-T04: same seed and scripts, same interleaving.
+    #[test]
+    fn interleaving_is_reproducible() {
+        let trace = || {
+            run_paused(async {
+                let harness = Harness::builder(4)
+                    .seed(9)
+                    .buffer2(Script::new().delay(Op::Write, 2, Duration::from_millis(10)))
+                    .build()
+                    .unwrap();
+                harness.run().await.unwrap();
+                harness.trace.entries()
+            })
+        };
+        assert_eq!(trace(), trace());
+    }
+}
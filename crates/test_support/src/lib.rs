@@ -0,0 +1,53 @@
+// Rust guideline compliant 2026-02-27
+
+//! Test support: deterministic, scripted interleavings of the pipeline.
+//!
+//! Cascade bugs (a missed close, a double drain) depend on the order in which
+//! `join!` polls the stages. This crate makes that order reproducible:
+//!
+//! - [`run_paused`] runs a future on a single-threaded runtime with a paused
+//!   clock, so timers fire in a fixed order and cost no wall time.
+//! - [`Scripted`] decorates any buffer, modelizer or storage adapter and
+//!   injects yields, delays or failures at chosen call counts, e.g. "delay the
+//!   3rd Buffer2 write by 10 ms", recording every call in a [`Trace`].
+//! - [`Harness`] wires the full pipeline over scripted in-memory adapters.
+//! - [`simulate()`] runs the in-memory pipeline for a virtual duration, e.g. five
+//!   minutes of 500 ms batches, and returns its report.
+//! - [`RecordingRng`] logs every draw a stage makes; [`ReplayRng`] replays
+//!   them, so an OS-seeded failing run can be reproduced exactly.
+//! - [`ChaosBuffer`] wraps any buffer and, from a seeded [`ChaosScript`],
+//!   delays, reorders or duplicates batches, logging every [`Anomaly`].
+//! - [`contract`] holds port-level checks any adapter can run from its tests,
+//!   e.g. [`contract::buffer1_exclusive_drain`] or
+//!   [`contract::modelizer_one_result_per_input`].
+//!
+//! Adapters wrapped in [`Scripted`] must not busy-wait (spin on
+//! `yield_now`) while empty: a spinning task keeps the runtime busy and the
+//! paused clock never advances.
+
+pub mod chaos;
+pub mod contract;
+pub mod harness;
+pub mod rng;
+pub mod scripted;
+pub mod simulate;
+
+pub use chaos::{Anomaly, ChaosBuffer, ChaosScript};
+pub use harness::{Harness, HarnessBuilder};
+pub use rng::{Draw, DrawLog, RecordingRng, ReplayRng};
+pub use scripted::{Action, Op, Script, Scripted, Trace};
+pub use simulate::{Simulation, SimulationBuilder, simulate};
+
+/// Run `future` to completion on a `current_thread` runtime with time paused.
+///
+/// # Panics
+///
+/// Panics if the runtime cannot be built.
+pub fn run_paused<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .start_paused(true)
+        .build()
+        .expect("current_thread runtime must build")
+        .block_on(future)
+}
@@ -0,0 +1,266 @@
+// Rust guideline compliant 2026-02-27
+
+//! Recording and replaying random generators, for exact failure reproduction.
+//!
+//! Any stage accepting a `domain::RngPort` (e.g. `Consumer::with_rng`) can be
+//! given a [`RecordingRng`]: it forwards to an inner generator and appends
+//! every value it hands out to a shared [`DrawLog`]. Feeding those draws to a
+//! [`ReplayRng`] replays the same sequence, so a failing run seeded from the
+//! OS can be rerun draw for draw.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use rand::RngCore;
+
+// ---------------------------------------------------------------------------
+// Draw / DrawLog
+// ---------------------------------------------------------------------------
+
+/// One value handed out by a generator, by `RngCore` method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Draw {
+    /// Result of `next_u32`.
+    U32(u32),
+    /// Result of `next_u64`.
+    U64(u64),
+    /// Bytes written by `fill_bytes`.
+    Bytes(Vec<u8>),
+}
+
+/// Shared, ordered log of the draws of a [`RecordingRng`].
+#[derive(Debug, Clone, Default)]
+pub struct DrawLog {
+    draws: Rc<RefCell<Vec<Draw>>>,
+}
+
+impl DrawLog {
+    /// Create an empty log.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy of every draw, in order.
+    #[must_use]
+    pub fn draws(&self) -> Vec<Draw> {
+        self.draws.borrow().clone()
+    }
+
+    /// Number of draws recorded so far.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.draws.borrow().len()
+    }
+
+    /// `true` when nothing was drawn yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.draws.borrow().is_empty()
+    }
+
+    fn push(&self, draw: Draw) {
+        self.draws.borrow_mut().push(draw);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RecordingRng
+// ---------------------------------------------------------------------------
+
+/// Generator forwarding to `inner` and recording every draw into a [`DrawLog`].
+#[derive(Debug)]
+pub struct RecordingRng<R> {
+    inner: R,
+    log: DrawLog,
+}
+
+impl<R: RngCore> RecordingRng<R> {
+    /// Wrap `inner`; draws are appended to `log`.
+    #[must_use]
+    pub fn new(inner: R, log: DrawLog) -> Self {
+        Self { inner, log }
+    }
+}
+
+impl<R: RngCore> RngCore for RecordingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        let value = self.inner.next_u32();
+        self.log.push(Draw::U32(value));
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.inner.next_u64();
+        self.log.push(Draw::U64(value));
+        value
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.inner.fill_bytes(dst);
+        self.log.push(Draw::Bytes(dst.to_vec()));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ReplayRng
+// ---------------------------------------------------------------------------
+
+/// Generator handing out a recorded sequence of draws, in order.
+///
+/// # Panics
+///
+/// Every method panics when the sequence is exhausted or when the next draw
+/// was recorded by a different method (or, for `fill_bytes`, with a different
+/// length): the replayed run has diverged from the recorded one.
+#[derive(Debug)]
+pub struct ReplayRng {
+    draws: VecDeque<Draw>,
+}
+
+impl ReplayRng {
+    /// Replay `draws`, e.g. [`DrawLog::draws`] of a recorded run.
+    #[must_use]
+    pub fn new(draws: Vec<Draw>) -> Self {
+        Self { draws: draws.into() }
+    }
+
+    /// Draws not replayed yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.draws.len()
+    }
+
+    fn next_draw(&mut self, wanted: &str) -> Draw {
+        self.draws
+            .pop_front()
+            .unwrap_or_else(|| panic!("replay exhausted on {wanted}: the run diverged"))
+    }
+}
+
+impl RngCore for ReplayRng {
+    fn next_u32(&mut self) -> u32 {
+        match self.next_draw("next_u32") {
+            Draw::U32(value) => value,
+            other => panic!("replay diverged: next_u32 called, recorded {other:?}"),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self.next_draw("next_u64") {
+            Draw::U64(value) => value,
+            other => panic!("replay diverged: next_u64 called, recorded {other:?}"),
+        }
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        match self.next_draw("fill_bytes") {
+            Draw::Bytes(bytes) if bytes.len() == dst.len() => dst.copy_from_slice(&bytes),
+            other => {
+                panic!("replay diverged: fill_bytes({}) called, recorded {other:?}", dst.len())
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{Draw, DrawLog, RecordingRng, ReplayRng};
+    use crate::run_paused;
+    use consumer::{BatchObserver, ConsumeOutcome, Consumer, ConsumerConfig};
+    use domain::{Buffer1 as _, Currency, InferredTransaction, Transaction, TransactionId};
+    use modelizer::Modelizer;
+    use pipeline::memory::{CountingAlarm, MemoryBuffer, RateModel};
+    use rand::rngs::StdRng;
+    use rand::{RngCore as _, SeedableRng as _};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    /// Records the size of every Buffer1 read.
+    #[derive(Debug, Clone, Default)]
+    struct BatchSizes(Rc<RefCell<Vec<usize>>>);
+
+    impl BatchObserver for BatchSizes {
+        fn on_batch(&self, outcome: &ConsumeOutcome) {
+            self.0.borrow_mut().push(outcome.read);
+        }
+    }
+
+    /// Batch sizes of an 8-iteration Consumer run over 1 000 transactions.
+    fn batch_sizes(consumer: Consumer) -> Vec<usize> {
+        let sizes = BatchSizes::default();
+        let consumer = consumer.with_observer(sizes.clone());
+        run_paused(async {
+            let buffer1 = MemoryBuffer::<Transaction>::new();
+            let txs = (0..1_000)
+                .map(|i| Transaction {
+                    id: TransactionId::new_v4(),
+                    amount: f64::from(i),
+                    last_name: format!("Name{i}"),
+                    currency: Currency::Eur,
+                    tenant_id: None,
+                })
+                .collect();
+            buffer1.write_batch(txs).await.unwrap();
+            let modelizer = Modelizer::new(RateModel::new(0.0, 1));
+            let buffer2 = MemoryBuffer::<InferredTransaction>::new();
+
+            consumer.run(&buffer1, &modelizer, &CountingAlarm::new(), &buffer2).await.unwrap();
+        });
+        sizes.0.take()
+    }
+
+    fn config() -> ConsumerConfig {
+        ConsumerConfig::builder(50).poll_interval2(Duration::ZERO).iterations(8).build().unwrap()
+    }
+
+    // RNG-T01: the recorder forwards the inner values and logs them in order.
+    #[test]
+    fn recording_rng_forwards_and_logs() {
+        let log = DrawLog::new();
+        let mut recording = RecordingRng::new(StdRng::seed_from_u64(3), log.clone());
+        let mut reference = StdRng::seed_from_u64(3);
+
+        let a = recording.next_u32();
+        let b = recording.next_u64();
+        let mut bytes = [0u8; 4];
+        recording.fill_bytes(&mut bytes);
+
+        let mut expected_bytes = [0u8; 4];
+        assert_eq!(a, reference.next_u32());
+        assert_eq!(b, reference.next_u64());
+        reference.fill_bytes(&mut expected_bytes);
+        assert_eq!(bytes, expected_bytes);
+        assert_eq!(log.draws(), [Draw::U32(a), Draw::U64(b), Draw::Bytes(bytes.to_vec())]);
+    }
+
+    // RNG-T02: replaying the draws of an OS-seeded run reproduces its
+    // batch-size series exactly.
+    #[test]
+    fn replayed_draws_reproduce_the_batch_sizes() {
+        let log = DrawLog::new();
+        let recording = RecordingRng::new(StdRng::from_os_rng(), log.clone());
+        let recorded = batch_sizes(Consumer::with_rng(config(), recording));
+
+        let replayed = batch_sizes(Consumer::with_rng(config(), ReplayRng::new(log.draws())));
+
+        assert!(!log.is_empty(), "batch sizes are drawn from the RNG");
+        assert_eq!(replayed.len(), 8);
+        assert_eq!(replayed, recorded);
+    }
+
+    // RNG-T03: a draw by another method than recorded is a divergence.
+    #[test]
+    #[should_panic(expected = "replay diverged")]
+    fn replay_panics_on_divergence() {
+        let mut replay = ReplayRng::new(vec![Draw::U32(7)]);
+        assert_eq!(replay.remaining(), 1);
+        replay.next_u64();
+    }
+}
@@ -0,0 +1,383 @@
+// Rust guideline compliant 2026-02-27
+
+//! Scripted decorator for buffer, modelizer and storage adapters.
+//!
+//! [`Scripted`] wraps any adapter and counts its calls per [`Op`]. Before the
+//! n-th call of an operation it applies the [`Action`]s its [`Script`] lists
+//! for that call: extra yields, a delay, or an injected failure. Every call is
+//! recorded in a shared [`Trace`], so a test can assert the interleaving that
+//! actually happened, e.g. that `buffer2.close` landed while `buffer2.read#2`
+//! was in flight.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use domain::{
+    Buffer1, Buffer1Read, Buffer2, Buffer2Read, BufferDepth, InferredTransaction, Modelizer,
+    ModelizerError, ModelVersion, PendingTransaction, ReadError, RunId, RunMeta, RunSummary,
+    Storage, StorageError, Transaction, WriteError,
+};
+use pipeline::Close;
+
+// ---------------------------------------------------------------------------
+// Op / Action / Script
+// ---------------------------------------------------------------------------
+
+/// Adapter operation a [`Script`] step applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `Buffer1Read::read_batch` / `Buffer2Read::read_batch`.
+    Read,
+    /// `Buffer1::write_batch` / `Buffer2::write_batch` / `Storage::write_batch`.
+    Write,
+    /// `Modelizer::infer`.
+    Infer,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Infer => "infer",
+        })
+    }
+}
+
+/// What to do before a scripted call reaches the wrapped adapter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Yield to the runtime this many times.
+    Yield(u32),
+    /// Sleep; under a paused clock this only reorders, it costs no wall time.
+    Delay(Duration),
+    /// Fail without calling the wrapped adapter.
+    ///
+    /// Buffer writes fail with `Full { capacity: 0, .. }`, buffer reads with
+    /// `Closed`, `infer` with `InferenceFailed` and storage writes with
+    /// `Unavailable`.
+    Fail,
+}
+
+/// Ordered list of `(op, nth call, action)` steps.
+///
+/// Calls are counted from 1 per operation. Several steps may target the same
+/// call; they are applied in insertion order, and `Fail` wins once reached.
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    steps: Vec<(Op, u64, Action)>,
+}
+
+impl Script {
+    /// An empty script: every call goes straight through.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `action` before the `nth` call of `op`.
+    #[must_use]
+    pub fn at(mut self, op: Op, nth: u64, action: Action) -> Self {
+        self.steps.push((op, nth, action));
+        self
+    }
+
+    /// Shorthand for `at(op, nth, Action::Delay(delay))`.
+    #[must_use]
+    pub fn delay(self, op: Op, nth: u64, delay: Duration) -> Self {
+        self.at(op, nth, Action::Delay(delay))
+    }
+
+    /// Shorthand for `at(op, nth, Action::Fail)`.
+    #[must_use]
+    pub fn fail(self, op: Op, nth: u64) -> Self {
+        self.at(op, nth, Action::Fail)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Trace
+// ---------------------------------------------------------------------------
+
+/// Shared, ordered log of scripted calls.
+///
+/// Entries are `"{label}.{op}#{n}"` when call `n` starts, `"{label}.{op}#{n}.done"`
+/// when it returns, and `"{label}.close"` when a buffer is closed.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    entries: Rc<RefCell<Vec<String>>>,
+}
+
+impl Trace {
+    /// Create an empty trace.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `entry`.
+    pub fn push(&self, entry: impl Into<String>) {
+        self.entries.borrow_mut().push(entry.into());
+    }
+
+    /// Copy of every entry, in order.
+    #[must_use]
+    pub fn entries(&self) -> Vec<String> {
+        self.entries.borrow().clone()
+    }
+
+    /// Index of the first entry equal to `entry`.
+    #[must_use]
+    pub fn position(&self, entry: &str) -> Option<usize> {
+        self.entries.borrow().iter().position(|e| e == entry)
+    }
+
+    /// Whether `first` and `second` were both recorded, `first` strictly before.
+    #[must_use]
+    pub fn ordered(&self, first: &str, second: &str) -> bool {
+        matches!((self.position(first), self.position(second)), (Some(a), Some(b)) if a < b)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scripted
+// ---------------------------------------------------------------------------
+
+/// Decorator applying a [`Script`] to any adapter and recording into a [`Trace`].
+///
+/// Implements every port the wrapped adapter implements, plus [`Close`] and
+/// `BufferDepth`.
+#[derive(Debug)]
+pub struct Scripted<T> {
+    inner: T,
+    label: &'static str,
+    script: Script,
+    trace: Trace,
+    reads: Cell<u64>,
+    writes: Cell<u64>,
+    infers: Cell<u64>,
+}
+
+impl<T> Scripted<T> {
+    /// Wrap `inner`; trace entries are prefixed with `label`.
+    #[must_use]
+    pub fn new(inner: T, label: &'static str, script: Script, trace: Trace) -> Self {
+        Self {
+            inner,
+            label,
+            script,
+            trace,
+            reads: Cell::new(0),
+            writes: Cell::new(0),
+            infers: Cell::new(0),
+        }
+    }
+
+    /// The wrapped adapter.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Number of `op` calls started so far.
+    #[must_use]
+    pub fn calls(&self, op: Op) -> u64 {
+        self.counter(op).get()
+    }
+
+    fn counter(&self, op: Op) -> &Cell<u64> {
+        match op {
+            Op::Read => &self.reads,
+            Op::Write => &self.writes,
+            Op::Infer => &self.infers,
+        }
+    }
+
+    /// Count and trace the call, then apply its steps.
+    ///
+    /// Returns the call number, or `None` when the script fails this call.
+    async fn begin(&self, op: Op) -> Option<u64> {
+        let counter = self.counter(op);
+        let nth = counter.get() + 1;
+        counter.set(nth);
+        self.trace.push(format!("{}.{op}#{nth}", self.label));
+        for &(_, _, action) in self.script.steps.iter().filter(|s| s.0 == op && s.1 == nth) {
+            match action {
+                Action::Yield(n) => {
+                    for _ in 0..n {
+                        tokio::task::yield_now().await;
+                    }
+                }
+                Action::Delay(d) => tokio::time::sleep(d).await,
+                Action::Fail => {
+                    self.end(op, nth);
+                    return None;
+                }
+            }
+        }
+        Some(nth)
+    }
+
+    fn end(&self, op: Op, nth: u64) {
+        self.trace.push(format!("{}.{op}#{nth}.done", self.label));
+    }
+}
+
+impl<T: Close> Close for Scripted<T> {
+    fn close(&self) {
+        self.trace.push(format!("{}.close", self.label));
+        self.inner.close();
+    }
+}
+
+impl<T: BufferDepth> BufferDepth for Scripted<T> {
+    fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+}
+
+impl<T: Buffer1> Buffer1 for Scripted<T> {
+    async fn write_batch(&self, batch: Vec<Transaction>) -> Result<(), WriteError> {
+        let Some(nth) = self.begin(Op::Write).await else {
+            return Err(WriteError::Full { capacity: 0, rejected: batch.len() });
+        };
+        let r = self.inner.write_batch(batch).await;
+        self.end(Op::Write, nth);
+        r
+    }
+
+    async fn write_batch_partial(&self, batch: &mut Vec<Transaction>) -> Result<usize, WriteError> {
+        let Some(nth) = self.begin(Op::Write).await else {
+            return Err(WriteError::Full { capacity: 0, rejected: batch.len() });
+        };
+        let r = self.inner.write_batch_partial(batch).await;
+        self.end(Op::Write, nth);
+        r
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+}
+
+impl<T: Buffer1Read> Buffer1Read for Scripted<T> {
+    async fn read_batch(&self, max: usize) -> Result<Vec<Transaction>, ReadError> {
+        let Some(nth) = self.begin(Op::Read).await else {
+            return Err(ReadError::Closed);
+        };
+        let r = self.inner.read_batch(max).await;
+        self.end(Op::Read, nth);
+        r
+    }
+}
+
+impl<T: Buffer2> Buffer2 for Scripted<T> {
+    async fn write_batch(&self, batch: Vec<InferredTransaction>) -> Result<(), WriteError> {
+        let Some(nth) = self.begin(Op::Write).await else {
+            return Err(WriteError::Full { capacity: 0, rejected: batch.len() });
+        };
+        let r = self.inner.write_batch(batch).await;
+        self.end(Op::Write, nth);
+        r
+    }
+
+    async fn write_batch_partial(
+        &self,
+        batch: &mut Vec<InferredTransaction>,
+    ) -> Result<usize, WriteError> {
+        let Some(nth) = self.begin(Op::Write).await else {
+            return Err(WriteError::Full { capacity: 0, rejected: batch.len() });
+        };
+        let r = self.inner.write_batch_partial(batch).await;
+        self.end(Op::Write, nth);
+        r
+    }
+}
+
+impl<T: Buffer2Read> Buffer2Read for Scripted<T> {
+    async fn read_batch(&self, max: usize) -> Result<Vec<InferredTransaction>, ReadError> {
+        let Some(nth) = self.begin(Op::Read).await else {
+            return Err(ReadError::Closed);
+        };
+        let r = self.inner.read_batch(max).await;
+        self.end(Op::Read, nth);
+        r
+    }
+}
+
+impl<T: Modelizer> Modelizer for Scripted<T> {
+    async fn infer(
+        &self,
+        batch: Vec<Transaction>,
+    ) -> Result<Vec<InferredTransaction>, ModelizerError> {
+        let Some(nth) = self.begin(Op::Infer).await else {
+            return Err(ModelizerError::InferenceFailed {
+                reason: format!("scripted failure on infer #{}", self.calls(Op::Infer)),
+            });
+        };
+        let r = self.inner.infer(batch).await;
+        self.end(Op::Infer, nth);
+        r
+    }
+
+    async fn switch_version(&self, version: ModelVersion) -> Result<(), ModelizerError> {
+        self.inner.switch_version(version).await
+    }
+}
+
+impl<T: Storage> Storage for Scripted<T> {
+    async fn write_batch(&self, batch: Vec<PendingTransaction>) -> Result<(), StorageError> {
+        let Some(nth) = self.begin(Op::Write).await else {
+            return Err(StorageError::Unavailable);
+        };
+        let r = self.inner.write_batch(batch).await;
+        self.end(Op::Write, nth);
+        r
+    }
+
+    async fn begin_run(&self, meta: RunMeta) -> Result<RunId, StorageError> {
+        self.inner.begin_run(meta).await
+    }
+
+    async fn end_run(&self, id: RunId, summary: RunSummary) -> Result<(), StorageError> {
+        self.inner.end_run(id, summary).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{Op, Script, Scripted, Trace};
+    use crate::run_paused;
+    use domain::{Storage as _, StorageError};
+    use pipeline::memory::MemoryStorage;
+    use std::time::Duration;
+
+    // SC-T01: steps apply to the n-th call of their op only.
+    #[test]
+    fn steps_target_the_nth_call() {
+        run_paused(async {
+            let trace = Trace::new();
+            let script =
+                Script::new().delay(Op::Write, 2, Duration::from_millis(10)).fail(Op::Write, 3);
+            let storage = Scripted::new(MemoryStorage::new(), "storage", script, trace.clone());
+            let start = tokio::time::Instant::now();
+
+            storage.write_batch(vec![]).await.unwrap();
+            assert_eq!(start.elapsed(), Duration::ZERO);
+            storage.write_batch(vec![]).await.unwrap();
+            assert!(start.elapsed() >= Duration::from_millis(10));
+            assert_eq!(storage.write_batch(vec![]).await, Err(StorageError::Unavailable));
+            storage.write_batch(vec![]).await.unwrap();
+
+            assert_eq!(storage.calls(Op::Write), 4);
+            assert_eq!(storage.calls(Op::Read), 0);
+            assert_eq!(trace.entries()[4..6], ["storage.write#3", "storage.write#3.done"]);
+        });
+    }
+}
@@ -0,0 +1,240 @@
+// Rust guideline compliant 2026-02-27
+
+//! Virtual-time runs of the full in-memory pipeline.
+//!
+//! [`simulate`] runs an unbounded Producer for a virtual duration on a paused
+//! clock, then closes Buffer1 and lets the shutdown cascade finish. Every
+//! stage sleep goes through the default `TokioSleeper`, so minutes of
+//! pipeline time cost milliseconds of wall time.
+
+use std::time::Duration;
+
+use consumer::{Consumer, ConsumerConfig};
+use logger::{Logger, LoggerConfig};
+use pipeline::memory::{CountingAlarm, MemoryBuffer, MemoryStorage, RateModel};
+use pipeline::{Close, Pipeline, PipelineError, PipelineReport};
+use producer::{Producer, ProducerConfig};
+
+use crate::run_paused;
+
+// ---------------------------------------------------------------------------
+// Simulation
+// ---------------------------------------------------------------------------
+
+/// Parameters of a [`simulate`] run.
+///
+/// Construct via [`Simulation::builder`].
+#[derive(Debug, Clone, Copy)]
+pub struct Simulation {
+    /// Virtual time after which Buffer1 is closed.
+    pub duration: Duration,
+    /// Delay between Producer batches.
+    pub producer_interval: Duration,
+    /// Delay between Consumer and Logger iterations.
+    pub poll_interval: Duration,
+    /// Maximum batch size of every stage.
+    pub batch_max: usize,
+    /// Probability that the model flags a transaction.
+    pub fraud_rate: f64,
+    /// Base seed; the stages and the model derive their own seeds from it.
+    pub seed: u64,
+}
+
+/// Builder for [`Simulation`].
+///
+/// Obtain via [`Simulation::builder`]; finalize with [`build`](Self::build).
+#[derive(Debug, Clone, Copy)]
+pub struct SimulationBuilder {
+    sim: Simulation,
+}
+
+impl Simulation {
+    /// Create a builder. `duration` is the virtual run time.
+    ///
+    /// Default values: `producer_interval = 500 ms`, `poll_interval = 100 ms`,
+    /// `batch_max = 10`, `fraud_rate = 0.04`, `seed = 0`.
+    #[must_use]
+    pub fn builder(duration: Duration) -> SimulationBuilder {
+        SimulationBuilder {
+            sim: Simulation {
+                duration,
+                producer_interval: Duration::from_millis(500),
+                // Downstream polls faster than the Producer, as in the binaries.
+                poll_interval: Duration::from_millis(100),
+                batch_max: 10,
+                fraud_rate: 0.04,
+                seed: 0,
+            },
+        }
+    }
+}
+
+impl SimulationBuilder {
+    /// Delay between Producer batches.
+    #[must_use]
+    pub fn producer_interval(mut self, interval: Duration) -> Self {
+        self.sim.producer_interval = interval;
+        self
+    }
+
+    /// Delay between Consumer and Logger iterations.
+    #[must_use]
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.sim.poll_interval = interval;
+        self
+    }
+
+    /// Maximum batch size of every stage.
+    #[must_use]
+    pub fn batch_max(mut self, n: usize) -> Self {
+        self.sim.batch_max = n;
+        self
+    }
+
+    /// Probability that the model flags a transaction.
+    #[must_use]
+    pub fn fraud_rate(mut self, rate: f64) -> Self {
+        self.sim.fraud_rate = rate;
+        self
+    }
+
+    /// Base seed for every RNG in the run.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sim.seed = seed;
+        self
+    }
+
+    /// Finalize the parameters; stage configurations are validated by [`simulate`].
+    #[must_use]
+    pub fn build(self) -> Simulation {
+        self.sim
+    }
+}
+
+// ---------------------------------------------------------------------------
+// simulate
+// ---------------------------------------------------------------------------
+
+/// Run the in-memory pipeline for `sim.duration` of virtual time and return
+/// its report.
+///
+/// Runs on its own paused `current_thread` runtime (see [`run_paused`]), so
+/// do not call it from inside another runtime. The Producer stops with
+/// `BufferClosed`; its `iterations` count the batches written. Same `sim`,
+/// same report.
+///
+/// # Errors
+///
+/// Returns [`PipelineError`] if a stage configuration is rejected (zero
+/// `batch_max`, `fraud_rate` outside `[0, 1]`) or a stage fails.
+pub fn simulate(sim: &Simulation) -> Result<PipelineReport, PipelineError> {
+    let seed = sim.seed;
+    let producer = Producer::new(
+        ProducerConfig::builder(sim.batch_max)
+            .poll_interval1(sim.producer_interval)
+            .seed(seed)
+            .build()?,
+    );
+    let consumer = Consumer::new(
+        ConsumerConfig::builder(sim.batch_max)
+            .poll_interval2(sim.poll_interval)
+            .seed(seed.wrapping_add(1))
+            .build()?,
+    );
+    let logger = Logger::new(
+        LoggerConfig::builder(sim.batch_max)
+            .poll_interval3(sim.poll_interval)
+            .seed(seed.wrapping_add(2))
+            .build()?,
+    );
+    let pipeline = Pipeline::new(producer, consumer, logger);
+    let model = RateModel::new(sim.fraud_rate, seed.wrapping_add(3));
+    let modelizer = modelizer::Modelizer::new(model);
+
+    run_paused(async {
+        let buffer1 = MemoryBuffer::new();
+        let buffer2 = MemoryBuffer::new();
+        let alarm = CountingAlarm::new();
+        let storage = MemoryStorage::new();
+
+        let (stops, ()) = tokio::join!(
+            pipeline.run(&buffer1, &modelizer, &alarm, &buffer2, &storage),
+            async {
+                tokio::time::sleep(sim.duration).await;
+                buffer1.close();
+            },
+        );
+        stops.map(|stops| pipeline.report(storage.len(), stops))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{Simulation, simulate};
+    use domain::StopReason;
+    use pipeline::PipelineReport;
+    use std::time::Duration;
+
+    const FIVE_MINUTES: Duration = Duration::from_mins(5);
+
+    // VT-T01: 5 virtual minutes at a 500 ms cadence write one batch per tick,
+    // and everything produced reaches storage.
+    #[test]
+    fn five_virtual_minutes_at_500ms_yield_600_batches() {
+        let sim = Simulation::builder(FIVE_MINUTES).seed(5).build();
+
+        let report = simulate(&sim).unwrap();
+
+        let StopReason::BufferClosed { iterations } = report.stops.producer else {
+            panic!("producer must stop on the closed buffer: {report}");
+        };
+        // Writes at t = 0, 0.5 s, ..., 299.5 s; the one due at 300 s races the close.
+        assert!((600..=601).contains(&iterations), "{iterations} batches: {report}");
+        // A batch generated after the close is counted as produced but rejected.
+        assert!(report.produced - report.inferred <= 10, "{report}");
+        assert_eq!(report.persisted, report.inferred);
+    }
+
+    // VT-T02: the run, pacing included, only depends on the parameters.
+    #[test]
+    fn same_simulation_same_report() {
+        let sim = Simulation::builder(FIVE_MINUTES)
+            .producer_interval(Duration::from_millis(250))
+            .poll_interval(Duration::from_millis(70))
+            .seed(11)
+            .build();
+        // Executor timings (pipeline's runtime-metrics feature) are wall-clock.
+        let deterministic = |r: PipelineReport| {
+            let digests = (r.produced_digest, r.persisted_digest);
+            (r.produced, r.inferred, r.flagged, r.persisted, digests, r.stops, r.configs, r.seeds)
+        };
+
+        let (first, second) = (simulate(&sim).unwrap(), simulate(&sim).unwrap());
+
+        assert_eq!(deterministic(first), deterministic(second));
+    }
+
+    // VT-T03: a different seed changes the data but not the cadence.
+    #[test]
+    fn seed_changes_data_not_cadence() {
+        let base = Simulation::builder(Duration::from_mins(1));
+        let a = simulate(&base.seed(1).build()).unwrap();
+        let b = simulate(&base.seed(2).build()).unwrap();
+
+        assert_eq!(a.stops.producer, b.stops.producer);
+        assert_ne!(a.produced, b.produced);
+    }
+
+    // VT-T04: invalid stage parameters are reported, not panicked on.
+    #[test]
+    fn zero_batch_max_is_rejected() {
+        let sim = Simulation::builder(FIVE_MINUTES).batch_max(0).build();
+
+        simulate(&sim).unwrap_err();
+    }
+}